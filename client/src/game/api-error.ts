@@ -0,0 +1,28 @@
+/**
+ * API errors
+ *
+ * Failed requests to the game, chat and admin routes answer with a JSON body naming what
+ * went wrong. `code` is stable and meant for branching; `message` is for people, and for
+ * chat it is already in the player's language.
+ */
+
+export interface ApiError {
+  code: string;
+  message: string;
+  details: Record<string, unknown> | null;
+}
+
+/**
+ * The error in a failed response, or one built from its status if the body isn't an ApiError
+ */
+export async function readApiError(response: Response): Promise<ApiError> {
+  try {
+    const body: unknown = await response.json();
+    if (typeof body === 'object' && body !== null && 'code' in body && 'message' in body) {
+      return body as ApiError;
+    }
+  } catch {
+    // Not JSON, e.g. from a proxy in front of the server
+  }
+  return { code: `http_${response.status}`, message: response.statusText, details: null };
+}
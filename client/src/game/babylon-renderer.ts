@@ -6,6 +6,7 @@ import {
   MeshBuilder,
   StandardMaterial,
   Color3,
+  Color4,
   Mesh,
 } from '@babylonjs/core';
 import { BaseDatastarReceiver } from '../interfaces/datastar';
@@ -13,6 +14,96 @@ import { createScene } from '../scene';
 import { gameState, type Player } from './player-state';
 import { ChatGUI } from './chat-gui';
 import { datastarManager } from './datastar-manager';
+import { Signals } from './signals';
+import { serverUrl } from './server-url';
+
+/** One player's position on one tick of a kill cam replay */
+type KillCamFrame = { tick: number; x: number; y: number; crouched: boolean };
+
+/** Replay of the last moments before another player killed this client's player */
+type KillCamData = {
+  killer: string;
+  victim: string;
+  killer_frames: KillCamFrame[];
+  victim_frames: KillCamFrame[];
+};
+
+/** Replay speed of kill cams: one recorded tick per frame at the server's default 60Hz */
+const KILL_CAM_FRAME_MS = 1000 / 60;
+
+/** A stretch of ground at its own height, with its color resolved by the server */
+type GroundSegment = { x_start: number; x_end: number; y_top: number; color: string };
+
+/** Static map geometry, as served by /api/config and the mapGeometry signal */
+type MapGeometry = {
+  platforms: Array<{
+    id: string;
+    x_start: number;
+    x_end: number;
+    y_top: number;
+    height: number;
+    color: string;
+  }>;
+  walls: Array<{
+    id: string;
+    x: number;
+    y_bottom: number;
+    y_top: number;
+    width: number;
+    color: string;
+  }>;
+  ladders: Array<{
+    id: string;
+    x_start: number;
+    x_end: number;
+    y_bottom: number;
+    y_top: number;
+    color: string;
+  }>;
+  hazards: Array<{
+    id: string;
+    x_start: number;
+    x_end: number;
+    y_bottom: number;
+    y_top: number;
+    color: string;
+  }>;
+  force_zones: Array<{
+    id: string;
+    x_start: number;
+    x_end: number;
+    y_bottom: number;
+    y_top: number;
+    force_x: number;
+    force_y: number;
+    color: string;
+  }>;
+  portals: Array<{
+    id: string;
+    x_start: number;
+    x_end: number;
+    y_bottom: number;
+    y_top: number;
+    exit: string;
+    color: string;
+  }>;
+  checkpoints: Array<{
+    id: string;
+    x_start: number;
+    x_end: number;
+    y_bottom: number;
+    y_top: number;
+    color: string;
+  }>;
+  control_zone: {
+    id: string;
+    x_start: number;
+    x_end: number;
+    y_bottom: number;
+    y_top: number;
+    color: string;
+  } | null;
+};
 
 export class BabylonRenderer extends BaseDatastarReceiver {
   readonly id = 'babylon-renderer';
@@ -20,37 +111,43 @@ export class BabylonRenderer extends BaseDatastarReceiver {
   private scene: Scene;
   private playerSprites: Map<string, Sprite> = new Map();
   private playerSpriteManagers: Map<string, SpriteManager> = new Map();
+  /** Team color each player's sprite texture was drawn with */
+  private playerSpriteColors: Map<string, string | undefined> = new Map();
   private spriteManager: SpriteManager | null = null;
-  private groundMesh: Mesh | null = null;
+  private groundMeshes: Mesh[] = [];
   private platformMeshes: Map<string, Mesh> = new Map();
   private wallMeshes: Map<string, Mesh> = new Map();
+  private hazardMeshes: Map<string, Mesh> = new Map();
+  private forceZoneMeshes: Map<string, Mesh> = new Map();
+  private portalMeshes: Map<string, Mesh> = new Map();
+  private checkpointMeshes: Map<string, Mesh> = new Map();
+  private controlZoneMesh: Mesh | null = null;
+  private ladderMeshes: Map<string, Mesh> = new Map();
+  /** Projectile meshes by projectile id */
+  private projectileMeshes: Map<number, Mesh> = new Map();
+  private projectileMaterial: StandardMaterial | null = null;
+  /** Coin and meteor meshes by world entity id */
+  private worldEntityMeshes: Map<number, Mesh> = new Map();
+  private worldEntityMaterials: Map<string, StandardMaterial> = new Map();
+  /** Ghosts of the killer and victim while a kill cam plays, and the timer stepping them */
+  private killCamMeshes: Mesh[] = [];
+  private killCamTimer: number | null = null;
   private chatGUI: ChatGUI | null = null;
+  /** Id of the map the static geometry was built for, from the map signal */
+  private mapId: string | null = null;
+  /** Version of the settings in gameConfig, from the configVersion signal */
+  private configVersion: number | null = null;
 
   // Game configuration (loaded from server)
-  private gameConfig: {
+  private gameConfig: (MapGeometry & {
     physics: {
       ground_y: number;
       player_width: number;
       player_height: number;
       ground_color: string;
+      ground_segments: GroundSegment[];
     };
-    platforms: Array<{
-      id: string;
-      x_start: number;
-      x_end: number;
-      y_top: number;
-      height: number;
-      color: string;
-    }>;
-    walls: Array<{
-      id: string;
-      x: number;
-      y_bottom: number;
-      y_top: number;
-      width: number;
-      color: string;
-    }>;
-  } | null = null;
+  }) | null = null;
 
   constructor(canvas: HTMLCanvasElement) {
     super();
@@ -73,6 +170,12 @@ export class BabylonRenderer extends BaseDatastarReceiver {
         this.createGround();
         this.createPlatforms();
         this.createWalls();
+        this.createHazards();
+        this.createForceZones();
+        this.createPortals();
+        this.createCheckpoints();
+        this.createControlZone();
+        this.createLadders();
       })
       .catch((error) => {
         console.error(`[${this.id}] ❌ CRITICAL: Failed to load game config:`, error);
@@ -181,7 +284,7 @@ export class BabylonRenderer extends BaseDatastarReceiver {
   /**
    * Create a unique sprite texture for a player
    */
-  private createPlayerSpriteTexture(playerId: string): string {
+  private createPlayerSpriteTexture(playerId: string, teamColor?: string): string {
     const textureSize = 64;
     const canvas = document.createElement('canvas');
     canvas.width = textureSize;
@@ -193,8 +296,8 @@ export class BabylonRenderer extends BaseDatastarReceiver {
       return '';
     }
 
-    // Get unique color for this player
-    const playerColor = this.getPlayerColor(playerId);
+    // Team or palette color from the server, otherwise a unique color for this player
+    const playerColor = teamColor ?? this.getPlayerColor(playerId);
 
     // Clear with transparent background
     ctx.clearRect(0, 0, textureSize, textureSize);
@@ -258,41 +361,55 @@ export class BabylonRenderer extends BaseDatastarReceiver {
       console.warn(`[${this.id}] ⚠️ Game config not loaded, using default ground color`);
     }
 
-    // Create ground as a box mesh for 2D Metroidvania style
-    // Ground positioned at the bottom of the camera view
-    // Camera view: -10 to 10 world units, so ground at y = -10 (bottom of view)
-
-    // Create a wide, thin box for the ground
-    this.groundMesh = MeshBuilder.CreateBox(
-      'ground',
-      {
-        width: 200, // 200 world units = 20000 pixels wide (very wide)
-        height: 1, // 1 world unit = 100 pixels tall
-        depth: 0.1, // Very thin depth for 2D look
-      },
-      this.scene
-    );
-
-    // Position ground at the bottom of the screen
+    // Ground is drawn as columns, one per stretch at a single height: ground segments at
+    // their own height and the default ground between and around them
     // Use ground_y from config if available, otherwise default to -10
     const groundY = this.gameConfig?.physics.ground_y ?? -10.0;
-    this.groundMesh.position.x = 0;
-    this.groundMesh.position.y = groundY; // Bottom of camera view (from config)
-    this.groundMesh.position.z = 0;
-
-    // Get ground color from config or use default
-    const groundColorHex = this.gameConfig?.physics.ground_color || '#8B6F47';
-    const groundColor = this.hexToColor3(groundColorHex);
-
-    // Create material with configurable color and toon shading
-    const groundMaterial = new StandardMaterial('groundMaterial', this.scene);
-    groundMaterial.diffuseColor = groundColor;
-    // Toon shading: use emissive to create flat, unlit appearance
-    groundMaterial.emissiveColor = groundColor; // Same as diffuse for flat look
-    groundMaterial.specularColor = new Color3(0, 0, 0); // No specular highlights
-    groundMaterial.disableLighting = true; // Completely flat, unlit appearance (toon style)
-    this.groundMesh.material = groundMaterial;
+    const groundColor = this.gameConfig?.physics.ground_color || '#8B6F47';
+    const segments = [...(this.gameConfig?.physics.ground_segments ?? [])].sort((a, b) => a.x_start - b.x_start);
+    // 200 world units wide, like the original single ground box
+    const extent = { left: -100, right: 100 };
+    const columns: GroundSegment[] = [];
+    let coveredTo = extent.left;
+    for (const segment of segments) {
+      if (segment.x_start > coveredTo) {
+        columns.push({ x_start: coveredTo, x_end: segment.x_start, y_top: groundY, color: groundColor });
+      }
+      columns.push(segment);
+      coveredTo = Math.max(coveredTo, segment.x_end);
+    }
+    if (coveredTo < extent.right) {
+      columns.push({ x_start: coveredTo, x_end: extent.right, y_top: groundY, color: groundColor });
+    }
+    // Every column reaches down to the same depth, one unit below the lowest ground
+    const bottom = Math.min(groundY, ...segments.map((segment) => segment.y_top)) - 1.0;
 
+    columns.forEach((column, index) => {
+      const height = column.y_top - bottom;
+      const mesh = MeshBuilder.CreateBox(
+        `ground_${index}`,
+        {
+          width: column.x_end - column.x_start,
+          height,
+          depth: 0.1, // Very thin depth for 2D look
+        },
+        this.scene
+      );
+      mesh.position.x = (column.x_start + column.x_end) / 2.0;
+      mesh.position.y = column.y_top - height / 2.0;
+      mesh.position.z = 0;
+
+      // Create material with configurable color and toon shading
+      const color = this.hexToColor3(column.color);
+      const material = new StandardMaterial(`groundMaterial_${index}`, this.scene);
+      material.diffuseColor = color;
+      // Toon shading: use emissive to create flat, unlit appearance
+      material.emissiveColor = color; // Same as diffuse for flat look
+      material.specularColor = new Color3(0, 0, 0); // No specular highlights
+      material.disableLighting = true; // Completely flat, unlit appearance (toon style)
+      mesh.material = material;
+      this.groundMeshes.push(mesh);
+    });
   }
 
   /**
@@ -300,7 +417,7 @@ export class BabylonRenderer extends BaseDatastarReceiver {
    */
   private async loadGameConfig(): Promise<void> {
     try {
-      const response = await fetch('/api/config');
+      const response = await fetch(serverUrl('/api/config'));
       if (!response.ok) {
         throw new Error(`Failed to load game config: ${response.statusText}`);
       }
@@ -313,6 +430,14 @@ export class BabylonRenderer extends BaseDatastarReceiver {
           player_width: rawConfig.physics?.player_width ?? 1.5,
           player_height: rawConfig.physics?.player_height ?? 1.5,
           ground_color: rawConfig.physics?.ground_color ?? '#8B6F47',
+          ground_segments: Array.isArray(rawConfig.physics?.ground_segments)
+            ? rawConfig.physics.ground_segments.map((g: Record<string, unknown>) => ({
+                x_start: Number(g['x_start'] ?? 0),
+                x_end: Number(g['x_end'] ?? 0),
+                y_top: Number(g['y_top'] ?? 0),
+                color: String(g['color'] ?? rawConfig.physics?.ground_color ?? '#8B6F47'),
+              }))
+            : [],
         },
         platforms: Array.isArray(rawConfig.platforms)
           ? rawConfig.platforms.map((p: unknown) => {
@@ -346,6 +471,100 @@ export class BabylonRenderer extends BaseDatastarReceiver {
               throw new Error('Invalid wall config');
             })
           : [],
+        ladders: Array.isArray(rawConfig.ladders)
+          ? rawConfig.ladders.map((l: unknown) => {
+              if (typeof l === 'object' && l !== null) {
+                const ladder = l as Record<string, unknown>;
+                return {
+                  id: String(ladder['id'] ?? ''),
+                  x_start: Number(ladder['x_start'] ?? 0),
+                  x_end: Number(ladder['x_end'] ?? 0),
+                  y_bottom: Number(ladder['y_bottom'] ?? 0),
+                  y_top: Number(ladder['y_top'] ?? 0),
+                  color: String(ladder['color'] ?? '#A0522D'),
+                };
+              }
+              throw new Error('Invalid ladder config');
+            })
+          : [],
+        hazards: Array.isArray(rawConfig.hazards)
+          ? rawConfig.hazards.map((h: unknown) => {
+              if (typeof h === 'object' && h !== null) {
+                const hazard = h as Record<string, unknown>;
+                return {
+                  id: String(hazard['id'] ?? ''),
+                  x_start: Number(hazard['x_start'] ?? 0),
+                  x_end: Number(hazard['x_end'] ?? 0),
+                  y_bottom: Number(hazard['y_bottom'] ?? 0),
+                  y_top: Number(hazard['y_top'] ?? 0),
+                  color: String(hazard['color'] ?? '#FF3030'),
+                };
+              }
+              throw new Error('Invalid hazard config');
+            })
+          : [],
+        force_zones: Array.isArray(rawConfig.force_zones)
+          ? rawConfig.force_zones.map((z: unknown) => {
+              if (typeof z === 'object' && z !== null) {
+                const zone = z as Record<string, unknown>;
+                return {
+                  id: String(zone['id'] ?? ''),
+                  x_start: Number(zone['x_start'] ?? 0),
+                  x_end: Number(zone['x_end'] ?? 0),
+                  y_bottom: Number(zone['y_bottom'] ?? 0),
+                  y_top: Number(zone['y_top'] ?? 0),
+                  force_x: Number(zone['force_x'] ?? 0),
+                  force_y: Number(zone['force_y'] ?? 0),
+                  color: String(zone['color'] ?? '#88CCFF'),
+                };
+              }
+              throw new Error('Invalid force zone config');
+            })
+          : [],
+        portals: Array.isArray(rawConfig.portals)
+          ? rawConfig.portals.map((p: unknown) => {
+              if (typeof p === 'object' && p !== null) {
+                const portal = p as Record<string, unknown>;
+                return {
+                  id: String(portal['id'] ?? ''),
+                  x_start: Number(portal['x_start'] ?? 0),
+                  x_end: Number(portal['x_end'] ?? 0),
+                  y_bottom: Number(portal['y_bottom'] ?? 0),
+                  y_top: Number(portal['y_top'] ?? 0),
+                  exit: String(portal['exit'] ?? ''),
+                  color: String(portal['color'] ?? '#AA44FF'),
+                };
+              }
+              throw new Error('Invalid portal config');
+            })
+          : [],
+        checkpoints: Array.isArray(rawConfig.checkpoints)
+          ? rawConfig.checkpoints.map((c: unknown) => {
+              if (typeof c === 'object' && c !== null) {
+                const checkpoint = c as Record<string, unknown>;
+                return {
+                  id: String(checkpoint['id'] ?? ''),
+                  x_start: Number(checkpoint['x_start'] ?? 0),
+                  x_end: Number(checkpoint['x_end'] ?? 0),
+                  y_bottom: Number(checkpoint['y_bottom'] ?? 0),
+                  y_top: Number(checkpoint['y_top'] ?? 0),
+                  color: String(checkpoint['color'] ?? '#FFD700'),
+                };
+              }
+              throw new Error('Invalid checkpoint config');
+            })
+          : [],
+        control_zone:
+          typeof rawConfig.control_zone === 'object' && rawConfig.control_zone !== null
+            ? {
+                id: String(rawConfig.control_zone['id'] ?? ''),
+                x_start: Number(rawConfig.control_zone['x_start'] ?? 0),
+                x_end: Number(rawConfig.control_zone['x_end'] ?? 0),
+                y_bottom: Number(rawConfig.control_zone['y_bottom'] ?? 0),
+                y_top: Number(rawConfig.control_zone['y_top'] ?? 0),
+                color: String(rawConfig.control_zone['color'] ?? '#FF8800'),
+              }
+            : null,
       };
     } catch (error) {
       console.error(`[${this.id}] ❌ Failed to load game config:`, error);
@@ -447,19 +666,516 @@ export class BabylonRenderer extends BaseDatastarReceiver {
     }
   }
 
+  /**
+   * Create all ladders from game configuration, drawn behind players and platforms
+   */
+  private createLadders(): void {
+    if (!this.gameConfig) {
+      console.error(`[${this.id}] ❌ Game config not loaded, cannot create ladders!`);
+      return;
+    }
+
+    for (const ladder of this.gameConfig.ladders) {
+      const ladderMesh = MeshBuilder.CreateBox(
+        `ladder_${ladder.id}`,
+        {
+          width: ladder.x_end - ladder.x_start,
+          height: ladder.y_top - ladder.y_bottom,
+          depth: 0.05,
+        },
+        this.scene
+      );
+
+      ladderMesh.position.x = (ladder.x_start + ladder.x_end) / 2.0;
+      ladderMesh.position.y = (ladder.y_bottom + ladder.y_top) / 2.0;
+      ladderMesh.position.z = 0.1; // Behind players and platforms
+
+      const ladderColor = this.hexToColor3(ladder.color);
+      const ladderMaterial = new StandardMaterial(`ladderMaterial_${ladder.id}`, this.scene);
+      ladderMaterial.diffuseColor = ladderColor;
+      ladderMaterial.emissiveColor = ladderColor; // Toon shading
+      ladderMaterial.specularColor = new Color3(0, 0, 0);
+      ladderMaterial.disableLighting = true;
+      ladderMesh.material = ladderMaterial;
+
+      this.ladderMeshes.set(ladder.id, ladderMesh);
+    }
+  }
+
+  /**
+   * Create all hazards from game configuration
+   */
+  private createHazards(): void {
+    if (!this.gameConfig) {
+      console.error(`[${this.id}] ❌ Game config not loaded, cannot create hazards!`);
+      return;
+    }
+
+    for (const hazard of this.gameConfig.hazards) {
+      const hazardMesh = MeshBuilder.CreateBox(
+        `hazard_${hazard.id}`,
+        {
+          width: hazard.x_end - hazard.x_start,
+          height: hazard.y_top - hazard.y_bottom,
+          depth: 0.1, // Very thin depth for 2D look
+        },
+        this.scene
+      );
+
+      hazardMesh.position.x = (hazard.x_start + hazard.x_end) / 2.0;
+      hazardMesh.position.y = (hazard.y_bottom + hazard.y_top) / 2.0;
+      hazardMesh.position.z = 0;
+
+      const hazardColor = this.hexToColor3(hazard.color);
+      const hazardMaterial = new StandardMaterial(`hazardMaterial_${hazard.id}`, this.scene);
+      hazardMaterial.diffuseColor = hazardColor;
+      hazardMaterial.emissiveColor = hazardColor; // Toon shading
+      hazardMaterial.specularColor = new Color3(0, 0, 0);
+      hazardMaterial.disableLighting = true;
+      hazardMesh.material = hazardMaterial;
+
+      this.hazardMeshes.set(hazard.id, hazardMesh);
+    }
+  }
+
+  /**
+   * Create all force zones from game configuration, translucent so players show through
+   */
+  private createForceZones(): void {
+    if (!this.gameConfig) {
+      console.error(`[${this.id}] ❌ Game config not loaded, cannot create force zones!`);
+      return;
+    }
+
+    for (const zone of this.gameConfig.force_zones) {
+      const zoneMesh = MeshBuilder.CreateBox(
+        `forceZone_${zone.id}`,
+        {
+          width: zone.x_end - zone.x_start,
+          height: zone.y_top - zone.y_bottom,
+          depth: 0.2,
+        },
+        this.scene
+      );
+      zoneMesh.position.x = (zone.x_start + zone.x_end) / 2.0;
+      zoneMesh.position.y = (zone.y_bottom + zone.y_top) / 2.0;
+      zoneMesh.position.z = 0.5; // Behind the players
+
+      const zoneColor = this.hexToColor3(zone.color);
+      const zoneMaterial = new StandardMaterial(`forceZoneMaterial_${zone.id}`, this.scene);
+      zoneMaterial.diffuseColor = zoneColor;
+      zoneMaterial.emissiveColor = zoneColor;
+      zoneMaterial.specularColor = new Color3(0, 0, 0);
+      zoneMaterial.disableLighting = true;
+      zoneMaterial.alpha = 0.25;
+      zoneMesh.material = zoneMaterial;
+
+      this.forceZoneMeshes.set(zone.id, zoneMesh);
+    }
+  }
+
+  /**
+   * Create all portals from game configuration, glowing so they stand out from scenery
+   */
+  private createPortals(): void {
+    if (!this.gameConfig) {
+      console.error(`[${this.id}] ❌ Game config not loaded, cannot create portals!`);
+      return;
+    }
+
+    for (const portal of this.gameConfig.portals) {
+      const portalMesh = MeshBuilder.CreateBox(
+        `portal_${portal.id}`,
+        {
+          width: portal.x_end - portal.x_start,
+          height: portal.y_top - portal.y_bottom,
+          depth: 0.2,
+        },
+        this.scene
+      );
+      portalMesh.position.x = (portal.x_start + portal.x_end) / 2.0;
+      portalMesh.position.y = (portal.y_bottom + portal.y_top) / 2.0;
+      portalMesh.position.z = 0.5; // Behind the players
+
+      const portalColor = this.hexToColor3(portal.color);
+      const portalMaterial = new StandardMaterial(`portalMaterial_${portal.id}`, this.scene);
+      portalMaterial.diffuseColor = portalColor;
+      portalMaterial.emissiveColor = portalColor;
+      portalMaterial.specularColor = new Color3(0, 0, 0);
+      portalMaterial.disableLighting = true;
+      portalMaterial.alpha = 0.6;
+      portalMesh.material = portalMaterial;
+
+      this.portalMeshes.set(portal.id, portalMesh);
+    }
+  }
+
+  /**
+   * Create race checkpoints from game configuration as thin translucent gates
+   */
+  private createCheckpoints(): void {
+    if (!this.gameConfig) {
+      console.error(`[${this.id}] ❌ Game config not loaded, cannot create checkpoints!`);
+      return;
+    }
+
+    for (const checkpoint of this.gameConfig.checkpoints) {
+      const checkpointMesh = MeshBuilder.CreateBox(
+        `checkpoint_${checkpoint.id}`,
+        {
+          width: checkpoint.x_end - checkpoint.x_start,
+          height: checkpoint.y_top - checkpoint.y_bottom,
+          depth: 0.2,
+        },
+        this.scene
+      );
+      checkpointMesh.position.x = (checkpoint.x_start + checkpoint.x_end) / 2.0;
+      checkpointMesh.position.y = (checkpoint.y_bottom + checkpoint.y_top) / 2.0;
+      checkpointMesh.position.z = 0.5; // Behind the players
+
+      const checkpointColor = this.hexToColor3(checkpoint.color);
+      const checkpointMaterial = new StandardMaterial(`checkpointMaterial_${checkpoint.id}`, this.scene);
+      checkpointMaterial.diffuseColor = checkpointColor;
+      checkpointMaterial.emissiveColor = checkpointColor;
+      checkpointMaterial.specularColor = new Color3(0, 0, 0);
+      checkpointMaterial.disableLighting = true;
+      checkpointMaterial.alpha = 0.35;
+      checkpointMesh.material = checkpointMaterial;
+
+      this.checkpointMeshes.set(checkpoint.id, checkpointMesh);
+    }
+  }
+
+  /**
+   * Create the king of the hill control zone as a translucent box
+   */
+  private createControlZone(): void {
+    const zone = this.gameConfig?.control_zone;
+    if (!zone) {
+      return;
+    }
+
+    const zoneMesh = MeshBuilder.CreateBox(
+      `controlZone_${zone.id}`,
+      {
+        width: zone.x_end - zone.x_start,
+        height: zone.y_top - zone.y_bottom,
+        depth: 0.2,
+      },
+      this.scene
+    );
+    zoneMesh.position.x = (zone.x_start + zone.x_end) / 2.0;
+    zoneMesh.position.y = (zone.y_bottom + zone.y_top) / 2.0;
+    zoneMesh.position.z = 0.5; // Behind the players
+
+    const zoneColor = this.hexToColor3(zone.color);
+    const zoneMaterial = new StandardMaterial(`controlZoneMaterial_${zone.id}`, this.scene);
+    zoneMaterial.diffuseColor = zoneColor;
+    zoneMaterial.emissiveColor = zoneColor;
+    zoneMaterial.specularColor = new Color3(0, 0, 0);
+    zoneMaterial.disableLighting = true;
+    zoneMaterial.alpha = 0.3;
+    zoneMesh.material = zoneMaterial;
+
+    this.controlZoneMesh = zoneMesh;
+  }
+
   /**
    * Handle game state signal updates
    * This is called by DatastarUpdateManager when gameState signal is received
    */
   override onSignalUpdate(signalName: string, data: unknown): void {
-    if (signalName === 'gameState' && Array.isArray(data)) {
+    if (signalName === Signals.GameState && Array.isArray(data)) {
       // Type-safe player array - data is already validated as array
       const players: Player[] = data;
       // Update sprites based on the new game state
       this.updateSprites(players);
+    } else if (signalName === Signals.Projectiles && Array.isArray(data)) {
+      this.updateProjectiles(data as Array<{ id: number; x: number; y: number }>);
+    } else if (signalName === Signals.KillCam && typeof data === 'object' && data !== null && 'killer_frames' in data) {
+      this.playKillCam(data as KillCamData);
+    } else if (signalName === Signals.WorldEntities && Array.isArray(data)) {
+      this.updateWorldEntities(data as Array<{ id: number; kind: string; x: number; y: number }>);
+    } else if (signalName === Signals.Map && typeof data === 'object' && data !== null && 'id' in data) {
+      const { id } = data as { id: string };
+      // The first signal names the map already loaded; later ones mean the rotation moved on
+      if (this.mapId !== null && this.mapId !== id) {
+        this.reloadGeometry();
+      }
+      this.mapId = id;
+    } else if (signalName === Signals.MapGeometry && typeof data === 'object' && data !== null && 'platforms' in data) {
+      this.applyMapEdit(data as MapGeometry);
+    } else if (signalName === Signals.Bounces && Array.isArray(data)) {
+      // Sound and particle effects listen for one event per bounce
+      for (const bounce of data as Array<{ player_id: string; surface: string; speed: number; x: number; y: number }>) {
+        window.dispatchEvent(new CustomEvent('bounce', { detail: bounce }));
+      }
+    } else if (signalName === Signals.Teleports && Array.isArray(data)) {
+      // Portal effects play at both ends of each trip
+      for (const teleport of data as Array<{
+        player_id: string;
+        portal: string;
+        exit: string;
+        from_x: number;
+        from_y: number;
+        x: number;
+        y: number;
+      }>) {
+        window.dispatchEvent(new CustomEvent('teleport', { detail: teleport }));
+      }
+    } else if (signalName === Signals.Race) {
+      // The race board listens for standings; null once the mode isn't a race
+      window.dispatchEvent(new CustomEvent('race', { detail: data }));
+    } else if (signalName === Signals.Tag) {
+      // The tag HUD shows who is it and who can't be tagged yet; null outside tag mode
+      window.dispatchEvent(new CustomEvent('tag', { detail: data }));
+    } else if (signalName === Signals.Hill) {
+      // The hill HUD shows the zone's owner, capture progress and scores; null outside king of the hill
+      window.dispatchEvent(new CustomEvent('hill', { detail: data }));
+    } else if (signalName === Signals.TimeScale && typeof data === 'number') {
+      // Slow motion and pauses on the server slow the scene's own animations to match
+      this.scene.animationTimeScale = data;
+    } else if (signalName === Signals.ConfigVersion && typeof data === 'number') {
+      // The first signal names the settings already loaded; later ones mean the room changed them
+      if (this.configVersion !== null && this.configVersion !== data) {
+        this.loadGameConfig()
+          .then(() => window.dispatchEvent(new CustomEvent('configchange')))
+          .catch((error) => {
+            console.error(`[${this.id}] ❌ Failed to reload the game config:`, error);
+          });
+      }
+      this.configVersion = data;
     }
   }
 
+  /**
+   * Rebuild the static geometry from a map an admin edited, keeping the ground
+   */
+  private applyMapEdit(geometry: MapGeometry): void {
+    if (!this.gameConfig) {
+      return;
+    }
+    const ground = this.groundMeshes;
+    this.groundMeshes = [];
+    this.disposeStaticGeometry();
+    this.groundMeshes = ground;
+    const { platforms, walls, ladders, hazards } = geometry;
+    const force_zones = geometry.force_zones ?? [];
+    const portals = geometry.portals ?? [];
+    const checkpoints = geometry.checkpoints ?? [];
+    const control_zone = geometry.control_zone ?? null;
+    this.gameConfig = { ...this.gameConfig, platforms, walls, ladders, hazards, force_zones, portals, checkpoints, control_zone };
+    this.createPlatforms();
+    this.createWalls();
+    this.createHazards();
+    this.createForceZones();
+    this.createPortals();
+    this.createCheckpoints();
+    this.createControlZone();
+    this.createLadders();
+    window.dispatchEvent(new CustomEvent('mapchange'));
+  }
+
+  /**
+   * Rebuild the static geometry from /api/config after the map changed
+   */
+  private reloadGeometry(): void {
+    this.disposeStaticGeometry();
+    this.loadGameConfig()
+      .then(() => {
+        this.createGround();
+        this.createPlatforms();
+        this.createWalls();
+        this.createHazards();
+        this.createForceZones();
+        this.createPortals();
+        this.createCheckpoints();
+        this.createControlZone();
+        this.createLadders();
+        window.dispatchEvent(new CustomEvent('mapchange'));
+      })
+      .catch((error) => {
+        console.error(`[${this.id}] ❌ Failed to load the new map:`, error);
+      });
+  }
+
+  /**
+   * Dispose the ground, platforms, walls, ladders and hazards
+   */
+  private disposeStaticGeometry(): void {
+    for (const mesh of this.groundMeshes) {
+      mesh.dispose();
+    }
+    this.groundMeshes = [];
+
+    // Dispose all platform meshes
+    for (const [_id, mesh] of this.platformMeshes) {
+      mesh.dispose();
+    }
+    this.platformMeshes.clear();
+
+    // Dispose all wall meshes
+    for (const [_id, mesh] of this.wallMeshes) {
+      mesh.dispose();
+    }
+    this.wallMeshes.clear();
+
+    // Dispose all ladder meshes
+    for (const [_id, mesh] of this.ladderMeshes) {
+      mesh.dispose();
+    }
+    this.ladderMeshes.clear();
+
+    // Dispose all hazard meshes
+    for (const [_id, mesh] of this.hazardMeshes) {
+      mesh.dispose();
+    }
+    this.hazardMeshes.clear();
+
+    // Dispose all force zone meshes
+    for (const [_id, mesh] of this.forceZoneMeshes) {
+      mesh.dispose();
+    }
+    this.forceZoneMeshes.clear();
+
+    // Dispose all portal meshes
+    for (const [_id, mesh] of this.portalMeshes) {
+      mesh.dispose();
+    }
+    this.portalMeshes.clear();
+
+    // Dispose all checkpoint meshes
+    for (const [_id, mesh] of this.checkpointMeshes) {
+      mesh.dispose();
+    }
+    this.checkpointMeshes.clear();
+
+    this.controlZoneMesh?.dispose();
+    this.controlZoneMesh = null;
+  }
+
+  /**
+   * Sync projectile meshes with the projectiles in flight
+   */
+  private updateProjectiles(projectiles: Array<{ id: number; x: number; y: number }>): void {
+    const inFlight = new Set(projectiles.map((p) => p.id));
+    for (const [id, mesh] of this.projectileMeshes.entries()) {
+      if (!inFlight.has(id)) {
+        mesh.dispose();
+        this.projectileMeshes.delete(id);
+      }
+    }
+
+    if (!this.projectileMaterial) {
+      this.projectileMaterial = new StandardMaterial('projectileMaterial', this.scene);
+      this.projectileMaterial.emissiveColor = Color3.FromHexString('#FFEE88');
+      this.projectileMaterial.disableLighting = true;
+    }
+    for (const projectile of projectiles) {
+      let mesh = this.projectileMeshes.get(projectile.id);
+      if (!mesh) {
+        mesh = MeshBuilder.CreateSphere(`projectile-${projectile.id}`, { diameter: 0.4 }, this.scene);
+        mesh.material = this.projectileMaterial;
+        this.projectileMeshes.set(projectile.id, mesh);
+      }
+      mesh.position.x = projectile.x;
+      mesh.position.y = projectile.y;
+      mesh.position.z = 0;
+    }
+  }
+
+  /**
+   * Replay a kill cam: ghosts of the killer (red) and this player (white) retrace the
+   * recorded frames, following the killer's inputs up to the moment of death
+   */
+  private playKillCam(killCam: KillCamData): void {
+    this.stopKillCam();
+    const width = this.gameConfig?.physics.player_width ?? 1.5;
+    const height = this.gameConfig?.physics.player_height ?? 2.0;
+    const ghost = (name: string, color: string): Mesh => {
+      const mesh = MeshBuilder.CreateBox(name, { width, height, depth: 0.2 }, this.scene);
+      const material = new StandardMaterial(`${name}-material`, this.scene);
+      material.emissiveColor = Color3.FromHexString(color);
+      material.disableLighting = true;
+      material.alpha = 0.5;
+      mesh.material = material;
+      return mesh;
+    };
+    const tracks: Array<[Mesh, KillCamFrame[]]> = [
+      [ghost('kill-cam-killer', '#FF4D4D'), killCam.killer_frames],
+      [ghost('kill-cam-victim', '#FFFFFF'), killCam.victim_frames],
+    ];
+    this.killCamMeshes = tracks.map(([mesh]) => mesh);
+
+    const frames = Math.max(killCam.killer_frames.length, killCam.victim_frames.length);
+    let index = 0;
+    this.killCamTimer = window.setInterval(() => {
+      if (index >= frames) {
+        this.stopKillCam();
+        return;
+      }
+      for (const [mesh, track] of tracks) {
+        const frame = track[Math.min(index, track.length - 1)];
+        mesh.isVisible = frame !== undefined;
+        if (frame) {
+          mesh.position.x = frame.x;
+          mesh.position.y = frame.y;
+          mesh.scaling.y = frame.crouched ? 0.5 : 1;
+        }
+      }
+      index += 1;
+    }, KILL_CAM_FRAME_MS);
+  }
+
+  private stopKillCam(): void {
+    if (this.killCamTimer !== null) {
+      window.clearInterval(this.killCamTimer);
+      this.killCamTimer = null;
+    }
+    for (const mesh of this.killCamMeshes) {
+      mesh.material?.dispose();
+      mesh.dispose();
+    }
+    this.killCamMeshes = [];
+  }
+
+  /**
+   * Sync coin and meteor meshes with the entities world events dropped
+   */
+  private updateWorldEntities(entities: Array<{ id: number; kind: string; x: number; y: number }>): void {
+    const present = new Set(entities.map((e) => e.id));
+    for (const [id, mesh] of this.worldEntityMeshes.entries()) {
+      if (!present.has(id)) {
+        mesh.dispose();
+        this.worldEntityMeshes.delete(id);
+      }
+    }
+
+    for (const entity of entities) {
+      let mesh = this.worldEntityMeshes.get(entity.id);
+      if (!mesh) {
+        mesh = MeshBuilder.CreateSphere(`world-entity-${entity.id}`, { diameter: 0.8 }, this.scene);
+        mesh.material = this.worldEntityMaterial(entity.kind);
+        this.worldEntityMeshes.set(entity.id, mesh);
+      }
+      mesh.position.x = entity.x;
+      mesh.position.y = entity.y;
+      mesh.position.z = 0;
+    }
+  }
+
+  /** Shared material per entity kind: gold coins, glowing red meteors */
+  private worldEntityMaterial(kind: string): StandardMaterial {
+    let material = this.worldEntityMaterials.get(kind);
+    if (!material) {
+      material = new StandardMaterial(`worldEntityMaterial-${kind}`, this.scene);
+      material.emissiveColor = Color3.FromHexString(kind === 'meteor' ? '#FF5522' : '#FFD700');
+      material.disableLighting = true;
+      this.worldEntityMaterials.set(kind, material);
+    }
+    return material;
+  }
+
   /**
    * Update player sprites based on game state
    */
@@ -475,16 +1191,28 @@ export class BabylonRenderer extends BaseDatastarReceiver {
         }
         sprite.dispose();
         this.playerSprites.delete(playerId);
+        this.playerSpriteColors.delete(playerId);
       }
     }
 
     // Update or create player sprites
     for (const player of players) {
       let playerSprite = this.playerSprites.get(player.id);
+      // Team color first, then a server-chosen palette color, else the local hash color
+      const spriteColor = player.team_color ?? player.color;
+
+      // Recreate the sprite when the player's color changes
+      if (playerSprite && this.playerSpriteColors.get(player.id) !== spriteColor) {
+        this.playerSpriteManagers.get(player.id)?.dispose();
+        this.playerSpriteManagers.delete(player.id);
+        playerSprite.dispose();
+        this.playerSprites.delete(player.id);
+        playerSprite = undefined;
+      }
 
       if (!playerSprite) {
         // Create unique texture for this player
-        const playerTexture = this.createPlayerSpriteTexture(player.id);
+        const playerTexture = this.createPlayerSpriteTexture(player.id, spriteColor);
 
         if (!playerTexture) {
           console.error(
@@ -511,6 +1239,7 @@ export class BabylonRenderer extends BaseDatastarReceiver {
         playerSprite.width = 1.5; // Fixed width in world units
         playerSprite.height = 1.5; // Fixed height in world units
         this.playerSprites.set(player.id, playerSprite);
+        this.playerSpriteColors.set(player.id, spriteColor);
       }
 
       // Update player position
@@ -525,8 +1254,13 @@ export class BabylonRenderer extends BaseDatastarReceiver {
       const fixedWidth = 1.5; // Fixed width in world units
       const fixedHeight = 1.5; // Fixed height in world units
       playerSprite.width = fixedWidth; // Always use positive width
-      playerSprite.height = fixedHeight;
+      // Crouching squashes the sprite to match the server's default crouch height
+      playerSprite.height = player.crouched ? fixedHeight / 2 : fixedHeight;
       playerSprite.invertU = !player.facing_right; // Flip horizontally when facing left
+      // Dead players are hidden until they respawn
+      playerSprite.isVisible = player.life?.state !== 'dead';
+      // AFK players are grayed out and faded until they send input again
+      playerSprite.color = player.afk ? new Color4(0.5, 0.5, 0.5, 0.5) : new Color4(1, 1, 1, 1);
     }
   }
 
@@ -559,21 +1293,7 @@ export class BabylonRenderer extends BaseDatastarReceiver {
       this.spriteManager.dispose();
     }
 
-    if (this.groundMesh) {
-      this.groundMesh.dispose();
-    }
-
-    // Dispose all platform meshes
-    for (const [_id, mesh] of this.platformMeshes) {
-      mesh.dispose();
-    }
-    this.platformMeshes.clear();
-
-    // Dispose all wall meshes
-    for (const [_id, mesh] of this.wallMeshes) {
-      mesh.dispose();
-    }
-    this.wallMeshes.clear();
+    this.disposeStaticGeometry();
 
     this.scene.dispose();
     this.engine.dispose();
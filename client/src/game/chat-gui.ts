@@ -34,7 +34,9 @@ import {
   Control,
 } from '@babylonjs/gui';
 import { BaseDatastarReceiver, type IDatastar } from '../interfaces/datastar';
-import { getPlayerId } from './player-state';
+import { getPlayerId, getSessionToken } from './player-state';
+import { serverUrl } from './server-url';
+import { readApiError } from './api-error';
 
 /**
  * Animation proxy interface for Babylon.js animations
@@ -60,7 +62,6 @@ export class ChatGUI extends BaseDatastarReceiver implements IDatastar {
   private sendButton: Button | null = null;
   private toggleButton: Button | null = null;
   private isOpen = false;
-  private playerId: string;
   private scene: Scene;
   private inputFocused = false;
 
@@ -74,7 +75,6 @@ export class ChatGUI extends BaseDatastarReceiver implements IDatastar {
 
   constructor(_engine: Engine, scene: Scene) {
     super();
-    this.playerId = getPlayerId();
     this.scene = scene;
     this.initializeGUI(_engine, scene);
   }
@@ -435,25 +435,28 @@ export class ChatGUI extends BaseDatastarReceiver implements IDatastar {
       return;
     }
 
-    if (!this.playerId) {
+    // Assigned by the server once init succeeds
+    const playerId = getPlayerId();
+    if (!playerId) {
       console.error(`[${this.id}] ❌ Player ID not available - cannot send message`);
       return;
     }
 
     console.log(
-      `[${this.id}] 📤 Sending chat message: "${text}" from player: ${this.playerId.substring(0, 8)}`
+      `[${this.id}] 📤 Sending chat message: "${text}" from player: ${playerId.substring(0, 8)}`
     );
 
     const payload = {
-      player_id: this.playerId,
+      player_id: playerId,
       text: text,
+      session_token: getSessionToken() ?? undefined,
     };
 
     // Clear input immediately (optimistic UI update)
     const originalText = this.inputField.text;
     this.inputField.text = '';
 
-    fetch('/api/chat', {
+    fetch(serverUrl('/api/chat'), {
       method: 'POST',
       headers: { 
         'Content-Type': 'application/json',
@@ -464,10 +467,11 @@ export class ChatGUI extends BaseDatastarReceiver implements IDatastar {
         console.log(`[${this.id}] 📥 Received response: status=${response.status}, ok=${response.ok}`);
         
         if (!response.ok) {
-          const errorText = await response.text();
+          const error = await readApiError(response);
           console.error(
-            `[${this.id}] ❌ Chat message failed with status: ${response.status}, error: ${errorText}`
+            `[${this.id}] ❌ Chat message failed with status: ${response.status}, error: ${error.code}`
           );
+          this.showLocalNotice(error.message);
           // Restore text if send failed
           if (this.inputField) {
             this.inputField.text = originalText;
@@ -488,6 +492,16 @@ export class ChatGUI extends BaseDatastarReceiver implements IDatastar {
       });
   }
 
+  /**
+   * Show a line in this player's chat only, styled like the server's system lines
+   */
+  private showLocalNotice(text: string): void {
+    const line = document.createElement('div');
+    line.style.cssText = 'margin-bottom: 8px; font-size: 14px; color: #FF6666; font-style: italic';
+    line.textContent = text;
+    this.onElementUpdate('#chat-messages', 'append', line.outerHTML);
+  }
+
   /**
    * Handle element updates from Datastar (chat messages)
    *
@@ -7,11 +7,16 @@
 
 import { datastarManager } from './datastar-manager';
 import { PlayerReceiver } from './receivers/player-receiver';
+import { AnnouncementReceiver } from './receivers/announcement-receiver';
+import { MatchReceiver } from './receivers/match-receiver';
+import { checkSignalManifest } from './signals';
 // Chat is now handled by ChatGUI (Babylon GUI), not a separate receiver
 // import { ChatReceiver } from './receivers/chat-receiver';
 
 // Create receiver instances
 const playerReceiver = new PlayerReceiver();
+const announcementReceiver = new AnnouncementReceiver();
+const matchReceiver = new MatchReceiver();
 // Chat receiver removed - ChatGUI handles chat updates now
 
 /**
@@ -27,8 +32,13 @@ export function initializeDatastar(endpoint: string = '/events'): void {
 
   // Register all receivers
   datastarManager.register(playerReceiver);
+  datastarManager.register(announcementReceiver);
+  datastarManager.register(matchReceiver);
   // Chat is registered by BabylonRenderer when ChatGUI is created
 
+  // Warn in the console if client and server disagree on signal names
+  void checkSignalManifest();
+
   // Connect to SSE endpoint
   datastarManager.connect(endpoint);
 
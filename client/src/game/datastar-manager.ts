@@ -7,6 +7,8 @@
  */
 
 import type { IDatastar } from '../interfaces/datastar';
+import { checkIncomingSignal, Signals } from './signals';
+import { serverUrl } from './server-url';
 
 export class DatastarUpdateManager {
   private receivers: Map<string, IDatastar> = new Map();
@@ -51,7 +53,7 @@ export class DatastarUpdateManager {
       this.disconnect();
     }
 
-    this.eventSource = new EventSource(endpoint);
+    this.eventSource = new EventSource(serverUrl(endpoint));
 
     // Handle signal patches
     this.eventSource.addEventListener('datastar-patch-signals', (event: MessageEvent) => {
@@ -224,8 +226,16 @@ export class DatastarUpdateManager {
 
       const signalData = JSON.parse(jsonStr);
 
+      // A refused stream would only be refused again, so stop instead of reconnecting
+      if (Signals.ProtocolError in signalData) {
+        console.error('[DatastarManager] ❌ Server refused the event protocol:', signalData[Signals.ProtocolError]);
+        this.disconnect();
+        return;
+      }
+
       // Route to all receivers
       for (const [signalName, value] of Object.entries(signalData)) {
+        checkIncomingSignal(signalName);
         for (const receiver of this.receivers.values()) {
           try {
             receiver.onSignalUpdate(signalName, value);
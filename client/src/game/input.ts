@@ -1,10 +1,46 @@
 import { Scene } from '@babylonjs/core';
-import { getPlayerId, initPlayer as initPlayerOnServer } from './player-state';
+import {
+  gameState,
+  getPlayerId,
+  getSessionToken,
+  initPlayer as initPlayerOnServer,
+} from './player-state';
 import { datastarManager } from './datastar-manager';
+import { serverUrl } from './server-url';
+import { readApiError } from './api-error';
 
-type PlayerCommand = 'MoveLeft' | 'MoveRight' | 'Jump' | 'Stop';
+type PlayerCommand =
+  | 'Jump'
+  | 'Stop'
+  | 'MoveUp'
+  | 'MoveDown'
+  | 'Crouch'
+  | 'StandUp'
+  | 'Sprint'
+  | 'StopSprint'
+  | 'Dash';
 
-let playerId: string;
+/** A direction the server keeps applying every tick until it is released */
+type HeldKey = 'Left' | 'Right';
+
+/** Server reply to a command, predicted against the state when it arrived */
+interface CommandAck {
+  status: 'accepted' | 'buffered' | 'ignored';
+  /** Why an ignored command did nothing, e.g. { code: 'not_grounded' } */
+  reason?: { code: string; detail?: string };
+  cooldowns: { dash_ms: number; shoot_ms: number; place_block_ms: number };
+}
+
+interface Ladder {
+  x_start: number;
+  x_end: number;
+  y_bottom: number;
+  y_top: number;
+}
+
+/** Ladders from the game config; up climbs instead of jumping while on one */
+let ladders: Ladder[] = [];
+let playerHeight = 0;
 
 /**
  * Check if chat input is currently focused
@@ -33,41 +69,68 @@ export function initPlayer(): void {
   initPlayerOnServer();
 }
 
+/** Load the ladders once so up/down can tell climbing from jumping */
+function loadLadders(): void {
+  fetch(serverUrl('/api/config'))
+    .then((response) => response.json())
+    .then((config: { ladders?: Ladder[]; physics?: { player_height?: number } }) => {
+      ladders = Array.isArray(config.ladders) ? config.ladders : [];
+      playerHeight = config.physics?.player_height ?? 0;
+    })
+    .catch((err) => {
+      console.error('[Input] ❌ Failed to load ladders:', err);
+    });
+}
+
+/** Whether the local player can hold on to a ladder, mirroring the server's check */
+function onLadder(): boolean {
+  const me = gameState.value.gameState.find((p) => p.id === getPlayerId());
+  if (!me) {
+    return false;
+  }
+  const halfHeight = playerHeight / 2;
+  return ladders.some(
+    (ladder) =>
+      me.x >= ladder.x_start &&
+      me.x <= ladder.x_end &&
+      me.y - halfHeight < ladder.y_top &&
+      me.y + halfHeight > ladder.y_bottom
+  );
+}
+
 export function setupInput(scene: Scene): void {
-  playerId = getPlayerId();
+  loadLadders();
+  // The renderer announces map rotations once the new geometry is loaded
+  window.addEventListener('mapchange', loadLadders);
+  window.addEventListener('configchange', loadLadders);
   const activeKeys = new Set<string>();
   let lastSendTime = 0;
-  const sendInterval = 100; // Send command every 100ms while key is held
+  // The server applies a held direction every tick; resending it keeps the hold alive,
+  // well inside the server's timeout for a lost KeyUp
+  const refreshInterval = 200;
+
+  /** The most recently pressed movement direction still held down */
+  const latestDirection = (): HeldKey | null => {
+    const movementKeys = Array.from(activeKeys).filter((k) => heldKeyFor(k) !== null);
+    const latestKey = movementKeys[movementKeys.length - 1];
+    return latestKey ? heldKeyFor(latestKey) : null;
+  };
 
-  // Use Babylon.js observable for continuous movement updates
+  const holdDirection = (key: HeldKey): void => {
+    postCommand({ type: 'KeyDown', key }, `KeyDown ${key}`);
+    lastSendTime = Date.now();
+  };
+
+  // Use Babylon.js observable to refresh the held direction
   const handleMovement = (): void => {
     // Don't process movement if chat input is focused
     if (isChatInputFocused()) {
       return;
     }
 
-    const hasMovementKey = ['ArrowLeft', 'ArrowRight', 'a', 'A', 'd', 'D'].some((key) =>
-      activeKeys.has(key)
-    );
-
-    if (hasMovementKey) {
-      const now = Date.now();
-      if (now - lastSendTime >= sendInterval) {
-        // Find the most recent movement key
-        const movementKeys = Array.from(activeKeys).filter((k) =>
-          ['ArrowLeft', 'ArrowRight', 'a', 'A', 'd', 'D'].includes(k)
-        );
-        if (movementKeys.length > 0) {
-          const latestKey = movementKeys[movementKeys.length - 1];
-          if (latestKey) {
-            const command = getCommandForKey(latestKey);
-            if (command) {
-              sendCommand(command);
-            }
-          }
-        }
-        lastSendTime = now;
-      }
+    const direction = latestDirection();
+    if (direction && Date.now() - lastSendTime >= refreshInterval) {
+      holdDirection(direction);
     }
   };
 
@@ -91,15 +154,34 @@ export function setupInput(scene: Scene): void {
     }
 
     // Prevent default for game keys
-    if (['ArrowLeft', 'ArrowRight', 'ArrowUp', ' ', 'a', 'A', 'd', 'D', 'w', 'W'].includes(e.key)) {
+    if (['ArrowLeft', 'ArrowRight', 'ArrowUp', 'ArrowDown', ' ', 'a', 'A', 'd', 'D', 'w', 'W', 's', 'S', 'f', 'F', 'e', 'E'].includes(e.key)) {
       e.preventDefault();
     }
 
+    // Shoot in the direction the player is facing
+    if ((e.key === 'f' || e.key === 'F') && !e.repeat) {
+      shoot();
+      return;
+    }
+
+    // Hold shift to sprint; E dashes the way the player faces
+    if (e.key === 'Shift' && !e.repeat) {
+      sendCommand('Sprint');
+      return;
+    }
+    if ((e.key === 'e' || e.key === 'E') && !e.repeat) {
+      sendCommand('Dash');
+      return;
+    }
+
     // Only add if not already pressed (avoid duplicate commands)
     if (!activeKeys.has(e.key)) {
       activeKeys.add(e.key);
+      const direction = heldKeyFor(e.key);
       const command = getCommandForKey(e.key);
-      if (command) {
+      if (direction) {
+        holdDirection(direction);
+      } else if (command) {
         sendCommand(command);
       }
     }
@@ -108,31 +190,57 @@ export function setupInput(scene: Scene): void {
   window.addEventListener('keyup', (e) => {
     activeKeys.delete(e.key);
 
-    // Stop continuous movement if no movement keys are pressed
-    const hasMovementKey = ['ArrowLeft', 'ArrowRight', 'a', 'A', 'd', 'D'].some((key) =>
-      activeKeys.has(key)
-    );
-    if (!hasMovementKey) {
-      sendCommand('Stop');
+    if (e.key === 'Shift') {
+      sendCommand('StopSprint');
+      return;
+    }
+
+    // Releasing down stands back up once there's room overhead
+    if (['ArrowDown', 's', 'S'].includes(e.key)) {
+      sendCommand('StandUp');
+    }
+
+    // Fall back to a direction still held, or let go and stop
+    const released = heldKeyFor(e.key);
+    if (released) {
+      const direction = latestDirection();
+      if (direction) {
+        holdDirection(direction);
+      } else {
+        postCommand({ type: 'KeyUp', key: released }, `KeyUp ${released}`);
+      }
     }
   });
 }
 
-function getCommandForKey(key: string): PlayerCommand | null {
+/** Direction a movement key holds down, or null for other keys */
+function heldKeyFor(key: string): HeldKey | null {
   switch (key) {
     case 'ArrowLeft':
     case 'a':
     case 'A':
-      return 'MoveLeft';
+      return 'Left';
     case 'ArrowRight':
     case 'd':
     case 'D':
-      return 'MoveRight';
+      return 'Right';
+    default:
+      return null;
+  }
+}
+
+function getCommandForKey(key: string): PlayerCommand | null {
+  switch (key) {
     case ' ':
+      return 'Jump';
     case 'ArrowUp':
     case 'w':
     case 'W':
-      return 'Jump';
+      return onLadder() ? 'MoveUp' : 'Jump';
+    case 'ArrowDown':
+    case 's':
+    case 'S':
+      return onLadder() ? 'MoveDown' : 'Crouch';
     default:
       return null;
   }
@@ -140,24 +248,44 @@ function getCommandForKey(key: string): PlayerCommand | null {
 
 // Removed handleKey - commands are now sent directly in setupInput
 
+/** Fire a projectile horizontally in the direction the player faces */
+function shoot(): void {
+  const me = gameState.value.gameState.find((p) => p.id === getPlayerId());
+  const dirX = me && !me.facing_right ? -1 : 1;
+  postCommand({ type: 'Shoot', dir_x: dirX, dir_y: 0 }, 'Shoot');
+}
+
 function sendCommand(command: PlayerCommand): void {
+  postCommand({ type: command }, command);
+}
+
+function postCommand(command: Record<string, unknown>, label: string): void {
+  const playerId = getPlayerId();
   const payload = {
     player_id: playerId,
-    command: { type: command },
+    command,
+    session_token: getSessionToken() ?? undefined,
   };
 
-  console.log(`[Input] 📤 Sending command: ${command} for player: ${playerId.substring(0, 8)}`);
+  console.log(`[Input] 📤 Sending command: ${label} for player: ${playerId.substring(0, 8)}`);
 
-  fetch('/api/player/command', {
+  fetch(serverUrl('/api/player/command'), {
     method: 'POST',
     headers: { 'Content-Type': 'application/json' },
     body: JSON.stringify(payload),
   })
-    .then((response) => {
+    .then(async (response) => {
       if (!response.ok) {
-        console.error(`[Input] ❌ Command failed with status: ${response.status}`);
+        const error = await readApiError(response);
+        console.error(`[Input] ❌ Command failed with status: ${response.status} (${error.code}: ${error.message})`);
+        return;
+      }
+      const ack = (await response.json()) as CommandAck;
+      if (ack.status === 'ignored') {
+        const reason = ack.reason?.detail ? `${ack.reason.code}: ${ack.reason.detail}` : ack.reason?.code;
+        console.log(`[Input] ⏸️ Command ignored: ${label} (${reason})`);
       } else {
-        console.log(`[Input] ✅ Command sent successfully: ${command}`);
+        console.log(`[Input] ✅ Command sent successfully: ${label}`);
       }
     })
     .catch((err) => {
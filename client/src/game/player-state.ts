@@ -1,3 +1,6 @@
+import { serverUrl } from './server-url';
+import { PROTOCOL_VERSION } from './signals';
+
 /**
  * Player data structure matching server-side Player
  * Note: ground_state is a discriminated union on server, but we use on_ground for compatibility
@@ -11,15 +14,51 @@ export interface Player {
   velocity_y: number;
   facing_right: boolean;
   ground_state: GroundState;
+  /** Team id and color when teams are enabled */
+  team?: string;
+  team_color?: string;
+  /** Server-chosen color, sent when the player picked a non-standard palette */
+  color?: string;
+  /** Remaining health; projectiles, hazards and stomps take it away */
+  health?: number;
+  /** Seconds of damage immunity left after a hit or respawn */
+  invulnerable_secs?: number;
+  /** Crouching players are half height and can slide under low platforms */
+  crouched?: boolean;
+  /** Remaining stamina for the HUD bar; sprinting, dashing and wall jumps spend it */
+  stamina?: number;
+  /** Running at the sprint speed */
+  sprinting?: boolean;
+  /** No input for a while; drawn grayed out until the player is back */
+  afk?: boolean;
+  /** Set on the first snapshot with this player: place it directly, don't interpolate */
+  spawn?: boolean;
+  /** Set when the player teleported (respawn): jump to the new position */
+  snap?: boolean;
+  /** Dead players wait out the respawn delay before coming back at a spawn point */
+  life?: LifeState;
+  /** Equipped cosmetic item ids; look values up in the config's cosmetics catalog */
+  cosmetics?: EquippedCosmetics;
+}
+
+export interface EquippedCosmetics {
+  trail?: string;
+  hat?: string;
+  name_color?: string;
 }
 
+export type LifeState =
+  | { state: 'alive' }
+  | { state: 'dead'; cause: 'fell_out' | 'out_of_bounds' | 'shot'; respawn_in_secs: number };
+
 /**
  * Discriminated union for ground state
  */
 export type GroundState =
   | { type: 'Grounded'; platform_id: number | null }
   | { type: 'Sliding'; side: 'left' | 'right'; platform_id: number | null }
-  | { type: 'Flying' };
+  | { type: 'Flying' }
+  | { type: 'Climbing'; ladder_id: number };
 
 /**
  * Simple reactive game state object (replaces Vue ref)
@@ -27,36 +66,185 @@ export type GroundState =
  */
 export const gameState = { value: { gameState: [] as Player[] } };
 
+/**
+ * This tab's player id, assigned by the server on the first init
+ * Kept in sessionStorage so each tab is its own player; empty until init succeeds
+ */
 export function getPlayerId(): string {
-  // Use sessionStorage instead of localStorage so each tab gets a unique ID
-  // This allows multiple players to connect from different tabs
-  let id = sessionStorage.getItem('playerId');
-  if (!id) {
-    id = crypto.randomUUID();
-    sessionStorage.setItem('playerId', id);
-    console.log(`[PlayerState] 🆕 Generated new player ID for this tab: ${id.substring(0, 8)}`);
-  } else {
-    console.log(`[PlayerState] ♻️ Using existing player ID for this tab: ${id.substring(0, 8)}`);
+  return sessionStorage.getItem('playerId') ?? '';
+}
+
+/**
+ * Session token from the last init, proving this tab owns its player
+ * Sent back on reconnect so the server hands over the same player instead of a new one
+ */
+export function getSessionToken(): string | null {
+  return sessionStorage.getItem('sessionToken');
+}
+
+/** Whether this tab only watches (opened with ?spectate): no player, no commands */
+export function isSpectator(): boolean {
+  return new URLSearchParams(window.location.search).has('spectate');
+}
+
+/** SSE endpoint, personalized with the session token when this tab has one */
+export function eventsEndpoint(): string {
+  const protocol = `protocol_version=${PROTOCOL_VERSION}`;
+  if (isSpectator()) {
+    return `/events?mode=spectator&${protocol}`;
+  }
+  const token = getSessionToken();
+  return token ? `/events?session=${encodeURIComponent(token)}&${protocol}` : `/events?${protocol}`;
+}
+
+let heartbeatTimer: number | null = null;
+
+/**
+ * Tell the server this tab is still here while the player is AFK
+ * The interval comes from /api/config; a 404 means the player was removed, so re-init
+ */
+async function startHeartbeat(): Promise<void> {
+  if (heartbeatTimer !== null) {
+    return;
+  }
+  let intervalSecs = 15;
+  try {
+    const config: unknown = await (await fetch(serverUrl('/api/config'))).json();
+    if (typeof config === 'object' && config !== null && 'heartbeat_interval_secs' in config) {
+      const value = (config as { heartbeat_interval_secs: unknown }).heartbeat_interval_secs;
+      if (typeof value === 'number' && value > 0) {
+        intervalSecs = value;
+      }
+    }
+  } catch (err) {
+    console.warn('Failed to load heartbeat interval, using default:', err);
+  }
+
+  heartbeatTimer = window.setInterval(() => {
+    fetch(serverUrl('/api/player/heartbeat'), {
+      method: 'POST',
+      headers: { 'Content-Type': 'application/json' },
+      body: JSON.stringify({
+        player_id: getPlayerId(),
+        session_token: getSessionToken() ?? undefined,
+      }),
+    })
+      .then((response) => {
+        if (response.status === 401) {
+          // The token no longer verifies (e.g. the server's secret changed): start over
+          sessionStorage.removeItem('sessionToken');
+          initPlayer();
+        } else if (response.status === 404) {
+          console.log('♻️ Player was removed, re-initializing');
+          initPlayer();
+        }
+      })
+      .catch((err) => {
+        console.error('Heartbeat failed:', err);
+      });
+  }, intervalSecs * 1000);
+}
+
+let leavesOnClose = false;
+
+/**
+ * Remove the player as soon as the tab closes instead of leaving them to the idle timeout
+ * sendBeacon outlives the page; pages kept in the back/forward cache haven't left yet
+ */
+function leaveOnClose(): void {
+  if (leavesOnClose) {
+    return;
+  }
+  leavesOnClose = true;
+  window.addEventListener('pagehide', (event) => {
+    const token = getSessionToken();
+    if (event.persisted || !token) {
+      return;
+    }
+    const body = new Blob([JSON.stringify({ session_token: token })], { type: 'application/json' });
+    navigator.sendBeacon(serverUrl('/api/player/leave'), body);
+  });
+}
+
+/** Client preferences (keybinds, volume, HUD layout) stored on the server for this player */
+export type PlayerSettings = Record<string, unknown>;
+
+export const playerSettings = { value: {} as PlayerSettings };
+
+/** Fetch this player's stored settings; requires a session token from init */
+export async function loadSettings(): Promise<PlayerSettings> {
+  const token = getSessionToken();
+  if (!token) {
+    return playerSettings.value;
+  }
+  try {
+    const response = await fetch(serverUrl(`/api/player/settings?session_token=${encodeURIComponent(token)}`));
+    if (response.ok) {
+      const body: unknown = await response.json();
+      if (typeof body === 'object' && body !== null && 'settings' in body) {
+        const settings = (body as { settings: unknown }).settings;
+        if (typeof settings === 'object' && settings !== null && !Array.isArray(settings)) {
+          playerSettings.value = settings as PlayerSettings;
+        }
+      }
+    }
+  } catch (err) {
+    console.error('Failed to load settings:', err);
+  }
+  return playerSettings.value;
+}
+
+/** Merge changes into this player's settings and store them on the server */
+export async function saveSettings(changes: PlayerSettings): Promise<boolean> {
+  const token = getSessionToken();
+  playerSettings.value = { ...playerSettings.value, ...changes };
+  if (!token) {
+    return false;
+  }
+  try {
+    const response = await fetch(serverUrl('/api/player/settings'), {
+      method: 'PUT',
+      headers: { 'Content-Type': 'application/json' },
+      body: JSON.stringify({ session_token: token, settings: playerSettings.value }),
+    });
+    return response.ok;
+  } catch (err) {
+    console.error('Failed to save settings:', err);
+    return false;
   }
-  return id;
 }
 
 // Initialize player on server when they connect
+// Without a session token the server creates a new player and returns its id
 export function initPlayer(): void {
-  const playerId = getPlayerId();
-  fetch('/api/player/init', {
+  const token = getSessionToken();
+  fetch(serverUrl('/api/player/init'), {
     method: 'POST',
     headers: { 'Content-Type': 'application/json' },
     body: JSON.stringify({
-      player_id: playerId,
+      player_id: token ? getPlayerId() : undefined,
+      session_token: token ?? undefined,
+      // Server-rendered chat notices use this language
+      language: navigator.language,
     }),
   })
-    .then((response) => {
+    .then(async (response) => {
       if (!response.ok) {
         console.error('Player initialization failed with status:', response.status);
-      } else {
-        console.log('✅ Player initialized successfully:', playerId);
+        return;
+      }
+      const body: unknown = await response.json();
+      if (typeof body === 'object' && body !== null && 'session_token' in body && 'player_id' in body) {
+        const { session_token: token, player_id: id } = body as { session_token: unknown; player_id: unknown };
+        if (typeof token === 'string' && typeof id === 'string') {
+          sessionStorage.setItem('sessionToken', token);
+          sessionStorage.setItem('playerId', id);
+        }
       }
+      console.log('✅ Player initialized successfully:', getPlayerId());
+      startHeartbeat();
+      leaveOnClose();
+      loadSettings();
     })
     .catch((err) => {
       console.error('Failed to initialize player:', err);
@@ -82,7 +270,7 @@ if (typeof window !== 'undefined' && false) {
     }
 
     console.log('📡 Connecting to SSE endpoint: /events');
-    eventSource = new EventSource('/events');
+    eventSource = new EventSource(serverUrl('/events'));
 
     // Handle SSE events - Datastar sends events with event type and data
     // Listen for both signal patches (game state) and element patches (chat)
@@ -130,6 +318,11 @@ if (typeof window !== 'undefined' && false) {
                           : 'left',
                       platform_id: typeof gs['platform_id'] === 'number' ? gs['platform_id'] : null,
                     };
+                  } else if (gs['type'] === 'Climbing') {
+                    parsedGroundState = {
+                      type: 'Climbing',
+                      ladder_id: typeof gs['ladder_id'] === 'number' ? gs['ladder_id'] : 0,
+                    };
                   } else {
                     parsedGroundState = { type: 'Flying' };
                   }
@@ -0,0 +1,48 @@
+/**
+ * AnnouncementReceiver
+ *
+ * Shows server announcement banners as a fixed overlay above the game canvas.
+ * The server patches #announcement-banner with a styled banner, or an empty one to clear it.
+ */
+
+import { BaseDatastarReceiver } from '../../interfaces/datastar';
+
+const BANNER_SELECTOR = '#announcement-banner';
+const OVERLAY_ID = 'announcement-overlay';
+
+export class AnnouncementReceiver extends BaseDatastarReceiver {
+  readonly id = 'announcement-receiver';
+
+  override onElementUpdate(selector: string, _mode: string, html: string): void {
+    if (selector !== BANNER_SELECTOR || typeof document === 'undefined') {
+      return;
+    }
+
+    const overlay = this.getOverlay();
+    overlay.innerHTML = html;
+    const banner = overlay.firstElementChild;
+    overlay.style.display = banner && banner.textContent?.trim() ? 'block' : 'none';
+  }
+
+  override dispose(): void {
+    document.getElementById(OVERLAY_ID)?.remove();
+  }
+
+  private getOverlay(): HTMLElement {
+    let overlay = document.getElementById(OVERLAY_ID);
+    if (!overlay) {
+      overlay = document.createElement('div');
+      overlay.id = OVERLAY_ID;
+      overlay.style.position = 'fixed';
+      overlay.style.top = '16px';
+      overlay.style.left = '50%';
+      overlay.style.transform = 'translateX(-50%)';
+      overlay.style.zIndex = '1000';
+      overlay.style.pointerEvents = 'none';
+      overlay.style.maxWidth = '80vw';
+      overlay.style.display = 'none';
+      document.body.appendChild(overlay);
+    }
+    return overlay;
+  }
+}
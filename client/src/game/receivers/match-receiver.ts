@@ -0,0 +1,117 @@
+/**
+ * MatchReceiver
+ *
+ * Shows the match phase as a HUD line: lobby, countdown to start, time left, and results,
+ * prefixed with the name of the active game mode.
+ * Phase deadlines are server Unix milliseconds, so the offset to the local clock is
+ * tracked from the serverTime signal that accompanies every state update.
+ */
+
+import { BaseDatastarReceiver } from '../../interfaces/datastar';
+import { Signals } from '../signals';
+
+const HUD_ID = 'match-hud';
+
+type MatchState =
+  | { phase: 'lobby' }
+  | { phase: 'countdown'; starts_at_ms: number }
+  | { phase: 'playing'; started_at_ms: number; ends_at_ms: number }
+  | {
+      phase: 'ended';
+      lobby_at_ms: number;
+      result: {
+        winner: string | null;
+        winning_team: string | null;
+        standings: Array<{ player_id: string; player_name: string; score: number }>;
+      };
+    };
+
+export class MatchReceiver extends BaseDatastarReceiver {
+  readonly id = 'match-receiver';
+
+  private state: MatchState = { phase: 'lobby' };
+  /** Display name from the mode signal */
+  private modeName: string | null = null;
+  /** Server clock minus local clock, in milliseconds */
+  private serverOffsetMs = 0;
+  private timer: number | null = null;
+
+  override onSignalUpdate(signalName: string, data: unknown): void {
+    if (signalName === Signals.ServerTime && typeof data === 'number') {
+      this.serverOffsetMs = data - Date.now();
+    } else if (signalName === Signals.MatchState && typeof data === 'object' && data !== null && 'phase' in data) {
+      this.state = data as MatchState;
+      this.render();
+    } else if (signalName === Signals.Mode && typeof data === 'object' && data !== null && 'name' in data) {
+      this.modeName = String((data as { name: unknown }).name);
+      this.render();
+    }
+  }
+
+  override initialize(): void {
+    if (typeof window === 'undefined' || this.timer !== null) {
+      return;
+    }
+    this.timer = window.setInterval(() => this.render(), 250);
+  }
+
+  override dispose(): void {
+    if (this.timer !== null) {
+      window.clearInterval(this.timer);
+      this.timer = null;
+    }
+    document.getElementById(HUD_ID)?.remove();
+  }
+
+  private secondsUntil(serverMs: number): number {
+    return Math.max(0, Math.ceil((serverMs - (Date.now() + this.serverOffsetMs)) / 1000));
+  }
+
+  private render(): void {
+    if (typeof document === 'undefined') {
+      return;
+    }
+    const hud = this.getHud();
+    const phase = this.phaseText();
+    hud.textContent = this.modeName ? `${this.modeName} · ${phase}` : phase;
+  }
+
+  private phaseText(): string {
+    switch (this.state.phase) {
+      case 'lobby':
+        return 'Waiting for players…';
+      case 'countdown':
+        return `Match starts in ${this.secondsUntil(this.state.starts_at_ms)}`;
+      case 'playing': {
+        const left = this.secondsUntil(this.state.ends_at_ms);
+        return `Time left ${Math.floor(left / 60)}:${String(left % 60).padStart(2, '0')}`;
+      }
+      case 'ended': {
+        const { winner, standings } = this.state.result;
+        const name = standings.find((p) => p.player_id === winner)?.player_name;
+        return name ? `🏆 ${name} wins!` : 'Match over: draw';
+      }
+    }
+  }
+
+  private getHud(): HTMLElement {
+    let hud = document.getElementById(HUD_ID);
+    if (!hud) {
+      hud = document.createElement('div');
+      hud.id = HUD_ID;
+      hud.style.position = 'fixed';
+      hud.style.top = '16px';
+      hud.style.right = '16px';
+      hud.style.padding = '6px 12px';
+      hud.style.background = 'rgba(0, 0, 0, 0.6)';
+      hud.style.color = '#FFFFFF';
+      hud.style.fontSize = '16px';
+      hud.style.fontWeight = 'bold';
+      hud.style.borderRadius = '4px';
+      hud.style.pointerEvents = 'none';
+      hud.style.zIndex = '900';
+      document.body.appendChild(hud);
+    }
+    return hud;
+  }
+}
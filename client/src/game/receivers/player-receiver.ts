@@ -6,13 +6,14 @@
  */
 
 import { BaseDatastarReceiver } from '../../interfaces/datastar';
-import { gameState, type Player, type GroundState } from '../player-state';
+import { gameState, type Player, type GroundState, type LifeState, type EquippedCosmetics } from '../player-state';
+import { Signals } from '../signals';
 
 export class PlayerReceiver extends BaseDatastarReceiver {
   readonly id = 'player-receiver';
 
   override onSignalUpdate(signalName: string, data: unknown): void {
-    if (signalName === 'gameState') {
+    if (signalName === Signals.GameState) {
       try {
         // Ensure data is an array of players
         if (Array.isArray(data)) {
@@ -41,6 +42,11 @@ export class PlayerReceiver extends BaseDatastarReceiver {
                         : 'left',
                     platform_id: typeof gs['platform_id'] === 'number' ? gs['platform_id'] : null,
                   };
+                } else if (gs['type'] === 'Climbing') {
+                  parsedGroundState = {
+                    type: 'Climbing',
+                    ladder_id: typeof gs['ladder_id'] === 'number' ? gs['ladder_id'] : 0,
+                  };
                 } else {
                   parsedGroundState = { type: 'Flying' };
                 }
@@ -60,6 +66,27 @@ export class PlayerReceiver extends BaseDatastarReceiver {
                 facing_right:
                   typeof playerObj['facing_right'] === 'boolean' ? playerObj['facing_right'] : true,
                 ground_state: parsedGroundState,
+                ...(typeof playerObj['team'] === 'string' ? { team: playerObj['team'] } : {}),
+                ...(typeof playerObj['team_color'] === 'string'
+                  ? { team_color: playerObj['team_color'] }
+                  : {}),
+                ...(typeof playerObj['color'] === 'string' ? { color: playerObj['color'] } : {}),
+                ...(typeof playerObj['health'] === 'number' ? { health: playerObj['health'] } : {}),
+                ...(typeof playerObj['invulnerable_secs'] === 'number'
+                  ? { invulnerable_secs: playerObj['invulnerable_secs'] }
+                  : {}),
+                ...(playerObj['crouched'] === true ? { crouched: true } : {}),
+                ...(typeof playerObj['stamina'] === 'number' ? { stamina: playerObj['stamina'] } : {}),
+                ...(playerObj['sprinting'] === true ? { sprinting: true } : {}),
+                ...(playerObj['afk'] === true ? { afk: true } : {}),
+                ...(playerObj['spawn'] === true ? { spawn: true } : {}),
+                ...(playerObj['snap'] === true ? { snap: true } : {}),
+                ...(typeof playerObj['life'] === 'object' && playerObj['life'] !== null
+                  ? { life: playerObj['life'] as LifeState }
+                  : {}),
+                ...(typeof playerObj['cosmetics'] === 'object' && playerObj['cosmetics'] !== null
+                  ? { cosmetics: playerObj['cosmetics'] as EquippedCosmetics }
+                  : {}),
               };
             }
             throw new Error('Invalid player data');
@@ -0,0 +1,20 @@
+/**
+ * Server URLs
+ *
+ * The server can be mounted under a path prefix (e.g. /game/ behind nginx), in which case
+ * it serves index.html with a <base> tag naming the prefix. Server paths are written from
+ * the root, like '/api/config', and go through serverUrl() to pick the prefix up.
+ */
+
+/** The prefix from the page's <base> tag, without a trailing slash; empty at the root */
+function basePath(): string {
+  const href = document.querySelector('base')?.getAttribute('href') ?? '';
+  return href.replace(/\/+$/, '');
+}
+
+/**
+ * Prefix a root-relative server path with the base path the page was served under
+ */
+export function serverUrl(path: string): string {
+  return `${basePath()}${path}`;
+}
@@ -0,0 +1,81 @@
+/**
+ * Signal names
+ *
+ * Names of the Datastar signals this client listens for. The server lists every signal it
+ * can send at /api/signals; checkSignalManifest() reports drift between the two, and the
+ * Datastar manager flags incoming signals the manifest doesn't know.
+ */
+
+import { serverUrl } from './server-url';
+
+export const Signals = {
+  GameState: 'gameState',
+  Projectiles: 'projectiles',
+  ServerTime: 'serverTime',
+  TimeScale: 'timeScale',
+  MatchState: 'matchState',
+  Map: 'map',
+  MapGeometry: 'mapGeometry',
+  Mode: 'mode',
+  WorldEvent: 'worldEvent',
+  WorldEntities: 'worldEntities',
+  KillCam: 'killCam',
+  ConfigVersion: 'configVersion',
+  ProtocolError: 'protocolError',
+  Bounces: 'bounces',
+  Teleports: 'teleports',
+  Race: 'race',
+  Tag: 'tag',
+  Hill: 'hill',
+} as const;
+
+/**
+ * Event protocol this client speaks, sent to /events as protocol_version
+ * The minor is the signals version of the newest signal listed above
+ */
+export const PROTOCOL_VERSION = '1.14';
+
+export type SignalName = (typeof Signals)[keyof typeof Signals];
+
+interface SignalManifest {
+  version: number;
+  protocol_version: string;
+  signals: Array<{ name: string; kind: string; nullable: boolean; description: string }>;
+}
+
+/** Signal names from the server's manifest, once loaded */
+let manifestNames: Set<string> | null = null;
+/** Unknown signals already reported, so each is logged once */
+const reported = new Set<string>();
+
+/**
+ * Fetch the server's signal manifest and report client names it doesn't list
+ */
+export async function checkSignalManifest(): Promise<void> {
+  try {
+    const response = await fetch(serverUrl('/api/signals'));
+    if (!response.ok) {
+      throw new Error(response.statusText);
+    }
+    const manifest = (await response.json()) as SignalManifest;
+    manifestNames = new Set(manifest.signals.map((signal) => signal.name));
+    for (const name of Object.values(Signals)) {
+      if (!manifestNames.has(name)) {
+        console.error(`[Signals] ❌ Client listens for "${name}", which the server no longer sends (manifest v${manifest.version})`);
+      }
+    }
+  } catch (err) {
+    console.warn('[Signals] ⚠️ Could not load the signal manifest:', err);
+  }
+}
+
+/**
+ * Report a signal from the stream that the server's manifest doesn't list
+ */
+export function checkIncomingSignal(name: string): void {
+  if (manifestNames === null || manifestNames.has(name) || reported.has(name)) {
+    return;
+  }
+  reported.add(name);
+  console.warn(`[Signals] ⚠️ Received signal "${name}", which is missing from /api/signals`);
+}
@@ -8,6 +8,7 @@
 import { BabylonRenderer } from './game/babylon-renderer';
 import { setupInput, initPlayer } from './game/input';
 import { initializeDatastar } from './game/datastar-init';
+import { eventsEndpoint, isSpectator } from './game/player-state';
 import { datastarManager } from './game/datastar-manager';
 import './datastar-boot';
 
@@ -25,16 +26,21 @@ function init(): void {
   canvas.height = window.innerHeight;
 
   // Initialize Datastar system
-  initializeDatastar('/events');
+  initializeDatastar(eventsEndpoint());
 
-  // Initialize player on server
-  initPlayer();
+  // Spectators watch without a player on the server
+  const spectating = isSpectator();
+  if (!spectating) {
+    initPlayer();
+  }
 
   // Create Babylon renderer (includes chat GUI)
   const renderer = new BabylonRenderer(canvas);
 
   // Setup input handlers (requires scene from renderer)
-  setupInput(renderer.getScene());
+  if (!spectating) {
+    setupInput(renderer.getScene());
+  }
 
   // Register renderer with Datastar manager
   datastarManager.register(renderer);
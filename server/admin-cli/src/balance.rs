@@ -0,0 +1,175 @@
+use std::sync::Arc;
+use serde_json::Value;
+use game_core::config::GameConfig;
+use game_core::simulation::{BotAction, ScriptedBot, Simulation};
+use game_core::PlayerCommand;
+
+/// Longest any single scenario may run, in simulated seconds
+const SCENARIO_TIMEOUT_SECS: f32 = 60.0;
+
+/// One physics parameter swept over evenly spaced values
+struct Sweep {
+    field: String,
+    values: Vec<f64>,
+}
+
+impl Sweep {
+    /// Parse `field=start:end:steps`, e.g. `gravity=-200:-100:5`
+    fn parse(spec: &str) -> Result<Self, String> {
+        let err = || format!("invalid sweep '{}', expected field=start:end:steps", spec);
+        let (field, range) = spec.split_once('=').ok_or_else(err)?;
+        let parts: Vec<&str> = range.split(':').collect();
+        let [start, end, steps] = parts.as_slice() else {
+            return Err(err());
+        };
+        let start: f64 = start.parse().map_err(|_| err())?;
+        let end: f64 = end.parse().map_err(|_| err())?;
+        let steps: usize = steps.parse().map_err(|_| err())?;
+        let values = match steps {
+            0 => return Err(err()),
+            1 => vec![start],
+            n => (0..n).map(|i| start + (end - start) * i as f64 / (n - 1) as f64).collect(),
+        };
+        Ok(Self {
+            field: field.trim().to_string(),
+            values,
+        })
+    }
+}
+
+/// Metrics measured for one physics configuration
+struct Metrics {
+    jump_height: f32,
+    jump_distance: f32,
+    /// Seconds of holding a direction before reaching max speed
+    top_speed_secs: Option<f32>,
+    /// Seconds to run across the map's ground from wall to wall
+    cross_secs: Option<f32>,
+}
+
+/// A copy of the config with physics fields overridden
+fn with_physics(base: &GameConfig, overrides: &[(&str, f64)]) -> Result<GameConfig, String> {
+    let mut physics = serde_json::to_value(&base.physics).map_err(|e| e.to_string())?;
+    for (field, value) in overrides {
+        let slot = physics
+            .get_mut(*field)
+            .filter(|slot| slot.is_number())
+            .ok_or_else(|| format!("unknown numeric physics field '{}'", field))?;
+        *slot = Value::from(*value);
+    }
+    let mut config = base.clone();
+    config.physics = serde_json::from_value(physics).map_err(|e| e.to_string())?;
+    Ok(config)
+}
+
+/// Jump straight up and from a full-speed run on an empty map
+fn measure_jumps(config: &GameConfig) -> (f32, f32, Option<f32>) {
+    let mut flat = config.clone();
+    flat.platforms.clear();
+    flat.walls.clear();
+    let flat = Arc::new(flat);
+    let max_speed = flat.physics.max_horizontal_velocity;
+
+    // Standing jump: highest point above the take-off height
+    let mut sim = Simulation::new(flat.clone());
+    let bot = sim.add_bot(ScriptedBot::new().at(0.0, BotAction::Press(PlayerCommand::Jump)));
+    let start_y = sim.player(&bot).map(|p| p.y).unwrap_or(0.0);
+    let mut max_y = start_y;
+    let mut airborne = false;
+    sim.run_until(SCENARIO_TIMEOUT_SECS, |sim| {
+        let Some(player) = sim.player(&bot) else { return true };
+        max_y = max_y.max(player.y);
+        airborne |= player.ground_state.is_flying();
+        airborne && player.ground_state.is_grounded()
+    });
+
+    // Running jump: hold right until top speed, then jump and measure the flight
+    let mut sim = Simulation::new(flat);
+    let bot = sim.add_bot(ScriptedBot::new().at(0.0, BotAction::Hold(PlayerCommand::MoveRight)));
+    let reached = sim.run_until(SCENARIO_TIMEOUT_SECS, |sim| {
+        sim.player(&bot).is_some_and(|p| p.velocity_x >= max_speed * 0.99)
+    });
+    let top_speed_secs = reached.then(|| sim.time());
+    let start_x = sim.player(&bot).map(|p| p.x).unwrap_or(0.0);
+    sim.command(&bot, &PlayerCommand::Jump);
+    let mut airborne = false;
+    sim.run_until(SCENARIO_TIMEOUT_SECS, |sim| {
+        let Some(player) = sim.player(&bot) else { return true };
+        airborne |= player.ground_state.is_flying();
+        airborne && player.ground_state.is_grounded()
+    });
+    let distance = sim.player(&bot).map(|p| p.x - start_x).unwrap_or(0.0);
+
+    (max_y - start_y, distance, top_speed_secs)
+}
+
+/// Run from the left end of the ground to the right, holding right the whole way
+/// None if something on the map blocks the run
+fn measure_cross(config: &GameConfig) -> Option<f32> {
+    let config = Arc::new(config.clone());
+    let (left, right) = game_core::map_check::ground_range(&config);
+    let mut sim = Simulation::new(config);
+    let bot = sim.add_bot(ScriptedBot::new().at(0.0, BotAction::Hold(PlayerCommand::MoveRight)));
+    sim.player_mut(&bot)?.x = left;
+    let crossed = sim.run_until(SCENARIO_TIMEOUT_SECS, |sim| {
+        sim.player(&bot).is_some_and(|p| p.x >= right - 0.1)
+    });
+    crossed.then(|| sim.time())
+}
+
+fn measure(config: &GameConfig) -> Metrics {
+    let (jump_height, jump_distance, top_speed_secs) = measure_jumps(config);
+    Metrics {
+        jump_height,
+        jump_distance,
+        top_speed_secs,
+        cross_secs: measure_cross(config),
+    }
+}
+
+/// Every combination of swept values, as (field, value) overrides
+fn combinations(sweeps: &[Sweep]) -> Vec<Vec<(&str, f64)>> {
+    sweeps.iter().fold(vec![Vec::new()], |combos, sweep| {
+        combos
+            .iter()
+            .flat_map(|combo| {
+                sweep.values.iter().map(move |value| {
+                    let mut combo = combo.clone();
+                    combo.push((sweep.field.as_str(), *value));
+                    combo
+                })
+            })
+            .collect()
+    })
+}
+
+/// Measure movement metrics for a config, once per combination of swept physics values
+pub fn run_balance(path: &str, sweep_specs: &[String], json: bool) -> Result<(), String> {
+    let base = GameConfig::load(path).map_err(|e| format!("failed to load {}: {}", path, e))?;
+    let sweeps = sweep_specs.iter().map(|s| Sweep::parse(s)).collect::<Result<Vec<_>, _>>()?;
+
+    let mut rows = Vec::new();
+    for overrides in combinations(&sweeps) {
+        let config = with_physics(&base, &overrides)?;
+        let metrics = measure(&config);
+        let mut row = serde_json::Map::new();
+        for (field, value) in &overrides {
+            row.insert(field.to_string(), Value::from(*value));
+        }
+        let round = |v: f32| Value::from((v as f64 * 100.0).round() / 100.0);
+        row.insert("jump_height".to_string(), round(metrics.jump_height));
+        row.insert("jump_distance".to_string(), round(metrics.jump_distance));
+        row.insert("top_speed_secs".to_string(), metrics.top_speed_secs.map(round).unwrap_or(Value::Null));
+        row.insert("cross_secs".to_string(), metrics.cross_secs.map(round).unwrap_or(Value::Null));
+        rows.push(Value::Object(row));
+    }
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&rows).unwrap_or_default());
+    } else {
+        let mut columns: Vec<&str> = sweeps.iter().map(|s| s.field.as_str()).collect();
+        columns.extend(["jump_height", "jump_distance", "top_speed_secs", "cross_secs"]);
+        crate::print_table(&rows, &columns);
+    }
+    Ok(())
+}
@@ -0,0 +1,398 @@
+use serde_json::Value;
+
+mod balance;
+mod map_check;
+
+const USAGE: &str = "\
+Usage: admin-cli [--server URL] [--token TOKEN] [--json] <command> [args]
+
+Commands:
+  ips                 Per-IP connection and player counts
+  players             Connected players
+  snapshot            Dump the full game state as JSON
+  map                 Platforms and walls of the current map
+  modes               Configured game modes, marking the active one
+  mode <id>           Switch game mode; applies once the round in progress ends
+  bots [count]        Server-controlled players; with a count, add or remove bots to match
+  mutators [list]     Round mutators and their votes; with a comma-separated list (or
+                      none), pick the next round's mutators
+  kick <player_id>    Disconnect a player
+  ban <ip>            Ban an IP address and kick its players until the server restarts
+  drain               Stop accepting new players ahead of a restart
+  tokens              Scoped API tokens issued to dashboards and tools
+  lanes               Command lane depths and dropped-command counts
+  export <player_id>  Everything stored about a player, as JSON
+  delete-data <player_id>
+                      Erase a player's stored data, leaving a tombstone
+  map-check <file>    Validate a local map config, report unreachable platforms
+                      and optionally write an SVG preview (runs offline)
+  balance <file>      Simulate bots on a local map config and report jump height,
+                      jump distance and time to cross the map (runs offline)
+
+Options:
+  --server URL        Server base URL (default: $ADMIN_SERVER_URL or http://localhost:3000)
+  --token TOKEN       Admin bearer token (default: $ADMIN_TOKEN)
+  --json              Print raw JSON instead of tables
+  --svg PATH          map-check: write an SVG preview of the map to PATH
+  --sweep SPEC        balance: sweep a physics field, as field=start:end:steps
+                      (repeat to sweep several fields over every combination)";
+
+/// An admin operation and the REST call it maps to
+#[derive(Debug)]
+enum Command {
+    Ips,
+    Players,
+    Snapshot,
+    Map,
+    Modes,
+    Mode(String),
+    /// List bots, or set how many there are
+    Bots(Option<usize>),
+    /// List mutators, or pick the next round's
+    Mutators(Option<Vec<String>>),
+    Kick(String),
+    Ban(String),
+    Drain,
+    Tokens,
+    Lanes,
+    Export(String),
+    DeleteData(String),
+    /// Local map validation; does not contact the server
+    MapCheck(String),
+    /// Local physics balance simulation; does not contact the server
+    Balance(String),
+}
+
+impl Command {
+    fn parse(name: &str, arg: Option<String>) -> Result<Self, String> {
+        let required = |what: &str| arg.clone().ok_or_else(|| format!("{} requires <{}>", name, what));
+        match name {
+            "ips" => Ok(Command::Ips),
+            "players" => Ok(Command::Players),
+            "snapshot" => Ok(Command::Snapshot),
+            "map" => Ok(Command::Map),
+            "modes" => Ok(Command::Modes),
+            "mode" => Ok(Command::Mode(required("id")?)),
+            "bots" => match &arg {
+                Some(count) => count
+                    .parse()
+                    .map(|count| Command::Bots(Some(count)))
+                    .map_err(|_| format!("bots count must be a number, got '{}'", count)),
+                None => Ok(Command::Bots(None)),
+            },
+            "mutators" => Ok(Command::Mutators(arg.as_deref().map(|list| {
+                list.split(',')
+                    .map(str::trim)
+                    .filter(|name| !name.is_empty() && !name.eq_ignore_ascii_case("none"))
+                    .map(String::from)
+                    .collect()
+            }))),
+            "kick" => Ok(Command::Kick(required("player_id")?)),
+            "ban" => Ok(Command::Ban(required("ip")?)),
+            "drain" => Ok(Command::Drain),
+            "tokens" => Ok(Command::Tokens),
+            "lanes" => Ok(Command::Lanes),
+            "export" => Ok(Command::Export(required("player_id")?)),
+            "delete-data" => Ok(Command::DeleteData(required("player_id")?)),
+            "map-check" => Ok(Command::MapCheck(required("file")?)),
+            "balance" => Ok(Command::Balance(required("file")?)),
+            _ => Err(format!("unknown command '{}'", name)),
+        }
+    }
+
+    /// HTTP method, path and optional JSON body; None for local commands
+    fn request(&self) -> Option<(reqwest::Method, String, Option<Value>)> {
+        use reqwest::Method;
+        let (method, path, body) = match self {
+            Command::MapCheck(_) | Command::Balance(_) => return None,
+            Command::Export(player_id) => {
+                return Some((Method::GET, format!("/api/player/{}/export", player_id), None));
+            }
+            Command::DeleteData(player_id) => {
+                return Some((Method::DELETE, format!("/api/player/{}/data", player_id), None));
+            }
+            Command::Ips => (Method::GET, "/api/admin/ips", None),
+            Command::Players | Command::Snapshot => (Method::GET, "/api/admin/state", None),
+            Command::Map => (Method::GET, "/api/config", None),
+            Command::Modes => (Method::GET, "/api/modes", None),
+            Command::Mode(id) => (Method::POST, "/api/admin/mode", Some(serde_json::json!({ "mode": id }))),
+            Command::Bots(None) => (Method::GET, "/api/admin/bots", None),
+            Command::Bots(Some(count)) => (Method::POST, "/api/admin/bots", Some(serde_json::json!({ "count": count }))),
+            Command::Mutators(None) => (Method::GET, "/api/mutators", None),
+            Command::Mutators(Some(mutators)) => (
+                Method::POST,
+                "/api/admin/mutators",
+                Some(serde_json::json!({ "mutators": mutators })),
+            ),
+            Command::Kick(player_id) => (
+                Method::POST,
+                "/api/admin/kick",
+                Some(serde_json::json!({ "player_id": player_id })),
+            ),
+            Command::Ban(ip) => (Method::POST, "/api/admin/ban", Some(serde_json::json!({ "ip": ip }))),
+            Command::Drain => (Method::POST, "/api/admin/drain", None),
+            Command::Tokens => (Method::GET, "/api/admin/tokens", None),
+            Command::Lanes => (Method::GET, "/api/admin/commands", None),
+        };
+        Some((method, path.to_string(), body))
+    }
+}
+
+struct Options {
+    server: String,
+    token: Option<String>,
+    json: bool,
+    svg: Option<String>,
+    sweeps: Vec<String>,
+    command: Command,
+}
+
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Options, String> {
+    let mut server = std::env::var("ADMIN_SERVER_URL").unwrap_or_else(|_| "http://localhost:3000".to_string());
+    let mut token = std::env::var("ADMIN_TOKEN").ok();
+    let mut json = false;
+    let mut svg = None;
+    let mut sweeps = Vec::new();
+    let mut positional = Vec::new();
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--server" => server = args.next().ok_or("--server requires a URL")?,
+            "--token" => token = Some(args.next().ok_or("--token requires a value")?),
+            "--json" => json = true,
+            "--svg" => svg = Some(args.next().ok_or("--svg requires a path")?),
+            "--sweep" => sweeps.push(args.next().ok_or("--sweep requires field=start:end:steps")?),
+            "-h" | "--help" => return Err(String::new()),
+            _ if arg.starts_with("--") => return Err(format!("unknown option '{}'", arg)),
+            _ => positional.push(arg),
+        }
+    }
+
+    let mut positional = positional.into_iter();
+    let name = positional.next().ok_or("missing command")?;
+    let command = Command::parse(&name, positional.next())?;
+    Ok(Options {
+        server: server.trim_end_matches('/').to_string(),
+        token,
+        json,
+        svg,
+        sweeps,
+        command,
+    })
+}
+
+/// Render a JSON value as a table cell
+fn cell(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        Value::Null => "-".to_string(),
+        other => other.to_string(),
+    }
+}
+
+/// Print rows of JSON objects as an aligned table using the given columns
+pub fn print_table(rows: &[Value], columns: &[&str]) {
+    let cells: Vec<Vec<String>> = rows
+        .iter()
+        .map(|row| columns.iter().map(|c| cell(&row[*c])).collect())
+        .collect();
+    let widths: Vec<usize> = columns
+        .iter()
+        .enumerate()
+        .map(|(i, c)| cells.iter().map(|r| r[i].len()).chain([c.len()]).max().unwrap_or(0))
+        .collect();
+
+    let line = |values: Vec<String>| {
+        values
+            .iter()
+            .zip(&widths)
+            .map(|(v, w)| format!("{:<width$}", v, width = w))
+            .collect::<Vec<_>>()
+            .join("  ")
+            .trim_end()
+            .to_string()
+    };
+    println!("{}", line(columns.iter().map(|c| c.to_uppercase()).collect()));
+    for row in cells {
+        println!("{}", line(row));
+    }
+}
+
+/// Print a single JSON object as key/value rows
+fn print_object(value: &Value) {
+    match value.as_object() {
+        Some(map) => {
+            let width = map.keys().map(|k| k.len()).max().unwrap_or(0);
+            for (key, value) in map {
+                println!("{:<width$}  {}", key, cell(value), width = width);
+            }
+        }
+        None => println!("{}", cell(value)),
+    }
+}
+
+fn print_output(command: &Command, body: &Value) {
+    match command {
+        Command::Ips => print_table(body.as_array().map(Vec::as_slice).unwrap_or(&[]), &["ip", "connections", "players"]),
+        Command::Players => {
+            let mut players: Vec<Value> = body["players"]
+                .as_object()
+                .map(|players| players.values().cloned().collect())
+                .unwrap_or_default();
+            players.sort_by_key(|p| cell(&p["name"]).to_lowercase());
+            print_table(&players, &["id", "name", "score", "x", "y"]);
+        }
+        Command::Snapshot | Command::Export(_) => println!("{}", serde_json::to_string_pretty(body).unwrap_or_default()),
+        Command::Map => {
+            // Platforms and walls are described differently; show both as bounding boxes
+            let num = |v: &Value| v.as_f64().unwrap_or(0.0);
+            let platforms = body["platforms"].as_array().into_iter().flatten().map(|p| {
+                serde_json::json!({
+                    "kind": "platform",
+                    "id": p["id"],
+                    "left": num(&p["x_start"]),
+                    "right": num(&p["x_end"]),
+                    "bottom": num(&p["y_top"]) - num(&p["height"]),
+                    "top": num(&p["y_top"]),
+                })
+            });
+            let walls = body["walls"].as_array().into_iter().flatten().map(|w| {
+                serde_json::json!({
+                    "kind": "wall",
+                    "id": w["id"],
+                    "left": num(&w["x"]),
+                    "right": num(&w["x"]) + num(&w["width"]),
+                    "bottom": num(&w["y_bottom"]),
+                    "top": num(&w["y_top"]),
+                })
+            });
+            let rows: Vec<Value> = platforms.chain(walls).collect();
+            print_table(&rows, &["kind", "id", "left", "right", "bottom", "top"]);
+        }
+        Command::Modes => {
+            let active = &body["active"]["id"];
+            let pending = &body["pending"];
+            let rows: Vec<Value> = body["modes"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .map(|mode| {
+                    let status = if &mode["id"] == active {
+                        "active"
+                    } else if &mode["id"] == pending {
+                        "pending"
+                    } else {
+                        ""
+                    };
+                    serde_json::json!({ "id": mode["id"], "name": mode["name"], "status": status })
+                })
+                .collect();
+            print_table(&rows, &["id", "name", "status"]);
+        }
+        Command::Bots(_) => print_table(
+            body["bots"].as_array().map(Vec::as_slice).unwrap_or(&[]),
+            &["id", "name", "behavior"],
+        ),
+        Command::Mutators(None) => {
+            let contains = |list: &Value, id: &Value| list.as_array().is_some_and(|list| list.contains(id));
+            let rows: Vec<Value> = body["mutators"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .map(|mutator| {
+                    let status = if contains(&body["active"], &mutator["id"]) {
+                        "active"
+                    } else if contains(&body["picked"], &mutator["id"]) {
+                        "picked"
+                    } else {
+                        ""
+                    };
+                    serde_json::json!({ "id": mutator["id"], "name": mutator["name"], "votes": mutator["votes"], "status": status })
+                })
+                .collect();
+            print_table(&rows, &["id", "name", "votes", "status"]);
+        }
+        Command::Tokens => print_table(
+            body["tokens"].as_array().map(Vec::as_slice).unwrap_or(&[]),
+            &["id", "name", "scope", "created_at"],
+        ),
+        Command::Lanes => print_table(
+            body["lanes"].as_array().map(Vec::as_slice).unwrap_or(&[]),
+            &["lane", "queued", "capacity", "overflow", "dropped"],
+        ),
+        Command::Kick(_)
+        | Command::DeleteData(_)
+        | Command::Ban(_)
+        | Command::Drain
+        | Command::Mode(_)
+        | Command::Mutators(Some(_))
+        | Command::MapCheck(_)
+        | Command::Balance(_) => print_object(body),
+    }
+}
+
+async fn run(options: Options) -> Result<(), String> {
+    let Some((method, path, body)) = options.command.request() else {
+        return match &options.command {
+            Command::MapCheck(file) => match map_check::check_map(file, options.svg.as_deref(), options.json)? {
+                true => Ok(()),
+                false => Err("map check failed".to_string()),
+            },
+            Command::Balance(file) => balance::run_balance(file, &options.sweeps, options.json),
+            _ => unreachable!("only local commands have no request"),
+        };
+    };
+    let client = reqwest::Client::new();
+    let mut request = client.request(method, format!("{}{}", options.server, path));
+    if let Some(token) = &options.token {
+        request = request.bearer_auth(token);
+    }
+    if let Some(body) = body {
+        request = request.json(&body);
+    }
+
+    let response = request.send().await.map_err(|e| format!("request failed: {}", e))?;
+    let status = response.status();
+    let text = response.text().await.map_err(|e| format!("failed to read response: {}", e))?;
+    if status == reqwest::StatusCode::UNAUTHORIZED {
+        return Err("unauthorized: check --token or ADMIN_TOKEN".to_string());
+    }
+    if status == reqwest::StatusCode::FORBIDDEN {
+        return Err("forbidden: the token's scope doesn't allow this command".to_string());
+    }
+    if !status.is_success() {
+        return Err(format!("server returned {}: {}", status, text.trim()));
+    }
+
+    // Some admin actions reply with an empty body
+    let body: Value = if text.trim().is_empty() {
+        serde_json::json!({ "status": status.as_u16() })
+    } else {
+        serde_json::from_str(&text).unwrap_or(Value::String(text))
+    };
+    if options.json {
+        println!("{}", serde_json::to_string_pretty(&body).unwrap_or_default());
+    } else {
+        print_output(&options.command, &body);
+    }
+    Ok(())
+}
+
+#[tokio::main]
+async fn main() {
+    let options = match parse_args(std::env::args().skip(1)) {
+        Ok(options) => options,
+        Err(e) => {
+            if !e.is_empty() {
+                eprintln!("error: {}\n", e);
+            }
+            eprintln!("{}", USAGE);
+            std::process::exit(2);
+        }
+    };
+
+    if let Err(e) = run(options).await {
+        eprintln!("error: {}", e);
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,161 @@
+use std::collections::HashSet;
+use std::fmt::Write;
+use std::sync::Arc;
+use game_core::config::GameConfig;
+use game_core::map_check::{self, Severity};
+
+/// Pixels per world unit in the SVG preview
+const SCALE: f32 = 20.0;
+/// Margin around the map in world units
+const MARGIN: f32 = 2.0;
+
+/// Render the map as an SVG, outlining unreachable platforms in red
+fn render_svg(config: &GameConfig, unreachable: &HashSet<&str>) -> String {
+    let physics = &config.physics;
+    let xs = config
+        .platforms
+        .iter()
+        .flat_map(|p| [p.x_start, p.x_end])
+        .chain(config.walls.iter().flat_map(|w| [w.x, w.x + w.width]))
+        .chain(physics.ground_segments.iter().flat_map(|g| [g.x_start, g.x_end]))
+        .chain([0.0]);
+    let ys = config
+        .platforms
+        .iter()
+        .flat_map(|p| [p.y_top, p.y_top - p.height])
+        .chain(config.walls.iter().flat_map(|w| [w.y_bottom, w.y_top]))
+        .chain(physics.ground_segments.iter().map(|g| g.y_top))
+        .chain([physics.ground_y]);
+    let (min_x, max_x) = xs.fold((f32::INFINITY, f32::NEG_INFINITY), |(lo, hi), x| (lo.min(x), hi.max(x)));
+    let (min_y, max_y) = ys.fold((f32::INFINITY, f32::NEG_INFINITY), |(lo, hi), y| (lo.min(y), hi.max(y)));
+    let (min_x, max_x) = (min_x - MARGIN, max_x + MARGIN);
+    let (min_y, max_y) = (min_y - MARGIN, max_y + MARGIN + physics.player_height);
+
+    let width = (max_x - min_x) * SCALE;
+    let height = (max_y - min_y) * SCALE;
+    // World y points up, SVG y points down
+    let px = |x: f32| (x - min_x) * SCALE;
+    let py = |y: f32| (max_y - y) * SCALE;
+
+    let mut svg = String::new();
+    let _ = writeln!(
+        svg,
+        r##"<svg xmlns="http://www.w3.org/2000/svg" width="{:.0}" height="{:.0}" viewBox="0 0 {:.1} {:.1}">"##,
+        width, height, width, height
+    );
+    let _ = writeln!(svg, r##"  <rect width="100%" height="100%" fill="#1E1E2E"/>"##);
+    let _ = writeln!(
+        svg,
+        r##"  <rect x="0" y="{:.1}" width="{:.1}" height="{:.1}" fill="{}"/>"##,
+        py(physics.ground_y),
+        width,
+        height - py(physics.ground_y),
+        physics.ground_color
+    );
+    for segment in &physics.ground_segments {
+        // Cut the default ground away, then fill in the segment at its own height
+        let (x, segment_width) = (px(segment.x_start), (segment.x_end - segment.x_start) * SCALE);
+        let _ = writeln!(
+            svg,
+            r##"  <rect x="{:.1}" y="0" width="{:.1}" height="{:.1}" fill="#1E1E2E"/>"##,
+            x, segment_width, height
+        );
+        let _ = writeln!(
+            svg,
+            r##"  <rect x="{:.1}" y="{:.1}" width="{:.1}" height="{:.1}" fill="{}"/>"##,
+            x,
+            py(segment.y_top),
+            segment_width,
+            height - py(segment.y_top),
+            segment.color.as_deref().unwrap_or(&physics.ground_color)
+        );
+    }
+
+    for wall in &config.walls {
+        let _ = writeln!(
+            svg,
+            r##"  <rect x="{:.1}" y="{:.1}" width="{:.1}" height="{:.1}" fill="{}"><title>{}</title></rect>"##,
+            px(wall.x),
+            py(wall.y_top),
+            wall.width * SCALE,
+            (wall.y_top - wall.y_bottom) * SCALE,
+            wall.color,
+            wall.id
+        );
+    }
+
+    for platform in &config.platforms {
+        let stroke = if unreachable.contains(platform.id.as_str()) {
+            r##" stroke="#FF3333" stroke-width="3" stroke-dasharray="6 3""##
+        } else {
+            ""
+        };
+        let _ = writeln!(
+            svg,
+            r##"  <rect x="{:.1}" y="{:.1}" width="{:.1}" height="{:.1}" fill="{}"{}><title>{}</title></rect>"##,
+            px(platform.x_start),
+            py(platform.y_top),
+            (platform.x_end - platform.x_start) * SCALE,
+            platform.height * SCALE,
+            platform.color,
+            stroke,
+            platform.id
+        );
+    }
+
+    // Spawn point, drawn as the player's box on the ground at x = 0
+    let _ = writeln!(
+        svg,
+        r##"  <rect x="{:.1}" y="{:.1}" width="{:.1}" height="{:.1}" fill="none" stroke="#FFFFFF" stroke-dasharray="2 2"><title>spawn</title></rect>"##,
+        px(-physics.player_width / 2.0),
+        py(physics.ground_at(0.0) + physics.player_height),
+        physics.player_width * SCALE,
+        physics.player_height * SCALE
+    );
+    svg.push_str("</svg>\n");
+    svg
+}
+
+/// Validate a map file, print its reachability and optionally write an SVG preview
+/// Returns whether the map passed (no errors and every platform reachable)
+pub fn check_map(path: &str, svg_path: Option<&str>, json: bool) -> Result<bool, String> {
+    let config = GameConfig::load(path).map_err(|e| format!("failed to load {}: {}", path, e))?;
+    let config = Arc::new(config);
+
+    let issues = map_check::validate(&config);
+    let world = game_core::PhysicsWorld::new(config.clone());
+    let reachability = map_check::reachability(&world);
+
+    if json {
+        let report = serde_json::json!({ "issues": issues, "reachability": reachability });
+        println!("{}", serde_json::to_string_pretty(&report).unwrap_or_default());
+    } else {
+        println!("Map {}: {} platforms, {} walls", path, config.platforms.len(), config.walls.len());
+        for issue in &issues {
+            let severity = match issue.severity {
+                Severity::Error => "error",
+                Severity::Warning => "warning",
+            };
+            match &issue.id {
+                Some(id) => println!("  {}: {}: {}", severity, id, issue.message),
+                None => println!("  {}: {}", severity, issue.message),
+            }
+        }
+        println!("Reachable from spawn: {}", reachability.reachable.join(", "));
+        if reachability.unreachable.is_empty() {
+            println!("Every platform is reachable");
+        } else {
+            println!("Unreachable: {}", reachability.unreachable.join(", "));
+        }
+    }
+
+    if let Some(svg_path) = svg_path {
+        let unreachable: HashSet<&str> = reachability.unreachable.iter().map(String::as_str).collect();
+        std::fs::write(svg_path, render_svg(&config, &unreachable))
+            .map_err(|e| format!("failed to write {}: {}", svg_path, e))?;
+        eprintln!("Wrote preview to {}", svg_path);
+    }
+
+    let has_errors = issues.iter().any(|issue| issue.severity == Severity::Error);
+    Ok(!has_errors && reachability.unreachable.is_empty())
+}
@@ -0,0 +1,139 @@
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpListener;
+use std::process::{Command, Output};
+use std::thread::JoinHandle;
+
+/// What the fake server was asked: request line, headers (lowercased names) and body
+struct Request {
+    line: String,
+    headers: Vec<(String, String)>,
+    body: String,
+}
+
+impl Request {
+    fn header(&self, name: &str) -> Option<&str> {
+        self.headers.iter().find(|(n, _)| n == name).map(|(_, v)| v.as_str())
+    }
+}
+
+/// Answer one request with `status` and a JSON `body`, returning the server URL and what it was asked
+fn serve_once(status: &'static str, body: &'static str) -> (String, JoinHandle<Request>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    let handle = std::thread::spawn(move || {
+        let (stream, _) = listener.accept().unwrap();
+        let mut reader = BufReader::new(stream);
+        let mut line = String::new();
+        reader.read_line(&mut line).unwrap();
+        let mut headers = Vec::new();
+        loop {
+            let mut header = String::new();
+            reader.read_line(&mut header).unwrap();
+            let Some((name, value)) = header.trim_end().split_once(": ") else { break };
+            headers.push((name.to_lowercase(), value.to_string()));
+        }
+        let length = headers
+            .iter()
+            .find(|(name, _)| name == "content-length")
+            .map_or(0, |(_, value)| value.parse().unwrap());
+        let mut request_body = vec![0; length];
+        reader.read_exact(&mut request_body).unwrap();
+
+        let mut stream = reader.into_inner();
+        write!(
+            stream,
+            "HTTP/1.1 {}\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{}",
+            status,
+            body.len(),
+            body
+        )
+        .unwrap();
+        Request {
+            line: line.trim_end().to_string(),
+            headers,
+            body: String::from_utf8(request_body).unwrap(),
+        }
+    });
+    (url, handle)
+}
+
+fn admin_cli(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_admin-cli"))
+        .args(args)
+        .env_remove("ADMIN_TOKEN")
+        .env_remove("ADMIN_SERVER_URL")
+        .output()
+        .unwrap()
+}
+
+#[test]
+fn commands_call_the_admin_api_with_the_token_and_print_a_table() {
+    let (url, server) = serve_once("200 OK", r#"{"kicked":"p1","name":"Ada"}"#);
+    let output = admin_cli(&["--server", &url, "--token", "secret", "kick", "p1"]);
+    let request = server.join().unwrap();
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    assert_eq!(request.line, "POST /api/admin/kick HTTP/1.1");
+    assert_eq!(request.header("authorization"), Some("Bearer secret"));
+    assert_eq!(serde_json::from_str::<serde_json::Value>(&request.body).unwrap(), serde_json::json!({ "player_id": "p1" }));
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert_eq!(stdout, "kicked  p1\nname    Ada\n");
+
+    let (url, server) = serve_once("200 OK", r#"[{"ip":"10.0.0.7","connections":2,"players":1}]"#);
+    let output = admin_cli(&["--server", &format!("{}/", url), "ips"]);
+    let request = server.join().unwrap();
+    assert_eq!(request.line, "GET /api/admin/ips HTTP/1.1");
+    assert_eq!(request.header("authorization"), None);
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert_eq!(stdout, "IP        CONNECTIONS  PLAYERS\n10.0.0.7  2            1\n");
+}
+
+#[test]
+fn refusals_and_bad_arguments_exit_with_an_error() {
+    let (url, server) = serve_once("401 Unauthorized", r#"{"code":"unauthorized"}"#);
+    let output = admin_cli(&["--server", &url, "--token", "wrong", "drain"]);
+    assert_eq!(server.join().unwrap().line, "POST /api/admin/drain HTTP/1.1");
+    assert_eq!(output.status.code(), Some(1));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("unauthorized: check --token or ADMIN_TOKEN"));
+
+    let output = admin_cli(&["kick"]);
+    assert_eq!(output.status.code(), Some(2));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("kick requires <player_id>") && stderr.contains("Usage: admin-cli"), "{}", stderr);
+}
+
+#[test]
+fn balance_sweeps_physics_offline_and_measures_each_combination() {
+    let map = concat!(env!("CARGO_MANIFEST_DIR"), "/../game_core/game_config.json");
+    let output = admin_cli(&[
+        "--json",
+        "balance",
+        map,
+        "--sweep",
+        "jump_velocity=30:60:2",
+        "--sweep",
+        "gravity=-200:-100:2",
+    ]);
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    let rows: Vec<serde_json::Value> = serde_json::from_slice(&output.stdout).unwrap();
+    let swept: Vec<(f64, f64)> = rows
+        .iter()
+        .map(|row| (row["jump_velocity"].as_f64().unwrap(), row["gravity"].as_f64().unwrap()))
+        .collect();
+    assert_eq!(swept, [(30.0, -200.0), (30.0, -100.0), (60.0, -200.0), (60.0, -100.0)]);
+    for row in &rows {
+        let (velocity, gravity) = (row["jump_velocity"].as_f64().unwrap(), row["gravity"].as_f64().unwrap());
+        let expected = velocity * velocity / (2.0 * -gravity);
+        let measured = row["jump_height"].as_f64().unwrap();
+        // Whole ticks shave a little off the peak
+        assert!(measured <= expected && measured > expected * 0.8, "jumped {} expecting {} in {}", measured, expected, row);
+        assert!(row["jump_distance"].as_f64().unwrap() > 0.0);
+        assert!(row["cross_secs"].as_f64().is_some(), "{}", row);
+    }
+
+    let height = |i: usize| rows[i]["jump_height"].as_f64().unwrap();
+    assert!(height(2) > 3.0 * height(0) && height(1) > 1.5 * height(0));
+
+    let output = admin_cli(&["balance", map, "--sweep", "name=1:2:2"]);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("unknown numeric physics field 'name'"));
+}
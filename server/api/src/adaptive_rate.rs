@@ -0,0 +1,82 @@
+use game_core::config::AdaptiveRateConfig;
+
+/// Minimum time between two step-downs, so one burst only costs one rate level
+const DEGRADE_COOLDOWN_MS: u64 = 1000;
+
+/// A subscriber's state update rate, stepped down while its queue backs up and
+/// restored once it keeps up again
+///
+/// Only full state updates are throttled; events always go through.
+#[derive(Debug, Clone)]
+pub struct AdaptiveRate {
+    config: AdaptiveRateConfig,
+    /// Index into `config.rates_hz`; 0 sends every state update
+    level: usize,
+    /// Server time since which the backlog has stayed low, while degraded
+    healthy_since_ms: Option<u64>,
+    /// Server time of the last step-down
+    degraded_at_ms: Option<u64>,
+    /// Server time of the last state sent
+    last_sent_ms: Option<u64>,
+}
+
+impl AdaptiveRate {
+    pub fn new(config: &AdaptiveRateConfig) -> Self {
+        Self {
+            config: config.clone(),
+            level: 0,
+            healthy_since_ms: None,
+            degraded_at_ms: None,
+            last_sent_ms: None,
+        }
+    }
+
+    /// Current target rate, or None when sending every state update
+    pub fn rate_hz(&self) -> Option<f32> {
+        (self.level > 0).then(|| self.config.rates_hz[self.level])
+    }
+
+    /// Record the subscriber's queue depth when a state update arrives at server time
+    /// `now_ms`; returns true if the rate changed
+    pub fn observe(&mut self, backlog: usize, now_ms: u64) -> bool {
+        if !self.config.enabled || self.config.rates_hz.len() < 2 {
+            return false;
+        }
+
+        let slowest = self.config.rates_hz.len() - 1;
+        let cooled_down = self
+            .degraded_at_ms
+            .is_none_or(|at| now_ms.saturating_sub(at) >= DEGRADE_COOLDOWN_MS);
+        if backlog >= self.config.degrade_backlog && self.level < slowest && cooled_down {
+            self.level += 1;
+            self.degraded_at_ms = Some(now_ms);
+            self.healthy_since_ms = None;
+            return true;
+        }
+
+        if backlog > self.config.recover_backlog {
+            self.healthy_since_ms = None;
+            return false;
+        }
+        let healthy_since = *self.healthy_since_ms.get_or_insert(now_ms);
+        if self.level > 0 && now_ms.saturating_sub(healthy_since) >= self.config.recover_after_secs * 1000 {
+            self.level -= 1;
+            // The next level up has to earn its own recovery window
+            self.healthy_since_ms = Some(now_ms);
+            return true;
+        }
+        false
+    }
+
+    /// Whether a state produced at server time `now_ms` should be sent at the current rate
+    pub fn should_send(&mut self, now_ms: u64) -> bool {
+        let due = match (self.rate_hz(), self.last_sent_ms) {
+            (Some(rate), Some(last)) => now_ms.saturating_sub(last) >= (1000.0 / rate) as u64,
+            _ => true,
+        };
+        if due {
+            self.last_sent_ms = Some(now_ms);
+        }
+        due
+    }
+}
@@ -0,0 +1,140 @@
+use std::sync::Arc;
+use axum::extract::{Request, State};
+use axum::http::{header, HeaderMap, HeaderValue, StatusCode};
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Json, Response};
+use serde_json::json;
+use game_core::config::{ClusterConfig, ClusterInstance};
+use crate::state::AppState;
+
+/// Response header naming the instance that answered
+pub const INSTANCE_HEADER: &str = "x-instance-id";
+
+/// Which instance hosts each room when several run behind one load balancer
+///
+/// Every instance hosts one room named after it (see `handlers::rooms::room_id`), so a room's
+/// owner is the instance with that id and every instance agrees without talking to the
+/// others. Clients name the room they want on init and /events, or carry it in the affinity
+/// cookie the load balancer routes on; requests that land on the wrong instance anyway are
+/// redirected.
+#[derive(Clone)]
+pub struct Affinity {
+    local: Option<ClusterInstance>,
+    instances: Arc<[ClusterInstance]>,
+    cookie_name: Arc<str>,
+}
+
+impl Affinity {
+    /// Affinity is off unless instances are listed and this instance's id is one of them
+    pub fn new(config: &ClusterConfig) -> Self {
+        let local = match config.resolve_instance_id() {
+            _ if config.instances.is_empty() => None,
+            Some(id) => {
+                let local = config.instances.iter().find(|i| i.id == id).cloned();
+                if local.is_none() {
+                    eprintln!("⚠️ Instance id {} is not in the cluster's instance list, room affinity disabled", id);
+                }
+                local
+            }
+            None => {
+                eprintln!("⚠️ No instance id configured (set {}), room affinity disabled", config.instance_id_env);
+                None
+            }
+        };
+        Self {
+            local,
+            instances: config.instances.clone().into(),
+            cookie_name: config.cookie_name.as_str().into(),
+        }
+    }
+
+    pub fn local(&self) -> Option<&ClusterInstance> {
+        self.local.as_ref()
+    }
+
+    /// The instance hosting the room; None when running standalone or for unknown rooms
+    pub fn owner(&self, room: &str) -> Option<&ClusterInstance> {
+        self.local.as_ref()?;
+        self.instances.iter().find(|instance| instance.id == room)
+    }
+
+    /// The room a request asks for: the one it names, otherwise the one its affinity cookie
+    /// pins it to
+    pub fn requested_room(&self, headers: &HeaderMap, named: Option<&str>) -> Option<String> {
+        if let Some(room) = named {
+            return Some(room.to_string());
+        }
+        headers
+            .get_all(header::COOKIE)
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .flat_map(|value| value.split(';'))
+            .filter_map(|cookie| cookie.trim().split_once('='))
+            .find(|(name, _)| *name == &*self.cookie_name)
+            .map(|(_, room)| room.to_string())
+    }
+
+    /// Set-Cookie value pinning the client to `instance`
+    pub fn cookie(&self, instance: &ClusterInstance) -> String {
+        format!("{}={}; Path=/; HttpOnly; SameSite=Lax", self.cookie_name, instance.id)
+    }
+
+    /// Attach the affinity cookie for this instance to a response
+    pub fn pin_here(&self, response: &mut Response) {
+        if let Some(value) = self.local.as_ref().and_then(|local| HeaderValue::from_str(&self.cookie(local)).ok()) {
+            response.headers_mut().append(header::SET_COOKIE, value);
+        }
+    }
+}
+
+/// A redirect to the instance hosting the requested room, or None if this instance should
+/// serve the request. Requests without a room join the room here, and players already here
+/// stay here, whatever room the request names
+pub async fn misdirected(
+    app_state: &AppState,
+    room: Option<&str>,
+    player_id: Option<&uuid::Uuid>,
+    path_and_query: &str,
+) -> Option<Response> {
+    let affinity = &app_state.affinity;
+    let owner = affinity.owner(room?)?;
+    if affinity.local().is_some_and(|local| local.id == owner.id) {
+        return None;
+    }
+    if let Some(player_id) = player_id {
+        let game_state = app_state.game_state.read().await;
+        if game_state.players.contains_key(player_id) || game_state.waiting.position(player_id).is_some() {
+            return None;
+        }
+    }
+
+    // Every instance serves under the same base path; `path_and_query` is relative to it
+    let url = owner.url.trim_end_matches('/');
+    let base_path = app_state.game_config.proxy.resolve_base_path();
+    let base_path = if url.ends_with(&base_path) { "" } else { base_path.as_str() };
+    let location = format!("{}{}{}", url, base_path, path_and_query);
+    eprintln!("🔀 Redirecting a request for room {} to its instance", owner.id);
+    // 307 keeps the method and body, so redirected POSTs are replayed as POSTs
+    let mut response = (
+        StatusCode::TEMPORARY_REDIRECT,
+        Json(json!({ "room": owner.id, "instance_id": owner.id, "url": owner.url })),
+    )
+        .into_response();
+    let headers = response.headers_mut();
+    if let Ok(location) = HeaderValue::from_str(&location) {
+        headers.insert(header::LOCATION, location);
+    }
+    if let Ok(cookie) = HeaderValue::from_str(&affinity.cookie(owner)) {
+        headers.insert(header::SET_COOKIE, cookie);
+    }
+    Some(response)
+}
+
+/// Name the answering instance on every response, for debugging load balancer routing
+pub async fn instance_header(State(app_state): State<AppState>, request: Request, next: Next) -> Response {
+    let mut response = next.run(request).await;
+    if let Some(value) = app_state.affinity.local().and_then(|local| HeaderValue::from_str(&local.id).ok()) {
+        response.headers_mut().insert(INSTANCE_HEADER, value);
+    }
+    response
+}
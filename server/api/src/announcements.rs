@@ -0,0 +1,117 @@
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use game_core::config::AnnouncementSeverity;
+use crate::chat_commands::escape_html;
+use crate::state::AppState;
+use crate::GameUpdate;
+
+/// Selector clients patch the banner into
+pub const BANNER_SELECTOR: &str = "#announcement-banner";
+
+/// Render a banner styled for its severity; empty text renders the cleared banner
+pub fn banner_html(text: &str, severity: AnnouncementSeverity) -> String {
+    if text.is_empty() {
+        return r#"<div id="announcement-banner"></div>"#.to_string();
+    }
+    let (background, border, icon) = match severity {
+        AnnouncementSeverity::Info => ("rgba(20, 60, 120, 0.9)", "#4DA6FF", "📢"),
+        AnnouncementSeverity::Warning => ("rgba(120, 80, 0, 0.9)", "#FFD700", "⚠️"),
+        AnnouncementSeverity::Critical => ("rgba(130, 10, 10, 0.92)", "#FF4D4D", "🚨"),
+    };
+    format!(
+        r#"<div id="announcement-banner" data-severity="{}" style="padding: 10px 18px; background: {}; border: 2px solid {}; border-radius: 6px; color: #FFFFFF; font-size: 18px; font-weight: bold; text-align: center">{} {}</div>"#,
+        severity_name(severity),
+        background,
+        border,
+        icon,
+        escape_html(text)
+    )
+}
+
+fn severity_name(severity: AnnouncementSeverity) -> &'static str {
+    match severity {
+        AnnouncementSeverity::Info => "info",
+        AnnouncementSeverity::Warning => "warning",
+        AnnouncementSeverity::Critical => "critical",
+    }
+}
+
+/// The banner currently on screen, so streams opened mid-announcement show it too
+#[derive(Clone, Default)]
+pub struct Announcer {
+    /// Sequence number of the latest announcement and its banner, while it is showing
+    current: Arc<Mutex<(u64, Option<String>)>>,
+}
+
+impl Announcer {
+    /// Banner HTML to show a newly connected client, if one is up
+    pub fn current(&self) -> Option<String> {
+        self.current.lock().unwrap().1.clone()
+    }
+
+    fn show(&self, html: String) -> u64 {
+        let mut current = self.current.lock().unwrap();
+        current.0 += 1;
+        current.1 = Some(html);
+        current.0
+    }
+
+    /// Clear the banner unless a newer announcement replaced it; true if cleared
+    fn clear(&self, seq: u64) -> bool {
+        let mut current = self.current.lock().unwrap();
+        if current.0 != seq {
+            return false;
+        }
+        current.1 = None;
+        true
+    }
+}
+
+/// Show a banner on every client, clearing it after `duration` (None keeps it up
+/// until the next announcement)
+pub fn announce(app_state: &AppState, text: &str, severity: AnnouncementSeverity, duration: Option<Duration>) {
+    let html = banner_html(text, severity);
+    let seq = app_state.announcer.show(html.clone());
+    let _ = app_state.game_tx.send(GameUpdate::Announcement { html });
+
+    if let Some(duration) = duration {
+        let app_state = app_state.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(duration).await;
+            if app_state.announcer.clear(seq) {
+                let _ = app_state.game_tx.send(GameUpdate::Announcement {
+                    html: banner_html("", AnnouncementSeverity::Info),
+                });
+            }
+        });
+    }
+}
+
+/// Show each configured scheduled announcement on its interval, forever
+pub async fn run_scheduled(app_state: AppState) {
+    let schedules: Vec<_> = app_state
+        .game_config
+        .announcements
+        .iter()
+        .filter(|scheduled| scheduled.interval_secs > 0 && !scheduled.text.trim().is_empty())
+        .cloned()
+        .collect();
+    if schedules.is_empty() {
+        return;
+    }
+    eprintln!("📢 Scheduled {} recurring announcement(s)", schedules.len());
+
+    let tasks = schedules.into_iter().map(|scheduled| {
+        let app_state = app_state.clone();
+        async move {
+            let period = Duration::from_secs(scheduled.interval_secs);
+            let mut interval = tokio::time::interval_at(tokio::time::Instant::now() + period, period);
+            let duration = (scheduled.duration_secs > 0).then(|| Duration::from_secs(scheduled.duration_secs));
+            loop {
+                interval.tick().await;
+                announce(&app_state, scheduled.text.trim(), scheduled.severity, duration);
+            }
+        }
+    });
+    futures::future::join_all(tasks).await;
+}
@@ -0,0 +1,90 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use game_core::SharedClock;
+
+/// What a bearer token may do on /api/admin; each scope includes the ones before it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TokenScope {
+    /// Read-only stats: IP usage and state dumps, for dashboards
+    Stats,
+    /// Stats plus moderation: flags, kicks and mutes
+    Moderation,
+    /// Everything, including announcements and managing tokens
+    Admin,
+}
+
+impl TokenScope {
+    pub fn allows(&self, required: TokenScope) -> bool {
+        *self >= required
+    }
+}
+
+/// An issued token as listed to admins; the secret itself is only shown once, at creation
+#[derive(Debug, Clone, Serialize)]
+pub struct ApiTokenInfo {
+    pub id: uuid::Uuid,
+    /// Who the token is for, e.g. "grafana"
+    pub name: String,
+    pub scope: TokenScope,
+    /// Unix time in seconds
+    pub created_at: u64,
+}
+
+/// Scoped bearer tokens handed out to external dashboards and tools
+///
+/// Only SHA-256 hashes of the secrets are kept, so a state dump can't leak usable tokens.
+/// Tokens live in memory and have to be reissued after a restart.
+#[derive(Clone)]
+pub struct ApiTokens {
+    tokens: Arc<Mutex<HashMap<[u8; 32], ApiTokenInfo>>>,
+    clock: SharedClock,
+}
+
+fn hash(secret: &str) -> [u8; 32] {
+    Sha256::digest(secret.as_bytes()).into()
+}
+
+impl ApiTokens {
+    pub fn new(clock: SharedClock) -> Self {
+        Self {
+            tokens: Arc::new(Mutex::new(HashMap::new())),
+            clock,
+        }
+    }
+
+    /// Issue a token, returning its listing and the secret to hand to the client
+    pub fn create(&self, name: String, scope: TokenScope) -> (ApiTokenInfo, String) {
+        let secret = format!("tok_{}{}", uuid::Uuid::new_v4().simple(), uuid::Uuid::new_v4().simple());
+        let info = ApiTokenInfo {
+            id: uuid::Uuid::new_v4(),
+            name,
+            scope,
+            created_at: self.clock.unix_secs(),
+        };
+        self.tokens.lock().unwrap().insert(hash(&secret), info.clone());
+        (info, secret)
+    }
+
+    /// Every issued token, oldest first
+    pub fn list(&self) -> Vec<ApiTokenInfo> {
+        let mut tokens: Vec<ApiTokenInfo> = self.tokens.lock().unwrap().values().cloned().collect();
+        tokens.sort_by_key(|t| (t.created_at, t.id));
+        tokens
+    }
+
+    /// Revoke a token by id; false if there was no such token
+    pub fn revoke(&self, id: uuid::Uuid) -> bool {
+        let mut tokens = self.tokens.lock().unwrap();
+        let before = tokens.len();
+        tokens.retain(|_, info| info.id != id);
+        tokens.len() < before
+    }
+
+    /// The scope a presented secret grants, if it was issued and not revoked
+    pub fn scope_for(&self, secret: &str) -> Option<TokenScope> {
+        self.tokens.lock().unwrap().get(&hash(secret)).map(|info| info.scope)
+    }
+}
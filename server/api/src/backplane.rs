@@ -0,0 +1,775 @@
+//! Distributed mode: several API instances behind one load balancer share a single game
+//!
+//! One instance holds a lease on a Redis key and runs the simulation. It publishes every game
+//! update, and the others mirror the world from those updates and serve them to their own
+//! streams. State updates only bring what clients see, so the leader also publishes its whole
+//! world every checkpoint interval and ahead of every map, mode, map edit or physics change
+//! (the RNG, rules progress, patched physics and everything else a snapshot holds), which
+//! followers load. Followers forward commands and joins to the leader, and every instance
+//! publishes the chat it receives. If the leader stops renewing its lease, a follower takes
+//! over from its mirror, losing what happened since the last checkpoint beyond what state
+//! updates brought. Waiting queues, challenge progress and admin changes stay with the
+//! instance that holds them, so admin requests belong on the leader. Distributed mode is off
+//! unless `backplane.enabled` is set.
+//!
+//! A standby mirrors the leader the same way but never claims the lease by itself. An admin
+//! promotes it: it asks the leader to stop, and the leader hands over its world as it
+//! stopped before the standby takes the lease, so the two never simulate at once and nothing
+//! is lost. A leader that doesn't answer within the lease is taken to be gone, and the
+//! standby carries on from its mirror.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::Duration;
+use serde::{Deserialize, Serialize};
+use tokio::sync::{broadcast, mpsc};
+use game_core::config::{BackplaneConfig, ClusterConfig, MapGeometry};
+use game_core::{
+    BounceEvent, ChallengeCompletion, ChatMessage, ComboBreak, DamageEvent, GeometrySync, LifeEvent, MapChange, MatchState,
+    ActiveWorldEvent, KillCam, ModeManifest, ModeStatus, Player, PlayerInternals, Projectile, TeleportEvent, WorldEntity,
+    WorldEventChange, WorldSnapshot,
+};
+use crate::command_lanes::QueuedCommand;
+use crate::i18n::Text;
+use crate::state::AppState;
+use crate::GameUpdate;
+
+/// URL scheme of the in-process bus, for running several instances in one process
+const MEMORY_SCHEME: &str = "memory://";
+
+/// Shortest time between a follower's requests for the full block list
+const RESYNC_INTERVAL: Duration = Duration::from_secs(1);
+
+/// A game update as the leader publishes it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", content = "data")]
+pub enum WireUpdate {
+    /// The world at a state broadcast
+    State {
+        tick: u64,
+        server_time_ms: u64,
+        players: Vec<Player>,
+        /// What serializing the players leaves out, such as dashes under way
+        #[serde(default)]
+        internals: Vec<PlayerInternals>,
+        projectiles: Vec<Projectile>,
+        match_state: MatchState,
+        world_event: Option<ActiveWorldEvent>,
+        world_entities: Vec<WorldEntity>,
+        /// Followers whose blocks are at another version ask for the full list
+        geometry_version: u64,
+    },
+    GeometryChanged(GeometrySync),
+    ComboBroken(Vec<ComboBreak>),
+    ChallengesCompleted(Vec<ChallengeCompletion>),
+    LifeEvents(Vec<LifeEvent>),
+    Damage(Vec<DamageEvent>),
+    Bounces(Vec<BounceEvent>),
+    Teleports(Vec<TeleportEvent>),
+    /// Sent by signal name, which stays stable across versions
+    ModeStatus { signal: String, state: serde_json::Value },
+    KillCam(Box<KillCam>),
+    PlayerLeft { player_id: uuid::Uuid, player_name: String },
+    Notice(Text),
+    MatchPhase(MatchState),
+    MapChanged(MapChange),
+    ModeChanged(ModeManifest),
+    MapEdited(MapGeometry),
+    ConfigChanged(u64),
+    WorldEvent(WorldEventChange),
+    Announcement { html: String },
+}
+
+impl WireUpdate {
+    /// The update as published; None for updates about this instance alone
+    pub fn from_update(update: &GameUpdate) -> Option<Self> {
+        let wire = match update {
+            GameUpdate::StateUpdate { state, server_time_ms } => WireUpdate::State {
+                tick: state.tick,
+                server_time_ms: *server_time_ms,
+                players: state.players.values().cloned().collect(),
+                internals: state.players.values().map(Player::internals).collect(),
+                projectiles: state.projectiles.clone(),
+                match_state: state.match_state.clone(),
+                world_event: state.world_events.active.clone(),
+                world_entities: state.world_events.entities.clone(),
+                geometry_version: state.blocks.version(),
+            },
+            GameUpdate::GeometryChanged(sync) => WireUpdate::GeometryChanged(sync.clone()),
+            GameUpdate::ComboBroken(breaks) => WireUpdate::ComboBroken(breaks.clone()),
+            GameUpdate::ChallengesCompleted(completions) => WireUpdate::ChallengesCompleted(completions.clone()),
+            GameUpdate::LifeEvents(events) => WireUpdate::LifeEvents(events.clone()),
+            GameUpdate::Damage(events) => WireUpdate::Damage(events.clone()),
+            GameUpdate::Bounces(events) => WireUpdate::Bounces(events.clone()),
+            GameUpdate::Teleports(events) => WireUpdate::Teleports(events.clone()),
+            GameUpdate::ModeStatus(status) => WireUpdate::ModeStatus {
+                signal: status.signal.name().to_string(),
+                state: status.state.clone(),
+            },
+            GameUpdate::KillCam(kill_cam) => WireUpdate::KillCam(kill_cam.clone()),
+            GameUpdate::PlayerLeft { player_id, player_name } => WireUpdate::PlayerLeft {
+                player_id: *player_id,
+                player_name: player_name.clone(),
+            },
+            GameUpdate::Notice(text) => WireUpdate::Notice(text.clone()),
+            GameUpdate::MatchPhase(state) => WireUpdate::MatchPhase(state.clone()),
+            GameUpdate::MapChanged(change) => WireUpdate::MapChanged(change.clone()),
+            GameUpdate::ModeChanged(mode) => WireUpdate::ModeChanged(mode.clone()),
+            GameUpdate::MapEdited(geometry) => WireUpdate::MapEdited(geometry.clone()),
+            GameUpdate::ConfigChanged(version) => WireUpdate::ConfigChanged(*version),
+            GameUpdate::WorldEvent(change) => WireUpdate::WorldEvent(change.clone()),
+            GameUpdate::Announcement { html } => WireUpdate::Announcement { html: html.clone() },
+            // Each instance counts its own spectators and shuts down on its own
+            GameUpdate::SpectatorCount(_) | GameUpdate::ServerShutdown { .. } => return None,
+        };
+        Some(wire)
+    }
+}
+
+/// What instances send each other
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", content = "data")]
+enum Message {
+    /// Leader to everyone
+    Update(WireUpdate),
+    /// Anyone to everyone
+    Chat(ChatMessage),
+    /// Follower to leader
+    Command(QueuedCommand),
+    /// Follower to leader: players with a session stream open on the follower
+    Presence(Vec<uuid::Uuid>),
+    /// Follower to leader: publish the full block list
+    Resync,
+    /// Leader to everyone: the whole world, for followers to carry on from
+    Checkpoint(Box<WorldSnapshot>),
+    /// Instance being promoted to everyone: the leader stops and hands over its world, and
+    /// nobody claims the lease until the promoted instance has it
+    TakeOver,
+    /// Old leader to everyone: its world as it stopped, in answer to `TakeOver`
+    Handover(Box<WorldSnapshot>),
+}
+
+impl Message {
+    fn channel(&self) -> &'static str {
+        match self {
+            Message::Update(_) | Message::Checkpoint(_) | Message::Handover(_) => "updates",
+            Message::Chat(_) => "chat",
+            Message::Command(_) | Message::Presence(_) | Message::Resync | Message::TakeOver => "commands",
+        }
+    }
+}
+
+/// A message tagged with the instance that sent it, so instances skip their own
+#[derive(Debug, Serialize, Deserialize)]
+struct Envelope {
+    origin: String,
+    message: Message,
+}
+
+/// In-process stand-in for Redis, shared by the instances in a process that name the same bus
+#[derive(Clone)]
+struct MemoryBus {
+    messages: broadcast::Sender<(String, String)>,
+    /// Leader key to holder and expiry
+    leases: Arc<Mutex<HashMap<String, (String, tokio::time::Instant)>>>,
+}
+
+impl MemoryBus {
+    fn named(name: &str) -> Self {
+        static BUSES: OnceLock<Mutex<HashMap<String, MemoryBus>>> = OnceLock::new();
+        BUSES
+            .get_or_init(Default::default)
+            .lock()
+            .unwrap()
+            .entry(name.to_string())
+            .or_insert_with(|| MemoryBus {
+                messages: broadcast::channel(1024).0,
+                leases: Default::default(),
+            })
+            .clone()
+    }
+}
+
+#[cfg(feature = "redis")]
+struct RedisBus {
+    client: redis::Client,
+    /// Reconnects by itself; opened on first use
+    connection: tokio::sync::OnceCell<redis::aio::ConnectionManager>,
+}
+
+#[cfg(feature = "redis")]
+impl RedisBus {
+    async fn connection(&self) -> redis::RedisResult<redis::aio::ConnectionManager> {
+        self.connection
+            .get_or_try_init(|| self.client.get_connection_manager())
+            .await
+            .cloned()
+    }
+}
+
+/// Take the lease if it's free or renew it if it's ours; 1 when we hold it afterwards
+#[cfg(feature = "redis")]
+const LEASE_SCRIPT: &str = r#"
+local holder = redis.call('GET', KEYS[1])
+if holder == ARGV[1] then
+  redis.call('PEXPIRE', KEYS[1], ARGV[2])
+  return 1
+end
+if not holder then
+  redis.call('SET', KEYS[1], ARGV[1], 'PX', ARGV[2])
+  return 1
+end
+return 0
+"#;
+
+#[derive(Clone)]
+enum Transport {
+    Memory(MemoryBus),
+    #[cfg(feature = "redis")]
+    Redis(Arc<RedisBus>),
+}
+
+impl Transport {
+    fn open(url: &str) -> Result<Self, String> {
+        if let Some(name) = url.strip_prefix(MEMORY_SCHEME) {
+            return Ok(Transport::Memory(MemoryBus::named(name)));
+        }
+        #[cfg(feature = "redis")]
+        {
+            let client = redis::Client::open(url).map_err(|e| e.to_string())?;
+            Ok(Transport::Redis(Arc::new(RedisBus {
+                client,
+                connection: tokio::sync::OnceCell::new(),
+            })))
+        }
+        #[cfg(not(feature = "redis"))]
+        Err("built without the redis feature".to_string())
+    }
+
+    async fn publish(&self, channel: &str, payload: String) -> Result<(), String> {
+        match self {
+            Transport::Memory(bus) => {
+                // No receivers just means no instance is listening yet
+                let _ = bus.messages.send((channel.to_string(), payload));
+                Ok(())
+            }
+            #[cfg(feature = "redis")]
+            Transport::Redis(bus) => {
+                use redis::AsyncCommands;
+                let mut connection = bus.connection().await.map_err(|e| e.to_string())?;
+                connection.publish::<_, _, ()>(channel, payload).await.map_err(|e| e.to_string())
+            }
+        }
+    }
+
+    /// Payloads published to any of `channels`, in order per channel
+    /// Subscribed before returning, so nothing published afterwards is missed
+    fn subscribe(&self, channels: Vec<String>) -> mpsc::UnboundedReceiver<String> {
+        let (tx, rx) = mpsc::unbounded_channel();
+        match self {
+            Transport::Memory(bus) => {
+                let mut messages = bus.messages.subscribe();
+                tokio::spawn(async move {
+                    loop {
+                        match messages.recv().await {
+                            Ok((channel, payload)) if channels.contains(&channel) => {
+                                if tx.send(payload).is_err() {
+                                    return;
+                                }
+                            }
+                            Ok(_) => {}
+                            Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                                eprintln!("⚠️ Backplane subscriber skipped {} message(s)", skipped);
+                            }
+                            Err(broadcast::error::RecvError::Closed) => return,
+                        }
+                    }
+                });
+            }
+            #[cfg(feature = "redis")]
+            Transport::Redis(bus) => {
+                let bus = bus.clone();
+                tokio::spawn(async move {
+                    use futures::StreamExt;
+                    // Reconnect whenever the subscription drops
+                    loop {
+                        match bus.client.get_async_pubsub().await {
+                            Ok(mut pubsub) => {
+                                let mut subscribed = true;
+                                for channel in &channels {
+                                    if let Err(e) = pubsub.subscribe(channel).await {
+                                        eprintln!("⚠️ Failed to subscribe to {}: {}", channel, e);
+                                        subscribed = false;
+                                    }
+                                }
+                                if subscribed {
+                                    let mut messages = pubsub.on_message();
+                                    while let Some(message) = messages.next().await {
+                                        let Ok(payload) = message.get_payload::<String>() else {
+                                            continue;
+                                        };
+                                        if tx.send(payload).is_err() {
+                                            return;
+                                        }
+                                    }
+                                    eprintln!("⚠️ Backplane subscription dropped, reconnecting");
+                                }
+                            }
+                            Err(e) => eprintln!("⚠️ Failed to connect to the backplane: {}", e),
+                        }
+                        tokio::time::sleep(Duration::from_secs(1)).await;
+                    }
+                });
+            }
+        }
+        rx
+    }
+
+    /// Take or renew the leader lease; whether `holder` has it afterwards
+    async fn claim_lease(&self, key: &str, holder: &str, lease: Duration) -> Result<bool, String> {
+        match self {
+            Transport::Memory(bus) => {
+                let now = tokio::time::Instant::now();
+                let mut leases = bus.leases.lock().unwrap();
+                let free = leases.get(key).is_none_or(|(current, expires)| current == holder || *expires <= now);
+                if free {
+                    leases.insert(key.to_string(), (holder.to_string(), now + lease));
+                }
+                Ok(free)
+            }
+            #[cfg(feature = "redis")]
+            Transport::Redis(bus) => {
+                let mut connection = bus.connection().await.map_err(|e| e.to_string())?;
+                let held: i32 = redis::Script::new(LEASE_SCRIPT)
+                    .key(key)
+                    .arg(holder)
+                    .arg(lease.as_millis() as u64)
+                    .invoke_async(&mut connection)
+                    .await
+                    .map_err(|e| e.to_string())?;
+                Ok(held == 1)
+            }
+        }
+    }
+
+    /// Take the lease whoever holds it, for promoting a standby
+    async fn force_lease(&self, key: &str, holder: &str, lease: Duration) -> Result<(), String> {
+        match self {
+            Transport::Memory(bus) => {
+                let expires = tokio::time::Instant::now() + lease;
+                bus.leases.lock().unwrap().insert(key.to_string(), (holder.to_string(), expires));
+                Ok(())
+            }
+            #[cfg(feature = "redis")]
+            Transport::Redis(bus) => {
+                use redis::AsyncCommands;
+                let mut connection = bus.connection().await.map_err(|e| e.to_string())?;
+                let options = redis::SetOptions::default().with_expiration(redis::SetExpiry::PX(lease.as_millis() as u64));
+                connection.set_options::<_, _, ()>(key, holder, options).await.map_err(|e| e.to_string())
+            }
+        }
+    }
+}
+
+struct Inner {
+    instance_id: String,
+    channel_prefix: String,
+    lease: Duration,
+    checkpoint_interval: Duration,
+    transport: Transport,
+    leader: AtomicBool,
+    /// Mirroring without competing for the lease until promoted
+    standby: AtomicBool,
+    /// Another instance is being promoted; the lease is left to it until then
+    yielded_until: Mutex<Option<tokio::time::Instant>>,
+    /// Woken when the old leader hands over, while this instance is being promoted
+    handed_over: tokio::sync::Notify,
+    /// Messages waiting for the writer task, so publishing never blocks the caller
+    outbox: mpsc::UnboundedSender<(String, String)>,
+    /// Taken by the writer task when the backplane is spawned
+    outbox_rx: Mutex<Option<mpsc::UnboundedReceiver<(String, String)>>>,
+}
+
+impl Inner {
+    fn channel(&self, name: &str) -> String {
+        format!("{}:{}", self.channel_prefix, name)
+    }
+}
+
+/// This instance's link to the others; disabled unless turned on and given a backplane URL
+#[derive(Clone, Default)]
+pub struct Backplane {
+    inner: Option<Arc<Inner>>,
+}
+
+/// Whether distributed mode is on and the role this instance plays in it
+#[derive(Debug, Clone, Serialize)]
+pub struct BackplaneStatus {
+    pub enabled: bool,
+    pub instance_id: Option<String>,
+    pub leader: bool,
+    pub standby: bool,
+}
+
+impl Backplane {
+    /// Join the backplane at the configured URL; instances are told apart by their cluster id
+    pub fn new(config: &BackplaneConfig, cluster: &ClusterConfig) -> Self {
+        let Some(url) = config.resolve_url() else {
+            return Self::default();
+        };
+        let transport = match Transport::open(&url) {
+            Ok(transport) => transport,
+            Err(e) => {
+                eprintln!("⚠️ Backplane unavailable ({}), running standalone", e);
+                return Self::default();
+            }
+        };
+        let instance_id = cluster.resolve_instance_id().unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+        let standby = config.resolve_standby();
+        eprintln!(
+            "🛰️ Instance {} joining the backplane with prefix {}{}",
+            instance_id,
+            config.channel_prefix,
+            if standby { " as a standby" } else { "" }
+        );
+        let (outbox, outbox_rx) = mpsc::unbounded_channel();
+        Self {
+            inner: Some(Arc::new(Inner {
+                instance_id,
+                channel_prefix: config.channel_prefix.clone(),
+                lease: Duration::from_millis(config.leader_lease_ms.max(100)),
+                checkpoint_interval: Duration::from_millis(config.checkpoint_interval_ms.max(10)),
+                transport,
+                leader: AtomicBool::new(false),
+                standby: AtomicBool::new(standby),
+                yielded_until: Mutex::new(None),
+                handed_over: tokio::sync::Notify::new(),
+                outbox,
+                outbox_rx: Mutex::new(Some(outbox_rx)),
+            })),
+        }
+    }
+
+    pub fn enabled(&self) -> bool {
+        self.inner.is_some()
+    }
+
+    /// Whether this instance runs the simulation for every instance
+    pub fn is_leader(&self) -> bool {
+        self.inner.as_ref().is_some_and(|inner| inner.leader.load(Ordering::SeqCst))
+    }
+
+    /// Whether this instance mirrors another's simulation instead of running its own
+    pub fn is_follower(&self) -> bool {
+        self.inner.as_ref().is_some_and(|inner| !inner.leader.load(Ordering::SeqCst))
+    }
+
+    pub fn status(&self) -> BackplaneStatus {
+        BackplaneStatus {
+            enabled: self.enabled(),
+            instance_id: self.inner.as_ref().map(|inner| inner.instance_id.clone()),
+            leader: self.is_leader(),
+            standby: self.is_standby(),
+        }
+    }
+
+    /// Whether this instance only mirrors, waiting to be promoted
+    pub fn is_standby(&self) -> bool {
+        self.inner.as_ref().is_some_and(|inner| inner.standby.load(Ordering::SeqCst))
+    }
+
+    /// Take over the simulation from the current leader, continuing from the world it hands
+    /// over, or from this instance's mirror if it doesn't answer within the lease; works on
+    /// any follower, standby or not
+    pub async fn promote(&self) -> Result<(), String> {
+        let Some(inner) = &self.inner else {
+            return Err("not running in distributed mode".to_string());
+        };
+        if self.is_leader() {
+            return Err("already running the simulation".to_string());
+        }
+        // The leader stops before the lease changes hands, so the two never both simulate
+        let handed_over = inner.handed_over.notified();
+        tokio::pin!(handed_over);
+        handed_over.as_mut().enable();
+        self.send(Message::TakeOver);
+        if tokio::time::timeout(inner.lease, handed_over).await.is_err() {
+            eprintln!("⚠️ No leader handed over within the lease; carrying on from the mirror");
+        }
+        inner
+            .transport
+            .force_lease(&inner.channel("leader"), &inner.instance_id, inner.lease)
+            .await?;
+        inner.standby.store(false, Ordering::SeqCst);
+        inner.leader.store(true, Ordering::SeqCst);
+        eprintln!("👑 Instance {} was promoted and is now running the simulation", inner.instance_id);
+        Ok(())
+    }
+
+    fn send(&self, message: Message) {
+        let Some(inner) = &self.inner else {
+            return;
+        };
+        let channel = inner.channel(message.channel());
+        let envelope = Envelope {
+            origin: inner.instance_id.clone(),
+            message,
+        };
+        let payload = serde_json::to_string(&envelope).expect("backplane messages serialize to JSON");
+        let _ = inner.outbox.send((channel, payload));
+    }
+
+    /// Pass a command to the leader's game loop
+    pub fn forward(&self, command: QueuedCommand) {
+        self.send(Message::Command(command));
+    }
+
+    /// Share a chat message with the other instances' clients
+    pub fn publish_chat(&self, message: &ChatMessage) {
+        self.send(Message::Chat(message.clone()));
+    }
+}
+
+/// Start publishing, electing and relaying; does nothing when the backplane is disabled
+pub fn spawn(app_state: &AppState) {
+    let Some(inner) = app_state.backplane.inner.clone() else {
+        return;
+    };
+    let Some(outbox) = inner.outbox_rx.lock().unwrap().take() else {
+        return;
+    };
+    // Subscribe before anything is published, so no instance misses the first messages
+    let channels = ["updates", "chat", "commands"].map(|name| inner.channel(name)).to_vec();
+    let incoming = inner.transport.subscribe(channels);
+    tokio::spawn(write(inner.transport.clone(), outbox));
+    tokio::spawn(hold_lease(app_state.clone(), inner.clone()));
+    tokio::spawn(publish_checkpoints(app_state.clone(), inner));
+    tokio::spawn(receive(app_state.clone(), incoming));
+    tokio::spawn(relay_updates(app_state.clone()));
+}
+
+/// Publish queued messages in order
+async fn write(transport: Transport, mut outbox: mpsc::UnboundedReceiver<(String, String)>) {
+    while let Some((channel, payload)) = outbox.recv().await {
+        if let Err(e) = transport.publish(&channel, payload).await {
+            eprintln!("⚠️ Failed to publish to {}: {}", channel, e);
+        }
+    }
+}
+
+/// Keep claiming the leader lease, and while following, report who is connected here
+async fn hold_lease(app_state: AppState, inner: Arc<Inner>) {
+    let key = inner.channel("leader");
+    let mut interval = tokio::time::interval(inner.lease / 3);
+    loop {
+        interval.tick().await;
+        let yielded = inner.yielded_until.lock().unwrap().is_some_and(|until| tokio::time::Instant::now() < until);
+        if inner.standby.load(Ordering::SeqCst) || yielded {
+            app_state.backplane.send(Message::Presence(app_state.sessions.connected()));
+            continue;
+        }
+        let leading = match inner.transport.claim_lease(&key, &inner.instance_id, inner.lease).await {
+            Ok(leading) => leading,
+            Err(e) => {
+                // Unable to tell whether anyone else leads, so stop simulating rather than risk two leaders
+                eprintln!("⚠️ Failed to renew the leader lease: {}", e);
+                false
+            }
+        };
+        if inner.leader.swap(leading, Ordering::SeqCst) != leading {
+            if leading {
+                eprintln!("👑 Instance {} is now running the simulation", inner.instance_id);
+            } else {
+                eprintln!("📡 Instance {} is now following the leader", inner.instance_id);
+            }
+        }
+        if !leading {
+            app_state.backplane.send(Message::Presence(app_state.sessions.connected()));
+        }
+    }
+}
+
+/// While leading, publish the whole world every checkpoint interval
+async fn publish_checkpoints(app_state: AppState, inner: Arc<Inner>) {
+    let mut interval = tokio::time::interval(inner.checkpoint_interval);
+    interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+    loop {
+        interval.tick().await;
+        if app_state.backplane.is_leader() {
+            let snapshot = app_state.game_state.read().await.snapshot();
+            app_state.backplane.send(Message::Checkpoint(Box::new(snapshot)));
+        }
+    }
+}
+
+/// Load a leader's whole world into this instance's mirror
+async fn mirror(app_state: &AppState, snapshot: WorldSnapshot) {
+    if let Err(e) = app_state.game_state.write().await.mirror(snapshot) {
+        eprintln!("⚠️ Failed to mirror the leader's world: {}", e);
+    }
+}
+
+/// Apply what other instances send, according to this instance's role
+async fn receive(app_state: AppState, mut incoming: mpsc::UnboundedReceiver<String>) {
+    let backplane = &app_state.backplane;
+    let Some(inner) = &backplane.inner else {
+        return;
+    };
+    let mut last_resync: Option<tokio::time::Instant> = None;
+    while let Some(payload) = incoming.recv().await {
+        let envelope: Envelope = match serde_json::from_str(&payload) {
+            Ok(envelope) => envelope,
+            Err(e) => {
+                eprintln!("⚠️ Ignoring unreadable backplane message: {}", e);
+                continue;
+            }
+        };
+        if envelope.origin == inner.instance_id {
+            continue;
+        }
+        match envelope.message {
+            Message::Update(update) if backplane.is_follower() => {
+                let in_step = follow(&app_state, update).await;
+                let due = last_resync.is_none_or(|at| at.elapsed() >= RESYNC_INTERVAL);
+                if !in_step && due {
+                    last_resync = Some(tokio::time::Instant::now());
+                    backplane.send(Message::Resync);
+                }
+            }
+            Message::Chat(message) => crate::handlers::chat::relay_chat(&app_state, message).await,
+            Message::Command(command) if backplane.is_leader() => {
+                let queued = app_state.commands.try_push(command.lane(), command);
+                if !queued {
+                    eprintln!("⚠️ Dropped a command forwarded by {}", envelope.origin);
+                }
+            }
+            Message::Presence(players) if backplane.is_leader() => {
+                let now = app_state.clock.now();
+                let mut game_state = app_state.game_state.write().await;
+                for player_id in players {
+                    if let Some(player) = game_state.players.get_mut(&player_id) {
+                        player.update_activity(now);
+                    }
+                }
+            }
+            Message::Checkpoint(snapshot) if backplane.is_follower() => mirror(&app_state, *snapshot).await,
+            Message::TakeOver => {
+                *inner.yielded_until.lock().unwrap() = Some(tokio::time::Instant::now() + inner.lease * 2);
+                if inner.leader.swap(false, Ordering::SeqCst) {
+                    // The game loop checks the role under the lock before stepping, so this is the world as it stopped
+                    let snapshot = app_state.game_state.read().await.snapshot();
+                    backplane.send(Message::Handover(Box::new(snapshot)));
+                    eprintln!("📡 Instance {} handed the simulation to promoted instance {}", inner.instance_id, envelope.origin);
+                }
+            }
+            Message::Handover(snapshot) if backplane.is_follower() => {
+                mirror(&app_state, *snapshot).await;
+                inner.handed_over.notify_waiters();
+            }
+            Message::Resync if backplane.is_leader() => {
+                let snapshot = app_state.game_state.read().await.blocks.snapshot();
+                backplane.send(Message::Update(WireUpdate::GeometryChanged(GeometrySync::Snapshot(snapshot))));
+            }
+            // Meant for the other role; seen briefly while leadership changes hands
+            _ => {}
+        }
+    }
+}
+
+/// Mirror a leader's update into this instance's state and pass it to local streams
+/// Returns false when the blocks fell out of step and need the full list
+async fn follow(app_state: &AppState, update: WireUpdate) -> bool {
+    let mut in_step = true;
+    let update = match update {
+        WireUpdate::State {
+            tick,
+            server_time_ms,
+            players,
+            internals,
+            projectiles,
+            match_state,
+            world_event,
+            world_entities,
+            geometry_version,
+        } => {
+            let mut game_state = app_state.game_state.write().await;
+            game_state.tick = tick;
+            game_state.players = players.into_iter().map(|player| (player.id, player)).collect();
+            for internals in &internals {
+                if let Some(player) = game_state.players.get_mut(&internals.id) {
+                    player.set_internals(internals);
+                }
+            }
+            game_state.projectiles = projectiles;
+            game_state.match_state = match_state;
+            game_state.world_events.active = world_event;
+            game_state.world_events.entities = world_entities;
+            in_step = game_state.blocks.version() == geometry_version;
+            GameUpdate::StateUpdate {
+                state: Box::new(game_state.clone()),
+                server_time_ms,
+            }
+        }
+        WireUpdate::GeometryChanged(sync) => {
+            in_step = app_state.game_state.write().await.blocks.follow(&sync);
+            GameUpdate::GeometryChanged(sync)
+        }
+        WireUpdate::ComboBroken(breaks) => GameUpdate::ComboBroken(breaks),
+        WireUpdate::ChallengesCompleted(completions) => GameUpdate::ChallengesCompleted(completions),
+        WireUpdate::LifeEvents(events) => GameUpdate::LifeEvents(events),
+        WireUpdate::Damage(events) => GameUpdate::Damage(events),
+        WireUpdate::Bounces(events) => GameUpdate::Bounces(events),
+        WireUpdate::Teleports(events) => GameUpdate::Teleports(events),
+        WireUpdate::ModeStatus { signal, state } => {
+            // A signal from a newer leader that this instance's clients wouldn't know
+            let Some(signal) = game_core::signals::Signal::from_name(&signal) else {
+                return in_step;
+            };
+            GameUpdate::ModeStatus(ModeStatus { signal, state })
+        }
+        WireUpdate::KillCam(kill_cam) => GameUpdate::KillCam(kill_cam),
+        WireUpdate::PlayerLeft { player_id, player_name } => {
+            app_state.sessions.forget(&player_id);
+            GameUpdate::PlayerLeft { player_id, player_name }
+        }
+        WireUpdate::Notice(text) => GameUpdate::Notice(text),
+        WireUpdate::MatchPhase(state) => GameUpdate::MatchPhase(state),
+        WireUpdate::MapChanged(change) => GameUpdate::MapChanged(change),
+        WireUpdate::ModeChanged(mode) => GameUpdate::ModeChanged(mode),
+        WireUpdate::MapEdited(geometry) => GameUpdate::MapEdited(geometry),
+        WireUpdate::ConfigChanged(version) => GameUpdate::ConfigChanged(version),
+        WireUpdate::WorldEvent(change) => GameUpdate::WorldEvent(change),
+        WireUpdate::Announcement { html } => GameUpdate::Announcement { html },
+    };
+    let _ = app_state.game_tx.send(update);
+    in_step
+}
+
+/// While leading, publish every update the game loop broadcasts
+async fn relay_updates(app_state: AppState) {
+    let mut updates = app_state.game_tx.subscribe();
+    loop {
+        match updates.recv().await {
+            Ok(update) if app_state.backplane.is_leader() => {
+                // Followers load the changed world before their clients hear and fetch it
+                let reshaped = matches!(
+                    update,
+                    GameUpdate::MapChanged(_) | GameUpdate::ModeChanged(_) | GameUpdate::MapEdited(_) | GameUpdate::ConfigChanged(_)
+                );
+                if reshaped {
+                    let snapshot = app_state.game_state.read().await.snapshot();
+                    app_state.backplane.send(Message::Checkpoint(Box::new(snapshot)));
+                }
+                if let Some(wire) = WireUpdate::from_update(&update) {
+                    app_state.backplane.send(Message::Update(wire));
+                }
+            }
+            Ok(_) => {}
+            Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                eprintln!("⚠️ Backplane relay skipped {} update(s)", skipped);
+            }
+            Err(broadcast::error::RecvError::Closed) => return,
+        }
+    }
+}
@@ -0,0 +1,94 @@
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use serde::Serialize;
+use game_core::config::BackpressureConfig;
+
+/// What a stream should do after missing broadcasts
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LagAction {
+    /// Send the full state so the client catches up
+    Resync,
+    /// Close the stream; the subscriber keeps falling behind
+    Disconnect,
+}
+
+/// Recent misses of one stream, to tell a one-off stall from a subscriber that can't keep up
+#[derive(Debug, Clone)]
+pub struct LagTracker {
+    config: BackpressureConfig,
+    /// Server times of misses within the window, oldest first
+    lags: VecDeque<u64>,
+}
+
+impl LagTracker {
+    pub fn new(config: &BackpressureConfig) -> Self {
+        Self {
+            config: config.clone(),
+            lags: VecDeque::new(),
+        }
+    }
+
+    /// Record a miss at server time `now_ms`
+    pub fn record(&mut self, now_ms: u64) -> LagAction {
+        let window_ms = self.config.lag_window_secs * 1000;
+        while self.lags.front().is_some_and(|&at| now_ms.saturating_sub(at) > window_ms) {
+            self.lags.pop_front();
+        }
+        self.lags.push_back(now_ms);
+        if self.config.max_lags > 0 && self.lags.len() >= self.config.max_lags as usize {
+            LagAction::Disconnect
+        } else {
+            LagAction::Resync
+        }
+    }
+}
+
+/// Totals across every stream since startup
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct LagStats {
+    /// Times a stream fell behind the broadcast
+    pub lag_events: u64,
+    /// Broadcasts those streams missed
+    pub updates_skipped: u64,
+    /// Full state resyncs sent after a miss
+    pub resyncs: u64,
+    /// Streams closed for falling behind too often
+    pub disconnects: u64,
+}
+
+#[derive(Default)]
+struct Counters {
+    lag_events: AtomicU64,
+    updates_skipped: AtomicU64,
+    resyncs: AtomicU64,
+    disconnects: AtomicU64,
+}
+
+/// Shared lag counters, served at /api/admin/streams
+#[derive(Clone, Default)]
+pub struct LagMetrics {
+    counters: Arc<Counters>,
+}
+
+impl LagMetrics {
+    /// Count a miss of `skipped` broadcasts and what the stream did about it
+    pub fn record(&self, skipped: u64, action: LagAction) {
+        self.counters.lag_events.fetch_add(1, Ordering::Relaxed);
+        self.counters.updates_skipped.fetch_add(skipped, Ordering::Relaxed);
+        let outcome = match action {
+            LagAction::Resync => &self.counters.resyncs,
+            LagAction::Disconnect => &self.counters.disconnects,
+        };
+        outcome.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn stats(&self) -> LagStats {
+        LagStats {
+            lag_events: self.counters.lag_events.load(Ordering::Relaxed),
+            updates_skipped: self.counters.updates_skipped.load(Ordering::Relaxed),
+            resyncs: self.counters.resyncs.load(Ordering::Relaxed),
+            disconnects: self.counters.disconnects.load(Ordering::Relaxed),
+        }
+    }
+}
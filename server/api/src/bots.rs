@@ -0,0 +1,113 @@
+use std::sync::{Arc, Mutex};
+use serde::Serialize;
+use game_core::config::BotsConfig;
+use game_core::{Behavior, BehaviorKind, GameState};
+use crate::command_lanes::{CommandLane, CommandLanes, QueuedCommand};
+
+/// A bot as the admin API lists it
+#[derive(Debug, Clone, Serialize)]
+pub struct BotInfo {
+    pub id: uuid::Uuid,
+    pub name: String,
+    pub behavior: BehaviorKind,
+}
+
+struct Bot {
+    info: BotInfo,
+    behavior: Box<dyn Behavior>,
+}
+
+#[derive(Default)]
+struct Roster {
+    bots: Vec<Bot>,
+    /// Bots spawned so far, for numbering their names
+    spawned: usize,
+    /// Time since the bots last thought
+    since_think: f32,
+}
+
+/// Server-controlled players, queueing their commands alongside everyone else's
+#[derive(Clone)]
+pub struct Bots {
+    roster: Arc<Mutex<Roster>>,
+    commands: CommandLanes,
+    config: BotsConfig,
+}
+
+impl Bots {
+    pub fn new(config: &BotsConfig, commands: CommandLanes) -> Self {
+        Self {
+            roster: Arc::new(Mutex::new(Roster::default())),
+            commands,
+            config: config.clone(),
+        }
+    }
+
+    pub fn list(&self) -> Vec<BotInfo> {
+        self.roster.lock().unwrap().bots.iter().map(|bot| bot.info.clone()).collect()
+    }
+
+    /// Add a bot to the game, with the next configured behavior unless one is given
+    /// Returns None when the room is full
+    pub fn spawn(&self, game_state: &mut GameState, behavior: Option<BehaviorKind>) -> Option<BotInfo> {
+        let mut roster = self.roster.lock().unwrap();
+        let kind = behavior.unwrap_or_else(|| self.config.behavior(roster.spawned));
+        let id = game_state.new_player_id();
+        let name = format!("{} Bot {}", kind.label(), roster.spawned + 1);
+        if !game_state.add_bot(id, &name) {
+            return None;
+        }
+        roster.spawned += 1;
+        let info = BotInfo { id, name, behavior: kind };
+        let behavior = kind.build(&game_state.players[&id]);
+        eprintln!("🤖 Bot {} joined ({:?})", info.name, kind);
+        roster.bots.push(Bot { info: info.clone(), behavior });
+        Some(info)
+    }
+
+    /// Add or remove bots until there are `count`, newest removed first
+    /// Returns the bots removed, so their departure can be broadcast
+    pub fn set_count(
+        &self,
+        game_state: &mut GameState,
+        count: usize,
+        behavior: Option<BehaviorKind>,
+    ) -> Vec<BotInfo> {
+        while self.roster.lock().unwrap().bots.len() < count {
+            if self.spawn(game_state, behavior).is_none() {
+                eprintln!("⚠️ Room is full, stopping at {} bot(s)", self.list().len());
+                break;
+            }
+        }
+        let mut roster = self.roster.lock().unwrap();
+        let keep = count.min(roster.bots.len());
+        let removed: Vec<BotInfo> = roster.bots.drain(keep..).map(|bot| bot.info).collect();
+        for bot in &removed {
+            eprintln!("🤖 Bot {} left", bot.name);
+            game_state.remove_player(&bot.id);
+        }
+        removed
+    }
+
+    /// Let every bot decide on a command once per think interval and queue it in the gameplay lane
+    /// Called by the game loop with the real time that has passed
+    pub fn think(&self, game_state: &GameState, elapsed: f32) {
+        let interval = 1.0 / self.config.think_hz.max(0.1);
+        let mut roster = self.roster.lock().unwrap();
+        roster.since_think += elapsed;
+        if roster.since_think < interval {
+            return;
+        }
+        roster.since_think = (roster.since_think - interval).min(interval);
+
+        // Bots can leave the game without the roster, e.g. when an admin kicks them
+        roster.bots.retain(|bot| game_state.players.contains_key(&bot.info.id));
+        for bot in roster.bots.iter_mut() {
+            let me = &game_state.players[&bot.info.id];
+            if let Some(command) = bot.behavior.think(me, game_state) {
+                let queued = QueuedCommand::Player { player_id: bot.info.id, command, seq: 0, trace: None };
+                self.commands.try_push(CommandLane::Gameplay, queued);
+            }
+        }
+    }
+}
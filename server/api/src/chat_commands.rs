@@ -0,0 +1,178 @@
+use game_core::{Language, Palette, VoteError};
+use crate::i18n::{translate, Text};
+use crate::state::AppState;
+
+/// A slash command typed into chat
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ChatCommand {
+    /// Change display name
+    Nick(String),
+    /// List connected players
+    Who,
+    /// Send a message only the sender's team sees
+    Team(String),
+    /// Choose the language of server messages
+    Lang(String),
+    /// Choose the palette players and teams are shown in
+    Palette(String),
+    /// Vote for the next map, or list the maps and their votes when empty
+    Map(String),
+    /// Show available commands
+    Help,
+    /// Anything else starting with '/'
+    Unknown(String),
+}
+
+/// Commands listed by /help, as (usage, description)
+const HELP_ENTRIES: &[(&str, Text)] = &[
+    ("/nick &lt;name&gt;", Text::HelpNick),
+    ("/who", Text::HelpWho),
+    ("/team &lt;message&gt;", Text::HelpTeam),
+    ("/lang &lt;code&gt;", Text::HelpLang),
+    ("/palette &lt;standard|colorblind&gt;", Text::HelpPalette),
+    ("/map [name]", Text::HelpMap),
+    ("/help", Text::HelpHelp),
+];
+
+/// Parse chat text as a slash command, or None if it is a regular message
+pub fn parse(text: &str) -> Option<ChatCommand> {
+    let text = text.trim();
+    let rest = text.strip_prefix('/')?;
+    let (name, args) = match rest.split_once(char::is_whitespace) {
+        Some((name, args)) => (name, args.trim()),
+        None => (rest, ""),
+    };
+    let command = match name.to_lowercase().as_str() {
+        "nick" | "name" => ChatCommand::Nick(args.to_string()),
+        "who" => ChatCommand::Who,
+        "team" | "t" => ChatCommand::Team(args.to_string()),
+        "lang" | "language" => ChatCommand::Lang(args.to_string()),
+        "palette" | "colors" => ChatCommand::Palette(args.to_string()),
+        "map" | "vote" => ChatCommand::Map(args.to_string()),
+        "help" | "?" => ChatCommand::Help,
+        _ => ChatCommand::Unknown(name.to_string()),
+    };
+    Some(command)
+}
+
+/// Escape text for safe inclusion in HTML patches
+pub fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// A system line shown only to the player who sent it
+pub fn system_line(text: &str, color: &str) -> String {
+    format!(
+        r#"<div style="margin-bottom: 8px; font-size: 14px; color: {}; font-style: italic">{}</div>"#,
+        color, text
+    )
+}
+
+/// Run a command and return the HTML to append to the sender's chat, in the sender's language
+pub async fn execute(app_state: &AppState, player_id: uuid::Uuid, command: ChatCommand, language: Language) -> String {
+    match command {
+        ChatCommand::Nick(name) => {
+            let result = app_state.game_state.write().await.rename_player(&player_id, &name);
+            match result {
+                Ok(name) => {
+                    app_state.moderator.review(player_id, crate::moderation::ContentKind::Name, name.clone());
+                    system_line(&translate(language, &Text::NowKnownAs { name }), "#AAAAAA")
+                }
+                Err(e) => system_line(&translate(language, &Text::RenameFailed(e)), "#FF6666"),
+            }
+        }
+        ChatCommand::Who => {
+            let game_state = app_state.game_state.read().await;
+            let palette = game_state.players.get(&player_id).and_then(|p| p.palette).unwrap_or_default();
+            let mut players: Vec<_> = game_state.players.values().collect();
+            players.sort_by_key(|p| p.name.to_lowercase());
+            let names: Vec<String> = players
+                .iter()
+                .map(|p| {
+                    format!(
+                        r#"<span style="color: {}; font-weight: bold;">{}</span>"#,
+                        game_core::player_color::player_color(&p.id, palette, &app_state.game_config.color_blind_palette),
+                        escape_html(&p.name)
+                    )
+                })
+                .collect();
+            let online = Text::PlayersOnline {
+                count: players.len(),
+                names_html: names.join(", "),
+            };
+            system_line(&translate(language, &online), "#AAAAAA")
+        }
+        // Team messages are broadcast by the chat handler; this only runs when there's nothing to send
+        ChatCommand::Team(_) => system_line(&translate(language, &Text::TeamUsage), "#AAAAAA"),
+        ChatCommand::Lang(tag) => match Language::parse(&tag) {
+            Some(language) => {
+                if let Some(player) = app_state.game_state.write().await.players.get_mut(&player_id) {
+                    player.language = Some(language);
+                }
+                system_line(&translate(language, &Text::LanguageChanged), "#AAAAAA")
+            }
+            None => system_line(&translate(language, &Text::UnknownLanguage { tag }), "#FF6666"),
+        },
+        ChatCommand::Palette(name) => match Palette::parse(&name) {
+            Some(palette) => {
+                if let Some(player) = app_state.game_state.write().await.players.get_mut(&player_id) {
+                    player.palette = Some(palette);
+                }
+                system_line(&translate(language, &Text::PaletteChanged(palette)), "#AAAAAA")
+            }
+            None => system_line(&translate(language, &Text::UnknownPalette { name }), "#FF6666"),
+        },
+        ChatCommand::Map(name) if name.is_empty() => {
+            let game_state = app_state.game_state.read().await;
+            let Some(current) = crate::handlers::maps::current_map(&game_state) else {
+                return system_line(&translate(language, &Text::MapVoteFailed(VoteError::Disabled)), "#FF6666");
+            };
+            let votes = game_state
+                .map_votes()
+                .into_iter()
+                .map(|map| (map.name, map.votes))
+                .collect();
+            system_line(&translate(language, &Text::MapVotes { current: current.name, votes }), "#AAAAAA")
+        }
+        ChatCommand::Map(name) => {
+            let result = app_state.game_state.write().await.vote_map(&player_id, &name);
+            match result {
+                Ok(map_id) => {
+                    let game_state = app_state.game_state.read().await;
+                    let map = game_state
+                        .map_votes()
+                        .into_iter()
+                        .find(|m| m.id == map_id)
+                        .map(|m| m.name)
+                        .unwrap_or(map_id);
+                    system_line(&translate(language, &Text::MapVoted { map }), "#AAAAAA")
+                }
+                Err(e) => system_line(&translate(language, &Text::MapVoteFailed(e)), "#FF6666"),
+            }
+        }
+        ChatCommand::Help => {
+            let rows: String = HELP_ENTRIES
+                .iter()
+                .map(|(usage, description)| {
+                    format!(r#"<div><b>{}</b> &mdash; {}</div>"#, usage, translate(language, description))
+                })
+                .collect();
+            format!(
+                r#"<div style="margin-bottom: 8px; font-size: 14px; color: #DDDDDD; border-left: 3px solid #888888; padding-left: 6px">{}</div>"#,
+                rows
+            )
+        }
+        ChatCommand::Unknown(name) => system_line(&translate(language, &Text::UnknownCommand { name }), "#FF6666"),
+    }
+}
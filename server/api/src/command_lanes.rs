@@ -0,0 +1,211 @@
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use serde::{Deserialize, Serialize};
+use tokio::sync::Notify;
+use game_core::config::{CommandLanesConfig, LaneConfig, OverflowPolicy};
+use game_core::{EquippedCosmetics, PlayerCommand};
+
+/// Which queue a command waits in; the game loop empties them in this order each tick
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CommandLane {
+    Admin,
+    Gameplay,
+    Cosmetic,
+}
+
+impl CommandLane {
+    /// Every lane, highest priority first
+    pub const ALL: [CommandLane; 3] = [CommandLane::Admin, CommandLane::Gameplay, CommandLane::Cosmetic];
+}
+
+/// Something for the game loop to apply to the game state
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum QueuedCommand {
+    /// Input for a player's character; seq 0 skips the duplicate check, as admin commands do
+    /// A sampled command carries its latency trace along
+    Player {
+        player_id: uuid::Uuid,
+        command: PlayerCommand,
+        seq: u64,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        trace: Option<crate::latency::Trace>,
+    },
+    /// Newly equipped cosmetics to show on a player
+    Cosmetics { player_id: uuid::Uuid, equipped: EquippedCosmetics },
+    /// A player who joined through another instance of a distributed deployment
+    Join { player_id: uuid::Uuid },
+    /// A player who left on purpose, e.g. by closing the tab; does nothing if already gone
+    Leave { player_id: uuid::Uuid },
+}
+
+impl QueuedCommand {
+    /// The lane a command forwarded from another instance waits in
+    pub fn lane(&self) -> CommandLane {
+        match self {
+            QueuedCommand::Player { .. } | QueuedCommand::Join { .. } | QueuedCommand::Leave { .. } => {
+                CommandLane::Gameplay
+            }
+            QueuedCommand::Cosmetics { .. } => CommandLane::Cosmetic,
+        }
+    }
+}
+
+/// A lane's current depth and how many commands it has dropped since startup
+#[derive(Debug, Clone, Serialize)]
+pub struct LaneStats {
+    pub lane: CommandLane,
+    pub queued: usize,
+    pub capacity: usize,
+    pub overflow: OverflowPolicy,
+    pub dropped: u64,
+}
+
+struct Lane {
+    queue: Mutex<VecDeque<QueuedCommand>>,
+    capacity: usize,
+    overflow: OverflowPolicy,
+    dropped: AtomicU64,
+}
+
+impl Lane {
+    fn new(config: &LaneConfig) -> Self {
+        Self {
+            queue: Mutex::new(VecDeque::new()),
+            capacity: config.capacity.max(1),
+            overflow: config.overflow,
+            dropped: AtomicU64::new(0),
+        }
+    }
+}
+
+struct Shared {
+    lanes: [Lane; 3],
+    /// Woken whenever the game loop empties the lanes, for senders blocked on a full one
+    space: Notify,
+    /// Set when the game loop is gone, so blocked senders give up
+    closed: AtomicBool,
+}
+
+impl Shared {
+    fn lane(&self, lane: CommandLane) -> &Lane {
+        &self.lanes[lane as usize]
+    }
+}
+
+/// Sending half: handlers push commands into the lane they belong to
+#[derive(Clone)]
+pub struct CommandLanes {
+    shared: Arc<Shared>,
+}
+
+/// Receiving half, owned by the game loop
+pub struct LaneReceiver {
+    shared: Arc<Shared>,
+}
+
+/// Bounded lanes with the configured capacities and overflow policies
+pub fn channel(config: &CommandLanesConfig) -> (CommandLanes, LaneReceiver) {
+    let shared = Arc::new(Shared {
+        lanes: [Lane::new(&config.admin), Lane::new(&config.gameplay), Lane::new(&config.cosmetic)],
+        space: Notify::new(),
+        closed: AtomicBool::new(false),
+    });
+    (CommandLanes { shared: shared.clone() }, LaneReceiver { shared })
+}
+
+impl CommandLanes {
+    /// Queue a command, applying the lane's overflow policy when it is full
+    /// Returns false if this command was dropped or the game loop has stopped
+    pub async fn push(&self, lane: CommandLane, command: QueuedCommand) -> bool {
+        let state = self.shared.lane(lane);
+        loop {
+            // Register for wakeups before checking, so a drain in between isn't missed
+            let space = self.shared.space.notified();
+            tokio::pin!(space);
+            space.as_mut().enable();
+            if self.shared.closed.load(Ordering::SeqCst) {
+                return false;
+            }
+            {
+                let mut queue = state.queue.lock().unwrap();
+                if queue.len() < state.capacity {
+                    queue.push_back(command);
+                    return true;
+                }
+                match state.overflow {
+                    OverflowPolicy::Block => {}
+                    OverflowPolicy::DropNewest => {
+                        state.dropped.fetch_add(1, Ordering::Relaxed);
+                        return false;
+                    }
+                    OverflowPolicy::DropOldest => {
+                        queue.pop_front();
+                        queue.push_back(command);
+                        state.dropped.fetch_add(1, Ordering::Relaxed);
+                        return true;
+                    }
+                }
+            }
+            space.await;
+        }
+    }
+
+    /// Queue a command without waiting; a full lane that would block drops it instead
+    /// For producers driven by the game loop, which would otherwise wait on themselves
+    pub fn try_push(&self, lane: CommandLane, command: QueuedCommand) -> bool {
+        let state = self.shared.lane(lane);
+        if self.shared.closed.load(Ordering::SeqCst) {
+            return false;
+        }
+        let mut queue = state.queue.lock().unwrap();
+        if queue.len() < state.capacity {
+            queue.push_back(command);
+            return true;
+        }
+        state.dropped.fetch_add(1, Ordering::Relaxed);
+        if state.overflow == OverflowPolicy::DropOldest {
+            queue.pop_front();
+            queue.push_back(command);
+            return true;
+        }
+        false
+    }
+
+    /// Depth and drop counts for every lane, highest priority first
+    pub fn stats(&self) -> Vec<LaneStats> {
+        CommandLane::ALL
+            .iter()
+            .map(|&lane| {
+                let state = self.shared.lane(lane);
+                LaneStats {
+                    lane,
+                    queued: state.queue.lock().unwrap().len(),
+                    capacity: state.capacity,
+                    overflow: state.overflow,
+                    dropped: state.dropped.load(Ordering::Relaxed),
+                }
+            })
+            .collect()
+    }
+}
+
+impl LaneReceiver {
+    /// Take everything queued, admin commands first, and wake blocked senders
+    pub fn drain(&mut self) -> Vec<QueuedCommand> {
+        let mut commands = Vec::new();
+        for lane in CommandLane::ALL {
+            commands.extend(self.shared.lane(lane).queue.lock().unwrap().drain(..));
+        }
+        self.shared.space.notify_waiters();
+        commands
+    }
+}
+
+impl Drop for LaneReceiver {
+    fn drop(&mut self) {
+        self.shared.closed.store(true, Ordering::SeqCst);
+        self.shared.space.notify_waiters();
+    }
+}
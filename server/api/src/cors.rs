@@ -0,0 +1,38 @@
+use std::time::Duration;
+use axum::http::{HeaderName, HeaderValue, Method};
+use tower_http::cors::{AllowHeaders, AllowOrigin, CorsLayer};
+use game_core::config::CorsConfig;
+
+/// The CORS layer for the configured origins, or None to stay same-origin only
+/// "*" allows any origin, without credentials since browsers refuse that combination
+pub fn layer(config: &CorsConfig) -> Option<CorsLayer> {
+    let origins = config.resolve_origins();
+    if origins.is_empty() {
+        return None;
+    }
+    let any = origins.iter().any(|origin| origin == "*");
+    let allow_origin = if any {
+        AllowOrigin::any()
+    } else {
+        let origins: Vec<HeaderValue> = origins
+            .iter()
+            .filter_map(|origin| {
+                let value = HeaderValue::from_str(origin.trim_end_matches('/')).ok();
+                if value.is_none() {
+                    eprintln!("⚠️ Ignoring invalid CORS origin: {}", origin);
+                }
+                value
+            })
+            .collect();
+        AllowOrigin::list(origins)
+    };
+    let layer = CorsLayer::new()
+        .allow_origin(allow_origin)
+        .allow_methods([Method::GET, Method::POST, Method::PUT, Method::PATCH, Method::DELETE])
+        .allow_headers(AllowHeaders::mirror_request())
+        .expose_headers([HeaderName::from_static(crate::affinity::INSTANCE_HEADER)])
+        .allow_credentials(config.allow_credentials && !any)
+        .max_age(Duration::from_secs(config.max_age_secs));
+    eprintln!("🌍 CORS allows {}", if any { "any origin".to_string() } else { origins.join(", ") });
+    Some(layer)
+}
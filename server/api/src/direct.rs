@@ -0,0 +1,102 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use tokio::sync::mpsc;
+use game_core::signals::SignalPatch;
+use crate::i18n::Text;
+
+/// Events queued per stream before further ones are dropped for that stream
+const STREAM_CAPACITY: usize = 64;
+
+/// A player's open streams, each with the id its receiver unregisters by
+type Streams = HashMap<uuid::Uuid, Vec<(u64, mpsc::Sender<DirectEvent>)>>;
+
+/// Something for one player's event streams only
+#[derive(Debug, Clone)]
+pub enum DirectEvent {
+    /// Signals patched on this player's streams and no one else's
+    Signals(SignalPatch),
+    /// A system line in the player's chat, translated for their stream
+    Notice(Text),
+}
+
+/// Senders into each player's open event streams, for server-to-one-client messages
+///
+/// Every session stream registers here while it is open, so a player with two tabs gets each
+/// message twice. Only streams on this instance are reached; in distributed mode a player
+/// connected to another instance gets nothing.
+#[derive(Clone, Default)]
+pub struct DirectMessages {
+    streams: Arc<Mutex<Streams>>,
+    next_id: Arc<AtomicU64>,
+}
+
+impl DirectMessages {
+    /// Register a stream for a player; it stays registered until the receiver drops
+    pub fn subscribe(&self, player_id: uuid::Uuid) -> DirectReceiver {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let (tx, rx) = mpsc::channel(STREAM_CAPACITY);
+        self.streams.lock().unwrap().entry(player_id).or_default().push((id, tx));
+        DirectReceiver {
+            rx,
+            player_id,
+            id,
+            messages: self.clone(),
+        }
+    }
+
+    /// Queue an event on every open stream of a player, returning how many it reached
+    /// Streams too far behind to take it miss it rather than holding up the sender
+    pub fn send(&self, player_id: &uuid::Uuid, event: DirectEvent) -> usize {
+        let streams = self.streams.lock().unwrap();
+        let Some(senders) = streams.get(player_id) else {
+            return 0;
+        };
+        senders
+            .iter()
+            .filter(|(_, tx)| match tx.try_send(event.clone()) {
+                Ok(()) => true,
+                Err(_) => {
+                    eprintln!("📭 Dropped a direct message for {}, their stream is behind", player_id);
+                    false
+                }
+            })
+            .count()
+    }
+
+    /// Open streams registered for a player
+    pub fn streams(&self, player_id: &uuid::Uuid) -> usize {
+        self.streams.lock().unwrap().get(player_id).map_or(0, Vec::len)
+    }
+
+    /// Players with at least one open stream
+    pub fn players(&self) -> usize {
+        self.streams.lock().unwrap().len()
+    }
+}
+
+/// One stream's end of the registry; dropping it unregisters the stream
+pub struct DirectReceiver {
+    rx: mpsc::Receiver<DirectEvent>,
+    player_id: uuid::Uuid,
+    id: u64,
+    messages: DirectMessages,
+}
+
+impl DirectReceiver {
+    pub async fn recv(&mut self) -> Option<DirectEvent> {
+        self.rx.recv().await
+    }
+}
+
+impl Drop for DirectReceiver {
+    fn drop(&mut self) {
+        let mut streams = self.messages.streams.lock().unwrap();
+        if let Some(senders) = streams.get_mut(&self.player_id) {
+            senders.retain(|(id, _)| *id != self.id);
+            if senders.is_empty() {
+                streams.remove(&self.player_id);
+            }
+        }
+    }
+}
@@ -0,0 +1,195 @@
+//! Error responses shared by the game, chat and admin handlers
+//!
+//! Every failure goes out as JSON `{ "code", "message", "details" }` with a status that
+//! matches it, so clients can branch on `code` instead of guessing from an empty body.
+
+use axum::http::{header, StatusCode};
+use axum::response::{IntoResponse, Json, Response};
+use serde_json::{json, Value};
+use crate::api_tokens::TokenScope;
+use crate::command_lanes::CommandLane;
+use crate::moderation::ChatRejection;
+
+/// Why a request failed
+#[derive(Debug, Clone, PartialEq)]
+pub enum ApiError {
+    /// The session token is missing, belongs to another player, or to no one
+    InvalidSession,
+    /// No bearer token, or one that isn't recognised
+    Unauthorized,
+    /// The bearer token's scope doesn't reach this route
+    InsufficientScope { scope: TokenScope, required: TokenScope },
+    /// The player isn't in the game; init again
+    UnknownPlayer,
+    /// Something else named in the request doesn't exist, or the feature it needs is off
+    NotFound(String),
+    /// Malformed or empty input
+    BadRequest(String),
+    /// Geometry that fails validation, one reason per problem
+    InvalidGeometry(Vec<String>),
+    /// The caller may not do this, whoever they are
+    Forbidden(String),
+    /// The request clashes with the current state, e.g. something already taken
+    Conflict(String),
+    /// The body is bigger than the server keeps
+    TooLarge(String),
+    /// The server can't take this now; retrying later may work
+    Unavailable(String),
+    /// The server failed, e.g. writing to disk
+    Internal(String),
+    /// The player is waiting for a slot and can't act in the world yet
+    Waiting,
+    /// The client's IP already has too many live players
+    PlayerLimit,
+    /// An admin banned the client's IP
+    Banned,
+    /// Too many requests; retry after this many seconds
+    RateLimited { retry_after_secs: u64 },
+    /// The room and the waiting queue are both full
+    ServerFull,
+    ShuttingDown,
+    /// An admin is draining the server ahead of a restart, so it takes no new players
+    Draining,
+    /// A command lane is full, so the command was dropped
+    QueueFull(CommandLane),
+    /// A display name was refused
+    Name(game_core::NameError),
+    /// A chat message was refused; `message` is the sender-facing text in their language
+    Chat { rejection: ChatRejection, message: String },
+    /// Team chat from a player without a team
+    NotOnTeam(String),
+}
+
+impl ApiError {
+    pub fn status(&self) -> StatusCode {
+        match self {
+            ApiError::InvalidSession | ApiError::Unauthorized => StatusCode::UNAUTHORIZED,
+            ApiError::InsufficientScope { .. } | ApiError::Banned => StatusCode::FORBIDDEN,
+            ApiError::Forbidden(_) => StatusCode::FORBIDDEN,
+            ApiError::UnknownPlayer | ApiError::NotFound(_) => StatusCode::NOT_FOUND,
+            ApiError::BadRequest(_) | ApiError::InvalidGeometry(_) => StatusCode::BAD_REQUEST,
+            ApiError::Waiting | ApiError::NotOnTeam(_) | ApiError::Conflict(_) => StatusCode::CONFLICT,
+            ApiError::TooLarge(_) => StatusCode::PAYLOAD_TOO_LARGE,
+            ApiError::Unavailable(_) => StatusCode::SERVICE_UNAVAILABLE,
+            ApiError::Internal(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            ApiError::PlayerLimit | ApiError::RateLimited { .. } => StatusCode::TOO_MANY_REQUESTS,
+            ApiError::ServerFull | ApiError::ShuttingDown | ApiError::Draining | ApiError::QueueFull(_) => {
+                StatusCode::SERVICE_UNAVAILABLE
+            }
+            ApiError::Name(e) => match e {
+                game_core::NameError::UnknownPlayer => StatusCode::NOT_FOUND,
+                game_core::NameError::Taken => StatusCode::CONFLICT,
+                game_core::NameError::Cooldown => StatusCode::TOO_MANY_REQUESTS,
+                _ => StatusCode::BAD_REQUEST,
+            },
+            ApiError::Chat { rejection, .. } => match rejection {
+                ChatRejection::Muted(_) => StatusCode::FORBIDDEN,
+                ChatRejection::RateLimited => StatusCode::TOO_MANY_REQUESTS,
+                _ => StatusCode::UNPROCESSABLE_ENTITY,
+            },
+        }
+    }
+
+    /// Stable, machine-readable name for the error
+    pub fn code(&self) -> &'static str {
+        match self {
+            ApiError::InvalidSession => "invalid_session",
+            ApiError::Unauthorized => "unauthorized",
+            ApiError::InsufficientScope { .. } => "insufficient_scope",
+            ApiError::UnknownPlayer => "unknown_player",
+            ApiError::NotFound(_) => "not_found",
+            ApiError::BadRequest(_) => "bad_request",
+            ApiError::InvalidGeometry(_) => "invalid_geometry",
+            ApiError::Forbidden(_) => "forbidden",
+            ApiError::Conflict(_) => "conflict",
+            ApiError::TooLarge(_) => "too_large",
+            ApiError::Unavailable(_) => "unavailable",
+            ApiError::Internal(_) => "internal_error",
+            ApiError::Waiting => "waiting_for_slot",
+            ApiError::PlayerLimit => "player_limit",
+            ApiError::Banned => "banned",
+            ApiError::RateLimited { .. } => "rate_limited",
+            ApiError::ServerFull => "server_full",
+            ApiError::ShuttingDown => "shutting_down",
+            ApiError::Draining => "draining",
+            ApiError::QueueFull(_) => "queue_full",
+            ApiError::Name(e) => match e {
+                game_core::NameError::Taken => "name_taken",
+                game_core::NameError::Cooldown => "rename_cooldown",
+                game_core::NameError::UnknownPlayer => "unknown_player",
+                _ => "invalid_name",
+            },
+            ApiError::Chat { rejection, .. } => match rejection {
+                ChatRejection::Muted(_) => "muted",
+                ChatRejection::RateLimited => "chat_rate_limited",
+                _ => "message_rejected",
+            },
+            ApiError::NotOnTeam(_) => "not_on_team",
+        }
+    }
+
+    pub fn message(&self) -> String {
+        match self {
+            ApiError::InvalidSession => "missing session token, or one that does not belong to this player".to_string(),
+            ApiError::Unauthorized => "missing or unknown bearer token".to_string(),
+            ApiError::InsufficientScope { .. } => "token scope does not allow this route".to_string(),
+            ApiError::UnknownPlayer => "player is not in the game".to_string(),
+            ApiError::InvalidGeometry(_) => "geometry failed validation".to_string(),
+            ApiError::NotFound(reason)
+            | ApiError::BadRequest(reason)
+            | ApiError::Forbidden(reason)
+            | ApiError::Conflict(reason)
+            | ApiError::TooLarge(reason)
+            | ApiError::Unavailable(reason)
+            | ApiError::Internal(reason) => reason.clone(),
+            ApiError::Waiting => "player is waiting for a slot".to_string(),
+            ApiError::PlayerLimit => "too many players from this address".to_string(),
+            ApiError::Banned => "this address is banned".to_string(),
+            ApiError::RateLimited { .. } => "too many requests".to_string(),
+            ApiError::ServerFull => "room and waiting queue are full".to_string(),
+            ApiError::ShuttingDown => "server is shutting down".to_string(),
+            ApiError::Draining => "server is not taking new players".to_string(),
+            ApiError::QueueFull(_) => "server is too busy to take the command".to_string(),
+            ApiError::Name(e) => e.to_string(),
+            ApiError::Chat { message, .. } | ApiError::NotOnTeam(message) => message.clone(),
+        }
+    }
+
+    /// Anything beyond the code a client might act on
+    pub fn details(&self) -> Value {
+        match self {
+            ApiError::InsufficientScope { scope, required } => json!({ "scope": scope, "required": required }),
+            ApiError::RateLimited { retry_after_secs } => json!({ "retry_after_secs": retry_after_secs }),
+            ApiError::QueueFull(lane) => json!({ "lane": lane }),
+            ApiError::InvalidGeometry(errors) => json!({ "errors": errors }),
+            ApiError::Chat { rejection: ChatRejection::Muted(remaining), .. } => {
+                json!({ "remaining_secs": remaining.as_secs() })
+            }
+            ApiError::Chat { rejection: ChatRejection::TooLong { max }, .. } => json!({ "max_length": max }),
+            _ => Value::Null,
+        }
+    }
+}
+
+impl std::fmt::Display for ApiError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.code(), self.message())
+    }
+}
+
+impl std::error::Error for ApiError {}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let body = json!({
+            "code": self.code(),
+            "message": self.message(),
+            "details": self.details(),
+        });
+        let mut response = (self.status(), Json(body)).into_response();
+        if let ApiError::RateLimited { retry_after_secs } = self {
+            response.headers_mut().insert(header::RETRY_AFTER, retry_after_secs.into());
+        }
+        response
+    }
+}
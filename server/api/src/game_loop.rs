@@ -0,0 +1,502 @@
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{broadcast, RwLock};
+use game_core::{CosmeticStore, GameConfig, GameState, MatchHistory, MatchState, ReplayRecorder, SharedClock};
+use crate::command_lanes::QueuedCommand;
+use crate::session::SessionStore;
+use crate::GameUpdate;
+
+/// Maximum physics steps run in one loop iteration when catching up
+const MAX_STEPS_PER_FRAME: u32 = 5;
+
+pub type CommandReceiver = crate::command_lanes::LaneReceiver;
+
+/// Fixed-timestep simulation fed by real elapsed time
+///
+/// `run` drives it from a timer; tests call `advance` directly to step ticks deterministically.
+pub struct GameLoop {
+    game_state: Arc<RwLock<GameState>>,
+    command_rx: CommandReceiver,
+    game_tx: broadcast::Sender<GameUpdate>,
+    match_history: Arc<RwLock<MatchHistory>>,
+    cosmetics: Arc<RwLock<CosmeticStore>>,
+    clock: SharedClock,
+    /// Server-controlled players whose commands are queued each frame
+    bots: Option<crate::bots::Bots>,
+    /// Where anti-cheat reports go for admin review
+    moderator: Option<crate::moderation::Moderator>,
+    /// In distributed mode, only the leader simulates; followers pass their commands to it
+    backplane: crate::backplane::Backplane,
+    /// Times sampled commands; traces wait in `applied_traces` for the next state broadcast
+    latency: Option<crate::latency::LatencyTracer>,
+    applied_traces: Vec<crate::latency::AppliedTrace>,
+    /// Records matches and their command logs when a replay directory is configured
+    recorder: Option<ReplayRecorder>,
+    replay_dir: Option<std::path::PathBuf>,
+    fixed_timestep: f32,
+    broadcast_interval: f32,
+    /// Geometry version most recently broadcast to clients
+    geometry_version: u64,
+    /// Real time not yet consumed by fixed physics steps
+    accumulator: f32,
+    /// Real time since the last state broadcast
+    since_broadcast: f32,
+}
+
+impl GameLoop {
+    pub fn new(
+        game_state: Arc<RwLock<GameState>>,
+        command_rx: CommandReceiver,
+        game_tx: broadcast::Sender<GameUpdate>,
+        match_history: Arc<RwLock<MatchHistory>>,
+        cosmetics: Arc<RwLock<CosmeticStore>>,
+        game_config: &GameConfig,
+        clock: SharedClock,
+    ) -> Self {
+        // Simulation and network send rates are tuned independently
+        Self {
+            game_state,
+            command_rx,
+            game_tx,
+            match_history,
+            cosmetics,
+            clock,
+            bots: None,
+            moderator: None,
+            backplane: crate::backplane::Backplane::default(),
+            latency: None,
+            applied_traces: Vec::new(),
+            recorder: game_config.replays.dir.as_ref().map(|_| {
+                let replays = &game_config.replays;
+                ReplayRecorder::new(replays.sample_hz, replays.snapshot_every_ticks, game_config.tick_rate_hz)
+            }),
+            replay_dir: game_config.replays.dir.as_ref().map(Into::into),
+            fixed_timestep: 1.0 / game_config.tick_rate_hz.max(1.0),
+            broadcast_interval: 1.0 / game_config.broadcast_rate_hz.max(1.0),
+            geometry_version: 0,
+            accumulator: 0.0,
+            since_broadcast: 0.0,
+        }
+    }
+
+    /// Drive these bots from the loop, so their commands arrive like any client's
+    pub fn with_bots(mut self, bots: crate::bots::Bots) -> Self {
+        self.bots = Some(bots);
+        self
+    }
+
+    /// Queue anti-cheat reports as moderation flags
+    pub fn with_moderator(mut self, moderator: crate::moderation::Moderator) -> Self {
+        self.moderator = Some(moderator);
+        self
+    }
+
+    /// Leave the simulation to the elected leader whenever this instance follows it
+    pub fn with_backplane(mut self, backplane: crate::backplane::Backplane) -> Self {
+        self.backplane = backplane;
+        self
+    }
+
+    /// Finish latency traces of the commands each state broadcast first reflects
+    pub fn with_latency_tracer(mut self, latency: crate::latency::LatencyTracer) -> Self {
+        self.latency = Some(latency);
+        self
+    }
+
+    /// Seconds per physics tick
+    pub fn fixed_timestep(&self) -> f32 {
+        self.fixed_timestep
+    }
+
+    /// Apply queued commands, run the physics steps `elapsed` seconds pay for and
+    /// broadcast the resulting events and state
+    pub async fn advance(&mut self, elapsed: f32) {
+        // The world on a follower is a mirror of the leader's, updated as its broadcasts arrive
+        if self.backplane.is_follower() {
+            for command in self.command_rx.drain() {
+                self.backplane.forward(command);
+            }
+            self.accumulator = 0.0;
+            self.since_broadcast = 0.0;
+            return;
+        }
+
+        self.accumulator += elapsed;
+        self.since_broadcast += elapsed;
+        // Cap catch-up work so a long stall doesn't spiral into ever-longer frames
+        self.accumulator = self.accumulator.min(self.fixed_timestep * MAX_STEPS_PER_FRAME as f32);
+
+        if let Some(bots) = &self.bots {
+            bots.think(&*self.game_state.read().await, elapsed);
+        }
+
+        let commands = self.command_rx.drain();
+        if !commands.is_empty() {
+            let mut game_state = self.game_state.write().await;
+            for command in commands {
+                match command {
+                    QueuedCommand::Player { player_id, command, seq, trace } => {
+                        let outcome = game_state.apply_command(&player_id, &command, seq);
+                        // Ignored commands changed nothing, so the match log leaves them out
+                        if let Some(recorder) = &mut self.recorder {
+                            if !matches!(outcome, game_core::CommandOutcome::Ignored { .. }) {
+                                recorder.record_command(&game_state, &player_id, &command);
+                            }
+                        }
+                        if let (Some(latency), Some(trace)) = (&self.latency, trace) {
+                            self.applied_traces.push(latency.applied(trace, player_id, game_state.tick));
+                        }
+                    }
+                    QueuedCommand::Cosmetics { player_id, equipped } => {
+                        if let Some(player) = game_state.players.get_mut(&player_id) {
+                            player.cosmetics = equipped;
+                        }
+                    }
+                    QueuedCommand::Join { player_id } => {
+                        let known = game_state.players.contains_key(&player_id);
+                        if game_state.join(player_id) == game_core::Admission::Playing && !known {
+                            let name = game_state.players[&player_id].name.clone();
+                            let _ = self.game_tx.send(GameUpdate::Notice(crate::i18n::Text::PlayerJoined { name }));
+                        }
+                    }
+                    QueuedCommand::Leave { player_id } => {
+                        let player_name = game_state.players.get(&player_id).map(|p| p.name.clone());
+                        game_state.remove_player(&player_id);
+                        if let Some(player_name) = player_name {
+                            eprintln!("👋 Player left: {} ({})", player_name, player_id);
+                            let _ = self.game_tx.send(GameUpdate::PlayerLeft { player_id, player_name });
+                        }
+                    }
+                }
+            }
+        }
+
+        if self.accumulator < self.fixed_timestep {
+            return;
+        }
+
+        let (
+            geometry_sync,
+            combo_breaks,
+            challenge_completions,
+            damage_events,
+            bounce_events,
+            teleport_events,
+            mode_statuses,
+            life_events,
+            match_transitions,
+            finished_matches,
+            map_changes,
+            mode_changes,
+            mutator_change,
+            map_edit,
+            world_event_changes,
+            kill_cams,
+            config_change,
+            cheat_reports,
+        ) = {
+            let mut game_state = self.game_state.write().await;
+            // Handed the simulation over while waiting for the lock
+            if self.backplane.is_follower() {
+                self.accumulator = 0.0;
+                return;
+            }
+            while self.accumulator >= self.fixed_timestep {
+                game_state.update(self.fixed_timestep);
+                self.accumulator -= self.fixed_timestep;
+            }
+            game_state.mark_afk();
+            // Written on a background task so the tick never waits on the disk
+            game_state.save_housing();
+            if let Some(recorder) = &mut self.recorder {
+                recorder.observe(&game_state);
+            }
+            let geometry_sync = if game_state.blocks.version() != self.geometry_version {
+                Some(game_state.blocks.sync_since(self.geometry_version))
+            } else {
+                None
+            };
+            (
+                geometry_sync,
+                game_state.drain_combo_breaks(),
+                game_state.drain_challenge_completions(),
+                game_state.drain_damage_events(),
+                game_state.drain_bounce_events(),
+                game_state.drain_teleport_events(),
+                game_state.drain_mode_statuses(),
+                game_state.drain_life_events(),
+                game_state.drain_match_transitions(),
+                game_state.drain_finished_matches(),
+                game_state.drain_map_changes(),
+                game_state.drain_mode_changes(),
+                game_state.take_mutator_change(),
+                game_state.take_map_edit(),
+                game_state.drain_world_event_changes(),
+                game_state.drain_kill_cams(),
+                game_state.take_config_change(),
+                game_state.drain_cheat_reports(),
+            )
+        };
+
+        // Broadcast geometry deltas only when building commands changed the world
+        if let Some(sync) = geometry_sync {
+            self.geometry_version = match &sync {
+                game_core::GeometrySync::Delta(delta) => delta.to_version,
+                game_core::GeometrySync::Snapshot(snapshot) => snapshot.version,
+            };
+            let _ = self.game_tx.send(GameUpdate::GeometryChanged(sync));
+        }
+
+        if !combo_breaks.is_empty() {
+            let _ = self.game_tx.send(GameUpdate::ComboBroken(combo_breaks));
+        }
+
+        if !challenge_completions.is_empty() {
+            self.reward_cosmetics(&challenge_completions).await;
+            let _ = self.game_tx.send(GameUpdate::ChallengesCompleted(challenge_completions));
+        }
+
+        if !damage_events.is_empty() {
+            let _ = self.game_tx.send(GameUpdate::Damage(damage_events));
+        }
+
+        if !bounce_events.is_empty() {
+            let _ = self.game_tx.send(GameUpdate::Bounces(bounce_events));
+        }
+
+        if !teleport_events.is_empty() {
+            let _ = self.game_tx.send(GameUpdate::Teleports(teleport_events));
+        }
+
+        for status in mode_statuses {
+            let _ = self.game_tx.send(GameUpdate::ModeStatus(status));
+        }
+
+        if !life_events.is_empty() {
+            let _ = self.game_tx.send(GameUpdate::LifeEvents(life_events));
+        }
+
+        for kill_cam in kill_cams {
+            let _ = self.game_tx.send(GameUpdate::KillCam(Box::new(kill_cam)));
+        }
+
+        if let Some(moderator) = &self.moderator {
+            for report in cheat_reports {
+                moderator.flag_cheat(report).await;
+            }
+        }
+
+        for record in finished_matches {
+            eprintln!("🏁 Match {} finished with {} player(s)", record.id, record.participants.len());
+            let recorded = self.recorder.as_mut().and_then(|recorder| recorder.finish(record.id, &record.mode));
+            if let (Some(recorded), Some(dir)) = (recorded, self.replay_dir.clone()) {
+                // Written off the tick, like housing; the replay list picks them up once on disk
+                tokio::spawn(async move {
+                    let (replay, match_log) = (recorded.replay, recorded.log);
+                    match replay.save(&dir).await {
+                        Ok(()) => eprintln!("📼 Saved replay of match {} ({} frames)", replay.id, replay.frames.len()),
+                        Err(e) => eprintln!("❌ Failed to save replay of match {}: {}", replay.id, e),
+                    }
+                    match match_log.save(&dir).await {
+                        Ok(()) => eprintln!("📼 Saved match log of {} ({} records)", replay.id, match_log.records.len()),
+                        Err(e) => eprintln!("❌ Failed to save match log of {}: {}", replay.id, e),
+                    }
+                });
+            }
+            self.match_history.write().await.record_in_background(record);
+        }
+
+        // A new mode or map goes out before the lobby phase that follows it
+        for mode in mode_changes {
+            eprintln!("🎮 Switched to game mode {}", mode.id);
+            let notice = crate::i18n::Text::ModeChanged { mode: mode.name.clone() };
+            let _ = self.game_tx.send(GameUpdate::ModeChanged(mode));
+            let _ = self.game_tx.send(GameUpdate::Notice(notice));
+        }
+
+        if let Some(mode) = mutator_change {
+            let names: Vec<String> = mode.mutators.iter().map(|m| m.label().to_string()).collect();
+            eprintln!("🧪 Mutators in effect: {:?}", names);
+            let _ = self.game_tx.send(GameUpdate::ModeChanged(mode));
+            if !names.is_empty() {
+                let _ = self.game_tx.send(GameUpdate::Notice(crate::i18n::Text::MutatorsActive { mutators: names }));
+            }
+        }
+
+        for change in map_changes {
+            eprintln!("🗺️ Switched to map {}", change.id);
+            let notice = crate::i18n::Text::NextMap { map: change.name.clone() };
+            let _ = self.game_tx.send(GameUpdate::MapChanged(change));
+            let _ = self.game_tx.send(GameUpdate::Notice(notice));
+        }
+
+        for change in world_event_changes {
+            let notice = match &change {
+                game_core::WorldEventChange::Started(event) => crate::i18n::Text::WorldEventStarted { kind: event.kind },
+                game_core::WorldEventChange::Ended { kind } => crate::i18n::Text::WorldEventEnded { kind: *kind },
+            };
+            let _ = self.game_tx.send(GameUpdate::WorldEvent(change));
+            let _ = self.game_tx.send(GameUpdate::Notice(notice));
+        }
+
+        if let Some(geometry) = map_edit {
+            let _ = self.game_tx.send(GameUpdate::MapEdited(geometry));
+        }
+
+        if let Some(version) = config_change {
+            let _ = self.game_tx.send(GameUpdate::ConfigChanged(version));
+        }
+
+        for state in match_transitions {
+            let summary = match &state {
+                MatchState::Ended { result, .. } => {
+                    let world = self.game_state.read().await.world.clone();
+                    Some(crate::i18n::match_summary(result, &world.config().teams))
+                }
+                _ => None,
+            };
+            let _ = self.game_tx.send(GameUpdate::MatchPhase(state));
+            if let Some(summary) = summary {
+                let _ = self.game_tx.send(GameUpdate::Notice(summary));
+            }
+        }
+
+        // Events above go out every tick; full state only at the broadcast rate
+        if self.since_broadcast < self.broadcast_interval {
+            return;
+        }
+        self.since_broadcast = self.since_broadcast.min(self.broadcast_interval * 2.0) - self.broadcast_interval;
+
+        let state = self.game_state.read().await.clone();
+        let tick = state.tick;
+        let _ = self.game_tx.send(GameUpdate::StateUpdate {
+            state: Box::new(state),
+            server_time_ms: self.clock.unix_millis(),
+        });
+        if let Some(latency) = &self.latency {
+            latency.broadcast(std::mem::take(&mut self.applied_traces), tick);
+        }
+    }
+
+    /// Pay challenge rewards out as coins and unlock the cosmetics tied to the challenges
+    async fn reward_cosmetics(&self, completions: &[game_core::ChallengeCompletion]) {
+        let world = self.game_state.read().await.world.clone();
+        let catalog = &world.config().cosmetics.items;
+        let mut cosmetics = self.cosmetics.write().await;
+        for completion in completions {
+            let unlocked = cosmetics.reward_challenge(
+                completion.player_id,
+                &completion.challenge_id,
+                completion.reward_points,
+                catalog,
+            );
+            for item_id in unlocked {
+                eprintln!("🎁 Player {} unlocked cosmetic {}", completion.player_id, item_id);
+            }
+        }
+        cosmetics.save_in_background();
+    }
+
+    /// Run forever at the configured tick rate
+    pub async fn run(mut self) {
+        let mut interval = tokio::time::interval(Duration::from_secs_f32(self.fixed_timestep));
+        let mut last_frame = self.clock.instant();
+        loop {
+            interval.tick().await;
+
+            // Measure real elapsed time so slow iterations don't cause time dilation
+            let now = self.clock.instant();
+            let elapsed = now.duration_since(last_frame).as_secs_f32();
+            last_frame = now;
+            self.advance(elapsed).await;
+        }
+    }
+}
+
+/// Remove players idle for longer than `timeout` by the clock, or whose session stream
+/// dropped longer ago than the reconnect grace period, broadcasting their departure
+/// Players with an open session stream are present even when idle
+/// Spectators waiting for a slot leave the queue under the same rules, without a broadcast
+/// Returns how many players were removed
+pub async fn remove_inactive_players(
+    game_state: &RwLock<GameState>,
+    game_tx: &broadcast::Sender<GameUpdate>,
+    sessions: &SessionStore,
+    timeout: Duration,
+) -> usize {
+    let mut players_to_remove = Vec::new();
+    let waiting_to_remove: Vec<uuid::Uuid>;
+
+    // Check all players for inactivity
+    {
+        let game_state_guard = game_state.read().await;
+        let now = game_state_guard.clock.now();
+        for (player_id, player) in game_state_guard.players.iter().filter(|(_, p)| !p.bot) {
+            let idle = now.duration_since(player.last_activity).unwrap_or_default();
+            if idle > timeout && !sessions.is_connected(player_id) {
+                eprintln!("⏰ [TIMEOUT] Player timed out due to inactivity: {} ({}) - inactive for {} seconds (timeout: {}s)",
+                    player_id, player.name, idle.as_secs(), timeout.as_secs());
+            } else if sessions.grace_expired(player_id, now) {
+                eprintln!("🔌 [TIMEOUT] Player did not reconnect within the grace period: {} ({})",
+                    player_id, player.name);
+            } else {
+                continue;
+            }
+            players_to_remove.push((*player_id, player.name.clone()));
+        }
+        let idle_waiting = game_state_guard.waiting.idle(now, timeout);
+        waiting_to_remove = game_state_guard
+            .waiting
+            .ids()
+            .filter(|id| {
+                (idle_waiting.contains(id) && !sessions.is_connected(id)) || sessions.grace_expired(id, now)
+            })
+            .copied()
+            .collect();
+    }
+
+    if !waiting_to_remove.is_empty() {
+        let mut game_state_guard = game_state.write().await;
+        for player_id in waiting_to_remove {
+            eprintln!("⏰ [TIMEOUT] Spectator left the waiting queue: {}", player_id);
+            game_state_guard.waiting.remove(&player_id);
+            sessions.forget(&player_id);
+        }
+    }
+
+    // Remove inactive players and broadcast
+    let removed = players_to_remove.len();
+    if !players_to_remove.is_empty() {
+        let mut game_state_guard = game_state.write().await;
+        for (player_id, player_name) in players_to_remove {
+            game_state_guard.remove_player(&player_id);
+            sessions.forget(&player_id);
+            let _ = game_tx.send(GameUpdate::PlayerLeft {
+                player_id,
+                player_name,
+            });
+        }
+    }
+    removed
+}
+
+/// Cleanup task that removes inactive players (configurable timeout)
+/// Followers in distributed mode leave this to the leader
+pub async fn cleanup_inactive_players(
+    game_state: Arc<RwLock<GameState>>,
+    game_tx: broadcast::Sender<GameUpdate>,
+    sessions: SessionStore,
+    game_config: Arc<GameConfig>,
+    backplane: crate::backplane::Backplane,
+) {
+    let mut interval = tokio::time::interval(Duration::from_secs(game_config.presence.cleanup_interval_secs.max(1)));
+    let timeout = Duration::from_secs(game_config.idle_timeout);
+
+    loop {
+        interval.tick().await;
+        if backplane.is_follower() {
+            continue;
+        }
+        remove_inactive_players(&game_state, &game_tx, &sessions, timeout).await;
+    }
+}
@@ -0,0 +1,315 @@
+use axum::extract::{Request, State};
+use axum::http::StatusCode;
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Json, Response};
+use serde::Deserialize;
+use serde_json::json;
+use crate::api_tokens::TokenScope;
+use crate::command_lanes::{CommandLane, QueuedCommand};
+use crate::error::ApiError;
+use crate::state::AppState;
+use crate::GameUpdate;
+
+/// Compare tokens without short-circuiting on the first differing byte
+fn tokens_match(given: &str, expected: &str) -> bool {
+    given.len() == expected.len()
+        && given
+            .bytes()
+            .zip(expected.bytes())
+            .fold(0u8, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
+/// The scope a bearer token grants: full admin for the configured admin token,
+/// otherwise whatever an issued API token was created with
+fn token_scope(app_state: &AppState, token: &str) -> Option<TokenScope> {
+    if app_state.admin_token.as_deref().is_some_and(|expected| tokens_match(token, expected)) {
+        return Some(TokenScope::Admin);
+    }
+    app_state.api_tokens.scope_for(token)
+}
+
+/// The token in an `Authorization: Bearer <token>` header
+pub(crate) fn bearer_token(headers: &axum::http::HeaderMap) -> Option<&str> {
+    headers
+        .get(axum::http::header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+}
+
+/// Whether the request carries the admin token or an admin-scoped API token
+pub(crate) fn is_admin(app_state: &AppState, headers: &axum::http::HeaderMap) -> bool {
+    bearer_token(headers)
+        .and_then(|token| token_scope(app_state, token))
+        .is_some_and(|scope| scope.allows(TokenScope::Admin))
+}
+
+/// Let the request through if its `Authorization: Bearer <token>` grants `required`
+/// Missing or unknown tokens get 401, tokens with too narrow a scope 403
+async fn require_scope(app_state: &AppState, required: TokenScope, request: Request, next: Next) -> Response {
+    let scope = bearer_token(request.headers()).and_then(|token| token_scope(app_state, token));
+    match scope {
+        None => {
+            eprintln!("🚫 Rejected unauthorized admin request: {} {}", request.method(), request.uri());
+            ApiError::Unauthorized.into_response()
+        }
+        Some(scope) if !scope.allows(required) => {
+            eprintln!("🚫 Rejected {:?} token for {:?} request: {} {}", scope, required, request.method(), request.uri());
+            ApiError::InsufficientScope { scope, required }.into_response()
+        }
+        Some(_) => next.run(request).await,
+    }
+}
+
+/// Auth middleware for read-only admin routes: any issued token or the admin token
+pub async fn require_stats(State(app_state): State<AppState>, request: Request, next: Next) -> Response {
+    require_scope(&app_state, TokenScope::Stats, request, next).await
+}
+
+/// Auth middleware for moderation routes: moderation or admin tokens
+pub async fn require_moderation(State(app_state): State<AppState>, request: Request, next: Next) -> Response {
+    require_scope(&app_state, TokenScope::Moderation, request, next).await
+}
+
+/// Auth middleware for everything else under /api/admin: admin-scoped tokens only
+/// With no admin token configured nobody can issue tokens, so every admin route is disabled
+pub async fn require_admin(State(app_state): State<AppState>, request: Request, next: Next) -> Response {
+    require_scope(&app_state, TokenScope::Admin, request, next).await
+}
+
+#[derive(Deserialize)]
+pub struct CreateTokenRequest {
+    pub name: String,
+    pub scope: TokenScope,
+}
+
+/// Issue a scoped API token; the secret is only returned here
+pub async fn create_token(
+    State(app_state): State<AppState>,
+    Json(request): Json<CreateTokenRequest>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    let name = request.name.trim().to_string();
+    if name.is_empty() {
+        return Err(ApiError::BadRequest("token name is empty".to_string()));
+    }
+    let (info, token) = app_state.api_tokens.create(name, request.scope);
+    eprintln!("🔑 [ADMIN] Issued {:?} token {} ({})", info.scope, info.name, info.id);
+    Ok(Json(json!({
+        "id": info.id,
+        "name": info.name,
+        "scope": info.scope,
+        "created_at": info.created_at,
+        "token": token,
+    })))
+}
+
+/// Every issued API token, without secrets
+pub async fn list_tokens(State(app_state): State<AppState>) -> impl IntoResponse {
+    Json(json!({ "tokens": app_state.api_tokens.list() }))
+}
+
+/// Revoke an API token; it stops working immediately
+pub async fn revoke_token(
+    State(app_state): State<AppState>,
+    axum::extract::Path(id): axum::extract::Path<uuid::Uuid>,
+) -> Result<StatusCode, ApiError> {
+    if !app_state.api_tokens.revoke(id) {
+        return Err(ApiError::NotFound("no such token".to_string()));
+    }
+    eprintln!("🔑 [ADMIN] Revoked token {}", id);
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[derive(Deserialize)]
+pub struct PlayerRequest {
+    pub player_id: uuid::Uuid,
+}
+
+#[derive(Deserialize)]
+pub struct BanRequest {
+    pub ip: std::net::IpAddr,
+}
+
+#[derive(Deserialize)]
+pub struct MuteRequest {
+    pub player_id: uuid::Uuid,
+    /// Mute duration in seconds
+    #[serde(default = "default_mute_secs")]
+    pub duration_secs: u64,
+}
+
+fn default_mute_secs() -> u64 {
+    300
+}
+
+#[derive(Deserialize)]
+pub struct CommandRequest {
+    pub player_id: uuid::Uuid,
+    pub command: game_core::PlayerCommand,
+}
+
+#[derive(Deserialize)]
+pub struct AnnounceRequest {
+    pub text: String,
+    #[serde(default)]
+    pub severity: game_core::config::AnnouncementSeverity,
+    /// Seconds the banner stays up; 0 keeps it until the next announcement
+    #[serde(default = "game_core::config::default_announcement_duration_secs")]
+    pub duration_secs: u64,
+}
+
+/// Per-IP connection and player counts
+pub async fn ip_usage(State(app_state): State<AppState>) -> impl IntoResponse {
+    Json(app_state.ip_limiter.usage())
+}
+
+/// Depth, capacity and dropped-command count of each command lane
+pub async fn command_lanes(State(app_state): State<AppState>) -> impl IntoResponse {
+    Json(json!({ "lanes": app_state.commands.stats() }))
+}
+
+/// Event streams that fell behind the broadcast since startup
+pub async fn stream_lag(State(app_state): State<AppState>) -> impl IntoResponse {
+    Json(app_state.lag_metrics.stats())
+}
+
+/// Recently traced commands, oldest first, with request-to-broadcast percentiles
+pub async fn latency_traces(State(app_state): State<AppState>) -> impl IntoResponse {
+    Json(json!({
+        "summary": app_state.latency.summary(),
+        "recent": app_state.latency.recent(),
+    }))
+}
+
+/// Content flagged by the moderation provider, oldest first
+pub async fn moderation_flags(State(app_state): State<AppState>) -> impl IntoResponse {
+    Json(app_state.moderator.state.read().await.flags.clone())
+}
+
+/// Remove a player from the game; they may rejoin
+pub async fn kick_player(
+    State(app_state): State<AppState>,
+    Json(request): Json<PlayerRequest>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    let player_name = {
+        let mut game_state = app_state.game_state.write().await;
+        let Some(player) = game_state.players.get(&request.player_id) else {
+            return Err(ApiError::UnknownPlayer);
+        };
+        let name = player.name.clone();
+        game_state.remove_player(&request.player_id);
+        name
+    };
+
+    eprintln!("👢 [ADMIN] Kicked player {} ({})", player_name, request.player_id);
+    let _ = app_state.game_tx.send(GameUpdate::PlayerLeft {
+        player_id: request.player_id,
+        player_name: player_name.clone(),
+    });
+    Ok(Json(json!({ "kicked": request.player_id, "player_name": player_name })))
+}
+
+/// Refuse an IP's new players and streams until the server restarts, and remove the
+/// players it already created
+pub async fn ban_ip(
+    State(app_state): State<AppState>,
+    Json(request): Json<BanRequest>,
+) -> Json<serde_json::Value> {
+    let player_ids = app_state.ip_limiter.ban(request.ip);
+    let mut kicked = Vec::new();
+    {
+        let mut game_state = app_state.game_state.write().await;
+        for player_id in player_ids {
+            let Some(player_name) = game_state.players.get(&player_id).map(|p| p.name.clone()) else {
+                continue;
+            };
+            game_state.remove_player(&player_id);
+            let _ = app_state.game_tx.send(GameUpdate::PlayerLeft { player_id, player_name });
+            kicked.push(player_id);
+        }
+    }
+    eprintln!("🚫 [ADMIN] Banned {} and kicked {} player(s)", request.ip, kicked.len());
+    Json(json!({ "banned": request.ip, "kicked": kicked }))
+}
+
+/// Stop taking new players ahead of a restart; players already here keep playing and may
+/// reconnect, and the health check reports the server as draining
+pub async fn drain(State(app_state): State<AppState>) -> Json<serde_json::Value> {
+    if !app_state.draining.swap(true, std::sync::atomic::Ordering::SeqCst) {
+        eprintln!("🚰 [ADMIN] Draining: no new players until restart");
+    }
+    let players = app_state.game_state.read().await.players.len();
+    Json(json!({ "draining": true, "players": players }))
+}
+
+/// Apply a command to a player ahead of their own input, e.g. to stop a griefer mid-run
+pub async fn command_player(
+    State(app_state): State<AppState>,
+    Json(request): Json<CommandRequest>,
+) -> Result<StatusCode, ApiError> {
+    if !app_state.game_state.read().await.players.contains_key(&request.player_id) {
+        return Err(ApiError::UnknownPlayer);
+    }
+    eprintln!("🕹️ [ADMIN] Sending {:?} to {}", request.command, request.player_id);
+    let command = QueuedCommand::Player {
+        player_id: request.player_id,
+        command: request.command,
+        seq: 0,
+        trace: None,
+    };
+    if !app_state.commands.push(CommandLane::Admin, command).await {
+        return Err(ApiError::QueueFull(CommandLane::Admin));
+    }
+    Ok(StatusCode::ACCEPTED)
+}
+
+/// Mute a player's chat for a duration
+pub async fn mute_player(
+    State(app_state): State<AppState>,
+    Json(request): Json<MuteRequest>,
+) -> impl IntoResponse {
+    let duration = std::time::Duration::from_secs(request.duration_secs);
+    app_state.moderator.mute(request.player_id, duration).await;
+    eprintln!("🔇 [ADMIN] Muted {} for {}s", request.player_id, request.duration_secs);
+    Json(json!({ "muted": request.player_id, "duration_secs": request.duration_secs }))
+}
+
+/// Broadcast a server announcement as a banner on every client, echoed to chat
+pub async fn announce(
+    State(app_state): State<AppState>,
+    Json(request): Json<AnnounceRequest>,
+) -> Result<StatusCode, ApiError> {
+    let text = request.text.trim().to_string();
+    if text.is_empty() {
+        return Err(ApiError::BadRequest("announcement text is empty".to_string()));
+    }
+
+    eprintln!("📢 [ADMIN] Announcement ({:?}, {}s): \"{}\"", request.severity, request.duration_secs, text);
+    let duration = (request.duration_secs > 0).then(|| std::time::Duration::from_secs(request.duration_secs));
+    crate::announcements::announce(&app_state, &text, request.severity, duration);
+    let message = game_core::ChatMessage {
+        player_id: uuid::Uuid::nil(),
+        player_name: "Server".to_string(),
+        player_color: "#FFD700".to_string(),
+        text,
+        timestamp: app_state.clock.unix_secs(),
+        team: None,
+    };
+    crate::handlers::chat::broadcast_chat(&app_state, message).await;
+    Ok(StatusCode::OK)
+}
+
+/// Dump the current game state as JSON for debugging
+pub async fn dump_state(State(app_state): State<AppState>) -> impl IntoResponse {
+    let game_state = app_state.game_state.read().await;
+    Json(json!({
+        "tick": game_state.tick,
+        "players": game_state.players,
+        "waiting": game_state.waiting.ids().collect::<Vec<_>>(),
+        "blocks": game_state.blocks.snapshot(),
+        "challenges": {
+            "day": game_state.challenges.day(),
+            "active": game_state.challenges.challenges(),
+        },
+    }))
+}
@@ -0,0 +1,31 @@
+use axum::extract::{Path, State};
+use axum::http::{header, HeaderValue};
+use axum::response::{IntoResponse, Json, Response};
+use serde_json::json;
+use crate::error::ApiError;
+use crate::state::AppState;
+
+/// Which instance hosts a room, with the cookie that routes clients there
+/// Standalone servers answer for every room themselves
+pub async fn lookup(
+    State(app_state): State<AppState>,
+    Path(room): Path<String>,
+) -> Result<Response, ApiError> {
+    let affinity = &app_state.affinity;
+    if affinity.local().is_none() {
+        return Ok(Json(json!({ "room": room, "instance_id": null, "url": null, "local": true })).into_response());
+    }
+    let owner = affinity.owner(&room).ok_or(ApiError::NotFound("no such room".to_string()))?;
+    let local = affinity.local().is_some_and(|local| local.id == owner.id);
+    let mut response = Json(json!({
+        "room": room,
+        "instance_id": owner.id,
+        "url": owner.url,
+        "local": local,
+    }))
+    .into_response();
+    if let Ok(cookie) = HeaderValue::from_str(&affinity.cookie(owner)) {
+        response.headers_mut().insert(header::SET_COOKIE, cookie);
+    }
+    Ok(response)
+}
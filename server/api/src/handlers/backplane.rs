@@ -0,0 +1,22 @@
+use axum::extract::State;
+use axum::response::{IntoResponse, Json, Response};
+use serde_json::json;
+use crate::backplane::BackplaneStatus;
+use crate::error::ApiError;
+use crate::state::AppState;
+
+/// This instance's role in distributed mode, for load balancer checks and debugging
+pub async fn status(State(app_state): State<AppState>) -> Json<BackplaneStatus> {
+    Json(app_state.backplane.status())
+}
+
+/// Promote this instance to run the simulation, taking over from the current leader
+pub async fn promote(State(app_state): State<AppState>) -> Response {
+    match app_state.backplane.promote().await {
+        Ok(()) => {
+            let tick = app_state.game_state.read().await.tick;
+            Json(json!({ "status": app_state.backplane.status(), "resumed_at_tick": tick })).into_response()
+        }
+        Err(error) => ApiError::Conflict(error).into_response(),
+    }
+}
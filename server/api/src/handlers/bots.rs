@@ -0,0 +1,43 @@
+use axum::extract::State;
+use axum::response::IntoResponse;
+use axum::Json;
+use serde::Deserialize;
+use serde_json::json;
+use game_core::BehaviorKind;
+use crate::state::AppState;
+use crate::GameUpdate;
+
+#[derive(Deserialize)]
+pub struct BotsRequest {
+    /// Bots wanted in the game
+    pub count: usize,
+    /// Behavior for any bots added; defaults to cycling through the configured ones
+    #[serde(default)]
+    pub behavior: Option<BehaviorKind>,
+}
+
+/// The bots currently playing
+pub async fn list_bots(State(app_state): State<AppState>) -> impl IntoResponse {
+    let bots = app_state.bots.list();
+    Json(json!({ "count": bots.len(), "bots": bots }))
+}
+
+/// Add or remove bots to reach the requested count, as far as the room has space
+pub async fn set_bots(
+    State(app_state): State<AppState>,
+    Json(request): Json<BotsRequest>,
+) -> impl IntoResponse {
+    let removed = {
+        let mut game_state = app_state.game_state.write().await;
+        app_state.bots.set_count(&mut game_state, request.count, request.behavior)
+    };
+    for bot in removed {
+        let _ = app_state.game_tx.send(GameUpdate::PlayerLeft {
+            player_id: bot.id,
+            player_name: bot.name,
+        });
+    }
+    let bots = app_state.bots.list();
+    eprintln!("🤖 [ADMIN] Bot count set to {} (requested {})", bots.len(), request.count);
+    Json(json!({ "count": bots.len(), "bots": bots }))
+}
@@ -0,0 +1,27 @@
+use axum::extract::{Query, State};
+use axum::response::Json;
+use serde::Deserialize;
+use serde_json::json;
+use crate::state::AppState;
+
+#[derive(Deserialize)]
+pub struct ChallengesQuery {
+    /// Include this player's progress on each challenge
+    pub player_id: Option<uuid::Uuid>,
+}
+
+/// Today's rotating challenges, optionally with a player's progress
+pub async fn get_today(
+    State(app_state): State<AppState>,
+    Query(query): Query<ChallengesQuery>,
+) -> impl axum::response::IntoResponse {
+    let game_state = app_state.game_state.read().await;
+    let tracker = &game_state.challenges;
+    let progress = query.player_id.map(|player_id| tracker.progress_for(&player_id));
+
+    Json(json!({
+        "day": tracker.day(),
+        "challenges": tracker.challenges(),
+        "progress": progress,
+    }))
+}
@@ -1,60 +1,138 @@
-use axum::extract::State;
+use axum::extract::{ConnectInfo, State};
+use axum::http::HeaderMap;
 use axum::response::IntoResponse;
+use axum::response::sse::Sse;
+use datastar::consts::ElementPatchMode;
 use serde::Deserialize;
+use std::convert::Infallible;
+use std::net::SocketAddr;
+use crate::chat_commands::ChatCommand;
+use crate::error::ApiError;
+use crate::i18n::{translate, Text};
 use crate::state::AppState;
 
 #[derive(Deserialize)]
 pub struct ChatRequest {
     pub player_id: uuid::Uuid,
     pub text: String,
+    /// Send to the player's team only
+    #[serde(default)]
+    pub team: bool,
+    /// Token from init_player; it must belong to player_id
+    #[serde(default)]
+    pub session_token: Option<String>,
+}
+
+/// Record a message in the chat history and send it to every client, on every instance
+pub async fn broadcast_chat(app_state: &AppState, message: game_core::ChatMessage) {
+    app_state.backplane.publish_chat(&message);
+    relay_chat(app_state, message).await;
+}
+
+/// Record a message in the chat history and send it to this instance's clients
+pub async fn relay_chat(app_state: &AppState, message: game_core::ChatMessage) {
+    // Keep a bounded history so late joiners can see recent conversation
+    {
+        let mut history = app_state.chat_history.write().await;
+        history.push_back(message.clone());
+        while history.len() > app_state.game_config.chat_history_size {
+            history.pop_front();
+        }
+    }
+
+    // Idempotent: Broadcasting the same message multiple times is safe
+    // (Datastar best practice for network resilience)
+    if let Err(e) = app_state.chat_tx.send(message) {
+        eprintln!("❌ Failed to broadcast chat message: {:?}", e);
+    }
+}
+
+/// Answer only the sender, as a Datastar SSE response instead of a broadcast
+fn reply_to_sender(html: String) -> axum::response::Response {
+    let event = crate::handlers::events::elements_event(html, "#chat-messages", ElementPatchMode::Append);
+    let stream = futures::stream::once(async move { Ok::<_, Infallible>(event) });
+    Sse::new(stream).into_response()
 }
 
 // Datastar best practice: Idempotent message handling
 // Processing the same message multiple times is safe (network resilience)
+// Refused messages get an error whose message is in the sender's language
 pub async fn send_message(
     State(app_state): State<AppState>,
+    ConnectInfo(peer): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
     request: axum::extract::Json<ChatRequest>,
-) -> impl IntoResponse {
-    // Look up player name and generate color, update activity timestamp
-    let (player_name, player_color) = {
+) -> Result<axum::response::Response, ApiError> {
+    crate::handlers::game::check_session(&app_state, request.player_id, request.session_token.as_deref())?;
+    // Replies to the sender are rendered in their language
+    let language = crate::i18n::player_language(&app_state, &request.player_id, &headers).await;
+
+    // Muted players can't chat or run commands
+    if let Some(remaining) = app_state.moderator.mute_remaining(&request.player_id).await {
+        let rejection = crate::moderation::ChatRejection::Muted(remaining);
+        let message = translate(language, &Text::Muted(rejection));
+        return Err(ApiError::Chat { rejection, message });
+    }
+
+    // Slash commands are handled server-side and answered only to the sender,
+    // except /team, which is a message for the sender's team
+    let (text, team_only) = match crate::chat_commands::parse(&request.text) {
+        Some(ChatCommand::Team(text)) if !text.is_empty() => (text, true),
+        Some(command) => {
+            let html = crate::chat_commands::execute(&app_state, request.player_id, command, language).await;
+            return Ok(reply_to_sender(html));
+        }
+        None => (request.text.clone(), request.team),
+    };
+
+    // Filter, length and rate limits; rejections are shown only to the sender
+    let text = match app_state.moderator.check_chat(request.player_id, &text).await {
+        Ok(text) => text,
+        Err(rejection) => {
+            eprintln!("🚫 Rejected chat message from {}: {}", request.player_id, rejection);
+            let message = translate(language, &Text::MessageNotSent(rejection));
+            return Err(ApiError::Chat { rejection, message });
+        }
+    };
+
+    // Look up player name, color and team, update activity timestamp
+    let (player_name, player_color, player_team) = {
         let mut game_state = app_state.game_state.write().await;
         let player = game_state.players.get_mut(&request.player_id);
         
         if let Some(player) = player {
-            // Update activity timestamp when player sends chat
-            player.update_activity();
-            (player.name.clone(), game_core::player_color::get_player_color(&player.id))
+            // Chatting counts as input, so it also clears AFK
+            player.record_input(app_state.clock.now());
+            (player.name.clone(), game_core::player_color::get_player_color(&player.id), player.team.clone())
         } else {
             // Fallback if player not found
-            (format!("Player-{}", &request.player_id.to_string()[..8]), "#FFFFFF".to_string())
+            (format!("Player-{}", &request.player_id.to_string()[..8]), "#FFFFFF".to_string(), None)
         }
     };
+    if team_only && player_team.is_none() {
+        return Err(ApiError::NotOnTeam(translate(language, &Text::NotOnTeam)));
+    }
     
     // Log received message before creating ChatMessage (player_name will be moved)
-    eprintln!("📨 Server received chat message from {} ({}): \"{}\"", player_name, request.player_id, request.text);
+    let ip = app_state.client_ip.resolve(&headers, peer);
+    eprintln!("📨 Server received chat message from {} ({}, {}): \"{}\"", player_name, request.player_id, ip, text);
     
+    // Score asynchronously; the message is delivered now and the sender muted after the fact
+    app_state.moderator.review(request.player_id, crate::moderation::ContentKind::Chat, text.clone());
+
     let message = game_core::ChatMessage {
         player_id: request.player_id,
         player_name,
         player_color,
-        text: request.text.clone(),
-        timestamp: std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .unwrap()
-            .as_secs(),
+        text,
+        timestamp: app_state.clock.unix_secs(),
+        team: if team_only { player_team } else { None },
     };
     
-    // Idempotent: Broadcasting the same message multiple times is safe
-    // (Datastar best practice for network resilience)
-    if let Err(e) = app_state.chat_tx.send(message) {
-        eprintln!("❌ Failed to broadcast chat message: {:?}", e);
-    }
+    broadcast_chat(&app_state, message).await;
     
     // Return empty response - Datastar will update via SSE patches
     // This follows Datastar's server-driven state management pattern
-    axum::response::Response::builder()
-        .status(axum::http::StatusCode::OK)
-        .body(axum::body::Body::empty())
-        .unwrap()
+    Ok(axum::http::StatusCode::OK.into_response())
 }
 
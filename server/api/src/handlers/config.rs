@@ -7,29 +7,61 @@ use serde_json::json;
 pub async fn get_config(
     State(app_state): State<AppState>,
 ) -> impl axum::response::IntoResponse {
+    // Geometry comes from the map being played, which changes as the rotation moves on,
+    // and physics from the room, whose creator may change it
+    let (world, map, config_version) = {
+        let game_state = app_state.game_state.read().await;
+        (game_state.world.clone(), crate::handlers::maps::current_map(&game_state), game_state.config_version())
+    };
+    // Gameplay settings come from the room too, since modes and mutators change them
+    let config = world.config();
+
     // Return the game configuration as JSON
     // This allows clients to fetch platform definitions and physics settings
     Json(json!({
+        "map": map,
+        "config_version": config_version,
+        // Clients pass a protocol_version to /events; majors outside this list are refused
+        "protocol_version": game_core::signals::ProtocolVersion::CURRENT.to_string(),
+        "supported_protocol_majors": game_core::signals::SUPPORTED_MAJORS,
+        "tick_rate_hz": app_state.game_config.tick_rate_hz,
+        "broadcast_rate_hz": app_state.game_config.broadcast_rate_hz,
+        "snapshot_rate_hz": app_state.game_config.snapshot_rate_hz,
+        "heartbeat_interval_secs": app_state.game_config.presence.heartbeat_interval_secs,
         "physics": {
-            "gravity": app_state.game_config.physics.gravity,
-            "jump_velocity": app_state.game_config.physics.jump_velocity,
-            "move_acceleration": app_state.game_config.physics.move_acceleration,
-            "move_deceleration": app_state.game_config.physics.move_deceleration,
-            "max_horizontal_velocity": app_state.game_config.physics.max_horizontal_velocity,
-            "ground_y": app_state.game_config.physics.ground_y,
-            "player_width": app_state.game_config.physics.player_width,
-            "player_height": app_state.game_config.physics.player_height,
-            "ground_color": app_state.game_config.physics.ground_color,
+            "gravity": config.physics.gravity,
+            "jump_velocity": config.physics.jump_velocity,
+            "move_acceleration": config.physics.move_acceleration,
+            "move_deceleration": config.physics.move_deceleration,
+            "max_horizontal_velocity": config.physics.max_horizontal_velocity,
+            "ground_y": config.physics.ground_y,
+            "player_width": config.physics.player_width,
+            "player_height": config.physics.player_height,
+            "ground_color": config.physics.ground_color,
+            "ground_segments": config.physics.ground_segments.iter().map(|g| json!({
+                "x_start": g.x_start,
+                "x_end": g.x_end,
+                "y_top": g.y_top,
+                "color": g.color.as_deref().unwrap_or(&config.physics.ground_color),
+                "surface": g.surface.unwrap_or(config.physics.ground_surface),
+                "restitution": config.physics.ground_restitution_at(g.x_start),
+            })).collect::<Vec<_>>(),
+            "ground_surface": config.physics.ground_surface,
+            // Clients predicting movement need the same grip and bounce as the server
+            "surfaces": config.physics.surfaces,
+            "bounce_min_speed": config.physics.bounce_min_speed,
         },
-        "platforms": app_state.game_config.platforms.iter().map(|p| json!({
+        "platforms": config.platforms.iter().map(|p| json!({
             "id": p.id,
             "x_start": p.x_start,
             "x_end": p.x_end,
             "y_top": p.y_top,
             "height": p.height,
             "color": p.color,
+            "surface": p.surface,
+            "restitution": p.restitution(&config.physics.surfaces),
         })).collect::<Vec<_>>(),
-        "walls": app_state.game_config.walls.iter().map(|w| json!({
+        "walls": config.walls.iter().map(|w| json!({
             "id": w.id,
             "x": w.x,
             "y_bottom": w.y_bottom,
@@ -37,6 +69,28 @@ pub async fn get_config(
             "width": w.width,
             "color": w.color,
         })).collect::<Vec<_>>(),
+        "ladders": config.ladders,
+        "teams": config.teams.teams,
+        "color_blind_palette": app_state.game_config.color_blind_palette,
+        "spawn_points": config.spawn_points,
+        "respawn": config.respawn,
+        "projectiles": config.projectiles,
+        "health": config.health,
+        "hazards": config.hazards,
+        "force_zones": config.force_zones,
+        "portals": config.portals,
+        "checkpoints": config.checkpoints,
+        "race": config.race,
+        "control_zone": config.control_zone,
+        "king_of_the_hill": config.king_of_the_hill,
+        "cosmetics": app_state.game_config.cosmetics.items,
+        "building": {
+            "enabled": config.building.enabled,
+            "block_size": config.building.block_size,
+            "max_blocks_per_player": config.building.max_blocks_per_player,
+            "max_reach": config.building.max_reach,
+            "place_cooldown_ms": config.building.place_cooldown_ms,
+        },
     }))
 }
 
@@ -0,0 +1,126 @@
+use axum::extract::{Query, State};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use serde::Deserialize;
+use serde_json::json;
+use game_core::{CosmeticError, CosmeticProfile, CosmeticSlot};
+use crate::command_lanes::{CommandLane, QueuedCommand};
+use crate::error::ApiError;
+use crate::state::AppState;
+
+#[derive(Deserialize)]
+pub struct CosmeticsQuery {
+    /// Token from init_player; cosmetics belong to the player it was issued for
+    pub session_token: String,
+}
+
+#[derive(Deserialize)]
+pub struct EquipRequest {
+    pub session_token: String,
+    pub slot: CosmeticSlot,
+    /// Item to show in the slot; omit or null to clear it
+    #[serde(default)]
+    pub item_id: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct PurchaseRequest {
+    pub session_token: String,
+    pub item_id: String,
+}
+
+fn error_response(e: CosmeticError) -> Response {
+    let reason = e.to_string();
+    let error = match e {
+        CosmeticError::UnknownItem => ApiError::NotFound(reason),
+        CosmeticError::WrongSlot | CosmeticError::NotForSale => ApiError::BadRequest(reason),
+        CosmeticError::Locked => ApiError::Forbidden(reason),
+        CosmeticError::AlreadyOwned | CosmeticError::InsufficientCoins { .. } => ApiError::Conflict(reason),
+    };
+    error.into_response()
+}
+
+/// Coins, every owned item id (free items included) and what's equipped
+fn profile_json(app_state: &AppState, profile: &CosmeticProfile) -> serde_json::Value {
+    let owned: Vec<&str> = app_state
+        .game_config
+        .cosmetics
+        .items
+        .iter()
+        .filter(|item| game_core::cosmetics::owns(profile, item))
+        .map(|item| item.id.as_str())
+        .collect();
+    json!({
+        "coins": profile.coins,
+        "owned": owned,
+        "equipped": profile.equipped,
+    })
+}
+
+/// The player's coins, owned cosmetics and equipped cosmetics
+pub async fn get_cosmetics(
+    State(app_state): State<AppState>,
+    Query(query): Query<CosmeticsQuery>,
+) -> Response {
+    let Some(player_id) = app_state.sessions.verify(&query.session_token) else {
+        return StatusCode::UNAUTHORIZED.into_response();
+    };
+    let profile = app_state.cosmetics.read().await.profile(&player_id);
+    Json(profile_json(&app_state, &profile)).into_response()
+}
+
+/// Show an owned cosmetic in its slot, or clear the slot; every client sees it on the player
+pub async fn equip_cosmetic(
+    State(app_state): State<AppState>,
+    Json(request): Json<EquipRequest>,
+) -> Response {
+    let Some(player_id) = app_state.sessions.verify(&request.session_token) else {
+        return StatusCode::UNAUTHORIZED.into_response();
+    };
+
+    let mut cosmetics = app_state.cosmetics.write().await;
+    let equipped = match cosmetics.equip(
+        player_id,
+        request.slot,
+        request.item_id.as_deref(),
+        &app_state.game_config.cosmetics.items,
+    ) {
+        Ok(equipped) => equipped,
+        Err(e) => return error_response(e),
+    };
+    if let Err(e) = cosmetics.save().await {
+        eprintln!("❌ Failed to save cosmetics for {}: {}", player_id, e);
+        return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+    }
+    drop(cosmetics);
+
+    // The change is saved either way; a full cosmetic lane only delays showing it until the next join
+    let shown = QueuedCommand::Cosmetics { player_id, equipped: equipped.clone() };
+    if !app_state.commands.push(CommandLane::Cosmetic, shown).await {
+        eprintln!("⚠️ Cosmetic lane full, {}'s new look shows after they rejoin", player_id);
+    }
+    Json(json!({ "equipped": equipped })).into_response()
+}
+
+/// Buy a cosmetic with coins
+pub async fn purchase_cosmetic(
+    State(app_state): State<AppState>,
+    Json(request): Json<PurchaseRequest>,
+) -> Response {
+    let Some(player_id) = app_state.sessions.verify(&request.session_token) else {
+        return StatusCode::UNAUTHORIZED.into_response();
+    };
+
+    let mut cosmetics = app_state.cosmetics.write().await;
+    let profile = match cosmetics.purchase(player_id, &request.item_id, &app_state.game_config.cosmetics.items) {
+        Ok(profile) => profile,
+        Err(e) => return error_response(e),
+    };
+    if let Err(e) = cosmetics.save().await {
+        eprintln!("❌ Failed to save cosmetics for {}: {}", player_id, e);
+        return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+    }
+    eprintln!("🛍️ Player {} bought cosmetic {}", player_id, request.item_id);
+    Json(profile_json(&app_state, &profile)).into_response()
+}
@@ -0,0 +1,11 @@
+use axum::response::{Html, IntoResponse, Json};
+
+/// Swagger UI for the API
+pub async fn docs_page() -> Html<String> {
+    Html(crate::openapi::docs_html())
+}
+
+/// OpenAPI document for every route, see `crate::openapi`
+pub async fn openapi_json() -> impl IntoResponse {
+    Json(crate::openapi::spec())
+}
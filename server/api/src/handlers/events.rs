@@ -1,85 +1,662 @@
-use axum::extract::State;
-use axum::response::sse::{Event, Sse};
+use axum::extract::{ConnectInfo, Query, State};
+use axum::http::{header, Extensions, HeaderMap, StatusCode, Version};
+use axum::response::sse::{Event, KeepAlive, Sse};
+use axum::response::{IntoResponse, Response};
 use futures::stream::Stream;
 use std::convert::Infallible;
+use std::net::SocketAddr;
+use crate::chat_commands::{escape_html, system_line};
+use crate::backpressure::{LagAction, LagTracker};
+use crate::direct::{DirectEvent, DirectReceiver};
+use crate::error::ApiError;
+use crate::hud::Hud;
+use crate::i18n::{translate, Text};
+use crate::resume::SubscriberFilter;
+use crate::spawn_hints::SpawnHints;
 use crate::state::AppState;
 use crate::GameUpdate;
 use datastar::patch_signals::PatchSignals;
 use datastar::patch_elements::PatchElements;
 use datastar::consts::ElementPatchMode;
+use game_core::player_color::{player_color, team_color};
+use game_core::config::StreamCompressionConfig;
+use game_core::Palette;
+use game_core::signals::{ProtocolVersion, Signal, SignalPatch};
+use serde::Deserialize;
+use tokio::sync::broadcast::error::RecvError;
+use tower_http::compression::predicate::Predicate;
+use tower_http::compression::{CompressionLayer, CompressionLevel};
+
+/// Wrap signals as a Datastar patch-signals SSE event
+/// Datastar signal format: {"signalName": value}
+pub fn signals_event(signals: SignalPatch) -> Event {
+    let patch = PatchSignals::new(serde_json::to_string(&signals).unwrap());
+    let event = patch.into_datastar_event();
+
+    Event::default()
+        .event("datastar-patch-signals")
+        .data(format!("{}", event))
+}
+
+/// Gzip or Brotli for the event stream, at the fastest level so events aren't held back
+/// The encoder flushes whenever the stream waits for its next event, so each event reaches
+/// the client as soon as it would uncompressed; with compression disabled nothing is offered
+pub fn compression(config: &StreamCompressionConfig) -> CompressionLayer<impl Predicate> {
+    CompressionLayer::new()
+        .gzip(config.enabled && config.gzip)
+        .br(config.enabled && config.brotli)
+        .quality(CompressionLevel::Fastest)
+        // The default predicate leaves event streams alone; errors are too small to bother with
+        .compress_when(|status: StatusCode, _: Version, headers: &HeaderMap, _: &Extensions| {
+            status.is_success()
+                && headers
+                    .get(header::CONTENT_TYPE)
+                    .and_then(|value| value.to_str().ok())
+                    .is_some_and(|content_type| content_type.starts_with("text/event-stream"))
+        })
+}
+
+/// What a stream is for
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StreamMode {
+    /// A player's stream, optionally personalized with a session or player id
+    #[default]
+    Player,
+    /// Watch the game and chat without a player
+    Spectator,
+}
+
+#[derive(Deserialize)]
+pub struct EventsQuery {
+    #[serde(default)]
+    pub mode: StreamMode,
+    /// Personalize the stream for this player (challenge progress)
+    pub player_id: Option<uuid::Uuid>,
+    /// Snapshot mode: receive state at the configured snapshot rate instead of every tick,
+    /// and interpolate between snapshots using their server timestamps
+    #[serde(default)]
+    pub snapshot: bool,
+    /// Comma-separated player ids whose chat this subscriber does not want to see
+    pub mute: Option<String>,
+    /// Token from a previous stream; restores its filter state and skips the join replay
+    pub resume: Option<String>,
+    /// Language tag for server messages when the stream has no player with a chosen language
+    pub lang: Option<String>,
+    /// Palette for player and team colors when the stream's player hasn't chosen one
+    pub palette: Option<String>,
+    /// Session token from init_player; personalizes the stream for its player and keeps
+    /// the player reclaimable for the reconnect grace period after the stream drops
+    pub session: Option<String>,
+    /// Also send server-rendered HUD fragments (see `crate::hud`), for pages without game code
+    #[serde(default)]
+    pub hud: bool,
+    /// Event protocol the client speaks, `MAJOR.MINOR`; signals newer than its minor are left
+    /// out, and unsupported majors get a protocolError. Defaults to the server's version
+    pub protocol_version: Option<String>,
+    /// Room to watch; in a cluster the stream is redirected to the instance hosting it.
+    /// Defaults to the room the affinity cookie pins, then to the room on this instance
+    pub room: Option<String>,
+}
+
+impl EventsQuery {
+    fn filter(&self) -> SubscriberFilter {
+        SubscriberFilter {
+            player_id: self.player_id,
+            snapshot: self.snapshot,
+            muted: self
+                .mute
+                .iter()
+                .flat_map(|ids| ids.split(','))
+                .filter_map(|id| id.trim().parse().ok())
+                .collect(),
+            spectator: self.mode == StreamMode::Spectator,
+            hud: self.hud,
+        }
+    }
+}
+
+/// Wrap HTML as a Datastar patch-elements SSE event
+pub fn elements_event(html: String, selector: &str, mode: ElementPatchMode) -> Event {
+    let patch = PatchElements::new(html)
+        .selector(selector)
+        .mode(mode);
+    let event = patch.into_datastar_event();
+
+    // Set the event type for proper SSE routing
+    Event::default()
+        .event("datastar-patch-elements")
+        .data(format!("{}", event))
+}
+
+/// Render a chat message with the player name in their color, tagged when it's team chat
+/// Server messages (nil player id) keep their own color in every palette
+fn chat_message_html(message: &game_core::ChatMessage, palette: Palette, config: &game_core::GameConfig) -> String {
+    let team_tag = if message.team.is_some() {
+        r#"<span style="color: #AAAAAA">[Team]</span> "#
+    } else {
+        ""
+    };
+    format!(
+        r#"<div style="margin-bottom: 8px; font-size: 14px">{}<span style="color: {}; font-weight: bold;">{}:</span> {}</div>"#,
+        team_tag,
+        if palette == Palette::Standard || message.player_id.is_nil() {
+            message.player_color.clone()
+        } else {
+            player_color(&message.player_id, palette, &config.color_blind_palette)
+        },
+        escape_html(&message.player_name),
+        escape_html(&message.text)
+    )
+}
+
+/// Players for the gameState signal, flagged with spawn hints; non-standard palettes get
+/// every player's color and team color rewritten so clients render them as-is
+fn players_signal(state: &game_core::GameState, palette: Palette, hints: &mut SpawnHints) -> serde_json::Value {
+    let config = state.world.config();
+    state
+        .players
+        .values()
+        .map(|player| {
+            let mut value = serde_json::to_value(player).unwrap();
+            if palette != Palette::Standard {
+                value["color"] = player_color(&player.id, palette, &config.color_blind_palette).into();
+                if let Some(team) = player.team.as_deref().and_then(|team_id| game_core::teams::find(&config.teams, team_id)) {
+                    value["team_color"] = team_color(team, palette, &config.color_blind_palette).into();
+                }
+            }
+            hints.mark_player(player, &mut value);
+            value
+        })
+        .collect()
+}
+
+/// Projectiles in flight, new ones flagged with spawn hints
+fn projectiles_signal(state: &game_core::GameState, hints: &mut SpawnHints) -> serde_json::Value {
+    state
+        .projectiles
+        .iter()
+        .map(|projectile| {
+            let mut value = serde_json::to_value(projectile).unwrap();
+            hints.mark_projectile(projectile, &mut value);
+            value
+        })
+        .collect()
+}
+
+/// Add the game mode's scoreboard, if the mode keeps one
+fn with_mode_status(patch: SignalPatch, status: Option<game_core::ModeStatus>) -> SignalPatch {
+    match status {
+        Some(status) => patch.with(status.signal, status.state),
+        None => patch,
+    }
+}
+
+/// Everything a client needs to redraw the world after missing broadcasts
+fn resync_patch(state: &game_core::GameState, palette: Palette, hints: &mut SpawnHints, server_time_ms: u64) -> SignalPatch {
+    let patch = SignalPatch::new()
+        .with(Signal::Geometry, state.blocks.snapshot())
+        .with(Signal::MatchState, &state.match_state)
+        .with(Signal::Map, crate::handlers::maps::current_map(state))
+        .with(Signal::Mode, state.mode())
+        .with(Signal::ConfigVersion, state.config_version())
+        .with(Signal::WorldEvent, &state.world_events.active)
+        .with(Signal::GameState, players_signal(state, palette, hints))
+        .with(Signal::Projectiles, projectiles_signal(state, hints))
+        .with(Signal::WorldEntities, &state.world_events.entities)
+        .with(Signal::Tick, state.tick)
+        .with(Signal::ServerTime, server_time_ms)
+        .with(Signal::TimeScale, state.time_scale());
+    with_mode_status(patch, state.mode_status())
+}
+
+/// Whether a stream should show a chat message: not muted, and team chat only for that team
+fn chat_visible(message: &game_core::ChatMessage, filter: &SubscriberFilter, team: Option<&str>) -> bool {
+    !filter.muted.contains(&message.player_id)
+        && message.team.as_deref().is_none_or(|message_team| Some(message_team) == team)
+}
+
+/// The next message for this stream's player; never resolves on streams without a session
+async fn next_direct(direct: &mut Option<DirectReceiver>) -> Option<DirectEvent> {
+    match direct {
+        Some(direct) => direct.recv().await,
+        None => std::future::pending().await,
+    }
+}
+
+/// A stream carrying only a protocolError, for clients on a protocol the server can't speak
+/// Sent as a 200 event stream so EventSource clients can read why they were refused
+fn protocol_refusal(requested: &str) -> Response {
+    let error = match ProtocolVersion::parse(requested) {
+        Some(_) => "unsupported_protocol",
+        None => "invalid_protocol",
+    };
+    eprintln!("🚫 Refused event stream on protocol {:?}", requested);
+    let refusal = SignalPatch::new().with(
+        Signal::ProtocolError,
+        serde_json::json!({
+            "error": error,
+            "requested": requested,
+            "current": ProtocolVersion::CURRENT.to_string(),
+            "supportedMajors": game_core::signals::SUPPORTED_MAJORS,
+        }),
+    );
+    Sse::new(futures::stream::once(async move { Ok::<_, Infallible>(signals_event(refusal)) })).into_response()
+}
 
 pub async fn events_handler(
     State(app_state): State<AppState>,
-) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
+    ConnectInfo(peer): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
+    uri: axum::http::Uri,
+    Query(query): Query<EventsQuery>,
+) -> Result<Sse<impl Stream<Item = Result<Event, Infallible>>>, Response> {
+    if app_state.shutting_down.load(std::sync::atomic::Ordering::SeqCst) {
+        return Err(StatusCode::SERVICE_UNAVAILABLE.into_response());
+    }
+    let protocol = match query.protocol_version.as_deref() {
+        None => ProtocolVersion::CURRENT,
+        Some(requested) => match ProtocolVersion::parse(requested).filter(|version| version.is_supported()) {
+            Some(version) => version,
+            None => return Err(protocol_refusal(requested)),
+        },
+    };
+
+    // Per-IP connection limit; the guard lives inside the stream and frees the slot on disconnect
+    let ip = app_state.client_ip.resolve(&headers, peer);
+    if app_state.ip_limiter.is_banned(ip) {
+        return Err(ApiError::Banned.into_response());
+    }
+    let Some(connection_guard) = app_state.ip_limiter.try_connect(ip) else {
+        eprintln!("🚫 Rejected SSE connection from {}: connection limit reached", ip);
+        return Err(StatusCode::TOO_MANY_REQUESTS.into_response());
+    };
+
+    // Spectators have no player, so their stream can't be tied to one
+    if query.mode == StreamMode::Spectator && (query.session.is_some() || query.player_id.is_some()) {
+        return Err(StatusCode::BAD_REQUEST.into_response());
+    }
+
+    let session_player = match query.session.as_deref() {
+        Some(token) => Some(app_state.sessions.verify(token).ok_or(StatusCode::UNAUTHORIZED.into_response())?),
+        None => None,
+    };
+
+    // Streams belong on the instance hosting the room they watch
+    let room = app_state.affinity.requested_room(&headers, query.room.as_deref());
+    let path = uri.path_and_query().map(|p| p.as_str()).unwrap_or("/events");
+    let player_id = session_player.or(query.player_id);
+    if let Some(redirect) = crate::affinity::misdirected(&app_state, room.as_deref(), player_id.as_ref(), path).await {
+        return Err(redirect);
+    }
+
+    // A valid resume token restores the previous stream's filter; otherwise use the query
+    let resumed = query.resume.as_deref().and_then(|token| app_state.resume.take(token));
+    let is_resumed = resumed.is_some();
+    let mut filter = resumed.unwrap_or_else(|| query.filter());
+
+    // Reconnecting with a session reclaims the player if it is still within the grace period
+    let session_guard = session_player.map(|player_id| {
+        filter.player_id = Some(player_id);
+        app_state.sessions.connect(player_id)
+    });
+    if let Some(player_id) = session_player {
+        if let Some(player) = app_state.game_state.write().await.players.get_mut(&player_id) {
+            player.update_activity(app_state.clock.now());
+        }
+    }
+    // Only streams opened with a session get messages meant for that player alone
+    let mut direct = session_player.map(|player_id| app_state.direct.subscribe(player_id));
+    let spectator_guard = filter.spectator.then(|| app_state.spectators.watch());
+    let spectator_count = app_state.spectators.count();
+    let resume_guard = app_state.resume.issue(filter.clone());
+
     let mut game_rx = app_state.game_tx.subscribe();
     let mut chat_rx = app_state.chat_tx.subscribe();
 
+    let snapshot_interval_ms = (1000.0 / app_state.game_config.snapshot_rate_hz.max(1.0)) as u64;
+
+    // Snapshot of runtime blocks so late joiners see geometry placed before they connected
+    let (geometry, match_state, map, mode, config_version, world_event, mode_status) = {
+        let game_state = app_state.game_state.read().await;
+        (
+            game_state.blocks.snapshot(),
+            game_state.match_state.clone(),
+            crate::handlers::maps::current_map(&game_state),
+            game_state.mode(),
+            game_state.config_version(),
+            game_state.world_events.active.clone(),
+            game_state.mode_status(),
+        )
+    };
+    // Recent chat so late joiners have context; resumed clients already have it
+    let chat_history: Vec<_> = if is_resumed {
+        Vec::new()
+    } else {
+        app_state.chat_history.read().await.iter().cloned().collect()
+    };
+    // Team of the stream's player, for delivering team chat; refreshed from state updates
+    // Only a verified session gets team chat, since anyone can pass ?player_id=
+    let (team, chosen_language, chosen_palette) = match filter.player_id {
+        Some(player_id) => {
+            let game_state = app_state.game_state.read().await;
+            let player = game_state.players.get(&player_id);
+            (
+                player.filter(|_| session_player.is_some()).and_then(|p| p.team.clone()),
+                player.and_then(|p| p.language),
+                player.and_then(|p| p.palette),
+            )
+        }
+        None => (None, None, None),
+    };
+    // Server-generated text on this stream: the player's choice, then ?lang=, then Accept-Language
+    let fallback_language = query
+        .lang
+        .as_deref()
+        .and_then(game_core::Language::parse)
+        .or_else(|| crate::i18n::header_language(&headers))
+        .unwrap_or(app_state.game_config.default_language);
+    let language = chosen_language.unwrap_or(fallback_language);
+    // Colors on this stream: the player's choice, then ?palette=
+    let fallback_palette = query.palette.as_deref().and_then(Palette::parse).unwrap_or_default();
+    let palette = chosen_palette.unwrap_or(fallback_palette);
+    let game_config = app_state.game_config.clone();
+    // A banner that is up now should show on streams opened while it's displayed
+    let banner = app_state.announcer.current();
+    let announcer = app_state.announcer.clone();
+    let lag_metrics = app_state.lag_metrics.clone();
+    let game_state = app_state.game_state.clone();
+    let recent_chat = app_state.chat_history.clone();
+    let clock = app_state.clock.clone();
+
     let stream = async_stream::stream! {
+        let _connection_guard = connection_guard;
+        let _session_guard = session_guard;
+        let _spectator_guard = spectator_guard;
+        // Signals only as far as the client's protocol minor knows them
+        let client_signals = |signals: SignalPatch| signals_event(signals.for_protocol(protocol));
+
+        yield Ok(client_signals(with_mode_status(
+            SignalPatch::new()
+                .with(Signal::ResumeToken, resume_guard.token())
+                .with(Signal::Geometry, geometry)
+                .with(Signal::SpectatorCount, spectator_count)
+                .with(Signal::MatchState, match_state)
+                .with(Signal::Map, map)
+                .with(Signal::Mode, mode)
+                .with(Signal::ConfigVersion, config_version)
+                .with(Signal::WorldEvent, world_event),
+            mode_status,
+        )));
+
+        let mut team = team;
+        let mut language = language;
+        let mut palette = palette;
+        for message in chat_history.iter().filter(|m| chat_visible(m, &filter, team.as_deref())) {
+            yield Ok(elements_event(chat_message_html(message, palette, &game_config), "#chat-messages", ElementPatchMode::Append));
+        }
+        if let Some(html) = banner {
+            yield Ok(elements_event(html, crate::announcements::BANNER_SELECTOR, ElementPatchMode::Replace));
+        }
+
+        // Last challenge progress sent on this personalized stream
+        let mut last_progress = None;
+        // Last queue position sent while this stream's player waits for a slot
+        let mut last_waiting: Option<Option<usize>> = None;
+        // Server time of the last state sent, for throttling in snapshot mode
+        let mut last_snapshot_ms = 0;
+        let mut adaptive_rate = crate::adaptive_rate::AdaptiveRate::new(&game_config.adaptive_rate);
+        let mut spawn_hints = SpawnHints::default();
+        let mut lag_tracker = LagTracker::new(&game_config.backpressure);
+        let mut hud = Hud::new(game_config.tick_rate_hz);
+        let hud_interval_ms = (1000.0 / game_config.hud.rate_hz.max(0.1)) as u64;
+        let mut last_hud_ms = 0;
+
         loop {
             tokio::select! {
-                Ok(update) = game_rx.recv() => {
+                update = game_rx.recv() => {
+                    let update = match update {
+                        Ok(update) => update,
+                        Err(RecvError::Lagged(skipped)) => {
+                            // The subscriber fell behind and missed broadcasts: catch it up with
+                            // the full state, or give up on it if it keeps happening
+                            let action = lag_tracker.record(clock.unix_millis());
+                            lag_metrics.record(skipped, action);
+                            if action == LagAction::Disconnect {
+                                eprintln!("🐢 Closed stream for {} after it fell behind repeatedly", ip);
+                                break;
+                            }
+                            eprintln!("🐢 Stream for {} missed {} update(s), resyncing", ip, skipped);
+                            let state = game_state.read().await.clone();
+                            spawn_hints.forget_missing(&state);
+                            yield Ok(client_signals(resync_patch(&state, palette, &mut spawn_hints, clock.unix_millis())));
+                            continue;
+                        }
+                        Err(RecvError::Closed) => break,
+                    };
                     match update {
-                        GameUpdate::StateUpdate(state) => {
+                        GameUpdate::StateUpdate { state, server_time_ms } => {
+                            // Subscribers falling behind get fewer states rather than lagging;
+                            // the events below are never throttled
+                            if adaptive_rate.observe(game_rx.len(), server_time_ms) {
+                                match adaptive_rate.rate_hz() {
+                                    Some(rate) => eprintln!("📉 Slowed state updates to {}Hz for {}", rate, ip),
+                                    None => eprintln!("📈 Restored full state rate for {}", ip),
+                                }
+                                yield Ok(client_signals(SignalPatch::new().with(Signal::StreamRate, adaptive_rate.rate_hz())));
+                            }
+                            if !adaptive_rate.should_send(server_time_ms) {
+                                continue;
+                            }
+                            if filter.snapshot {
+                                if server_time_ms.saturating_sub(last_snapshot_ms) < snapshot_interval_ms {
+                                    continue;
+                                }
+                                last_snapshot_ms = server_time_ms;
+                            }
+
                             // Datastar best practice: Send only what changed (delta updates)
                             // For now, send full state, but in production implement delta encoding
-                            let players: Vec<_> = state.players.values().cloned().collect();
-                            
-                            // Datastar signal format: {"signalName": value}
-                            // Send the players array directly as the signal value
-                            let signals_json = serde_json::json!({
-                                "gameState": players
-                            });
-                            
-                            let patch = PatchSignals::new(serde_json::to_string(&signals_json).unwrap());
-                            let event = patch.into_datastar_event();
-                            
-                            yield Ok(Event::default()
-                                .event("datastar-patch-signals")
-                                .data(format!("{}", event)));
+                            // Personalized streams pick up palette changes before rendering colors
+                            if let Some(player) = filter.player_id.and_then(|id| state.players.get(&id)) {
+                                palette = player.palette.unwrap_or(fallback_palette);
+                            }
+
+                            // Send the players array directly as the signal value,
+                            // tagged with the tick it was produced on for client interpolation
+                            spawn_hints.forget_missing(&state);
+                            yield Ok(client_signals(
+                                SignalPatch::new()
+                                    .with(Signal::GameState, players_signal(&state, palette, &mut spawn_hints))
+                                    .with(Signal::Projectiles, projectiles_signal(&state, &mut spawn_hints))
+                                    .with(Signal::WorldEntities, &state.world_events.entities)
+                                    .with(Signal::Tick, state.tick)
+                                    .with(Signal::ServerTime, server_time_ms)
+                                    .with(Signal::TimeScale, state.time_scale()),
+                            ));
+
+                            // Personalized streams also get the player's challenge progress when it changes
+                            if let Some(player_id) = filter.player_id {
+                                let player = state.players.get(&player_id);
+                                team = player.filter(|_| session_player.is_some()).and_then(|p| p.team.clone());
+                                language = player.and_then(|p| p.language).unwrap_or(fallback_language);
+
+                                // Spectators waiting for a slot see their place; null once promoted
+                                let waiting = state.waiting.position(&player_id);
+                                if last_waiting != Some(waiting) && (waiting.is_some() || last_waiting.is_some()) {
+                                    let waiting_for_slot = waiting.map(|position| serde_json::json!({
+                                        "position": position,
+                                        "queueLength": state.waiting.len()
+                                    }));
+                                    yield Ok(client_signals(SignalPatch::new().with(Signal::WaitingForSlot, waiting_for_slot)));
+                                }
+                                last_waiting = Some(waiting);
+
+                                let progress = state.challenges.progress_for(&player_id);
+                                if last_progress.as_ref() != Some(&progress) {
+                                    yield Ok(client_signals(SignalPatch::new().with(Signal::ChallengeProgress, &progress)));
+                                    last_progress = Some(progress);
+                                }
+                            }
+
+                            if filter.hud && server_time_ms.saturating_sub(last_hud_ms) >= hud_interval_ms {
+                                last_hud_ms = server_time_ms;
+                                for (selector, html) in hud.render(&state, filter.player_id, server_time_ms) {
+                                    yield Ok(elements_event(html, selector, ElementPatchMode::Outer));
+                                }
+                            }
+                        }
+                        GameUpdate::GeometryChanged(sync) => {
+                            // Send only the cells that changed; clients whose version does not
+                            // match from_version resync via /api/geometry
+                            yield Ok(client_signals(SignalPatch::new().with(Signal::GeometryDelta, sync)));
+                        }
+                        GameUpdate::ComboBroken(breaks) => {
+                            // Clients use this to play the combo-break effect on the HUD
+                            yield Ok(client_signals(SignalPatch::new().with(Signal::ComboBreak, breaks)));
+                        }
+                        GameUpdate::ChallengesCompleted(completions) => {
+                            yield Ok(client_signals(SignalPatch::new().with(Signal::ChallengeCompleted, completions)));
+                        }
+                        GameUpdate::Damage(events) => {
+                            // Clients flash hit players and show the damage taken
+                            yield Ok(client_signals(SignalPatch::new().with(Signal::Damage, events)));
+                        }
+                        GameUpdate::Bounces(events) => {
+                            // Clients play the trampoline sound and dust where each player bounced
+                            yield Ok(client_signals(SignalPatch::new().with(Signal::Bounces, events)));
+                        }
+                        GameUpdate::Teleports(events) => {
+                            // Clients flash both ends of each portal trip
+                            yield Ok(client_signals(SignalPatch::new().with(Signal::Teleports, events)));
+                        }
+                        GameUpdate::ModeStatus(status) => {
+                            // Clients update the mode's scoreboard, e.g. race places or who is it
+                            yield Ok(client_signals(SignalPatch::new().with(status.signal, status.state)));
+                        }
+                        GameUpdate::LifeEvents(events) => {
+                            // Clients play death effects and snap respawned players to their spawn
+                            yield Ok(client_signals(SignalPatch::new().with(Signal::LifeEvents, events)));
+                        }
+                        GameUpdate::KillCam(kill_cam) => {
+                            // Only the victim replays their death, and only on a verified session
+                            if session_player == Some(kill_cam.victim) {
+                                yield Ok(client_signals(SignalPatch::new().with(Signal::KillCam, kill_cam)));
+                            }
                         }
                         GameUpdate::PlayerLeft { player_id, player_name } => {
                             // Broadcast player left message as a signal update
                             // Clients can listen for this to remove the player from rendering
-                            let signals_json = serde_json::json!({
-                                "playerLeft": {
-                                    "player_id": player_id.to_string(),
-                                    "player_name": player_name
-                                }
+                            let player_left = serde_json::json!({
+                                "player_id": player_id.to_string(),
+                                "player_name": player_name
                             });
-                            
-                            let patch = PatchSignals::new(serde_json::to_string(&signals_json).unwrap());
-                            let event = patch.into_datastar_event();
-                            
-                            yield Ok(Event::default()
-                                .event("datastar-patch-signals")
-                                .data(format!("{}", event)));
+                            yield Ok(client_signals(SignalPatch::new().with(Signal::PlayerLeft, player_left)));
+                            let notice = translate(language, &Text::PlayerLeft { name: player_name });
+                            yield Ok(elements_event(system_line(&notice, "#AAAAAA"), "#chat-messages", ElementPatchMode::Append));
+                        }
+                        GameUpdate::MatchPhase(match_state) => {
+                            // Clients count down to the phase's deadline using serverTime
+                            yield Ok(client_signals(SignalPatch::new().with(Signal::MatchState, match_state)));
+                        }
+                        GameUpdate::MapChanged(map) => {
+                            // Clients reload the static geometry from /api/config
+                            yield Ok(client_signals(SignalPatch::new().with(Signal::Map, map)));
+                        }
+                        GameUpdate::MapEdited(geometry) => {
+                            yield Ok(client_signals(SignalPatch::new().with(Signal::MapGeometry, geometry)));
+                        }
+                        GameUpdate::ModeChanged(mode) => {
+                            yield Ok(client_signals(SignalPatch::new().with(Signal::Mode, mode)));
+                        }
+                        GameUpdate::ConfigChanged(version) => {
+                            // Clients refetch /api/config for the new settings
+                            yield Ok(client_signals(SignalPatch::new().with(Signal::ConfigVersion, version)));
+                        }
+                        GameUpdate::Notice(text) => {
+                            let notice = translate(language, &text);
+                            yield Ok(elements_event(system_line(&notice, "#AAAAAA"), "#chat-messages", ElementPatchMode::Append));
+                        }
+                        GameUpdate::WorldEvent(change) => {
+                            let (event, banner_text) = match change {
+                                game_core::WorldEventChange::Started(event) => {
+                                    let name = crate::i18n::world_event_name(language, event.kind);
+                                    (Some(event), name.to_string())
+                                }
+                                game_core::WorldEventChange::Ended { .. } => (None, String::new()),
+                            };
+                            yield Ok(client_signals(SignalPatch::new().with(Signal::WorldEvent, event)));
+                            // The event gets the banner only while no announcement holds it
+                            if announcer.current().is_none() {
+                                let html = crate::announcements::banner_html(&banner_text, game_core::config::AnnouncementSeverity::Info);
+                                yield Ok(elements_event(html, crate::announcements::BANNER_SELECTOR, ElementPatchMode::Replace));
+                            }
+                        }
+                        GameUpdate::SpectatorCount(count) => {
+                            yield Ok(client_signals(SignalPatch::new().with(Signal::SpectatorCount, count)));
+                        }
+                        GameUpdate::Announcement { html } => {
+                            yield Ok(elements_event(html, crate::announcements::BANNER_SELECTOR, ElementPatchMode::Replace));
+                        }
+                        GameUpdate::ServerShutdown { seconds_remaining } => {
+                            // Clients show the countdown; at zero the stream ends so the
+                            // server can finish shutting down
+                            let shutdown = serde_json::json!({ "secondsRemaining": seconds_remaining });
+                            yield Ok(client_signals(SignalPatch::new().with(Signal::ServerShutdown, shutdown)));
+                            if seconds_remaining == 0 {
+                                break;
+                            }
                         }
                     }
                 }
-                Ok(message) = chat_rx.recv() => {
-                    // Format message with player name in their color
-                    let html_content = format!(
-                        r#"<div style="margin-bottom: 8px; font-size: 14px"><span style="color: {}; font-weight: bold;">{}:</span> {}</div>"#,
-                        message.player_color,
-                        message.player_name,
-                        message.text
-                    );
-                    
-                    let patch = PatchElements::new(html_content.clone())
-                        .selector("#chat-messages")
-                        .mode(ElementPatchMode::Append);
-                    let event = patch.into_datastar_event();
-                    let event_data = format!("{}", event);
-                    
-                    // Set the event type for proper SSE routing
-                    yield Ok(Event::default()
-                        .event("datastar-patch-elements")
-                        .data(event_data));
+                Some(event) = next_direct(&mut direct) => {
+                    match event {
+                        DirectEvent::Signals(patch) => yield Ok(client_signals(patch)),
+                        DirectEvent::Notice(text) => {
+                            let notice = translate(language, &text);
+                            yield Ok(elements_event(system_line(&notice, "#AAAAAA"), "#chat-messages", ElementPatchMode::Append));
+                        }
+                    }
+                }
+                message = chat_rx.recv() => {
+                    let message = match message {
+                        Ok(message) => message,
+                        Err(RecvError::Lagged(skipped)) => {
+                            // Missed chat counts against the same lag limit as missed updates;
+                            // the recent history takes the place of whatever was missed
+                            let action = lag_tracker.record(clock.unix_millis());
+                            lag_metrics.record(skipped, action);
+                            if action == LagAction::Disconnect {
+                                eprintln!("🐢 Closed stream for {} after it fell behind repeatedly", ip);
+                                break;
+                            }
+                            eprintln!("🐢 Stream for {} missed {} chat message(s), resending history", ip, skipped);
+                            let history: Vec<_> = recent_chat
+                                .read()
+                                .await
+                                .iter()
+                                .filter(|m| chat_visible(m, &filter, team.as_deref()))
+                                .cloned()
+                                .collect();
+                            for (i, message) in history.iter().enumerate() {
+                                let mode = if i == 0 { ElementPatchMode::Replace } else { ElementPatchMode::Append };
+                                yield Ok(elements_event(chat_message_html(message, palette, &game_config), "#chat-messages", mode));
+                            }
+                            continue;
+                        }
+                        Err(RecvError::Closed) => break,
+                    };
+                    if !chat_visible(&message, &filter, team.as_deref()) {
+                        continue;
+                    }
+                    yield Ok(elements_event(chat_message_html(&message, palette, &game_config), "#chat-messages", ElementPatchMode::Append));
                 }
             }
         }
     };
 
-    Sse::new(stream)
+    // Keepalive comments make writes to a dead connection fail within seconds,
+    // which drops the stream and starts the session's reconnect grace period
+    let keepalive = std::time::Duration::from_secs(app_state.game_config.presence.keepalive_secs.max(1));
+    Ok(Sse::new(stream).keep_alive(KeepAlive::new().interval(keepalive)))
 }
-
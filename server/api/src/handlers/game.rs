@@ -1,67 +1,350 @@
-use axum::extract::State;
+use axum::extract::{ConnectInfo, State};
+use axum::http::{HeaderMap, StatusCode};
+use axum::response::{IntoResponse, Response};
 use axum::Json;
+use serde_json::json;
+use std::net::SocketAddr;
 use serde::Deserialize;
+use crate::command_lanes::{CommandLane, QueuedCommand};
+use crate::error::ApiError;
 use crate::state::AppState;
+use game_core::Admission;
 
 #[derive(Deserialize)]
 pub struct CommandRequest {
     pub player_id: uuid::Uuid,
     pub command: game_core::PlayerCommand,
+    /// Client-assigned sequence number, echoed back in state updates for reconciliation
+    /// Zero (or omitted) means the client doesn't track sequence numbers
+    #[serde(default)]
+    pub seq: u64,
+    /// Token from init_player; it must belong to player_id
+    #[serde(default)]
+    pub session_token: Option<String>,
 }
 
 #[derive(Deserialize)]
 pub struct InitRequest {
-    pub player_id: uuid::Uuid,
+    /// The player to reclaim; only honored together with their session token
+    #[serde(default)]
+    pub player_id: Option<uuid::Uuid>,
+    /// Token from an earlier init, to reclaim the player after a dropped connection
+    #[serde(default)]
+    pub session_token: Option<String>,
+    /// Language tag for server messages (e.g. "es"); defaults to the Accept-Language header
+    #[serde(default)]
+    pub language: Option<String>,
+    /// Room to join; in a cluster the request is redirected to the instance hosting it.
+    /// Defaults to the room the affinity cookie pins, then to the room on this instance
+    #[serde(default)]
+    pub room: Option<String>,
 }
 
-// Initialize a player when they first connect
+/// Require the session token issued for `player_id`; a missing token is rejected too,
+/// since player ids are public (game state, plot owners, room creators)
+pub(crate) fn check_session(app_state: &AppState, player_id: uuid::Uuid, token: Option<&str>) -> Result<(), ApiError> {
+    match token.and_then(|token| app_state.sessions.verify(token)) {
+        Some(owner) if owner == player_id => Ok(()),
+        _ => Err(ApiError::InvalidSession),
+    }
+}
+
+/// The player an init is for: the token's owner when reclaiming, otherwise a fresh id
+/// The server picks new ids so nobody can start a session as a player they don't own
+fn init_identity(app_state: &AppState, request: &InitRequest) -> Result<uuid::Uuid, ApiError> {
+    match request.session_token.as_deref() {
+        Some(token) => {
+            let player_id = app_state.sessions.verify(token).ok_or(ApiError::InvalidSession)?;
+            if request.player_id.is_some_and(|requested| requested != player_id) {
+                return Err(ApiError::InvalidSession);
+            }
+            Ok(player_id)
+        }
+        None => Ok(uuid::Uuid::new_v4()),
+    }
+}
+
+/// Add a player to the game if they don't exist yet (idempotent)
+/// When the room is full the player waits for a slot as a spectator instead
+/// Fails with 429 when the client's IP already has too many live players, and with 503
+/// once the server is shutting down or draining, or the waiting queue is full
+async fn ensure_player(
+    app_state: &AppState,
+    player_id: uuid::Uuid,
+    headers: &HeaderMap,
+    peer: SocketAddr,
+) -> Result<Admission, ApiError> {
+    let mut game_state = app_state.game_state.write().await;
+    let known = game_state.players.contains_key(&player_id) || game_state.waiting.position(&player_id).is_some();
+    if known {
+        return Ok(game_state.join(player_id));
+    }
+    if app_state.shutting_down.load(std::sync::atomic::Ordering::SeqCst) {
+        return Err(ApiError::ShuttingDown);
+    }
+    if app_state.draining.load(std::sync::atomic::Ordering::SeqCst) {
+        return Err(ApiError::Draining);
+    }
+
+    let ip = app_state.client_ip.resolve(headers, peer);
+    let allowed = app_state.ip_limiter.try_create_player(ip, player_id, |id| {
+        game_state.players.contains_key(id) || game_state.waiting.position(id).is_some()
+    });
+    if !allowed {
+        eprintln!("🚫 Rejected new player {} from {}: player limit reached", player_id, ip);
+        return Err(ApiError::PlayerLimit);
+    }
+
+    let admission = game_state.join(player_id);
+    match admission {
+        Admission::Playing => {
+            crate::handlers::names::restore_name(app_state, &mut game_state, player_id).await;
+            if let Some(player) = game_state.players.get_mut(&player_id) {
+                player.language = crate::i18n::header_language(headers);
+                app_state.moderator.review(player_id, crate::moderation::ContentKind::Name, player.name.clone());
+                // In distributed mode the leader announces the player once they reach it
+                if !app_state.backplane.is_follower() {
+                    let _ = app_state.game_tx.send(crate::GameUpdate::Notice(crate::i18n::Text::PlayerJoined {
+                        name: player.name.clone(),
+                    }));
+                }
+            }
+        }
+        Admission::Waiting { .. } => {}
+        Admission::Full => {
+            eprintln!("🚫 Rejected new player {}: room and waiting queue are full", player_id);
+            return Err(ApiError::ServerFull);
+        }
+    }
+    if app_state.backplane.is_follower() {
+        app_state.backplane.forward(QueuedCommand::Join { player_id });
+    }
+    Ok(admission)
+}
+
+// Initialize a player when they first connect, or reclaim them after a reconnect
+// Returns the player's id and a session token for /events and every player route;
+// state updates come via SSE
 pub async fn init_player(
     State(app_state): State<AppState>,
+    ConnectInfo(peer): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
     Json(request): Json<InitRequest>,
-) -> impl axum::response::IntoResponse {
-    // Add player to game state if they don't exist (idempotent)
-    {
-        let mut game_state = app_state.game_state.write().await;
-        if !game_state.players.contains_key(&request.player_id) {
-            game_state.add_player(request.player_id);
+) -> Result<Response, ApiError> {
+    if app_state.ip_limiter.is_banned(app_state.client_ip.resolve(&headers, peer)) {
+        return Err(ApiError::Banned);
+    }
+    let player_id = init_identity(&app_state, &request)?;
+    let room = app_state.affinity.requested_room(&headers, request.room.as_deref());
+    if let Some(redirect) = crate::affinity::misdirected(&app_state, room.as_deref(), Some(&player_id), "/api/player/init").await {
+        return Ok(redirect);
+    }
+    let admission = ensure_player(&app_state, player_id, &headers, peer).await?;
+
+    // A deleted player who comes back still serves out the mute they had
+    let owed_mute = app_state
+        .tombstones
+        .read()
+        .await
+        .mute_remaining(&player_id, app_state.clock.unix_secs());
+    if let Some(remaining) = owed_mute {
+        app_state.moderator.mute(player_id, remaining).await;
+    }
+
+    let equipped = app_state.cosmetics.read().await.profile(&player_id).equipped;
+    if app_state.backplane.is_follower() {
+        app_state.backplane.forward(QueuedCommand::Cosmetics {
+            player_id,
+            equipped: equipped.clone(),
+        });
+    }
+    if let Some(player) = app_state.game_state.write().await.players.get_mut(&player_id) {
+        player.record_input(app_state.clock.now());
+        player.cosmetics = equipped;
+        if let Some(language) = request.language.as_deref().and_then(game_core::Language::parse) {
+            player.language = Some(language);
         }
     }
-    
-    // Return empty response - state updates come via SSE (Datastar best practice)
-    axum::response::Response::builder()
-        .status(axum::http::StatusCode::OK)
-        .body(axum::body::Body::empty())
-        .unwrap()
+
+    // Waiting players spectate; their stream reports their place until they are promoted
+    let waiting = match admission {
+        Admission::Waiting { position } => Some(position),
+        _ => None,
+    };
+    let mut response = Json(json!({
+        "player_id": player_id,
+        "session_token": app_state.sessions.issue(player_id),
+        "waiting": waiting,
+    }))
+    .into_response();
+    // Keep the load balancer sending this client here, where their room lives
+    app_state.affinity.pin_here(&mut response);
+    Ok(response)
 }
 
 // Datastar best practice: Idempotent command handling
 // Processing the same command multiple times should be safe
+// Replies with whether the command will be accepted or ignored (and why) against the current
+// state, plus the cooldowns it leaves; the command itself applies on the next tick
 pub async fn player_command(
     State(app_state): State<AppState>,
+    ConnectInfo(peer): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
     Json(request): Json<CommandRequest>,
-) -> impl axum::response::IntoResponse {
+) -> Result<Response, ApiError> {
+    let requested_trace = headers.get(crate::latency::TRACE_HEADER).and_then(|v| v.to_str().ok());
+    let mut trace = app_state.latency.start(requested_trace);
+    check_session(&app_state, request.player_id, request.session_token.as_deref())?;
+    let room = app_state.affinity.requested_room(&headers, None);
+    if let Some(redirect) = crate::affinity::misdirected(&app_state, room.as_deref(), Some(&request.player_id), "/api/player/command").await {
+        return Ok(redirect);
+    }
+
     // Add player to game state if they don't exist (idempotent)
-    // Update activity timestamp when player sends a command
-    {
+    // Spectators waiting for a slot can't act in the world yet
+    if ensure_player(&app_state, request.player_id, &headers, peer).await? != Admission::Playing {
+        return Err(ApiError::Waiting);
+    }
+
+    // Update activity timestamp when player sends a command, and work out what the command
+    // will do so the client can react without waiting for the next state update
+    let ack = {
         let mut game_state = app_state.game_state.write().await;
-        if !game_state.players.contains_key(&request.player_id) {
-            game_state.add_player(request.player_id);
-        }
-        // Update activity timestamp
+        // Update activity timestamp, bringing the player back if they were AFK
         if let Some(player) = game_state.players.get_mut(&request.player_id) {
-            player.update_activity();
+            player.record_input(app_state.clock.now());
         }
-    }
+        game_state.preview_command(&request.player_id, &request.command, request.seq)
+    };
     
-    // Send command to game loop (idempotent - game loop handles deduplication)
-    if let Err(e) = app_state.command_tx.send((request.player_id, request.command)).await {
-        eprintln!("❌ Failed to send command to game loop: {:?}", e);
+    // Queue the command for the game loop (idempotent - game loop handles deduplication)
+    // A full gameplay lane sheds commands rather than stalling the request
+    if let Some(trace) = &mut trace {
+        app_state.latency.queued(trace);
+    }
+    let trace_id = trace.as_ref().map(|trace| trace.id.clone());
+    let command = QueuedCommand::Player {
+        player_id: request.player_id,
+        command: request.command,
+        seq: request.seq,
+        trace,
+    };
+    if !app_state.commands.push(CommandLane::Gameplay, command).await {
+        return Err(ApiError::QueueFull(CommandLane::Gameplay));
     }
     
-    // Return empty response - state updates come via SSE (Datastar best practice)
-    axum::response::Response::builder()
-        .status(axum::http::StatusCode::OK)
-        .body(axum::body::Body::empty())
-        .unwrap()
+    // The acknowledgment only; state updates come via SSE (Datastar best practice)
+    // A traced command's id comes back so the client can find it in the server's logs
+    let mut response = Json(ack).into_response();
+    if let Some(value) = trace_id.and_then(|id| axum::http::HeaderValue::from_str(&id).ok()) {
+        response.headers_mut().insert(crate::latency::TRACE_HEADER, value);
+    }
+    Ok(response)
+}
+
+
+#[derive(Deserialize)]
+pub struct HeartbeatRequest {
+    pub player_id: uuid::Uuid,
+    #[serde(default)]
+    pub session_token: Option<String>,
+}
+
+/// Mark a player as present without sending a command, so AFK players aren't timed out
+/// Never creates players: 404 tells the client to init again
+pub async fn heartbeat(
+    State(app_state): State<AppState>,
+    Json(request): Json<HeartbeatRequest>,
+) -> Result<StatusCode, ApiError> {
+    check_session(&app_state, request.player_id, request.session_token.as_deref())?;
+    let now = app_state.clock.now();
+    let mut game_state = app_state.game_state.write().await;
+    if let Some(player) = game_state.players.get_mut(&request.player_id) {
+        player.update_activity(now);
+        return Ok(StatusCode::NO_CONTENT);
+    }
+    if game_state.waiting.touch(&request.player_id, now) {
+        return Ok(StatusCode::NO_CONTENT);
+    }
+    Err(ApiError::UnknownPlayer)
 }
 
+#[derive(Deserialize)]
+pub struct LeaveRequest {
+    /// Token from init_player; the player it was issued for is the one who leaves
+    pub session_token: String,
+}
+
+/// Remove a player right away instead of waiting for the idle timeout, e.g. from a
+/// `sendBeacon` as the tab closes; the game loop broadcasts PlayerLeft and frees the name
+/// Idempotent: leaving again, or after a timeout, is still 204
+pub async fn leave(
+    State(app_state): State<AppState>,
+    Json(request): Json<LeaveRequest>,
+) -> Result<StatusCode, ApiError> {
+    let player_id = app_state.sessions.verify(&request.session_token).ok_or(ApiError::InvalidSession)?;
+    if !app_state.commands.push(CommandLane::Gameplay, QueuedCommand::Leave { player_id }).await {
+        return Err(ApiError::QueueFull(CommandLane::Gameplay));
+    }
+    app_state.sessions.forget(&player_id);
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[derive(Deserialize)]
+pub struct NameRequest {
+    pub player_id: uuid::Uuid,
+    pub name: String,
+    /// Token from init_player; it must belong to player_id
+    #[serde(default)]
+    pub session_token: Option<String>,
+}
+
+/// Register a display name for a player, creating them if needed
+/// Invalid or taken names fall back to the generated name rather than failing
+pub async fn register_player(
+    State(app_state): State<AppState>,
+    ConnectInfo(peer): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
+    Json(request): Json<NameRequest>,
+) -> Result<Response, ApiError> {
+    check_session(&app_state, request.player_id, request.session_token.as_deref())?;
+    ensure_player(&app_state, request.player_id, &headers, peer).await?;
+
+    let mut game_state = app_state.game_state.write().await;
+    let desired = crate::handlers::names::claim_name(&app_state, &game_state, request.player_id, &request.name).await;
+    let result = game_state.rename_player(&request.player_id, &desired);
+    let player = game_state.players.get_mut(&request.player_id);
+    let name = player.as_ref().map(|p| p.name.clone()).unwrap_or_default();
+    // A player who chose their name is registered and may reserve it
+    if let (Some(player), true) = (player, result.is_ok()) {
+        player.registered = true;
+    }
+
+    if result.is_ok() {
+        app_state.moderator.review(request.player_id, crate::moderation::ContentKind::Name, name.clone());
+    }
+    Ok(Json(json!({
+        "name": name,
+        "accepted": result.is_ok(),
+        "reason": result.err().map(|e| e.to_string()),
+    }))
+    .into_response())
+}
+
+/// Change an existing player's display name, subject to the rename cooldown
+/// A name someone else reserved comes back with a number on the end
+pub async fn rename_player(
+    State(app_state): State<AppState>,
+    Json(request): Json<NameRequest>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    check_session(&app_state, request.player_id, request.session_token.as_deref())?;
+    let result = {
+        let mut game_state = app_state.game_state.write().await;
+        let desired = crate::handlers::names::claim_name(&app_state, &game_state, request.player_id, &request.name).await;
+        game_state.rename_player(&request.player_id, &desired)
+    };
+
+    let name = result.map_err(ApiError::Name)?;
+    app_state.moderator.review(request.player_id, crate::moderation::ContentKind::Name, name.clone());
+    Ok(Json(json!({ "name": name })))
+}
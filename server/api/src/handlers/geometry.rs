@@ -0,0 +1,24 @@
+use axum::extract::{Query, State};
+use axum::response::Json;
+use serde::Deserialize;
+use crate::state::AppState;
+
+#[derive(Deserialize)]
+pub struct GeometryQuery {
+    /// Geometry version the client already has; omit for a full snapshot
+    pub since: Option<u64>,
+}
+
+/// Resync runtime geometry (player-placed blocks)
+/// Returns a delta since the given version when still in history, otherwise a full snapshot
+pub async fn get_geometry(
+    State(app_state): State<AppState>,
+    Query(query): Query<GeometryQuery>,
+) -> impl axum::response::IntoResponse {
+    let game_state = app_state.game_state.read().await;
+    let sync = match query.since {
+        Some(version) => game_state.blocks.sync_since(version),
+        None => game_core::GeometrySync::Snapshot(game_state.blocks.snapshot()),
+    };
+    Json(sync)
+}
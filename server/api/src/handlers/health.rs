@@ -1,8 +1,13 @@
+use axum::extract::State;
 use axum::response::Json;
 use axum::http::StatusCode;
 use serde_json::json;
+use crate::state::AppState;
 
-pub async fn health_check() -> impl axum::response::IntoResponse {
+/// 503 while draining, so load balancers send new clients elsewhere
+pub async fn health_check(State(app_state): State<AppState>) -> impl axum::response::IntoResponse {
+    if app_state.draining.load(std::sync::atomic::Ordering::SeqCst) {
+        return (StatusCode::SERVICE_UNAVAILABLE, Json(json!({"status": "draining"})));
+    }
     (StatusCode::OK, Json(json!({"status": "ok"})))
 }
-
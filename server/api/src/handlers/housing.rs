@@ -0,0 +1,90 @@
+use axum::extract::{Path, State};
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use serde::Deserialize;
+use serde_json::json;
+use game_core::PlotError;
+use crate::error::ApiError;
+use crate::state::AppState;
+
+#[derive(Deserialize)]
+pub struct PlotRequest {
+    /// Token from init_player; the player it was issued for is the one who claims, releases
+    /// or travels, so a plot can only be touched by the session that owns it
+    pub session_token: String,
+    /// Plot to claim; the first free one when missing
+    #[serde(default)]
+    pub plot_id: Option<u32>,
+}
+
+fn error_response(error: PlotError) -> Response {
+    let reason = error.to_string();
+    let error = match error {
+        PlotError::Disabled | PlotError::UnknownPlot | PlotError::UnknownPlayer => ApiError::NotFound(reason),
+        PlotError::Unregistered => ApiError::Forbidden(reason),
+        PlotError::Taken | PlotError::AlreadyOwner | PlotError::NoFreePlot => ApiError::Conflict(reason),
+        PlotError::MatchInProgress | PlotError::NotAlive => ApiError::Conflict(reason),
+    };
+    error.into_response()
+}
+
+/// The player whose session token the request carries
+fn session_player(app_state: &AppState, request: &PlotRequest) -> Result<uuid::Uuid, ApiError> {
+    app_state.sessions.verify(&request.session_token).ok_or(ApiError::InvalidSession)
+}
+
+/// Every hub plot with its owner and the size of the house built in it
+pub async fn list_plots(State(app_state): State<AppState>) -> impl IntoResponse {
+    let game_state = app_state.game_state.read().await;
+    Json(json!({
+        "enabled": game_state.housing.enabled(),
+        "plots": game_state.housing.list(),
+    }))
+}
+
+/// Claim a plot; blocks the owner places inside it persist across visits and restarts
+pub async fn claim_plot(State(app_state): State<AppState>, Json(request): Json<PlotRequest>) -> Response {
+    let player_id = match session_player(&app_state, &request) {
+        Ok(player_id) => player_id,
+        Err(e) => return e.into_response(),
+    };
+    let mut game_state = app_state.game_state.write().await;
+    match game_state.claim_plot(&player_id, request.plot_id) {
+        Ok(plot_id) => {
+            eprintln!("🏠 Player {} claimed plot {}", player_id, plot_id);
+            Json(json!({ "plot": game_state.housing.plot(plot_id) })).into_response()
+        }
+        Err(e) => error_response(e),
+    }
+}
+
+/// Give up the player's plot; the house built in it is removed
+pub async fn release_plot(State(app_state): State<AppState>, Json(request): Json<PlotRequest>) -> Response {
+    let player_id = match session_player(&app_state, &request) {
+        Ok(player_id) => player_id,
+        Err(e) => return e.into_response(),
+    };
+    match app_state.game_state.write().await.release_plot(&player_id) {
+        Some(plot_id) => {
+            eprintln!("🏠 Player {} released plot {}", player_id, plot_id);
+            Json(json!({ "released": plot_id })).into_response()
+        }
+        None => ApiError::NotFound("player owns no plot".to_string()).into_response(),
+    }
+}
+
+/// Teleport to a plot, arriving just above it
+pub async fn visit_plot(
+    State(app_state): State<AppState>,
+    Path(plot_id): Path<u32>,
+    Json(request): Json<PlotRequest>,
+) -> Response {
+    let player_id = match session_player(&app_state, &request) {
+        Ok(player_id) => player_id,
+        Err(e) => return e.into_response(),
+    };
+    match app_state.game_state.write().await.visit_plot(&player_id, plot_id) {
+        Ok((x, y)) => Json(json!({ "plot_id": plot_id, "x": x, "y": y })).into_response(),
+        Err(e) => error_response(e),
+    }
+}
@@ -0,0 +1,63 @@
+use axum::extract::{Path, State};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use serde::Deserialize;
+use game_core::config::WallConfig;
+use game_core::{MapEdit, MapEditError, PlatformConfig};
+use crate::error::ApiError;
+use crate::state::AppState;
+
+#[derive(Deserialize)]
+pub struct MoveRequest {
+    #[serde(default)]
+    pub dx: f32,
+    #[serde(default)]
+    pub dy: f32,
+}
+
+/// Apply an edit and reply with the map's new geometry; clients get it on the next tick
+async fn edit(app_state: &AppState, edit: MapEdit, status: StatusCode) -> Response {
+    let description = format!("{:?}", edit);
+    let mut game_state = app_state.game_state.write().await;
+    match game_state.edit_map(edit) {
+        Ok(geometry) => {
+            eprintln!("🛠️ [ADMIN] Map edit: {}", description);
+            (status, Json(geometry)).into_response()
+        }
+        Err(e) => {
+            let error = match e {
+                MapEditError::DuplicateId(_) => ApiError::Conflict(e.to_string()),
+                MapEditError::UnknownObject(_) => ApiError::NotFound(e.to_string()),
+                MapEditError::Invalid(errors) => ApiError::InvalidGeometry(errors.0.iter().map(|e| e.to_string()).collect()),
+            };
+            error.into_response()
+        }
+    }
+}
+
+pub async fn add_platform(State(app_state): State<AppState>, Json(platform): Json<PlatformConfig>) -> Response {
+    edit(&app_state, MapEdit::AddPlatform(platform), StatusCode::CREATED).await
+}
+
+pub async fn add_wall(State(app_state): State<AppState>, Json(wall): Json<WallConfig>) -> Response {
+    edit(&app_state, MapEdit::AddWall(wall), StatusCode::CREATED).await
+}
+
+/// Shift a platform or wall by dx and dy
+pub async fn move_object(
+    State(app_state): State<AppState>,
+    Path(id): Path<String>,
+    Json(request): Json<MoveRequest>,
+) -> Response {
+    let edit_request = MapEdit::Move {
+        id,
+        dx: request.dx,
+        dy: request.dy,
+    };
+    edit(&app_state, edit_request, StatusCode::OK).await
+}
+
+pub async fn delete_object(State(app_state): State<AppState>, Path(id): Path<String>) -> Response {
+    edit(&app_state, MapEdit::Delete { id }, StatusCode::OK).await
+}
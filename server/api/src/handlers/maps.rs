@@ -0,0 +1,65 @@
+use axum::extract::State;
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use serde::Deserialize;
+use serde_json::json;
+use game_core::{GameState, MapChange, VoteError};
+use crate::error::ApiError;
+use crate::state::AppState;
+
+#[derive(Deserialize)]
+pub struct MapVoteRequest {
+    pub player_id: uuid::Uuid,
+    /// Id or display name of the map
+    pub map: String,
+    /// Token from init_player; it must belong to player_id
+    #[serde(default)]
+    pub session_token: Option<String>,
+}
+
+/// The map being played, or None when no rotation is configured
+pub fn current_map(game_state: &GameState) -> Option<MapChange> {
+    let config = game_state.world.config();
+    config.maps.get(game_state.maps.current()).map(|map| MapChange {
+        id: map.id.clone(),
+        name: map.display_name().to_string(),
+    })
+}
+
+/// The rotation with vote counts for the next map
+pub async fn list_maps(State(app_state): State<AppState>) -> impl IntoResponse {
+    let game_state = app_state.game_state.read().await;
+    let rotation = &game_state.world.config().map_rotation;
+    Json(json!({
+        "current": current_map(&game_state),
+        "voting": rotation.voting,
+        "matches_per_map": rotation.matches_per_map,
+        "maps": game_state.map_votes(),
+    }))
+}
+
+/// Vote for the map to play after the current match
+pub async fn vote_map(
+    State(app_state): State<AppState>,
+    Json(request): Json<MapVoteRequest>,
+) -> Response {
+    if let Err(status) = crate::handlers::game::check_session(&app_state, request.player_id, request.session_token.as_deref()) {
+        return status.into_response();
+    }
+
+    let mut game_state = app_state.game_state.write().await;
+    match game_state.vote_map(&request.player_id, &request.map) {
+        Ok(map) => {
+            eprintln!("🗳️ Player {} voted for map {}", request.player_id, map);
+            Json(json!({ "map": map, "maps": game_state.map_votes() })).into_response()
+        }
+        Err(e) => {
+            let reason = e.to_string();
+            let error = match e {
+                VoteError::Disabled | VoteError::UnknownPlayer => ApiError::NotFound(reason),
+                VoteError::UnknownMap => ApiError::BadRequest(reason),
+            };
+            error.into_response()
+        }
+    }
+}
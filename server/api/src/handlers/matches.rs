@@ -0,0 +1,31 @@
+use axum::extract::{Query, State};
+use axum::response::Json;
+use serde::Deserialize;
+use crate::state::AppState;
+
+/// Page size when the client doesn't specify a limit
+const DEFAULT_LIMIT: usize = 20;
+
+#[derive(Deserialize)]
+pub struct MatchesQuery {
+    /// Only matches this player took part in
+    pub player: Option<uuid::Uuid>,
+    /// Only matches played in this mode
+    pub mode: Option<String>,
+    pub limit: Option<usize>,
+    pub offset: Option<usize>,
+}
+
+/// List completed matches, newest first, with offset-based pagination
+pub async fn list_matches(
+    State(app_state): State<AppState>,
+    Query(query): Query<MatchesQuery>,
+) -> impl axum::response::IntoResponse {
+    let query = game_core::MatchQuery {
+        player: query.player,
+        mode: query.mode,
+        offset: query.offset.unwrap_or(0),
+        limit: query.limit.unwrap_or(DEFAULT_LIMIT),
+    };
+    Json(app_state.match_history.read().await.query(&query))
+}
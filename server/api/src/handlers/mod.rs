@@ -1,15 +1,43 @@
 pub mod health;
+pub mod affinity;
+pub mod backplane;
 pub mod events;
 pub mod chat;
 pub mod game;
 pub mod config;
+pub mod geometry;
+pub mod matches;
+pub mod challenges;
+pub mod time;
+pub mod admin;
+pub mod bots;
+pub mod settings;
+pub mod names;
+pub mod teams;
+pub mod map_editor;
+pub mod maps;
+pub mod modes;
+pub mod mutators;
+pub mod overlay;
+pub mod replays;
+pub mod signals;
+pub mod cosmetics;
+pub mod privacy;
+pub mod snapshots;
+pub mod housing;
+pub mod world_events;
+pub mod state_transport;
+pub mod rooms;
+pub mod docs;
 
 use axum::response::IntoResponse;
 
 /// Serves index.html for SPA routing fallback
-pub async fn serve_index_html() -> axum::response::Response {
+/// Under a base path it gains a `<base>` tag, which the client resolves its URLs against
+pub async fn serve_index_html(base_path: &str) -> axum::response::Response {
     match std::fs::read_to_string("client/dist/index.html") {
-        Ok(html) => axum::response::Html(html).into_response(),
+        Ok(html) if base_path.is_empty() => axum::response::Html(html).into_response(),
+        Ok(html) => axum::response::Html(with_base(&html, base_path)).into_response(),
         Err(_) => {
             let body = axum::body::Body::from("Not found");
             axum::response::Response::builder()
@@ -21,3 +49,33 @@ pub async fn serve_index_html() -> axum::response::Response {
     }
 }
 
+/// index.html with `<base href="{base_path}/">` as the first thing in its head and its
+/// root-relative `src` and `href` attributes (the built scripts, styles and icons) moved
+/// under the base path
+pub fn with_base(html: &str, base_path: &str) -> String {
+    let mut html = html.to_string();
+    for attribute in ["src", "href"] {
+        let root = format!("{}=\"/", attribute);
+        let mut rewritten = String::with_capacity(html.len());
+        let mut rest = html.as_str();
+        while let Some(at) = rest.find(&root) {
+            let (before, after) = rest.split_at(at + root.len() - 1);
+            rewritten.push_str(before);
+            // Protocol-relative URLs ("//cdn...") point at other hosts
+            if !after.starts_with("//") {
+                rewritten.push_str(base_path);
+            }
+            rest = after;
+        }
+        rewritten.push_str(rest);
+        html = rewritten;
+    }
+    let tag = format!("<base href=\"{}/\">", base_path);
+    match html.find("<head>") {
+        Some(at) => {
+            let at = at + "<head>".len();
+            format!("{}\n  {}{}", &html[..at], tag, &html[at..])
+        }
+        None => format!("{}{}", tag, html),
+    }
+}
@@ -0,0 +1,49 @@
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use serde::Deserialize;
+use serde_json::json;
+use game_core::ModeSwitch;
+use crate::error::ApiError;
+use crate::state::AppState;
+
+#[derive(Deserialize)]
+pub struct ModeRequest {
+    pub mode: String,
+}
+
+/// The active mode, any mode waiting for the round to end, and every configured mode
+pub async fn list_modes(State(app_state): State<AppState>) -> impl IntoResponse {
+    let game_state = app_state.game_state.read().await;
+    let modes: Vec<_> = app_state
+        .game_config
+        .modes
+        .iter()
+        .map(|mode| json!({ "id": mode.id, "name": mode.display_name(), "description": mode.description }))
+        .collect();
+    Json(json!({
+        "active": game_state.mode(),
+        "pending": game_state.pending_mode(),
+        "modes": modes,
+    }))
+}
+
+/// Switch the game mode without a restart; a round in progress finishes under the old mode
+pub async fn switch_mode(
+    State(app_state): State<AppState>,
+    Json(request): Json<ModeRequest>,
+) -> Response {
+    let mut game_state = app_state.game_state.write().await;
+    match game_state.request_mode(&request.mode) {
+        Ok(ModeSwitch::Applied) => {
+            eprintln!("🎮 [ADMIN] Switched game mode to {}", request.mode);
+            Json(json!({ "applied": true, "mode": game_state.mode() })).into_response()
+        }
+        Ok(ModeSwitch::Pending) => {
+            eprintln!("🎮 [ADMIN] Game mode {} will apply when the current round ends", request.mode);
+            (StatusCode::ACCEPTED, Json(json!({ "applied": false, "pending": request.mode }))).into_response()
+        }
+        Err(e) => ApiError::NotFound(e.to_string()).into_response(),
+    }
+}
@@ -0,0 +1,84 @@
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use serde::Deserialize;
+use serde_json::json;
+use game_core::{ModeSwitch, Mutator, MutatorError};
+use crate::error::ApiError;
+use crate::state::AppState;
+
+#[derive(Deserialize)]
+pub struct MutatorVoteRequest {
+    pub player_id: uuid::Uuid,
+    /// Id or display name of the mutator
+    pub mutator: String,
+    /// Token from init_player; it must belong to player_id
+    #[serde(default)]
+    pub session_token: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct MutatorPickRequest {
+    /// Ids or display names; empty plays the next round without mutators
+    pub mutators: Vec<String>,
+}
+
+/// Mutators in effect, any admin pick for the next round, and every mutator's votes
+pub async fn list_mutators(State(app_state): State<AppState>) -> impl IntoResponse {
+    let game_state = app_state.game_state.read().await;
+    Json(json!({
+        "active": game_state.active_mutators(),
+        "picked": game_state.mutators.picked(),
+        "voting": game_state.world.config().mutators.voting,
+        "mutators": game_state.mutator_votes(),
+    }))
+}
+
+/// Vote for a mutator to play the next round with
+pub async fn vote_mutator(
+    State(app_state): State<AppState>,
+    Json(request): Json<MutatorVoteRequest>,
+) -> Response {
+    if let Err(status) = crate::handlers::game::check_session(&app_state, request.player_id, request.session_token.as_deref()) {
+        return status.into_response();
+    }
+
+    let mut game_state = app_state.game_state.write().await;
+    match game_state.vote_mutator(&request.player_id, &request.mutator) {
+        Ok(mutator) => {
+            eprintln!("🗳️ Player {} voted for mutator {}", request.player_id, mutator.id());
+            Json(json!({ "mutator": mutator, "mutators": game_state.mutator_votes() })).into_response()
+        }
+        Err(e) => {
+            let reason = e.to_string();
+            let error = match e {
+                MutatorError::Disabled | MutatorError::UnknownPlayer => ApiError::NotFound(reason),
+                MutatorError::UnknownMutator => ApiError::BadRequest(reason),
+            };
+            error.into_response()
+        }
+    }
+}
+
+/// Choose the next round's mutators, overriding votes; applies at once when matches are off
+pub async fn pick_mutators(
+    State(app_state): State<AppState>,
+    Json(request): Json<MutatorPickRequest>,
+) -> Response {
+    let mutators: Option<Vec<Mutator>> = request.mutators.iter().map(|name| Mutator::parse(name)).collect();
+    let Some(mutators) = mutators else {
+        return ApiError::BadRequest(MutatorError::UnknownMutator.to_string()).into_response();
+    };
+    let mut game_state = app_state.game_state.write().await;
+    match game_state.pick_mutators(mutators.clone()) {
+        ModeSwitch::Applied => {
+            eprintln!("🧪 [ADMIN] Applied mutators {:?}", mutators);
+            Json(json!({ "applied": true, "mode": game_state.mode() })).into_response()
+        }
+        ModeSwitch::Pending => {
+            eprintln!("🧪 [ADMIN] Mutators {:?} will apply when the next round starts", mutators);
+            (StatusCode::ACCEPTED, Json(json!({ "applied": false, "pending": mutators }))).into_response()
+        }
+    }
+}
@@ -0,0 +1,120 @@
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use serde::Deserialize;
+use serde_json::json;
+use game_core::{GameState, ReservationError};
+use crate::error::ApiError;
+use crate::state::AppState;
+
+#[derive(Deserialize)]
+pub struct ReservationRequest {
+    /// Token from init_player; the reservation belongs to the player it was issued for
+    pub session_token: String,
+}
+
+/// The name a player gets when asking for `desired`
+/// Asking for a name someone else reserved is logged as an impersonation attempt and
+/// answered with the name and a number on the end
+pub(crate) async fn claim_name(app_state: &AppState, game_state: &GameState, player_id: uuid::Uuid, desired: &str) -> String {
+    let reservations = app_state.name_reservations.read().await;
+    let desired = desired.trim();
+    if !reservations.reserved_for_other(desired, &player_id) {
+        return desired.to_string();
+    }
+    let max_length = game_state.world.config().names.max_length;
+    let name = reservations.usable_name(&player_id, desired, max_length, |candidate| {
+        game_state
+            .players
+            .values()
+            .any(|p| p.id != player_id && p.name.to_lowercase() == candidate.to_lowercase())
+    });
+    eprintln!("🎭 {} asked for {:?}, reserved by another player; named {:?} instead", player_id, desired, name);
+    name
+}
+
+/// Give a newly joined player their reserved name, or a suffixed one if the name they
+/// were generated is someone else's
+pub(crate) async fn restore_name(app_state: &AppState, game_state: &mut GameState, player_id: uuid::Uuid) {
+    let Some(current) = game_state.players.get(&player_id).map(|p| p.name.clone()) else {
+        return;
+    };
+    let reserved = app_state.name_reservations.read().await.get(&player_id).map(str::to_string);
+    let name = match reserved {
+        Some(reserved) => {
+            let taken = game_state
+                .players
+                .values()
+                .any(|p| p.id != player_id && p.name.to_lowercase() == reserved.to_lowercase());
+            if taken {
+                return;
+            }
+            reserved
+        }
+        None => claim_name(app_state, game_state, player_id, &current).await,
+    };
+    if let Some(player) = game_state.players.get_mut(&player_id) {
+        player.name = name;
+    }
+}
+
+/// Reserve the player's current display name so guests can't take it
+/// Only players who registered a name through /api/player/register can reserve one
+pub async fn reserve_name(
+    State(app_state): State<AppState>,
+    Json(request): Json<ReservationRequest>,
+) -> Response {
+    let Some(player_id) = app_state.sessions.verify(&request.session_token) else {
+        return ApiError::InvalidSession.into_response();
+    };
+    let Some((name, registered)) = app_state
+        .game_state
+        .read()
+        .await
+        .players
+        .get(&player_id)
+        .map(|p| (p.name.clone(), p.registered))
+    else {
+        return ApiError::UnknownPlayer.into_response();
+    };
+    if !registered {
+        return ApiError::Forbidden("only registered players can reserve a name".to_string()).into_response();
+    }
+
+    let max_reservations = app_state.game_config.names.max_reservations;
+    let mut reservations = app_state.name_reservations.write().await;
+    if let Err(e) = reservations.check(&player_id, &name, max_reservations) {
+        let reason = e.to_string();
+        let error = match e {
+            ReservationError::Reserved => ApiError::Conflict(reason),
+            ReservationError::Indistinct => ApiError::BadRequest(reason),
+            ReservationError::Full => ApiError::Unavailable(reason),
+        };
+        return error.into_response();
+    }
+    if let Err(e) = reservations.reserve(player_id, &name).await {
+        eprintln!("❌ Failed to save name reservation for {}: {}", player_id, e);
+        return ApiError::Internal("failed to save the reservation".to_string()).into_response();
+    }
+    eprintln!("📛 {} reserved the name {:?}", player_id, name);
+    Json(json!({ "name": name })).into_response()
+}
+
+/// Give up the player's reserved name
+pub async fn release_name(
+    State(app_state): State<AppState>,
+    Json(request): Json<ReservationRequest>,
+) -> Response {
+    let Some(player_id) = app_state.sessions.verify(&request.session_token) else {
+        return ApiError::InvalidSession.into_response();
+    };
+    match app_state.name_reservations.write().await.remove(&player_id).await {
+        Ok(true) => StatusCode::NO_CONTENT.into_response(),
+        Ok(false) => ApiError::NotFound("player has no reserved name".to_string()).into_response(),
+        Err(e) => {
+            eprintln!("❌ Failed to save name reservations after {} released theirs: {}", player_id, e);
+            ApiError::Internal("failed to save the reservations".to_string()).into_response()
+        }
+    }
+}
@@ -0,0 +1,69 @@
+use axum::extract::{ConnectInfo, State};
+use axum::http::{HeaderMap, StatusCode};
+use axum::response::sse::{Event, KeepAlive, Sse};
+use axum::response::{Html, IntoResponse, Response};
+use datastar::consts::ElementPatchMode;
+use futures::stream::Stream;
+use game_core::OverlayStats;
+use std::convert::Infallible;
+use std::net::SocketAddr;
+use crate::handlers::events::elements_event;
+use crate::overlay::{overlay_html, page_html, OVERLAY_SELECTOR};
+use crate::state::AppState;
+use crate::GameUpdate;
+
+/// Page for a broadcast tool's browser source
+pub async fn overlay_page() -> Html<String> {
+    Html(page_html())
+}
+
+/// Aggregate stats re-rendered at the overlay rate, independent of the game's tick rate
+pub async fn overlay_events(
+    State(app_state): State<AppState>,
+    ConnectInfo(peer): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
+) -> Result<Sse<impl Stream<Item = Result<Event, Infallible>>>, Response> {
+    if app_state.shutting_down.load(std::sync::atomic::Ordering::SeqCst) {
+        return Err(StatusCode::SERVICE_UNAVAILABLE.into_response());
+    }
+    let ip = app_state.client_ip.resolve(&headers, peer);
+    let Some(connection_guard) = app_state.ip_limiter.try_connect(ip) else {
+        return Err(StatusCode::TOO_MANY_REQUESTS.into_response());
+    };
+
+    let config = &app_state.game_config.overlay;
+    let interval_ms = (1000.0 / config.rate_hz.max(0.1)) as u64;
+    let columns = config.density_columns;
+    let initial = {
+        let game_state = app_state.game_state.read().await;
+        OverlayStats::of(&game_state, app_state.clock.unix_millis(), columns)
+    };
+    let mut game_rx = app_state.game_tx.subscribe();
+
+    let stream = async_stream::stream! {
+        let _connection_guard = connection_guard;
+        yield Ok(elements_event(overlay_html(&initial), OVERLAY_SELECTOR, ElementPatchMode::Outer));
+
+        let mut last_render_ms = 0;
+        loop {
+            match game_rx.recv().await {
+                Ok(GameUpdate::StateUpdate { state, server_time_ms }) => {
+                    if server_time_ms.saturating_sub(last_render_ms) < interval_ms {
+                        continue;
+                    }
+                    last_render_ms = server_time_ms;
+                    let stats = OverlayStats::of(&state, server_time_ms, columns);
+                    yield Ok(elements_event(overlay_html(&stats), OVERLAY_SELECTOR, ElementPatchMode::Outer));
+                }
+                Ok(GameUpdate::ServerShutdown { seconds_remaining: 0 }) => break,
+                Ok(_) => {}
+                // A slow overlay just skips the states it missed
+                Err(tokio::sync::broadcast::error::RecvError::Lagged(_)) => {}
+                Err(tokio::sync::broadcast::error::RecvError::Closed) => break,
+            }
+        }
+    };
+
+    let keepalive = std::time::Duration::from_secs(app_state.game_config.presence.keepalive_secs.max(1));
+    Ok(Sse::new(stream).keep_alive(KeepAlive::new().interval(keepalive)))
+}
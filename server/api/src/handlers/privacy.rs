@@ -0,0 +1,135 @@
+use axum::extract::{Path, State};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Json, Response};
+use serde_json::json;
+use crate::privacy::Tombstone;
+use crate::state::AppState;
+use crate::GameUpdate;
+
+/// Everything stored about a player id: live state, settings, reserved name, cosmetics, matches,
+/// recent chat and moderation records
+pub async fn export_player_data(
+    State(app_state): State<AppState>,
+    Path(player_id): Path<uuid::Uuid>,
+) -> Response {
+    let player = app_state.game_state.read().await.players.get(&player_id).cloned();
+    let settings = app_state.player_settings.read().await.get(&player_id).cloned();
+    let reserved_name = app_state.name_reservations.read().await.get(&player_id).map(str::to_string);
+    let cosmetics = app_state.cosmetics.read().await.profile(&player_id);
+    let matches = app_state.match_history.read().await.player_matches(&player_id);
+    let chat: Vec<_> = app_state
+        .chat_history
+        .read()
+        .await
+        .iter()
+        .filter(|message| message.player_id == player_id)
+        .cloned()
+        .collect();
+    let flags: Vec<_> = app_state
+        .moderator
+        .state
+        .read()
+        .await
+        .flags
+        .iter()
+        .filter(|flag| flag.player_id == player_id)
+        .cloned()
+        .collect();
+    let muted_for_secs = app_state.moderator.mute_remaining(&player_id).await.map(|d| d.as_secs());
+    let deleted_at = app_state.tombstones.read().await.get(&player_id).map(|t| t.deleted_at);
+
+    eprintln!("📦 [ADMIN] Exported data for {}", player_id);
+    Json(json!({
+        "player_id": player_id,
+        "exported_at": app_state.clock.unix_secs(),
+        "deleted_at": deleted_at,
+        "player": player,
+        "settings": settings,
+        "reserved_name": reserved_name,
+        "cosmetics": cosmetics,
+        "matches": matches,
+        "chat": chat,
+        "moderation": {
+            "muted_for_secs": muted_for_secs,
+            "flags": flags,
+        },
+    }))
+    .into_response()
+}
+
+/// Erase a player's data from every store and leave a tombstone in its place
+/// Match records keep their scores under an anonymous participant, and an active mute
+/// is carried by the tombstone so the same id can't come back unmuted
+pub async fn delete_player_data(
+    State(app_state): State<AppState>,
+    Path(player_id): Path<uuid::Uuid>,
+) -> Response {
+    let removed_player = {
+        let mut game_state = app_state.game_state.write().await;
+        let name = game_state.players.get(&player_id).map(|p| p.name.clone());
+        game_state.remove_player(&player_id);
+        name
+    };
+    if let Some(player_name) = &removed_player {
+        let _ = app_state.game_tx.send(GameUpdate::PlayerLeft {
+            player_id,
+            player_name: player_name.clone(),
+        });
+    }
+    app_state.sessions.forget(&player_id);
+
+    let settings = match app_state.player_settings.write().await.remove(&player_id).await {
+        Ok(removed) => removed,
+        Err(e) => return storage_failure(player_id, "settings", e),
+    };
+    let name_reservation = match app_state.name_reservations.write().await.remove(&player_id).await {
+        Ok(removed) => removed,
+        Err(e) => return storage_failure(player_id, "name reservation", e),
+    };
+    let cosmetics = {
+        let mut store = app_state.cosmetics.write().await;
+        let removed = store.remove(&player_id);
+        if let Err(e) = store.save().await {
+            return storage_failure(player_id, "cosmetics", e);
+        }
+        removed
+    };
+    let matches = match app_state.match_history.write().await.anonymize(&player_id).await {
+        Ok(changed) => changed,
+        Err(e) => return storage_failure(player_id, "match history", e),
+    };
+    let chat_messages = {
+        let mut history = app_state.chat_history.write().await;
+        let before = history.len();
+        history.retain(|message| message.player_id != player_id);
+        before - history.len()
+    };
+    let flags = app_state.moderator.state.write().await.forget(&player_id);
+
+    let now = app_state.clock.unix_secs();
+    let muted_until = app_state.moderator.mute_remaining(&player_id).await.map(|d| now + d.as_secs().max(1));
+    let tombstone = Tombstone { deleted_at: now, muted_until };
+    if let Err(e) = app_state.tombstones.write().await.record(&player_id, tombstone).await {
+        return storage_failure(player_id, "tombstones", e);
+    }
+
+    eprintln!("🗑️ [ADMIN] Deleted data for {}", player_id);
+    Json(json!({
+        "player_id": player_id,
+        "deleted": {
+            "player": removed_player.is_some(),
+            "settings": settings,
+            "name_reservation": name_reservation,
+            "cosmetics": cosmetics,
+            "matches": matches,
+            "chat_messages": chat_messages,
+            "flags": flags,
+        },
+    }))
+    .into_response()
+}
+
+fn storage_failure(player_id: uuid::Uuid, store: &str, e: Box<dyn std::error::Error>) -> Response {
+    eprintln!("❌ Failed to delete {} for {}: {}", store, player_id, e);
+    StatusCode::INTERNAL_SERVER_ERROR.into_response()
+}
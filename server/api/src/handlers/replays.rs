@@ -0,0 +1,159 @@
+use axum::extract::{ConnectInfo, Path, Query, State};
+use axum::http::{header, HeaderMap, StatusCode};
+use axum::response::sse::{Event, KeepAlive, Sse};
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use futures::stream::Stream;
+use game_core::signals::{Signal, SignalPatch};
+use serde::Deserialize;
+use serde_json::json;
+use std::convert::Infallible;
+use std::net::SocketAddr;
+use crate::handlers::events::signals_event;
+use crate::error::ApiError;
+use crate::replay_jobs::{JobStatus, ReplayError};
+use crate::state::AppState;
+
+fn error_response(error: ReplayError) -> Response {
+    let reason = error.to_string();
+    let error = match error {
+        ReplayError::Disabled | ReplayError::NotFound => ApiError::NotFound(reason),
+        ReplayError::Corrupt(_) => ApiError::Internal(reason),
+        ReplayError::Busy => ApiError::Unavailable(reason),
+    };
+    error.into_response()
+}
+
+/// Ids of every recorded match
+pub async fn list_replays(State(app_state): State<AppState>) -> Response {
+    match app_state.replays.list().await {
+        Ok(ids) => Json(json!({ "replays": ids })).into_response(),
+        Err(e) => error_response(e),
+    }
+}
+
+/// Start summarizing a replay in the background; poll the returned job for progress
+pub async fn request_summary(State(app_state): State<AppState>, Path(replay_id): Path<uuid::Uuid>) -> Response {
+    match app_state.replays.request(replay_id).await {
+        Ok(job) => (StatusCode::ACCEPTED, Json(job)).into_response(),
+        Err(e) => error_response(e),
+    }
+}
+
+/// Download a finished summary; while its job is still going, the job is returned instead
+pub async fn get_summary(State(app_state): State<AppState>, Path(replay_id): Path<uuid::Uuid>) -> Response {
+    if let Some(rendered) = app_state.replays.summary(&replay_id) {
+        let disposition = format!("attachment; filename=\"replay-{}-summary.json\"", replay_id);
+        return ([(header::CONTENT_DISPOSITION, disposition)], Json(&rendered.summary)).into_response();
+    }
+    match app_state.replays.latest_job(&replay_id) {
+        Some(job) if matches!(job.status, JobStatus::Failed { .. }) => {
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(job)).into_response()
+        }
+        Some(job) => (StatusCode::ACCEPTED, Json(job)).into_response(),
+        None => ApiError::NotFound("no summary yet; POST to this URL to make one".to_string()).into_response(),
+    }
+}
+
+/// Every player's path over the map, when the summary was rendered with one
+pub async fn get_trajectory(State(app_state): State<AppState>, Path(replay_id): Path<uuid::Uuid>) -> Response {
+    match app_state.replays.summary(&replay_id).and_then(|rendered| rendered.svg.clone()) {
+        Some(svg) => ([(header::CONTENT_TYPE, "image/svg+xml")], svg).into_response(),
+        None => ApiError::NotFound("no trajectory rendered for this replay".to_string()).into_response(),
+    }
+}
+
+pub async fn get_job(State(app_state): State<AppState>, Path(job_id): Path<uuid::Uuid>) -> Response {
+    match app_state.replays.job(&job_id) {
+        Some(job) => Json(job).into_response(),
+        None => ApiError::NotFound("no such job".to_string()).into_response(),
+    }
+}
+
+/// Download the binary log of every command and snapshot in a match
+pub async fn get_log(State(app_state): State<AppState>, Path(replay_id): Path<uuid::Uuid>) -> Response {
+    match app_state.replays.log_bytes(&replay_id).await {
+        Ok(bytes) => {
+            let disposition = format!("attachment; filename=\"replay-{}.rlog\"", replay_id);
+            ([(header::CONTENT_TYPE, "application/octet-stream".to_string()), (header::CONTENT_DISPOSITION, disposition)], bytes)
+                .into_response()
+        }
+        Err(e) => error_response(e),
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PlaybackQuery {
+    /// Multiple of real time; 1 plays the match as it happened
+    pub speed: Option<f32>,
+}
+
+/// Stream a recorded match to a spectator as if it were live, at original or faster speed
+pub async fn playback_events(
+    State(app_state): State<AppState>,
+    Path(replay_id): Path<uuid::Uuid>,
+    Query(query): Query<PlaybackQuery>,
+    ConnectInfo(peer): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
+) -> Result<Sse<impl Stream<Item = Result<Event, Infallible>>>, Response> {
+    if app_state.shutting_down.load(std::sync::atomic::Ordering::SeqCst) {
+        return Err(StatusCode::SERVICE_UNAVAILABLE.into_response());
+    }
+    let max_speed = app_state.game_config.replays.max_playback_speed.max(1.0);
+    let speed = match query.speed {
+        None => 1.0,
+        Some(speed) if speed.is_finite() && speed > 0.0 => speed.clamp(0.1, max_speed),
+        Some(_) => {
+            return Err(ApiError::BadRequest("speed must be a positive number".to_string()).into_response())
+        }
+    };
+    let log = app_state.replays.load_log(&replay_id).await.map_err(error_response)?;
+    let ip = app_state.client_ip.resolve(&headers, peer);
+    let Some(connection_guard) = app_state.ip_limiter.try_connect(ip) else {
+        return Err(StatusCode::TOO_MANY_REQUESTS.into_response());
+    };
+
+    let duration_secs = log.duration_secs();
+    let mode = log.header.mode.clone();
+    let progress = move |t: f32, finished: bool| {
+        json!({
+            "replay_id": replay_id,
+            "mode": mode,
+            "t": t,
+            "duration_secs": duration_secs,
+            "speed": speed,
+            "finished": finished,
+        })
+    };
+    let geometry = log.header.geometry.clone();
+    let frames = log.frames();
+    eprintln!("📼 Playing back replay {} at {}x ({} frames)", replay_id, speed, frames.len());
+
+    let stream = async_stream::stream! {
+        let _connection_guard = connection_guard;
+        yield Ok(signals_event(
+            SignalPatch::new()
+                .with(Signal::Playback, progress(0.0, false))
+                .with(Signal::MapGeometry, geometry),
+        ));
+
+        let mut last_t = 0.0;
+        for frame in frames {
+            let wait = ((frame.t - last_t) / speed).max(0.0);
+            last_t = frame.t;
+            if wait > 0.0 {
+                tokio::time::sleep(std::time::Duration::from_secs_f32(wait)).await;
+            }
+            yield Ok(signals_event(
+                SignalPatch::new()
+                    .with(Signal::GameState, &frame.players)
+                    .with(Signal::Tick, frame.tick)
+                    .with(Signal::Playback, progress(frame.t, false)),
+            ));
+        }
+        yield Ok(signals_event(SignalPatch::new().with(Signal::Playback, progress(duration_secs, true))));
+    };
+
+    let keepalive = std::time::Duration::from_secs(app_state.game_config.presence.keepalive_secs.max(1));
+    Ok(Sse::new(stream).keep_alive(KeepAlive::new().interval(keepalive)))
+}
@@ -0,0 +1,71 @@
+use axum::extract::{Path, State};
+use axum::http::HeaderMap;
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use serde_json::json;
+use game_core::PhysicsPatchError;
+use crate::error::ApiError;
+use crate::state::AppState;
+
+/// Room id of a server outside a cluster
+pub const DEFAULT_ROOM_ID: &str = "main";
+
+/// Each server hosts one room, named after its cluster instance
+pub fn room_id(app_state: &AppState) -> &str {
+    app_state.affinity.local().map_or(DEFAULT_ROOM_ID, |instance| instance.id.as_str())
+}
+
+/// The room's physics and the bounds its creator may change them within
+pub async fn get_physics(State(app_state): State<AppState>, Path(id): Path<String>) -> Response {
+    if id != room_id(&app_state) {
+        return ApiError::NotFound("no such room".to_string()).into_response();
+    }
+    let game_state = app_state.game_state.read().await;
+    let config = game_state.world.config();
+    Json(json!({
+        "room": id,
+        "config_version": game_state.config_version(),
+        "creator": game_state.room_creator,
+        "physics": config.physics,
+        "sandbox": config.physics_sandbox,
+    }))
+    .into_response()
+}
+
+/// Change the room's physics live; allowed for its creator's session token or an admin token
+/// The body maps physics setting names to new values, e.g. `{ "gravity": -60 }`
+pub async fn patch_physics(
+    State(app_state): State<AppState>,
+    Path(id): Path<String>,
+    headers: HeaderMap,
+    Json(changes): Json<serde_json::Map<String, serde_json::Value>>,
+) -> Response {
+    if id != room_id(&app_state) {
+        return ApiError::NotFound("no such room".to_string()).into_response();
+    }
+    let Some(token) = crate::handlers::admin::bearer_token(&headers) else {
+        return ApiError::Unauthorized.into_response();
+    };
+    let admin = crate::handlers::admin::is_admin(&app_state, &headers);
+    let mut game_state = app_state.game_state.write().await;
+    let creator = game_state.room_creator.filter(|creator| app_state.sessions.verify(token) == Some(*creator));
+    if !admin && creator.is_none() {
+        eprintln!("🚫 Rejected physics change for room {} from someone other than its creator", id);
+        return ApiError::Forbidden("only the room's creator or an admin can change its physics".to_string()).into_response();
+    }
+    match game_state.patch_physics(&changes) {
+        Ok(physics) => {
+            let by = creator.map_or_else(|| "an admin".to_string(), |creator| creator.to_string());
+            let fields: Vec<&String> = changes.keys().collect();
+            eprintln!("🧪 Physics of room {} changed by {}: {:?}", id, by, fields);
+            Json(json!({
+                "room": id,
+                "config_version": game_state.config_version(),
+                "physics": physics,
+            }))
+            .into_response()
+        }
+        Err(e @ PhysicsPatchError::Disabled) => ApiError::Forbidden(e.to_string()).into_response(),
+        Err(e) => ApiError::BadRequest(e.to_string()).into_response(),
+    }
+}
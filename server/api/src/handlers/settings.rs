@@ -0,0 +1,69 @@
+use axum::extract::{Query, State};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use serde::Deserialize;
+use serde_json::json;
+use game_core::{PlayerSettings, SettingsError};
+use crate::error::ApiError;
+use crate::state::AppState;
+
+#[derive(Deserialize)]
+pub struct SettingsQuery {
+    /// Token from init_player; settings belong to the player it was issued for
+    pub session_token: String,
+}
+
+#[derive(Deserialize)]
+pub struct SettingsRequest {
+    pub session_token: String,
+    pub settings: serde_json::Value,
+}
+
+/// The player's stored client settings, or an empty object if they have none
+pub async fn get_settings(
+    State(app_state): State<AppState>,
+    Query(query): Query<SettingsQuery>,
+) -> Response {
+    let Some(player_id) = app_state.sessions.verify(&query.session_token) else {
+        return ApiError::InvalidSession.into_response();
+    };
+    let settings = app_state
+        .player_settings
+        .read()
+        .await
+        .get(&player_id)
+        .cloned()
+        .unwrap_or_else(|| json!({}));
+    Json(json!({ "settings": settings })).into_response()
+}
+
+/// Replace the player's client settings
+pub async fn put_settings(
+    State(app_state): State<AppState>,
+    Json(request): Json<SettingsRequest>,
+) -> Response {
+    let Some(player_id) = app_state.sessions.verify(&request.session_token) else {
+        return ApiError::InvalidSession.into_response();
+    };
+    if let Err(e) = PlayerSettings::validate(&request.settings, app_state.game_config.player_settings.max_bytes) {
+        let reason = e.to_string();
+        let error = match e {
+            SettingsError::NotAnObject => ApiError::BadRequest(reason),
+            SettingsError::TooLarge { .. } => ApiError::TooLarge(reason),
+        };
+        return error.into_response();
+    }
+
+    let result = app_state
+        .player_settings
+        .write()
+        .await
+        .set(player_id, request.settings)
+        .await;
+    if let Err(e) = result {
+        eprintln!("❌ Failed to save settings for {}: {}", player_id, e);
+        return ApiError::Internal("failed to save the settings".to_string()).into_response();
+    }
+    StatusCode::NO_CONTENT.into_response()
+}
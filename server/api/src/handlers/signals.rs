@@ -0,0 +1,7 @@
+use axum::response::{IntoResponse, Json};
+
+/// Names, types and descriptions of every signal the event stream can send,
+/// so clients can check the names they listen for against the server's
+pub async fn get_signals() -> impl IntoResponse {
+    Json(game_core::signals::manifest())
+}
@@ -0,0 +1,77 @@
+use axum::body::Bytes;
+use axum::extract::{Query, State};
+use axum::http::{header, HeaderMap};
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use serde::Deserialize;
+use serde_json::json;
+use game_core::{Encoding, SnapshotError, WorldSnapshot};
+use crate::error::ApiError;
+use crate::state::AppState;
+
+#[derive(Debug, Deserialize)]
+pub struct SnapshotQuery {
+    /// `json` (the default) or `msgpack`; on restore, overrides the request's content type
+    pub format: Option<String>,
+}
+
+fn unknown_format(name: &str) -> Response {
+    ApiError::BadRequest(format!("unknown snapshot format {}; use json or msgpack", name)).into_response()
+}
+
+/// Download the whole world, to restore later or on another server
+pub async fn take_snapshot(State(app_state): State<AppState>, Query(query): Query<SnapshotQuery>) -> Response {
+    let format = match query.format.as_deref() {
+        None => Encoding::default(),
+        Some(name) => match Encoding::parse(name) {
+            Some(format) => format,
+            None => return unknown_format(name),
+        },
+    };
+    let snapshot = app_state.game_state.read().await.snapshot();
+    eprintln!("💾 [ADMIN] Snapshot taken at tick {} ({} player(s))", snapshot.tick, snapshot.players.len());
+    let disposition = format!("attachment; filename=\"snapshot-{}.{}\"", snapshot.tick, format.extension());
+    (
+        [(header::CONTENT_TYPE, format.content_type().to_string()), (header::CONTENT_DISPOSITION, disposition)],
+        snapshot.encode(format),
+    )
+        .into_response()
+}
+
+/// Replace the world with a snapshot from `/api/admin/snapshot`; the format comes from
+/// `?format=` or the content type, defaulting to JSON
+pub async fn restore_snapshot(
+    State(app_state): State<AppState>,
+    Query(query): Query<SnapshotQuery>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Response {
+    let format = match query.format.as_deref() {
+        Some(name) => match Encoding::parse(name) {
+            Some(format) => format,
+            None => return unknown_format(name),
+        },
+        None => headers
+            .get(header::CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .and_then(Encoding::from_content_type)
+            .unwrap_or_default(),
+    };
+    let snapshot = match WorldSnapshot::decode(&body, format) {
+        Ok(snapshot) => snapshot,
+        Err(e) => return ApiError::BadRequest(e.to_string()).into_response(),
+    };
+    let (tick, players) = (snapshot.tick, snapshot.players.len());
+    let mut game_state = app_state.game_state.write().await;
+    match game_state.restore(snapshot) {
+        Ok(()) => {
+            eprintln!("💾 [ADMIN] Restored snapshot from tick {} ({} player(s))", tick, players);
+            Json(json!({ "tick": tick, "players": players, "mode": game_state.mode() })).into_response()
+        }
+        Err(e @ SnapshotError::UnknownMode(_)) => ApiError::Conflict(e.to_string()).into_response(),
+        Err(SnapshotError::InvalidGeometry(errors)) => {
+            ApiError::InvalidGeometry(errors.0.iter().map(|e| e.to_string()).collect()).into_response()
+        }
+        Err(e) => ApiError::BadRequest(e.to_string()).into_response(),
+    }
+}
@@ -0,0 +1,95 @@
+use axum::extract::ws::{Message, WebSocket, WebSocketUpgrade};
+use axum::extract::{Query, State};
+use axum::http::{header, HeaderMap};
+use axum::response::{IntoResponse, Response};
+use serde::Deserialize;
+use tokio::sync::broadcast::error::RecvError;
+use game_core::{Encoding, StateFrame};
+use crate::error::ApiError;
+use crate::state::AppState;
+use crate::GameUpdate;
+
+#[derive(Deserialize)]
+pub struct EncodingQuery {
+    /// "json" or "msgpack"; overrides the Accept header and the configured default
+    #[serde(default)]
+    pub encoding: Option<String>,
+}
+
+/// The encoding a client asked for, falling back to the configured one
+fn negotiate(app_state: &AppState, query: &EncodingQuery, headers: &HeaderMap) -> Result<Encoding, String> {
+    if let Some(name) = &query.encoding {
+        return Encoding::parse(name).ok_or_else(|| format!("unknown encoding {}", name));
+    }
+    let accepted = headers
+        .get(header::ACCEPT)
+        .and_then(|value| value.to_str().ok())
+        .and_then(Encoding::from_accept);
+    Ok(accepted.unwrap_or(app_state.game_config.state_transport.encoding))
+}
+
+/// The current state frame, as JSON or MessagePack
+pub async fn get_state(
+    State(app_state): State<AppState>,
+    Query(query): Query<EncodingQuery>,
+    headers: HeaderMap,
+) -> Response {
+    let encoding = match negotiate(&app_state, &query, &headers) {
+        Ok(encoding) => encoding,
+        Err(error) => return ApiError::BadRequest(error).into_response(),
+    };
+    let frame = StateFrame::from_state(&*app_state.game_state.read().await, app_state.clock.unix_millis());
+    ([(header::CONTENT_TYPE, encoding.content_type())], frame.encode(encoding)).into_response()
+}
+
+/// Stream a state frame per broadcast tick over a WebSocket
+/// JSON frames go out as text messages, MessagePack frames as binary ones
+pub async fn state_socket(
+    State(app_state): State<AppState>,
+    Query(query): Query<EncodingQuery>,
+    headers: HeaderMap,
+    upgrade: WebSocketUpgrade,
+) -> Response {
+    let encoding = match negotiate(&app_state, &query, &headers) {
+        Ok(encoding) => encoding,
+        Err(error) => return ApiError::BadRequest(error).into_response(),
+    };
+    upgrade.on_upgrade(move |socket| stream_states(app_state, socket, encoding))
+}
+
+async fn stream_states(app_state: AppState, mut socket: WebSocket, encoding: Encoding) {
+    let mut game_rx = app_state.game_tx.subscribe();
+    loop {
+        tokio::select! {
+            update = game_rx.recv() => {
+                let (state, server_time_ms) = match update {
+                    Ok(GameUpdate::StateUpdate { state, server_time_ms }) => (state, server_time_ms),
+                    Ok(GameUpdate::ServerShutdown { seconds_remaining: 0 }) => break,
+                    Ok(_) => continue,
+                    // Every frame carries the whole state, so a slow socket just skips ahead
+                    Err(RecvError::Lagged(_)) => continue,
+                    Err(RecvError::Closed) => break,
+                };
+                let bytes = StateFrame::from_state(&state, server_time_ms).encode(encoding);
+                let message = match encoding {
+                    Encoding::Json => match String::from_utf8(bytes) {
+                        Ok(text) => Message::Text(text.into()),
+                        Err(_) => continue,
+                    },
+                    Encoding::MessagePack => Message::Binary(bytes.into()),
+                };
+                if socket.send(message).await.is_err() {
+                    break;
+                }
+            }
+            incoming = socket.recv() => {
+                // The transport is one-way; anything but a close is ignored
+                match incoming {
+                    Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                    Some(Ok(_)) => {}
+                }
+            }
+        }
+    }
+    let _ = socket.send(Message::Close(None)).await;
+}
@@ -0,0 +1,70 @@
+use axum::extract::State;
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use serde::Deserialize;
+use serde_json::json;
+use game_core::TeamError;
+use crate::error::ApiError;
+use crate::state::AppState;
+
+#[derive(Deserialize)]
+pub struct TeamJoinRequest {
+    pub player_id: uuid::Uuid,
+    pub team: String,
+    /// Token from init_player; it must belong to player_id
+    #[serde(default)]
+    pub session_token: Option<String>,
+}
+
+/// Configured teams with their colors and current member counts
+pub async fn list_teams(State(app_state): State<AppState>) -> impl IntoResponse {
+    let game_state = app_state.game_state.read().await;
+    let sizes = game_core::teams::team_sizes(&app_state.game_config.teams, &game_state.players);
+    let teams: Vec<_> = app_state
+        .game_config
+        .teams
+        .teams
+        .iter()
+        .zip(sizes)
+        .map(|(team, (_, members))| {
+            json!({
+                "id": team.id,
+                "name": team.name,
+                "color": team.color,
+                "members": members,
+            })
+        })
+        .collect();
+    Json(json!({ "teams": teams }))
+}
+
+/// Switch a player to the team of their choice, within the balance limit
+pub async fn join_team(
+    State(app_state): State<AppState>,
+    Json(request): Json<TeamJoinRequest>,
+) -> Response {
+    if let Err(status) = crate::handlers::game::check_session(&app_state, request.player_id, request.session_token.as_deref()) {
+        return status.into_response();
+    }
+
+    let result = app_state
+        .game_state
+        .write()
+        .await
+        .join_team(&request.player_id, &request.team);
+    match result {
+        Ok(team) => {
+            eprintln!("🚩 Player {} joined team {}", request.player_id, team);
+            Json(json!({ "team": team })).into_response()
+        }
+        Err(e) => {
+            let reason = e.to_string();
+            let error = match e {
+                TeamError::Disabled | TeamError::UnknownPlayer => ApiError::NotFound(reason),
+                TeamError::UnknownTeam => ApiError::BadRequest(reason),
+                TeamError::Unbalanced => ApiError::Conflict(reason),
+            };
+            error.into_response()
+        }
+    }
+}
@@ -0,0 +1,22 @@
+use axum::extract::{Query, State};
+use axum::response::Json;
+use serde::Deserialize;
+use serde_json::json;
+
+#[derive(Deserialize)]
+pub struct TimeQuery {
+    /// Client send time, echoed back so the client can measure round-trip time
+    pub client_time: Option<u64>,
+}
+
+/// Clock sync for snapshot interpolation
+/// Clients estimate offset as server_time - (client_send + rtt / 2)
+pub async fn get_time(
+    State(app_state): State<crate::state::AppState>,
+    Query(query): Query<TimeQuery>,
+) -> impl axum::response::IntoResponse {
+    Json(json!({
+        "server_time_ms": app_state.clock.unix_millis(),
+        "client_time": query.client_time,
+    }))
+}
@@ -0,0 +1,41 @@
+use axum::extract::State;
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use serde::Deserialize;
+use serde_json::json;
+use game_core::{WorldEventError, WorldEventKind};
+use crate::error::ApiError;
+use crate::state::AppState;
+
+#[derive(Deserialize)]
+pub struct WorldEventRequest {
+    /// Id or display name of the event
+    pub kind: String,
+}
+
+/// The event under way, if any, and whether the server schedules events itself
+pub async fn get_world_event(State(app_state): State<AppState>) -> impl IntoResponse {
+    let game_state = app_state.game_state.read().await;
+    Json(json!({
+        "active": game_state.world_events.active,
+        "scheduled": game_state.world.config().world_events.enabled,
+    }))
+}
+
+/// Start a world event now, outside the schedule
+pub async fn start_world_event(
+    State(app_state): State<AppState>,
+    Json(request): Json<WorldEventRequest>,
+) -> Response {
+    let Some(kind) = WorldEventKind::parse(&request.kind) else {
+        return ApiError::BadRequest(format!("unknown world event {}", request.kind)).into_response();
+    };
+    let mut game_state = app_state.game_state.write().await;
+    match game_state.start_world_event(kind) {
+        Ok(()) => {
+            eprintln!("🌠 [ADMIN] Started world event {}", kind.label());
+            Json(json!({ "active": game_state.world_events.active })).into_response()
+        }
+        Err(e @ WorldEventError::AlreadyActive) => ApiError::Conflict(e.to_string()).into_response(),
+    }
+}
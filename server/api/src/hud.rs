@@ -0,0 +1,112 @@
+//! Server-rendered HUD fragments for event streams opened with `?hud=true`
+//!
+//! Each fragment carries the id of the element it replaces, so a page with empty
+//! `#hud-players`, `#hud-tick`, `#hud-score` and `#hud-match` elements and Datastar gets a
+//! live HUD without any game code. Fragments go out at the HUD rate, and only when changed.
+
+use std::fmt::Write;
+use game_core::{GameState, MatchState};
+
+pub const PLAYERS_SELECTOR: &str = "#hud-players";
+pub const TICK_SELECTOR: &str = "#hud-tick";
+pub const SCORE_SELECTOR: &str = "#hud-score";
+pub const MATCH_SELECTOR: &str = "#hud-match";
+
+/// Measured tick rates below this fraction of the configured rate show as slow
+const SLOW_TICK_RATIO: f32 = 0.9;
+
+/// One stream's HUD: what it last sent, and the tick it last measured from
+pub struct Hud {
+    tick_rate_hz: f32,
+    /// Tick and server time at the previous render
+    last_tick: Option<(u64, u64)>,
+    /// HTML last sent per fragment, in selector order
+    sent: [Option<String>; 4],
+}
+
+impl Hud {
+    pub fn new(tick_rate_hz: f32) -> Self {
+        Self {
+            tick_rate_hz,
+            last_tick: None,
+            sent: Default::default(),
+        }
+    }
+
+    /// Fragments that changed since the last render, as (selector, html)
+    /// The score fragment is only rendered for a stream's own player
+    pub fn render(&mut self, state: &GameState, player_id: Option<uuid::Uuid>, server_time_ms: u64) -> Vec<(&'static str, String)> {
+        let measured_hz = match self.last_tick {
+            Some((tick, at_ms)) if server_time_ms > at_ms => {
+                state.tick.saturating_sub(tick) as f32 * 1000.0 / (server_time_ms - at_ms) as f32
+            }
+            _ => self.tick_rate_hz,
+        };
+        self.last_tick = Some((state.tick, server_time_ms));
+
+        let fragments = [
+            (PLAYERS_SELECTOR, Some(players_html(state))),
+            (TICK_SELECTOR, Some(tick_html(measured_hz, self.tick_rate_hz))),
+            (SCORE_SELECTOR, player_id.and_then(|id| state.players.get(&id)).map(score_html)),
+            (MATCH_SELECTOR, Some(match_html(&state.match_state, server_time_ms))),
+        ];
+        fragments
+            .into_iter()
+            .zip(self.sent.iter_mut())
+            .filter_map(|((selector, html), sent)| {
+                let html = html?;
+                if sent.as_ref() == Some(&html) {
+                    return None;
+                }
+                *sent = Some(html.clone());
+                Some((selector, html))
+            })
+            .collect()
+    }
+}
+
+fn players_html(state: &GameState) -> String {
+    let mut html = format!(r#"<div id="hud-players">Players: <b>{}</b>"#, state.players.len());
+    if !state.waiting.is_empty() {
+        let _ = write!(html, " (+{} waiting)", state.waiting.len());
+    }
+    html.push_str("</div>");
+    html
+}
+
+fn tick_html(measured_hz: f32, target_hz: f32) -> String {
+    let health = if measured_hz < target_hz * SLOW_TICK_RATIO { "slow" } else { "ok" };
+    format!(r#"<div id="hud-tick" data-health="{}">Tick: <b>{:.0} Hz</b></div>"#, health, measured_hz)
+}
+
+fn score_html(player: &game_core::Player) -> String {
+    let mut html = format!(r#"<div id="hud-score">Score: <b>{}</b>"#, player.score);
+    if player.combo.count > 1 {
+        let _ = write!(html, " · Combo x{:.1}", player.combo.multiplier);
+    }
+    html.push_str("</div>");
+    html
+}
+
+fn match_html(match_state: &MatchState, now_ms: u64) -> String {
+    let secs_until = |at_ms: u64| at_ms.saturating_sub(now_ms).div_ceil(1000);
+    let text = match match_state {
+        MatchState::Lobby => "Waiting for players".to_string(),
+        MatchState::Countdown { starts_at_ms } => format!("Starts in <b>{}</b>", secs_until(*starts_at_ms)),
+        MatchState::Playing { ends_at_ms, .. } => {
+            let left = secs_until(*ends_at_ms);
+            format!("Time left: <b>{}:{:02}</b>", left / 60, left % 60)
+        }
+        MatchState::Ended { .. } => "Match over".to_string(),
+    };
+    format!(r#"<div id="hud-match" data-phase="{}">{}</div>"#, phase_name(match_state), text)
+}
+
+fn phase_name(match_state: &MatchState) -> &'static str {
+    match match_state {
+        MatchState::Lobby => "lobby",
+        MatchState::Countdown { .. } => "countdown",
+        MatchState::Playing { .. } => "playing",
+        MatchState::Ended { .. } => "ended",
+    }
+}
@@ -0,0 +1,404 @@
+use axum::http::HeaderMap;
+use serde::{Deserialize, Serialize};
+use game_core::{Language, NameError, Palette, VoteError, WorldEventKind};
+use crate::chat_commands::escape_html;
+use crate::moderation::ChatRejection;
+use crate::state::AppState;
+
+/// A server-generated, user-facing string, translated when rendered for a player
+///
+/// Arguments are raw text and get escaped during translation; the result is HTML-safe.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum Text {
+    PlayerJoined { name: String },
+    PlayerLeft { name: String },
+    NowKnownAs { name: String },
+    RenameFailed(NameError),
+    /// `names_html` is already rendered, one colored span per player
+    PlayersOnline { count: usize, names_html: String },
+    TeamUsage,
+    NotOnTeam,
+    MessageNotSent(ChatRejection),
+    Muted(ChatRejection),
+    UnknownCommand { name: String },
+    LanguageChanged,
+    /// End-of-match summary; names are None when there's no outright winner
+    MatchEnded { winner: Option<String>, team: Option<String> },
+    UnknownLanguage { tag: String },
+    PaletteChanged(Palette),
+    UnknownPalette { name: String },
+    /// The map being played and each map's votes for the next one, in rotation order
+    MapVotes { current: String, votes: Vec<(String, usize)> },
+    MapVoted { map: String },
+    MapVoteFailed(VoteError),
+    /// The rotation moved on to another map
+    NextMap { map: String },
+    /// An admin switched the game mode
+    ModeChanged { mode: String },
+    /// The round starting is played with these mutators
+    MutatorsActive { mutators: Vec<String> },
+    /// A random world event began
+    WorldEventStarted { kind: WorldEventKind },
+    /// The random world event under way is over
+    WorldEventEnded { kind: WorldEventKind },
+    HelpNick,
+    HelpWho,
+    HelpTeam,
+    HelpLang,
+    HelpPalette,
+    HelpMap,
+    HelpHelp,
+}
+
+/// Render a server-generated string in the given language
+pub fn translate(language: Language, text: &Text) -> String {
+    use Language::{En, Es, Fr};
+    match text {
+        Text::PlayerJoined { name } => {
+            let name = escape_html(name);
+            match language {
+                En => format!("{} joined the game", name),
+                Es => format!("{} se unió a la partida", name),
+                Fr => format!("{} a rejoint la partie", name),
+            }
+        }
+        Text::PlayerLeft { name } => {
+            let name = escape_html(name);
+            match language {
+                En => format!("{} left the game", name),
+                Es => format!("{} salió de la partida", name),
+                Fr => format!("{} a quitté la partie", name),
+            }
+        }
+        Text::NowKnownAs { name } => {
+            let name = escape_html(name);
+            match language {
+                En => format!("You are now known as <b>{}</b>", name),
+                Es => format!("Ahora te llamas <b>{}</b>", name),
+                Fr => format!("Vous vous appelez désormais <b>{}</b>", name),
+            }
+        }
+        Text::RenameFailed(error) => {
+            let reason = name_error(language, *error);
+            match language {
+                En => format!("Could not change name: {}", reason),
+                Es => format!("No se pudo cambiar el nombre: {}", reason),
+                Fr => format!("Impossible de changer de nom : {}", reason),
+            }
+        }
+        Text::PlayersOnline { count, names_html } => match language {
+            En => format!("{} player(s) online: {}", count, names_html),
+            Es => format!("{} jugador(es) en línea: {}", count, names_html),
+            Fr => format!("{} joueur(s) en ligne : {}", count, names_html),
+        },
+        Text::TeamUsage => match language {
+            En => "Usage: /team &lt;message&gt;".to_string(),
+            Es => "Uso: /team &lt;mensaje&gt;".to_string(),
+            Fr => "Utilisation : /team &lt;message&gt;".to_string(),
+        },
+        Text::NotOnTeam => match language {
+            En => "Message not sent: you are not on a team".to_string(),
+            Es => "Mensaje no enviado: no estás en ningún equipo".to_string(),
+            Fr => "Message non envoyé : vous n'êtes dans aucune équipe".to_string(),
+        },
+        Text::MessageNotSent(rejection) => {
+            let reason = chat_rejection(language, rejection);
+            match language {
+                En => format!("Message not sent: {}", reason),
+                Es => format!("Mensaje no enviado: {}", reason),
+                Fr => format!("Message non envoyé : {}", reason),
+            }
+        }
+        Text::Muted(rejection) => chat_rejection(language, rejection),
+        Text::UnknownCommand { name } => {
+            let name = escape_html(name);
+            match language {
+                En => format!("Unknown command /{}. Type /help for a list of commands.", name),
+                Es => format!("Comando desconocido /{}. Escribe /help para ver la lista de comandos.", name),
+                Fr => format!("Commande inconnue /{}. Tapez /help pour la liste des commandes.", name),
+            }
+        }
+        Text::MatchEnded { winner, team } => {
+            let winner = winner.as_deref().map(escape_html);
+            let team = team.as_deref().map(escape_html);
+            let mut summary = match (language, winner) {
+                (En, Some(winner)) => format!("Match over! <b>{}</b> wins", winner),
+                (Es, Some(winner)) => format!("¡Fin de la partida! Gana <b>{}</b>", winner),
+                (Fr, Some(winner)) => format!("Fin du match ! <b>{}</b> gagne", winner),
+                (En, None) => "Match over! It's a draw".to_string(),
+                (Es, None) => "¡Fin de la partida! Empate".to_string(),
+                (Fr, None) => "Fin du match ! Égalité".to_string(),
+            };
+            if let Some(team) = team {
+                summary.push_str(&match language {
+                    En => format!(" &mdash; team <b>{}</b> takes the round", team),
+                    Es => format!(" &mdash; el equipo <b>{}</b> gana la ronda", team),
+                    Fr => format!(" &mdash; l'équipe <b>{}</b> remporte la manche", team),
+                });
+            }
+            summary
+        }
+        Text::LanguageChanged => match language {
+            En => "Server messages are now in English".to_string(),
+            Es => "Los mensajes del servidor ahora están en español".to_string(),
+            Fr => "Les messages du serveur sont désormais en français".to_string(),
+        },
+        Text::UnknownLanguage { tag } => {
+            let tag = escape_html(tag);
+            let supported: Vec<&str> = Language::ALL.iter().map(|l| l.code()).collect();
+            let supported = supported.join(", ");
+            match language {
+                En => format!("Unknown language \"{}\". Available: {}", tag, supported),
+                Es => format!("Idioma desconocido \"{}\". Disponibles: {}", tag, supported),
+                Fr => format!("Langue inconnue « {} ». Disponibles : {}", tag, supported),
+            }
+        }
+        Text::PaletteChanged(palette) => match (palette, language) {
+            (Palette::Standard, En) => "Players and teams are now shown in the standard palette",
+            (Palette::Standard, Es) => "Jugadores y equipos se muestran ahora con la paleta estándar",
+            (Palette::Standard, Fr) => "Les joueurs et les équipes utilisent désormais la palette standard",
+            (Palette::ColorBlind, En) => "Players and teams are now shown in the color-blind palette",
+            (Palette::ColorBlind, Es) => "Jugadores y equipos se muestran ahora con la paleta para daltónicos",
+            (Palette::ColorBlind, Fr) => "Les joueurs et les équipes utilisent désormais la palette pour daltoniens",
+        }
+        .to_string(),
+        Text::UnknownPalette { name } => {
+            let name = escape_html(name);
+            let available: Vec<&str> = Palette::ALL.iter().map(|p| p.name()).collect();
+            let available = available.join(", ");
+            match language {
+                En => format!("Unknown palette \"{}\". Available: {}", name, available),
+                Es => format!("Paleta desconocida \"{}\". Disponibles: {}", name, available),
+                Fr => format!("Palette inconnue « {} ». Disponibles : {}", name, available),
+            }
+        }
+        Text::MapVotes { current, votes } => {
+            let current = escape_html(current);
+            let votes: Vec<String> = votes
+                .iter()
+                .map(|(name, count)| format!("<b>{}</b> ({})", escape_html(name), count))
+                .collect();
+            let votes = votes.join(", ");
+            match language {
+                En => format!("Now playing {}. Vote for the next map with /map &lt;name&gt;: {}", current, votes),
+                Es => format!("Mapa actual: {}. Vota el siguiente con /map &lt;nombre&gt;: {}", current, votes),
+                Fr => format!("Carte actuelle : {}. Votez pour la suivante avec /map &lt;nom&gt; : {}", current, votes),
+            }
+        }
+        Text::MapVoted { map } => {
+            let map = escape_html(map);
+            match language {
+                En => format!("You voted for <b>{}</b> as the next map", map),
+                Es => format!("Votaste por <b>{}</b> como siguiente mapa", map),
+                Fr => format!("Vous avez voté pour <b>{}</b> comme prochaine carte", map),
+            }
+        }
+        Text::MapVoteFailed(error) => {
+            let reason = match (error, language) {
+                (VoteError::Disabled, En) => "map voting is not available",
+                (VoteError::Disabled, Es) => "la votación de mapas no está disponible",
+                (VoteError::Disabled, Fr) => "le vote des cartes n'est pas disponible",
+                (VoteError::UnknownMap, En) => "there is no map by that name; type /map to list them",
+                (VoteError::UnknownMap, Es) => "no hay ningún mapa con ese nombre; escribe /map para verlos",
+                (VoteError::UnknownMap, Fr) => "aucune carte ne porte ce nom ; tapez /map pour la liste",
+                (VoteError::UnknownPlayer, En) => "you are not in the game",
+                (VoteError::UnknownPlayer, Es) => "no estás en la partida",
+                (VoteError::UnknownPlayer, Fr) => "vous n'êtes pas dans la partie",
+            };
+            match language {
+                En => format!("Vote not counted: {}", reason),
+                Es => format!("Voto no registrado: {}", reason),
+                Fr => format!("Vote non pris en compte : {}", reason),
+            }
+        }
+        Text::NextMap { map } => {
+            let map = escape_html(map);
+            match language {
+                En => format!("Next map: <b>{}</b>", map),
+                Es => format!("Siguiente mapa: <b>{}</b>", map),
+                Fr => format!("Carte suivante : <b>{}</b>", map),
+            }
+        }
+        Text::ModeChanged { mode } => {
+            let mode = escape_html(mode);
+            match language {
+                En => format!("Game mode is now <b>{}</b>", mode),
+                Es => format!("El modo de juego ahora es <b>{}</b>", mode),
+                Fr => format!("Le mode de jeu est désormais <b>{}</b>", mode),
+            }
+        }
+        Text::MutatorsActive { mutators } => {
+            let mutators = escape_html(&mutators.join(", "));
+            match language {
+                En => format!("This round's mutators: <b>{}</b>", mutators),
+                Es => format!("Modificadores de esta ronda: <b>{}</b>", mutators),
+                Fr => format!("Mutateurs de cette manche : <b>{}</b>", mutators),
+            }
+        }
+        Text::HelpNick => match language {
+            En => "Change your display name",
+            Es => "Cambia tu nombre visible",
+            Fr => "Changer votre nom affiché",
+        }
+        .to_string(),
+        Text::HelpWho => match language {
+            En => "List connected players",
+            Es => "Lista los jugadores conectados",
+            Fr => "Lister les joueurs connectés",
+        }
+        .to_string(),
+        Text::HelpTeam => match language {
+            En => "Message only your team",
+            Es => "Envía un mensaje solo a tu equipo",
+            Fr => "Envoyer un message à votre équipe",
+        }
+        .to_string(),
+        Text::HelpLang => match language {
+            En => "Choose the language of server messages",
+            Es => "Elige el idioma de los mensajes del servidor",
+            Fr => "Choisir la langue des messages du serveur",
+        }
+        .to_string(),
+        Text::HelpPalette => match language {
+            En => "Switch to a color-blind-safe palette",
+            Es => "Cambia a una paleta apta para daltónicos",
+            Fr => "Passer à une palette adaptée aux daltoniens",
+        }
+        .to_string(),
+        Text::HelpMap => match language {
+            En => "Vote for the next map, or list the maps",
+            Es => "Vota el siguiente mapa o lista los mapas",
+            Fr => "Voter pour la prochaine carte ou lister les cartes",
+        }
+        .to_string(),
+        Text::WorldEventStarted { kind } => {
+            let event = world_event_name(language, *kind);
+            match language {
+                En => format!("<b>{}</b> has begun!", event),
+                Es => format!("¡Comienza <b>{}</b>!", event),
+                Fr => format!("<b>{}</b> commence !", event),
+            }
+        }
+        Text::WorldEventEnded { kind } => {
+            let event = world_event_name(language, *kind);
+            match language {
+                En => format!("<b>{}</b> is over", event),
+                Es => format!("<b>{}</b> ha terminado", event),
+                Fr => format!("<b>{}</b> est terminé", event),
+            }
+        }
+        Text::HelpHelp => match language {
+            En => "Show this help",
+            Es => "Muestra esta ayuda",
+            Fr => "Afficher cette aide",
+        }
+        .to_string(),
+    }
+}
+
+/// A world event's name as plain text, for chat notices and the event banner
+pub fn world_event_name(language: Language, kind: WorldEventKind) -> &'static str {
+    use Language::{En, Es, Fr};
+    use WorldEventKind::{CoinRain, MeteorShower, SpeedFrenzy};
+    match (language, kind) {
+        (En, _) => kind.label(),
+        (Es, CoinRain) => "Lluvia de monedas",
+        (Es, MeteorShower) => "Lluvia de meteoritos",
+        (Es, SpeedFrenzy) => "Frenesí de velocidad",
+        (Fr, CoinRain) => "Pluie de pièces",
+        (Fr, MeteorShower) => "Pluie de météores",
+        (Fr, SpeedFrenzy) => "Frénésie de vitesse",
+    }
+}
+
+fn name_error(language: Language, error: NameError) -> &'static str {
+    use Language::{En, Es, Fr};
+    match (error, language) {
+        (NameError::TooShort, En) => "name is too short",
+        (NameError::TooShort, Es) => "el nombre es demasiado corto",
+        (NameError::TooShort, Fr) => "le nom est trop court",
+        (NameError::TooLong, En) => "name is too long",
+        (NameError::TooLong, Es) => "el nombre es demasiado largo",
+        (NameError::TooLong, Fr) => "le nom est trop long",
+        (NameError::InvalidCharacters, En) => "name contains invalid characters",
+        (NameError::InvalidCharacters, Es) => "el nombre contiene caracteres no válidos",
+        (NameError::InvalidCharacters, Fr) => "le nom contient des caractères invalides",
+        (NameError::Blocked, En) => "name is not allowed",
+        (NameError::Blocked, Es) => "ese nombre no está permitido",
+        (NameError::Blocked, Fr) => "ce nom n'est pas autorisé",
+        (NameError::Taken, En) => "name is already taken",
+        (NameError::Taken, Es) => "ese nombre ya está en uso",
+        (NameError::Taken, Fr) => "ce nom est déjà pris",
+        (NameError::Cooldown, En) => "renamed too recently",
+        (NameError::Cooldown, Es) => "cambiaste de nombre hace muy poco",
+        (NameError::Cooldown, Fr) => "nom changé trop récemment",
+        (NameError::UnknownPlayer, En) => "player is not in the game",
+        (NameError::UnknownPlayer, Es) => "el jugador no está en la partida",
+        (NameError::UnknownPlayer, Fr) => "le joueur n'est pas dans la partie",
+    }
+}
+
+fn chat_rejection(language: Language, rejection: &ChatRejection) -> String {
+    use Language::{En, Es, Fr};
+    match (rejection, language) {
+        (ChatRejection::Empty, En) => "message is empty".to_string(),
+        (ChatRejection::Empty, Es) => "el mensaje está vacío".to_string(),
+        (ChatRejection::Empty, Fr) => "le message est vide".to_string(),
+        (ChatRejection::TooLong { max }, En) => format!("message is longer than {} characters", max),
+        (ChatRejection::TooLong { max }, Es) => format!("el mensaje supera los {} caracteres", max),
+        (ChatRejection::TooLong { max }, Fr) => format!("le message dépasse {} caractères", max),
+        (ChatRejection::RateLimited, En) => "you are sending messages too quickly".to_string(),
+        (ChatRejection::RateLimited, Es) => "estás enviando mensajes demasiado rápido".to_string(),
+        (ChatRejection::RateLimited, Fr) => "vous envoyez des messages trop vite".to_string(),
+        (ChatRejection::Blocked, En) => "message contains a blocked word".to_string(),
+        (ChatRejection::Blocked, Es) => "el mensaje contiene una palabra bloqueada".to_string(),
+        (ChatRejection::Blocked, Fr) => "le message contient un mot interdit".to_string(),
+        (ChatRejection::Muted(remaining), language) => {
+            let secs = remaining.as_secs().max(1);
+            match language {
+                En => format!("you are muted for another {}s", secs),
+                Es => format!("estás silenciado durante {}s más", secs),
+                Fr => format!("vous êtes muet encore {}s", secs),
+            }
+        }
+    }
+}
+
+/// Summary notice for a finished match
+pub fn match_summary(result: &game_core::MatchResult, teams: &game_core::config::TeamsConfig) -> Text {
+    let winner = result.winner.and_then(|winner| {
+        result
+            .standings
+            .iter()
+            .find(|p| p.player_id == winner)
+            .map(|p| p.player_name.clone())
+    });
+    let team = result
+        .winning_team
+        .as_deref()
+        .and_then(|team_id| game_core::teams::find(teams, team_id))
+        .map(|team| team.name.clone());
+    Text::MatchEnded { winner, team }
+}
+
+/// The supported language a request's Accept-Language header prefers
+pub fn header_language(headers: &HeaderMap) -> Option<Language> {
+    headers
+        .get(axum::http::header::ACCEPT_LANGUAGE)
+        .and_then(|value| value.to_str().ok())
+        .and_then(Language::from_accept_language)
+}
+
+/// The language to answer a player in: their choice, then Accept-Language, then the default
+pub async fn player_language(app_state: &AppState, player_id: &uuid::Uuid, headers: &HeaderMap) -> Language {
+    let chosen = app_state
+        .game_state
+        .read()
+        .await
+        .players
+        .get(player_id)
+        .and_then(|p| p.language);
+    chosen
+        .or_else(|| header_language(headers))
+        .unwrap_or(app_state.game_config.default_language)
+}
@@ -0,0 +1,196 @@
+//! Input-to-broadcast latency tracing
+//!
+//! A traced command carries a [`Trace`] from the request handler through the command lanes
+//! (and the backplane, in distributed mode) to the game loop, which notes when it applied the
+//! command and finishes the trace at the first state broadcast that reflects it. Every stage
+//! is timestamped; finished traces are logged and the most recent kept for
+//! /api/admin/latency. Timestamps come from each instance's own clock, so traces forwarded
+//! between instances include any skew between them.
+
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::UNIX_EPOCH;
+use serde::{Deserialize, Serialize};
+use game_core::config::LatencyTracingConfig;
+use game_core::SharedClock;
+
+/// Header a client may set to pick a command's trace id, and the response echoes
+pub const TRACE_HEADER: &str = "x-trace-id";
+
+/// Longest client-chosen trace id accepted; longer or odd ones get a generated id instead
+const MAX_ID_LEN: usize = 64;
+
+/// A command being traced, as it travels to the game loop
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Trace {
+    pub id: String,
+    /// Unix microseconds when the request arrived
+    pub received_us: u64,
+    /// Unix microseconds when it was queued for the game loop
+    pub queued_us: u64,
+}
+
+/// A traced command the game loop has applied, waiting for a broadcast
+#[derive(Debug, Clone)]
+pub struct AppliedTrace {
+    trace: Trace,
+    player_id: uuid::Uuid,
+    tick: u64,
+    applied_us: u64,
+}
+
+/// A command's journey from request to broadcast
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CompletedTrace {
+    pub id: String,
+    pub player_id: uuid::Uuid,
+    pub received_us: u64,
+    pub queued_us: u64,
+    pub applied_us: u64,
+    pub broadcast_us: u64,
+    /// Tick the command was applied after; the next step is the first to include it
+    pub applied_tick: u64,
+    /// Tick of the state broadcast that first reflected it
+    pub broadcast_tick: u64,
+}
+
+impl CompletedTrace {
+    /// Microseconds from request to broadcast
+    pub fn total_us(&self) -> u64 {
+        self.broadcast_us.saturating_sub(self.received_us)
+    }
+}
+
+/// Totals over the kept traces, in microseconds from request to broadcast
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct LatencySummary {
+    pub count: usize,
+    pub p50_us: u64,
+    pub p95_us: u64,
+    pub max_us: u64,
+}
+
+/// Starts traces on sampled commands and keeps the finished ones
+#[derive(Clone)]
+pub struct LatencyTracer {
+    enabled: bool,
+    sample_every: u64,
+    keep: usize,
+    /// Commands seen, for sampling
+    seen: Arc<AtomicU64>,
+    recent: Arc<Mutex<VecDeque<CompletedTrace>>>,
+    clock: SharedClock,
+}
+
+impl LatencyTracer {
+    pub fn new(config: &LatencyTracingConfig, clock: SharedClock) -> Self {
+        Self {
+            enabled: config.enabled,
+            sample_every: u64::from(config.sample_every.max(1)),
+            keep: config.keep,
+            seen: Arc::new(AtomicU64::new(0)),
+            recent: Arc::new(Mutex::new(VecDeque::new())),
+            clock,
+        }
+    }
+
+    fn now_us(&self) -> u64 {
+        self.clock.now().duration_since(UNIX_EPOCH).map(|d| d.as_micros() as u64).unwrap_or(0)
+    }
+
+    /// Start tracing a command that just arrived, if tracing is on and it is sampled
+    /// A valid client-chosen id is always traced, so clients can follow their own commands;
+    /// an invalid one is ignored and the command sampled like any other
+    pub fn start(&self, requested_id: Option<&str>) -> Option<Trace> {
+        if !self.enabled {
+            return None;
+        }
+        let sampled = self.seen.fetch_add(1, Ordering::Relaxed).is_multiple_of(self.sample_every);
+        let requested = requested_id.filter(|id| {
+            !id.is_empty() && id.len() <= MAX_ID_LEN && id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+        });
+        if requested.is_none() && !sampled {
+            return None;
+        }
+        let id = requested.map_or_else(|| uuid::Uuid::new_v4().simple().to_string()[..16].to_string(), str::to_string);
+        let now = self.now_us();
+        Some(Trace {
+            id,
+            received_us: now,
+            queued_us: now,
+        })
+    }
+
+    /// Note that the command is about to be queued
+    pub fn queued(&self, trace: &mut Trace) {
+        trace.queued_us = self.now_us();
+    }
+
+    /// Note that the game loop applied the command after `tick`
+    pub fn applied(&self, trace: Trace, player_id: uuid::Uuid, tick: u64) -> AppliedTrace {
+        AppliedTrace {
+            trace,
+            player_id,
+            tick,
+            applied_us: self.now_us(),
+        }
+    }
+
+    /// Finish traces whose commands went out in the state broadcast of `tick`, logging each
+    pub fn broadcast(&self, applied: Vec<AppliedTrace>, tick: u64) {
+        if applied.is_empty() {
+            return;
+        }
+        let broadcast_us = self.now_us();
+        let mut recent = self.recent.lock().unwrap();
+        for AppliedTrace { trace, player_id, tick: applied_tick, applied_us } in applied {
+            let completed = CompletedTrace {
+                id: trace.id,
+                player_id,
+                received_us: trace.received_us,
+                queued_us: trace.queued_us,
+                applied_us,
+                broadcast_us,
+                applied_tick,
+                broadcast_tick: tick,
+            };
+            let ms = |from: u64, to: u64| to.saturating_sub(from) as f64 / 1000.0;
+            eprintln!(
+                "⏱️ Trace {} for {}: queued +{:.1}ms, applied +{:.1}ms after tick {}, broadcast +{:.1}ms at tick {}, {:.1}ms in total",
+                completed.id,
+                player_id,
+                ms(completed.received_us, completed.queued_us),
+                ms(completed.queued_us, completed.applied_us),
+                applied_tick,
+                ms(completed.applied_us, broadcast_us),
+                tick,
+                ms(completed.received_us, broadcast_us),
+            );
+            recent.push_back(completed);
+        }
+        while recent.len() > self.keep {
+            recent.pop_front();
+        }
+    }
+
+    /// Finished traces, oldest first
+    pub fn recent(&self) -> Vec<CompletedTrace> {
+        self.recent.lock().unwrap().iter().cloned().collect()
+    }
+
+    pub fn summary(&self) -> LatencySummary {
+        let mut totals: Vec<u64> = self.recent.lock().unwrap().iter().map(CompletedTrace::total_us).collect();
+        if totals.is_empty() {
+            return LatencySummary::default();
+        }
+        totals.sort_unstable();
+        let percentile = |p: usize| totals[(totals.len() - 1) * p / 100];
+        LatencySummary {
+            count: totals.len(),
+            p50_us: percentile(50),
+            p95_us: percentile(95),
+            max_us: totals[totals.len() - 1],
+        }
+    }
+}
@@ -0,0 +1,126 @@
+pub mod adaptive_rate;
+pub mod affinity;
+pub mod announcements;
+pub mod api_tokens;
+pub mod backplane;
+pub mod backpressure;
+pub mod bots;
+pub mod chat_commands;
+pub mod command_lanes;
+pub mod cors;
+pub mod direct;
+pub mod error;
+pub mod game_loop;
+pub mod handlers;
+pub mod hud;
+pub mod i18n;
+pub mod latency;
+pub mod limits;
+pub mod moderation;
+pub mod openapi;
+pub mod overlay;
+pub mod privacy;
+pub mod proxy;
+pub mod rate_limit;
+pub mod replay_jobs;
+pub mod resume;
+pub mod routes;
+pub mod session;
+pub mod shutdown;
+pub mod spawn_hints;
+pub mod spectators;
+pub mod state;
+pub mod tls;
+
+use axum::Router;
+use tower::service_fn;
+use tower_http::services::ServeDir;
+
+#[derive(Debug, Clone)]
+pub enum GameUpdate {
+    StateUpdate {
+        state: Box<game_core::GameState>,
+        /// Unix time in milliseconds when the state was produced, for client interpolation
+        server_time_ms: u64,
+    },
+    GeometryChanged(game_core::GeometrySync),
+    ComboBroken(Vec<game_core::ComboBreak>),
+    ChallengesCompleted(Vec<game_core::ChallengeCompletion>),
+    /// Players died or respawned this step
+    LifeEvents(Vec<game_core::LifeEvent>),
+    /// Players took damage this step
+    Damage(Vec<game_core::DamageEvent>),
+    /// Players bounced off springy surfaces this step
+    Bounces(Vec<game_core::BounceEvent>),
+    /// Players went through portals this step
+    Teleports(Vec<game_core::TeleportEvent>),
+    /// The game mode's scoreboard changed, e.g. racers touched checkpoints or the tag passed on
+    ModeStatus(game_core::ModeStatus),
+    PlayerLeft {
+        player_id: uuid::Uuid,
+        player_name: String,
+    },
+    /// A system line for every client's chat, translated per stream
+    Notice(i18n::Text),
+    /// The match entered a new phase
+    MatchPhase(game_core::MatchState),
+    /// The rotation switched to another map and everyone respawned on it
+    MapChanged(game_core::MapChange),
+    /// An admin switched the game mode
+    ModeChanged(game_core::ModeManifest),
+    /// An admin edited the live map; carries the new geometry
+    MapEdited(game_core::config::MapGeometry),
+    /// A random world event started or ended
+    WorldEvent(game_core::WorldEventChange),
+    /// Settings served by /api/config changed; carries the new config version
+    ConfigChanged(u64),
+    /// A player was killed by another; only the victim's stream sends it
+    KillCam(Box<game_core::KillCam>),
+    /// Number of open spectator streams changed
+    SpectatorCount(usize),
+    /// Banner HTML for every client's announcement overlay; empty clears it
+    Announcement {
+        html: String,
+    },
+    /// The server is shutting down; streams close when this reaches zero
+    ServerShutdown {
+        seconds_remaining: u64,
+    },
+}
+
+/// The full application: API routes plus the static client, under the configured base
+/// path and behind CORS when origins are configured
+pub fn app(app_state: state::AppState) -> Router {
+    let base_path = app_state.game_config.proxy.resolve_base_path();
+    let cors = cors::layer(&app_state.game_config.cors);
+
+    // Serve static files with fallback to index.html for SPA routing
+    // This is the Axum 0.8 best practice: use fallback_service with ServeDir
+    // and not_found_service to handle SPA routing
+    // Directories go to the fallback too, so index.html always gets the base path
+    let index_base = base_path.clone();
+    let static_files = ServeDir::new("client/dist")
+        .append_index_html_on_directories(false)
+        .not_found_service(service_fn(move |_req: axum::http::Request<axum::body::Body>| {
+            let base_path = index_base.clone();
+            async move {
+                Ok::<axum::response::Response, std::convert::Infallible>(
+                    handlers::serve_index_html(&base_path).await
+                )
+            }
+        }));
+
+    let app = Router::new()
+        .merge(routes::create_routes(app_state))
+        .fallback_service(static_files);
+    let app = if base_path.is_empty() {
+        app
+    } else {
+        eprintln!("📂 Serving under {}/", base_path);
+        Router::new().nest(&base_path, app)
+    };
+    match cors {
+        Some(cors) => app.layer(cors),
+        None => app,
+    }
+}
@@ -0,0 +1,125 @@
+use std::collections::{HashMap, HashSet};
+use std::net::IpAddr;
+use std::sync::{Arc, Mutex};
+use serde::Serialize;
+use game_core::config::LimitsConfig;
+
+#[derive(Default)]
+struct IpCounts {
+    connections: HashMap<IpAddr, usize>,
+    players: HashMap<IpAddr, HashSet<uuid::Uuid>>,
+}
+
+/// Usage for one IP, for admin visibility
+#[derive(Debug, Clone, Serialize)]
+pub struct IpUsage {
+    pub ip: IpAddr,
+    pub connections: usize,
+    pub players: usize,
+}
+
+/// Enforces per-IP connection and player creation limits, and admin bans
+#[derive(Clone)]
+pub struct IpLimiter {
+    limits: LimitsConfig,
+    counts: Arc<Mutex<IpCounts>>,
+    /// Addresses refused outright until the server restarts
+    banned: Arc<Mutex<HashSet<IpAddr>>>,
+}
+
+impl IpLimiter {
+    pub fn new(limits: LimitsConfig) -> Self {
+        Self {
+            limits,
+            counts: Arc::new(Mutex::new(IpCounts::default())),
+            banned: Arc::new(Mutex::new(HashSet::new())),
+        }
+    }
+
+    /// Refuse an IP from now on; returns the players created from it, for removing
+    pub fn ban(&self, ip: IpAddr) -> Vec<uuid::Uuid> {
+        self.banned.lock().unwrap().insert(ip);
+        let mut players: Vec<uuid::Uuid> = self
+            .counts
+            .lock()
+            .unwrap()
+            .players
+            .remove(&ip)
+            .map(|players| players.into_iter().collect())
+            .unwrap_or_default();
+        players.sort();
+        players
+    }
+
+    pub fn is_banned(&self, ip: IpAddr) -> bool {
+        self.banned.lock().unwrap().contains(&ip)
+    }
+
+    /// Reserve a connection slot for an IP, released when the guard is dropped
+    pub fn try_connect(&self, ip: IpAddr) -> Option<ConnectionGuard> {
+        let mut counts = self.counts.lock().unwrap();
+        let connections = counts.connections.entry(ip).or_insert(0);
+        if *connections >= self.limits.max_connections_per_ip {
+            return None;
+        }
+        *connections += 1;
+        Some(ConnectionGuard {
+            ip,
+            counts: self.counts.clone(),
+        })
+    }
+
+    /// Record a new player created from an IP
+    /// `is_live` reports whether a previously created player still exists, so players
+    /// that left or timed out no longer count against the limit
+    pub fn try_create_player(&self, ip: IpAddr, player_id: uuid::Uuid, is_live: impl Fn(&uuid::Uuid) -> bool) -> bool {
+        let mut counts = self.counts.lock().unwrap();
+        let players = counts.players.entry(ip).or_default();
+        players.retain(|id| is_live(id));
+        if players.len() >= self.limits.max_players_per_ip {
+            return false;
+        }
+        players.insert(player_id);
+        true
+    }
+
+    /// Current per-IP usage, busiest first
+    pub fn usage(&self) -> Vec<IpUsage> {
+        let counts = self.counts.lock().unwrap();
+        let ips: HashSet<IpAddr> = counts
+            .connections
+            .keys()
+            .chain(counts.players.keys())
+            .copied()
+            .collect();
+        let mut usage: Vec<IpUsage> = ips
+            .into_iter()
+            .map(|ip| IpUsage {
+                ip,
+                connections: counts.connections.get(&ip).copied().unwrap_or(0),
+                players: counts.players.get(&ip).map(|p| p.len()).unwrap_or(0),
+            })
+            .filter(|u| u.connections > 0 || u.players > 0)
+            .collect();
+        usage.sort_by_key(|u| std::cmp::Reverse(u.connections + u.players));
+        usage
+    }
+}
+
+/// Held for the lifetime of an SSE connection
+pub struct ConnectionGuard {
+    ip: IpAddr,
+    counts: Arc<Mutex<IpCounts>>,
+}
+
+impl Drop for ConnectionGuard {
+    fn drop(&mut self) {
+        let mut counts = self.counts.lock().unwrap();
+        if let Some(connections) = counts.connections.get_mut(&self.ip) {
+            *connections = connections.saturating_sub(1);
+            if *connections == 0 {
+                counts.connections.remove(&self.ip);
+            }
+        }
+    }
+}
@@ -1,14 +1,9 @@
-mod handlers;
-mod routes;
-mod state;
-
-use axum::Router;
 use std::net::SocketAddr;
 use std::sync::Arc;
-use tokio::sync::{broadcast, mpsc, RwLock};
-use tower::service_fn;
-use tower_http::services::ServeDir;
-use game_core::GameState;
+use game_core::SystemClock;
+use api::game_loop::{cleanup_inactive_players, GameLoop};
+use api::shutdown;
+use api::state::AppState;
 
 #[tokio::main]
 async fn main() {
@@ -39,6 +34,16 @@ async fn main() {
                     config.platforms.len(), config.walls.len());
                 std::sync::Arc::new(config)
             }
+            // A config that loads but is broken is refused rather than replaced with defaults
+            Err(e) if e.is::<game_core::config::ValidationErrors>() => {
+                eprintln!("❌ Config {} is invalid:", path);
+                if let Some(errors) = e.downcast_ref::<game_core::config::ValidationErrors>() {
+                    for error in &errors.0 {
+                        eprintln!("   - {}", error);
+                    }
+                }
+                std::process::exit(1);
+            }
             Err(e) => {
                 eprintln!("⚠️ Failed to load config from {}: {}, using defaults", path, e);
                 std::sync::Arc::new(game_core::config::GameConfig::default())
@@ -49,123 +54,189 @@ async fn main() {
         std::sync::Arc::new(game_core::config::GameConfig::default())
     };
     
-    // Initialize physics system with configuration
-    game_core::physics::init(game_config.clone());
-    
     let port = std::env::var("PORT")
         .unwrap_or_else(|_| "3000".to_string())
         .parse::<u16>()
         .expect("PORT must be a valid number");
     let addr = SocketAddr::from(([0, 0, 0, 0], port));
 
-    let (game_tx, _) = broadcast::channel::<GameUpdate>(100);
-    let (chat_tx, _) = broadcast::channel::<game_core::ChatMessage>(100);
-    let (command_tx, command_rx) = mpsc::channel::<(uuid::Uuid, game_core::PlayerCommand)>(100);
-
-    let game_state = Arc::new(RwLock::new(GameState::new()));
-
-    let app_state = state::AppState {
-        game_state: game_state.clone(),
-        game_tx: game_tx.clone(),
-        chat_tx: chat_tx.clone(),
-        command_tx,
-        game_config: game_config.clone(),
+    // Completed matches, persisted to disk when a path is configured
+    let match_history = match &game_config.match_history_path {
+        Some(path) => match game_core::MatchHistory::load_async(path).await {
+            Ok(history) => {
+                eprintln!("✅ Loaded {} match record(s) from {}", history.len(), path);
+                history
+            }
+            Err(e) => {
+                eprintln!("⚠️ Failed to load match history from {}: {}, keeping history in memory", path, e);
+                game_core::MatchHistory::in_memory()
+            }
+        },
+        None => game_core::MatchHistory::in_memory(),
     };
 
-    tokio::spawn(game_loop(game_state.clone(), command_rx, game_tx.clone()));
-    tokio::spawn(cleanup_inactive_players(game_state, game_tx.clone(), game_config.clone()));
-
-    // Serve static files with fallback to index.html for SPA routing
-    // This is the Axum 0.8 best practice: use fallback_service with ServeDir
-    // and not_found_service to handle SPA routing
-    let static_files = ServeDir::new("client/dist")
-        .not_found_service(service_fn(|_req: axum::http::Request<axum::body::Body>| async {
-            Ok::<axum::response::Response, std::convert::Infallible>(
-                handlers::serve_index_html().await
-            )
-        }));
+    // Per-player client settings, persisted to disk when a path is configured
+    let player_settings = match &game_config.player_settings.path {
+        Some(path) => match game_core::PlayerSettings::load_async(path).await {
+            Ok(settings) => {
+                eprintln!("✅ Loaded settings for {} player(s) from {}", settings.len(), path);
+                settings
+            }
+            Err(e) => {
+                eprintln!("⚠️ Failed to load player settings from {}: {}, keeping settings in memory", path, e);
+                game_core::PlayerSettings::in_memory()
+            }
+        },
+        None => game_core::PlayerSettings::in_memory(),
+    };
 
-    let app = Router::new()
-        .merge(routes::create_routes(app_state))
-        .fallback_service(static_files);
+    // Reserved display names, persisted to disk when a path is configured
+    let name_reservations = match &game_config.names.reservations_path {
+        Some(path) => match game_core::NameReservations::load_async(path).await {
+            Ok(reservations) => {
+                eprintln!("✅ Loaded {} name reservation(s) from {}", reservations.len(), path);
+                reservations
+            }
+            Err(e) => {
+                eprintln!("⚠️ Failed to load name reservations from {}: {}, keeping reservations in memory", path, e);
+                game_core::NameReservations::in_memory()
+            }
+        },
+        None => game_core::NameReservations::in_memory(),
+    };
 
-    eprintln!("🌐 Starting HTTP server on {}...", addr);
-    let listener = tokio::net::TcpListener::bind(&addr).await.unwrap();
-    eprintln!("✅ Server is ready! Listening on http://{}", addr);
-    eprintln!("📡 SSE endpoint available at: http://{}/events", addr);
-    axum::serve(listener, app).await.unwrap();
-}
+    // Cosmetic unlocks and coins, persisted to disk when a path is configured
+    let cosmetics = match &game_config.cosmetics.path {
+        Some(path) => match game_core::CosmeticStore::load_async(path).await {
+            Ok(store) => {
+                eprintln!("✅ Loaded cosmetics for {} player(s) from {}", store.len(), path);
+                store
+            }
+            Err(e) => {
+                eprintln!("⚠️ Failed to load cosmetics from {}: {}, keeping cosmetics in memory", path, e);
+                game_core::CosmeticStore::in_memory()
+            }
+        },
+        None => game_core::CosmeticStore::in_memory(),
+    };
 
-async fn game_loop(
-    game_state: Arc<RwLock<GameState>>,
-    mut command_rx: mpsc::Receiver<(uuid::Uuid, game_core::PlayerCommand)>,
-    game_tx: broadcast::Sender<GameUpdate>,
-) {
-    let mut interval = tokio::time::interval(tokio::time::Duration::from_secs_f32(1.0 / 60.0));
-    loop {
-        interval.tick().await;
-        let delta_time = 1.0 / 60.0;
+    // Tombstones of deleted players, persisted to disk when a path is configured
+    let tombstones = match &game_config.privacy.tombstones_path {
+        Some(path) => match api::privacy::Tombstones::load_async(path).await {
+            Ok(tombstones) => {
+                eprintln!("✅ Loaded {} tombstone(s) from {}", tombstones.len(), path);
+                tombstones
+            }
+            Err(e) => {
+                eprintln!("⚠️ Failed to load tombstones from {}: {}, keeping tombstones in memory", path, e);
+                api::privacy::Tombstones::in_memory()
+            }
+        },
+        None => api::privacy::Tombstones::in_memory(),
+    };
 
-        while let Ok((player_id, command)) = command_rx.try_recv() {
-            game_state.write().await.apply_command(&player_id, &command);
+    let (app_state, command_rx) = AppState::new(
+        game_config.clone(),
+        match_history,
+        player_settings,
+        name_reservations,
+        cosmetics,
+        tombstones,
+        Arc::new(SystemClock),
+    );
+    // Hub plots and the houses built in them, persisted to disk when a path is configured
+    if let Some(path) = &game_config.housing.path {
+        match game_core::Housing::load(&game_config.housing).await {
+            Ok(housing) => {
+                eprintln!("✅ Loaded {} claimed plot(s) from {}", housing.list().iter().filter(|p| p.owner.is_some()).count(), path);
+                app_state.game_state.write().await.restore_housing(housing);
+            }
+            Err(e) => eprintln!("⚠️ Failed to load plots from {}: {}, starting with every plot free", path, e),
         }
-
-        game_state.write().await.update(delta_time);
-
-        let state = game_state.read().await.clone();
-        let _ = game_tx.send(GameUpdate::StateUpdate(state));
     }
-}
-
-#[derive(Debug, Clone)]
-pub enum GameUpdate {
-    StateUpdate(game_core::GameState),
-    PlayerLeft {
-        player_id: uuid::Uuid,
-        player_name: String,
-    },
-}
+    let started_at = app_state.clock.unix_secs();
+    if app_state.admin_token.is_none() {
+        eprintln!("🔒 No admin token configured (set {}), admin API disabled", game_config.admin.token_env);
+    }
 
-/// Cleanup task that removes inactive players (configurable timeout)
-async fn cleanup_inactive_players(
-    game_state: Arc<RwLock<GameState>>,
-    game_tx: broadcast::Sender<GameUpdate>,
-    game_config: Arc<game_core::GameConfig>,
-) {
-    let mut interval = tokio::time::interval(tokio::time::Duration::from_secs(30));
-    let timeout_duration = std::time::Duration::from_secs(game_config.idle_timeout);
-    
-    loop {
-        interval.tick().await;
-        
-        let now = std::time::SystemTime::now();
-        let mut players_to_remove = Vec::new();
-        
-        // Check all players for inactivity
-        {
-            let game_state_guard = game_state.read().await;
-            for (player_id, player) in game_state_guard.players.iter() {
-                if let Ok(elapsed) = now.duration_since(player.last_activity) {
-                    if elapsed > timeout_duration {
-                        players_to_remove.push((*player_id, player.name.clone(), elapsed));
+    let game_loop = GameLoop::new(
+        app_state.game_state.clone(),
+        command_rx,
+        app_state.game_tx.clone(),
+        app_state.match_history.clone(),
+        app_state.cosmetics.clone(),
+        &game_config,
+        app_state.clock.clone(),
+    )
+    .with_bots(app_state.bots.clone())
+    .with_moderator(app_state.moderator.clone())
+    .with_backplane(app_state.backplane.clone())
+    .with_latency_tracer(app_state.latency.clone());
+    tokio::spawn(game_loop.run());
+    tokio::spawn(cleanup_inactive_players(
+        app_state.game_state.clone(),
+        app_state.game_tx.clone(),
+        app_state.sessions.clone(),
+        game_config.clone(),
+        app_state.backplane.clone(),
+    ));
+    api::backplane::spawn(&app_state);
+    tokio::spawn(api::announcements::run_scheduled(app_state.clone()));
+    app_state.replays.spawn_workers(game_config.replays.workers);
+
+    let shutdown_signal = shutdown::graceful(app_state.clone(), started_at);
+    let app = api::app(app_state);
+
+    // HTTPS when a certificate is configured, otherwise plain HTTP
+    let tls_paths = match game_config.tls.resolve_paths() {
+        Ok(paths) => paths,
+        Err(e) => {
+            eprintln!("❌ Invalid TLS config: {}", e);
+            std::process::exit(1);
+        }
+    };
+    if let Some((cert_path, key_path)) = tls_paths {
+        let tls_config = match api::tls::load(&cert_path, &key_path).await {
+            Ok(config) => config,
+            Err(e) => {
+                eprintln!("❌ {}", e);
+                std::process::exit(1);
+            }
+        };
+        match game_config.tls.resolve_redirect_port() {
+            Ok(Some(redirect_port)) => {
+                let redirect_addr = SocketAddr::from(([0, 0, 0, 0], redirect_port));
+                match tokio::net::TcpListener::bind(&redirect_addr).await {
+                    Ok(listener) => {
+                        eprintln!("↪️ Redirecting http://{} to HTTPS", redirect_addr);
+                        tokio::spawn(async move {
+                            if let Err(e) = axum::serve(listener, api::tls::redirect_app(port)).await {
+                                eprintln!("⚠️ HTTP redirect listener stopped: {}", e);
+                            }
+                        });
                     }
+                    Err(e) => eprintln!("⚠️ Failed to bind HTTP redirect on {}: {}, not redirecting", redirect_addr, e),
                 }
             }
+            Ok(None) => {}
+            Err(e) => eprintln!("⚠️ {}, not redirecting HTTP", e),
         }
-        
-        // Remove inactive players and broadcast
-        if !players_to_remove.is_empty() {
-            let mut game_state_guard = game_state.write().await;
-            for (player_id, player_name, elapsed) in players_to_remove {
-                eprintln!("⏰ [TIMEOUT] Player timed out due to inactivity: {} ({}) - inactive for {} seconds (timeout: {}s)", 
-                    player_id, player_name, elapsed.as_secs(), timeout_duration.as_secs());
-                game_state_guard.remove_player(&player_id);
-                let _ = game_tx.send(GameUpdate::PlayerLeft {
-                    player_id,
-                    player_name,
-                });
-            }
-        }
+
+        eprintln!("🔐 Starting HTTPS server on {}...", addr);
+        let listener = std::net::TcpListener::bind(addr).unwrap();
+        eprintln!("✅ Server is ready! Listening on https://{}", addr);
+        eprintln!("📡 SSE endpoint available at: https://{}/events", addr);
+        api::tls::serve(listener, tls_config, app, shutdown_signal).await.unwrap();
+        return;
     }
-}
 
+    eprintln!("🌐 Starting HTTP server on {}...", addr);
+    let listener = tokio::net::TcpListener::bind(&addr).await.unwrap();
+    eprintln!("✅ Server is ready! Listening on http://{}", addr);
+    eprintln!("📡 SSE endpoint available at: http://{}/events", addr);
+    // Connect info gives handlers the peer address for per-IP limits
+    axum::serve(listener, app.into_make_service_with_connect_info::<SocketAddr>())
+        .with_graceful_shutdown(shutdown_signal)
+        .await
+        .unwrap();
+}
@@ -0,0 +1,348 @@
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime};
+use serde::{Deserialize, Serialize};
+use tokio::sync::RwLock;
+use game_core::config::{ChatConfig, ModerationConfig};
+use game_core::SharedClock;
+
+/// Maximum number of flags kept for admin review
+const MAX_FLAGS: usize = 500;
+
+/// What kind of content is being scored
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ContentKind {
+    Chat,
+    Name,
+    /// Movement or input the anti-cheat flagged
+    Gameplay,
+}
+
+pub type ModerationFuture<'a> =
+    Pin<Box<dyn Future<Output = Result<f32, Box<dyn std::error::Error + Send + Sync>>> + Send + 'a>>;
+
+/// A content moderation backend
+/// Returns a score in 0.0..=1.0 where higher means more likely to be abusive
+pub trait ModerationProvider: Send + Sync {
+    fn score<'a>(&'a self, kind: ContentKind, text: &'a str) -> ModerationFuture<'a>;
+}
+
+/// Moderation provider backed by an HTTP endpoint (hosted API or local model server)
+/// POSTs `{"kind": "chat", "text": "..."}` and expects `{"score": 0.0..1.0}` back
+pub struct HttpModerationProvider {
+    client: reqwest::Client,
+    endpoint: String,
+    api_key: Option<String>,
+}
+
+impl HttpModerationProvider {
+    pub fn new(config: &ModerationConfig) -> Result<Self, reqwest::Error> {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_millis(config.timeout_ms))
+            .build()?;
+        let api_key = config
+            .api_key_env
+            .as_ref()
+            .and_then(|var| std::env::var(var).ok());
+        Ok(Self {
+            client,
+            endpoint: config.endpoint.clone(),
+            api_key,
+        })
+    }
+}
+
+#[derive(Serialize)]
+struct ScoreRequest<'a> {
+    kind: ContentKind,
+    text: &'a str,
+}
+
+#[derive(Deserialize)]
+struct ScoreResponse {
+    score: f32,
+}
+
+impl ModerationProvider for HttpModerationProvider {
+    fn score<'a>(&'a self, kind: ContentKind, text: &'a str) -> ModerationFuture<'a> {
+        Box::pin(async move {
+            let mut request = self.client.post(&self.endpoint).json(&ScoreRequest { kind, text });
+            if let Some(key) = &self.api_key {
+                request = request.bearer_auth(key);
+            }
+            let response = request.send().await?;
+            if !response.status().is_success() {
+                return Err(format!("HTTP error: {}", response.status()).into());
+            }
+            let body: ScoreResponse = response.json().await?;
+            Ok(body.score.clamp(0.0, 1.0))
+        })
+    }
+}
+
+/// Content that scored above the flag threshold, queued for admin review
+#[derive(Debug, Clone, Serialize)]
+pub struct ModerationFlag {
+    pub player_id: uuid::Uuid,
+    pub kind: ContentKind,
+    pub text: String,
+    pub score: f32,
+    /// Unix timestamp in seconds
+    pub flagged_at: u64,
+    /// Whether the score also triggered an automatic mute
+    pub auto_muted: bool,
+    /// For gameplay flags, the replay of the player's recent inputs and its verdict
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub replay_check: Option<game_core::ReplayCheck>,
+}
+
+/// Why a chat message was not delivered
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ChatRejection {
+    Empty,
+    TooLong { max: usize },
+    /// Sending too fast
+    RateLimited,
+    /// Contains a blocked word
+    Blocked,
+    /// Player is muted for this long
+    Muted(Duration),
+}
+
+impl fmt::Display for ChatRejection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ChatRejection::Empty => write!(f, "message is empty"),
+            ChatRejection::TooLong { max } => write!(f, "message is longer than {} characters", max),
+            ChatRejection::RateLimited => write!(f, "you are sending messages too quickly"),
+            ChatRejection::Blocked => write!(f, "message contains a blocked word"),
+            ChatRejection::Muted(remaining) => {
+                write!(f, "you are muted for another {}s", remaining.as_secs().max(1))
+            }
+        }
+    }
+}
+
+/// A player's recent chat behaviour, for rate limiting and mute escalation
+#[derive(Default)]
+struct ChatStanding {
+    /// When recent messages were sent, oldest first
+    recent: VecDeque<Instant>,
+    /// Violations since the last mute
+    strikes: u32,
+    /// Mutes issued so far, selecting the next escalation step
+    mutes: usize,
+}
+
+/// Mutes and flags shared across handlers
+#[derive(Default)]
+pub struct ModerationState {
+    /// Muted players and when their mute expires
+    pub mutes: HashMap<uuid::Uuid, SystemTime>,
+    /// Flags for admin review, oldest first
+    pub flags: Vec<ModerationFlag>,
+    chat_standing: HashMap<uuid::Uuid, ChatStanding>,
+}
+
+impl ModerationState {
+    /// Time left on a player's mute as of `now`, if they are muted
+    pub fn mute_remaining(&self, player_id: &uuid::Uuid, now: SystemTime) -> Option<Duration> {
+        self.mutes
+            .get(player_id)
+            .and_then(|until| until.duration_since(now).ok())
+    }
+
+    pub fn mute(&mut self, player_id: uuid::Uuid, duration: Duration, now: SystemTime) {
+        let until = now + duration;
+        let entry = self.mutes.entry(player_id).or_insert(until);
+        // Never shorten an existing mute
+        *entry = (*entry).max(until);
+    }
+
+    /// Drop a player's flags and chat history for a data deletion, returning how many
+    /// flags went; mutes stay so deleting data can't lift them
+    pub fn forget(&mut self, player_id: &uuid::Uuid) -> usize {
+        let before = self.flags.len();
+        self.flags.retain(|flag| flag.player_id != *player_id);
+        self.chat_standing.remove(player_id);
+        before - self.flags.len()
+    }
+
+    fn push_flag(&mut self, flag: ModerationFlag) {
+        self.flags.push(flag);
+        if self.flags.len() > MAX_FLAGS {
+            self.flags.remove(0);
+        }
+    }
+}
+
+/// Blocked words from config plus the optional word list file
+fn load_blocked_words(chat: &ChatConfig) -> Vec<String> {
+    let mut words = chat.blocked_words.clone();
+    if let Some(path) = &chat.blocked_words_file {
+        match std::fs::read_to_string(path) {
+            Ok(contents) => {
+                let before = words.len();
+                words.extend(
+                    contents
+                        .lines()
+                        .map(|line| line.split('#').next().unwrap_or("").trim())
+                        .filter(|word| !word.is_empty())
+                        .map(|word| word.to_string()),
+                );
+                eprintln!("📋 Loaded {} blocked words from {}", words.len() - before, path);
+            }
+            Err(e) => eprintln!("⚠️ Failed to read blocked words file {}: {}", path, e),
+        }
+    }
+    words
+}
+
+/// Moderation hook: the configured provider plus shared mute/flag state
+#[derive(Clone)]
+pub struct Moderator {
+    provider: Option<Arc<dyn ModerationProvider>>,
+    config: Option<ModerationConfig>,
+    chat: Arc<ChatConfig>,
+    blocked_words: Arc<Vec<String>>,
+    pub state: Arc<RwLock<ModerationState>>,
+    clock: SharedClock,
+}
+
+impl Moderator {
+    /// Build from config; provider scoring is a no-op when no provider is configured,
+    /// the local chat filter always applies
+    pub fn from_config(config: Option<&ModerationConfig>, chat: &ChatConfig, clock: SharedClock) -> Self {
+        let provider = config.and_then(|config| match HttpModerationProvider::new(config) {
+            Ok(provider) => Some(Arc::new(provider) as Arc<dyn ModerationProvider>),
+            Err(e) => {
+                eprintln!("⚠️ Failed to create moderation provider: {}, moderation disabled", e);
+                None
+            }
+        });
+        Self {
+            provider,
+            config: config.cloned(),
+            chat: Arc::new(chat.clone()),
+            blocked_words: Arc::new(load_blocked_words(chat)),
+            state: Arc::new(RwLock::new(ModerationState::default())),
+            clock,
+        }
+    }
+
+    /// Time left on a player's mute, if they are muted
+    pub async fn mute_remaining(&self, player_id: &uuid::Uuid) -> Option<Duration> {
+        self.state.read().await.mute_remaining(player_id, self.clock.now())
+    }
+
+    pub async fn mute(&self, player_id: uuid::Uuid, duration: Duration) {
+        self.state.write().await.mute(player_id, duration, self.clock.now());
+    }
+
+    /// Check a chat message before it is broadcast, returning the trimmed text
+    /// Blocked words and flooding count as strikes; enough strikes mute the player,
+    /// each mute lasting longer than the last
+    pub async fn check_chat(&self, player_id: uuid::Uuid, text: &str) -> Result<String, ChatRejection> {
+        let mut state = self.state.write().await;
+        let now = self.clock.now();
+        if let Some(remaining) = state.mute_remaining(&player_id, now) {
+            return Err(ChatRejection::Muted(remaining));
+        }
+
+        let text = text.trim();
+        if text.is_empty() {
+            return Err(ChatRejection::Empty);
+        }
+        if text.chars().count() > self.chat.max_length {
+            return Err(ChatRejection::TooLong { max: self.chat.max_length });
+        }
+
+        let window = Duration::from_secs(self.chat.rate_limit_window_secs);
+        let standing = state.chat_standing.entry(player_id).or_default();
+        let instant = self.clock.instant();
+        while standing.recent.front().is_some_and(|sent| instant.duration_since(*sent) >= window) {
+            standing.recent.pop_front();
+        }
+        let violation = if standing.recent.len() >= self.chat.rate_limit_messages {
+            ChatRejection::RateLimited
+        } else if game_core::names::contains_blocked_chat_word(text, &self.blocked_words) {
+            ChatRejection::Blocked
+        } else {
+            standing.recent.push_back(instant);
+            return Ok(text.to_string());
+        };
+
+        standing.strikes += 1;
+        if standing.strikes < self.chat.strikes_before_mute {
+            return Err(violation);
+        }
+        standing.strikes = 0;
+        let step = standing.mutes.min(self.chat.mute_escalation_secs.len().saturating_sub(1));
+        standing.mutes += 1;
+        let duration = Duration::from_secs(self.chat.mute_escalation_secs.get(step).copied().unwrap_or(60));
+        eprintln!("🔇 Muted {} for {}s after repeated chat violations", player_id, duration.as_secs());
+        state.mute(player_id, duration, now);
+        Err(ChatRejection::Muted(duration))
+    }
+
+    /// Score content in the background, flagging or muting the player above thresholds
+    /// Never blocks the caller; provider failures are logged and ignored
+    pub fn review(&self, player_id: uuid::Uuid, kind: ContentKind, text: String) {
+        let (Some(provider), Some(config)) = (self.provider.clone(), self.config.clone()) else {
+            return;
+        };
+        let state = self.state.clone();
+        let clock = self.clock.clone();
+        tokio::spawn(async move {
+            let score = match provider.score(kind, &text).await {
+                Ok(score) => score,
+                Err(e) => {
+                    eprintln!("⚠️ Moderation provider failed: {}", e);
+                    return;
+                }
+            };
+            if score < config.flag_threshold && score < config.mute_threshold {
+                return;
+            }
+
+            let auto_muted = score >= config.mute_threshold;
+            let mut state = state.write().await;
+            if auto_muted {
+                eprintln!("🔇 Auto-muted {} for {}s (score {:.2})", player_id, config.mute_secs, score);
+                state.mute(player_id, Duration::from_secs(config.mute_secs), clock.now());
+            }
+            state.push_flag(ModerationFlag {
+                player_id,
+                kind,
+                text,
+                score,
+                flagged_at: clock.unix_secs(),
+                auto_muted,
+                replay_check: None,
+            });
+        });
+    }
+
+    /// Queue an anti-cheat report for admin review
+    /// Scores 1.0 when the replay shows the movement was impossible, 0.0 otherwise
+    pub async fn flag_cheat(&self, report: game_core::CheatReport) {
+        let score = match report.check.verdict {
+            game_core::Verdict::Impossible { .. } => 1.0,
+            _ => 0.0,
+        };
+        eprintln!("🚩 Flagged {} for review: {} (replay {:?})", report.player_id, report.suspicion, report.check.verdict);
+        self.state.write().await.push_flag(ModerationFlag {
+            player_id: report.player_id,
+            kind: ContentKind::Gameplay,
+            text: report.suspicion.to_string(),
+            score,
+            flagged_at: self.clock.unix_secs(),
+            auto_muted: false,
+            replay_check: Some(report.check),
+        });
+    }
+}
@@ -0,0 +1,519 @@
+//! OpenAPI description of the HTTP API, served at /api/docs/openapi.json
+//!
+//! Written out by hand, like the signal manifest, so it stays a plain JSON document with no
+//! derive macros on the handlers. Request bodies for the player, chat, config and admin
+//! routes are described field by field; the rest get a summary and their status codes.
+//! Keep it in step with `routes.rs` when adding a route.
+
+use serde_json::{json, Map, Value};
+
+/// Swagger UI build the docs page loads, like the overlay loads Datastar
+const SWAGGER_UI: &str = "https://cdn.jsdelivr.net/npm/swagger-ui-dist@5.17.14";
+
+/// Who may call a route
+#[derive(Clone, Copy, PartialEq)]
+enum Access {
+    Public,
+    /// Any issued API token or the admin token
+    Stats,
+    /// Moderation or admin tokens
+    Moderation,
+    /// Admin tokens only
+    Admin,
+}
+
+/// One method on one path: (method, path, tag, summary, access)
+type Route = (&'static str, &'static str, &'static str, &'static str, Access);
+
+const ROUTES: &[Route] = &[
+    ("get", "/health", "server", "Liveness and game loop health", Access::Public),
+    ("get", "/events", "streams", "Datastar SSE stream of game state, chat and HUD patches", Access::Public),
+    ("get", "/ws/state", "streams", "WebSocket stream of binary state snapshots", Access::Public),
+    ("get", "/overlay", "streams", "Caster overlay page for broadcast tools", Access::Public),
+    ("get", "/overlay/events", "streams", "SSE stream of rendered overlay fragments", Access::Public),
+    ("get", "/api/config", "config", "World geometry, physics and rates for the current map", Access::Public),
+    ("get", "/api/time", "config", "Server clock, for clock sync", Access::Public),
+    ("get", "/api/signals", "config", "Every signal the event stream can send, with the protocol version", Access::Public),
+    ("get", "/api/affinity/{room}", "server", "The instance a room lives on", Access::Public),
+    ("get", "/api/backplane", "server", "This instance's role in the distributed backplane", Access::Public),
+    ("get", "/api/geometry", "config", "Geometry of the current map", Access::Public),
+    ("get", "/api/matches", "matches", "Recent match results", Access::Public),
+    ("get", "/api/challenges/today", "matches", "Today's daily challenges", Access::Public),
+    ("post", "/api/player/init", "player", "Join or reclaim a player and get a session token", Access::Public),
+    ("post", "/api/player/heartbeat", "player", "Mark a player present without sending a command", Access::Public),
+    ("post", "/api/player/leave", "player", "Leave the game now, e.g. from sendBeacon as the tab closes", Access::Public),
+    ("post", "/api/player/register", "player", "Join with a chosen display name", Access::Public),
+    ("post", "/api/player/rename", "player", "Change a player's display name", Access::Public),
+    ("post", "/api/player/name/reservation", "player", "Reserve the player's current name", Access::Public),
+    ("delete", "/api/player/name/reservation", "player", "Release the player's name reservation", Access::Public),
+    ("get", "/api/player/settings", "player", "A player's saved settings", Access::Public),
+    ("put", "/api/player/settings", "player", "Save a player's settings", Access::Public),
+    ("get", "/api/player/cosmetics", "cosmetics", "A player's owned and equipped cosmetics", Access::Public),
+    ("post", "/api/player/cosmetics/equip", "cosmetics", "Equip an owned cosmetic", Access::Public),
+    ("post", "/api/player/cosmetics/purchase", "cosmetics", "Buy a cosmetic with earned currency", Access::Public),
+    ("post", "/api/player/command", "player", "Send a game command; state changes arrive over /events", Access::Public),
+    ("post", "/api/chat", "chat", "Send a chat message or slash command", Access::Public),
+    ("get", "/api/teams", "matches", "Teams and their members", Access::Public),
+    ("post", "/api/team/join", "matches", "Join a team", Access::Public),
+    ("get", "/api/maps", "matches", "Maps in the rotation and the current vote", Access::Public),
+    ("post", "/api/vote/map", "matches", "Vote for the next map", Access::Public),
+    ("get", "/api/modes", "matches", "Available game modes", Access::Public),
+    ("get", "/api/mutators", "matches", "Available mutators and the current vote", Access::Public),
+    ("post", "/api/vote/mutator", "matches", "Vote for a mutator", Access::Public),
+    ("get", "/api/world-event", "matches", "The world event under way, if any", Access::Public),
+    ("get", "/api/state", "streams", "The latest state snapshot", Access::Public),
+    ("get", "/api/rooms/{id}/physics", "config", "A room's physics settings", Access::Public),
+    ("patch", "/api/rooms/{id}/physics", "config", "Change a room's physics, as its creator", Access::Public),
+    ("get", "/api/plots", "housing", "Housing plots and their owners", Access::Public),
+    ("post", "/api/plots/claim", "housing", "Claim a free plot", Access::Public),
+    ("post", "/api/plots/release", "housing", "Give up a claimed plot", Access::Public),
+    ("post", "/api/plots/{id}/visit", "housing", "Visit a plot", Access::Public),
+    ("get", "/api/replays", "replays", "Recorded replays", Access::Public),
+    ("get", "/api/replays/{id}/summary", "replays", "A replay's generated summary", Access::Public),
+    ("post", "/api/replays/{id}/summary", "replays", "Queue a summary job for a replay", Access::Public),
+    ("get", "/api/replays/{id}/trajectory.svg", "replays", "SVG of every player's path through a replay", Access::Public),
+    ("get", "/api/replays/{id}/log", "replays", "A replay's event log", Access::Public),
+    ("get", "/api/replays/{id}/playback", "replays", "SSE playback of a replay", Access::Public),
+    ("get", "/api/jobs/{id}", "replays", "Status of a background job", Access::Public),
+    ("get", "/api/docs", "server", "Swagger UI for this document", Access::Public),
+    ("get", "/api/docs/openapi.json", "server", "This document", Access::Public),
+    ("get", "/api/player/{id}/export", "privacy", "Everything stored about a player", Access::Admin),
+    ("delete", "/api/player/{id}/data", "privacy", "Delete everything stored about a player", Access::Admin),
+    ("get", "/api/admin/ips", "admin", "Per-IP connection and player counts", Access::Stats),
+    ("get", "/api/admin/state", "admin", "Dump the full game state", Access::Stats),
+    ("get", "/api/admin/commands", "admin", "Depth and drops of each command lane", Access::Stats),
+    ("get", "/api/admin/streams", "admin", "Event streams that fell behind the broadcast", Access::Stats),
+    ("get", "/api/admin/latency", "admin", "Recently traced commands and latency percentiles", Access::Stats),
+    ("get", "/api/admin/flags", "admin", "Content flagged by the moderation provider", Access::Moderation),
+    ("post", "/api/admin/kick", "admin", "Remove a player from the game", Access::Moderation),
+    ("post", "/api/admin/ban", "admin", "Ban an IP address and remove its players", Access::Moderation),
+    ("post", "/api/admin/mute", "admin", "Mute a player's chat", Access::Moderation),
+    ("post", "/api/admin/command", "admin", "Apply a command to a player", Access::Moderation),
+    ("post", "/api/admin/announce", "admin", "Show an announcement banner on every client", Access::Admin),
+    ("post", "/api/admin/mode", "admin", "Switch the game mode", Access::Admin),
+    ("post", "/api/admin/mutators", "admin", "Pick the active mutators", Access::Admin),
+    ("post", "/api/admin/world-event", "admin", "Start a world event", Access::Admin),
+    ("post", "/api/admin/promote", "admin", "Promote this instance to backplane leader", Access::Admin),
+    ("post", "/api/admin/drain", "admin", "Stop taking new players ahead of a restart", Access::Admin),
+    ("get", "/api/admin/snapshot", "admin", "Snapshot the game state", Access::Admin),
+    ("post", "/api/admin/restore", "admin", "Restore a game state snapshot", Access::Admin),
+    ("get", "/api/admin/bots", "admin", "Bots in the game", Access::Admin),
+    ("post", "/api/admin/bots", "admin", "Set how many bots play", Access::Admin),
+    ("post", "/api/admin/map/platforms", "admin", "Add a platform to the current map", Access::Admin),
+    ("post", "/api/admin/map/walls", "admin", "Add a wall to the current map", Access::Admin),
+    ("post", "/api/admin/map/{id}/move", "admin", "Move a map object", Access::Admin),
+    ("delete", "/api/admin/map/{id}", "admin", "Delete a map object", Access::Admin),
+    ("get", "/api/admin/tokens", "admin", "Every issued API token, without secrets", Access::Admin),
+    ("post", "/api/admin/tokens", "admin", "Issue a scoped API token", Access::Admin),
+    ("delete", "/api/admin/tokens/{id}", "admin", "Revoke an API token", Access::Admin),
+];
+
+/// Routes, besides the admin ones, whose failures have an `Error` body (see `crate::error`)
+const API_ERROR_ROUTES: &[&str] = &[
+    "/api/player/init",
+    "/api/player/heartbeat",
+    "/api/player/leave",
+    "/api/player/register",
+    "/api/player/rename",
+    "/api/player/command",
+    "/api/chat",
+];
+
+/// Admin routes handled outside `handlers::admin`; only their auth failures have `Error` bodies
+const ADMIN_AUTH_ONLY: &[&str] = &["mode", "mutators", "world-event", "promote", "snapshot", "restore", "bots", "map/"];
+
+/// Give the failure responses of a route the `Error` schema
+fn error_bodies(responses: &mut Value) {
+    let Some(responses) = responses.as_object_mut() else {
+        return;
+    };
+    for (status, response) in responses.iter_mut() {
+        if status.as_str() >= "400" {
+            response["content"] = json_content("Error");
+        }
+    }
+}
+
+/// The OpenAPI 3 document for every route the server mounts
+pub fn spec() -> Value {
+    let mut paths = Map::new();
+    for &(method, path, tag, summary, access) in ROUTES {
+        let mut operation = json!({
+            "tags": [tag],
+            "summary": summary,
+            "responses": { "200": { "description": "OK" } },
+        });
+        let parameters: Vec<Value> = path_parameters(path).chain(query_parameters(path)).collect();
+        if !parameters.is_empty() {
+            operation["parameters"] = Value::Array(parameters);
+        }
+        if let Some(details) = details(method, path) {
+            merge(&mut operation, details);
+        }
+        if access != Access::Public {
+            operation["security"] = json!([{ "bearerAuth": [] }]);
+            operation["x-token-scope"] = json!(match access {
+                Access::Stats => "stats",
+                Access::Moderation => "moderation",
+                _ => "admin",
+            });
+            let responses = &mut operation["responses"];
+            responses["401"] = json!({ "description": "Missing or invalid bearer token", "content": json_content("Error") });
+            responses["403"] = json!({ "description": "Token scope too low for this route", "content": json_content("Error") });
+        }
+        let admin_handler = path
+            .strip_prefix("/api/admin/")
+            .is_some_and(|rest| !ADMIN_AUTH_ONLY.iter().any(|prefix| rest.starts_with(prefix)));
+        if admin_handler || API_ERROR_ROUTES.contains(&path) {
+            error_bodies(&mut operation["responses"]);
+        }
+        let item = paths.entry(path.to_string()).or_insert_with(|| json!({}));
+        item[method] = operation;
+    }
+
+    json!({
+        "openapi": "3.0.3",
+        "info": {
+            "title": "rust-datastar-mp",
+            "version": env!("CARGO_PKG_VERSION"),
+            "description": "Multiplayer platformer server. Commands go in over JSON; state comes back over the Datastar event stream at /events.",
+        },
+        "paths": paths,
+        "components": {
+            "securitySchemes": {
+                "bearerAuth": {
+                    "type": "http",
+                    "scheme": "bearer",
+                    "description": "The configured admin token, or an API token issued at /api/admin/tokens",
+                },
+            },
+            "schemas": schemas(),
+        },
+    })
+}
+
+/// The Swagger UI page; it fetches the document from next to itself
+pub fn docs_html() -> String {
+    format!(
+        r#"<!DOCTYPE html>
+<html>
+<head>
+<meta charset="utf-8">
+<title>API docs</title>
+<link rel="stylesheet" href="{ui}/swagger-ui.css">
+</head>
+<body>
+<div id="swagger-ui"></div>
+<script src="{ui}/swagger-ui-bundle.js"></script>
+<script>
+window.ui = SwaggerUIBundle({{ url: 'docs/openapi.json', dom_id: '#swagger-ui' }});
+</script>
+</body>
+</html>
+"#,
+        ui = SWAGGER_UI
+    )
+}
+
+/// `{name}` segments of a path as required string parameters
+fn path_parameters(path: &str) -> impl Iterator<Item = Value> + '_ {
+    path.split('/')
+        .filter_map(|segment| segment.strip_prefix('{')?.strip_suffix('}'))
+        .map(|name| json!({ "name": name, "in": "path", "required": true, "schema": { "type": "string" } }))
+}
+
+fn query_parameters(path: &str) -> impl Iterator<Item = Value> {
+    let parameters: &[(&str, &str, &str)] = match path {
+        "/events" => &[
+            ("mode", "string", "`player` (default) or `spectator`"),
+            ("player_id", "string", "Personalize the stream for this player"),
+            ("session", "string", "Session token from /api/player/init; personalizes the stream and keeps the player reclaimable"),
+            ("snapshot", "boolean", "Receive state at the snapshot rate and interpolate between snapshots"),
+            ("mute", "string", "Comma-separated player ids whose chat to leave out"),
+            ("resume", "string", "Token from a previous stream, restoring its filters"),
+            ("lang", "string", "Language tag for server messages"),
+            ("palette", "string", "Color palette for players and teams"),
+            ("hud", "boolean", "Also send server-rendered HUD fragments"),
+            ("protocol_version", "string", "Event protocol the client speaks, MAJOR.MINOR"),
+            ("room", "string", "Room to watch; clustered servers redirect to the instance hosting it"),
+        ],
+        _ => &[],
+    };
+    parameters.iter().map(|(name, kind, description)| {
+        json!({ "name": name, "in": "query", "schema": { "type": kind }, "description": description })
+    })
+}
+
+/// Request bodies and responses beyond the default 200
+fn details(method: &str, path: &str) -> Option<Value> {
+    let details = match (method, path) {
+        ("get", "/events") => json!({
+            "responses": {
+                "200": { "description": "Datastar patch-signals and patch-elements events", "content": { "text/event-stream": {} } },
+                "503": { "description": "Server is shutting down" },
+                "403": { "description": "The client's IP is banned" },
+                "429": { "description": "Too many connections from this IP" },
+            },
+        }),
+        ("get", "/api/config") => json!({
+            "responses": { "200": { "description": "Game configuration", "content": json_content("GameConfig") } },
+        }),
+        ("post", "/api/player/init") => json!({
+            "requestBody": body("InitRequest"),
+            "responses": {
+                "200": { "description": "Session issued", "content": json_content("InitResponse") },
+                "401": { "description": "Session token belongs to another player" },
+                "403": { "description": "The client's IP is banned" },
+                "429": { "description": "Too many players from this IP" },
+                "503": { "description": "Server full, draining or shutting down" },
+            },
+        }),
+        ("post", "/api/player/heartbeat") => json!({
+            "requestBody": body("HeartbeatRequest"),
+            "responses": {
+                "204": { "description": "Marked present" },
+                "404": { "description": "Player not in the game; init again" },
+            },
+        }),
+        ("post", "/api/player/leave") => json!({
+            "requestBody": body("LeaveRequest"),
+            "responses": {
+                "204": { "description": "Left, or already gone" },
+                "401": { "description": "Unknown session token" },
+                "503": { "description": "Gameplay command lane full" },
+            },
+        }),
+        ("post", "/api/player/register") | ("post", "/api/player/rename") => json!({
+            "requestBody": body("NameRequest"),
+        }),
+        ("post", "/api/player/name/reservation") | ("delete", "/api/player/name/reservation") => json!({
+            "requestBody": body("ReservationRequest"),
+        }),
+        ("post", "/api/player/command") => json!({
+            "requestBody": body("CommandRequest"),
+            "parameters": [{
+                "name": "X-Trace-Id",
+                "in": "header",
+                "schema": { "type": "string" },
+                "description": "Trace this command under the given id when latency tracing is on; echoed on the response",
+            }],
+            "responses": {
+                "200": { "description": "What the command will do against the current state", "content": json_content("CommandAck") },
+                "401": { "description": "Session token belongs to another player" },
+                "409": { "description": "Player is waiting for a slot" },
+                "429": { "description": "Rate limited" },
+                "503": { "description": "Gameplay command lane full" },
+            },
+        }),
+        ("post", "/api/chat") => json!({
+            "requestBody": body("ChatRequest"),
+            "responses": {
+                "200": { "description": "Sent; slash command replies come back as a patch-elements event for the sender", "content": { "text/event-stream": {} } },
+                "403": { "description": "Sender is muted" },
+                "409": { "description": "Team chat from a player without a team" },
+                "422": { "description": "Refused by the chat filter or length limit" },
+                "429": { "description": "Rate limited" },
+            },
+        }),
+        ("post", "/api/admin/kick") => json!({
+            "requestBody": body("PlayerRequest"),
+            "responses": { "404": { "description": "Player not in the game" } },
+        }),
+        ("post", "/api/admin/ban") => json!({ "requestBody": body("BanRequest") }),
+        ("post", "/api/admin/mute") => json!({ "requestBody": body("MuteRequest") }),
+        ("post", "/api/admin/command") => json!({
+            "requestBody": body("AdminCommandRequest"),
+            "responses": {
+                "202": { "description": "Queued on the admin lane" },
+                "404": { "description": "Player not in the game" },
+            },
+        }),
+        ("post", "/api/admin/announce") => json!({
+            "requestBody": body("AnnounceRequest"),
+            "responses": { "400": { "description": "Empty text" } },
+        }),
+        ("post", "/api/admin/tokens") => json!({ "requestBody": body("CreateTokenRequest") }),
+        _ => return None,
+    };
+    Some(details)
+}
+
+/// Deep-merge `extra` into `target`, appending arrays
+fn merge(target: &mut Value, extra: Value) {
+    match (target, extra) {
+        (Value::Object(target), Value::Object(extra)) => {
+            for (key, value) in extra {
+                merge(target.entry(key).or_insert(Value::Null), value);
+            }
+        }
+        (Value::Array(target), Value::Array(extra)) => target.extend(extra),
+        (target, extra) => *target = extra,
+    }
+}
+
+fn schema_ref(name: &str) -> Value {
+    json!({ "$ref": format!("#/components/schemas/{}", name) })
+}
+
+fn json_content(schema: &str) -> Value {
+    json!({ "application/json": { "schema": schema_ref(schema) } })
+}
+
+fn body(schema: &str) -> Value {
+    json!({ "required": true, "content": json_content(schema) })
+}
+
+fn object(required: &[&str], properties: Value) -> Value {
+    json!({ "type": "object", "required": required, "properties": properties })
+}
+
+fn schemas() -> Value {
+    let uuid = json!({ "type": "string", "format": "uuid" });
+    json!({
+        "Error": object(&["code", "message", "details"], json!({
+            "code": { "type": "string", "description": "Stable name for the failure, e.g. unknown_player or queue_full" },
+            "message": { "type": "string", "description": "Human-readable explanation; for chat, in the sender's language" },
+            "details": { "type": "object", "nullable": true, "description": "Anything else a client might act on, e.g. retry_after_secs" },
+        })),
+        "PlayerCommand": player_command_schema(),
+        "CommandRequest": object(&["player_id", "command", "session_token"], json!({
+            "player_id": uuid,
+            "command": schema_ref("PlayerCommand"),
+            "seq": { "type": "integer", "minimum": 0, "default": 0, "description": "Client sequence number, echoed in state updates; 0 means untracked" },
+            "session_token": { "type": "string", "description": "Token from /api/player/init; must belong to player_id" },
+        })),
+        "CommandAck": {
+            "type": "object",
+            "required": ["status", "cooldowns"],
+            "properties": {
+                "status": { "type": "string", "enum": ["accepted", "buffered", "ignored"] },
+                "reason": {
+                    "type": "object",
+                    "description": "Why an ignored command did nothing",
+                    "properties": { "code": { "type": "string" }, "detail": {} },
+                },
+                "cooldowns": object(&["dash_ms", "shoot_ms", "place_block_ms"], json!({
+                    "dash_ms": { "type": "integer" },
+                    "shoot_ms": { "type": "integer" },
+                    "place_block_ms": { "type": "integer" },
+                })),
+            },
+        },
+        "InitRequest": object(&[], json!({
+            "player_id": { "type": "string", "format": "uuid", "description": "The player to reclaim; only honored with their session token" },
+            "session_token": { "type": "string", "description": "Token from an earlier init, to reclaim the player; without one a new player is created" },
+            "language": { "type": "string", "description": "Language tag for server messages; defaults to Accept-Language" },
+            "room": { "type": "string", "description": "Room to join; clustered servers redirect to the instance hosting it" },
+        })),
+        "InitResponse": object(&["player_id", "session_token"], json!({
+            "player_id": uuid,
+            "session_token": { "type": "string" },
+            "waiting": { "type": "integer", "nullable": true, "description": "Place in the queue when the server is full" },
+        })),
+        "HeartbeatRequest": object(&["player_id", "session_token"], json!({
+            "player_id": uuid,
+            "session_token": { "type": "string", "description": "Token from /api/player/init; must belong to player_id" },
+        })),
+        "NameRequest": object(&["player_id", "name", "session_token"], json!({
+            "player_id": uuid,
+            "name": { "type": "string" },
+            "session_token": { "type": "string", "description": "Token from /api/player/init; must belong to player_id" },
+        })),
+        "ReservationRequest": object(&["session_token"], json!({
+            "session_token": { "type": "string" },
+        })),
+        "LeaveRequest": object(&["session_token"], json!({
+            "session_token": { "type": "string", "description": "Token from /api/player/init; its player is the one who leaves" },
+        })),
+        "ChatRequest": object(&["player_id", "text", "session_token"], json!({
+            "player_id": uuid,
+            "session_token": { "type": "string", "description": "Token from /api/player/init; must belong to player_id" },
+            "text": { "type": "string", "description": "Message, or a slash command such as /help" },
+            "team": { "type": "boolean", "default": false, "description": "Send to the player's team only" },
+        })),
+        "GameConfig": {
+            "type": "object",
+            "required": ["map", "config_version", "protocol_version", "tick_rate_hz", "physics", "platforms", "walls"],
+            "properties": {
+                "map": { "type": "string" },
+                "config_version": { "type": "integer" },
+                "protocol_version": { "type": "string", "description": "Event protocol version, MAJOR.MINOR" },
+                "supported_protocol_majors": { "type": "array", "items": { "type": "integer" } },
+                "tick_rate_hz": { "type": "number" },
+                "broadcast_rate_hz": { "type": "number" },
+                "snapshot_rate_hz": { "type": "number" },
+                "heartbeat_interval_secs": { "type": "integer" },
+                "physics": { "type": "object" },
+                "platforms": { "type": "array", "items": { "type": "object" } },
+                "walls": { "type": "array", "items": { "type": "object" } },
+                "ladders": { "type": "array", "items": { "type": "object" } },
+                "teams": { "type": "array", "items": { "type": "object" } },
+                "spawn_points": { "type": "array", "items": { "type": "object" } },
+                "hazards": { "type": "array", "items": { "type": "object" } },
+                "force_zones": { "type": "array", "items": { "type": "object" } },
+                "portals": { "type": "array", "items": { "type": "object" } },
+                "checkpoints": { "type": "array", "items": { "type": "object" } },
+                "race": { "type": "object" },
+                "control_zone": { "type": "object", "nullable": true },
+                "king_of_the_hill": { "type": "object" },
+                "cosmetics": { "type": "array", "items": { "type": "object" } },
+                "building": { "type": "object" },
+            },
+        },
+        "PlayerRequest": object(&["player_id"], json!({ "player_id": uuid })),
+        "BanRequest": object(&["ip"], json!({ "ip": { "type": "string", "description": "IPv4 or IPv6 address" } })),
+        "MuteRequest": object(&["player_id"], json!({
+            "player_id": uuid,
+            "duration_secs": { "type": "integer", "default": 300 },
+        })),
+        "AdminCommandRequest": object(&["player_id", "command"], json!({
+            "player_id": uuid,
+            "command": schema_ref("PlayerCommand"),
+        })),
+        "AnnounceRequest": object(&["text"], json!({
+            "text": { "type": "string" },
+            "severity": { "type": "string", "enum": ["info", "warning", "critical"], "default": "info" },
+            "duration_secs": { "type": "integer", "description": "Seconds the banner stays up; 0 keeps it until the next announcement" },
+        })),
+        "CreateTokenRequest": object(&["name", "scope"], json!({
+            "name": { "type": "string" },
+            "scope": { "type": "string", "enum": ["stats", "moderation", "admin"] },
+        })),
+    })
+}
+
+/// One variant per command, told apart by `type`
+fn player_command_schema() -> Value {
+    let number = json!({ "type": "number" });
+    let key = json!({ "type": "string", "enum": ["Left", "Right"] });
+    let variants = [
+        ("MoveLeft", json!({})),
+        ("MoveRight", json!({})),
+        ("Jump", json!({})),
+        ("Stop", json!({})),
+        ("MoveUp", json!({})),
+        ("MoveDown", json!({})),
+        ("Crouch", json!({})),
+        ("StandUp", json!({})),
+        ("Sprint", json!({})),
+        ("StopSprint", json!({})),
+        ("Dash", json!({})),
+        ("PlaceBlock", json!({ "x": number, "y": number })),
+        ("RemoveBlock", json!({ "x": number, "y": number })),
+        ("Shoot", json!({ "dir_x": number, "dir_y": number })),
+        ("KeyDown", json!({ "key": key })),
+        ("KeyUp", json!({ "key": key })),
+        ("Move", json!({ "axis": number })),
+    ];
+    let one_of: Vec<Value> = variants
+        .into_iter()
+        .map(|(name, fields)| {
+            let mut required = vec!["type".to_string()];
+            required.extend(fields.as_object().into_iter().flat_map(|f| f.keys().cloned()));
+            let mut properties = json!({ "type": { "type": "string", "enum": [name] } });
+            merge(&mut properties, fields);
+            json!({ "title": name, "type": "object", "required": required, "properties": properties })
+        })
+        .collect();
+    json!({ "oneOf": one_of, "discriminator": { "propertyName": "type" } })
+}
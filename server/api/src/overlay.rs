@@ -0,0 +1,76 @@
+//! Fragments for the caster overlay at /overlay
+//!
+//! The page is plain HTML plus Datastar from a CDN; everything it shows is rendered here
+//! and patched in over /overlay/events, so broadcast tools only need a browser source.
+
+use std::fmt::Write;
+use game_core::OverlayStats;
+use crate::chat_commands::escape_html;
+
+pub const OVERLAY_SELECTOR: &str = "#overlay";
+
+const DATASTAR_BUNDLE: &str = "https://cdn.jsdelivr.net/gh/starfederation/datastar@1.0.0-RC.1/bundles/datastar.js";
+
+/// Rows in the overlay's leaderboard
+const LEADERBOARD_ROWS: usize = 8;
+
+/// The page broadcast tools load; it opens the stream and waits for fragments
+pub fn page_html() -> String {
+    format!(
+        r#"<!DOCTYPE html>
+<html>
+<head>
+<meta charset="utf-8">
+<title>Live stats</title>
+<script type="module" src="{}"></script>
+<style>
+body {{ margin: 0; background: transparent; font-family: sans-serif; color: #FFFFFF; }}
+#overlay {{ display: inline-block; padding: 12px 16px; background: rgba(0, 0, 0, 0.6); border-radius: 6px; }}
+</style>
+</head>
+<body data-on-load="@get('overlay/events')">
+<div id="overlay"></div>
+</body>
+</html>
+"#,
+        DATASTAR_BUNDLE
+    )
+}
+
+/// Leaderboard with actions per minute, lead changes and where players are on the map
+pub fn overlay_html(stats: &OverlayStats) -> String {
+    let mut html = String::from(r#"<div id="overlay">"#);
+
+    let leader = stats.leader.as_deref().map(escape_html).unwrap_or_else(|| "-".to_string());
+    let _ = write!(
+        html,
+        r#"<div class="lead" style="font-size: 14px; margin-bottom: 8px">Leader: <b>{}</b> · Lead changes: <b>{}</b></div>"#,
+        leader, stats.lead_changes
+    );
+
+    html.push_str(r#"<table class="leaderboard" style="font-size: 14px; border-collapse: collapse"><tr><th align="left">Player</th><th align="right">Score</th><th align="right">APM</th></tr>"#);
+    for player in stats.players.iter().take(LEADERBOARD_ROWS) {
+        let _ = write!(
+            html,
+            r#"<tr><td style="padding-right: 16px">{}</td><td align="right">{}</td><td align="right" style="padding-left: 12px">{}</td></tr>"#,
+            escape_html(&player.name),
+            player.score,
+            player.apm
+        );
+    }
+    html.push_str("</table>");
+
+    // One cell per column of the map, brighter where more players are
+    let busiest = stats.density.iter().copied().max().unwrap_or(0).max(1);
+    html.push_str(r#"<div class="density" style="display: flex; gap: 2px; margin-top: 8px">"#);
+    for &count in &stats.density {
+        let _ = write!(
+            html,
+            r#"<div title="{}" style="flex: 1; min-width: 12px; height: 10px; background: rgba(255, 200, 0, {:.2})"></div>"#,
+            count,
+            0.1 + 0.9 * count as f32 / busiest as f32
+        );
+    }
+    html.push_str("</div></div>");
+    html
+}
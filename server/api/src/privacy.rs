@@ -0,0 +1,84 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::time::Duration;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use game_core::JsonStore;
+
+/// What's left of a player after their data is deleted
+///
+/// Only a SHA-256 hash of the player id is kept, so the tombstone can't be traced back to
+/// a person, but the same id coming back is recognised and picks up any mute it had.
+/// Deleting your data is not a way to get unmuted.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Tombstone {
+    /// Unix time in seconds
+    pub deleted_at: u64,
+    /// Unix time in seconds the player's mute ran until, if they were muted when deleted
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub muted_until: Option<u64>,
+}
+
+/// Hex SHA-256 of a player id, the key tombstones are stored under
+pub fn hash_player_id(player_id: &uuid::Uuid) -> String {
+    Sha256::digest(player_id.as_bytes())
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+/// Tombstones of deleted players, optionally persisted to a JSON file
+#[derive(Debug, Default)]
+pub struct Tombstones {
+    tombstones: JsonStore<HashMap<String, Tombstone>>,
+}
+
+impl Tombstones {
+    /// In-memory tombstones that are lost on restart
+    pub fn in_memory() -> Self {
+        Self::default()
+    }
+
+    /// Load tombstones from a JSON file, creating it on first write if missing
+    pub async fn load_async(path: impl Into<PathBuf>) -> Result<Self, Box<dyn std::error::Error>> {
+        Ok(Self {
+            tombstones: JsonStore::load(path).await?,
+        })
+    }
+
+    pub fn get(&self, player_id: &uuid::Uuid) -> Option<&Tombstone> {
+        self.tombstones.get().get(&hash_player_id(player_id))
+    }
+
+    /// Mute time a returning deleted player still owes as of `now` (unix seconds)
+    pub fn mute_remaining(&self, player_id: &uuid::Uuid, now: u64) -> Option<Duration> {
+        self.get(player_id)
+            .and_then(|t| t.muted_until)
+            .filter(|until| *until > now)
+            .map(|until| Duration::from_secs(until - now))
+    }
+
+    /// Record a deletion, rewriting the tombstones file if one is configured
+    /// Deleting an already deleted player keeps the first deletion time and the longer mute
+    pub async fn record(
+        &mut self,
+        player_id: &uuid::Uuid,
+        tombstone: Tombstone,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let entry = self
+            .tombstones
+            .get_mut()
+            .entry(hash_player_id(player_id))
+            .or_insert_with(|| tombstone.clone());
+        entry.muted_until = entry.muted_until.max(tombstone.muted_until);
+        self.tombstones.save().await
+    }
+
+    pub fn len(&self) -> usize {
+        self.tombstones.get().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tombstones.get().is_empty()
+    }
+}
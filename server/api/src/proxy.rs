@@ -0,0 +1,132 @@
+use std::net::{IpAddr, SocketAddr};
+use axum::http::HeaderMap;
+use game_core::config::ProxyConfig;
+
+/// An IP network in CIDR notation (e.g. "10.0.0.0/8" or "fd00::/8")
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IpNet {
+    addr: IpAddr,
+    prefix: u8,
+}
+
+impl IpNet {
+    /// Parse CIDR notation; a bare address is treated as a single-host network
+    pub fn parse(text: &str) -> Option<Self> {
+        let text = text.trim();
+        let (addr, prefix) = match text.split_once('/') {
+            Some((addr, prefix)) => (addr.parse::<IpAddr>().ok()?, Some(prefix.parse::<u8>().ok()?)),
+            None => (text.parse::<IpAddr>().ok()?, None),
+        };
+        let max = if addr.is_ipv4() { 32 } else { 128 };
+        let prefix = prefix.unwrap_or(max);
+        (prefix <= max).then_some(Self { addr, prefix })
+    }
+
+    pub fn contains(&self, ip: &IpAddr) -> bool {
+        match (self.addr, ip.to_canonical()) {
+            (IpAddr::V4(net), IpAddr::V4(ip)) => {
+                let mask = u32::MAX.checked_shl(32 - self.prefix as u32).unwrap_or(0);
+                u32::from(net) & mask == u32::from(ip) & mask
+            }
+            (IpAddr::V6(net), IpAddr::V6(ip)) => {
+                let mask = u128::MAX.checked_shl(128 - self.prefix as u32).unwrap_or(0);
+                u128::from(net) & mask == u128::from(ip) & mask
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Resolves the real client IP behind trusted reverse proxies
+#[derive(Debug, Clone)]
+pub struct ClientIpResolver {
+    trusted: Vec<IpNet>,
+    /// Header the trusted proxies put the client in, instead of X-Forwarded-For
+    header: Option<String>,
+}
+
+impl ClientIpResolver {
+    pub fn new(config: &ProxyConfig) -> Self {
+        let trusted = config
+            .resolve_trusted_proxies()
+            .into_iter()
+            .filter_map(|cidr| {
+                let net = IpNet::parse(&cidr);
+                if net.is_none() {
+                    eprintln!("⚠️ Ignoring invalid trusted proxy CIDR: {}", cidr);
+                }
+                net
+            })
+            .collect();
+        Self {
+            trusted,
+            header: config.client_ip_header.clone(),
+        }
+    }
+
+    fn is_trusted(&self, ip: &IpAddr) -> bool {
+        self.trusted.iter().any(|net| net.contains(ip))
+    }
+
+    /// Determine the client IP for a request
+    ///
+    /// Forwarded headers are only honored when the direct peer is a trusted proxy, so
+    /// without any trusted proxies the peer address is always used.
+    /// The chain is walked right to left, skipping trusted proxies, so a client can't
+    /// spoof its address by prepending entries to X-Forwarded-For.
+    pub fn resolve(&self, headers: &HeaderMap, peer: SocketAddr) -> IpAddr {
+        let peer_ip = peer.ip().to_canonical();
+        if !self.is_trusted(&peer_ip) {
+            return peer_ip;
+        }
+
+        let header = self.header.as_deref().unwrap_or("x-forwarded-for");
+        let mut chain = forwarded_chain(headers, header);
+        if chain.is_empty() {
+            chain = forwarded_chain(headers, "forwarded");
+        }
+        chain
+            .into_iter()
+            .rev()
+            .find(|ip| !self.is_trusted(ip))
+            .unwrap_or(peer_ip)
+    }
+}
+
+/// Addresses from a forwarding header, in order from original client to nearest proxy
+/// Supports X-Forwarded-For style lists and RFC 7239 `Forwarded: for=...` entries
+fn forwarded_chain(headers: &HeaderMap, name: &str) -> Vec<IpAddr> {
+    let is_rfc7239 = name.eq_ignore_ascii_case("forwarded");
+    headers
+        .get_all(name)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .filter_map(|entry| {
+            let entry = entry.trim();
+            if is_rfc7239 {
+                entry
+                    .split(';')
+                    .find_map(|pair| {
+                        let (key, value) = pair.trim().split_once('=')?;
+                        key.eq_ignore_ascii_case("for").then_some(value)
+                    })
+                    .and_then(parse_forwarded_node)
+            } else {
+                entry.parse().ok()
+            }
+        })
+        .map(|ip: IpAddr| ip.to_canonical())
+        .collect()
+}
+
+/// Parse an RFC 7239 node: `192.0.2.1`, `"192.0.2.1:8080"` or `"[2001:db8::1]:443"`
+fn parse_forwarded_node(node: &str) -> Option<IpAddr> {
+    let node = node.trim().trim_matches('"');
+    if let Some(rest) = node.strip_prefix('[') {
+        return rest.split(']').next()?.parse().ok();
+    }
+    node.parse()
+        .ok()
+        .or_else(|| node.rsplit_once(':').and_then(|(ip, _)| ip.parse().ok()))
+}
@@ -0,0 +1,167 @@
+//! Request rate limits on the routes that feed the game loop
+//!
+//! Each IP and each player named in a request has a token bucket: a request takes a token,
+//! and tokens refill at the sustained rate up to the burst size. A request needs a token from
+//! both its buckets; refused ones get 429 with Retry-After and never reach the handler, so
+//! floods of commands or chat don't pile up in front of the single game loop.
+
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use axum::body::Body;
+use axum::extract::{ConnectInfo, Request, State};
+use axum::http::StatusCode;
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use serde::Deserialize;
+use game_core::config::{RateLimit, RequestRateConfig};
+use game_core::SharedClock;
+use crate::error::ApiError;
+use crate::state::AppState;
+
+/// Largest request body read to find the player; bigger ones are refused
+const MAX_BODY_BYTES: usize = 64 * 1024;
+
+/// Buckets kept before full ones, which behave like new ones, are dropped
+const PRUNE_ABOVE: usize = 4096;
+
+#[derive(Debug, Clone, Copy)]
+struct Bucket {
+    tokens: f32,
+    updated_ms: u64,
+}
+
+/// Buckets for one kind of key, all with the same limit
+struct Buckets<K> {
+    limit: RateLimit,
+    buckets: HashMap<K, Bucket>,
+}
+
+impl<K: Eq + Hash + Copy> Buckets<K> {
+    fn new(limit: RateLimit) -> Self {
+        Self { limit, buckets: HashMap::new() }
+    }
+
+    /// Tokens `key` has at `now_ms`, refilled since it last spent any
+    fn available(&self, key: &K, now_ms: u64) -> f32 {
+        let burst = self.limit.burst as f32;
+        self.buckets.get(key).map_or(burst, |bucket| {
+            let refilled = now_ms.saturating_sub(bucket.updated_ms) as f32 / 1000.0 * self.limit.per_sec;
+            (bucket.tokens + refilled).min(burst)
+        })
+    }
+
+    /// How long until `key` has a token, or zero if it has one now
+    fn wait(&self, key: &K, now_ms: u64) -> Duration {
+        let missing = 1.0 - self.available(key, now_ms);
+        if missing <= 0.0 {
+            Duration::ZERO
+        } else {
+            // A bucket that never refills waits forever
+            Duration::try_from_secs_f32(missing / self.limit.per_sec).unwrap_or(Duration::MAX)
+        }
+    }
+
+    fn take(&mut self, key: K, now_ms: u64) {
+        let tokens = self.available(&key, now_ms) - 1.0;
+        self.buckets.insert(key, Bucket { tokens, updated_ms: now_ms });
+        if self.buckets.len() > PRUNE_ABOVE {
+            let burst = self.limit.burst as f32;
+            let limit = self.limit;
+            self.buckets.retain(|_, bucket| {
+                bucket.tokens + now_ms.saturating_sub(bucket.updated_ms) as f32 / 1000.0 * limit.per_sec < burst
+            });
+        }
+    }
+}
+
+struct Limits {
+    ips: Buckets<IpAddr>,
+    players: Buckets<uuid::Uuid>,
+}
+
+/// Per-IP and per-player token buckets
+#[derive(Clone)]
+pub struct RateLimiter {
+    enabled: bool,
+    limits: Arc<Mutex<Limits>>,
+    clock: SharedClock,
+}
+
+impl RateLimiter {
+    pub fn new(config: &RequestRateConfig, clock: SharedClock) -> Self {
+        Self {
+            enabled: config.enabled,
+            limits: Arc::new(Mutex::new(Limits {
+                ips: Buckets::new(config.per_ip),
+                players: Buckets::new(config.per_player),
+            })),
+            clock,
+        }
+    }
+
+    pub fn enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Spend a request from the IP's bucket and the player's, if one is named
+    /// Nothing is spent when either is empty; the error says how long until both have one
+    pub fn check(&self, ip: IpAddr, player_id: Option<uuid::Uuid>) -> Result<(), Duration> {
+        if !self.enabled {
+            return Ok(());
+        }
+        let now_ms = self.clock.unix_millis();
+        let mut limits = self.limits.lock().unwrap();
+        let wait = player_id
+            .map(|player_id| limits.players.wait(&player_id, now_ms))
+            .unwrap_or_default()
+            .max(limits.ips.wait(&ip, now_ms));
+        if !wait.is_zero() {
+            return Err(wait);
+        }
+        limits.ips.take(ip, now_ms);
+        if let Some(player_id) = player_id {
+            limits.players.take(player_id, now_ms);
+        }
+        Ok(())
+    }
+}
+
+/// The player a JSON request body is about, and the session token proving it
+#[derive(Deserialize)]
+struct PlayerRef {
+    player_id: Option<uuid::Uuid>,
+    session_token: Option<String>,
+}
+
+/// Middleware applying the request rate limits
+pub async fn limit(
+    State(app_state): State<AppState>,
+    ConnectInfo(peer): ConnectInfo<SocketAddr>,
+    request: Request,
+    next: Next,
+) -> Response {
+    if !app_state.rate_limiter.enabled() {
+        return next.run(request).await;
+    }
+    let ip = app_state.client_ip.resolve(request.headers(), peer);
+    let (parts, body) = request.into_parts();
+    let Ok(bytes) = axum::body::to_bytes(body, MAX_BODY_BYTES).await else {
+        return StatusCode::PAYLOAD_TOO_LARGE.into_response();
+    };
+    // Only a verified player is charged, so naming someone else's id can't drain their bucket;
+    // requests without a valid token are refused by the handler and pay from the IP's bucket
+    let player_id = serde_json::from_slice::<PlayerRef>(&bytes).ok().and_then(|r| {
+        let verified = app_state.sessions.verify(r.session_token.as_deref()?)?;
+        (r.player_id == Some(verified)).then_some(verified)
+    });
+
+    if let Err(wait) = app_state.rate_limiter.check(ip, player_id) {
+        // Whole seconds, rounded up so a retry right on time succeeds
+        let retry_after = wait.as_secs().saturating_add(u64::from(wait.subsec_nanos() > 0));
+        return ApiError::RateLimited { retry_after_secs: retry_after.max(1) }.into_response();
+    }
+    next.run(Request::from_parts(parts, Body::from(bytes))).await
+}
@@ -0,0 +1,218 @@
+use std::collections::{HashMap, VecDeque};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use serde::Serialize;
+use tokio::sync::mpsc;
+use game_core::config::ReplayConfig;
+use game_core::{MatchLog, Replay, ReplaySummary, SharedClock};
+
+/// Most jobs remembered for polling; the oldest finished ones are forgotten first
+const MAX_JOBS: usize = 256;
+
+/// Where a summary job is up to
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum JobStatus {
+    Queued,
+    Running,
+    Done,
+    Failed { error: String },
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Job {
+    pub id: uuid::Uuid,
+    pub replay_id: uuid::Uuid,
+    #[serde(flatten)]
+    pub status: JobStatus,
+    /// Unix time in seconds
+    pub created_at: u64,
+}
+
+/// A finished summary, ready to download
+#[derive(Debug, Clone)]
+pub struct RenderedSummary {
+    pub summary: ReplaySummary,
+    pub svg: Option<String>,
+}
+
+/// Why a summary can't be started or served
+#[derive(Debug, Clone, PartialEq)]
+pub enum ReplayError {
+    /// No replay directory is configured
+    Disabled,
+    NotFound,
+    /// The saved file couldn't be read back
+    Corrupt(String),
+    /// Every remembered job is still queued or running
+    Busy,
+}
+
+impl std::fmt::Display for ReplayError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ReplayError::Disabled => write!(f, "replays are not recorded on this server"),
+            ReplayError::NotFound => write!(f, "no such replay"),
+            ReplayError::Corrupt(e) => write!(f, "replay can't be read: {}", e),
+            ReplayError::Busy => write!(f, "too many summaries are under way; try again later"),
+        }
+    }
+}
+
+impl std::error::Error for ReplayError {}
+
+struct Shared {
+    /// Oldest first
+    jobs: Mutex<VecDeque<Job>>,
+    summaries: Mutex<HashMap<uuid::Uuid, Arc<RenderedSummary>>>,
+    queue: mpsc::UnboundedSender<uuid::Uuid>,
+    /// Workers take turns receiving the next job
+    pending: tokio::sync::Mutex<mpsc::UnboundedReceiver<uuid::Uuid>>,
+}
+
+/// Turns saved replays into summaries on background workers, tracking each job for polling
+#[derive(Clone)]
+pub struct ReplayJobs {
+    shared: Arc<Shared>,
+    dir: Option<PathBuf>,
+    render_svg: bool,
+    clock: SharedClock,
+}
+
+impl ReplayJobs {
+    pub fn new(config: &ReplayConfig, clock: SharedClock) -> Self {
+        let (queue, pending) = mpsc::unbounded_channel();
+        Self {
+            shared: Arc::new(Shared {
+                jobs: Mutex::new(VecDeque::new()),
+                summaries: Mutex::new(HashMap::new()),
+                queue,
+                pending: tokio::sync::Mutex::new(pending),
+            }),
+            dir: config.dir.as_ref().map(Into::into),
+            render_svg: config.render_svg,
+            clock,
+        }
+    }
+
+    /// Start `count` workers on the current runtime
+    pub fn spawn_workers(&self, count: usize) {
+        for _ in 0..count.max(1) {
+            tokio::spawn(self.clone().run_worker());
+        }
+    }
+
+    /// Ids of every saved replay
+    pub async fn list(&self) -> Result<Vec<uuid::Uuid>, ReplayError> {
+        let dir = self.dir.as_ref().ok_or(ReplayError::Disabled)?;
+        let mut ids = Vec::new();
+        let Ok(mut entries) = tokio::fs::read_dir(dir).await else {
+            return Ok(ids);
+        };
+        while let Ok(Some(entry)) = entries.next_entry().await {
+            let path = entry.path();
+            if path.extension().is_some_and(|e| e == "json") {
+                if let Some(id) = path.file_stem().and_then(|s| s.to_str()).and_then(|s| s.parse().ok()) {
+                    ids.push(id);
+                }
+            }
+        }
+        ids.sort();
+        Ok(ids)
+    }
+
+    /// The binary match log of a replay, as saved
+    pub async fn log_bytes(&self, replay_id: &uuid::Uuid) -> Result<Vec<u8>, ReplayError> {
+        let dir = self.dir.as_ref().ok_or(ReplayError::Disabled)?;
+        tokio::fs::read(MatchLog::path(dir, replay_id)).await.map_err(|_| ReplayError::NotFound)
+    }
+
+    /// The match log of a replay, decoded for playback
+    pub async fn load_log(&self, replay_id: &uuid::Uuid) -> Result<MatchLog, ReplayError> {
+        let bytes = self.log_bytes(replay_id).await?;
+        MatchLog::decode(&bytes).map_err(|e| ReplayError::Corrupt(e.to_string()))
+    }
+
+    /// Queue a summary of the replay, unless one is done or already on its way
+    pub async fn request(&self, replay_id: uuid::Uuid) -> Result<Job, ReplayError> {
+        let dir = self.dir.as_ref().ok_or(ReplayError::Disabled)?;
+        if let Some(job) = self.latest_job(&replay_id).filter(|job| !matches!(job.status, JobStatus::Failed { .. })) {
+            return Ok(job);
+        }
+        if !tokio::fs::try_exists(Replay::path(dir, &replay_id)).await.unwrap_or(false) {
+            return Err(ReplayError::NotFound);
+        }
+        let job = Job {
+            id: uuid::Uuid::new_v4(),
+            replay_id,
+            status: JobStatus::Queued,
+            created_at: self.clock.unix_secs(),
+        };
+        {
+            let mut jobs = self.shared.jobs.lock().unwrap();
+            if jobs.len() >= MAX_JOBS {
+                let finished = jobs
+                    .iter()
+                    .position(|job| matches!(job.status, JobStatus::Done | JobStatus::Failed { .. }))
+                    .ok_or(ReplayError::Busy)?;
+                jobs.remove(finished);
+            }
+            jobs.push_back(job.clone());
+        }
+        let _ = self.shared.queue.send(job.id);
+        eprintln!("📼 Queued summary job {} for replay {}", job.id, replay_id);
+        Ok(job)
+    }
+
+    pub fn job(&self, job_id: &uuid::Uuid) -> Option<Job> {
+        self.shared.jobs.lock().unwrap().iter().find(|job| &job.id == job_id).cloned()
+    }
+
+    /// The job for a replay requested last
+    pub fn latest_job(&self, replay_id: &uuid::Uuid) -> Option<Job> {
+        self.shared.jobs.lock().unwrap().iter().rev().find(|job| &job.replay_id == replay_id).cloned()
+    }
+
+    pub fn summary(&self, replay_id: &uuid::Uuid) -> Option<Arc<RenderedSummary>> {
+        self.shared.summaries.lock().unwrap().get(replay_id).cloned()
+    }
+
+    fn set_status(&self, job_id: &uuid::Uuid, status: JobStatus) {
+        if let Some(job) = self.shared.jobs.lock().unwrap().iter_mut().find(|job| &job.id == job_id) {
+            job.status = status;
+        }
+    }
+
+    async fn run_worker(self) {
+        loop {
+            let next = self.shared.pending.lock().await.recv().await;
+            let Some(job_id) = next else { return };
+            let Some(job) = self.job(&job_id) else { continue };
+            self.set_status(&job_id, JobStatus::Running);
+            match self.summarize(job.replay_id).await {
+                Ok(rendered) => {
+                    self.shared.summaries.lock().unwrap().insert(job.replay_id, Arc::new(rendered));
+                    self.set_status(&job_id, JobStatus::Done);
+                    eprintln!("📼 Summarized replay {}", job.replay_id);
+                }
+                Err(e) => {
+                    eprintln!("❌ Failed to summarize replay {}: {}", job.replay_id, e);
+                    self.set_status(&job_id, JobStatus::Failed { error: e.to_string() });
+                }
+            }
+        }
+    }
+
+    async fn summarize(&self, replay_id: uuid::Uuid) -> Result<RenderedSummary, Box<dyn std::error::Error + Send + Sync>> {
+        let dir = self.dir.as_ref().ok_or(ReplayError::Disabled)?;
+        let replay = Replay::load(dir, &replay_id).await?;
+        let render_svg = self.render_svg;
+        // Long matches make for big replays; keep the number crunching off the async workers
+        let rendered = tokio::task::spawn_blocking(move || RenderedSummary {
+            summary: ReplaySummary::of(&replay),
+            svg: render_svg.then(|| game_core::replay::trajectory_svg(&replay)),
+        })
+        .await?;
+        Ok(rendered)
+    }
+}
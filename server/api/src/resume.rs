@@ -0,0 +1,94 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Per-subscriber stream settings restored when a client resumes
+#[derive(Debug, Clone, Default)]
+pub struct SubscriberFilter {
+    /// Personalize the stream for this player (challenge progress)
+    pub player_id: Option<uuid::Uuid>,
+    /// Receive state at the snapshot rate instead of every tick
+    pub snapshot: bool,
+    /// Players whose chat this subscriber has muted
+    pub muted: HashSet<uuid::Uuid>,
+    /// Watching without a player; counted in the spectator count
+    pub spectator: bool,
+    /// Also receive server-rendered HUD fragments
+    pub hud: bool,
+}
+
+struct ResumeEntry {
+    filter: SubscriberFilter,
+    /// When the stream holding this token disconnected; None while it is live
+    disconnected_at: Option<Instant>,
+}
+
+/// Issues resume tokens for SSE streams so reconnecting clients keep their filter state
+#[derive(Clone)]
+pub struct ResumeStore {
+    ttl: Duration,
+    entries: Arc<Mutex<HashMap<String, ResumeEntry>>>,
+}
+
+impl ResumeStore {
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            entries: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Register a stream's filter and return its token, marked live until the guard drops
+    pub fn issue(&self, filter: SubscriberFilter) -> ResumeGuard {
+        let token = uuid::Uuid::new_v4().simple().to_string();
+        let mut entries = self.entries.lock().unwrap();
+        // Forget tokens whose reconnect window has passed
+        entries.retain(|_, entry| {
+            entry
+                .disconnected_at
+                .is_none_or(|at| at.elapsed() < self.ttl)
+        });
+        entries.insert(
+            token.clone(),
+            ResumeEntry {
+                filter,
+                disconnected_at: None,
+            },
+        );
+        ResumeGuard {
+            token,
+            entries: self.entries.clone(),
+        }
+    }
+
+    /// Take the filter for a token if it is still within its reconnect window
+    /// Tokens are single use: the resumed stream is issued a fresh one
+    pub fn take(&self, token: &str) -> Option<SubscriberFilter> {
+        let mut entries = self.entries.lock().unwrap();
+        let entry = entries.remove(token)?;
+        let expired = entry
+            .disconnected_at
+            .is_some_and(|at| at.elapsed() >= self.ttl);
+        (!expired).then_some(entry.filter)
+    }
+}
+
+/// Holds a stream's token; starts the reconnect window when the stream ends
+pub struct ResumeGuard {
+    token: String,
+    entries: Arc<Mutex<HashMap<String, ResumeEntry>>>,
+}
+
+impl ResumeGuard {
+    pub fn token(&self) -> &str {
+        &self.token
+    }
+}
+
+impl Drop for ResumeGuard {
+    fn drop(&mut self) {
+        if let Some(entry) = self.entries.lock().unwrap().get_mut(&self.token) {
+            entry.disconnected_at = Some(Instant::now());
+        }
+    }
+}
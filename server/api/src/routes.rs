@@ -3,14 +3,143 @@ use crate::handlers;
 use crate::state::AppState;
 
 pub fn create_routes(app_state: AppState) -> Router {
+    // Admin endpoints all sit behind bearer token middleware; scoped API tokens reach
+    // the read-only and moderation routes, only admin tokens reach the rest
+    let stats_routes = Router::new()
+        .route("/ips", axum::routing::get(handlers::admin::ip_usage))
+        .route("/state", axum::routing::get(handlers::admin::dump_state))
+        .route("/commands", axum::routing::get(handlers::admin::command_lanes))
+        .route("/streams", axum::routing::get(handlers::admin::stream_lag))
+        .route("/latency", axum::routing::get(handlers::admin::latency_traces))
+        .route_layer(axum::middleware::from_fn_with_state(
+            app_state.clone(),
+            handlers::admin::require_stats,
+        ));
+    let moderation_routes = Router::new()
+        .route("/flags", axum::routing::get(handlers::admin::moderation_flags))
+        .route("/kick", axum::routing::post(handlers::admin::kick_player))
+        .route("/ban", axum::routing::post(handlers::admin::ban_ip))
+        .route("/mute", axum::routing::post(handlers::admin::mute_player))
+        .route("/command", axum::routing::post(handlers::admin::command_player))
+        .route_layer(axum::middleware::from_fn_with_state(
+            app_state.clone(),
+            handlers::admin::require_moderation,
+        ));
+    let admin_routes = Router::new()
+        .route("/announce", axum::routing::post(handlers::admin::announce))
+        .route("/mode", axum::routing::post(handlers::modes::switch_mode))
+        .route("/mutators", axum::routing::post(handlers::mutators::pick_mutators))
+        .route("/world-event", axum::routing::post(handlers::world_events::start_world_event))
+        .route("/promote", axum::routing::post(handlers::backplane::promote))
+        .route("/drain", axum::routing::post(handlers::admin::drain))
+        .route("/snapshot", axum::routing::get(handlers::snapshots::take_snapshot))
+        .route("/restore", axum::routing::post(handlers::snapshots::restore_snapshot))
+        .route(
+            "/bots",
+            axum::routing::get(handlers::bots::list_bots).post(handlers::bots::set_bots),
+        )
+        .route("/map/platforms", axum::routing::post(handlers::map_editor::add_platform))
+        .route("/map/walls", axum::routing::post(handlers::map_editor::add_wall))
+        .route("/map/{id}/move", axum::routing::post(handlers::map_editor::move_object))
+        .route("/map/{id}", axum::routing::delete(handlers::map_editor::delete_object))
+        .route(
+            "/tokens",
+            axum::routing::get(handlers::admin::list_tokens).post(handlers::admin::create_token),
+        )
+        .route("/tokens/{id}", axum::routing::delete(handlers::admin::revoke_token))
+        .route_layer(axum::middleware::from_fn_with_state(
+            app_state.clone(),
+            handlers::admin::require_admin,
+        ))
+        .merge(stats_routes)
+        .merge(moderation_routes);
+    // Data export and deletion act on any player's stored data, so they need an admin token
+    let privacy_routes = Router::new()
+        .route("/api/player/{id}/export", axum::routing::get(handlers::privacy::export_player_data))
+        .route("/api/player/{id}/data", axum::routing::delete(handlers::privacy::delete_player_data))
+        .route_layer(axum::middleware::from_fn_with_state(
+            app_state.clone(),
+            handlers::admin::require_admin,
+        ));
+    // Commands and chat go straight to the game loop, so floods are turned away first
+    let rate_limited_routes = Router::new()
+        .route("/api/player/command", axum::routing::post(handlers::game::player_command))
+        // Datastar best practice: Support JSON for API calls
+        .route("/api/chat", axum::routing::post(handlers::chat::send_message))
+        .route_layer(axum::middleware::from_fn_with_state(
+            app_state.clone(),
+            crate::rate_limit::limit,
+        ));
+
     Router::new()
         .route("/health", axum::routing::get(handlers::health::health_check))
-        .route("/events", axum::routing::get(handlers::events::events_handler))
+        .route(
+            "/events",
+            axum::routing::get(handlers::events::events_handler)
+                .layer(handlers::events::compression(&app_state.game_config.stream_compression)),
+        )
+        .route("/ws/state", axum::routing::get(handlers::state_transport::state_socket))
+        .route("/overlay", axum::routing::get(handlers::overlay::overlay_page))
+        .route("/overlay/events", axum::routing::get(handlers::overlay::overlay_events))
         .route("/api/config", axum::routing::get(handlers::config::get_config))
+        .route("/api/time", axum::routing::get(handlers::time::get_time))
+        .route("/api/signals", axum::routing::get(handlers::signals::get_signals))
+        .route("/api/docs", axum::routing::get(handlers::docs::docs_page))
+        .route("/api/docs/openapi.json", axum::routing::get(handlers::docs::openapi_json))
+        .route("/api/affinity/{room}", axum::routing::get(handlers::affinity::lookup))
+        .route("/api/backplane", axum::routing::get(handlers::backplane::status))
+        .route("/api/geometry", axum::routing::get(handlers::geometry::get_geometry))
+        .route("/api/matches", axum::routing::get(handlers::matches::list_matches))
+        .route("/api/challenges/today", axum::routing::get(handlers::challenges::get_today))
         .route("/api/player/init", axum::routing::post(handlers::game::init_player))
-        .route("/api/player/command", axum::routing::post(handlers::game::player_command))
-        // Datastar best practice: Support JSON for API calls
-        .route("/api/chat", axum::routing::post(handlers::chat::send_message))
+        .route("/api/player/heartbeat", axum::routing::post(handlers::game::heartbeat))
+        .route("/api/player/leave", axum::routing::post(handlers::game::leave))
+        .route("/api/player/register", axum::routing::post(handlers::game::register_player))
+        .route("/api/player/rename", axum::routing::post(handlers::game::rename_player))
+        .route(
+            "/api/player/name/reservation",
+            axum::routing::post(handlers::names::reserve_name).delete(handlers::names::release_name),
+        )
+        .route(
+            "/api/player/settings",
+            axum::routing::get(handlers::settings::get_settings).put(handlers::settings::put_settings),
+        )
+        .route("/api/player/cosmetics", axum::routing::get(handlers::cosmetics::get_cosmetics))
+        .route("/api/player/cosmetics/equip", axum::routing::post(handlers::cosmetics::equip_cosmetic))
+        .route("/api/player/cosmetics/purchase", axum::routing::post(handlers::cosmetics::purchase_cosmetic))
+        .route("/api/teams", axum::routing::get(handlers::teams::list_teams))
+        .route("/api/team/join", axum::routing::post(handlers::teams::join_team))
+        .route("/api/maps", axum::routing::get(handlers::maps::list_maps))
+        .route("/api/vote/map", axum::routing::post(handlers::maps::vote_map))
+        .route("/api/modes", axum::routing::get(handlers::modes::list_modes))
+        .route("/api/mutators", axum::routing::get(handlers::mutators::list_mutators))
+        .route("/api/vote/mutator", axum::routing::post(handlers::mutators::vote_mutator))
+        .route("/api/world-event", axum::routing::get(handlers::world_events::get_world_event))
+        .route("/api/state", axum::routing::get(handlers::state_transport::get_state))
+        .route(
+            "/api/rooms/{id}/physics",
+            axum::routing::get(handlers::rooms::get_physics).patch(handlers::rooms::patch_physics),
+        )
+        .route("/api/plots", axum::routing::get(handlers::housing::list_plots))
+        .route("/api/plots/claim", axum::routing::post(handlers::housing::claim_plot))
+        .route("/api/plots/release", axum::routing::post(handlers::housing::release_plot))
+        .route("/api/plots/{id}/visit", axum::routing::post(handlers::housing::visit_plot))
+        .route("/api/replays", axum::routing::get(handlers::replays::list_replays))
+        .route(
+            "/api/replays/{id}/summary",
+            axum::routing::get(handlers::replays::get_summary).post(handlers::replays::request_summary),
+        )
+        .route("/api/replays/{id}/trajectory.svg", axum::routing::get(handlers::replays::get_trajectory))
+        .route("/api/replays/{id}/log", axum::routing::get(handlers::replays::get_log))
+        .route("/api/replays/{id}/playback", axum::routing::get(handlers::replays::playback_events))
+        .route("/api/jobs/{id}", axum::routing::get(handlers::replays::get_job))
+        .nest("/api/admin", admin_routes)
+        .merge(privacy_routes)
+        .merge(rate_limited_routes)
+        .layer(axum::middleware::from_fn_with_state(
+            app_state.clone(),
+            crate::affinity::instance_header,
+        ))
         .with_state(app_state)
 }
 
@@ -0,0 +1,148 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime};
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use game_core::config::SessionConfig;
+use game_core::SharedClock;
+
+type HmacSha256 = Hmac<Sha256>;
+
+#[derive(Default)]
+struct Presence {
+    /// Session streams currently open for the player
+    live_streams: usize,
+    /// When the last stream closed; None while one is open
+    disconnected_at: Option<SystemTime>,
+}
+
+/// Signs session tokens and tracks which players' streams have dropped
+///
+/// A token is `<player_id>.<hex HMAC-SHA256 of the id>`, so it proves which player a client
+/// created without any server-side storage. Players whose session stream closes are kept for
+/// the reconnect grace period, then removed by the idle cleanup.
+#[derive(Clone)]
+pub struct SessionStore {
+    key: Arc<[u8]>,
+    grace: Duration,
+    clock: SharedClock,
+    presence: Arc<Mutex<HashMap<uuid::Uuid, Presence>>>,
+}
+
+impl SessionStore {
+    pub fn new(config: &SessionConfig, clock: SharedClock) -> Self {
+        let key = match config.resolve_secret() {
+            Some(secret) => secret.into_bytes(),
+            None => {
+                eprintln!("🔑 No session secret configured (set {}), sessions end on restart", config.secret_env);
+                [uuid::Uuid::new_v4().into_bytes(), uuid::Uuid::new_v4().into_bytes()].concat()
+            }
+        };
+        Self {
+            key: key.into(),
+            grace: Duration::from_secs(config.reconnect_grace_secs),
+            clock,
+            presence: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    fn mac(&self, player_id: &uuid::Uuid) -> HmacSha256 {
+        let mut mac = HmacSha256::new_from_slice(&self.key).expect("HMAC accepts any key length");
+        mac.update(player_id.as_bytes());
+        mac
+    }
+
+    /// Token proving ownership of `player_id`
+    pub fn issue(&self, player_id: uuid::Uuid) -> String {
+        let signature: String = self
+            .mac(&player_id)
+            .finalize()
+            .into_bytes()
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect();
+        format!("{}.{}", player_id, signature)
+    }
+
+    /// The player a token was issued for, if its signature is valid
+    pub fn verify(&self, token: &str) -> Option<uuid::Uuid> {
+        let (id, signature) = token.split_once('.')?;
+        let player_id: uuid::Uuid = id.parse().ok()?;
+        let signature = (0..signature.len())
+            .step_by(2)
+            .map(|i| signature.get(i..i + 2).and_then(|hex| u8::from_str_radix(hex, 16).ok()))
+            .collect::<Option<Vec<u8>>>()?;
+        // Constant-time comparison
+        self.mac(&player_id).verify_slice(&signature).ok()?;
+        Some(player_id)
+    }
+
+    /// Mark a session stream open for the player until the guard drops
+    pub fn connect(&self, player_id: uuid::Uuid) -> SessionGuard {
+        let mut presence = self.presence.lock().unwrap();
+        let entry = presence.entry(player_id).or_default();
+        entry.live_streams += 1;
+        entry.disconnected_at = None;
+        SessionGuard {
+            player_id,
+            store: self.clone(),
+        }
+    }
+
+    /// Whether a session stream is open for the player
+    pub fn is_connected(&self, player_id: &uuid::Uuid) -> bool {
+        self.presence
+            .lock()
+            .unwrap()
+            .get(player_id)
+            .is_some_and(|p| p.live_streams > 0)
+    }
+
+    /// Players with a session stream open
+    pub fn connected(&self) -> Vec<uuid::Uuid> {
+        self.presence
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|(_, p)| p.live_streams > 0)
+            .map(|(id, _)| *id)
+            .collect()
+    }
+
+    /// Whether the player's session stream dropped longer ago than the reconnect grace period
+    pub fn grace_expired(&self, player_id: &uuid::Uuid, now: SystemTime) -> bool {
+        self.presence
+            .lock()
+            .unwrap()
+            .get(player_id)
+            .and_then(|p| p.disconnected_at)
+            .and_then(|at| now.duration_since(at).ok())
+            .is_some_and(|elapsed| elapsed > self.grace)
+    }
+
+    /// Stop tracking a removed player
+    pub fn forget(&self, player_id: &uuid::Uuid) {
+        let mut presence = self.presence.lock().unwrap();
+        if presence.get(player_id).is_some_and(|p| p.live_streams == 0) {
+            presence.remove(player_id);
+        }
+    }
+}
+
+/// Held by an open session stream; starts the reconnect grace period when dropped
+pub struct SessionGuard {
+    player_id: uuid::Uuid,
+    store: SessionStore,
+}
+
+impl Drop for SessionGuard {
+    fn drop(&mut self) {
+        let now = self.store.clock.now();
+        if let Some(entry) = self.store.presence.lock().unwrap().get_mut(&self.player_id) {
+            entry.live_streams = entry.live_streams.saturating_sub(1);
+            if entry.live_streams == 0 {
+                entry.disconnected_at = Some(now);
+            }
+        }
+    }
+}
@@ -0,0 +1,74 @@
+use std::sync::atomic::Ordering;
+use std::time::Duration;
+use crate::state::AppState;
+use crate::GameUpdate;
+
+/// How long open connections get to close after the countdown before the process exits anyway
+const FORCE_EXIT_AFTER: Duration = Duration::from_secs(5);
+
+/// Resolve on SIGINT (Ctrl+C) or, on Unix, SIGTERM
+async fn wait_for_signal() {
+    let ctrl_c = async {
+        if let Err(e) = tokio::signal::ctrl_c().await {
+            eprintln!("⚠️ Failed to listen for Ctrl+C: {}", e);
+            std::future::pending::<()>().await;
+        }
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
+            Ok(mut signal) => {
+                signal.recv().await;
+            }
+            Err(e) => {
+                eprintln!("⚠️ Failed to listen for SIGTERM: {}", e);
+                std::future::pending::<()>().await;
+            }
+        }
+    };
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => {}
+        _ = terminate => {}
+    }
+}
+
+/// Graceful shutdown for `axum::serve`
+///
+/// `started_at` is the Unix time in seconds the session began. After a signal, new players and streams are refused and clients get a `serverShutdown`
+/// countdown. When it reaches zero SSE streams close, the current session is recorded in
+/// match history and the server stops.
+pub async fn graceful(app_state: AppState, started_at: u64) {
+    wait_for_signal().await;
+    let grace = app_state.game_config.shutdown_grace_secs;
+    eprintln!("🛑 Shutdown requested, closing in {}s", grace);
+    app_state.shutting_down.store(true, Ordering::SeqCst);
+
+    for seconds_remaining in (1..=grace).rev() {
+        let _ = app_state.game_tx.send(GameUpdate::ServerShutdown { seconds_remaining });
+        tokio::time::sleep(Duration::from_secs(1)).await;
+    }
+    let _ = app_state.game_tx.send(GameUpdate::ServerShutdown { seconds_remaining: 0 });
+
+    // Flush persistence: the session so far counts as a finished sandbox match
+    {
+        let game_state = app_state.game_state.read().await;
+        if !game_state.players.is_empty() {
+            let record = game_core::MatchRecord::from_state(&game_state, "sandbox", started_at, app_state.clock.unix_secs());
+            if let Err(e) = app_state.match_history.write().await.record(record).await {
+                eprintln!("❌ Failed to save match record on shutdown: {}", e);
+            }
+        }
+    }
+
+    // Backstop in case a client never lets its connection close
+    tokio::spawn(async {
+        tokio::time::sleep(FORCE_EXIT_AFTER).await;
+        eprintln!("⚠️ Connections still open after shutdown, exiting");
+        std::process::exit(0);
+    });
+    eprintln!("👋 Shutting down");
+}
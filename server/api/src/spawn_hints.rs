@@ -0,0 +1,39 @@
+use std::collections::{HashMap, HashSet};
+use game_core::{GameState, Player, Projectile};
+
+/// What a stream has already shown its client, so the first snapshot with a new entity
+/// flags it `"spawn": true` and a teleported player `"snap": true`
+///
+/// Clients place flagged entities directly instead of interpolating them in from the
+/// origin or across the teleport. Kept per stream because throttled streams skip snapshots.
+#[derive(Debug, Default)]
+pub struct SpawnHints {
+    /// Players sent on this stream and their teleport count at the time
+    players: HashMap<uuid::Uuid, u64>,
+    /// Projectiles sent on this stream
+    projectiles: HashSet<u64>,
+}
+
+impl SpawnHints {
+    /// Flag a serialized player that is new to this stream or has teleported since
+    pub fn mark_player(&mut self, player: &Player, value: &mut serde_json::Value) {
+        match self.players.insert(player.id, player.teleports) {
+            None => value["spawn"] = true.into(),
+            Some(teleports) if teleports != player.teleports => value["snap"] = true.into(),
+            Some(_) => {}
+        }
+    }
+
+    /// Flag a serialized projectile that is new to this stream
+    pub fn mark_projectile(&mut self, projectile: &Projectile, value: &mut serde_json::Value) {
+        if self.projectiles.insert(projectile.id) {
+            value["spawn"] = true.into();
+        }
+    }
+
+    /// Forget entities that are gone, so a player who rejoins spawns again
+    pub fn forget_missing(&mut self, state: &GameState) {
+        self.players.retain(|id, _| state.players.contains_key(id));
+        self.projectiles.retain(|id| state.projectiles.iter().any(|p| p.id == *id));
+    }
+}
@@ -0,0 +1,43 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use tokio::sync::broadcast;
+use crate::GameUpdate;
+
+/// Counts open spectator streams and broadcasts the count whenever it changes
+#[derive(Clone)]
+pub struct Spectators {
+    count: Arc<AtomicUsize>,
+    game_tx: broadcast::Sender<GameUpdate>,
+}
+
+impl Spectators {
+    pub fn new(game_tx: broadcast::Sender<GameUpdate>) -> Self {
+        Self {
+            count: Arc::new(AtomicUsize::new(0)),
+            game_tx,
+        }
+    }
+
+    pub fn count(&self) -> usize {
+        self.count.load(Ordering::SeqCst)
+    }
+
+    /// Count a spectator stream until the guard drops
+    pub fn watch(&self) -> SpectatorGuard {
+        let count = self.count.fetch_add(1, Ordering::SeqCst) + 1;
+        let _ = self.game_tx.send(GameUpdate::SpectatorCount(count));
+        SpectatorGuard { spectators: self.clone() }
+    }
+}
+
+/// Held by an open spectator stream
+pub struct SpectatorGuard {
+    spectators: Spectators,
+}
+
+impl Drop for SpectatorGuard {
+    fn drop(&mut self) {
+        let count = self.spectators.count.fetch_sub(1, Ordering::SeqCst) - 1;
+        let _ = self.spectators.game_tx.send(GameUpdate::SpectatorCount(count));
+    }
+}
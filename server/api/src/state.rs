@@ -1,14 +1,125 @@
+use std::collections::VecDeque;
+use std::sync::atomic::AtomicBool;
 use std::sync::Arc;
-use tokio::sync::{broadcast, mpsc, RwLock};
+use tokio::sync::{broadcast, RwLock};
 use game_core::GameState;
 use game_core::GameConfig;
+use game_core::MatchHistory;
+use game_core::PlayerSettings;
+use game_core::NameReservations;
+use game_core::CosmeticStore;
+use game_core::SharedClock;
 
 #[derive(Clone)]
 pub struct AppState {
     pub game_state: Arc<RwLock<GameState>>,
     pub game_tx: broadcast::Sender<crate::GameUpdate>,
     pub chat_tx: broadcast::Sender<game_core::ChatMessage>,
-    pub command_tx: mpsc::Sender<(uuid::Uuid, game_core::PlayerCommand)>,
+    /// Prioritised queues feeding commands to the game loop
+    pub commands: crate::command_lanes::CommandLanes,
+    /// Server-controlled players
+    pub bots: crate::bots::Bots,
+    /// Background jobs summarizing recorded matches
+    pub replays: crate::replay_jobs::ReplayJobs,
     pub game_config: Arc<GameConfig>,
+    pub match_history: Arc<RwLock<MatchHistory>>,
+    /// Client preferences per player, opaque to the server
+    pub player_settings: Arc<RwLock<PlayerSettings>>,
+    /// Display names registered players have reserved
+    pub name_reservations: Arc<RwLock<NameReservations>>,
+    /// Cosmetic unlocks, equipped items and coins per player
+    pub cosmetics: Arc<RwLock<CosmeticStore>>,
+    /// Hashed ids of players whose data was deleted
+    pub tombstones: Arc<RwLock<crate::privacy::Tombstones>>,
+    pub moderator: crate::moderation::Moderator,
+    pub ip_limiter: crate::limits::IpLimiter,
+    pub client_ip: crate::proxy::ClientIpResolver,
+    /// Request rates on commands and chat, per IP and per player
+    pub rate_limiter: crate::rate_limit::RateLimiter,
+    /// Most recent chat messages, oldest first, bounded by chat_history_size
+    pub chat_history: Arc<RwLock<VecDeque<game_core::ChatMessage>>>,
+    pub resume: crate::resume::ResumeStore,
+    pub sessions: crate::session::SessionStore,
+    pub announcer: crate::announcements::Announcer,
+    pub spectators: crate::spectators::Spectators,
+    /// Senders into each player's open session streams, for messages meant for them alone
+    pub direct: crate::direct::DirectMessages,
+    /// Event streams that fell behind the broadcast, and what was done about it
+    pub lag_metrics: crate::backpressure::LagMetrics,
+    /// Sampled commands timed from request to broadcast
+    pub latency: crate::latency::LatencyTracer,
+    /// Bearer token for /api/admin; None disables the admin API
+    pub admin_token: Option<Arc<str>>,
+    /// Scoped tokens admins issue to dashboards and tools
+    pub api_tokens: crate::api_tokens::ApiTokens,
+    /// Which instance owns each player's room when running behind a load balancer
+    pub affinity: crate::affinity::Affinity,
+    /// Link to the other instances sharing this game in distributed mode
+    pub backplane: crate::backplane::Backplane,
+    /// Set once a shutdown signal arrives; new players and streams are refused
+    pub shutting_down: Arc<AtomicBool>,
+    /// Set by an admin ahead of a restart; new players are refused, everyone else plays on
+    pub draining: Arc<AtomicBool>,
+    /// Time source for timestamps, timeouts and cooldowns
+    pub clock: SharedClock,
 }
 
+
+impl AppState {
+    /// Fresh state for a room; the returned receiver feeds the game loop
+    pub fn new(
+        game_config: Arc<GameConfig>,
+        match_history: MatchHistory,
+        player_settings: PlayerSettings,
+        name_reservations: NameReservations,
+        cosmetics: CosmeticStore,
+        tombstones: crate::privacy::Tombstones,
+        clock: SharedClock,
+    ) -> (Self, crate::game_loop::CommandReceiver) {
+        let world = Arc::new(game_core::PhysicsWorld::new(game_config.clone()));
+        let (game_tx, _) = broadcast::channel::<crate::GameUpdate>(100);
+        let (chat_tx, _) = broadcast::channel::<game_core::ChatMessage>(100);
+        let (commands, command_rx) = crate::command_lanes::channel(&game_config.command_lanes);
+
+        let spectators = crate::spectators::Spectators::new(game_tx.clone());
+
+        let mut game_state = GameState::with_clock(world, clock.clone());
+        let bots = crate::bots::Bots::new(&game_config.bots, commands.clone());
+        bots.set_count(&mut game_state, game_config.bots.count, None);
+
+        let app_state = Self {
+            game_state: Arc::new(RwLock::new(game_state)),
+            game_tx,
+            chat_tx,
+            commands,
+            bots,
+            replays: crate::replay_jobs::ReplayJobs::new(&game_config.replays, clock.clone()),
+            match_history: Arc::new(RwLock::new(match_history)),
+            player_settings: Arc::new(RwLock::new(player_settings)),
+            name_reservations: Arc::new(RwLock::new(name_reservations)),
+            cosmetics: Arc::new(RwLock::new(cosmetics)),
+            tombstones: Arc::new(RwLock::new(tombstones)),
+            moderator: crate::moderation::Moderator::from_config(game_config.moderation.as_ref(), &game_config.chat, clock.clone()),
+            ip_limiter: crate::limits::IpLimiter::new(game_config.limits.clone()),
+            client_ip: crate::proxy::ClientIpResolver::new(&game_config.proxy),
+            rate_limiter: crate::rate_limit::RateLimiter::new(&game_config.limits.request_rate, clock.clone()),
+            chat_history: Arc::new(RwLock::new(VecDeque::with_capacity(game_config.chat_history_size))),
+            resume: crate::resume::ResumeStore::new(std::time::Duration::from_secs(game_config.resume_ttl_secs)),
+            sessions: crate::session::SessionStore::new(&game_config.session, clock.clone()),
+            announcer: crate::announcements::Announcer::default(),
+            spectators,
+            direct: crate::direct::DirectMessages::default(),
+            lag_metrics: crate::backpressure::LagMetrics::default(),
+            latency: crate::latency::LatencyTracer::new(&game_config.latency_tracing, clock.clone()),
+            admin_token: game_config.admin.resolve_token().map(Arc::from),
+            api_tokens: crate::api_tokens::ApiTokens::new(clock.clone()),
+            affinity: crate::affinity::Affinity::new(&game_config.cluster),
+            backplane: crate::backplane::Backplane::new(&game_config.backplane, &game_config.cluster),
+            shutting_down: Arc::new(AtomicBool::new(false)),
+            draining: Arc::new(AtomicBool::new(false)),
+            game_config,
+            clock,
+        };
+        (app_state, command_rx)
+    }
+}
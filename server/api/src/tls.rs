@@ -0,0 +1,66 @@
+//! HTTPS served directly with rustls, for deployments without a reverse proxy in front
+//!
+//! A plain HTTP listener can run alongside it, answering every request with a redirect to
+//! the same host and path over HTTPS.
+
+use std::future::Future;
+use std::net::SocketAddr;
+use axum::http::header::HOST;
+use axum::http::uri::Authority;
+use axum::http::{HeaderMap, StatusCode, Uri};
+use axum::response::{IntoResponse, Redirect, Response};
+use axum::Router;
+use axum_server::Handle;
+
+pub use axum_server::tls_rustls::RustlsConfig;
+
+/// Load a PEM certificate chain and private key
+pub async fn load(cert_path: &str, key_path: &str) -> Result<RustlsConfig, String> {
+    RustlsConfig::from_pem_file(cert_path, key_path)
+        .await
+        .map_err(|e| format!("failed to load TLS certificate {} and key {}: {}", cert_path, key_path, e))
+}
+
+/// Serve `app` over HTTPS until `shutdown` resolves, then wait for open connections to close
+///
+/// Handlers get the peer address as connect info, as with `axum::serve`.
+pub async fn serve(
+    listener: std::net::TcpListener,
+    config: RustlsConfig,
+    app: Router,
+    shutdown: impl Future<Output = ()> + Send + 'static,
+) -> std::io::Result<()> {
+    listener.set_nonblocking(true)?;
+    let handle = Handle::new();
+    let on_shutdown = handle.clone();
+    tokio::spawn(async move {
+        shutdown.await;
+        on_shutdown.graceful_shutdown(None);
+    });
+    axum_server::from_tcp_rustls(listener, config)
+        .handle(handle)
+        .serve(app.into_make_service_with_connect_info::<SocketAddr>())
+        .await
+}
+
+/// Router that redirects every request to the same host and path over HTTPS on `https_port`
+pub fn redirect_app(https_port: u16) -> Router {
+    Router::new().fallback(move |headers: HeaderMap, uri: Uri| async move { redirect(&headers, &uri, https_port) })
+}
+
+fn redirect(headers: &HeaderMap, uri: &Uri, https_port: u16) -> Response {
+    match https_location(headers, uri, https_port) {
+        // Permanent, and keeping the method so POSTs arrive as POSTs
+        Some(location) => Redirect::permanent(&location).into_response(),
+        None => (StatusCode::BAD_REQUEST, "Missing or invalid Host header").into_response(),
+    }
+}
+
+/// The HTTPS URL for a plain HTTP request, from its Host header with the port swapped
+fn https_location(headers: &HeaderMap, uri: &Uri, https_port: u16) -> Option<String> {
+    let host = headers.get(HOST)?.to_str().ok()?;
+    let authority: Authority = host.parse().ok()?;
+    let port = if https_port == 443 { String::new() } else { format!(":{}", https_port) };
+    let path = uri.path_and_query().map(|p| p.as_str()).unwrap_or("/");
+    Some(format!("https://{}{}{}", authority.host(), port, path))
+}
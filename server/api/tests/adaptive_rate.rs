@@ -0,0 +1,80 @@
+mod harness;
+
+use api::adaptive_rate::AdaptiveRate;
+use game_core::config::AdaptiveRateConfig;
+use game_core::GameConfig;
+use harness::TestServer;
+
+fn config() -> AdaptiveRateConfig {
+    AdaptiveRateConfig {
+        enabled: true,
+        rates_hz: vec![60.0, 30.0, 15.0],
+        degrade_backlog: 10,
+        recover_backlog: 2,
+        recover_after_secs: 5,
+    }
+}
+
+#[test]
+fn steps_down_under_backlog_and_back_up_after_keeping_up() {
+    let mut rate = AdaptiveRate::new(&config());
+    assert_eq!(rate.rate_hz(), None);
+
+    assert!(rate.observe(12, 0));
+    assert_eq!(rate.rate_hz(), Some(30.0));
+    // One burst costs one level
+    assert!(!rate.observe(12, 500));
+    assert!(rate.observe(12, 1000));
+    assert_eq!(rate.rate_hz(), Some(15.0));
+    // Already at the slowest rate
+    assert!(!rate.observe(50, 3000));
+
+    // Each level up needs its own window of keeping up
+    assert!(!rate.observe(1, 4000));
+    assert!(!rate.observe(5, 6000));
+    assert!(!rate.observe(0, 7000));
+    assert!(rate.observe(0, 12000));
+    assert_eq!(rate.rate_hz(), Some(30.0));
+    assert!(!rate.observe(0, 14000));
+    assert!(rate.observe(0, 17000));
+    assert_eq!(rate.rate_hz(), None);
+}
+
+#[test]
+fn degraded_rates_skip_states_in_between() {
+    let mut rate = AdaptiveRate::new(&config());
+    assert!(rate.should_send(0));
+    assert!(rate.should_send(16));
+
+    rate.observe(10, 16);
+    let sent: Vec<u64> = (2..=8).map(|tick| tick * 1000 / 60).filter(|ms| rate.should_send(*ms)).collect();
+    assert_eq!(sent, vec![50, 83, 116]);
+}
+
+#[test]
+fn disabled_rates_never_change() {
+    let mut rate = AdaptiveRate::new(&AdaptiveRateConfig {
+        enabled: false,
+        ..config()
+    });
+    assert!(!rate.observe(1000, 0));
+    assert_eq!(rate.rate_hz(), None);
+}
+
+#[tokio::test]
+async fn backed_up_streams_are_told_their_new_rate() {
+    let mut server = TestServer::with_config(GameConfig {
+        adaptive_rate: AdaptiveRateConfig {
+            degrade_backlog: 5,
+            ..config()
+        },
+        ..harness::test_config()
+    })
+    .await;
+    let mut events = server.subscribe("").await;
+    server.join().await;
+
+    // Many ticks without yielding pile up in the stream's queue
+    server.step(30).await;
+    assert_eq!(events.next_signal("streamRate").await, 30.0);
+}
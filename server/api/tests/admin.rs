@@ -0,0 +1,61 @@
+mod harness;
+
+use game_core::config::AdminConfig;
+use harness::{test_config, TestServer, ADMIN_TOKEN};
+use serde_json::{json, Value};
+
+/// Send a request with an optional bearer token, returning the status
+async fn status_with(server: &TestServer, method: reqwest::Method, path: &str, token: Option<&str>) -> u16 {
+    let mut request = reqwest::Client::new().request(method, server.url(path)).json(&json!({}));
+    if let Some(token) = token {
+        request = request.bearer_auth(token);
+    }
+    request.send().await.unwrap().status().as_u16()
+}
+
+#[tokio::test]
+async fn admin_routes_need_the_configured_bearer_token() {
+    let server = TestServer::start().await;
+    let routes = [
+        (reqwest::Method::GET, "/api/admin/state"),
+        (reqwest::Method::POST, "/api/admin/kick"),
+        (reqwest::Method::POST, "/api/admin/mute"),
+        (reqwest::Method::POST, "/api/admin/announce"),
+    ];
+    for (method, path) in &routes {
+        assert_eq!(status_with(&server, method.clone(), path, None).await, 401, "{}", path);
+        assert_eq!(status_with(&server, method.clone(), path, Some("guess")).await, 401, "{}", path);
+    }
+
+    // Without a configured token there's nothing to match, so the routes stay shut
+    let mut config = test_config();
+    config.admin = AdminConfig {
+        token_env: "UNSET_ADMIN_TOKEN_FOR_TESTS".to_string(),
+        token: None,
+    };
+    let locked = TestServer::with_config(config).await;
+    assert_eq!(status_with(&locked, reqwest::Method::GET, "/api/admin/state", Some(ADMIN_TOKEN)).await, 401);
+    assert_eq!(status_with(&locked, reqwest::Method::GET, "/api/admin/state", Some("")).await, 401);
+}
+
+#[tokio::test]
+async fn admins_dump_state_mute_and_kick_players() {
+    let server = TestServer::start().await;
+    let mut events = server.subscribe("").await;
+    let player_id = server.join().await;
+
+    let state: Value = server.admin_get("/api/admin/state").await.json().await.unwrap();
+    assert!(state["players"].get(player_id.to_string()).is_some(), "{}", state);
+    assert_eq!(state["tick"], 0);
+
+    let muted = server.admin_post("/api/admin/mute", json!({ "player_id": player_id, "duration_secs": 30 })).await;
+    assert_eq!(muted.status(), 200);
+    assert_eq!(server.chat(player_id, "let me talk").await.status(), 403);
+
+    let kicked: Value = server.admin_post("/api/admin/kick", json!({ "player_id": player_id })).await.json().await.unwrap();
+    assert_eq!(kicked["kicked"], player_id.to_string());
+    events.next_element_containing("left the game").await;
+    assert!(!server.app_state.game_state.read().await.players.contains_key(&player_id));
+    let again = server.admin_post("/api/admin/kick", json!({ "player_id": player_id })).await;
+    assert_eq!(again.status(), 404);
+}
@@ -0,0 +1,139 @@
+mod harness;
+
+use game_core::config::{ClusterConfig, ClusterInstance, ProxyConfig};
+use game_core::GameConfig;
+use harness::TestServer;
+use serde_json::{json, Value};
+
+/// This server is instance "a" of two
+async fn clustered() -> TestServer {
+    clustered_under(ProxyConfig::default()).await
+}
+
+async fn clustered_under(proxy: ProxyConfig) -> TestServer {
+    let instance = |id: &str| ClusterInstance {
+        id: id.to_string(),
+        url: format!("http://{}.example", id),
+    };
+    TestServer::with_config(GameConfig {
+        cluster: ClusterConfig {
+            instance_id_env: "AFFINITY_TEST_INSTANCE_ID".to_string(),
+            instance_id: Some("a".to_string()),
+            instances: vec![instance("a"), instance("b")],
+            ..ClusterConfig::default()
+        },
+        proxy,
+        ..harness::test_config()
+    })
+    .await
+}
+
+fn no_redirects() -> reqwest::Client {
+    reqwest::Client::builder().redirect(reqwest::redirect::Policy::none()).build().unwrap()
+}
+
+fn set_cookie(response: &reqwest::Response) -> &str {
+    response.headers()["set-cookie"].to_str().unwrap()
+}
+
+#[tokio::test]
+async fn joining_a_room_hosted_elsewhere_is_redirected_with_a_cookie() {
+    let server = clustered().await;
+
+    let response = no_redirects()
+        .post(server.url("/api/player/init"))
+        .json(&json!({ "room": "b" }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 307);
+    assert_eq!(response.headers()["location"], "http://b.example/api/player/init");
+    assert!(set_cookie(&response).starts_with("room_affinity=b;"));
+    assert_eq!(response.headers()["x-instance-id"], "a");
+    assert!(server.app_state.game_state.read().await.players.is_empty());
+    let body: Value = response.json().await.unwrap();
+    assert_eq!(body["instance_id"], "b");
+
+    // A client pinned to the other room by its cookie goes there too, streams included
+    let response = no_redirects()
+        .get(server.url("/events"))
+        .header("cookie", "theme=dark; room_affinity=b")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 307);
+    assert_eq!(response.headers()["location"], "http://b.example/events");
+}
+
+#[tokio::test]
+async fn redirects_keep_the_base_path() {
+    let server = clustered_under(ProxyConfig {
+        base_path: Some("/game".to_string()),
+        ..ProxyConfig::default()
+    })
+    .await;
+    let response = no_redirects()
+        .post(server.url("/game/api/player/init"))
+        .json(&json!({ "room": "b" }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 307);
+    assert_eq!(response.headers()["location"], "http://b.example/game/api/player/init");
+
+    let response = no_redirects()
+        .get(server.url("/game/events?room=b"))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 307);
+    assert_eq!(response.headers()["location"], "http://b.example/game/events?room=b");
+}
+
+#[tokio::test]
+async fn players_join_the_room_here_and_stay_pinned_to_it() {
+    let server = clustered().await;
+
+    // Whatever id the server hands out, a join without a room lands here
+    for _ in 0..2 {
+        let response = server.post("/api/player/init", json!({})).await;
+        assert_eq!(response.status(), 200);
+        assert!(set_cookie(&response).starts_with("room_affinity=a;"));
+        assert_eq!(response.headers()["x-instance-id"], "a");
+    }
+    let response = server.post("/api/player/init", json!({ "room": "a" })).await;
+    assert_eq!(response.status(), 200);
+
+    // Players in this room keep being served here even when asking for another
+    let (player_id, token) = server.join_session().await;
+    let response = no_redirects()
+        .post(server.url("/api/player/init"))
+        .json(&json!({ "player_id": player_id, "session_token": token, "room": "b" }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 200);
+}
+
+#[tokio::test]
+async fn lookup_names_the_hosting_instance() {
+    let server = clustered().await;
+
+    let response = server.get("/api/affinity/b").await;
+    assert_eq!(response.status(), 200);
+    assert!(set_cookie(&response).starts_with("room_affinity=b;"));
+    let body: Value = response.json().await.unwrap();
+    assert_eq!(body["instance_id"], "b");
+    assert_eq!(body["url"], "http://b.example");
+    assert_eq!(body["local"], false);
+    assert_eq!(server.get("/api/affinity/a").await.json::<Value>().await.unwrap()["local"], true);
+    assert_eq!(server.get("/api/affinity/nowhere").await.status(), 404);
+
+    // Standalone servers own every room and send no instance header
+    let standalone = TestServer::start().await;
+    let response = standalone.get("/api/affinity/main").await;
+    assert!(response.headers().get("x-instance-id").is_none());
+    let body: Value = response.json().await.unwrap();
+    assert_eq!(body["instance_id"], Value::Null);
+    assert_eq!(body["local"], true);
+}
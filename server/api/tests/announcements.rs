@@ -0,0 +1,49 @@
+mod harness;
+
+use harness::TestServer;
+
+#[tokio::test]
+async fn announcements_show_a_banner_on_every_client() {
+    let server = TestServer::start().await;
+    let mut first = server.subscribe("").await;
+    let mut second = server.subscribe("").await;
+
+    let response = server
+        .admin_post(
+            "/api/admin/announce",
+            serde_json::json!({ "text": "Restart in <5> minutes", "severity": "warning", "duration_secs": 0 }),
+        )
+        .await;
+    assert_eq!(response.status(), 200);
+
+    for events in [&mut first, &mut second] {
+        let html = events.next_element_containing("announcement-banner").await;
+        assert!(html.contains(r#"data-severity="warning""#), "unexpected banner: {}", html);
+        assert!(html.contains("Restart in &lt;5&gt; minutes"), "banner not escaped: {}", html);
+    }
+
+    // Streams opened while the banner is up see it too
+    let mut late = server.subscribe("").await;
+    late.next_element_containing("Restart in").await;
+}
+
+#[tokio::test]
+async fn announcements_require_the_admin_token() {
+    let server = TestServer::start().await;
+    let response = server.post("/api/admin/announce", serde_json::json!({ "text": "hi" })).await;
+    assert_eq!(response.status(), 401);
+}
+
+#[tokio::test]
+async fn timed_banners_clear_themselves() {
+    let server = TestServer::start().await;
+    let mut events = server.subscribe("").await;
+
+    server
+        .admin_post("/api/admin/announce", serde_json::json!({ "text": "Brief", "duration_secs": 1 }))
+        .await;
+    events.next_element_containing("Brief").await;
+    let cleared = events.next_element_containing(r#"<div id="announcement-banner"></div>"#).await;
+    assert!(!cleared.contains("Brief"));
+    assert!(server.app_state.announcer.current().is_none());
+}
@@ -0,0 +1,29 @@
+mod harness;
+
+use harness::TestServer;
+use serde_json::Value;
+
+#[tokio::test]
+async fn flagged_movement_reaches_admins_with_its_replay_verdict() {
+    let mut server = TestServer::start().await;
+    let player = server.join().await;
+    server.step(120).await;
+
+    // Moved by something other than the player's own inputs
+    server.app_state.game_state.write().await.players.get_mut(&player).unwrap().x += 50.0;
+    server.step(1).await;
+
+    let response = server.admin_get("/api/admin/flags").await;
+    assert_eq!(response.status(), 200);
+    let flags: Vec<Value> = response.json().await.unwrap();
+    assert_eq!(flags.len(), 1);
+    let flag = &flags[0];
+    assert_eq!(flag["player_id"], player.to_string());
+    assert_eq!(flag["kind"], "gameplay");
+    assert_eq!(flag["score"], 1.0);
+    assert!(flag["text"].as_str().unwrap().starts_with("moved at"));
+    let check = &flag["replay_check"];
+    assert_eq!(check["verdict"], "impossible");
+    assert!(check["max_deviation"].as_f64().unwrap() > 49.0);
+    assert!(!check["segment"].as_array().unwrap().is_empty());
+}
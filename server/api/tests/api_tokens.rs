@@ -0,0 +1,95 @@
+mod harness;
+
+use harness::TestServer;
+use serde_json::{json, Value};
+
+async fn issue(server: &TestServer, name: &str, scope: &str) -> (String, String) {
+    let response = server.admin_post("/api/admin/tokens", json!({ "name": name, "scope": scope })).await;
+    assert_eq!(response.status(), 200);
+    let body: Value = response.json().await.unwrap();
+    assert_eq!(body["scope"], scope);
+    (body["id"].as_str().unwrap().to_string(), body["token"].as_str().unwrap().to_string())
+}
+
+async fn get_with(server: &TestServer, path: &str, token: &str) -> reqwest::StatusCode {
+    reqwest::Client::new()
+        .get(server.url(path))
+        .bearer_auth(token)
+        .send()
+        .await
+        .unwrap()
+        .status()
+}
+
+async fn post_with(server: &TestServer, path: &str, token: &str, body: Value) -> reqwest::StatusCode {
+    reqwest::Client::new()
+        .post(server.url(path))
+        .bearer_auth(token)
+        .json(&body)
+        .send()
+        .await
+        .unwrap()
+        .status()
+}
+
+#[tokio::test]
+async fn stats_tokens_read_but_cannot_moderate() {
+    let server = TestServer::start().await;
+    let player_id = server.join().await;
+    let (_, token) = issue(&server, "dashboard", "stats").await;
+
+    assert_eq!(get_with(&server, "/api/admin/state", &token).await, 200);
+    assert_eq!(get_with(&server, "/api/admin/ips", &token).await, 200);
+    assert_eq!(get_with(&server, "/api/admin/flags", &token).await, 403);
+    let kick = post_with(&server, "/api/admin/kick", &token, json!({ "player_id": player_id })).await;
+    assert_eq!(kick, 403);
+    assert!(server.app_state.game_state.read().await.players.contains_key(&player_id));
+    assert_eq!(get_with(&server, "/api/admin/tokens", &token).await, 403);
+}
+
+#[tokio::test]
+async fn moderation_tokens_kick_but_cannot_announce_or_issue_tokens() {
+    let server = TestServer::start().await;
+    let player_id = server.join().await;
+    let (_, token) = issue(&server, "mods", "moderation").await;
+
+    assert_eq!(get_with(&server, "/api/admin/state", &token).await, 200);
+    let kick = post_with(&server, "/api/admin/kick", &token, json!({ "player_id": player_id })).await;
+    assert_eq!(kick, 200);
+    assert!(!server.app_state.game_state.read().await.players.contains_key(&player_id));
+
+    let announce = post_with(&server, "/api/admin/announce", &token, json!({ "text": "hi" })).await;
+    assert_eq!(announce, 403);
+    let escalate = post_with(&server, "/api/admin/tokens", &token, json!({ "name": "me", "scope": "admin" })).await;
+    assert_eq!(escalate, 403);
+}
+
+#[tokio::test]
+async fn revoked_and_unknown_tokens_are_rejected() {
+    let server = TestServer::start().await;
+    let (id, token) = issue(&server, "dashboard", "stats").await;
+
+    let listed: Value = reqwest::Client::new()
+        .get(server.url("/api/admin/tokens"))
+        .bearer_auth(harness::ADMIN_TOKEN)
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(listed["tokens"][0]["id"], id);
+    assert_eq!(listed["tokens"][0]["name"], "dashboard");
+    assert!(listed["tokens"][0].get("token").is_none(), "listing leaked the secret");
+
+    let revoked = reqwest::Client::new()
+        .delete(server.url(&format!("/api/admin/tokens/{}", id)))
+        .bearer_auth(harness::ADMIN_TOKEN)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(revoked.status(), 204);
+
+    assert_eq!(get_with(&server, "/api/admin/state", &token).await, 401);
+    assert_eq!(get_with(&server, "/api/admin/state", "tok_made_up").await, 401);
+}
@@ -0,0 +1,285 @@
+mod harness;
+
+use std::time::Duration;
+use game_core::config::{BackplaneConfig, ClusterConfig};
+use game_core::GameConfig;
+use harness::TestServer;
+use serde_json::{json, Value};
+
+/// Instance `id` of a distributed game sharing the in-process bus `bus`
+fn distributed(bus: &str, id: &str) -> GameConfig {
+    GameConfig {
+        backplane: BackplaneConfig {
+            enabled: true,
+            url_env: "BACKPLANE_TEST_URL".to_string(),
+            url: Some(format!("memory://{}", bus)),
+            leader_lease_ms: 300,
+            ..BackplaneConfig::default()
+        },
+        cluster: ClusterConfig {
+            instance_id_env: "BACKPLANE_TEST_INSTANCE_ID".to_string(),
+            instance_id: Some(id.to_string()),
+            ..ClusterConfig::default()
+        },
+        ..harness::test_config()
+    }
+}
+
+/// A warm standby on the bus `bus`
+fn standby(bus: &str, id: &str) -> GameConfig {
+    let mut config = distributed(bus, id);
+    config.backplane.standby = true;
+    config.backplane.standby_env = "BACKPLANE_TEST_STANDBY".to_string();
+    config
+}
+
+async fn status(server: &TestServer) -> Value {
+    server.get("/api/backplane").await.json().await.unwrap()
+}
+
+async fn tick(server: &TestServer) -> u64 {
+    server.app_state.game_state.read().await.tick
+}
+
+/// Step both game loops until `done` holds for the pair
+async fn settle(
+    leader: &mut TestServer,
+    follower: &mut TestServer,
+    what: &str,
+    done: impl AsyncFn(&TestServer, &TestServer) -> bool,
+) {
+    for _ in 0..200 {
+        if done(leader, follower).await {
+            return;
+        }
+        follower.step(1).await;
+        leader.step(1).await;
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    panic!("timed out waiting for {}", what);
+}
+
+async fn processed_seq(server: &TestServer, player_id: uuid::Uuid) -> Option<u64> {
+    server.app_state.game_state.read().await.players.get(&player_id).map(|p| p.last_processed_seq)
+}
+
+async fn heard(server: &TestServer, text: &str) -> bool {
+    server.app_state.chat_history.read().await.iter().any(|m| m.text.contains(text))
+}
+
+#[tokio::test]
+async fn one_instance_simulates_and_every_instance_serves_its_world_and_chat() {
+    let bus = uuid::Uuid::new_v4().to_string();
+    let first = TestServer::with_config(distributed(&bus, "first")).await;
+    let second = TestServer::with_config(distributed(&bus, "second")).await;
+
+    // Exactly one of the two wins the lease
+    let mut statuses = Vec::new();
+    for _ in 0..100 {
+        statuses.clear();
+        for server in [&first, &second] {
+            statuses.push(server.get("/api/backplane").await.json::<Value>().await.unwrap());
+        }
+        if statuses.iter().any(|s| s["leader"] == true) {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+    assert_eq!(statuses.iter().filter(|s| s["leader"] == true).count(), 1, "{:?}", statuses);
+    assert!(statuses.iter().all(|s| s["enabled"] == true));
+    let (mut leader, mut follower) = if statuses[0]["leader"] == true { (first, second) } else { (second, first) };
+
+    // A player joining through the follower is added to the leader's world
+    let mut events = follower.subscribe("").await;
+    let (player_id, token) = follower.join_session().await;
+    settle(&mut leader, &mut follower, "the join to reach the leader", async |leader, _| {
+        leader.app_state.game_state.read().await.players.contains_key(&player_id)
+    })
+    .await;
+
+    // Commands sent to the follower are applied by the leader, and its state comes back
+    let response = follower
+        .post(
+            "/api/player/command",
+            json!({ "player_id": player_id, "command": { "type": "Jump" }, "seq": 7, "session_token": token }),
+        )
+        .await;
+    assert!(response.status().is_success());
+    settle(&mut leader, &mut follower, "the command to be applied and mirrored", async |leader, follower| {
+        processed_seq(leader, player_id).await == Some(7) && processed_seq(follower, player_id).await == Some(7)
+    })
+    .await;
+    // The follower's loop never steps the world, so its ticks are the leader's
+    let follower_tick = follower.app_state.game_state.read().await.tick;
+    assert!(follower_tick > 0 && follower_tick <= leader.app_state.game_state.read().await.tick);
+    events
+        .next_matching("the player in the follower's stream", |e| {
+            e.signals().and_then(|s| s.get("gameState")?.as_array().cloned()).is_some_and(|players| {
+                players.iter().any(|p| p["id"] == player_id.to_string())
+            })
+        })
+        .await;
+
+    // Chat sent to either instance reaches the other's history
+    assert!(leader.chat(player_id, "hello from the leader").await.status().is_success());
+    assert!(follower.chat(player_id, "hello from the follower").await.status().is_success());
+    settle(&mut leader, &mut follower, "chat on both instances", async |leader, follower| {
+        heard(follower, "hello from the leader").await && heard(leader, "hello from the follower").await
+    })
+    .await;
+}
+
+#[tokio::test]
+async fn followers_mirror_what_state_updates_leave_out() {
+    let bus = uuid::Uuid::new_v4().to_string();
+    let mut config = distributed(&bus, "leader");
+    config.backplane.checkpoint_interval_ms = 20;
+    config.physics_sandbox.enabled = true;
+    let leader = TestServer::with_config(config).await;
+    for _ in 0..100 {
+        if status(&leader).await["leader"] == true {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+    let follower = TestServer::with_config(standby(&bus, "follower")).await;
+
+    let player_id = leader.join().await;
+    {
+        let mut game_state = leader.app_state.game_state.write().await;
+        let changes = json!({ "gravity": -80.0 });
+        game_state.patch_physics(changes.as_object().unwrap()).unwrap();
+        game_state.players.get_mut(&player_id).unwrap().dash_secs = 0.15;
+        game_state.rng.next_u64();
+    }
+
+    // Neither loop steps, so only the leader's checkpoints can bring these across
+    for _ in 0..100 {
+        let expected = leader.app_state.game_state.read().await.snapshot();
+        let mirrored = follower.app_state.game_state.read().await.snapshot();
+        if mirrored.rng_state == expected.rng_state && mirrored.player_internals == expected.player_internals {
+            assert_eq!(mirrored.config_version, expected.config_version);
+            let game_state = follower.app_state.game_state.read().await;
+            assert_eq!(game_state.world.config().physics.gravity, -80.0);
+            assert_eq!(game_state.players[&player_id].dash_secs, 0.15);
+            return;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    panic!("the follower never mirrored the leader's checkpoint");
+}
+
+#[tokio::test]
+async fn followers_load_a_changed_world_before_their_clients_hear_of_it() {
+    let bus = uuid::Uuid::new_v4().to_string();
+    let mut config = distributed(&bus, "leader");
+    config.backplane.checkpoint_interval_ms = 60_000;
+    config.physics_sandbox.enabled = true;
+    let mut leader = TestServer::with_config(config).await;
+    for _ in 0..100 {
+        if status(&leader).await["leader"] == true {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+    let mut follower_config = standby(&bus, "follower");
+    follower_config.backplane.checkpoint_interval_ms = 60_000;
+    let follower = TestServer::with_config(follower_config).await;
+    let mut events = follower.subscribe("").await;
+
+    let version = {
+        let mut game_state = leader.app_state.game_state.write().await;
+        let changes = json!({ "gravity": -80.0 });
+        game_state.patch_physics(changes.as_object().unwrap()).unwrap();
+        game_state.config_version()
+    };
+    leader.step(1).await;
+    events
+        .next_matching("the new config version", |e| {
+            e.signals().is_some_and(|s| s.get("configVersion") == Some(&json!(version)))
+        })
+        .await;
+    let game_state = follower.app_state.game_state.read().await;
+    assert_eq!(game_state.world.config().physics.gravity, -80.0);
+    assert_eq!(game_state.config_version(), version);
+}
+
+#[tokio::test]
+async fn standalone_servers_report_the_backplane_disabled() {
+    let server = TestServer::start().await;
+    let expected = json!({ "enabled": false, "instance_id": null, "leader": false, "standby": false });
+    assert_eq!(status(&server).await, expected);
+
+    // A backplane URL alone doesn't turn distributed mode on
+    let bus = uuid::Uuid::new_v4().to_string();
+    let mut config = distributed(&bus, "unconfigured");
+    config.backplane.enabled = false;
+    let server = TestServer::with_config(config).await;
+    assert_eq!(status(&server).await["enabled"], false);
+}
+
+#[tokio::test]
+async fn a_standby_never_takes_over_by_itself() {
+    let bus = uuid::Uuid::new_v4().to_string();
+    let server = TestServer::with_config(standby(&bus, "standby")).await;
+    // Well past the lease, with nobody else holding it
+    tokio::time::sleep(Duration::from_millis(700)).await;
+    let status = status(&server).await;
+    assert_eq!((status["leader"].clone(), status["standby"].clone()), (json!(false), json!(true)));
+}
+
+#[tokio::test]
+async fn a_promoted_standby_carries_on_from_the_tick_it_mirrored() {
+    let bus = uuid::Uuid::new_v4().to_string();
+    let mut primary = TestServer::with_config(distributed(&bus, "primary")).await;
+    let mut standby = TestServer::with_config(standby(&bus, "standby")).await;
+    for _ in 0..100 {
+        if status(&primary).await["leader"] == true {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+    assert_eq!(status(&primary).await["leader"], true);
+
+    let player_id = primary.join().await;
+    settle(&mut primary, &mut standby, "the standby to mirror the player", async |primary, standby| {
+        standby.app_state.game_state.read().await.players.contains_key(&player_id)
+            && tick(standby).await == tick(primary).await
+    })
+    .await;
+    assert_eq!(status(&standby).await["leader"], false);
+
+    // Promotion takes the lease from a live leader, which stops as soon as it hears
+    let mirrored = tick(&standby).await;
+    let response = standby.admin_post("/api/admin/promote", json!({})).await;
+    assert_eq!(response.status(), 200);
+    let body: Value = response.json().await.unwrap();
+    assert_eq!(body["status"]["leader"], true);
+    assert_eq!(body["status"]["standby"], false);
+    assert_eq!(body["resumed_at_tick"], mirrored);
+    for _ in 0..100 {
+        if status(&primary).await["leader"] == false {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    assert_eq!(status(&primary).await["leader"], false);
+
+    // The standby simulates on from its mirror, and the old primary now follows it
+    standby.step(5).await;
+    assert_eq!(tick(&standby).await, mirrored + 5);
+    assert!(standby.app_state.game_state.read().await.players.contains_key(&player_id));
+    settle(&mut standby, &mut primary, "the old primary to follow", async |standby, primary| {
+        tick(primary).await == tick(standby).await
+    })
+    .await;
+
+    let response = standby.admin_post("/api/admin/promote", json!({})).await;
+    assert_eq!(response.status(), 409);
+}
+
+#[tokio::test]
+async fn standalone_servers_cant_be_promoted() {
+    let server = TestServer::start().await;
+    assert_eq!(server.admin_post("/api/admin/promote", json!({})).await.status(), 409);
+}
@@ -0,0 +1,97 @@
+mod harness;
+
+use api::GameUpdate;
+use game_core::config::BackpressureConfig;
+use game_core::{ChatMessage, GameConfig};
+use harness::{test_config, TestServer, ADMIN_TOKEN};
+use serde_json::Value;
+
+/// Broadcast more updates than a stream can buffer, without giving it a chance to read any
+fn flood(server: &TestServer) {
+    for _ in 0..150 {
+        let _ = server.app_state.game_tx.send(GameUpdate::SpectatorCount(0));
+    }
+}
+
+async fn lag_stats(server: &TestServer) -> Value {
+    reqwest::Client::new()
+        .get(server.url("/api/admin/streams"))
+        .bearer_auth(ADMIN_TOKEN)
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn streams_that_fall_behind_are_resynced_and_closed_when_it_keeps_happening() {
+    let mut server = TestServer::with_config(GameConfig {
+        backpressure: BackpressureConfig {
+            max_lags: 2,
+            lag_window_secs: 60,
+        },
+        ..test_config()
+    })
+    .await;
+    let (player_id, token) = server.join_session().await;
+    let mut events = server.subscribe(&format!("session={}", token)).await;
+
+    // The first miss is answered with the whole world
+    flood(&server);
+    let resync = events
+        .next_matching("a full state resync", |e| {
+            e.signals().is_some_and(|s| s.get("geometry").is_some() && s.get("gameState").is_some())
+        })
+        .await
+        .signals()
+        .unwrap();
+    assert!(resync["gameState"].as_array().unwrap().iter().any(|p| p["id"] == player_id.to_string()));
+    assert!(resync.get("matchState").is_some() && resync.get("tick").is_some());
+    let stats = lag_stats(&server).await;
+    assert_eq!(stats["lag_events"], 1);
+    assert_eq!(stats["resyncs"], 1);
+    assert_eq!(stats["disconnects"], 0);
+    assert!(stats["updates_skipped"].as_u64().unwrap() > 0, "{}", stats);
+
+    // Missing again within the window closes the stream
+    flood(&server);
+    server.wait_for_disconnect(player_id).await;
+    let stats = lag_stats(&server).await;
+    assert_eq!(stats["lag_events"], 2);
+    assert_eq!(stats["disconnects"], 1);
+}
+
+fn chat_message(text: &str) -> ChatMessage {
+    ChatMessage {
+        player_id: uuid::Uuid::new_v4(),
+        player_name: "Chatty".to_string(),
+        player_color: "#FFFFFF".to_string(),
+        text: text.to_string(),
+        timestamp: 0,
+        team: None,
+    }
+}
+
+#[tokio::test]
+async fn streams_that_miss_chat_get_the_recent_history_again() {
+    let server = TestServer::start().await;
+    let (_, token) = server.join_session().await;
+    let mut events = server.subscribe(&format!("session={}", token)).await;
+
+    server.app_state.chat_history.write().await.push_back(chat_message("kept in history"));
+    for _ in 0..150 {
+        let _ = server.app_state.chat_tx.send(chat_message("flood"));
+    }
+    let resent = events
+        .next_matching("the chat history replacing what was missed", |e| {
+            e.data.contains("mode replace") && e.elements().is_some_and(|html| html.contains("kept in history"))
+        })
+        .await;
+    assert!(resent.data.contains("selector #chat-messages"));
+    let stats = lag_stats(&server).await;
+    assert_eq!(stats["lag_events"], 1);
+    assert_eq!(stats["resyncs"], 1);
+    assert!(stats["updates_skipped"].as_u64().unwrap() > 0, "{}", stats);
+}
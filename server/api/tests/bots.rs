@@ -0,0 +1,103 @@
+mod harness;
+
+use std::time::Duration;
+use harness::{test_config, TestServer, ADMIN_TOKEN};
+use game_core::config::{BotsConfig, RoomConfig};
+use game_core::{BehaviorKind, GameConfig};
+use serde_json::{json, Value};
+
+const TICKS_PER_SEC: u32 = 60;
+
+#[tokio::test]
+async fn configured_bots_join_at_startup_move_and_never_time_out() {
+    let mut server = TestServer::with_config(GameConfig {
+        bots: BotsConfig {
+            count: 3,
+            behaviors: vec![BehaviorKind::Patrol, BehaviorKind::Jumper],
+            ..BotsConfig::default()
+        },
+        ..test_config()
+    })
+    .await;
+
+    let listed: Value = reqwest::Client::new()
+        .get(server.url("/api/admin/bots"))
+        .bearer_auth(ADMIN_TOKEN)
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(listed["count"], 3);
+    let behaviors: Vec<&str> = listed["bots"].as_array().unwrap().iter().map(|b| b["behavior"].as_str().unwrap()).collect();
+    assert_eq!(behaviors, ["patrol", "jumper", "patrol"]);
+
+    let start: Vec<(uuid::Uuid, f32)> = {
+        let game_state = server.app_state.game_state.read().await;
+        assert!(game_state.players.values().all(|p| p.bot));
+        game_state.players.values().map(|p| (p.id, p.x)).collect()
+    };
+    server.step(TICKS_PER_SEC).await;
+    {
+        let game_state = server.app_state.game_state.read().await;
+        for (id, x) in &start {
+            let bot = &game_state.players[id];
+            assert!((bot.x - x).abs() > 0.5, "bot {} stood still at {}", bot.name, bot.x);
+        }
+    }
+
+    server.advance_clock(Duration::from_secs(server.app_state.game_config.idle_timeout * 2));
+    assert_eq!(server.expire_idle().await, 0);
+    assert_eq!(server.app_state.game_state.read().await.players.len(), 3);
+}
+
+#[tokio::test]
+async fn admins_add_and_remove_bots_and_chasers_run_at_players() {
+    let mut server = TestServer::start().await;
+    let human = server.join().await;
+
+    assert_eq!(server.post("/api/admin/bots", json!({ "count": 1 })).await.status(), 401);
+    let reply = server.admin_post("/api/admin/bots", json!({ "count": 1, "behavior": "chase" })).await;
+    assert_eq!(reply.status(), 200);
+    let body: Value = reply.json().await.unwrap();
+    let chaser: uuid::Uuid = serde_json::from_value(body["bots"][0]["id"].clone()).unwrap();
+    assert_eq!(body["bots"][0]["behavior"], "chase");
+
+    let gap = {
+        let mut game_state = server.app_state.game_state.write().await;
+        game_state.players.get_mut(&human).unwrap().x = 12.0;
+        game_state.players.get_mut(&chaser).unwrap().x = 0.0;
+        12.0
+    };
+    server.step(TICKS_PER_SEC).await;
+    let closer = {
+        let game_state = server.app_state.game_state.read().await;
+        game_state.players[&human].x - game_state.players[&chaser].x
+    };
+    assert!(closer < gap - 2.0, "chaser only closed the gap to {}", closer);
+
+    let mut events = server.subscribe("").await;
+    let reply = server.admin_post("/api/admin/bots", json!({ "count": 0 })).await;
+    let body: Value = reply.json().await.unwrap();
+    assert_eq!(body["count"], 0);
+    let left = events.next_signal("playerLeft").await;
+    assert_eq!(left["player_id"], chaser.to_string());
+    let game_state = server.app_state.game_state.read().await;
+    assert_eq!(game_state.players.keys().collect::<Vec<_>>(), [&human]);
+}
+
+#[tokio::test]
+async fn bots_only_take_open_slots() {
+    let server = TestServer::with_config(GameConfig {
+        room: RoomConfig { max_players: 2, max_waiting: 0 },
+        ..test_config()
+    })
+    .await;
+    server.join().await;
+
+    let reply = server.admin_post("/api/admin/bots", json!({ "count": 5 })).await;
+    let body: Value = reply.json().await.unwrap();
+    assert_eq!(body["count"], 1);
+    assert_eq!(server.app_state.game_state.read().await.players.len(), 2);
+}
@@ -0,0 +1,50 @@
+mod harness;
+
+use game_core::ScoreSource;
+use harness::TestServer;
+use serde_json::Value;
+
+#[tokio::test]
+async fn todays_challenges_report_progress_over_http_and_on_the_players_stream() {
+    let mut server = TestServer::start().await;
+    let (player_id, token) = server.join_session().await;
+    let mut events = server.subscribe(&format!("session={}", token)).await;
+    server.step(5).await;
+
+    let path = format!("/api/challenges/today?player_id={}", player_id);
+    let today: Value = server.get(&path).await.json().await.unwrap();
+    let challenges = today["challenges"].as_array().unwrap();
+    assert_eq!(challenges.len(), game_core::challenges::DAILY_CHALLENGE_COUNT);
+    let untouched = events.next_signal("challengeProgress").await;
+    assert_eq!(untouched, today["progress"]);
+    assert!(untouched.as_array().unwrap().iter().all(|p| p["progress"] == 0 && p["completed"] == false));
+
+    // Everything but placing blocks moves on from one jump and one coin
+    server.command(player_id, "Jump").await;
+    server.app_state.game_state.write().await.award_points(&player_id, ScoreSource::Coin);
+    server.step(1).await;
+    let progress = events.next_signal("challengeProgress").await;
+    assert_eq!(progress, server.get(&path).await.json::<Value>().await.unwrap()["progress"]);
+    for (challenge, progress) in challenges.iter().zip(progress.as_array().unwrap()) {
+        assert_eq!(progress["challenge_id"], challenge["id"]);
+        assert_eq!(progress["target"], challenge["target"]);
+        let moved = progress["progress"].as_u64().unwrap() > 0;
+        assert_eq!(moved, challenge["goal"] != "blocks_placed", "{}", progress);
+    }
+
+    // Unchanged progress isn't sent again, and other players see none of it
+    server.step(1).await;
+    events.next_signal("tick").await;
+    let sent = events
+        .recorded()
+        .iter()
+        .filter(|e| e.signals().is_some_and(|s| s.get("challengeProgress").is_some()))
+        .count();
+    assert_eq!(sent, 2);
+    let mut anonymous = server.subscribe("").await;
+    server.step(1).await;
+    anonymous.next_signal("tick").await;
+    assert!(!anonymous.recorded().iter().any(|e| e.signals().is_some_and(|s| s.get("challengeProgress").is_some())));
+    let nobody: Value = server.get("/api/challenges/today").await.json().await.unwrap();
+    assert!(nobody["progress"].is_null());
+}
@@ -0,0 +1,50 @@
+mod harness;
+
+use api::chat_commands::{parse, ChatCommand};
+use harness::TestServer;
+
+#[test]
+fn slash_commands_parse_with_their_aliases_and_arguments() {
+    assert_eq!(parse("  /NICK   Ada Lovelace "), Some(ChatCommand::Nick("Ada Lovelace".to_string())));
+    assert_eq!(parse("/name"), Some(ChatCommand::Nick(String::new())));
+    assert_eq!(parse("/who"), Some(ChatCommand::Who));
+    assert_eq!(parse("/?"), Some(ChatCommand::Help));
+    assert_eq!(parse("/dance now"), Some(ChatCommand::Unknown("dance".to_string())));
+    assert_eq!(parse("hello /who"), None);
+}
+
+#[tokio::test]
+async fn commands_answer_only_the_sender() {
+    let server = TestServer::start().await;
+    let mut observer = server.subscribe("").await;
+    let player_id = server.join().await;
+    let other = server.join().await;
+    server.app_state.game_state.write().await.players.get_mut(&other).unwrap().name = "Zed<3".to_string();
+
+    let nick = server.chat(player_id, "/nick Ada").await;
+    assert_eq!(nick.status(), 200);
+    assert!(nick.text().await.unwrap().contains("You are now known as <b>Ada</b>"));
+    assert_eq!(server.app_state.game_state.read().await.players[&player_id].name, "Ada");
+    let refused = server.chat(player_id, "/nick zed<3").await.text().await.unwrap();
+    assert!(refused.contains("Could not change name"), "{}", refused);
+
+    let who = server.chat(other, "/who").await.text().await.unwrap();
+    assert!(who.contains("2 player(s) online"), "{}", who);
+    let ada = who.find(">Ada<").unwrap();
+    assert!(who.find(">Zed&lt;3<").unwrap() > ada, "names sorted and escaped: {}", who);
+
+    let help = server.chat(player_id, "/help").await.text().await.unwrap();
+    for usage in ["/nick &lt;name&gt;", "/who", "/help"] {
+        assert!(help.contains(usage), "{} missing from {}", usage, help);
+    }
+
+    // Nothing above reached the room, unlike the message after it
+    server.chat(player_id, "gg").await;
+    observer.next_element_containing("gg").await;
+    let leaked = observer
+        .recorded()
+        .iter()
+        .filter_map(|e| e.elements())
+        .any(|html| html.contains("known as") || html.contains("online") || html.contains("/help"));
+    assert!(!leaked);
+}
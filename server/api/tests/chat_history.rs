@@ -0,0 +1,31 @@
+mod harness;
+
+use harness::{test_config, TestServer};
+
+#[tokio::test]
+async fn late_subscribers_get_the_last_messages_in_order() {
+    let server = TestServer::with_config(game_core::GameConfig {
+        chat_history_size: 3,
+        ..test_config()
+    })
+    .await;
+    let player_id = server.join().await;
+    for n in 1..=5 {
+        assert_eq!(server.chat(player_id, &format!("message {}", n)).await.status(), 200);
+    }
+    assert_eq!(server.app_state.chat_history.read().await.len(), 3);
+
+    let mut late = server.subscribe("").await;
+    late.next_element_containing("message 5").await;
+    let replayed: Vec<String> = late
+        .recorded()
+        .iter()
+        .filter_map(|e| e.elements())
+        .filter(|html| html.contains("message "))
+        .map(|html| html.to_string())
+        .collect();
+    assert_eq!(replayed.len(), 3);
+    for (html, n) in replayed.iter().zip(3..) {
+        assert!(html.contains(&format!("message {}", n)), "expected message {} in {}", n, html);
+    }
+}
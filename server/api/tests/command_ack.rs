@@ -0,0 +1,105 @@
+mod harness;
+
+use std::time::Duration;
+use serde_json::{json, Value};
+use harness::{test_config, TestServer};
+
+async fn ack(response: reqwest::Response) -> Value {
+    assert_eq!(response.status(), 200);
+    response.json().await.unwrap()
+}
+
+async fn send(server: &TestServer, player_id: uuid::Uuid, command: Value, seq: u64) -> Value {
+    ack(server
+        .post(
+            "/api/player/command",
+            json!({ "player_id": player_id, "command": command, "seq": seq, "session_token": server.token(player_id) }),
+        )
+        .await)
+    .await
+}
+
+#[tokio::test]
+async fn jumping_in_the_air_is_acknowledged_as_ignored() {
+    let mut config = test_config();
+    config.physics.jump_buffer_secs = 0.0;
+    let mut server = TestServer::with_config(config).await;
+    let player_id = server.join().await;
+    server.step(5).await;
+
+    let jump = ack(server.command(player_id, "Jump").await).await;
+    assert_eq!(jump["status"], "accepted");
+    assert!(jump.get("reason").is_none());
+    server.step(1).await;
+
+    let again = ack(server.command(player_id, "Jump").await).await;
+    assert_eq!(again["status"], "ignored");
+    assert_eq!(again["reason"], json!({ "code": "not_grounded" }));
+    let steer = ack(server.command(player_id, "MoveRight").await).await;
+    assert_eq!(steer["reason"]["code"], "flying");
+}
+
+#[tokio::test]
+async fn jumping_just_before_landing_is_acknowledged_as_buffered() {
+    let mut server = TestServer::start().await;
+    let player_id = server.join().await;
+    server.step(5).await;
+    ack(server.command(player_id, "Jump").await).await;
+    server.step(1).await;
+    assert_eq!(ack(server.command(player_id, "Jump").await).await["status"], "buffered");
+}
+
+#[tokio::test]
+async fn acknowledgments_carry_the_resulting_cooldowns() {
+    let mut server = TestServer::start().await;
+    let player_id = server.join().await;
+    let cooldown_ms = server.app_state.game_config.projectiles.cooldown_ms;
+
+    let shot = send(&server, player_id, json!({ "type": "Shoot", "dir_x": 1.0, "dir_y": 0.0 }), 0).await;
+    assert_eq!(shot["status"], "accepted");
+    assert_eq!(shot["cooldowns"]["shoot_ms"], cooldown_ms);
+    server.step(1).await;
+    server.advance_clock(Duration::from_millis(100));
+
+    let too_soon = send(&server, player_id, json!({ "type": "Shoot", "dir_x": 1.0, "dir_y": 0.0 }), 0).await;
+    assert_eq!(too_soon["reason"], json!({ "code": "shoot", "detail": "cooldown" }));
+    assert_eq!(too_soon["cooldowns"]["shoot_ms"], cooldown_ms - 100);
+
+    let aimless = send(&server, player_id, json!({ "type": "Shoot", "dir_x": 0.0, "dir_y": 0.0 }), 0).await;
+    assert_eq!(aimless["reason"]["detail"], "invalid_direction");
+}
+
+#[tokio::test]
+async fn replayed_sequence_numbers_are_stale() {
+    let mut server = TestServer::start().await;
+    let player_id = server.join().await;
+    assert_eq!(send(&server, player_id, json!({ "type": "Stop" }), 3).await["status"], "accepted");
+    server.step(1).await;
+    let replayed = send(&server, player_id, json!({ "type": "Stop" }), 3).await;
+    assert_eq!(replayed["reason"]["code"], "stale");
+}
+
+#[tokio::test]
+async fn state_updates_echo_each_players_last_processed_sequence() {
+    let mut server = TestServer::start().await;
+    let mut events = server.subscribe("").await;
+    let player_id = server.join().await;
+    let other = server.join().await;
+    send(&server, player_id, json!({ "type": "MoveRight" }), 4).await;
+    send(&server, player_id, json!({ "type": "Stop" }), 5).await;
+    server.step(1).await;
+
+    let seq_of = |state: &Value, id: uuid::Uuid| {
+        state.as_array().unwrap().iter().find(|p| p["id"] == id.to_string()).unwrap()["last_processed_seq"].clone()
+    };
+    let state = events
+        .next_matching("player's commands reflected", |e| {
+            e.signals()
+                .and_then(|s| s.get("gameState").cloned())
+                .is_some_and(|state| state.as_array().unwrap().len() == 2)
+        })
+        .await;
+    let state = &state.signals().unwrap()["gameState"];
+    assert_eq!(seq_of(state, player_id), 5);
+    assert_eq!(seq_of(state, other), 0);
+}
@@ -0,0 +1,134 @@
+mod harness;
+
+use std::time::Duration;
+use api::command_lanes::{self, CommandLane, QueuedCommand};
+use game_core::config::{CommandLanesConfig, LaneConfig, OverflowPolicy};
+use game_core::{GameConfig, PlayerCommand};
+use harness::TestServer;
+use serde_json::{json, Value};
+
+fn lanes(capacity: usize, overflow: OverflowPolicy) -> CommandLanesConfig {
+    let lane = LaneConfig { capacity, overflow };
+    CommandLanesConfig {
+        admin: lane.clone(),
+        gameplay: lane.clone(),
+        cosmetic: lane,
+    }
+}
+
+fn command(player_id: uuid::Uuid, command: PlayerCommand) -> QueuedCommand {
+    QueuedCommand::Player { player_id, command, seq: 0, trace: None }
+}
+
+async fn lane_stats(server: &TestServer) -> Value {
+    let response = reqwest::Client::new()
+        .get(server.url("/api/admin/commands"))
+        .bearer_auth(harness::ADMIN_TOKEN)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 200);
+    let body: Value = response.json().await.unwrap();
+    body["lanes"].as_array().unwrap().iter().find(|l| l["lane"] == "gameplay").unwrap().clone()
+}
+
+#[tokio::test]
+async fn admin_commands_drain_before_gameplay_and_cosmetics() {
+    let (sender, mut receiver) = command_lanes::channel(&CommandLanesConfig::default());
+    let player_id = uuid::Uuid::new_v4();
+    let equipped = game_core::EquippedCosmetics::default();
+    assert!(sender.push(CommandLane::Cosmetic, QueuedCommand::Cosmetics { player_id, equipped }).await);
+    assert!(sender.push(CommandLane::Gameplay, command(player_id, PlayerCommand::Jump)).await);
+    assert!(sender.push(CommandLane::Admin, command(player_id, PlayerCommand::Stop)).await);
+
+    let drained = receiver.drain();
+    assert!(matches!(drained[0], QueuedCommand::Player { command: PlayerCommand::Stop, .. }));
+    assert!(matches!(drained[1], QueuedCommand::Player { command: PlayerCommand::Jump, .. }));
+    assert!(matches!(drained[2], QueuedCommand::Cosmetics { .. }));
+    assert!(receiver.drain().is_empty());
+}
+
+#[tokio::test]
+async fn blocking_lanes_wait_for_the_game_loop_instead_of_dropping() {
+    let (sender, mut receiver) = command_lanes::channel(&lanes(1, OverflowPolicy::Block));
+    let player_id = uuid::Uuid::new_v4();
+    assert!(sender.push(CommandLane::Admin, command(player_id, PlayerCommand::Stop)).await);
+
+    let blocked = {
+        let sender = sender.clone();
+        tokio::spawn(async move { sender.push(CommandLane::Admin, command(player_id, PlayerCommand::Jump)).await })
+    };
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    assert!(!blocked.is_finished(), "full blocking lane accepted a command");
+
+    assert_eq!(receiver.drain().len(), 1);
+    assert!(blocked.await.unwrap());
+    assert_eq!(receiver.drain().len(), 1);
+    assert_eq!(sender.stats()[0].dropped, 0);
+
+    // Senders give up once the game loop is gone
+    assert!(sender.push(CommandLane::Admin, command(player_id, PlayerCommand::Stop)).await);
+    let stuck = {
+        let sender = sender.clone();
+        tokio::spawn(async move { sender.push(CommandLane::Admin, command(player_id, PlayerCommand::Stop)).await })
+    };
+    drop(receiver);
+    assert!(!stuck.await.unwrap());
+}
+
+#[tokio::test]
+async fn full_gameplay_lane_sheds_the_oldest_commands_and_counts_them() {
+    let server = TestServer::with_config(GameConfig {
+        command_lanes: lanes(2, OverflowPolicy::DropOldest),
+        ..harness::test_config()
+    })
+    .await;
+    let player_id = server.join().await;
+
+    for _ in 0..5 {
+        assert_eq!(server.command(player_id, "MoveRight").await.status(), 200);
+    }
+    let stats = lane_stats(&server).await;
+    assert_eq!(stats["queued"], 2);
+    assert_eq!(stats["capacity"], 2);
+    assert_eq!(stats["overflow"], "drop_oldest");
+    assert_eq!(stats["dropped"], 3);
+}
+
+#[tokio::test]
+async fn full_drop_newest_lane_refuses_the_command() {
+    let mut server = TestServer::with_config(GameConfig {
+        command_lanes: lanes(1, OverflowPolicy::DropNewest),
+        ..harness::test_config()
+    })
+    .await;
+    let player_id = server.join().await;
+
+    assert_eq!(server.command(player_id, "MoveRight").await.status(), 200);
+    assert_eq!(server.command(player_id, "MoveLeft").await.status(), 503);
+    assert_eq!(lane_stats(&server).await["dropped"], 1);
+
+    server.step(1).await;
+    assert_eq!(lane_stats(&server).await["queued"], 0);
+    assert_eq!(server.command(player_id, "MoveLeft").await.status(), 200);
+}
+
+#[tokio::test]
+async fn moderators_can_queue_commands_for_players() {
+    let mut server = TestServer::start().await;
+    let player_id = server.join().await;
+    {
+        let mut game_state = server.app_state.game_state.write().await;
+        game_state.players.get_mut(&player_id).unwrap().velocity_x = 10.0;
+    }
+
+    let response = server
+        .admin_post("/api/admin/command", json!({ "player_id": player_id, "command": { "type": "Stop" } }))
+        .await;
+    assert_eq!(response.status(), 202);
+    server.step(1).await;
+    assert_eq!(server.app_state.game_state.read().await.players[&player_id].velocity_x, 0.0);
+
+    let unknown = json!({ "player_id": uuid::Uuid::new_v4(), "command": { "type": "Stop" } });
+    assert_eq!(server.admin_post("/api/admin/command", unknown).await.status(), 404);
+}
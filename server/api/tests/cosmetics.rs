@@ -0,0 +1,136 @@
+mod harness;
+
+use game_core::config::{CosmeticItem, CosmeticsConfig};
+use game_core::cosmetics::UnlockRule;
+use game_core::{CosmeticSlot, CosmeticStore, GameConfig};
+use harness::TestServer;
+use serde_json::{json, Value};
+
+fn item(id: &str, slot: CosmeticSlot, unlock: UnlockRule) -> CosmeticItem {
+    CosmeticItem {
+        id: id.to_string(),
+        name: id.to_string(),
+        slot,
+        value: "#FFFFFF".to_string(),
+        unlock,
+    }
+}
+
+fn catalog() -> Vec<CosmeticItem> {
+    vec![
+        item("cap", CosmeticSlot::Hat, UnlockRule::Free),
+        item("crown", CosmeticSlot::Hat, UnlockRule::Challenge { challenge_id: "jumps_50".to_string() }),
+        item("neon", CosmeticSlot::Trail, UnlockRule::Purchase { price: 150 }),
+    ]
+}
+
+fn cosmetics_config() -> GameConfig {
+    GameConfig {
+        cosmetics: CosmeticsConfig { path: None, items: catalog() },
+        ..harness::test_config()
+    }
+}
+
+#[tokio::test]
+async fn equipped_cosmetics_show_on_the_player_for_everyone() {
+    let mut server = TestServer::with_config(cosmetics_config()).await;
+    let (player_id, token) = server.join_session().await;
+    let mut events = server.subscribe("").await;
+
+    let response = server
+        .post("/api/player/cosmetics/equip", json!({ "session_token": token, "slot": "hat", "item_id": "cap" }))
+        .await;
+    assert_eq!(response.status(), 200);
+
+    server.step(1).await;
+    let players = events.next_signal("gameState").await;
+    let player = players.as_array().unwrap().iter().find(|p| p["id"] == player_id.to_string()).unwrap();
+    assert_eq!(player["cosmetics"], json!({ "hat": "cap" }));
+
+    let profile: Value = server
+        .get(&format!("/api/player/cosmetics?session_token={}", token))
+        .await
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(profile["coins"], 0);
+    assert_eq!(profile["owned"], json!(["cap"]));
+    assert_eq!(profile["equipped"], json!({ "hat": "cap" }));
+}
+
+#[tokio::test]
+async fn locked_unknown_and_misplaced_cosmetics_are_refused() {
+    let server = TestServer::with_config(cosmetics_config()).await;
+    let (_, token) = server.join_session().await;
+
+    let equip = |slot: &str, item_id: &str| {
+        server.post("/api/player/cosmetics/equip", json!({ "session_token": token, "slot": slot, "item_id": item_id }))
+    };
+    assert_eq!(equip("hat", "crown").await.status(), 403);
+    assert_eq!(equip("hat", "nope").await.status(), 404);
+    assert_eq!(equip("trail", "cap").await.status(), 400);
+
+    let response = server
+        .post("/api/player/cosmetics/purchase", json!({ "session_token": token, "item_id": "neon" }))
+        .await;
+    assert_eq!(response.status(), 409, "bought without coins");
+    let response = server
+        .post("/api/player/cosmetics/purchase", json!({ "session_token": token, "item_id": "crown" }))
+        .await;
+    assert_eq!(response.status(), 400, "bought a challenge reward");
+
+    let forged = format!("{}.00", uuid::Uuid::new_v4());
+    assert_eq!(server.get(&format!("/api/player/cosmetics?session_token={}", forged)).await.status(), 401);
+}
+
+#[tokio::test]
+async fn challenge_rewards_unlock_items_and_pay_coins_for_purchases() {
+    let server = TestServer::with_config(cosmetics_config()).await;
+    let (player_id, token) = server.join_session().await;
+
+    let unlocked = server
+        .app_state
+        .cosmetics
+        .write()
+        .await
+        .reward_challenge(player_id, "jumps_50", 200, &catalog());
+    assert_eq!(unlocked, vec!["crown".to_string()]);
+
+    let response = server
+        .post("/api/player/cosmetics/purchase", json!({ "session_token": token, "item_id": "neon" }))
+        .await;
+    assert_eq!(response.status(), 200);
+    let profile: Value = response.json().await.unwrap();
+    assert_eq!(profile["coins"], 50);
+    assert_eq!(profile["owned"], json!(["cap", "crown", "neon"]));
+
+    let response = server
+        .post("/api/player/cosmetics/equip", json!({ "session_token": token, "slot": "trail", "item_id": "neon" }))
+        .await;
+    assert_eq!(response.status(), 200);
+    let response = server
+        .post("/api/player/cosmetics/equip", json!({ "session_token": token, "slot": "trail" }))
+        .await;
+    let body: Value = response.json().await.unwrap();
+    assert_eq!(body["equipped"], json!({}));
+}
+
+#[tokio::test]
+async fn cosmetics_persist_across_restarts() {
+    let path = std::env::temp_dir().join(format!("cosmetics-{}.json", uuid::Uuid::new_v4()));
+    let player_id = uuid::Uuid::new_v4();
+
+    let mut store = CosmeticStore::load_async(&path).await.unwrap();
+    store.reward_challenge(player_id, "jumps_50", 300, &catalog());
+    store.purchase(player_id, "neon", &catalog()).unwrap();
+    store.equip(player_id, CosmeticSlot::Hat, Some("crown"), &catalog()).unwrap();
+    store.save().await.unwrap();
+
+    let reloaded = CosmeticStore::load_async(&path).await.unwrap();
+    let profile = reloaded.profile(&player_id);
+    assert_eq!(profile.coins, 150);
+    assert!(profile.unlocked.contains("crown") && profile.unlocked.contains("neon"));
+    assert_eq!(profile.equipped.hat.as_deref(), Some("crown"));
+
+    let _ = tokio::fs::remove_file(&path).await;
+}
@@ -0,0 +1,110 @@
+mod harness;
+
+use game_core::config::PlatformConfig;
+use game_core::GameConfig;
+use harness::TestServer;
+
+const TICKS_PER_SEC: u32 = 60;
+
+/// A slab from x = 2 to 6 whose underside sits 1 above the ground: too low to walk under
+/// standing (1.5 tall), high enough to crouch under (0.75)
+fn tunnel_config() -> GameConfig {
+    let defaults = harness::test_config();
+    let ground_y = defaults.physics.ground_y;
+    GameConfig {
+        platforms: vec![PlatformConfig {
+            id: "tunnel".to_string(),
+            x_start: 2.0,
+            x_end: 6.0,
+            y_top: ground_y + 1.5,
+            height: 0.5,
+            color: "#B34733".to_string(),
+            surface: Default::default(),
+            restitution: None,
+        }],
+        ..defaults
+    }
+}
+
+async fn place(server: &TestServer, player_id: uuid::Uuid, x: f32, velocity_x: f32) {
+    let mut game_state = server.app_state.game_state.write().await;
+    let player = game_state.players.get_mut(&player_id).unwrap();
+    player.x = x;
+    player.velocity_x = velocity_x;
+}
+
+#[tokio::test]
+async fn crouching_slides_under_low_platforms() {
+    let mut server = TestServer::with_config(tunnel_config()).await;
+    let mut events = server.subscribe("").await;
+    let standing = server.join().await;
+    let crouching = server.join().await;
+    place(&server, standing, 0.0, 12.0).await;
+    place(&server, crouching, 0.0, 12.0).await;
+
+    server.command(crouching, "Crouch").await;
+    server.step(1).await;
+    let players = events.next_signal("gameState").await;
+    let signal = players.as_array().unwrap().iter().find(|p| p["id"] == crouching.to_string()).unwrap();
+    assert_eq!(signal["crouched"], true);
+
+    server.step(TICKS_PER_SEC * 2).await;
+    let game_state = server.app_state.game_state.read().await;
+    let blocked = &game_state.players[&standing];
+    assert!(blocked.x <= 2.0 - 0.75 + 0.01, "standing player walked into the slab: {}", blocked.x);
+    let slid = &game_state.players[&crouching];
+    assert!(slid.x > 6.0, "crouching player didn't get through: {}", slid.x);
+    assert!(slid.crouched);
+}
+
+#[tokio::test]
+async fn standing_up_waits_for_headroom() {
+    let mut server = TestServer::with_config(tunnel_config()).await;
+    let player_id = server.join().await;
+    let ground_y = server.app_state.game_state.read().await.world.config().physics.ground_y;
+    place(&server, player_id, 0.0, 0.0).await;
+
+    server.command(player_id, "Crouch").await;
+    server.step(1).await;
+    place(&server, player_id, 4.0, 0.0).await;
+    server.command(player_id, "StandUp").await;
+    server.step(TICKS_PER_SEC / 2).await;
+    {
+        let game_state = server.app_state.game_state.read().await;
+        let player = &game_state.players[&player_id];
+        assert!(player.crouched, "stood up into the slab");
+        assert!((player.y - (ground_y + 0.375)).abs() < 0.01, "crouched at y = {}", player.y);
+    }
+
+    // Once out from under the slab the player stands without being asked again
+    place(&server, player_id, 8.0, 0.0).await;
+    server.step(2).await;
+    let game_state = server.app_state.game_state.read().await;
+    let player = &game_state.players[&player_id];
+    assert!(!player.crouched);
+    assert!((player.y - (ground_y + 0.75)).abs() < 0.01, "stood at y = {}", player.y);
+}
+
+#[tokio::test]
+async fn crouching_at_speed_boosts_and_blocks_jumping() {
+    let mut server = TestServer::with_config(harness::test_config()).await;
+    let runner = server.join().await;
+    let idler = server.join().await;
+    place(&server, runner, -20.0, 10.0).await;
+    place(&server, idler, 20.0, 0.0).await;
+
+    server.command(runner, "Crouch").await;
+    server.command(idler, "Crouch").await;
+    server.step(1).await;
+    {
+        let game_state = server.app_state.game_state.read().await;
+        assert!(game_state.players[&runner].velocity_x > 14.0, "no slide boost");
+        assert_eq!(game_state.players[&idler].velocity_x, 0.0);
+    }
+
+    let resting_y = server.app_state.game_state.read().await.players[&idler].y;
+    server.command(idler, "Jump").await;
+    server.step(TICKS_PER_SEC / 4).await;
+    let y = server.app_state.game_state.read().await.players[&idler].y;
+    assert!((y - resting_y).abs() < 0.01, "jumped while crouched");
+}
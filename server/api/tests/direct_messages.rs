@@ -0,0 +1,58 @@
+mod harness;
+
+use api::direct::DirectEvent;
+use api::i18n::Text;
+use game_core::signals::{Signal, SignalPatch};
+use harness::TestServer;
+use serde_json::json;
+
+fn notice(name: &str) -> DirectEvent {
+    DirectEvent::Notice(Text::NowKnownAs { name: name.to_string() })
+}
+
+#[tokio::test]
+async fn direct_messages_reach_only_their_player() {
+    let server = TestServer::start().await;
+    let (alice, alice_token) = server.join_session().await;
+    let (bob, bob_token) = server.join_session().await;
+    let mut alice_events = server.subscribe(&format!("session={}", alice_token)).await;
+    let mut bob_events = server.subscribe(&format!("session={}", bob_token)).await;
+
+    assert_eq!(server.app_state.direct.send(&alice, notice("OnlyForAlice")), 1);
+    assert_eq!(server.app_state.direct.send(&bob, notice("OnlyForBob")), 1);
+    alice_events.next_element_containing("OnlyForAlice").await;
+    bob_events.next_element_containing("OnlyForBob").await;
+    assert!(!bob_events.recorded().iter().any(|e| e.data.contains("OnlyForAlice")));
+
+    let patch = SignalPatch::new().with(Signal::KillCam, json!({ "private": true }));
+    server.app_state.direct.send(&bob, DirectEvent::Signals(patch));
+    assert_eq!(bob_events.next_signal("killCam").await, json!({ "private": true }));
+}
+
+#[tokio::test]
+async fn every_stream_of_a_player_gets_it_until_they_close() {
+    let mut server = TestServer::start().await;
+    let (player_id, token) = server.join_session().await;
+    let mut first = server.subscribe(&format!("session={}", token)).await;
+    let mut second = server.subscribe(&format!("session={}", token)).await;
+    assert_eq!(server.app_state.direct.streams(&player_id), 2);
+
+    assert_eq!(server.app_state.direct.send(&player_id, notice("BothTabs")), 2);
+    first.next_element_containing("BothTabs").await;
+    second.next_element_containing("BothTabs").await;
+
+    drop((first, second));
+    server.wait_for_disconnect(player_id).await;
+    assert_eq!(server.app_state.direct.streams(&player_id), 0);
+    assert_eq!(server.app_state.direct.players(), 0);
+    assert_eq!(server.app_state.direct.send(&player_id, notice("Gone")), 0);
+}
+
+#[tokio::test]
+async fn streams_without_a_session_get_no_direct_messages() {
+    let server = TestServer::start().await;
+    let player_id = server.join().await;
+    let _events = server.subscribe(&format!("player_id={}", player_id)).await;
+    assert_eq!(server.app_state.direct.players(), 0);
+    assert_eq!(server.app_state.direct.send(&player_id, notice("Nobody")), 0);
+}
@@ -0,0 +1,47 @@
+mod harness;
+
+use harness::TestServer;
+use serde_json::{json, Value};
+
+#[tokio::test]
+async fn a_banned_address_loses_its_players_and_cannot_come_back() {
+    let server = TestServer::start().await;
+    let (player_id, token) = server.join_session().await;
+
+    let response = server.admin_post("/api/admin/ban", json!({ "ip": "127.0.0.1" })).await;
+    assert_eq!(response.status(), 200);
+    let body: Value = response.json().await.unwrap();
+    assert_eq!(body["kicked"], json!([player_id]));
+    assert!(!server.app_state.game_state.read().await.players.contains_key(&player_id));
+
+    let response = server.post("/api/player/init", json!({})).await;
+    assert_eq!(response.status(), 403);
+    assert_eq!(response.json::<Value>().await.unwrap()["code"], "banned");
+    let reclaim = json!({ "player_id": player_id, "session_token": token });
+    assert_eq!(server.post("/api/player/init", reclaim).await.status(), 403);
+    assert_eq!(server.get("/events").await.status(), 403);
+
+    let response = server.admin_post("/api/admin/ban", json!({ "ip": "not an address" })).await;
+    assert_eq!(response.status(), 422);
+}
+
+#[tokio::test]
+async fn a_draining_server_keeps_its_players_but_takes_no_new_ones() {
+    let server = TestServer::start().await;
+    let (player_id, token) = server.join_session().await;
+    assert_eq!(server.get("/health").await.status(), 200);
+
+    let response = server.admin_post("/api/admin/drain", json!({})).await;
+    assert_eq!(response.status(), 200);
+    assert_eq!(response.json::<Value>().await.unwrap()["draining"], true);
+    assert_eq!(server.get("/health").await.status(), 503);
+
+    let response = server.post("/api/player/init", json!({})).await;
+    assert_eq!(response.status(), 503);
+    assert_eq!(response.json::<Value>().await.unwrap()["code"], "draining");
+
+    // Players already here reconnect and play on
+    let reclaim = json!({ "player_id": player_id, "session_token": token });
+    assert_eq!(server.post("/api/player/init", reclaim).await.status(), 200);
+    assert!(server.command(player_id, "Jump").await.status().is_success());
+}
@@ -0,0 +1,203 @@
+mod harness;
+
+use std::time::Duration;
+use harness::TestServer;
+
+/// A player's entry in a `gameState` signal
+fn find_player(game_state: &serde_json::Value, player_id: uuid::Uuid) -> Option<serde_json::Value> {
+    game_state
+        .as_array()?
+        .iter()
+        .find(|p| p["id"] == player_id.to_string())
+        .cloned()
+}
+
+#[tokio::test]
+async fn joined_player_appears_in_state() {
+    let mut server = TestServer::start().await;
+    let mut events = server.subscribe("").await;
+
+    let player_id = server.join().await;
+    server.step(1).await;
+
+    let game_state = events.next_signal("gameState").await;
+    assert!(find_player(&game_state, player_id).is_some(), "player missing from {}", game_state);
+}
+
+#[tokio::test]
+async fn state_is_only_broadcast_when_stepped() {
+    let mut server = TestServer::start().await;
+    let mut events = server.subscribe("").await;
+    server.join().await;
+
+    server.step(3).await;
+    let mut ticks = Vec::new();
+    for _ in 0..3 {
+        ticks.push(events.next_signal("tick").await);
+    }
+    assert_eq!(ticks, [1, 2, 3]);
+
+    let states = events
+        .recorded()
+        .iter()
+        .filter(|e| e.signals().is_some_and(|s| s.get("gameState").is_some()))
+        .count();
+    assert_eq!(states, 3);
+}
+
+#[tokio::test]
+async fn slow_frames_catch_up_in_fixed_steps_and_broadcast_once() {
+    let mut server = TestServer::start().await;
+    let mut events = server.subscribe("").await;
+    server.join().await;
+    let dt = 1.0 / server.app_state.game_config.tick_rate_hz;
+
+    // A frame shorter than a step carries its time over to the next one
+    server.frame(dt * 0.6).await;
+    assert_eq!(server.app_state.game_state.read().await.tick, 0);
+    server.frame(dt * 0.6).await;
+    assert_eq!(events.next_signal("tick").await, 1);
+
+    server.frame(dt * 2.9).await;
+    assert_eq!(events.next_signal("tick").await, 4);
+    // A long stall runs a bounded number of steps rather than every one it missed
+    server.frame(10.0).await;
+    let after_stall = events.next_signal("tick").await.as_u64().unwrap();
+    assert!((8..=9).contains(&after_stall), "tick {}", after_stall);
+
+    let states = events
+        .recorded()
+        .iter()
+        .filter(|e| e.signals().is_some_and(|s| s.get("gameState").is_some()))
+        .count();
+    assert_eq!(states, 3);
+}
+
+#[tokio::test]
+async fn state_goes_out_at_the_broadcast_rate_while_simulating_every_tick() {
+    let mut server = TestServer::with_config(game_core::GameConfig {
+        broadcast_rate_hz: 20.0,
+        ..harness::test_config()
+    })
+    .await;
+    let mut events = server.subscribe("").await;
+    let player_id = server.join().await;
+    server.command(player_id, "MoveRight").await;
+
+    server.step(60).await;
+    let last = events.next_matching("the last broadcast", |e| e.signals().is_some_and(|s| s["tick"] == 60)).await;
+    let ticks: Vec<u64> = events
+        .recorded()
+        .iter()
+        .filter_map(|e| e.signals().and_then(|s| s["tick"].as_u64()))
+        .collect();
+    assert_eq!(ticks, (1..=20).map(|n| n * 3).collect::<Vec<_>>());
+    // Physics kept its own pace between broadcasts
+    let game_state = server.app_state.game_state.read().await;
+    assert_eq!(game_state.tick, 60);
+    let broadcast = find_player(&last.signals().unwrap()["gameState"], player_id).unwrap();
+    assert!(broadcast["x"].as_f64().unwrap() > 0.0);
+    assert_eq!(broadcast["x"].as_f64().unwrap() as f32, game_state.players[&player_id].x);
+}
+
+#[tokio::test]
+async fn commands_apply_on_the_next_tick() {
+    let mut server = TestServer::start().await;
+    let mut events = server.subscribe("").await;
+    let player_id = server.join().await;
+
+    let response = server.command(player_id, "MoveRight").await;
+    assert_eq!(response.status(), 200);
+    server.step(10).await;
+
+    let mut x = 0.0;
+    for _ in 0..10 {
+        let game_state = events.next_signal("gameState").await;
+        x = find_player(&game_state, player_id).unwrap()["x"].as_f64().unwrap();
+    }
+    assert!(x > 0.0, "player did not move right, x = {}", x);
+}
+
+#[tokio::test]
+async fn chat_reaches_subscribers_and_late_joiners() {
+    let server = TestServer::start().await;
+    let mut events = server.subscribe("").await;
+    let player_id = server.join().await;
+
+    let response = server.chat(player_id, "hello <world>").await;
+    assert_eq!(response.status(), 200);
+    let html = events.next_element_containing("hello").await;
+    assert!(html.contains("hello &lt;world&gt;"), "chat not escaped: {}", html);
+
+    // A stream opened afterwards gets the message from history
+    let mut late = server.subscribe("").await;
+    late.next_element_containing("hello").await;
+}
+
+#[tokio::test]
+async fn muted_subscribers_do_not_see_chat() {
+    let server = TestServer::start().await;
+    let talker = server.join().await;
+    let mut muting = server.subscribe(&format!("mute={}", talker)).await;
+    let mut listening = server.subscribe("").await;
+
+    server.chat(talker, "first").await;
+    listening.next_element_containing("first").await;
+
+    let other = server.join().await;
+    server.chat(other, "second").await;
+    muting.next_element_containing("second").await;
+    assert!(muting.recorded().iter().all(|e| !e.data.contains("first")));
+}
+
+#[tokio::test]
+async fn idle_players_time_out() {
+    let mut server = TestServer::start().await;
+    let mut events = server.subscribe("").await;
+    let player_id = server.join().await;
+    let idle_timeout = Duration::from_secs(server.app_state.game_config.idle_timeout);
+
+    server.advance_clock(idle_timeout / 2);
+    assert_eq!(server.expire_idle().await, 0);
+
+    server.advance_clock(idle_timeout);
+    assert_eq!(server.expire_idle().await, 1);
+    let left = events.next_signal("playerLeft").await;
+    assert_eq!(left["player_id"], player_id.to_string());
+
+    server.step(1).await;
+    let game_state = events.next_signal("gameState").await;
+    assert!(find_player(&game_state, player_id).is_none());
+}
+
+#[tokio::test]
+async fn activity_resets_the_idle_timer() {
+    let server = TestServer::start().await;
+    let player_id = server.join().await;
+    let idle_timeout = Duration::from_secs(server.app_state.game_config.idle_timeout);
+
+    server.advance_clock(idle_timeout * 3 / 4);
+    server.command(player_id, "Stop").await;
+    server.advance_clock(idle_timeout * 3 / 4);
+    assert_eq!(server.expire_idle().await, 0);
+}
+
+#[tokio::test]
+async fn rename_cooldown_follows_the_clock() {
+    let server = TestServer::start().await;
+    let player_id = server.join().await;
+    let token = server.token(player_id);
+    let rename = |name: &'static str| {
+        server.post(
+            "/api/player/rename",
+            serde_json::json!({ "player_id": player_id, "name": name, "session_token": token }),
+        )
+    };
+
+    assert_eq!(rename("Alice").await.status(), 200);
+    assert_eq!(rename("Alicia").await.status(), 429);
+
+    let cooldown = server.app_state.game_config.names.rename_cooldown_secs;
+    server.advance_clock(Duration::from_secs(cooldown + 1));
+    assert_eq!(rename("Alicia").await.status(), 200);
+}
@@ -0,0 +1,90 @@
+mod harness;
+
+use std::time::Duration;
+use harness::TestServer;
+use serde_json::{json, Value};
+
+/// Status and body of a failed request
+async fn error(response: reqwest::Response) -> (u16, Value) {
+    let status = response.status().as_u16();
+    (status, response.json().await.unwrap())
+}
+
+#[tokio::test]
+async fn game_routes_explain_their_failures() {
+    let server = TestServer::start().await;
+    let (player_id, _) = server.join_session().await;
+    let (_, other_token) = server.join_session().await;
+
+    let stolen = json!({ "player_id": player_id, "command": { "type": "Jump" }, "session_token": other_token });
+    let (status, body) = error(server.post("/api/player/command", stolen).await).await;
+    assert_eq!(status, 401);
+    assert_eq!(body["code"], "invalid_session");
+    assert!(body["message"].as_str().is_some_and(|m| !m.is_empty()));
+    assert!(body["details"].is_null());
+
+    let missing = json!({ "player_id": player_id, "command": { "type": "Jump" } });
+    let (status, body) = error(server.post("/api/player/command", missing).await).await;
+    assert_eq!((status, body["code"].as_str()), (401, Some("invalid_session")));
+
+    let gone = uuid::Uuid::new_v4();
+    let unknown = json!({ "player_id": gone, "session_token": server.token(gone) });
+    let (status, body) = error(server.post("/api/player/heartbeat", unknown).await).await;
+    assert_eq!((status, body["code"].as_str()), (404, Some("unknown_player")));
+
+    let rename = json!({ "player_id": gone, "name": "Nobody", "session_token": server.token(gone) });
+    let (status, body) = error(server.post("/api/player/rename", rename).await).await;
+    assert_eq!((status, body["code"].as_str()), (404, Some("unknown_player")));
+}
+
+#[tokio::test]
+async fn refused_chat_is_an_error_in_the_senders_language() {
+    let server = TestServer::start().await;
+    let player_id = server.join().await;
+
+    let (status, body) = error(server.chat(player_id, "").await).await;
+    assert_eq!((status, body["code"].as_str()), (422, Some("message_rejected")));
+    assert_eq!(body["message"], "Message not sent: message is empty");
+
+    server.app_state.game_state.write().await.players.get_mut(&player_id).unwrap().team = None;
+    let team_chat = json!({ "player_id": player_id, "text": "rush left", "team": true, "session_token": server.token(player_id) });
+    let (status, body) = error(server.post("/api/chat", team_chat).await).await;
+    assert_eq!((status, body["code"].as_str()), (409, Some("not_on_team")));
+
+    server.app_state.moderator.mute(player_id, Duration::from_secs(120)).await;
+    let (status, body) = error(server.chat(player_id, "hello").await).await;
+    assert_eq!((status, body["code"].as_str()), (403, Some("muted")));
+    assert_eq!(body["details"]["remaining_secs"], 120);
+}
+
+#[tokio::test]
+async fn admin_routes_explain_their_failures() {
+    let server = TestServer::start().await;
+
+    let (status, body) = error(server.post("/api/admin/kick", json!({ "player_id": uuid::Uuid::new_v4() })).await).await;
+    assert_eq!((status, body["code"].as_str()), (401, Some("unauthorized")));
+
+    let (status, body) = error(server.admin_post("/api/admin/kick", json!({ "player_id": uuid::Uuid::new_v4() })).await).await;
+    assert_eq!((status, body["code"].as_str()), (404, Some("unknown_player")));
+
+    let (status, body) = error(server.admin_post("/api/admin/announce", json!({ "text": "  " })).await).await;
+    assert_eq!((status, body["code"].as_str()), (400, Some("bad_request")));
+}
+
+#[tokio::test]
+async fn feature_routes_use_the_same_error_body() {
+    let server = TestServer::start().await;
+    let (_, token) = server.join_session().await;
+
+    let reserve = json!({ "session_token": token });
+    let (status, body) = error(server.post("/api/player/name/reservation", reserve).await).await;
+    assert_eq!((status, body["code"].as_str()), (403, Some("forbidden")));
+    assert_eq!(body["message"], "only registered players can reserve a name");
+
+    let (status, body) = error(server.post("/api/plots/release", json!({ "session_token": token })).await).await;
+    assert_eq!((status, body["code"].as_str()), (404, Some("not_found")));
+
+    let (status, body) = error(server.admin_post("/api/admin/mode", json!({ "mode": "nonsense" })).await).await;
+    assert_eq!((status, body["code"].as_str()), (404, Some("not_found")));
+    assert!(body.get("error").is_none());
+}
@@ -0,0 +1,51 @@
+mod harness;
+
+use game_core::config::ForceZoneConfig;
+use game_core::GameConfig;
+use harness::TestServer;
+
+/// A strong rightward wind over x = -5..5 near the ground
+fn windy_config() -> GameConfig {
+    let mut config = harness::test_config();
+    let ground_y = config.physics.ground_y;
+    config.force_zones = vec![ForceZoneConfig {
+        id: "gust".to_string(),
+        x_start: -5.0,
+        x_end: 5.0,
+        y_bottom: ground_y,
+        y_top: ground_y + 4.0,
+        force_x: 300.0,
+        force_y: 0.0,
+        color: "#88CCFF".to_string(),
+    }];
+    config
+}
+
+#[tokio::test]
+async fn config_lists_force_zones_for_rendering() {
+    let server = TestServer::with_config(windy_config()).await;
+    let config = server.get("/api/config").await.json::<serde_json::Value>().await.unwrap();
+    let zones = config["force_zones"].as_array().unwrap();
+    assert_eq!(zones.len(), 1);
+    assert_eq!(zones[0]["id"], "gust");
+    assert_eq!(zones[0]["force_x"], 300.0);
+    assert_eq!(zones[0]["color"], "#88CCFF");
+}
+
+#[tokio::test]
+async fn players_in_a_wind_zone_drift_with_it() {
+    let mut server = TestServer::with_config(windy_config()).await;
+    let player_id = server.join().await;
+    {
+        let mut game_state = server.app_state.game_state.write().await;
+        let ground_y = game_state.world.config().physics.ground_y;
+        let player = game_state.players.get_mut(&player_id).unwrap();
+        player.x = -4.0;
+        player.y = ground_y + 1.0;
+        player.velocity_x = 0.0;
+    }
+
+    server.step(30).await;
+    let game_state = server.app_state.game_state.read().await;
+    assert!(game_state.players[&player_id].x > -3.0, "stayed at {}", game_state.players[&player_id].x);
+}
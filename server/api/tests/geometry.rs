@@ -0,0 +1,49 @@
+mod harness;
+
+use game_core::Block;
+use harness::TestServer;
+use serde_json::{json, Value};
+
+fn block(cell_x: i32, cell_y: i32) -> Block {
+    Block {
+        cell_x,
+        cell_y,
+        owner: uuid::Uuid::nil(),
+        color: "#fff".to_string(),
+    }
+}
+
+#[tokio::test]
+async fn clients_resync_from_their_version_or_fall_back_to_a_snapshot() {
+    let server = TestServer::start().await;
+    {
+        let mut game_state = server.app_state.game_state.write().await;
+        game_state.blocks.insert(block(0, 0));
+        game_state.blocks.insert(block(1, 0));
+        game_state.blocks.remove(&(0, 0));
+        game_state.blocks.insert(block(2, 0));
+    }
+
+    let full: Value = server.get("/api/geometry").await.json().await.unwrap();
+    assert_eq!(full["type"], "snapshot");
+    assert_eq!(full["version"], 4);
+    assert_eq!(full["blocks"].as_array().unwrap().len(), 2);
+
+    // Changes since a known version collapse to each cell's final state
+    let delta: Value = server.get("/api/geometry?since=1").await.json().await.unwrap();
+    assert_eq!(delta["type"], "delta");
+    assert_eq!(delta["from_version"], 1);
+    assert_eq!(delta["to_version"], 4);
+    let added: Vec<&Value> = delta["added"].as_array().unwrap().iter().map(|b| &b["cell_x"]).collect();
+    assert_eq!(added, [&json!(1), &json!(2)]);
+    assert_eq!(delta["removed"], json!([[0, 0]]));
+
+    let current: Value = server.get("/api/geometry?since=4").await.json().await.unwrap();
+    assert_eq!(current["type"], "delta");
+    assert_eq!(current["added"], json!([]));
+
+    // A version the server never reached can't be patched
+    let ahead: Value = server.get("/api/geometry?since=9").await.json().await.unwrap();
+    assert_eq!(ahead["type"], "snapshot");
+    assert_eq!(ahead["version"], 4);
+}
@@ -0,0 +1,393 @@
+//! In-process harness for end-to-end API tests
+//!
+//! Boots the full Axum app on a local port, but keeps the game loop and the clock in the
+//! test's hands: nothing moves until the test calls `step` or `advance_clock`.
+
+// Each test binary uses a different subset of the harness
+#![allow(dead_code)]
+
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use futures::StreamExt;
+use serde_json::Value;
+use tokio::sync::mpsc;
+use api::game_loop::{remove_inactive_players, GameLoop};
+use api::state::AppState;
+use game_core::{CosmeticStore, GameConfig, MatchHistory, MockClock, PlayerSettings};
+
+/// How long to wait for an expected SSE event before failing the test
+const EVENT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Bearer token accepted by the test server's admin API
+pub const ADMIN_TOKEN: &str = "test-admin-token";
+
+/// Config for tests: state is broadcast on every tick so each step is observable
+pub fn test_config() -> GameConfig {
+    let defaults = GameConfig::default();
+    GameConfig {
+        broadcast_rate_hz: defaults.tick_rate_hz,
+        snapshot_rate_hz: defaults.tick_rate_hz,
+        // Tests step many ticks at once, which would look like a slow subscriber
+        adaptive_rate: game_core::config::AdaptiveRateConfig {
+            enabled: false,
+            ..Default::default()
+        },
+        match_history_path: None,
+        remote_config: None,
+        admin: game_core::config::AdminConfig {
+            token_env: "TEST_SERVER_ADMIN_TOKEN".to_string(),
+            token: Some(ADMIN_TOKEN.to_string()),
+        },
+        ..defaults
+    }
+}
+
+pub struct TestServer {
+    pub app_state: AppState,
+    pub base_url: String,
+    client: reqwest::Client,
+    pub clock: Arc<MockClock>,
+    game_loop: GameLoop,
+}
+
+impl TestServer {
+    pub async fn start() -> Self {
+        Self::with_config(test_config()).await
+    }
+
+    pub async fn with_config(config: GameConfig) -> Self {
+        Self::boot(config, None).await
+    }
+
+    /// Serve over HTTPS with the given certificate; the client accepts it unverified
+    pub async fn with_tls(config: GameConfig, tls: api::tls::RustlsConfig) -> Self {
+        Self::boot(config, Some(tls)).await
+    }
+
+    async fn boot(config: GameConfig, tls: Option<api::tls::RustlsConfig>) -> Self {
+        let config = Arc::new(config);
+        let clock = Arc::new(MockClock::new());
+        let (app_state, command_rx) = AppState::new(
+            config.clone(),
+            MatchHistory::in_memory(),
+            PlayerSettings::in_memory(),
+            game_core::NameReservations::in_memory(),
+            CosmeticStore::in_memory(),
+            api::privacy::Tombstones::in_memory(),
+            clock.clone(),
+        );
+        if config.housing.path.is_some() {
+            let housing = game_core::Housing::load(&config.housing).await.unwrap();
+            app_state.game_state.write().await.restore_housing(housing);
+        }
+        let game_loop = GameLoop::new(
+            app_state.game_state.clone(),
+            command_rx,
+            app_state.game_tx.clone(),
+            app_state.match_history.clone(),
+            app_state.cosmetics.clone(),
+            &config,
+            clock.clone(),
+        )
+        .with_bots(app_state.bots.clone())
+        .with_moderator(app_state.moderator.clone())
+        .with_backplane(app_state.backplane.clone())
+        .with_latency_tracer(app_state.latency.clone());
+        api::backplane::spawn(&app_state);
+
+        app_state.replays.spawn_workers(config.replays.workers);
+        let app = api::app(app_state.clone());
+        let (base_url, client) = match tls {
+            Some(tls) => {
+                let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+                let addr = listener.local_addr().unwrap();
+                tokio::spawn(async move {
+                    api::tls::serve(listener, tls, app, std::future::pending()).await.unwrap();
+                });
+                let client = reqwest::Client::builder().danger_accept_invalid_certs(true).build().unwrap();
+                (format!("https://{}", addr), client)
+            }
+            None => {
+                let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+                let addr = listener.local_addr().unwrap();
+                tokio::spawn(async move {
+                    axum::serve(listener, app.into_make_service_with_connect_info::<SocketAddr>())
+                        .await
+                        .unwrap();
+                });
+                (format!("http://{}", addr), reqwest::Client::new())
+            }
+        };
+
+        Self {
+            app_state,
+            base_url,
+            client,
+            clock,
+            game_loop,
+        }
+    }
+
+    pub fn url(&self, path: &str) -> String {
+        format!("{}{}", self.base_url, path)
+    }
+
+    pub async fn get(&self, path: &str) -> reqwest::Response {
+        self.client.get(self.url(path)).send().await.unwrap()
+    }
+
+    pub async fn post(&self, path: &str, body: Value) -> reqwest::Response {
+        self.client.post(self.url(path)).json(&body).send().await.unwrap()
+    }
+
+    pub async fn put(&self, path: &str, body: Value) -> reqwest::Response {
+        self.client.put(self.url(path)).json(&body).send().await.unwrap()
+    }
+
+    pub async fn delete(&self, path: &str, body: Value) -> reqwest::Response {
+        self.client.delete(self.url(path)).json(&body).send().await.unwrap()
+    }
+
+    /// GET an admin route with the test admin token
+    pub async fn admin_get(&self, path: &str) -> reqwest::Response {
+        self.client.get(self.url(path)).bearer_auth(ADMIN_TOKEN).send().await.unwrap()
+    }
+
+    /// POST to an admin route with the test admin token
+    pub async fn admin_post(&self, path: &str, body: Value) -> reqwest::Response {
+        self.client
+            .post(self.url(path))
+            .bearer_auth(ADMIN_TOKEN)
+            .json(&body)
+            .send()
+            .await
+            .unwrap()
+    }
+
+    /// Join as a new player, asserting the server accepted them
+    pub async fn join(&self) -> uuid::Uuid {
+        self.join_session().await.0
+    }
+
+    /// Join as a new player, returning the id the server assigned and their session token
+    pub async fn join_session(&self) -> (uuid::Uuid, String) {
+        let response = self.post("/api/player/init", serde_json::json!({})).await;
+        assert_eq!(response.status(), 200, "join rejected");
+        let body: Value = response.json().await.unwrap();
+        let player_id = body["player_id"].as_str().unwrap().parse().unwrap();
+        (player_id, body["session_token"].as_str().unwrap().to_string())
+    }
+
+    /// The session token the server issues for `player_id`
+    pub fn token(&self, player_id: uuid::Uuid) -> String {
+        self.app_state.sessions.issue(player_id)
+    }
+
+    /// Send a command, e.g. `command(id, "MoveRight")`; it applies on the next `step`
+    pub async fn command(&self, player_id: uuid::Uuid, command: &str) -> reqwest::Response {
+        self.post(
+            "/api/player/command",
+            serde_json::json!({
+                "player_id": player_id,
+                "command": { "type": command },
+                "session_token": self.token(player_id),
+            }),
+        )
+        .await
+    }
+
+    pub async fn chat(&self, player_id: uuid::Uuid, text: &str) -> reqwest::Response {
+        self.post(
+            "/api/chat",
+            serde_json::json!({ "player_id": player_id, "text": text, "session_token": self.token(player_id) }),
+        )
+        .await
+    }
+
+    /// Run exactly `ticks` fixed physics steps, applying queued commands and broadcasting
+    /// The clock advances by one timestep per tick
+    pub async fn step(&mut self, ticks: u32) {
+        let dt = self.game_loop.fixed_timestep();
+        for _ in 0..ticks {
+            self.clock.advance(Duration::from_secs_f32(dt));
+            self.game_loop.advance(dt).await;
+        }
+    }
+
+    /// Run one game loop frame that took `secs` of real time, however many steps that pays for
+    pub async fn frame(&mut self, secs: f32) {
+        self.clock.advance(Duration::from_secs_f32(secs));
+        self.game_loop.advance(secs).await;
+    }
+
+    /// Move the clock forward without running the game loop
+    pub fn advance_clock(&self, by: Duration) {
+        self.clock.advance(by);
+    }
+
+    /// Run the idle cleanup at the clock's current time
+    pub async fn expire_idle(&self) -> usize {
+        let timeout = Duration::from_secs(self.app_state.game_config.idle_timeout);
+        remove_inactive_players(
+            &self.app_state.game_state,
+            &self.app_state.game_tx,
+            &self.app_state.sessions,
+            timeout,
+        )
+        .await
+    }
+
+    /// Step until the server notices the player's session streams have closed
+    pub async fn wait_for_disconnect(&mut self, player_id: uuid::Uuid) {
+        for _ in 0..100 {
+            if !self.app_state.sessions.is_connected(&player_id) {
+                return;
+            }
+            self.step(1).await;
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        panic!("session stream for {} never closed", player_id);
+    }
+
+    /// Open an SSE stream with the given query string (e.g. `player_id=...`)
+    /// Returns once the server has sent the initial signals, so later broadcasts are seen
+    pub async fn subscribe(&self, query: &str) -> SseClient {
+        let mut client = self.stream(&format!("/events?{}", query)).await;
+        client.next_signal("resumeToken").await;
+        client
+    }
+
+    /// Open any SSE stream, such as the overlay's
+    pub async fn stream(&self, path: &str) -> SseClient {
+        let response = self.get(path).await;
+        assert_eq!(response.status(), 200, "SSE connection rejected");
+        SseClient::new(response)
+    }
+}
+
+/// One SSE event as received
+#[derive(Debug, Clone)]
+pub struct SseEvent {
+    pub event: String,
+    /// The Datastar payload: `signals {...}` or `elements <html>` lines joined
+    pub data: String,
+}
+
+impl SseEvent {
+    /// The signals object of a patch-signals event
+    pub fn signals(&self) -> Option<Value> {
+        if self.event != "datastar-patch-signals" {
+            return None;
+        }
+        self.data
+            .lines()
+            .find_map(|line| line.strip_prefix("signals "))
+            .and_then(|json| serde_json::from_str(json).ok())
+    }
+
+    /// The HTML of a patch-elements event
+    pub fn elements(&self) -> Option<String> {
+        if self.event != "datastar-patch-elements" {
+            return None;
+        }
+        let html: Vec<&str> = self.data.lines().filter_map(|line| line.strip_prefix("elements ")).collect();
+        (!html.is_empty()).then(|| html.join("\n"))
+    }
+}
+
+/// Parse one SSE frame; Datastar events are nested, so `data:` prefixes are stripped twice
+fn parse_frame(frame: &str) -> Option<SseEvent> {
+    let mut event = String::new();
+    let mut data = Vec::new();
+    for line in frame.lines() {
+        if let Some(name) = line.strip_prefix("event:") {
+            event = name.trim().to_string();
+        } else if let Some(value) = line.strip_prefix("data:") {
+            let value = value.strip_prefix(' ').unwrap_or(value);
+            data.push(value.strip_prefix("data: ").unwrap_or(value));
+        }
+    }
+    (!event.is_empty()).then(|| SseEvent { event, data: data.join("\n") })
+}
+
+/// Reads an SSE stream in the background, recording every event
+/// Dropping it closes the connection
+pub struct SseClient {
+    rx: mpsc::UnboundedReceiver<SseEvent>,
+    recorded: Arc<Mutex<Vec<SseEvent>>>,
+    reader: tokio::task::JoinHandle<()>,
+}
+
+impl SseClient {
+    fn new(response: reqwest::Response) -> Self {
+        let (tx, rx) = mpsc::unbounded_channel();
+        let recorded = Arc::new(Mutex::new(Vec::new()));
+        let log = recorded.clone();
+        let reader = tokio::spawn(async move {
+            let mut body = response.bytes_stream();
+            let mut buffer = String::new();
+            while let Some(Ok(chunk)) = body.next().await {
+                buffer.push_str(&String::from_utf8_lossy(&chunk));
+                while let Some(end) = buffer.find("\n\n") {
+                    let frame: String = buffer.drain(..end + 2).collect();
+                    if let Some(event) = parse_frame(&frame) {
+                        log.lock().unwrap().push(event.clone());
+                        if tx.send(event).is_err() {
+                            return;
+                        }
+                    }
+                }
+            }
+        });
+        Self { rx, recorded, reader }
+    }
+
+    /// Wait for the next event matching `matches`, skipping others
+    pub async fn next_matching(&mut self, what: &str, mut matches: impl FnMut(&SseEvent) -> bool) -> SseEvent {
+        let wait = async {
+            while let Some(event) = self.rx.recv().await {
+                if matches(&event) {
+                    return Some(event);
+                }
+            }
+            None
+        };
+        match tokio::time::timeout(EVENT_TIMEOUT, wait).await {
+            Ok(Some(event)) => event,
+            Ok(None) => panic!("stream closed while waiting for {}", what),
+            Err(_) => panic!("timed out waiting for {}", what),
+        }
+    }
+
+    /// Wait for a patch-signals event carrying `key` and return its value
+    pub async fn next_signal(&mut self, key: &str) -> Value {
+        let event = self
+            .next_matching(&format!("signal '{}'", key), |e| {
+                e.signals().is_some_and(|s| s.get(key).is_some())
+            })
+            .await;
+        event.signals().unwrap()[key].clone()
+    }
+
+    /// Wait for a patch-elements event whose HTML contains `needle`
+    pub async fn next_element_containing(&mut self, needle: &str) -> String {
+        let event = self
+            .next_matching(&format!("element containing '{}'", needle), |e| {
+                e.elements().is_some_and(|html| html.contains(needle))
+            })
+            .await;
+        event.elements().unwrap()
+    }
+
+    /// Every event received so far, in order
+    pub fn recorded(&self) -> Vec<SseEvent> {
+        self.recorded.lock().unwrap().clone()
+    }
+}
+
+impl Drop for SseClient {
+    fn drop(&mut self) {
+        self.reader.abort();
+    }
+}
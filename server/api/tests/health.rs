@@ -0,0 +1,141 @@
+mod harness;
+
+use game_core::config::{HazardConfig, SpawnPoint};
+use game_core::GameConfig;
+use harness::TestServer;
+
+const TICKS_PER_SEC: u32 = 60;
+
+/// Spikes on the ground at x 4..6 and spawn points at x = -5 and x = 5 (on the spikes)
+fn hazard_config() -> GameConfig {
+    let defaults = harness::test_config();
+    let ground_y = defaults.physics.ground_y;
+    let standing_y = ground_y + defaults.physics.player_height / 2.0;
+    GameConfig {
+        spawn_points: vec![SpawnPoint { x: -5.0, y: standing_y }, SpawnPoint { x: 5.0, y: standing_y }],
+        hazards: vec![HazardConfig {
+            id: "spikes".to_string(),
+            x_start: 4.0,
+            x_end: 6.0,
+            y_bottom: ground_y,
+            y_top: ground_y + 0.5,
+            damage: 20,
+            damage_per_second: 0.0,
+            instant_kill: false,
+            knockback: 10.0,
+            color: "#CC2222".to_string(),
+        }],
+        ..defaults
+    }
+}
+
+/// The spikes of `hazard_config` turned into lava or a kill zone
+fn config_with_spikes(damage_per_second: f32, instant_kill: bool) -> GameConfig {
+    let mut config = hazard_config();
+    let spikes = &mut config.hazards[0];
+    spikes.damage = 0;
+    spikes.knockback = 0.0;
+    spikes.damage_per_second = damage_per_second;
+    spikes.instant_kill = instant_kill;
+    config
+}
+
+#[tokio::test]
+async fn hazards_hurt_and_knock_up_with_invulnerability_between_hits() {
+    let mut server = TestServer::with_config(hazard_config()).await;
+    let mut events = server.subscribe("").await;
+    server.join().await;
+    let victim = server.join().await;
+
+    server.step(1).await;
+    let damage = events.next_signal("damage").await;
+    assert_eq!(damage[0]["target"], victim.to_string());
+    assert_eq!(damage[0]["source"]["type"], "hazard");
+    assert_eq!(damage[0]["source"]["hazard_id"], "spikes");
+    assert_eq!(damage[0]["amount"], 20);
+
+    let players = events.next_signal("gameState").await;
+    let state = players.as_array().unwrap().iter().find(|p| p["id"] == victim.to_string()).unwrap();
+    assert_eq!(state["health"], 80);
+    assert!(state["invulnerable_secs"].as_f64().unwrap() > 0.0);
+
+    {
+        let game_state = server.app_state.game_state.read().await;
+        assert!(game_state.players[&victim].velocity_y > 0.0, "hazard did not knock the player up");
+    }
+
+    // Bouncing on the spikes only hurts once per invulnerability window
+    server.step(TICKS_PER_SEC / 2).await;
+    assert_eq!(server.app_state.game_state.read().await.players[&victim].health, 80);
+}
+
+#[tokio::test]
+async fn landing_on_a_head_stomps() {
+    let config = harness::test_config();
+    let player_height = config.physics.player_height;
+    let mut server = TestServer::with_config(config).await;
+    let mut events = server.subscribe("").await;
+    let target = server.join().await;
+    let stomper = server.join().await;
+
+    {
+        let mut game_state = server.app_state.game_state.write().await;
+        let target_y = game_state.players[&target].y;
+        let player = game_state.players.get_mut(&stomper).unwrap();
+        player.y = target_y + player_height * 3.0;
+        player.ground_state = game_core::GroundState::Flying;
+    }
+
+    let mut damage = None;
+    for _ in 0..TICKS_PER_SEC {
+        server.step(1).await;
+        if server.app_state.game_state.read().await.players[&target].health < 100 {
+            damage = Some(events.next_signal("damage").await);
+            break;
+        }
+    }
+    let damage = damage.expect("stomper never landed");
+    assert_eq!(damage[0]["target"], target.to_string());
+    assert_eq!(damage[0]["source"]["type"], "stomp");
+    assert_eq!(damage[0]["source"]["by"], stomper.to_string());
+
+    let game_state = server.app_state.game_state.read().await;
+    assert_eq!(game_state.players[&target].health, 50);
+    assert!(game_state.players[&stomper].velocity_y > 0.0, "stomper did not bounce");
+    assert_eq!(game_state.players[&stomper].health, 100);
+}
+
+#[tokio::test]
+async fn lava_drains_health_every_second_through_invulnerability() {
+    let mut server = TestServer::with_config(config_with_spikes(30.0, false)).await;
+    server.join().await;
+    let victim = server.join().await;
+
+    server.app_state.game_state.write().await.players.get_mut(&victim).unwrap().invulnerable_secs = 5.0;
+    server.step(TICKS_PER_SEC + 1).await;
+    assert_eq!(server.app_state.game_state.read().await.players[&victim].health, 70);
+
+    server.step(TICKS_PER_SEC).await;
+    let game_state = server.app_state.game_state.read().await;
+    assert_eq!(game_state.players[&victim].health, 40);
+    assert!(game_state.players[&victim].invulnerable_secs > 0.0, "lava granted fresh invulnerability");
+}
+
+#[tokio::test]
+async fn instant_kill_hazards_kill_on_touch() {
+    let mut server = TestServer::with_config(config_with_spikes(0.0, true)).await;
+    let mut events = server.subscribe("").await;
+    server.join().await;
+    let victim = server.join().await;
+    server.app_state.game_state.write().await.players.get_mut(&victim).unwrap().invulnerable_secs = 5.0;
+
+    server.step(1).await;
+    let damage = events.next_signal("damage").await;
+    assert_eq!(damage[0]["target"], victim.to_string());
+    assert_eq!(damage[0]["amount"], 100);
+
+    let life = events.next_signal("lifeEvents").await;
+    assert_eq!(life[0]["type"], "died");
+    assert_eq!(life[0]["player_id"], victim.to_string());
+    assert_eq!(life[0]["cause"], "hazard");
+}
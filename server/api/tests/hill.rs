@@ -0,0 +1,58 @@
+mod harness;
+
+use game_core::config::{ControlZoneConfig, GameRules, KingOfTheHillConfig};
+use game_core::{GameConfig, HillSide};
+use harness::TestServer;
+
+/// A hill around x = 10 that's taken the moment a player stands in it
+fn hill_config() -> GameConfig {
+    let mut config = harness::test_config();
+    let ground_y = config.physics.ground_y;
+    config.game_mode = GameRules::KING_OF_THE_HILL;
+    config.king_of_the_hill = KingOfTheHillConfig { capture_secs: 0.0, ..KingOfTheHillConfig::default() };
+    config.matches.enabled = false;
+    config.control_zone = Some(ControlZoneConfig {
+        id: "hill".to_string(),
+        x_start: 9.0,
+        x_end: 11.0,
+        y_bottom: ground_y,
+        y_top: ground_y + 4.0,
+        color: "#FF8800".to_string(),
+    });
+    config
+}
+
+#[tokio::test]
+async fn capturing_the_zone_broadcasts_its_owner() {
+    let mut server = TestServer::with_config(hill_config()).await;
+    let mut events = server.subscribe("").await;
+    let player_id = server.join().await;
+    server.step(1).await;
+    let side = {
+        let mut game_state = server.app_state.game_state.write().await;
+        let ground_y = game_state.world.config().physics.ground_y;
+        let player = game_state.players.get_mut(&player_id).unwrap();
+        player.x = 10.0;
+        player.y = ground_y + 1.0;
+        serde_json::to_value(HillSide::of(player)).unwrap()
+    };
+
+    server.step(1).await;
+    let hill = loop {
+        let hill = events.next_signal("hill").await;
+        if !hill["owner"].is_null() {
+            break hill;
+        }
+    };
+    assert_eq!(hill["owner"], side);
+    assert_eq!(hill["contested"], false);
+    assert_eq!(hill["score_cap"], 100);
+}
+
+#[tokio::test]
+async fn config_describes_the_control_zone() {
+    let server = TestServer::with_config(hill_config()).await;
+    let config = server.get("/api/config").await.json::<serde_json::Value>().await.unwrap();
+    assert_eq!(config["control_zone"]["id"], "hill");
+    assert_eq!(config["king_of_the_hill"]["score_cap"], 100);
+}
@@ -0,0 +1,95 @@
+mod harness;
+
+use game_core::config::HousingConfig;
+use game_core::GameConfig;
+use harness::{test_config, TestServer};
+use serde_json::{json, Value};
+
+fn housing_config(path: &std::path::Path) -> GameConfig {
+    GameConfig {
+        housing: HousingConfig {
+            enabled: true,
+            path: Some(path.to_string_lossy().into_owned()),
+            ..HousingConfig::default()
+        },
+        ..test_config()
+    }
+}
+
+/// A player who registered a name, with their session token
+async fn registered(server: &TestServer) -> (uuid::Uuid, String) {
+    let (player_id, token) = server.join_session().await;
+    let name = format!("Owner{}", &player_id.simple().to_string()[..6]);
+    let request = json!({ "player_id": player_id, "name": name, "session_token": token });
+    let body: Value = server.post("/api/player/register", request).await.json().await.unwrap();
+    assert_eq!(body["accepted"], true);
+    (player_id, token)
+}
+
+#[tokio::test]
+async fn houses_built_in_a_claimed_plot_survive_a_restart() {
+    let path = std::env::temp_dir().join(format!("plots-{}.json", uuid::Uuid::new_v4()));
+    let mut server = TestServer::with_config(housing_config(&path)).await;
+    let (owner, token) = registered(&server).await;
+
+    let anonymous = server.post("/api/plots/claim", json!({})).await;
+    assert_eq!(anonymous.status(), 422);
+    let forged = format!("{}.{}", owner, "0".repeat(64));
+    let stolen = server.post("/api/plots/claim", json!({ "session_token": forged })).await;
+    assert_eq!(stolen.status(), 401);
+    let reply = server.post("/api/plots/claim", json!({ "session_token": token })).await;
+    assert_eq!(reply.status(), 200);
+    let plot: Value = reply.json().await.unwrap();
+    assert_eq!(plot["plot"]["id"], 0);
+    let again = server
+        .post("/api/plots/claim", json!({ "session_token": token, "plot_id": 1 }))
+        .await;
+    assert_eq!(again.status(), 409);
+
+    // Travel to the plot, land, and build
+    let visit = server.post("/api/plots/0/visit", json!({ "session_token": token })).await;
+    assert_eq!(visit.status(), 200);
+    server.step(90).await;
+    let command = json!({
+        "player_id": owner,
+        "command": { "type": "PlaceBlock", "x": -18.5, "y": -9.5 },
+        "session_token": token,
+    });
+    assert_eq!(server.post("/api/player/command", command).await.status(), 200);
+    server.step(1).await;
+
+    let plots: Value = server.get("/api/plots").await.json().await.unwrap();
+    assert_eq!(plots["plots"][0]["owner"], owner.to_string());
+    assert_eq!(plots["plots"][0]["blocks"], 1);
+    assert!(plots["plots"][1]["owner"].is_null());
+
+    // Plots are written in the background; wait for the house to reach the file
+    for _ in 0..100 {
+        if std::fs::read_to_string(&path).is_ok_and(|contents| contents.contains("\"cell_x\"")) {
+            break;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+    }
+    let restarted = TestServer::with_config(housing_config(&path)).await;
+    let plots: Value = restarted.get("/api/plots").await.json().await.unwrap();
+    assert_eq!(plots["plots"][0]["owner"], owner.to_string());
+    assert_eq!(restarted.app_state.game_state.read().await.blocks.blocks.len(), 1);
+    assert_eq!(restarted.post("/api/plots/7/visit", json!({ "session_token": restarted.token(owner) })).await.status(), 404);
+
+    let released = server.post("/api/plots/release", json!({ "session_token": token })).await;
+    assert_eq!(released.status(), 200);
+    assert!(server.app_state.game_state.read().await.blocks.blocks.is_empty());
+    let _ = std::fs::remove_file(&path);
+}
+
+#[tokio::test]
+async fn guests_cannot_claim_plots() {
+    let path = std::env::temp_dir().join(format!("plots-{}.json", uuid::Uuid::new_v4()));
+    let server = TestServer::with_config(housing_config(&path)).await;
+    let (_, token) = server.join_session().await;
+    let reply = server.post("/api/plots/claim", json!({ "session_token": token })).await;
+    assert_eq!(reply.status(), 403);
+    let plots: Value = server.get("/api/plots").await.json().await.unwrap();
+    assert!(plots["plots"].as_array().unwrap().iter().all(|plot| plot["owner"].is_null()));
+    assert!(!path.exists(), "nothing was saved");
+}
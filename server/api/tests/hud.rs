@@ -0,0 +1,48 @@
+mod harness;
+
+use harness::TestServer;
+
+#[tokio::test]
+async fn hud_streams_get_rendered_fragments_as_they_change() {
+    let mut server = TestServer::start().await;
+    let (_, token) = server.join_session().await;
+    let mut events = server.subscribe(&format!("session={}&hud=true", token)).await;
+    server.step(1).await;
+
+    assert!(events.next_element_containing(r#"id="hud-players""#).await.contains("<b>1</b>"));
+    let tick = events.next_element_containing(r#"id="hud-tick""#).await;
+    assert!(tick.contains(r#"data-health="ok""#) && tick.contains("60 Hz"), "{}", tick);
+    assert!(events.next_element_containing(r#"id="hud-score""#).await.contains("Score: <b>0</b>"));
+    assert!(events.next_element_containing(r#"id="hud-match""#).await.contains("Waiting for players"));
+
+    // Half a second later, at the default HUD rate, the player count has changed but not the score
+    server.join().await;
+    server.step(30).await;
+    assert!(events.next_element_containing(r#"id="hud-players""#).await.contains("<b>2</b>"));
+    server.step(1).await;
+    let sent = |id: &str| {
+        events
+            .recorded()
+            .iter()
+            .filter(|e| e.elements().is_some_and(|html| html.contains(&format!(r#"id="{}""#, id))))
+            .count()
+    };
+    assert_eq!(sent("hud-players"), 2);
+    assert_eq!(sent("hud-tick"), 1);
+    assert_eq!(sent("hud-score"), 1);
+}
+
+#[tokio::test]
+async fn other_streams_get_no_hud() {
+    let mut server = TestServer::start().await;
+    let (_, token) = server.join_session().await;
+    let mut events = server.subscribe(&format!("session={}", token)).await;
+    let mut spectator = server.subscribe("mode=spectator&hud=true").await;
+    server.step(1).await;
+
+    spectator.next_element_containing(r#"id="hud-players""#).await;
+    events.next_signal("tick").await;
+    assert!(!events.recorded().iter().any(|e| e.elements().is_some_and(|html| html.contains("hud-"))));
+    // Without a player there's no score to show
+    assert!(!spectator.recorded().iter().any(|e| e.elements().is_some_and(|html| html.contains("hud-score"))));
+}
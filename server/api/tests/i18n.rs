@@ -0,0 +1,58 @@
+mod harness;
+
+use std::time::Duration;
+use game_core::Language;
+use harness::TestServer;
+use serde_json::json;
+
+#[tokio::test]
+async fn command_replies_follow_the_chosen_language() {
+    let server = TestServer::start().await;
+    let player_id = server.join().await;
+
+    let reply = server.chat(player_id, "/lang es").await.text().await.unwrap();
+    assert!(reply.contains("ahora están en español"), "unexpected reply: {}", reply);
+
+    let help = server.chat(player_id, "/help").await.text().await.unwrap();
+    assert!(help.contains("Muestra esta ayuda"), "help not translated: {}", help);
+
+    let unknown = server.chat(player_id, "/dance").await.text().await.unwrap();
+    assert!(unknown.contains("Comando desconocido /dance"), "unexpected reply: {}", unknown);
+}
+
+#[tokio::test]
+async fn init_sets_the_language_of_rejections() {
+    let server = TestServer::start().await;
+    let response = server.post("/api/player/init", json!({ "language": "fr-CA" })).await;
+    assert_eq!(response.status(), 200);
+    let body: serde_json::Value = response.json().await.unwrap();
+    let player_id = body["player_id"].as_str().unwrap().parse().unwrap();
+
+    let reply = server.chat(player_id, "/nick !!").await.text().await.unwrap();
+    assert!(reply.contains("Impossible de changer de nom"), "unexpected reply: {}", reply);
+}
+
+#[tokio::test]
+async fn join_and_leave_notices_are_translated_per_stream() {
+    let server = TestServer::start().await;
+    let mut english = server.subscribe("").await;
+    let mut spanish = server.subscribe("lang=es").await;
+
+    let player_id = server.join().await;
+    english.next_element_containing("joined the game").await;
+    spanish.next_element_containing("se unió a la partida").await;
+
+    let idle_timeout = Duration::from_secs(server.app_state.game_config.idle_timeout);
+    server.advance_clock(idle_timeout * 2);
+    assert_eq!(server.expire_idle().await, 1);
+    english.next_element_containing("left the game").await;
+    spanish.next_element_containing("salió de la partida").await;
+    assert!(!server.app_state.game_state.read().await.players.contains_key(&player_id));
+}
+
+#[test]
+fn accept_language_picks_the_most_preferred_supported_language() {
+    assert_eq!(Language::from_accept_language("de-DE, fr;q=0.8, en;q=0.5"), Some(Language::Fr));
+    assert_eq!(Language::from_accept_language("es-MX,es;q=0.9"), Some(Language::Es));
+    assert_eq!(Language::from_accept_language("ja, zh;q=0.5"), None);
+}
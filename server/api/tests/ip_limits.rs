@@ -0,0 +1,69 @@
+mod harness;
+
+use std::time::Duration;
+use game_core::config::ProxyConfig;
+use harness::{test_config, TestServer};
+use serde_json::{json, Value};
+
+/// Connections and players counted against each IP, as the admin API lists them
+async fn ip_usage(server: &TestServer) -> Vec<Value> {
+    server.admin_get("/api/admin/ips").await.json().await.unwrap()
+}
+
+#[tokio::test]
+async fn streams_over_the_per_ip_limit_are_refused_until_one_closes() {
+    let mut config = test_config();
+    config.limits.max_connections_per_ip = 1;
+    let server = TestServer::with_config(config).await;
+
+    let first = server.subscribe("").await;
+    assert_eq!(server.get("/events").await.status(), 429);
+
+    drop(first);
+    for _ in 0..100 {
+        if ip_usage(&server).await.is_empty() {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    assert!(ip_usage(&server).await.is_empty(), "closing the stream frees its slot");
+    server.subscribe("").await;
+}
+
+#[tokio::test]
+async fn players_over_the_per_ip_limit_are_refused_until_one_leaves() {
+    let mut config = test_config();
+    config.limits.max_players_per_ip = 1;
+    let server = TestServer::with_config(config).await;
+    let join = || server.post("/api/player/init", json!({ "player_id": uuid::Uuid::new_v4() }));
+
+    let first = server.join().await;
+    assert_eq!(join().await.status(), 429);
+    let usage = ip_usage(&server).await;
+    assert_eq!(usage[0]["players"], 1);
+
+    server.app_state.game_state.write().await.remove_player(&first);
+    assert_eq!(join().await.status(), 200);
+}
+
+#[tokio::test]
+async fn limits_count_the_client_a_trusted_proxy_forwarded() {
+    let mut config = test_config();
+    config.proxy = ProxyConfig {
+        trusted_proxies: vec!["127.0.0.1/32".to_string()],
+        ..ProxyConfig::default()
+    };
+    let server = TestServer::with_config(config).await;
+    let stream = reqwest::Client::new()
+        .get(server.url("/events"))
+        .header("x-forwarded-for", "198.51.100.1, 203.0.113.5")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(stream.status(), 200);
+
+    let usage = ip_usage(&server).await;
+    assert_eq!(usage.len(), 1);
+    assert_eq!(usage[0]["ip"], "203.0.113.5");
+    assert_eq!(usage[0]["connections"], 1);
+}
@@ -0,0 +1,38 @@
+mod harness;
+
+use game_core::DamageSource;
+use harness::TestServer;
+
+#[tokio::test]
+async fn only_the_victims_stream_gets_the_kill_cam() {
+    let mut server = TestServer::start().await;
+    let (killer, killer_token) = server.join_session().await;
+    let (victim, victim_token) = server.join_session().await;
+    let mut killer_events = server.subscribe(&format!("session={}", killer_token)).await;
+    let mut victim_events = server.subscribe(&format!("session={}", victim_token)).await;
+    let mut impostor_events = server.subscribe(&format!("player_id={}", victim)).await;
+    server.step(30).await;
+
+    {
+        let mut game_state = server.app_state.game_state.write().await;
+        game_state.players.get_mut(&victim).unwrap().invulnerable_secs = 0.0;
+        game_state.damage_player(&victim, u32::MAX, DamageSource::Stomp { by: killer }, None);
+    }
+    server.step(1).await;
+
+    let kill_cam = victim_events.next_signal("killCam").await;
+    assert_eq!(kill_cam["killer"], killer.to_string());
+    assert_eq!(kill_cam["cause"], "stomped");
+    assert!(!kill_cam["killer_frames"].as_array().unwrap().is_empty());
+    assert!(!kill_cam["victim_frames"].as_array().unwrap().is_empty());
+
+    // The killer's stream, and one naming the victim without their session, carry on without it
+    killer_events.next_signal("lifeEvents").await;
+    impostor_events.next_signal("lifeEvents").await;
+    server.step(1).await;
+    killer_events.next_signal("tick").await;
+    impostor_events.next_signal("tick").await;
+    for events in [&killer_events, &impostor_events] {
+        assert!(events.recorded().iter().all(|e| e.signals().is_none_or(|s| s.get("killCam").is_none())));
+    }
+}
@@ -0,0 +1,99 @@
+mod harness;
+
+use game_core::config::LadderConfig;
+use game_core::GameConfig;
+use harness::TestServer;
+
+const TICKS_PER_SEC: u32 = 60;
+
+/// A ladder from the ground up to y = -4 around x = 0, where players join
+fn ladder_config() -> GameConfig {
+    let defaults = harness::test_config();
+    GameConfig {
+        ladders: vec![LadderConfig {
+            id: "ladder".to_string(),
+            x_start: -0.5,
+            x_end: 0.5,
+            y_bottom: defaults.physics.ground_y,
+            y_top: -4.0,
+            climb_speed: 6.0,
+            color: "#A0522D".to_string(),
+        }],
+        ..defaults
+    }
+}
+
+async fn player_y(server: &TestServer, player_id: uuid::Uuid) -> f32 {
+    server.app_state.game_state.read().await.players[&player_id].y
+}
+
+#[tokio::test]
+async fn climbing_moves_vertically_without_gravity() {
+    let mut server = TestServer::with_config(ladder_config()).await;
+    let mut events = server.subscribe("").await;
+    let player_id = server.join().await;
+    let start_y = player_y(&server, player_id).await;
+
+    server.command(player_id, "MoveUp").await;
+    server.step(TICKS_PER_SEC / 2).await;
+    let climbed_y = player_y(&server, player_id).await;
+    assert!((climbed_y - (start_y + 3.0)).abs() < 0.2, "climbed to {} from {}", climbed_y, start_y);
+
+    let players = events.next_signal("gameState").await;
+    assert_eq!(players[0]["ground_state"]["type"], "Climbing");
+    assert_eq!(players[0]["ground_state"]["ladder_id"], 0);
+
+    // Holding still on the ladder doesn't fall
+    server.command(player_id, "Stop").await;
+    server.step(TICKS_PER_SEC).await;
+    assert!((player_y(&server, player_id).await - climbed_y).abs() < 0.2);
+
+    server.command(player_id, "MoveDown").await;
+    server.step(TICKS_PER_SEC).await;
+    let game_state = server.app_state.game_state.read().await;
+    let player = &game_state.players[&player_id];
+    assert!(!player.ground_state.is_climbing(), "still climbing at the bottom");
+    assert!((player.y - start_y).abs() < 0.01);
+}
+
+#[tokio::test]
+async fn climbing_needs_a_ladder() {
+    let mut server = TestServer::with_config(ladder_config()).await;
+    let player_id = server.join().await;
+    {
+        let mut game_state = server.app_state.game_state.write().await;
+        game_state.players.get_mut(&player_id).unwrap().x = 5.0;
+    }
+    let start_y = player_y(&server, player_id).await;
+
+    server.command(player_id, "MoveUp").await;
+    server.step(TICKS_PER_SEC / 2).await;
+    let game_state = server.app_state.game_state.read().await;
+    assert!((game_state.players[&player_id].y - start_y).abs() < 0.01);
+    assert!(!game_state.players[&player_id].ground_state.is_climbing());
+}
+
+#[tokio::test]
+async fn leaving_the_top_of_the_ladder_resumes_gravity() {
+    let mut server = TestServer::with_config(ladder_config()).await;
+    let player_id = server.join().await;
+    let start_y = player_y(&server, player_id).await;
+
+    // The ladder is 6 tall; two seconds of climbing carries the player off the top
+    server.command(player_id, "MoveUp").await;
+    let mut left_ladder = false;
+    for _ in 0..TICKS_PER_SEC * 2 {
+        server.step(1).await;
+        if server.app_state.game_state.read().await.players[&player_id].ground_state.is_flying() {
+            left_ladder = true;
+            break;
+        }
+    }
+    assert!(left_ladder, "player never left the ladder");
+
+    server.step(TICKS_PER_SEC * 2).await;
+    let game_state = server.app_state.game_state.read().await;
+    let player = &game_state.players[&player_id];
+    assert!(!player.ground_state.is_climbing());
+    assert!((player.y - start_y).abs() < 0.01, "player didn't fall back down: {}", player.y);
+}
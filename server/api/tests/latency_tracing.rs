@@ -0,0 +1,90 @@
+mod harness;
+
+use game_core::config::LatencyTracingConfig;
+use game_core::GameConfig;
+use harness::TestServer;
+use serde_json::{json, Value};
+
+fn tracing(sample_every: u32) -> GameConfig {
+    GameConfig {
+        latency_tracing: LatencyTracingConfig {
+            enabled: true,
+            sample_every,
+            keep: 8,
+        },
+        ..harness::test_config()
+    }
+}
+
+async fn traced_command(server: &TestServer, player_id: uuid::Uuid, trace_id: Option<&str>) -> reqwest::Response {
+    let request = reqwest::Client::new()
+        .post(server.url("/api/player/command"))
+        .json(&json!({ "player_id": player_id, "command": { "type": "Jump" }, "session_token": server.token(player_id) }));
+    let request = match trace_id {
+        Some(id) => request.header("X-Trace-Id", id),
+        None => request,
+    };
+    request.send().await.unwrap()
+}
+
+async fn latency(server: &TestServer) -> Value {
+    let response = server.admin_get("/api/admin/latency").await;
+    assert_eq!(response.status(), 200);
+    response.json().await.unwrap()
+}
+
+#[tokio::test]
+async fn a_traced_command_is_timed_to_the_broadcast_that_shows_it() {
+    let mut server = TestServer::with_config(tracing(1000)).await;
+    let player_id = server.join().await;
+    server.step(1).await;
+
+    let response = traced_command(&server, player_id, Some("jump-1")).await;
+    assert_eq!(response.status(), 200);
+    assert_eq!(response.headers()["x-trace-id"], "jump-1");
+    assert_eq!(latency(&server).await["recent"], json!([]));
+
+    server.step(1).await;
+    let body = latency(&server).await;
+    let trace = &body["recent"][0];
+    assert_eq!(trace["id"], "jump-1");
+    assert_eq!(trace["player_id"], player_id.to_string());
+    let stage = |name: &str| trace[name].as_u64().unwrap();
+    assert!(stage("received_us") <= stage("queued_us"));
+    assert!(stage("queued_us") < stage("applied_us"));
+    assert!(stage("applied_us") <= stage("broadcast_us"));
+    assert_eq!(stage("broadcast_tick"), stage("applied_tick") + 1);
+    assert_eq!(body["summary"]["count"], 1);
+    assert_eq!(body["summary"]["max_us"], stage("broadcast_us") - stage("received_us"));
+}
+
+#[tokio::test]
+async fn untagged_commands_are_sampled() {
+    let mut server = TestServer::with_config(tracing(2)).await;
+    let player_id = server.join().await;
+    for _ in 0..4 {
+        traced_command(&server, player_id, None).await;
+    }
+    // Ids that can't be logged safely are swapped for generated ones, and don't get a
+    // command traced that sampling would skip
+    let response = traced_command(&server, player_id, Some("not a valid id!")).await;
+    let generated = response.headers()["x-trace-id"].to_str().unwrap().to_string();
+    assert_ne!(generated, "not a valid id!");
+    let response = traced_command(&server, player_id, Some("not a valid id!")).await;
+    assert!(response.headers().get("x-trace-id").is_none());
+    server.step(1).await;
+
+    let recent = latency(&server).await["recent"].as_array().unwrap().clone();
+    assert_eq!(recent.len(), 3);
+    assert_eq!(recent[2]["id"], generated);
+}
+
+#[tokio::test]
+async fn nothing_is_traced_when_disabled() {
+    let mut server = TestServer::start().await;
+    let player_id = server.join().await;
+    let response = traced_command(&server, player_id, Some("jump-1")).await;
+    assert!(response.headers().get("x-trace-id").is_none());
+    server.step(1).await;
+    assert_eq!(latency(&server).await["summary"]["count"], 0);
+}
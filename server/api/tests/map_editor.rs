@@ -0,0 +1,92 @@
+mod harness;
+
+use harness::{TestServer, ADMIN_TOKEN};
+use serde_json::{json, Value};
+
+const TICKS_PER_SEC: u32 = 60;
+
+async fn admin_delete(server: &TestServer, path: &str) -> reqwest::Response {
+    reqwest::Client::new()
+        .delete(server.url(path))
+        .bearer_auth(ADMIN_TOKEN)
+        .send()
+        .await
+        .unwrap()
+}
+
+/// Put a player on the ground at x = 0, running right
+async fn run_right(server: &TestServer, player_id: uuid::Uuid) {
+    let mut game_state = server.app_state.game_state.write().await;
+    let player = game_state.players.get_mut(&player_id).unwrap();
+    player.x = 0.0;
+    player.velocity_x = 12.0;
+}
+
+async fn player_x(server: &TestServer, player_id: uuid::Uuid) -> f32 {
+    server.app_state.game_state.read().await.players[&player_id].x
+}
+
+#[tokio::test]
+async fn walls_added_moved_and_deleted_at_runtime_change_collision() {
+    let mut server = TestServer::start().await;
+    let ground_y = server.app_state.game_config.physics.ground_y;
+    let mut events = server.subscribe("").await;
+    let player_id = server.join().await;
+
+    let wall = json!({ "id": "gate", "x": 2.0, "y_bottom": ground_y, "y_top": ground_y + 5.0, "width": 1.0, "color": "#666666" });
+    let reply = server.admin_post("/api/admin/map/walls", wall).await;
+    assert_eq!(reply.status(), 201);
+
+    server.step(1).await;
+    let geometry = events.next_signal("mapGeometry").await;
+    assert!(geometry["walls"].as_array().unwrap().iter().any(|w| w["id"] == "gate"));
+    let config: Value = server.get("/api/config").await.json().await.unwrap();
+    assert!(config["walls"].as_array().unwrap().iter().any(|w| w["id"] == "gate"));
+
+    run_right(&server, player_id).await;
+    server.step(TICKS_PER_SEC).await;
+    let blocked = player_x(&server, player_id).await;
+    assert!(blocked <= 2.0 - 0.75 + 0.01, "player ran through the new wall: {}", blocked);
+
+    // Moved out of the way, then gone
+    let reply = server.admin_post("/api/admin/map/gate/move", json!({ "dx": 20.0 })).await;
+    assert_eq!(reply.status(), 200);
+    let moved: Value = reply.json().await.unwrap();
+    let gate = moved["walls"].as_array().unwrap().iter().find(|w| w["id"] == "gate").unwrap().clone();
+    assert_eq!(gate["x"], 22.0);
+
+    run_right(&server, player_id).await;
+    server.step(TICKS_PER_SEC / 2).await;
+    assert!(player_x(&server, player_id).await > 3.0);
+    let geometry = events.next_signal("mapGeometry").await;
+    assert_eq!(geometry["walls"], moved["walls"]);
+
+    assert_eq!(admin_delete(&server, "/api/admin/map/gate").await.status(), 200);
+    server.step(1).await;
+    let geometry = events.next_signal("mapGeometry").await;
+    assert!(!geometry["walls"].as_array().unwrap().iter().any(|w| w["id"] == "gate"));
+}
+
+#[tokio::test]
+async fn edits_that_break_the_map_are_refused() {
+    let server = TestServer::start().await;
+    let platform = json!({ "id": "shelf", "x_start": 20.0, "x_end": 24.0, "y_top": 1.0, "height": 0.5, "color": "#B34733" });
+    let reply = server.admin_post("/api/admin/map/platforms", platform.clone()).await;
+    assert_eq!(reply.status(), 201);
+
+    assert_eq!(server.admin_post("/api/admin/map/platforms", platform).await.status(), 409);
+    let overlapping = json!({ "id": "shelf_2", "x_start": 22.0, "x_end": 26.0, "y_top": 1.2, "height": 0.5, "color": "#B34733" });
+    let reply = server.admin_post("/api/admin/map/platforms", overlapping).await;
+    assert_eq!(reply.status(), 400);
+    let body: Value = reply.json().await.unwrap();
+    assert_eq!(body["code"], "invalid_geometry");
+    assert_eq!(body["details"]["errors"][0], "platform shelf_2 overlaps platform shelf");
+
+    assert_eq!(admin_delete(&server, "/api/admin/map/nowhere").await.status(), 404);
+
+    let anonymous = server.post("/api/admin/map/shelf/move", json!({ "dy": 1.0 })).await;
+    assert_eq!(anonymous.status(), 401);
+    let config: Value = server.get("/api/config").await.json().await.unwrap();
+    let shelf = config["platforms"].as_array().unwrap().iter().find(|p| p["id"] == "shelf").unwrap().clone();
+    assert_eq!(shelf["y_top"], 1.0);
+}
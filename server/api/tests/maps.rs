@@ -0,0 +1,131 @@
+mod harness;
+
+use game_core::config::{MapConfig, MapGeometry, MapRotationConfig, MatchConfig};
+use game_core::{GameConfig, PlatformConfig};
+use harness::{test_config, TestServer};
+use serde_json::{json, Value};
+
+const TICKS_PER_SEC: u32 = 60;
+
+/// A map with one platform named after it
+fn map(id: &str) -> MapConfig {
+    MapConfig {
+        id: id.to_string(),
+        name: Some(format!("Map {}", id.to_uppercase())),
+        file: None,
+        format: None,
+        geometry: MapGeometry {
+            platforms: vec![PlatformConfig {
+                id: format!("{}_ledge", id),
+                x_start: -2.0,
+                x_end: 2.0,
+                y_top: -5.0,
+                height: 0.5,
+                color: "#B34733".to_string(),
+                surface: Default::default(),
+                restitution: None,
+            }],
+            ..MapGeometry::default()
+        },
+    }
+}
+
+/// One-player matches lasting a second each, starting on map "a"
+async fn rotating(ids: &[&str], rotation: MapRotationConfig) -> TestServer {
+    let maps: Vec<MapConfig> = ids.iter().map(|id| map(id)).collect();
+    let mut config = GameConfig {
+        tick_rate_hz: TICKS_PER_SEC as f32,
+        broadcast_rate_hz: TICKS_PER_SEC as f32,
+        snapshot_rate_hz: TICKS_PER_SEC as f32,
+        matches: MatchConfig {
+            min_players: 1,
+            countdown_secs: 1,
+            duration_secs: 1,
+            results_secs: 1,
+            ..MatchConfig::default()
+        },
+        map_rotation: rotation,
+        ..test_config()
+    };
+    config.set_geometry(maps[0].geometry.clone());
+    config.maps = maps;
+    TestServer::with_config(config).await
+}
+
+/// Countdown, play and results: three phases of a second, plus a tick each for rounding
+async fn play_match(server: &mut TestServer) {
+    server.step(1).await;
+    server.step(3 * (TICKS_PER_SEC + 1)).await;
+}
+
+async fn current_platform(server: &TestServer) -> String {
+    let config: Value = server.get("/api/config").await.json().await.unwrap();
+    config["platforms"][0]["id"].as_str().unwrap().to_string()
+}
+
+#[tokio::test]
+async fn rotation_moves_on_after_the_results_and_respawns_everyone() {
+    let mut server = rotating(&["a", "b"], MapRotationConfig::default()).await;
+    let player_id = server.join().await;
+    let mut events = server.subscribe("").await;
+    assert_eq!(events.recorded()[0].signals().unwrap()["map"]["id"], "a");
+    assert_eq!(current_platform(&server).await, "a_ledge");
+
+    let teleports = server.app_state.game_state.read().await.players[&player_id].teleports;
+    play_match(&mut server).await;
+
+    let map = events.next_signal("map").await;
+    assert_eq!(map, json!({ "id": "b", "name": "Map B" }));
+    events.next_element_containing("Next map: <b>Map B</b>").await;
+    assert_eq!(current_platform(&server).await, "b_ledge");
+    assert!(server.app_state.game_state.read().await.players[&player_id].teleports > teleports);
+
+    // The rotation wraps around
+    play_match(&mut server).await;
+    assert_eq!(events.next_signal("map").await["id"], "a");
+}
+
+#[tokio::test]
+async fn most_voted_map_is_played_next() {
+    let mut server = rotating(&["a", "b", "c"], MapRotationConfig::default()).await;
+    let (alice, token) = server.join_session().await;
+    let bob = server.join().await;
+
+    let vote = json!({ "player_id": alice, "map": "c", "session_token": token });
+    assert_eq!(server.post("/api/vote/map", vote).await.status(), 200);
+    let reply = server.chat(bob, "/map map c").await.text().await.unwrap();
+    assert!(reply.contains("You voted for <b>Map C</b>"), "unexpected reply: {}", reply);
+
+    let maps: Value = server.get("/api/maps").await.json().await.unwrap();
+    assert_eq!(maps["current"]["id"], "a");
+    assert_eq!(maps["maps"][2], json!({ "id": "c", "name": "Map C", "votes": 2 }));
+    let listing = server.chat(bob, "/map").await.text().await.unwrap();
+    assert!(listing.contains("<b>Map C</b> (2)"), "unexpected listing: {}", listing);
+
+    let unknown = json!({ "player_id": alice, "map": "nowhere", "session_token": token });
+    assert_eq!(server.post("/api/vote/map", unknown).await.status(), 400);
+
+    play_match(&mut server).await;
+    assert_eq!(current_platform(&server).await, "c_ledge");
+    // Votes are spent once the map changes
+    let maps: Value = server.get("/api/maps").await.json().await.unwrap();
+    assert_eq!(maps["maps"][2]["votes"], 0);
+}
+
+#[tokio::test]
+async fn maps_stay_for_the_configured_number_of_matches() {
+    let rotation = MapRotationConfig {
+        matches_per_map: 2,
+        voting: false,
+    };
+    let mut server = rotating(&["a", "b"], rotation).await;
+    let player_id = server.join().await;
+
+    let vote = json!({ "player_id": player_id, "map": "b", "session_token": server.token(player_id) });
+    assert_eq!(server.post("/api/vote/map", vote).await.status(), 404);
+
+    play_match(&mut server).await;
+    assert_eq!(current_platform(&server).await, "a_ledge");
+    play_match(&mut server).await;
+    assert_eq!(current_platform(&server).await, "b_ledge");
+}
@@ -0,0 +1,137 @@
+mod harness;
+
+use game_core::config::MatchConfig;
+use game_core::match_history::MatchParticipant;
+use game_core::{MatchQuery, MatchRecord, ScoreSource};
+use harness::{test_config, TestServer};
+
+/// Ticks per second of game time; phase waits step one extra tick to absorb float rounding
+const TICKS_PER_SEC: u32 = 60;
+
+async fn quick_matches() -> TestServer {
+    let config = game_core::GameConfig {
+        tick_rate_hz: TICKS_PER_SEC as f32,
+        broadcast_rate_hz: TICKS_PER_SEC as f32,
+        snapshot_rate_hz: TICKS_PER_SEC as f32,
+        matches: MatchConfig {
+            min_players: 2,
+            countdown_secs: 1,
+            duration_secs: 2,
+            results_secs: 1,
+            ..MatchConfig::default()
+        },
+        ..test_config()
+    };
+    TestServer::with_config(config).await
+}
+
+#[tokio::test]
+async fn matches_run_through_every_phase() {
+    let mut server = quick_matches().await;
+    let mut events = server.subscribe("").await;
+    let initial = events.recorded()[0].signals().unwrap();
+    assert_eq!(initial["matchState"]["phase"], "lobby");
+
+    let winner = server.join().await;
+    server.step(1).await;
+    assert_eq!(server.app_state.game_state.read().await.match_state, game_core::MatchState::Lobby);
+
+    let loser = server.join().await;
+    server.step(1).await;
+    assert_eq!(events.next_signal("matchState").await["phase"], "countdown");
+
+    server.step(TICKS_PER_SEC + 1).await;
+    assert_eq!(events.next_signal("matchState").await["phase"], "playing");
+    {
+        let mut game_state = server.app_state.game_state.write().await;
+        game_state.award_points(&winner, ScoreSource::Tag);
+        game_state.award_points(&loser, ScoreSource::Coin);
+    }
+
+    server.step(2 * TICKS_PER_SEC + 1).await;
+    let ended = events.next_signal("matchState").await;
+    assert_eq!(ended["phase"], "ended");
+    assert_eq!(ended["result"]["winner"], winner.to_string());
+    assert_eq!(ended["result"]["standings"][0]["player_id"], winner.to_string());
+    events.next_element_containing("Match over!").await;
+
+    let history = server.app_state.match_history.read().await.query(&MatchQuery {
+        limit: 10,
+        ..MatchQuery::default()
+    });
+    assert_eq!(history.matches.len(), 1);
+    assert_eq!(history.matches[0].winner, Some(winner));
+
+    // Back to the lobby, and straight into the next countdown since both players stayed
+    server.step(TICKS_PER_SEC + 1).await;
+    assert_eq!(events.next_signal("matchState").await["phase"], "lobby");
+    assert_eq!(events.next_signal("matchState").await["phase"], "countdown");
+}
+
+#[tokio::test]
+async fn countdown_is_cancelled_when_players_leave() {
+    let mut server = quick_matches().await;
+    let mut events = server.subscribe("").await;
+    server.join().await;
+    let leaving = server.join().await;
+    server.step(1).await;
+    assert_eq!(events.next_signal("matchState").await["phase"], "countdown");
+
+    server.app_state.game_state.write().await.remove_player(&leaving);
+    server.step(1).await;
+    assert_eq!(events.next_signal("matchState").await["phase"], "lobby");
+}
+
+#[tokio::test]
+async fn match_history_is_paged_newest_first_and_filtered_by_player_and_mode() {
+    let server = TestServer::start().await;
+    let regular = uuid::Uuid::new_v4();
+    {
+        let mut history = server.app_state.match_history.write().await;
+        for ended_at in 1..=5u64 {
+            let mut participants = vec![MatchParticipant {
+                player_id: uuid::Uuid::new_v4(),
+                player_name: "Drifter".to_string(),
+                score: 10,
+            }];
+            if ended_at % 2 == 1 {
+                participants.push(MatchParticipant {
+                    player_id: regular,
+                    player_name: "Regular".to_string(),
+                    score: 20,
+                });
+            }
+            let record = MatchRecord {
+                id: uuid::Uuid::new_v4(),
+                mode: if ended_at <= 2 { "race" } else { "sandbox" }.to_string(),
+                started_at: ended_at * 100,
+                ended_at: ended_at * 100 + 60,
+                participants,
+                winner: None,
+            };
+            history.record(record).await.unwrap();
+        }
+    }
+    let ended = |page: &serde_json::Value| -> Vec<u64> {
+        page["matches"].as_array().unwrap().iter().map(|m| m["ended_at"].as_u64().unwrap()).collect()
+    };
+
+    let first: serde_json::Value = server.get("/api/matches?limit=2").await.json().await.unwrap();
+    assert_eq!(ended(&first), [560, 460]);
+    assert_eq!(first["next_offset"], 2);
+    let last: serde_json::Value = server.get("/api/matches?limit=2&offset=4").await.json().await.unwrap();
+    assert_eq!(ended(&last), [160]);
+    assert!(last["next_offset"].is_null());
+
+    let played: serde_json::Value = server.get(&format!("/api/matches?player={}", regular)).await.json().await.unwrap();
+    assert_eq!(ended(&played), [560, 360, 160]);
+    let raced: serde_json::Value = server
+        .get(&format!("/api/matches?player={}&mode=race", regular))
+        .await
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(ended(&raced), [160]);
+
+    assert_eq!(server.get("/api/matches?player=someone").await.status(), 400);
+}
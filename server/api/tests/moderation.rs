@@ -0,0 +1,79 @@
+mod harness;
+
+use std::time::Duration;
+use axum::Json;
+use game_core::config::ModerationConfig;
+use game_core::GameConfig;
+use harness::{test_config, TestServer};
+use serde_json::{json, Value};
+
+/// Serve a moderation provider that scores by keyword, returning its endpoint
+async fn provider() -> String {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let app = axum::Router::new().route(
+        "/score",
+        axum::routing::post(|Json(request): Json<Value>| async move {
+            let text = request["text"].as_str().unwrap_or_default();
+            let score = if text.contains("vile") {
+                0.95
+            } else if text.contains("rude") {
+                0.6
+            } else {
+                0.0
+            };
+            Json(json!({ "score": score }))
+        }),
+    );
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+    format!("http://{}/score", addr)
+}
+
+/// Poll the admin flags until there are `count` of them
+async fn flags(server: &TestServer, count: usize) -> Vec<Value> {
+    for _ in 0..100 {
+        let flags: Vec<Value> = server.admin_get("/api/admin/flags").await.json().await.unwrap();
+        if flags.len() >= count {
+            return flags;
+        }
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+    panic!("never got {} flags", count);
+}
+
+#[tokio::test]
+async fn scored_chat_is_flagged_above_one_threshold_and_muted_above_the_other() {
+    let server = TestServer::with_config(GameConfig {
+        moderation: Some(ModerationConfig {
+            endpoint: provider().await,
+            api_key_env: None,
+            flag_threshold: 0.5,
+            mute_threshold: 0.9,
+            mute_secs: 60,
+            timeout_ms: 1000,
+        }),
+        ..test_config()
+    })
+    .await;
+    let player_id = server.join().await;
+
+    // Scoring happens after delivery, so even flagged messages go out
+    assert_eq!(server.chat(player_id, "good game").await.status(), 200);
+    assert_eq!(server.chat(player_id, "that was rude").await.status(), 200);
+    let flagged = flags(&server, 1).await;
+    assert_eq!(flagged.len(), 1);
+    assert_eq!(flagged[0]["player_id"], player_id.to_string());
+    assert_eq!(flagged[0]["kind"], "chat");
+    assert_eq!(flagged[0]["auto_muted"], false);
+    assert_eq!(server.chat(player_id, "still here").await.status(), 200);
+
+    assert_eq!(server.chat(player_id, "something vile").await.status(), 200);
+    let flagged = flags(&server, 2).await;
+    assert_eq!(flagged[1]["text"], "something vile");
+    assert_eq!(flagged[1]["auto_muted"], true);
+    let muted = server.chat(player_id, "hello?").await;
+    assert_eq!(muted.status(), 403);
+    assert!(server.app_state.moderator.mute_remaining(&player_id).await.unwrap() > Duration::from_secs(50));
+}
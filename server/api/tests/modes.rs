@@ -0,0 +1,135 @@
+mod harness;
+
+use game_core::config::{GameModeConfig, MatchConfig, ScorePolicy};
+use game_core::{Block, BuildingConfig, GameConfig};
+use harness::{test_config, TestServer};
+use serde_json::{json, Value};
+
+const TICKS_PER_SEC: u32 = 60;
+
+fn mode(id: &str, score_policy: ScorePolicy, building: Option<bool>) -> GameModeConfig {
+    GameModeConfig {
+        id: id.to_string(),
+        name: Some(format!("Mode {}", id.to_uppercase())),
+        description: String::new(),
+        game_mode: None,
+        score_policy,
+        duration_secs: None,
+        building,
+        projectiles: None,
+        mutators: Vec::new(),
+        slow_motion: None,
+    }
+}
+
+/// One-player matches lasting a second each, with building on and three modes to pick from
+async fn with_modes() -> TestServer {
+    TestServer::with_config(GameConfig {
+        tick_rate_hz: TICKS_PER_SEC as f32,
+        broadcast_rate_hz: TICKS_PER_SEC as f32,
+        snapshot_rate_hz: TICKS_PER_SEC as f32,
+        matches: MatchConfig {
+            min_players: 1,
+            countdown_secs: 1,
+            duration_secs: 1,
+            results_secs: 1,
+            mode: "standard".to_string(),
+            ..MatchConfig::default()
+        },
+        building: BuildingConfig {
+            enabled: true,
+            ..BuildingConfig::default()
+        },
+        modes: vec![
+            mode("standard", ScorePolicy::Reset, None),
+            mode("duel", ScorePolicy::Reset, Some(false)),
+            mode("builder", ScorePolicy::Keep, Some(true)),
+        ],
+        ..test_config()
+    })
+    .await
+}
+
+async fn score_and_build(server: &TestServer, player_id: uuid::Uuid) {
+    let mut game_state = server.app_state.game_state.write().await;
+    game_state.players.get_mut(&player_id).unwrap().score = 7;
+    game_state.blocks.insert(Block {
+        cell_x: 3,
+        cell_y: 0,
+        owner: player_id,
+        color: "#FFFFFF".to_string(),
+    });
+}
+
+#[tokio::test]
+async fn switching_in_the_lobby_applies_at_once_and_resets_scores() {
+    let mut server = with_modes().await;
+    let player_id = server.join().await;
+    score_and_build(&server, player_id).await;
+    let mut events = server.subscribe("").await;
+    assert_eq!(events.recorded()[0].signals().unwrap()["mode"]["id"], "standard");
+
+    let reply = server.admin_post("/api/admin/mode", json!({ "mode": "duel" })).await;
+    assert_eq!(reply.status(), 200);
+    let body: Value = reply.json().await.unwrap();
+    assert_eq!(body["mode"]["id"], "duel");
+    assert_eq!(body["mode"]["building"], false);
+
+    server.step(1).await;
+    let mode = events.next_signal("mode").await;
+    assert_eq!(mode["name"], "Mode DUEL");
+    assert_eq!(mode["score_policy"], "reset");
+    events.next_element_containing("Game mode is now <b>Mode DUEL</b>").await;
+    // Clients read the room's settings, not the ones the server started with
+    let config: Value = server.get("/api/config").await.json().await.unwrap();
+    assert_eq!(config["building"]["enabled"], false);
+
+    let game_state = server.app_state.game_state.read().await;
+    assert_eq!(game_state.players[&player_id].score, 0);
+    assert!(game_state.blocks.blocks.is_empty());
+    assert!(!game_state.world.config().building.enabled);
+}
+
+#[tokio::test]
+async fn keep_policy_leaves_scores_and_blocks_alone() {
+    let server = with_modes().await;
+    let player_id = server.join().await;
+    score_and_build(&server, player_id).await;
+
+    let reply = server.admin_post("/api/admin/mode", json!({ "mode": "builder" })).await;
+    assert_eq!(reply.status(), 200);
+
+    let game_state = server.app_state.game_state.read().await;
+    assert_eq!(game_state.players[&player_id].score, 7);
+    assert!(!game_state.blocks.blocks.is_empty());
+}
+
+#[tokio::test]
+async fn switching_mid_round_waits_for_the_round_to_end() {
+    let mut server = with_modes().await;
+    server.join().await;
+    // Through the countdown and into play
+    server.step(TICKS_PER_SEC + 2).await;
+
+    let reply = server.admin_post("/api/admin/mode", json!({ "mode": "duel" })).await;
+    assert_eq!(reply.status(), 202);
+    let modes: Value = server.get("/api/modes").await.json().await.unwrap();
+    assert_eq!(modes["active"]["id"], "standard");
+    assert_eq!(modes["pending"], "duel");
+    assert_eq!(modes["modes"].as_array().unwrap().len(), 3);
+
+    // Play and results
+    server.step(2 * (TICKS_PER_SEC + 1)).await;
+    let modes: Value = server.get("/api/modes").await.json().await.unwrap();
+    assert_eq!(modes["active"]["id"], "duel");
+    assert_eq!(modes["pending"], Value::Null);
+}
+
+#[tokio::test]
+async fn unknown_modes_and_unscoped_callers_are_refused() {
+    let server = with_modes().await;
+    let reply = server.admin_post("/api/admin/mode", json!({ "mode": "capture" })).await;
+    assert_eq!(reply.status(), 404);
+    let anonymous = server.post("/api/admin/mode", json!({ "mode": "duel" })).await;
+    assert_eq!(anonymous.status(), 401);
+}
@@ -0,0 +1,99 @@
+mod harness;
+
+use game_core::config::{GameModeConfig, MatchConfig};
+use game_core::{GameConfig, Mutator};
+use harness::{test_config, TestServer};
+use serde_json::{json, Value};
+
+const TICKS_PER_SEC: u32 = 60;
+
+/// One-player matches lasting a second each, in a mode with no mutators of its own
+async fn one_second_rounds() -> TestServer {
+    TestServer::with_config(GameConfig {
+        tick_rate_hz: TICKS_PER_SEC as f32,
+        broadcast_rate_hz: TICKS_PER_SEC as f32,
+        snapshot_rate_hz: TICKS_PER_SEC as f32,
+        matches: MatchConfig {
+            min_players: 1,
+            countdown_secs: 1,
+            duration_secs: 1,
+            results_secs: 1,
+            mode: "standard".to_string(),
+            ..MatchConfig::default()
+        },
+        modes: Vec::<GameModeConfig>::new(),
+        ..test_config()
+    })
+    .await
+}
+
+#[tokio::test]
+async fn voted_mutators_apply_for_one_round_and_go_out_in_the_manifest() {
+    let mut server = one_second_rounds().await;
+    let base_gravity = server.app_state.game_config.physics.gravity;
+    let player_id = server.join().await;
+    let vote = json!({ "player_id": player_id, "mutator": "Low Gravity", "session_token": server.token(player_id) });
+    assert_eq!(server.post("/api/vote/mutator", vote).await.status(), 200);
+    let listed: Value = server.get("/api/mutators").await.json().await.unwrap();
+    assert_eq!(listed["mutators"][0]["id"], "low_gravity");
+    assert_eq!(listed["mutators"][0]["votes"], 1);
+
+    let mut events = server.subscribe("").await;
+    // Through the countdown and into play
+    server.step(TICKS_PER_SEC + 2).await;
+    let mode = events.next_signal("mode").await;
+    assert_eq!(mode["mutators"], json!(["low_gravity"]));
+    assert_eq!(mode["head_scale"], 1.0);
+    events.next_element_containing("This round's mutators: <b>Low Gravity</b>").await;
+    {
+        let game_state = server.app_state.game_state.read().await;
+        assert_eq!(game_state.active_mutators(), &[Mutator::LowGravity]);
+        assert_eq!(game_state.world.config().physics.gravity, base_gravity * 0.4);
+    }
+
+    // Play and results; the lobby is back to normal and the ballot is empty
+    server.step(2 * (TICKS_PER_SEC + 1)).await;
+    let game_state = server.app_state.game_state.read().await;
+    assert!(game_state.active_mutators().is_empty());
+    assert_eq!(game_state.world.config().physics.gravity, base_gravity);
+    assert!(game_state.mutator_votes().iter().all(|m| m.votes == 0));
+}
+
+#[tokio::test]
+async fn admin_picks_override_votes() {
+    let mut server = one_second_rounds().await;
+    let player_id = server.join().await;
+    let vote = json!({ "player_id": player_id, "mutator": "low_gravity", "session_token": server.token(player_id) });
+    assert_eq!(server.post("/api/vote/mutator", vote).await.status(), 200);
+
+    let reply = server
+        .admin_post("/api/admin/mutators", json!({ "mutators": ["one_hit_ko", "Big Heads"] }))
+        .await;
+    assert_eq!(reply.status(), 202);
+
+    server.step(TICKS_PER_SEC + 2).await;
+    let modes: Value = server.get("/api/modes").await.json().await.unwrap();
+    assert_eq!(modes["active"]["mutators"], json!(["one_hit_ko", "big_heads"]));
+    assert_eq!(modes["active"]["head_scale"], 2.0);
+    let config: Value = server.get("/api/config").await.json().await.unwrap();
+    assert_eq!(config["health"]["max_health"], 1);
+    let game_state = server.app_state.game_state.read().await;
+    assert_eq!(game_state.players[&player_id].health, 1);
+    assert_eq!(
+        game_state.world.config().physics.gravity,
+        server.app_state.game_config.physics.gravity,
+        "votes counted despite the pick"
+    );
+}
+
+#[tokio::test]
+async fn unknown_mutators_and_unscoped_callers_are_refused() {
+    let server = one_second_rounds().await;
+    let player_id = server.join().await;
+    let vote = json!({ "player_id": player_id, "mutator": "zero_g", "session_token": server.token(player_id) });
+    assert_eq!(server.post("/api/vote/mutator", vote).await.status(), 400);
+    let reply = server.admin_post("/api/admin/mutators", json!({ "mutators": ["zero_g"] })).await;
+    assert_eq!(reply.status(), 400);
+    let anonymous = server.post("/api/admin/mutators", json!({ "mutators": [] })).await;
+    assert_eq!(anonymous.status(), 401);
+}
@@ -0,0 +1,161 @@
+mod harness;
+
+use serde_json::{json, Value};
+use game_core::NameReservations;
+use harness::TestServer;
+
+async fn rename(server: &TestServer, player_id: uuid::Uuid, name: &str) -> String {
+    let request = json!({ "player_id": player_id, "name": name, "session_token": server.token(player_id) });
+    let response = server.post("/api/player/rename", request).await;
+    assert_eq!(response.status(), 200);
+    response.json::<Value>().await.unwrap()["name"].as_str().unwrap().to_string()
+}
+
+async fn reserve(server: &TestServer, token: &str) -> reqwest::Response {
+    server.post("/api/player/name/reservation", json!({ "session_token": token })).await
+}
+
+/// Register `name` for the player, making them a registered player
+async fn register(server: &TestServer, player_id: uuid::Uuid, token: &str, name: &str) {
+    let request = json!({ "player_id": player_id, "name": name, "session_token": token });
+    let body: Value = server.post("/api/player/register", request).await.json().await.unwrap();
+    assert_eq!(body["accepted"], true);
+}
+
+/// A registered player named `name` who has reserved it
+async fn owner(server: &TestServer, name: &str) -> (uuid::Uuid, String) {
+    let (player_id, token) = server.join_session().await;
+    register(server, player_id, &token, name).await;
+    let response = reserve(server, &token).await;
+    assert_eq!(response.status(), 200);
+    assert_eq!(response.json::<Value>().await.unwrap()["name"], name);
+    (player_id, token)
+}
+
+#[tokio::test]
+async fn guests_asking_for_a_reserved_name_get_a_suffix() {
+    let server = TestServer::start().await;
+    owner(&server, "Ada").await;
+    let guest = server.join().await;
+    assert_eq!(rename(&server, guest, "Ada").await, "Ada2");
+
+    // Lookalikes are the same name
+    let another = server.join().await;
+    assert_eq!(rename(&server, another, "4DA").await, "4DA2");
+
+    let (registering, token) = server.join_session().await;
+    let request = json!({ "player_id": registering, "name": "ada_", "session_token": token });
+    let response = server.post("/api/player/register", request).await;
+    let body: Value = response.json().await.unwrap();
+    assert_eq!(body["accepted"], true);
+    assert_eq!(body["name"], "ada_2");
+}
+
+#[tokio::test]
+async fn reservations_need_a_registered_session_and_a_free_name() {
+    let server = TestServer::start().await;
+    assert_eq!(reserve(&server, "not-a-token").await.status(), 401);
+    owner(&server, "Grace").await;
+
+    // Guests who only renamed themselves aren't registered
+    let (guest, guest_token) = server.join_session().await;
+    rename(&server, guest, "Lovelace").await;
+    assert_eq!(reserve(&server, &guest_token).await.status(), 403);
+
+    // Someone who got the name some other way still can't reserve it
+    let (rival, rival_token) = server.join_session().await;
+    register(&server, rival, &rival_token, "Hopper").await;
+    server.app_state.game_state.write().await.players.get_mut(&rival).unwrap().name = "Gr4ce".to_string();
+    assert_eq!(reserve(&server, &rival_token).await.status(), 409);
+
+    let (dashes, dashes_token) = server.join_session().await;
+    register(&server, dashes, &dashes_token, "---").await;
+    assert_eq!(reserve(&server, &dashes_token).await.status(), 400);
+}
+
+#[tokio::test]
+async fn reservations_stop_at_the_configured_limit() {
+    let mut config = harness::test_config();
+    config.names.max_reservations = 1;
+    let server = TestServer::with_config(config).await;
+    let (_, token) = owner(&server, "Knuth").await;
+    // Re-reserving your own name doesn't need a free slot
+    assert_eq!(reserve(&server, &token).await.status(), 200);
+
+    let (late, late_token) = server.join_session().await;
+    register(&server, late, &late_token, "Dijkstra").await;
+    assert_eq!(reserve(&server, &late_token).await.status(), 503);
+}
+
+#[tokio::test]
+async fn owners_get_their_name_back_when_they_return() {
+    let server = TestServer::start().await;
+    let (player_id, token) = owner(&server, "Linus").await;
+    server.app_state.game_state.write().await.remove_player(&player_id);
+
+    let response = server.post("/api/player/init", json!({ "player_id": player_id, "session_token": token })).await;
+    assert_eq!(response.status(), 200);
+    assert_eq!(server.app_state.game_state.read().await.players[&player_id].name, "Linus");
+}
+
+#[tokio::test]
+async fn released_names_are_free_again() {
+    let server = TestServer::start().await;
+    let (_, token) = owner(&server, "Turing").await;
+    let release = json!({ "session_token": token });
+    assert_eq!(server.delete("/api/player/name/reservation", release.clone()).await.status(), 204);
+    assert_eq!(server.delete("/api/player/name/reservation", release).await.status(), 404);
+
+    // No longer reserved: the guest is only refused because the owner still uses it
+    let guest = server.join().await;
+    let request = json!({ "player_id": guest, "name": "Turing", "session_token": server.token(guest) });
+    let response = server.post("/api/player/rename", request).await;
+    assert_eq!(response.status(), 409);
+}
+
+#[tokio::test]
+async fn reservations_persist_across_restarts() {
+    let path = std::env::temp_dir().join(format!("name-reservations-{}.json", uuid::Uuid::new_v4()));
+    let player_id = uuid::Uuid::new_v4();
+
+    let mut reservations = NameReservations::load_async(&path).await.unwrap();
+    reservations.reserve(player_id, "Hopper").await.unwrap();
+
+    let reloaded = NameReservations::load_async(&path).await.unwrap();
+    assert_eq!(reloaded.owner("h0pper"), Some(player_id));
+    // Suffixes shorten the name to fit the length limit
+    let guest = uuid::Uuid::new_v4();
+    assert_eq!(reloaded.usable_name(&guest, "Hopper", 6, |_| false), "Hoppe2");
+    assert_eq!(reloaded.usable_name(&guest, "Hopper", 16, |name| name == "Hopper2"), "Hopper3");
+    let _ = std::fs::remove_file(&path);
+}
+
+#[tokio::test]
+async fn refused_registrations_keep_the_generated_name_and_say_why() {
+    let server = TestServer::start().await;
+    let (taker, token) = server.join_session().await;
+    register(&server, taker, &token, "Nova").await;
+
+    let (player_id, token) = server.join_session().await;
+    let generated = server.app_state.game_state.read().await.players[&player_id].name.clone();
+    let attempt = |name: &str| {
+        let request = json!({ "player_id": player_id, "name": name, "session_token": token });
+        server.post("/api/player/register", request)
+    };
+    for (name, reason) in [
+        ("Jo", "name is too short"),
+        ("TheLongestNameInTown", "name is too long"),
+        ("ServerBot", "name is not allowed"),
+        ("nova", "name is already taken"),
+    ] {
+        let body: Value = attempt(name).await.json().await.unwrap();
+        assert_eq!(body["accepted"], false, "{}", name);
+        assert_eq!(body["reason"], reason, "{}", name);
+        assert_eq!(body["name"], generated.as_str());
+    }
+    assert!(!server.app_state.game_state.read().await.players[&player_id].registered);
+
+    let body: Value = attempt("  Nova Two ").await.json().await.unwrap();
+    assert_eq!((body["accepted"].as_bool(), body["name"].as_str()), (Some(true), Some("Nova Two")));
+    assert!(server.app_state.game_state.read().await.players[&player_id].registered);
+}
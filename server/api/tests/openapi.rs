@@ -0,0 +1,62 @@
+mod harness;
+
+use game_core::{HeldKey, PlayerCommand};
+use harness::TestServer;
+use serde_json::Value;
+
+#[tokio::test]
+async fn spec_documents_the_player_chat_config_and_admin_routes() {
+    let server = TestServer::start().await;
+    let spec: Value = server.get("/api/docs/openapi.json").await.json().await.unwrap();
+    assert!(spec["openapi"].as_str().unwrap().starts_with("3."));
+
+    let paths = &spec["paths"];
+    let command = &paths["/api/player/command"]["post"];
+    assert_eq!(command["requestBody"]["content"]["application/json"]["schema"]["$ref"], "#/components/schemas/CommandRequest");
+    assert!(command["parameters"].as_array().unwrap().iter().any(|p| p["name"] == "X-Trace-Id"));
+    assert!(paths["/api/chat"]["post"]["requestBody"].is_object());
+    assert!(paths["/api/player/init"]["post"]["requestBody"].is_object());
+    assert!(paths["/api/config"]["get"]["responses"]["200"].is_object());
+    assert!(paths["/events"]["get"]["parameters"].as_array().unwrap().iter().any(|p| p["name"] == "protocol_version"));
+
+    // Admin routes need a bearer token; public ones don't
+    let kick = &paths["/api/admin/kick"]["post"];
+    assert_eq!(kick["security"][0]["bearerAuth"], serde_json::json!([]));
+    assert_eq!(kick["x-token-scope"], "moderation");
+    assert_eq!(paths["/api/admin/tokens"]["post"]["x-token-scope"], "admin");
+    assert!(command.get("security").is_none());
+    assert_eq!(spec["components"]["securitySchemes"]["bearerAuth"]["scheme"], "bearer");
+}
+
+#[tokio::test]
+async fn command_schema_matches_how_commands_serialize() {
+    let spec = api::openapi::spec();
+    let variants = spec["components"]["schemas"]["PlayerCommand"]["oneOf"].as_array().unwrap();
+    let commands = [
+        PlayerCommand::Jump,
+        PlayerCommand::Shoot { dir_x: 1.0, dir_y: 0.0 },
+        PlayerCommand::KeyDown { key: HeldKey::Left },
+        PlayerCommand::Move { axis: 0.5 },
+    ];
+    for command in commands {
+        let json = serde_json::to_value(&command).unwrap();
+        let variant = variants
+            .iter()
+            .find(|v| v["title"] == json["type"])
+            .unwrap_or_else(|| panic!("{} is not documented", json["type"]));
+        let mut documented: Vec<&str> = variant["required"].as_array().unwrap().iter().map(|f| f.as_str().unwrap()).collect();
+        let mut sent: Vec<&str> = json.as_object().unwrap().keys().map(String::as_str).collect();
+        documented.sort();
+        sent.sort();
+        assert_eq!(documented, sent);
+    }
+}
+
+#[tokio::test]
+async fn docs_page_loads_swagger_ui_with_the_spec() {
+    let server = TestServer::start().await;
+    let response = server.get("/api/docs").await;
+    assert!(response.headers()["content-type"].to_str().unwrap().starts_with("text/html"));
+    let html = response.text().await.unwrap();
+    assert!(html.contains("SwaggerUIBundle") && html.contains("docs/openapi.json"), "{}", html);
+}
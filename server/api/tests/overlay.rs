@@ -0,0 +1,64 @@
+mod harness;
+
+use game_core::config::OverlayConfig;
+use game_core::GameConfig;
+use harness::{test_config, TestServer};
+
+const TICKS_PER_SEC: u32 = 60;
+
+async fn set_score(server: &TestServer, player_id: uuid::Uuid, score: u64) {
+    server.app_state.game_state.write().await.players.get_mut(&player_id).unwrap().score = score;
+}
+
+#[tokio::test]
+async fn overlay_streams_leaderboard_apm_and_lead_changes_at_its_own_rate() {
+    let mut server = TestServer::with_config(GameConfig {
+        tick_rate_hz: TICKS_PER_SEC as f32,
+        broadcast_rate_hz: TICKS_PER_SEC as f32,
+        overlay: OverlayConfig {
+            rate_hz: 2.0,
+            density_columns: 4,
+        },
+        ..test_config()
+    })
+    .await;
+    let alice = server.join().await;
+    let bob = server.join().await;
+    let mut overlay = server.stream("/overlay/events").await;
+    overlay.next_element_containing("Lead changes: <b>0</b>").await;
+
+    for command in ["MoveRight", "Jump", "Stop"] {
+        assert_eq!(server.command(alice, command).await.status(), 200);
+    }
+    set_score(&server, alice, 10).await;
+    server.step(1).await;
+    set_score(&server, bob, 20).await;
+    server.step(TICKS_PER_SEC).await;
+
+    let html = overlay.next_element_containing("Lead changes: <b>1</b>").await;
+    let (alice_name, bob_name) = {
+        let game_state = server.app_state.game_state.read().await;
+        (game_state.players[&alice].name.clone(), game_state.players[&bob].name.clone())
+    };
+    assert!(html.contains(&format!("Leader: <b>{}</b>", bob_name)), "unexpected overlay: {}", html);
+    let alice_row = format!(
+        r#"{}</td><td align="right">10</td><td align="right" style="padding-left: 12px">3</td>"#,
+        alice_name
+    );
+    assert!(html.contains(&alice_row), "unexpected overlay: {}", html);
+    // Bob leads, so he's listed first
+    assert!(html.find(&bob_name) < html.find(&alice_name));
+    assert_eq!(html.matches(r#"<div title=""#).count(), 4);
+
+    // Two renders a second, not one per tick
+    let renders = overlay.recorded().iter().filter(|e| e.elements().is_some()).count();
+    assert!(renders <= 4, "overlay rendered {} times in a second", renders);
+}
+
+#[tokio::test]
+async fn overlay_page_opens_the_stream() {
+    let server = TestServer::start().await;
+    let page = server.get("/overlay").await.text().await.unwrap();
+    assert!(page.contains(r#"data-on-load="@get('overlay/events')""#));
+    assert!(page.contains(r#"<div id="overlay"></div>"#));
+}
@@ -0,0 +1,42 @@
+mod harness;
+
+use game_core::player_color::{get_player_color, player_color};
+use game_core::Palette;
+use harness::TestServer;
+
+#[tokio::test]
+async fn color_blind_streams_get_rewritten_player_and_team_colors() {
+    let mut server = TestServer::start().await;
+    let mut standard = server.subscribe("").await;
+    let mut color_blind = server.subscribe("palette=colorblind").await;
+    let red = server.join().await;
+
+    server.step(1).await;
+    let player = &standard.next_signal("gameState").await[0];
+    assert_eq!(player["team_color"], "#E74C3C");
+    assert!(player.get("color").is_none(), "standard streams leave colors to the client");
+
+    let player = &color_blind.next_signal("gameState").await[0];
+    assert_eq!(player["team_color"], "#D55E00");
+    let expected = player_color(&red, Palette::ColorBlind, &server.app_state.game_config.color_blind_palette);
+    assert_eq!(player["color"], expected.as_str());
+}
+
+#[tokio::test]
+async fn palette_command_recolors_chat_on_the_players_stream() {
+    let server = TestServer::start().await;
+    let viewer = server.join().await;
+    let speaker = server.join().await;
+
+    let reply = server.chat(viewer, "/palette color-blind").await.text().await.unwrap();
+    assert!(reply.contains("color-blind palette"), "unexpected reply: {}", reply);
+    let unknown = server.chat(viewer, "/palette sepia").await.text().await.unwrap();
+    assert!(unknown.contains("Unknown palette"), "unexpected reply: {}", unknown);
+
+    let mut events = server.subscribe(&format!("player_id={}", viewer)).await;
+    server.chat(speaker, "hello").await;
+    let html = events.next_element_containing("hello").await;
+    let expected = player_color(&speaker, Palette::ColorBlind, &server.app_state.game_config.color_blind_palette);
+    assert!(html.contains(&expected), "chat not in the color-blind palette: {}", html);
+    assert!(!html.contains(&get_player_color(&speaker)), "standard color leaked: {}", html);
+}
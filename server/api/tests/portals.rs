@@ -0,0 +1,65 @@
+mod harness;
+
+use game_core::config::PortalConfig;
+use game_core::GameConfig;
+use harness::TestServer;
+
+fn portal(id: &str, x_start: f32, exit: &str, y_bottom: f32) -> PortalConfig {
+    PortalConfig {
+        id: id.to_string(),
+        x_start,
+        x_end: x_start + 2.0,
+        y_bottom,
+        y_top: y_bottom + 3.0,
+        exit: exit.to_string(),
+        drop_velocity: false,
+        rotation_degrees: 0.0,
+        cooldown_secs: 0.5,
+        color: "#AA44FF".to_string(),
+    }
+}
+
+/// A two-way pair on the ground at x = -10 and x = 10
+fn portal_config() -> GameConfig {
+    let mut config = harness::test_config();
+    let ground_y = config.physics.ground_y;
+    config.portals = vec![portal("west", -11.0, "east", ground_y), portal("east", 9.0, "west", ground_y)];
+    config
+}
+
+#[tokio::test]
+async fn stepping_into_a_portal_broadcasts_the_trip() {
+    let mut server = TestServer::with_config(portal_config()).await;
+    let mut events = server.subscribe("").await;
+    let player_id = server.join().await;
+    {
+        let mut game_state = server.app_state.game_state.write().await;
+        let ground_y = game_state.world.config().physics.ground_y;
+        let player = game_state.players.get_mut(&player_id).unwrap();
+        player.x = -10.0;
+        player.y = ground_y + 1.0;
+    }
+
+    server.step(1).await;
+    let teleports = events.next_signal("teleports").await;
+    let teleport = &teleports.as_array().unwrap()[0];
+    assert_eq!(teleport["player_id"], player_id.to_string());
+    assert_eq!(teleport["portal"], "west");
+    assert_eq!(teleport["exit"], "east");
+    assert_eq!(teleport["x"], 10.0);
+
+    // Still standing in the exit, so they aren't sent straight back
+    server.step(60).await;
+    let game_state = server.app_state.game_state.read().await;
+    assert!((game_state.players[&player_id].x - 10.0).abs() < 1.0);
+}
+
+#[tokio::test]
+async fn config_lists_portals_for_rendering() {
+    let server = TestServer::with_config(portal_config()).await;
+    let config = server.get("/api/config").await.json::<serde_json::Value>().await.unwrap();
+    let portals = config["portals"].as_array().unwrap();
+    assert_eq!(portals.len(), 2);
+    assert_eq!(portals[0]["id"], "west");
+    assert_eq!(portals[0]["exit"], "east");
+}
@@ -0,0 +1,120 @@
+mod harness;
+
+use std::time::Duration;
+use harness::TestServer;
+
+#[tokio::test]
+async fn heartbeats_keep_afk_players() {
+    let server = TestServer::start().await;
+    let (player_id, token) = server.join_session().await;
+    let idle_timeout = Duration::from_secs(server.app_state.game_config.idle_timeout);
+
+    for _ in 0..3 {
+        server.advance_clock(idle_timeout / 2);
+        let response = server
+            .post("/api/player/heartbeat", serde_json::json!({ "player_id": player_id, "session_token": token }))
+            .await;
+        assert_eq!(response.status(), 204);
+        assert_eq!(server.expire_idle().await, 0);
+    }
+}
+
+#[tokio::test]
+async fn heartbeats_do_not_create_players() {
+    let server = TestServer::start().await;
+    let gone = uuid::Uuid::new_v4();
+    let response = server
+        .post("/api/player/heartbeat", serde_json::json!({ "player_id": gone, "session_token": server.token(gone) }))
+        .await;
+    assert_eq!(response.status(), 404);
+    assert!(server.app_state.game_state.read().await.players.is_empty());
+}
+
+#[tokio::test]
+async fn connected_players_are_not_idle() {
+    let mut server = TestServer::start().await;
+    let (player_id, token) = server.join_session().await;
+    let idle_timeout = Duration::from_secs(server.app_state.game_config.idle_timeout);
+
+    let events = server.subscribe(&format!("session={}", token)).await;
+    server.advance_clock(idle_timeout * 2);
+    assert_eq!(server.expire_idle().await, 0);
+
+    // Once the stream drops, the long idle time counts again
+    drop(events);
+    server.wait_for_disconnect(player_id).await;
+    assert_eq!(server.expire_idle().await, 1);
+}
+
+#[tokio::test]
+async fn idle_players_show_as_afk_before_they_are_removed() {
+    let mut server = TestServer::start().await;
+    let player_id = server.join().await;
+    let config = &server.app_state.game_config;
+    let afk_after = Duration::from_secs(config.presence.afk_after_secs);
+    let idle_timeout = Duration::from_secs(config.idle_timeout);
+
+    server.advance_clock(afk_after - Duration::from_secs(1));
+    server.step(1).await;
+    assert!(!server.app_state.game_state.read().await.players[&player_id].afk);
+    server.advance_clock(Duration::from_secs(1));
+    server.step(1).await;
+    let player = server.app_state.game_state.read().await.players[&player_id].clone();
+    assert!(player.afk);
+    assert_eq!(serde_json::to_value(&player).unwrap()["afk"], true);
+
+    server.advance_clock(idle_timeout - afk_after);
+    assert_eq!(server.expire_idle().await, 1);
+}
+
+#[tokio::test]
+async fn input_brings_players_back_from_afk_but_heartbeats_do_not() {
+    let mut server = TestServer::start().await;
+    let (player_id, token) = server.join_session().await;
+    server.advance_clock(Duration::from_secs(server.app_state.game_config.presence.afk_after_secs + 1));
+    server.step(1).await;
+
+    let response = server
+        .post("/api/player/heartbeat", serde_json::json!({ "player_id": player_id, "session_token": token }))
+        .await;
+    assert_eq!(response.status(), 204);
+    server.step(1).await;
+    assert!(server.app_state.game_state.read().await.players[&player_id].afk);
+
+    assert_eq!(server.command(player_id, "Jump").await.status(), 200);
+    assert!(!server.app_state.game_state.read().await.players[&player_id].afk);
+    server.step(1).await;
+    assert!(!server.app_state.game_state.read().await.players[&player_id].afk);
+}
+
+#[tokio::test]
+async fn leaving_removes_the_player_on_the_next_tick() {
+    let mut server = TestServer::start().await;
+    let (player_id, token) = server.join_session().await;
+    let name = server.app_state.game_state.read().await.players[&player_id].name.clone();
+    let watcher = server.join().await;
+    let mut events = server.subscribe(&format!("player_id={}", watcher)).await;
+
+    let leave = serde_json::json!({ "session_token": token });
+    assert_eq!(server.post("/api/player/leave", leave.clone()).await.status(), 204);
+    server.step(1).await;
+    assert!(!server.app_state.game_state.read().await.players.contains_key(&player_id));
+    assert_eq!(events.next_signal("playerLeft").await["player_id"], player_id.to_string());
+
+    // The name is free for someone else, and leaving again changes nothing
+    let rename = serde_json::json!({ "player_id": watcher, "name": name, "session_token": server.token(watcher) });
+    assert_eq!(server.post("/api/player/rename", rename).await.status(), 200);
+    assert_eq!(server.post("/api/player/leave", leave).await.status(), 204);
+    server.step(1).await;
+    assert_eq!(server.app_state.game_state.read().await.players.len(), 1);
+}
+
+#[tokio::test]
+async fn leaving_needs_a_valid_session_token() {
+    let mut server = TestServer::start().await;
+    server.join().await;
+    let response = server.post("/api/player/leave", serde_json::json!({ "session_token": "forged" })).await;
+    assert_eq!(response.status(), 401);
+    server.step(1).await;
+    assert_eq!(server.app_state.game_state.read().await.players.len(), 1);
+}
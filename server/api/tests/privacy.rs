@@ -0,0 +1,153 @@
+mod harness;
+
+use api::privacy::{hash_player_id, Tombstone, Tombstones};
+use game_core::match_history::MatchParticipant;
+use game_core::MatchRecord;
+use harness::TestServer;
+use serde_json::{json, Value};
+
+async fn admin_request(server: &TestServer, method: reqwest::Method, path: &str) -> reqwest::Response {
+    reqwest::Client::new()
+        .request(method, server.url(path))
+        .bearer_auth(harness::ADMIN_TOKEN)
+        .send()
+        .await
+        .unwrap()
+}
+
+async fn export(server: &TestServer, player_id: uuid::Uuid) -> Value {
+    let response = admin_request(server, reqwest::Method::GET, &format!("/api/player/{}/export", player_id)).await;
+    assert_eq!(response.status(), 200);
+    response.json().await.unwrap()
+}
+
+/// A finished match between the two players, `player_id` winning
+async fn record_match(server: &TestServer, player_id: uuid::Uuid, other: uuid::Uuid) {
+    let participant = |id, name: &str, score| MatchParticipant {
+        player_id: id,
+        player_name: name.to_string(),
+        score,
+    };
+    let record = MatchRecord {
+        id: uuid::Uuid::new_v4(),
+        mode: "sandbox".to_string(),
+        started_at: 0,
+        ended_at: 60,
+        participants: vec![participant(player_id, "Leaver", 20), participant(other, "Stayer", 10)],
+        winner: Some(player_id),
+    };
+    server.app_state.match_history.write().await.record(record).await.unwrap();
+}
+
+#[tokio::test]
+async fn export_collects_everything_stored_about_a_player() {
+    let server = TestServer::start().await;
+    let (player_id, token) = server.join_session().await;
+    let other = server.join().await;
+
+    let response = server
+        .put("/api/player/settings", json!({ "session_token": token, "settings": { "volume": 3 } }))
+        .await;
+    assert_eq!(response.status(), 204);
+    server.app_state.cosmetics.write().await.reward_challenge(player_id, "jumps_50", 25, &[]);
+    record_match(&server, player_id, other).await;
+    server.chat(player_id, "hello there").await;
+
+    let data = export(&server, player_id).await;
+    assert_eq!(data["player_id"], player_id.to_string());
+    assert_eq!(data["player"]["id"], player_id.to_string());
+    assert_eq!(data["settings"], json!({ "volume": 3 }));
+    assert_eq!(data["cosmetics"]["coins"], 25);
+    assert_eq!(data["matches"].as_array().unwrap().len(), 1);
+    assert_eq!(data["chat"][0]["text"], "hello there");
+    assert!(data["deleted_at"].is_null());
+
+    let anonymous = server.get(&format!("/api/player/{}/export", player_id)).await;
+    assert_eq!(anonymous.status(), 401);
+}
+
+#[tokio::test]
+async fn deletion_erases_the_player_and_anonymizes_shared_records() {
+    let server = TestServer::start().await;
+    let (player_id, token) = server.join_session().await;
+    let other = server.join().await;
+    server
+        .put("/api/player/settings", json!({ "session_token": token, "settings": { "volume": 3 } }))
+        .await;
+    server.app_state.cosmetics.write().await.reward_challenge(player_id, "jumps_50", 25, &[]);
+    record_match(&server, player_id, other).await;
+    server.chat(player_id, "hello there").await;
+
+    let response = admin_request(&server, reqwest::Method::DELETE, &format!("/api/player/{}/data", player_id)).await;
+    assert_eq!(response.status(), 200);
+    let body: Value = response.json().await.unwrap();
+    assert_eq!(
+        body["deleted"],
+        json!({ "player": true, "settings": true, "name_reservation": false, "cosmetics": true, "matches": 1, "chat_messages": 1, "flags": 0 })
+    );
+
+    let data = export(&server, player_id).await;
+    assert!(data["player"].is_null());
+    assert!(data["settings"].is_null());
+    assert_eq!(data["cosmetics"]["coins"], 0);
+    assert_eq!(data["matches"], json!([]));
+    assert_eq!(data["chat"], json!([]));
+    assert!(data["deleted_at"].is_u64());
+
+    // The other player's result still counts, against an anonymous opponent
+    let theirs = export(&server, other).await;
+    let participants = &theirs["matches"][0]["participants"];
+    assert_eq!(participants[0]["player_name"], "[deleted]");
+    assert_eq!(participants[0]["player_id"], uuid::Uuid::nil().to_string());
+    assert_eq!(participants[1]["score"], 10);
+}
+
+#[tokio::test]
+async fn deleted_players_keep_their_mute_when_they_return() {
+    let server = TestServer::start().await;
+    let (player_id, token) = server.join_session().await;
+    let response = server
+        .admin_post("/api/admin/mute", json!({ "player_id": player_id, "duration_secs": 600 }))
+        .await;
+    assert_eq!(response.status(), 200);
+
+    let response = admin_request(&server, reqwest::Method::DELETE, &format!("/api/player/{}/data", player_id)).await;
+    assert_eq!(response.status(), 200);
+
+    // Mutes live in memory; a restart loses them but not the tombstone
+    server.app_state.moderator.state.write().await.mutes.clear();
+    let response = server
+        .post("/api/player/init", json!({ "player_id": player_id, "session_token": token }))
+        .await;
+    assert_eq!(response.status(), 200);
+    let remaining = server.app_state.moderator.mute_remaining(&player_id).await.unwrap();
+    assert!(remaining.as_secs() > 590, "mute shrank to {:?}", remaining);
+}
+
+#[tokio::test]
+async fn tombstones_persist_hashed_ids_across_restarts() {
+    let path = std::env::temp_dir().join(format!("tombstones-{}.json", uuid::Uuid::new_v4()));
+    let player_id = uuid::Uuid::new_v4();
+
+    let mut tombstones = Tombstones::load_async(&path).await.unwrap();
+    tombstones
+        .record(&player_id, Tombstone { deleted_at: 100, muted_until: Some(500) })
+        .await
+        .unwrap();
+    tombstones
+        .record(&player_id, Tombstone { deleted_at: 200, muted_until: None })
+        .await
+        .unwrap();
+
+    let contents = tokio::fs::read_to_string(&path).await.unwrap();
+    assert!(!contents.contains(&player_id.to_string()), "tombstone kept the raw id");
+    assert!(contents.contains(&hash_player_id(&player_id)));
+
+    let reloaded = Tombstones::load_async(&path).await.unwrap();
+    assert_eq!(reloaded.len(), 1);
+    assert_eq!(reloaded.get(&player_id).unwrap().deleted_at, 100);
+    assert_eq!(reloaded.mute_remaining(&player_id, 400).unwrap().as_secs(), 100);
+    assert!(reloaded.mute_remaining(&player_id, 600).is_none());
+
+    let _ = tokio::fs::remove_file(&path).await;
+}
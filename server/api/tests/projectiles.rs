@@ -0,0 +1,103 @@
+mod harness;
+
+use game_core::config::{ProjectileConfig, SpawnPoint};
+use game_core::{GameConfig, ShootError};
+use harness::TestServer;
+use serde_json::json;
+
+const TICKS_PER_SEC: u32 = 60;
+
+/// Shooter spawns at x = -5 and the target at x = 0, both on the ground
+fn duel_config(projectiles: ProjectileConfig) -> GameConfig {
+    GameConfig {
+        spawn_points: vec![SpawnPoint { x: -5.0, y: -9.25 }, SpawnPoint { x: 0.0, y: -9.25 }],
+        projectiles,
+        ..harness::test_config()
+    }
+}
+
+async fn shoot_right(server: &TestServer, player_id: uuid::Uuid) {
+    let response = server
+        .post(
+            "/api/player/command",
+            json!({
+                "player_id": player_id,
+                "command": { "type": "Shoot", "dir_x": 1.0, "dir_y": 0.0 },
+                "session_token": server.token(player_id),
+            }),
+        )
+        .await;
+    assert_eq!(response.status(), 200);
+}
+
+#[tokio::test]
+async fn hits_damage_and_knock_back_the_target() {
+    let mut server = TestServer::with_config(duel_config(ProjectileConfig::default())).await;
+    let mut events = server.subscribe("").await;
+    let shooter = server.join().await;
+    let target = server.join().await;
+
+    shoot_right(&server, shooter).await;
+    server.step(1).await;
+    let projectiles = events.next_signal("projectiles").await;
+    assert_eq!(projectiles[0]["owner"], shooter.to_string());
+
+    server.step(TICKS_PER_SEC / 2).await;
+    let hits = events.next_signal("damage").await;
+    assert_eq!(hits[0]["source"]["type"], "projectile");
+    assert_eq!(hits[0]["source"]["shooter"], shooter.to_string());
+    assert_eq!(hits[0]["target"], target.to_string());
+    assert_eq!(hits[0]["amount"], 25);
+
+    let game_state = server.app_state.game_state.read().await;
+    assert_eq!(game_state.players[&target].health, 75);
+    assert!(game_state.players[&target].x > 0.0, "target was not knocked back");
+    assert_eq!(game_state.players[&shooter].health, 100);
+    assert!(game_state.projectiles.is_empty());
+}
+
+#[tokio::test]
+async fn losing_all_health_kills_and_respawn_restores_it() {
+    let config = duel_config(ProjectileConfig {
+        damage: 100,
+        knockback: 0.0,
+        ..ProjectileConfig::default()
+    });
+    let delay_secs = config.respawn.delay_secs;
+    let mut server = TestServer::with_config(config).await;
+    let mut events = server.subscribe("").await;
+    let shooter = server.join().await;
+    let target = server.join().await;
+
+    shoot_right(&server, shooter).await;
+    server.step(TICKS_PER_SEC / 2).await;
+    let died = events.next_signal("lifeEvents").await;
+    assert_eq!(died[0]["type"], "died");
+    assert_eq!(died[0]["cause"], "shot");
+    assert_eq!(died[0]["player_id"], target.to_string());
+
+    server.step((delay_secs * TICKS_PER_SEC as f32) as u32 + 1).await;
+    let game_state = server.app_state.game_state.read().await;
+    assert!(game_state.players[&target].life.is_alive());
+    assert_eq!(game_state.players[&target].health, 100);
+}
+
+#[tokio::test]
+async fn shots_respect_the_cooldown_and_in_flight_limit() {
+    let config = duel_config(ProjectileConfig {
+        max_per_player: 2,
+        ..ProjectileConfig::default()
+    });
+    let server = TestServer::with_config(config).await;
+    let shooter = server.join().await;
+    let mut game_state = server.app_state.game_state.write().await;
+
+    assert!(game_state.shoot(&shooter, 0.0, 1.0).is_ok());
+    assert_eq!(game_state.shoot(&shooter, 0.0, 1.0).unwrap_err(), ShootError::Cooldown);
+    assert_eq!(game_state.shoot(&shooter, 0.0, 0.0).unwrap_err(), ShootError::InvalidDirection);
+
+    server.advance_clock(std::time::Duration::from_secs(1));
+    assert!(game_state.shoot(&shooter, 0.0, 1.0).is_ok());
+    server.advance_clock(std::time::Duration::from_secs(1));
+    assert_eq!(game_state.shoot(&shooter, 0.0, 1.0).unwrap_err(), ShootError::TooMany);
+}
@@ -0,0 +1,59 @@
+mod harness;
+
+use game_core::config::{CheckpointConfig, GameRules};
+use game_core::GameConfig;
+use harness::TestServer;
+
+/// A one-lap race to a finish line on the ground at x = 10
+fn race_config() -> GameConfig {
+    let mut config = harness::test_config();
+    let ground_y = config.physics.ground_y;
+    config.game_mode = GameRules::RACE;
+    config.matches.enabled = false;
+    config.checkpoints = vec![CheckpointConfig {
+        id: "finish".to_string(),
+        x_start: 9.0,
+        x_end: 11.0,
+        y_bottom: ground_y,
+        y_top: ground_y + 4.0,
+        color: "#FFD700".to_string(),
+    }];
+    config
+}
+
+#[tokio::test]
+async fn finishing_broadcasts_race_standings() {
+    let mut server = TestServer::with_config(race_config()).await;
+    let mut events = server.subscribe("").await;
+    let player_id = server.join().await;
+    server.step(1).await;
+    {
+        let mut game_state = server.app_state.game_state.write().await;
+        let ground_y = game_state.world.config().physics.ground_y;
+        let player = game_state.players.get_mut(&player_id).unwrap();
+        player.x = 10.0;
+        player.y = ground_y + 1.0;
+    }
+
+    server.step(1).await;
+    let race = loop {
+        let race = events.next_signal("race").await;
+        if race["complete"] == true {
+            break race;
+        }
+    };
+    let racer = &race["racers"][0];
+    assert_eq!(racer["player_id"], player_id.to_string());
+    assert_eq!(racer["place"], 1);
+    assert_eq!(racer["laps"], 1);
+}
+
+#[tokio::test]
+async fn config_and_mode_describe_the_course() {
+    let server = TestServer::with_config(race_config()).await;
+    let config = server.get("/api/config").await.json::<serde_json::Value>().await.unwrap();
+    assert_eq!(config["checkpoints"][0]["id"], "finish");
+    assert_eq!(config["race"]["laps"], 1);
+    let game_state = server.app_state.game_state.read().await;
+    assert_eq!(game_state.mode().game_mode, GameRules::RACE);
+}
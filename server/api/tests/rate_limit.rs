@@ -0,0 +1,90 @@
+mod harness;
+
+use std::time::Duration;
+use game_core::config::{RateLimit, RequestRateConfig};
+use harness::{test_config, TestServer};
+
+async fn limited(per_ip: RateLimit, per_player: RateLimit) -> TestServer {
+    let mut config = test_config();
+    config.limits.request_rate = RequestRateConfig {
+        enabled: true,
+        per_ip,
+        per_player,
+    };
+    TestServer::with_config(config).await
+}
+
+const GENEROUS: RateLimit = RateLimit { burst: 1000, per_sec: 1000.0 };
+
+#[tokio::test]
+async fn a_player_past_their_burst_gets_429_until_tokens_refill() {
+    let server = limited(GENEROUS, RateLimit { burst: 3, per_sec: 0.5 }).await;
+    let player = server.join().await;
+    for _ in 0..3 {
+        assert_eq!(server.command(player, "MoveRight").await.status(), 200);
+    }
+    let refused = server.command(player, "MoveRight").await;
+    assert_eq!(refused.status(), 429);
+    // One token at half a token per second
+    assert_eq!(refused.headers()["retry-after"], "2");
+
+    server.advance_clock(Duration::from_secs(1));
+    assert_eq!(server.command(player, "MoveRight").await.status(), 429);
+    server.advance_clock(Duration::from_secs(1));
+    assert_eq!(server.command(player, "MoveRight").await.status(), 200);
+}
+
+#[tokio::test]
+async fn players_have_separate_buckets_but_share_their_ips() {
+    let server = limited(RateLimit { burst: 4, per_sec: 1.0 }, RateLimit { burst: 2, per_sec: 1.0 }).await;
+    let (first, second) = (server.join().await, server.join().await);
+    assert_eq!(server.command(first, "Jump").await.status(), 200);
+    assert_eq!(server.command(first, "Jump").await.status(), 200);
+    assert_eq!(server.command(first, "Jump").await.status(), 429);
+    // A refused request spends nothing from the IP's bucket
+    assert_eq!(server.command(second, "Jump").await.status(), 200);
+    assert_eq!(server.command(second, "Jump").await.status(), 200);
+    assert_eq!(server.chat(uuid::Uuid::new_v4(), "hello").await.status(), 429);
+}
+
+#[tokio::test]
+async fn requests_naming_someone_else_do_not_drain_their_bucket() {
+    let server = limited(GENEROUS, RateLimit { burst: 2, per_sec: 0.1 }).await;
+    let victim = server.join().await;
+    let spoofed = serde_json::json!({ "player_id": victim, "command": { "type": "Jump" } });
+    for _ in 0..5 {
+        assert_eq!(server.post("/api/player/command", spoofed.clone()).await.status(), 401);
+    }
+    assert_eq!(server.command(victim, "Jump").await.status(), 200);
+    assert_eq!(server.command(victim, "Jump").await.status(), 200);
+}
+
+#[tokio::test]
+async fn chat_is_limited_like_commands() {
+    let server = limited(GENEROUS, RateLimit { burst: 2, per_sec: 1.0 }).await;
+    let player = server.join().await;
+    assert_eq!(server.chat(player, "one").await.status(), 200);
+    assert_eq!(server.command(player, "Jump").await.status(), 200);
+    let refused = server.chat(player, "two").await;
+    assert_eq!(refused.status(), 429);
+    assert_eq!(refused.headers()["retry-after"], "1");
+}
+
+#[tokio::test]
+async fn other_routes_and_disabled_limits_are_not_counted() {
+    let server = limited(RateLimit { burst: 1, per_sec: 0.0 }, GENEROUS).await;
+    for _ in 0..5 {
+        assert_eq!(server.get("/api/config").await.status(), 200);
+    }
+    let player = server.join().await;
+    assert_eq!(server.command(player, "Jump").await.status(), 200);
+    assert_eq!(server.command(player, "Jump").await.status(), 429);
+
+    let mut config = test_config();
+    config.limits.request_rate.enabled = false;
+    let server = TestServer::with_config(config).await;
+    let player = server.join().await;
+    for _ in 0..300 {
+        assert_eq!(server.command(player, "Jump").await.status(), 200);
+    }
+}
@@ -0,0 +1,179 @@
+mod harness;
+
+use std::time::Duration;
+use game_core::config::{MatchConfig, ReplayConfig};
+use game_core::{DamageSource, GameConfig, MatchLog, ScoreSource};
+use harness::{test_config, TestServer};
+use serde_json::Value;
+
+const TICKS_PER_SEC: u32 = 60;
+
+async fn recording_server(dir: &std::path::Path) -> TestServer {
+    TestServer::with_config(GameConfig {
+        matches: MatchConfig {
+            min_players: 2,
+            countdown_secs: 1,
+            duration_secs: 2,
+            results_secs: 1,
+            ..MatchConfig::default()
+        },
+        replays: ReplayConfig {
+            dir: Some(dir.to_string_lossy().into_owned()),
+            ..ReplayConfig::default()
+        },
+        ..test_config()
+    })
+    .await
+}
+
+/// Wait for the one finished match's replay and log to be written, returning its id
+async fn saved_replay(server: &TestServer) -> String {
+    for _ in 0..100 {
+        let listed: Value = server.get("/api/replays").await.json().await.unwrap();
+        if let Some(replay_id) = listed["replays"][0].as_str() {
+            assert_eq!(listed["replays"].as_array().unwrap().len(), 1);
+            if server.get(&format!("/api/replays/{}/log", replay_id)).await.status() == 200 {
+                return replay_id.to_string();
+            }
+        }
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+    panic!("the replay was never saved");
+}
+
+/// Poll a job until the workers finish it
+async fn wait_for_job(server: &TestServer, job_id: &str) -> Value {
+    for _ in 0..100 {
+        let job: Value = server.get(&format!("/api/jobs/{}", job_id)).await.json().await.unwrap();
+        if job["status"] != "queued" && job["status"] != "running" {
+            return job;
+        }
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+    panic!("job {} never finished", job_id);
+}
+
+#[tokio::test]
+async fn finished_matches_are_recorded_and_summarized_in_the_background() {
+    let dir = std::env::temp_dir().join(format!("replays-{}", uuid::Uuid::new_v4()));
+    let mut server = recording_server(&dir).await;
+    let winner = server.join().await;
+    server.join().await;
+    server.step(TICKS_PER_SEC + 2).await;
+    {
+        let mut game_state = server.app_state.game_state.write().await;
+        game_state.award_points(&winner, ScoreSource::Coin);
+        game_state.players.get_mut(&winner).unwrap().velocity_x = 8.0;
+    }
+    server.step(2 * TICKS_PER_SEC + 1).await;
+
+    let replay_id = saved_replay(&server).await;
+    let summary_path = format!("/api/replays/{}/summary", replay_id);
+    assert_eq!(server.get(&summary_path).await.status(), 404);
+
+    let reply = server.post(&summary_path, Value::Null).await;
+    assert_eq!(reply.status(), 202);
+    let job: Value = reply.json().await.unwrap();
+    let job = wait_for_job(&server, job["id"].as_str().unwrap()).await;
+    assert_eq!(job["status"], "done");
+    assert_eq!(job["replay_id"], replay_id);
+
+    let reply = server.get(&summary_path).await;
+    assert_eq!(reply.status(), 200);
+    assert!(reply.headers()["content-disposition"].to_str().unwrap().starts_with("attachment"));
+    let summary: Value = reply.json().await.unwrap();
+    assert_eq!(summary["players"].as_array().unwrap().len(), 2);
+    assert_eq!(summary["players"][0]["id"], winner.to_string());
+    assert!(summary["players"][0]["distance"].as_f64().unwrap() > 0.5);
+    assert!(summary["duration_secs"].as_f64().unwrap() >= 1.5);
+    let highlights = summary["highlights"].as_array().unwrap();
+    assert!(highlights.iter().any(|h| h["kind"] == "took_lead" && h["player"] == summary["players"][0]["name"]));
+
+    let svg = server.get(&format!("/api/replays/{}/trajectory.svg", replay_id)).await;
+    assert_eq!(svg.headers()["content-type"], "image/svg+xml");
+    assert_eq!(svg.text().await.unwrap().matches("<polyline").count(), 2);
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[tokio::test]
+async fn match_logs_can_be_downloaded_and_played_back() {
+    let dir = std::env::temp_dir().join(format!("replays-{}", uuid::Uuid::new_v4()));
+    let mut server = recording_server(&dir).await;
+    let runner = server.join().await;
+    server.join().await;
+    server.step(TICKS_PER_SEC + 2).await;
+    server.command(runner, "Sprint").await;
+    // A command the state ignores, here from a dead player, stays out of the log
+    let dead = server.join().await;
+    {
+        let mut game_state = server.app_state.game_state.write().await;
+        game_state.players.get_mut(&dead).unwrap().invulnerable_secs = 0.0;
+        game_state.damage_player(&dead, u32::MAX, DamageSource::Stomp { by: runner }, None);
+    }
+    server.command(dead, "Jump").await;
+    server.step(1).await;
+    server.app_state.game_state.write().await.players.get_mut(&runner).unwrap().velocity_x = 8.0;
+    server.step(2 * TICKS_PER_SEC).await;
+
+    let replay_id = saved_replay(&server).await;
+    let reply = server.get(&format!("/api/replays/{}/log", replay_id)).await;
+    assert_eq!(reply.headers()["content-type"], "application/octet-stream");
+    let log = MatchLog::decode(&reply.bytes().await.unwrap()).unwrap();
+    assert_eq!(log.header.id.to_string(), replay_id);
+    let commands: Vec<_> = log.commands().collect();
+    assert_eq!(commands.len(), 1);
+    assert_eq!(commands[0].1, &runner);
+    let frames = log.frames();
+    assert!(frames.len() >= 2 * TICKS_PER_SEC as usize / 6);
+    let runner_x = |frame: &game_core::PlaybackFrame| frame.players.iter().find(|p| p.id == runner).unwrap().x;
+    assert!(runner_x(frames.last().unwrap()) > runner_x(&frames[0]));
+
+    // Played back far faster than it was recorded
+    let mut playback = server.stream(&format!("/api/replays/{}/playback?speed=1000", replay_id)).await;
+    let start = playback.next_signal("playback").await;
+    assert_eq!(start["finished"], false);
+    assert_eq!(start["speed"], 8.0);
+    let state = playback.next_signal("gameState").await;
+    assert_eq!(state.as_array().unwrap().len(), 2);
+    let end = playback
+        .next_matching("the end of playback", |e| e.signals().is_some_and(|s| s["playback"]["finished"] == true))
+        .await;
+    let end = &end.signals().unwrap()["playback"];
+    assert!(end["t"].as_f64().unwrap() >= 1.5);
+    assert_eq!(end["replay_id"], replay_id);
+
+    assert_eq!(server.get(&format!("/api/replays/{}/playback?speed=-1", replay_id)).await.status(), 400);
+    let missing = uuid::Uuid::new_v4();
+    assert_eq!(server.get(&format!("/api/replays/{}/log", missing)).await.status(), 404);
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[tokio::test]
+async fn unknown_replays_and_servers_without_recording_say_so() {
+    let dir = std::env::temp_dir().join(format!("replays-{}", uuid::Uuid::new_v4()));
+    let server = recording_server(&dir).await;
+    let missing = format!("/api/replays/{}/summary", uuid::Uuid::new_v4());
+    assert_eq!(server.post(&missing, Value::Null).await.status(), 404);
+    assert_eq!(server.get(&format!("/api/jobs/{}", uuid::Uuid::new_v4())).await.status(), 404);
+
+    // A corrupt file fails its job rather than a worker
+    std::fs::create_dir_all(&dir).unwrap();
+    let broken = uuid::Uuid::new_v4();
+    std::fs::write(dir.join(format!("{}.json", broken)), "{ not json").unwrap();
+    let path = format!("/api/replays/{}/summary", broken);
+    let job: Value = server.post(&path, Value::Null).await.json().await.unwrap();
+    let job = wait_for_job(&server, job["id"].as_str().unwrap()).await;
+    assert_eq!(job["status"], "failed");
+    assert_eq!(server.get(&path).await.status(), 500);
+
+    // A retry within the same second is still the job reported
+    let retry: Value = server.post(&path, Value::Null).await.json().await.unwrap();
+    assert_ne!(retry["id"], job["id"]);
+    wait_for_job(&server, retry["id"].as_str().unwrap()).await;
+    let reported: Value = server.get(&path).await.json().await.unwrap();
+    assert_eq!(reported["id"], retry["id"]);
+    let _ = std::fs::remove_dir_all(&dir);
+
+    let off = TestServer::start().await;
+    assert_eq!(off.get("/api/replays").await.status(), 404);
+}
@@ -0,0 +1,84 @@
+mod harness;
+
+use game_core::config::{MapBounds, RespawnConfig, SpawnPoint};
+use game_core::{GameConfig, LifeState};
+use harness::TestServer;
+
+const TICKS_PER_SEC: u32 = 60;
+
+fn respawn_config() -> GameConfig {
+    GameConfig {
+        spawn_points: vec![SpawnPoint { x: -5.0, y: -9.25 }, SpawnPoint { x: 5.0, y: -9.25 }],
+        respawn: RespawnConfig {
+            kill_y: -50.0,
+            bounds: Some(MapBounds { min_x: -20.0, max_x: 20.0, max_y: 100.0 }),
+            delay_secs: 1.0,
+        },
+        ..harness::test_config()
+    }
+}
+
+async fn teleport(server: &TestServer, player_id: uuid::Uuid, x: f32, y: f32) {
+    let mut game_state = server.app_state.game_state.write().await;
+    let player = game_state.players.get_mut(&player_id).unwrap();
+    player.x = x;
+    player.y = y;
+}
+
+#[tokio::test]
+async fn leaving_the_bounds_kills_and_respawns_after_the_delay() {
+    let mut server = TestServer::with_config(respawn_config()).await;
+    let mut events = server.subscribe("").await;
+    let player_id = server.join().await;
+    assert_eq!(server.app_state.game_state.read().await.players[&player_id].x, -5.0);
+
+    teleport(&server, player_id, 30.0, -9.25).await;
+    server.step(1).await;
+    let died = events.next_signal("lifeEvents").await;
+    assert_eq!(died[0]["type"], "died");
+    assert_eq!(died[0]["cause"], "out_of_bounds");
+    assert_eq!(died[0]["player_id"], player_id.to_string());
+    let players = events.next_signal("gameState").await;
+    assert_eq!(players[0]["life"]["state"], "dead");
+
+    // Dead players can't move
+    server.command(player_id, "MoveRight").await;
+    server.step(1).await;
+    assert_eq!(server.app_state.game_state.read().await.players[&player_id].x, 30.0);
+
+    server.step(TICKS_PER_SEC).await;
+    let respawned = events.next_signal("lifeEvents").await;
+    assert_eq!(respawned[0]["type"], "respawned");
+    assert_eq!(respawned[0]["x"], -5.0);
+    let game_state = server.app_state.game_state.read().await;
+    let player = &game_state.players[&player_id];
+    assert_eq!(player.life, LifeState::Alive);
+    assert_eq!(player.x, -5.0);
+}
+
+#[tokio::test]
+async fn falling_below_kill_y_kills() {
+    // A kill plane above the ground makes the floor deadly
+    let mut config = respawn_config();
+    config.respawn.kill_y = config.physics.ground_y + 2.0;
+    config.spawn_points = vec![SpawnPoint { x: -5.0, y: config.physics.ground_y + 10.0 }];
+    let mut server = TestServer::with_config(config).await;
+    let mut events = server.subscribe("").await;
+    let player_id = server.join().await;
+
+    server.step(TICKS_PER_SEC).await;
+    let died = events.next_signal("lifeEvents").await;
+    assert_eq!(died[0]["cause"], "fell_out");
+    assert_eq!(died[0]["player_id"], player_id.to_string());
+}
+
+#[tokio::test]
+async fn players_spawn_away_from_each_other() {
+    let server = TestServer::with_config(respawn_config()).await;
+    let first = server.join().await;
+    let second = server.join().await;
+
+    let game_state = server.app_state.game_state.read().await;
+    assert_eq!(game_state.players[&first].x, -5.0);
+    assert_eq!(game_state.players[&second].x, 5.0);
+}
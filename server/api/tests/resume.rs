@@ -0,0 +1,35 @@
+mod harness;
+
+use harness::TestServer;
+
+#[tokio::test]
+async fn resumed_streams_keep_their_mutes_and_skip_the_chat_replay() {
+    let server = TestServer::start().await;
+    let loud = server.join().await;
+    let quiet = server.join().await;
+    server.chat(quiet, "before the blip").await;
+
+    let mut first = server.stream(&format!("/events?mute={}", loud)).await;
+    let token = first.next_signal("resumeToken").await.as_str().unwrap().to_string();
+    first.next_element_containing("before the blip").await;
+    drop(first);
+
+    let mut resumed = server.stream(&format!("/events?resume={}", token)).await;
+    let fresh = resumed.next_signal("resumeToken").await;
+    assert_ne!(fresh, token.as_str());
+    server.chat(loud, "still muted").await;
+    server.chat(quiet, "after the blip").await;
+    resumed.next_element_containing("after the blip").await;
+    let chat: Vec<String> = resumed
+        .recorded()
+        .iter()
+        .filter_map(|e| e.elements())
+        .map(|html| html.to_string())
+        .collect();
+    assert!(!chat.iter().any(|html| html.contains("still muted") || html.contains("before the blip")), "{:?}", chat);
+
+    // Tokens are single use; a second reconnect with the same one starts over
+    let mut reused = server.stream(&format!("/events?resume={}", token)).await;
+    reused.next_element_containing("before the blip").await;
+    reused.next_element_containing("still muted").await;
+}
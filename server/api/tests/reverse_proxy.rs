@@ -0,0 +1,171 @@
+mod harness;
+
+use std::net::IpAddr;
+use game_core::config::{CorsConfig, ProxyConfig};
+use harness::{test_config, TestServer};
+
+const ORIGIN: &str = "https://play.example.com";
+
+async fn preflight(server: &TestServer, path: &str, origin: &str) -> reqwest::Response {
+    reqwest::Client::new()
+        .request(reqwest::Method::OPTIONS, server.url(path))
+        .header("origin", origin)
+        .header("access-control-request-method", "POST")
+        .header("access-control-request-headers", "content-type")
+        .send()
+        .await
+        .unwrap()
+}
+
+fn allowed_origin(response: &reqwest::Response) -> Option<&str> {
+    response
+        .headers()
+        .get("access-control-allow-origin")
+        .and_then(|value| value.to_str().ok())
+}
+
+#[tokio::test]
+async fn configured_origins_pass_preflight_and_others_dont() {
+    let server = TestServer::with_config(game_core::GameConfig {
+        cors: CorsConfig {
+            allowed_origins: vec![ORIGIN.to_string()],
+            ..CorsConfig::default()
+        },
+        ..test_config()
+    })
+    .await;
+
+    let response = preflight(&server, "/api/player/init", ORIGIN).await;
+    assert_eq!(response.status(), 200);
+    assert_eq!(allowed_origin(&response), Some(ORIGIN));
+    let allowed_headers = response.headers()["access-control-allow-headers"].to_str().unwrap();
+    assert!(allowed_headers.contains("content-type"));
+
+    let response = preflight(&server, "/api/player/init", "https://elsewhere.example.com").await;
+    assert_eq!(allowed_origin(&response), None);
+
+    let response = reqwest::Client::new()
+        .get(server.url("/api/config"))
+        .header("origin", ORIGIN)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 200);
+    assert_eq!(allowed_origin(&response), Some(ORIGIN));
+}
+
+#[tokio::test]
+async fn without_origins_the_api_stays_same_origin() {
+    let server = TestServer::start().await;
+    let response = reqwest::Client::new()
+        .get(server.url("/api/config"))
+        .header("origin", ORIGIN)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 200);
+    assert_eq!(allowed_origin(&response), None);
+}
+
+#[tokio::test]
+async fn a_base_path_moves_every_route_under_it() {
+    let server = TestServer::with_config(game_core::GameConfig {
+        proxy: ProxyConfig {
+            base_path: Some("/game/".to_string()),
+            ..ProxyConfig::default()
+        },
+        ..test_config()
+    })
+    .await;
+
+    assert_eq!(server.get("/game/health").await.status(), 200);
+    assert_eq!(server.get("/game/api/config").await.status(), 200);
+    let response = server.post("/game/api/player/init", serde_json::json!({ "player_id": uuid::Uuid::new_v4() })).await;
+    assert_eq!(response.status(), 200);
+    assert_eq!(server.get("/health").await.status(), 404);
+    assert_eq!(server.get("/api/config").await.status(), 404);
+}
+
+#[test]
+fn base_paths_are_normalized() {
+    let base = |path: &str| ProxyConfig { base_path: Some(path.to_string()), ..ProxyConfig::default() }.resolve_base_path();
+    assert_eq!(base("/game/"), "/game");
+    assert_eq!(base("game"), "/game");
+    assert_eq!(base("//games//mp/"), "/games/mp");
+    assert_eq!(base("/"), "");
+    assert_eq!(ProxyConfig::default().resolve_base_path(), "");
+}
+
+#[test]
+fn index_html_gets_a_base_tag_and_its_assets_move_under_the_base_path() {
+    let html = r#"<html><head><link rel="icon" href="/rocket.webp"><script src="/assets/index.js"></script><script src="//cdn.example.com/x.js"></script></head></html>"#;
+    let based = api::handlers::with_base(html, "/game");
+    assert!(based.contains(r#"<head>"#));
+    assert!(based.find(r#"<base href="/game/">"#).unwrap() < based.find("<link").unwrap());
+    assert!(based.contains(r#"href="/game/rocket.webp""#));
+    assert!(based.contains(r#"src="/game/assets/index.js""#));
+    assert!(based.contains(r#"src="//cdn.example.com/x.js""#));
+}
+
+#[tokio::test]
+async fn per_ip_limits_count_the_forwarded_client_behind_a_trusted_proxy() {
+    let mut config = test_config();
+    config.limits.max_players_per_ip = 1;
+    config.proxy = ProxyConfig {
+        trusted_proxies: vec!["127.0.0.1/32".to_string()],
+        ..ProxyConfig::default()
+    };
+    let server = TestServer::with_config(config).await;
+    let join = |client_ip: &'static str| {
+        let url = server.url("/api/player/init");
+        async move {
+            reqwest::Client::new()
+                .post(url)
+                .header("x-forwarded-for", format!("{}, 127.0.0.1", client_ip))
+                .json(&serde_json::json!({ "player_id": uuid::Uuid::new_v4() }))
+                .send()
+                .await
+                .unwrap()
+                .status()
+        }
+    };
+
+    assert_eq!(join("203.0.113.1").await, 200);
+    assert_eq!(join("203.0.113.1").await, 429);
+    assert_eq!(join("203.0.113.2").await, 200);
+}
+
+fn resolve(proxy: ProxyConfig, peer: &str, forwarded: &str) -> IpAddr {
+    let mut headers = axum::http::HeaderMap::new();
+    headers.insert("x-forwarded-for", forwarded.parse().unwrap());
+    api::proxy::ClientIpResolver::new(&proxy).resolve(&headers, peer.parse().unwrap())
+}
+
+fn ip(text: &str) -> IpAddr {
+    text.parse().unwrap()
+}
+
+#[test]
+fn forwarded_headers_from_untrusted_peers_are_ignored() {
+    let header_only = ProxyConfig {
+        client_ip_header: Some("X-Forwarded-For".to_string()),
+        ..ProxyConfig::default()
+    };
+    assert_eq!(resolve(header_only, "198.51.100.7:4000", "203.0.113.1"), ip("198.51.100.7"));
+
+    let behind_proxy = ProxyConfig {
+        trusted_proxies: vec!["10.0.0.0/8".to_string()],
+        ..ProxyConfig::default()
+    };
+    assert_eq!(resolve(behind_proxy, "198.51.100.7:4000", "203.0.113.1"), ip("198.51.100.7"));
+}
+
+#[test]
+fn the_client_is_the_nearest_untrusted_hop_behind_trusted_proxies() {
+    let proxy = ProxyConfig {
+        trusted_proxies: vec!["10.0.0.0/8".to_string()],
+        ..ProxyConfig::default()
+    };
+    // The leftmost entry is whatever the client sent; the proxies appended the rest
+    assert_eq!(resolve(proxy, "10.0.0.2:4000", "1.2.3.4, 203.0.113.9, 10.0.0.1"), ip("203.0.113.9"));
+}
@@ -0,0 +1,65 @@
+mod harness;
+
+use game_core::config::RoomConfig;
+use harness::{test_config, TestServer};
+
+async fn small_room() -> TestServer {
+    let config = game_core::GameConfig {
+        room: RoomConfig { max_players: 2, max_waiting: 1 },
+        ..test_config()
+    };
+    TestServer::with_config(config).await
+}
+
+async fn init(server: &TestServer) -> reqwest::Response {
+    server.post("/api/player/init", serde_json::json!({})).await
+}
+
+/// The id the server assigned to a player it admitted
+fn player_id(body: &serde_json::Value) -> uuid::Uuid {
+    body["player_id"].as_str().unwrap().parse().unwrap()
+}
+
+#[tokio::test]
+async fn joiners_beyond_capacity_wait_as_spectators() {
+    let server = small_room().await;
+    server.join().await;
+    server.join().await;
+
+    let response = init(&server).await;
+    assert_eq!(response.status(), 200);
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(body["waiting"], 1);
+    let waiting_id = player_id(&body);
+
+    let game_state = server.app_state.game_state.read().await;
+    assert_eq!(game_state.players.len(), 2);
+    assert!(!game_state.players.contains_key(&waiting_id));
+    drop(game_state);
+
+    // Spectators can't act, and the queue itself has a limit
+    assert_eq!(server.command(waiting_id, "Jump").await.status(), 409);
+    assert_eq!(init(&server).await.status(), 503);
+}
+
+#[tokio::test]
+async fn waiting_spectators_are_promoted_when_a_slot_opens() {
+    let mut server = small_room().await;
+    let first = server.join().await;
+    server.join().await;
+
+    let body: serde_json::Value = init(&server).await.json().await.unwrap();
+    let waiting_id = player_id(&body);
+    let token = body["session_token"].as_str().unwrap().to_string();
+    let mut events = server.subscribe(&format!("session={}", token)).await;
+    server.step(1).await;
+    let waiting = events.next_signal("waitingForSlot").await;
+    assert_eq!(waiting["position"], 1);
+
+    server.app_state.game_state.write().await.remove_player(&first);
+
+    server.step(1).await;
+    assert!(events.next_signal("waitingForSlot").await.is_null());
+    assert!(server.app_state.game_state.read().await.players.contains_key(&waiting_id));
+    assert_eq!(server.command(waiting_id, "Jump").await.status(), 200);
+}
@@ -0,0 +1,59 @@
+mod harness;
+
+use game_core::config::PhysicsSandboxConfig;
+use game_core::GameConfig;
+use harness::{test_config, TestServer, ADMIN_TOKEN};
+use serde_json::{json, Value};
+
+const PHYSICS: &str = "/api/rooms/main/physics";
+
+async fn patch(server: &TestServer, path: &str, token: Option<&str>, body: Value) -> reqwest::Response {
+    let mut request = reqwest::Client::new().patch(server.url(path)).json(&body);
+    if let Some(token) = token {
+        request = request.bearer_auth(token);
+    }
+    request.send().await.unwrap()
+}
+
+#[tokio::test]
+async fn the_room_creator_changes_physics_live_and_clients_see_a_config_version_bump() {
+    let mut server = TestServer::with_config(GameConfig {
+        physics_sandbox: PhysicsSandboxConfig { enabled: true, ..PhysicsSandboxConfig::default() },
+        ..test_config()
+    })
+    .await;
+    let (creator, creator_token) = server.join_session().await;
+    let (_, other_token) = server.join_session().await;
+    let mut events = server.subscribe(&format!("player_id={}", creator)).await;
+    let connected = events.recorded()[0].signals().unwrap();
+    let version = connected["configVersion"].as_u64().unwrap();
+
+    let room: Value = server.get(PHYSICS).await.json().await.unwrap();
+    assert_eq!(room["creator"], creator.to_string());
+    assert_eq!(room["sandbox"]["bounds"]["gravity"]["max"], -20.0);
+
+    assert_eq!(patch(&server, PHYSICS, None, json!({ "gravity": -60 })).await.status(), 401);
+    assert_eq!(patch(&server, PHYSICS, Some(&other_token), json!({ "gravity": -60 })).await.status(), 403);
+    assert_eq!(patch(&server, "/api/rooms/elsewhere/physics", Some(&creator_token), json!({ "gravity": -60 })).await.status(), 404);
+    let out_of_bounds = patch(&server, PHYSICS, Some(&creator_token), json!({ "gravity": 5 })).await;
+    assert_eq!(out_of_bounds.status(), 400);
+    let body: Value = out_of_bounds.json().await.unwrap();
+    assert_eq!(body["code"], "bad_request");
+    assert_eq!(body["message"], "gravity must be between -300 and -20");
+
+    let changed = patch(&server, PHYSICS, Some(&creator_token), json!({ "gravity": -60 })).await;
+    assert_eq!(changed.status(), 200);
+    let body: Value = changed.json().await.unwrap();
+    assert_eq!(body["physics"]["gravity"], -60.0);
+    assert_eq!(body["config_version"], version + 1);
+    server.step(1).await;
+    assert_eq!(events.next_signal("configVersion").await, version + 1);
+    let config: Value = server.get("/api/config").await.json().await.unwrap();
+    assert_eq!(config["physics"]["gravity"], -60.0);
+    assert_eq!(config["config_version"], version + 1);
+
+    // Admins may change any room's physics
+    let by_admin = patch(&server, PHYSICS, Some(ADMIN_TOKEN), json!({ "jump_velocity": 70 })).await;
+    assert_eq!(by_admin.status(), 200);
+    assert_eq!(server.app_state.game_state.read().await.world.config().physics.jump_velocity, 70.0);
+}
@@ -0,0 +1,95 @@
+mod harness;
+
+use std::time::Duration;
+use harness::TestServer;
+
+#[tokio::test]
+async fn commands_require_the_players_own_token() {
+    let server = TestServer::start().await;
+    let (player_id, token) = server.join_session().await;
+    let (_, other_token) = server.join_session().await;
+
+    let command = |token: &str| {
+        server.post(
+            "/api/player/command",
+            serde_json::json!({ "player_id": player_id, "command": { "type": "Jump" }, "session_token": token }),
+        )
+    };
+    assert_eq!(command(&token).await.status(), 200);
+    assert_eq!(command(&other_token).await.status(), 401);
+    assert_eq!(command("not-a-token").await.status(), 401);
+}
+
+#[tokio::test]
+async fn streams_reject_forged_tokens() {
+    let server = TestServer::start().await;
+    let (player_id, token) = server.join_session().await;
+    let forged = format!("{}.{}", player_id, "0".repeat(64));
+
+    let response = server.get(&format!("/events?session={}", forged)).await;
+    assert_eq!(response.status(), 401);
+    server.subscribe(&format!("session={}", token)).await;
+}
+
+#[tokio::test]
+async fn reconnecting_within_grace_reclaims_the_player() {
+    let mut server = TestServer::start().await;
+    let (player_id, token) = server.join_session().await;
+    let response = server
+        .post(
+            "/api/player/rename",
+            serde_json::json!({ "player_id": player_id, "name": "Comeback", "session_token": token }),
+        )
+        .await;
+    assert_eq!(response.status(), 200);
+
+    let events = server.subscribe(&format!("session={}", token)).await;
+    drop(events);
+    server.wait_for_disconnect(player_id).await;
+
+    let grace = Duration::from_secs(server.app_state.game_config.session.reconnect_grace_secs);
+    server.advance_clock(grace / 2);
+    assert_eq!(server.expire_idle().await, 0);
+
+    let response = server
+        .post("/api/player/init", serde_json::json!({ "player_id": player_id, "session_token": token }))
+        .await;
+    assert_eq!(response.status(), 200);
+    let _events = server.subscribe(&format!("session={}", token)).await;
+    server.advance_clock(grace);
+    assert_eq!(server.expire_idle().await, 0);
+
+    // A recreated player would have its generated name back
+    let game_state = server.app_state.game_state.read().await;
+    assert_eq!(game_state.players[&player_id].name, "Comeback", "player was recreated");
+}
+
+#[tokio::test]
+async fn players_who_do_not_reconnect_are_removed_after_grace() {
+    let mut server = TestServer::start().await;
+    let (player_id, token) = server.join_session().await;
+
+    let events = server.subscribe(&format!("session={}", token)).await;
+    drop(events);
+    server.wait_for_disconnect(player_id).await;
+
+    let grace = Duration::from_secs(server.app_state.game_config.session.reconnect_grace_secs);
+    server.advance_clock(grace + Duration::from_secs(1));
+    assert_eq!(server.expire_idle().await, 1);
+    assert!(!server.app_state.game_state.read().await.players.contains_key(&player_id));
+}
+
+#[tokio::test]
+async fn init_without_a_token_never_hands_over_an_existing_player() {
+    let server = TestServer::start().await;
+    let victim = server.join().await;
+
+    let response = server.post("/api/player/init", serde_json::json!({ "player_id": victim })).await;
+    assert_eq!(response.status(), 200);
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_ne!(body["player_id"], victim.to_string());
+    assert!(server.app_state.sessions.verify(body["session_token"].as_str().unwrap()) != Some(victim));
+
+    let rename = serde_json::json!({ "player_id": victim, "name": "Hijacked" });
+    assert_eq!(server.post("/api/player/rename", rename).await.status(), 401);
+}
@@ -0,0 +1,86 @@
+mod harness;
+
+use game_core::PlayerSettings;
+use harness::TestServer;
+use serde_json::json;
+
+#[tokio::test]
+async fn settings_round_trip_per_player() {
+    let server = TestServer::start().await;
+    let (_, token) = server.join_session().await;
+    let (_, other_token) = server.join_session().await;
+
+    let empty: serde_json::Value = server
+        .get(&format!("/api/player/settings?session_token={}", token))
+        .await
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(empty["settings"], json!({}));
+
+    let settings = json!({ "volume": 0.4, "keybinds": { "jump": "Space" } });
+    let response = server
+        .put("/api/player/settings", json!({ "session_token": token, "settings": settings }))
+        .await;
+    assert_eq!(response.status(), 204);
+
+    let stored: serde_json::Value = server
+        .get(&format!("/api/player/settings?session_token={}", token))
+        .await
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(stored["settings"], settings);
+
+    let other: serde_json::Value = server
+        .get(&format!("/api/player/settings?session_token={}", other_token))
+        .await
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(other["settings"], json!({}));
+}
+
+#[tokio::test]
+async fn settings_require_a_valid_session() {
+    let server = TestServer::start().await;
+    let forged = format!("{}.00", uuid::Uuid::new_v4());
+
+    let response = server.get(&format!("/api/player/settings?session_token={}", forged)).await;
+    assert_eq!(response.status(), 401);
+    let response = server
+        .put("/api/player/settings", json!({ "session_token": forged, "settings": {} }))
+        .await;
+    assert_eq!(response.status(), 401);
+}
+
+#[tokio::test]
+async fn oversized_or_non_object_settings_are_rejected() {
+    let server = TestServer::start().await;
+    let (_, token) = server.join_session().await;
+    let max_bytes = server.app_state.game_config.player_settings.max_bytes;
+
+    let huge = json!({ "hud": "x".repeat(max_bytes) });
+    let response = server
+        .put("/api/player/settings", json!({ "session_token": token, "settings": huge }))
+        .await;
+    assert_eq!(response.status(), 413);
+
+    let response = server
+        .put("/api/player/settings", json!({ "session_token": token, "settings": [1, 2, 3] }))
+        .await;
+    assert_eq!(response.status(), 400);
+}
+
+#[tokio::test]
+async fn settings_persist_across_restarts() {
+    let path = std::env::temp_dir().join(format!("player-settings-{}.json", uuid::Uuid::new_v4()));
+    let player_id = uuid::Uuid::new_v4();
+
+    let mut settings = PlayerSettings::load_async(&path).await.unwrap();
+    settings.set(player_id, json!({ "volume": 0.8 })).await.unwrap();
+
+    let reloaded = PlayerSettings::load_async(&path).await.unwrap();
+    assert_eq!(reloaded.get(&player_id), Some(&json!({ "volume": 0.8 })));
+    let _ = std::fs::remove_file(&path);
+}
@@ -0,0 +1,28 @@
+mod harness;
+
+use std::sync::atomic::Ordering;
+use api::GameUpdate;
+use harness::TestServer;
+use serde_json::{json, Value};
+
+#[tokio::test]
+async fn shutting_down_refuses_newcomers_and_counts_streams_down_to_closing() {
+    let mut server = TestServer::start().await;
+    let (player_id, token) = server.join_session().await;
+    let mut events = server.subscribe(&format!("session={}", token)).await;
+
+    server.app_state.shutting_down.store(true, Ordering::SeqCst);
+    let refused = server.post("/api/player/init", json!({})).await;
+    assert_eq!(refused.status(), 503);
+    assert_eq!(refused.json::<Value>().await.unwrap()["code"], "shutting_down");
+    assert_eq!(server.get("/events").await.status(), 503);
+    // Players already in keep playing through the countdown
+    assert_eq!(server.command(player_id, "MoveRight").await.status(), 200);
+
+    for seconds_remaining in (0..=2).rev() {
+        server.app_state.game_tx.send(GameUpdate::ServerShutdown { seconds_remaining }).unwrap();
+        let shutdown = events.next_signal("serverShutdown").await;
+        assert_eq!(shutdown["secondsRemaining"], seconds_remaining);
+    }
+    server.wait_for_disconnect(player_id).await;
+}
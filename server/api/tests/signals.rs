@@ -0,0 +1,119 @@
+mod harness;
+
+use std::collections::HashSet;
+use game_core::signals::{Signal, SignalKind};
+use harness::TestServer;
+use serde_json::{json, Value};
+
+fn kind_of(value: &Value) -> Option<SignalKind> {
+    match value {
+        Value::Object(_) => Some(SignalKind::Object),
+        Value::Array(_) => Some(SignalKind::Array),
+        Value::Number(_) => Some(SignalKind::Number),
+        Value::String(_) => Some(SignalKind::String),
+        _ => None,
+    }
+}
+
+#[tokio::test]
+async fn manifest_lists_every_registered_signal_once() {
+    let server = TestServer::start().await;
+    let manifest: Value = server.get("/api/signals").await.json().await.unwrap();
+    assert_eq!(manifest["version"], game_core::signals::SIGNALS_VERSION);
+
+    let names: Vec<&str> = manifest["signals"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|s| s["name"].as_str().unwrap())
+        .collect();
+    assert_eq!(names.len(), Signal::ALL.len());
+    assert_eq!(names.iter().collect::<HashSet<_>>().len(), names.len(), "duplicate signal names");
+    for signal in Signal::ALL {
+        assert_eq!(Signal::from_name(signal.name()), Some(signal));
+    }
+
+    let game_state = &manifest["signals"][names.iter().position(|n| *n == "gameState").unwrap()];
+    assert_eq!(game_state["kind"], "array");
+    assert_eq!(game_state["nullable"], false);
+}
+
+#[tokio::test]
+async fn streamed_signals_match_the_registry() {
+    let mut server = TestServer::start().await;
+    let mut events = server.subscribe("").await;
+    let player_id = server.join().await;
+    server.join().await;
+    server.step(3).await;
+    events.next_signal("gameState").await;
+
+    server.admin_post("/api/admin/kick", json!({ "player_id": player_id })).await;
+    server.step(1).await;
+    events.next_signal("playerLeft").await;
+
+    let mut seen = HashSet::new();
+    for signals in events.recorded().iter().filter_map(|e| e.signals()) {
+        for (name, value) in signals.as_object().unwrap() {
+            let signal = Signal::from_name(name).unwrap_or_else(|| panic!("unregistered signal {}", name));
+            if value.is_null() {
+                assert!(signal.nullable(), "{} was null", name);
+            } else {
+                assert_eq!(kind_of(value), Some(signal.kind()), "{} has the wrong type: {}", name, value);
+            }
+            seen.insert(signal);
+        }
+    }
+    for expected in [Signal::ResumeToken, Signal::MatchState, Signal::GameState, Signal::Tick, Signal::PlayerLeft] {
+        assert!(seen.contains(&expected), "{} was never sent", expected.name());
+    }
+}
+
+#[tokio::test]
+async fn streams_leave_out_signals_newer_than_the_clients_protocol() {
+    let mut server = TestServer::start().await;
+    let mut current = server.subscribe("").await;
+    let mut older = server.subscribe("protocol_version=1.7").await;
+    server.join().await;
+    server.step(1).await;
+
+    let state = current.next_matching("state", |e| e.signals().is_some_and(|s| s.get("gameState").is_some())).await;
+    assert!(state.signals().unwrap().get("timeScale").is_some());
+    let state = older.next_signal("gameState").await;
+    assert!(state.is_array());
+    let sent_to_older: Vec<Value> = older.recorded().iter().filter_map(|e| e.signals()).collect();
+    assert!(sent_to_older.iter().all(|s| s.get("timeScale").is_none()), "timeScale is newer than 1.7");
+    assert!(sent_to_older.iter().any(|s| s.get("configVersion").is_some()));
+}
+
+#[tokio::test]
+async fn unknown_protocol_majors_are_refused_with_an_error_event() {
+    let server = TestServer::start().await;
+    let mut refused = server.stream("/events?protocol_version=2.0").await;
+    let error = refused.next_signal("protocolError").await;
+    assert_eq!(error["error"], "unsupported_protocol");
+    assert_eq!(error["requested"], "2.0");
+    assert_eq!(error["current"], format!("1.{}", game_core::signals::SIGNALS_VERSION));
+    assert_eq!(error["supportedMajors"], json!([1]));
+
+    let mut garbled = server.stream("/events?protocol_version=latest").await;
+    assert_eq!(garbled.next_signal("protocolError").await["error"], "invalid_protocol");
+
+    // A bare major means its newest minor
+    let mut events = server.stream("/events?protocol_version=1").await;
+    events.next_signal("resumeToken").await;
+}
+
+#[tokio::test]
+async fn config_and_manifest_report_the_protocol_version() {
+    let server = TestServer::start().await;
+    let version = format!("1.{}", game_core::signals::SIGNALS_VERSION);
+    let config: Value = server.get("/api/config").await.json().await.unwrap();
+    assert_eq!(config["protocol_version"], version);
+    assert_eq!(config["supported_protocol_majors"], json!([1]));
+
+    let manifest: Value = server.get("/api/signals").await.json().await.unwrap();
+    assert_eq!(manifest["protocol_version"], version);
+    let since = |name: &str| manifest["signals"].as_array().unwrap().iter().find(|s| s["name"] == name).unwrap()["since"].clone();
+    assert_eq!(since("gameState"), 1);
+    assert_eq!(since("timeScale"), 8);
+}
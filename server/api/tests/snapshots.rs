@@ -0,0 +1,117 @@
+mod harness;
+
+use game_core::config::{MatchConfig, WallConfig};
+use game_core::{GameConfig, MatchState, ScoreSource};
+use harness::{test_config, TestServer, ADMIN_TOKEN};
+use serde_json::Value;
+
+const TICKS_PER_SEC: u32 = 60;
+
+fn match_config() -> GameConfig {
+    GameConfig {
+        matches: MatchConfig {
+            min_players: 2,
+            countdown_secs: 1,
+            duration_secs: 60,
+            ..MatchConfig::default()
+        },
+        ..test_config()
+    }
+}
+
+#[tokio::test]
+async fn a_live_match_moves_to_another_server_and_carries_on() {
+    let mut source = TestServer::with_config(match_config()).await;
+    let leader = source.join().await;
+    source.join().await;
+    source.step(TICKS_PER_SEC + 2).await;
+    source.app_state.game_state.write().await.award_points(&leader, ScoreSource::Coin);
+
+    let client = reqwest::Client::new();
+    let reply = client
+        .get(source.url("/api/admin/snapshot?format=msgpack"))
+        .bearer_auth(ADMIN_TOKEN)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(reply.status(), 200);
+    assert_eq!(reply.headers()["content-type"], "application/msgpack");
+    let snapshot = reply.bytes().await.unwrap();
+
+    let mut target = TestServer::with_config(match_config()).await;
+    let mut events = target.subscribe("").await;
+    let reply = client
+        .post(target.url("/api/admin/restore"))
+        .bearer_auth(ADMIN_TOKEN)
+        .header("content-type", "application/msgpack")
+        .body(snapshot)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(reply.status(), 200);
+    let restored: Value = reply.json().await.unwrap();
+    assert_eq!(restored["players"], 2);
+    {
+        let source_state = source.app_state.game_state.read().await;
+        let target_state = target.app_state.game_state.read().await;
+        assert_eq!(target_state.tick, source_state.tick);
+        assert_eq!(target_state.players[&leader].score, source_state.players[&leader].score);
+        assert!(matches!(target_state.match_state, MatchState::Playing { .. }));
+    }
+
+    // The leader's client carries on against the new server
+    assert_eq!(target.command(leader, "Jump").await.status(), 200);
+    target.step(2).await;
+    let players = events.next_signal("gameState").await;
+    assert!(players.as_array().unwrap().iter().any(|p| p["id"] == leader.to_string()));
+
+    // JSON snapshots restore too, and the format can be named instead of the content type
+    let json = client.get(target.url("/api/admin/snapshot")).bearer_auth(ADMIN_TOKEN).send().await.unwrap();
+    assert_eq!(json.headers()["content-type"], "application/json");
+    let json = json.bytes().await.unwrap();
+    let reply = client
+        .post(source.url("/api/admin/restore?format=json"))
+        .bearer_auth(ADMIN_TOKEN)
+        .body(json)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(reply.status(), 200);
+}
+
+#[tokio::test]
+async fn snapshots_need_an_admin_and_a_readable_body() {
+    let server = TestServer::start().await;
+    let client = reqwest::Client::new();
+    assert_eq!(client.get(server.url("/api/admin/snapshot")).send().await.unwrap().status(), 401);
+    let reply = client
+        .get(server.url("/api/admin/snapshot?format=xml"))
+        .bearer_auth(ADMIN_TOKEN)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(reply.status(), 400);
+
+    let player_id = server.join().await;
+    let reply = server.admin_post("/api/admin/restore", serde_json::json!({ "tick": 3 })).await;
+    assert_eq!(reply.status(), 400);
+    assert!(server.app_state.game_state.read().await.players.contains_key(&player_id));
+
+    // A readable snapshot of a broken map is refused with the map's errors
+    let mut snapshot = server.app_state.game_state.read().await.snapshot();
+    snapshot.players.clear();
+    snapshot.geometry.walls.push(WallConfig {
+        id: "sliver".to_string(),
+        x: 0.0,
+        y_bottom: 0.0,
+        y_top: 5.0,
+        width: 0.0,
+        color: "#666666".to_string(),
+    });
+    let reply = server.admin_post("/api/admin/restore", serde_json::to_value(&snapshot).unwrap()).await;
+    assert_eq!(reply.status(), 400);
+    let body: serde_json::Value = reply.json().await.unwrap();
+    assert_eq!(body["code"], "invalid_geometry");
+    assert_eq!(body["details"]["errors"].as_array().unwrap().len(), 1);
+    assert!(server.app_state.game_state.read().await.players.contains_key(&player_id));
+}
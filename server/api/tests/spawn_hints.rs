@@ -0,0 +1,92 @@
+mod harness;
+
+use game_core::config::{MapBounds, RespawnConfig};
+use game_core::GameConfig;
+use harness::TestServer;
+use serde_json::{json, Value};
+
+fn player<'a>(players: &'a Value, player_id: &uuid::Uuid) -> &'a Value {
+    players
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|p| p["id"] == player_id.to_string())
+        .unwrap()
+}
+
+#[tokio::test]
+async fn new_players_are_flagged_once_per_stream() {
+    let mut server = TestServer::start().await;
+    let mut events = server.subscribe("").await;
+    let first = server.join().await;
+
+    server.step(1).await;
+    let players = events.next_signal("gameState").await;
+    assert_eq!(player(&players, &first)["spawn"], true);
+
+    let second = server.join().await;
+    server.step(1).await;
+    let players = events.next_signal("gameState").await;
+    assert!(player(&players, &first).get("spawn").is_none());
+    assert_eq!(player(&players, &second)["spawn"], true);
+
+    // A stream opened later sees everyone as new
+    let mut late = server.subscribe("").await;
+    server.step(1).await;
+    let players = late.next_signal("gameState").await;
+    assert_eq!(player(&players, &first)["spawn"], true);
+}
+
+#[tokio::test]
+async fn respawns_are_flagged_as_snaps() {
+    let mut server = TestServer::with_config(GameConfig {
+        respawn: RespawnConfig {
+            bounds: Some(MapBounds { min_x: -20.0, max_x: 20.0, max_y: 100.0 }),
+            delay_secs: 0.0,
+            ..RespawnConfig::default()
+        },
+        ..harness::test_config()
+    })
+    .await;
+    let mut events = server.subscribe("").await;
+    let player_id = server.join().await;
+    server.step(1).await;
+    events.next_signal("gameState").await;
+
+    server.app_state.game_state.write().await.players.get_mut(&player_id).unwrap().x = 30.0;
+    server.step(2).await;
+    let mut snapped = false;
+    for _ in 0..2 {
+        let players = events.next_signal("gameState").await;
+        snapped |= player(&players, &player_id).get("snap") == Some(&json!(true));
+    }
+    assert!(snapped, "respawn was not flagged as a snap");
+
+    server.step(1).await;
+    let players = events.next_signal("gameState").await;
+    assert!(player(&players, &player_id).get("snap").is_none());
+}
+
+#[tokio::test]
+async fn new_projectiles_are_flagged() {
+    let mut server = TestServer::start().await;
+    let mut events = server.subscribe("").await;
+    let shooter = server.join().await;
+    server
+        .post(
+            "/api/player/command",
+            json!({
+                "player_id": shooter,
+                "command": { "type": "Shoot", "dir_x": 0.0, "dir_y": 1.0 },
+                "session_token": server.token(shooter),
+            }),
+        )
+        .await;
+
+    server.step(1).await;
+    let projectiles = events.next_signal("projectiles").await;
+    assert_eq!(projectiles[0]["spawn"], true);
+    server.step(1).await;
+    let projectiles = events.next_signal("projectiles").await;
+    assert!(projectiles[0].get("spawn").is_none());
+}
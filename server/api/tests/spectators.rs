@@ -0,0 +1,56 @@
+mod harness;
+
+use std::time::Duration;
+use harness::TestServer;
+
+#[tokio::test]
+async fn spectators_see_state_and_chat_without_joining() {
+    let mut server = TestServer::start().await;
+    let player_id = server.join().await;
+    let mut spectator = server.subscribe("mode=spectator").await;
+
+    server.step(1).await;
+    let game_state = spectator.next_signal("gameState").await;
+    assert_eq!(game_state.as_array().unwrap().len(), 1, "spectator became a player: {}", game_state);
+
+    server.chat(player_id, "hello watchers").await;
+    spectator.next_element_containing("hello watchers").await;
+    assert_eq!(server.app_state.game_state.read().await.players.len(), 1);
+}
+
+#[tokio::test]
+async fn spectator_count_is_broadcast_to_everyone() {
+    let mut server = TestServer::start().await;
+    let mut player_stream = server.subscribe("").await;
+
+    let first = server.subscribe("mode=spectator").await;
+    assert_eq!(player_stream.next_signal("spectatorCount").await, 1);
+    let initial = first.recorded()[0].signals().unwrap();
+    assert_eq!(initial["spectatorCount"], 1);
+
+    let second = server.subscribe("mode=spectator").await;
+    assert_eq!(player_stream.next_signal("spectatorCount").await, 2);
+
+    // Dropping the client closes the stream once the server next writes to it
+    drop(first);
+    for _ in 0..100 {
+        if server.app_state.spectators.count() == 1 {
+            break;
+        }
+        server.step(1).await;
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    assert_eq!(player_stream.next_signal("spectatorCount").await, 1);
+    drop(second);
+}
+
+#[tokio::test]
+async fn spectator_streams_cannot_claim_a_player() {
+    let server = TestServer::start().await;
+    let (player_id, token) = server.join_session().await;
+
+    let with_session = server.get(&format!("/events?mode=spectator&session={}", token)).await;
+    assert_eq!(with_session.status(), 400);
+    let with_player = server.get(&format!("/events?mode=spectator&player_id={}", player_id)).await;
+    assert_eq!(with_player.status(), 400);
+}
@@ -0,0 +1,19 @@
+mod harness;
+
+use harness::TestServer;
+
+#[tokio::test]
+async fn dashing_spends_stamina_shown_in_the_game_state_signal() {
+    let mut server = TestServer::start().await;
+    let mut events = server.subscribe("").await;
+    let player_id = server.join().await;
+    let physics = server.app_state.game_state.read().await.world.config().physics.clone();
+
+    server.command(player_id, "Dash").await;
+    server.step(1).await;
+    let players = events.next_signal("gameState").await;
+    let signal = players.as_array().unwrap().iter().find(|p| p["id"] == player_id.to_string()).unwrap();
+    let stamina = signal["stamina"].as_f64().unwrap() as f32;
+    assert!(stamina < physics.max_stamina - physics.dash_cost + 1.0, "dash didn't cost stamina: {stamina}");
+    assert_eq!(signal["sprinting"], false);
+}
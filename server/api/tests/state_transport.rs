@@ -0,0 +1,117 @@
+mod harness;
+
+use std::time::Duration;
+use futures::StreamExt;
+use game_core::{Clock, Encoding, StateFrame};
+use harness::TestServer;
+use tokio_tungstenite::tungstenite::Message;
+
+type Socket = tokio_tungstenite::WebSocketStream<tokio_tungstenite::MaybeTlsStream<tokio::net::TcpStream>>;
+
+async fn connect(server: &TestServer, query: &str) -> Socket {
+    let url = server.url(&format!("/ws/state{}", query)).replacen("http", "ws", 1);
+    tokio_tungstenite::connect_async(url).await.unwrap().0
+}
+
+/// Step until the socket delivers a frame; the server subscribes just after the handshake
+async fn next_message(server: &mut TestServer, socket: &mut Socket) -> Message {
+    for _ in 0..100 {
+        server.step(1).await;
+        if let Ok(message) = tokio::time::timeout(Duration::from_millis(20), socket.next()).await {
+            return message.unwrap().unwrap();
+        }
+    }
+    panic!("no state frame arrived");
+}
+
+#[tokio::test]
+async fn state_is_served_as_json_or_msgpack_by_accept_header_or_query() {
+    let server = TestServer::start().await;
+    let player_id = server.join().await;
+    let client = reqwest::Client::new();
+
+    let json = client.get(server.url("/api/state")).send().await.unwrap();
+    assert_eq!(json.headers()["content-type"], "application/json");
+    let frame: serde_json::Value = json.json().await.unwrap();
+    assert_eq!(frame["players"][0]["id"], player_id.to_string());
+    assert!(frame["serverTimeMs"].is_u64());
+
+    let msgpack = client
+        .get(server.url("/api/state"))
+        .header("accept", "application/msgpack")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(msgpack.headers()["content-type"], "application/msgpack");
+    let frame = StateFrame::decode(&msgpack.bytes().await.unwrap(), Encoding::MessagePack).unwrap();
+    assert_eq!(frame.players[0].id, player_id);
+
+    let by_query = client.get(server.url("/api/state?encoding=msgpack")).send().await.unwrap();
+    assert_eq!(by_query.headers()["content-type"], "application/msgpack");
+    let unknown = client.get(server.url("/api/state?encoding=xml")).send().await.unwrap();
+    assert_eq!(unknown.status(), 400);
+}
+
+#[tokio::test]
+async fn sockets_stream_a_frame_per_broadcast_in_the_chosen_encoding() {
+    let mut server = TestServer::start().await;
+    let player_id = server.join().await;
+
+    let mut binary = connect(&server, "?encoding=msgpack").await;
+    let Message::Binary(bytes) = next_message(&mut server, &mut binary).await else {
+        panic!("msgpack frames go out as binary messages");
+    };
+    let first = StateFrame::decode(&bytes, Encoding::MessagePack).unwrap();
+    assert_eq!(first.players[0].id, player_id);
+    let Message::Binary(bytes) = next_message(&mut server, &mut binary).await else {
+        panic!("msgpack frames go out as binary messages");
+    };
+    let second = StateFrame::decode(&bytes, Encoding::MessagePack).unwrap();
+    assert_eq!(second.tick, first.tick + 1);
+
+    // The configured default, JSON, as text
+    let mut text = connect(&server, "").await;
+    let Message::Text(json) = next_message(&mut server, &mut text).await else {
+        panic!("json frames go out as text messages");
+    };
+    let frame = StateFrame::decode(json.as_bytes(), Encoding::Json).unwrap();
+    assert_eq!(frame.players[0].id, player_id);
+}
+
+#[tokio::test]
+async fn snapshot_streams_get_timestamped_state_at_the_snapshot_rate() {
+    let mut server = TestServer::with_config(game_core::GameConfig {
+        snapshot_rate_hz: 20.0,
+        ..harness::test_config()
+    })
+    .await;
+    let mut every_tick = server.subscribe("").await;
+    let mut snapshots = server.subscribe("snapshot=true").await;
+    server.join().await;
+    server.step(60).await;
+    // A snapshot after the first second shows the stream has caught up with it
+    server.step(4).await;
+    let past = |e: &harness::SseEvent| e.signals().is_some_and(|s| s["tick"].as_u64().is_some_and(|t| t > 60));
+    every_tick.next_matching("a tick after the first second", past).await;
+    snapshots.next_matching("a snapshot after the first second", past).await;
+
+    let server_times = |stream: &harness::SseClient| -> Vec<u64> {
+        stream
+            .recorded()
+            .iter()
+            .filter_map(|e| e.signals())
+            .filter(|s| s["tick"].as_u64().is_some_and(|t| t <= 60))
+            .map(|s| s["serverTime"].as_u64().unwrap())
+            .collect()
+    };
+    assert_eq!(server_times(&every_tick).len(), 60);
+    let sent = server_times(&snapshots);
+    assert!((19..=21).contains(&sent.len()), "{} snapshots", sent.len());
+    assert!(sent.windows(2).all(|pair| pair[1] - pair[0] >= 50), "{:?}", sent);
+
+    // Clients line their clocks up with the one those timestamps come from
+    let time: serde_json::Value = server.get("/api/time?client_time=1234").await.json().await.unwrap();
+    assert_eq!(time["client_time"], 1234);
+    assert_eq!(time["server_time_ms"], server.clock.unix_millis());
+    assert!(time["server_time_ms"].as_u64().unwrap() > *sent.last().unwrap());
+}
@@ -0,0 +1,70 @@
+mod harness;
+
+use std::time::Duration;
+use futures::StreamExt;
+use game_core::config::StreamCompressionConfig;
+use game_core::GameConfig;
+use harness::{test_config, TestServer};
+
+/// Open /events asking for `encoding`, without decompressing the body
+async fn open(server: &TestServer, encoding: &str) -> reqwest::Response {
+    let client = reqwest::Client::builder().no_gzip().no_brotli().build().unwrap();
+    client
+        .get(server.url("/events"))
+        .header("accept-encoding", encoding)
+        .send()
+        .await
+        .unwrap()
+}
+
+fn content_encoding(response: &reqwest::Response) -> Option<&str> {
+    response.headers().get("content-encoding").and_then(|value| value.to_str().ok())
+}
+
+#[tokio::test]
+async fn event_streams_are_compressed_for_clients_that_accept_it() {
+    let server = TestServer::start().await;
+    assert_eq!(content_encoding(&open(&server, "gzip").await), Some("gzip"));
+    assert_eq!(content_encoding(&open(&server, "br").await), Some("br"));
+    assert_eq!(content_encoding(&open(&server, "identity").await), None);
+    // Other routes are left as they were
+    let client = reqwest::Client::builder().no_gzip().build().unwrap();
+    let config = client.get(server.url("/api/config")).header("accept-encoding", "gzip").send().await.unwrap();
+    assert_eq!(content_encoding(&config), None);
+}
+
+#[tokio::test]
+async fn each_compressed_event_arrives_without_waiting_for_more() {
+    let mut server = TestServer::start().await;
+    server.join().await;
+    let client = reqwest::Client::builder().gzip(true).build().unwrap();
+    let response = client.get(server.url("/events")).send().await.unwrap();
+    let mut body = response.bytes_stream();
+    let mut next_chunk = async || {
+        let chunk = tokio::time::timeout(Duration::from_secs(5), body.next()).await;
+        String::from_utf8(chunk.expect("event held back by the compressor").unwrap().unwrap().to_vec()).unwrap()
+    };
+    // The connect signals alone, then a single state once the server steps
+    let mut received = String::new();
+    while !received.contains("resumeToken") {
+        received += &next_chunk().await;
+    }
+    server.step(1).await;
+    received.clear();
+    while !received.contains("gameState") {
+        received += &next_chunk().await;
+    }
+}
+
+#[tokio::test]
+async fn compression_can_be_turned_off_for_buffering_proxies() {
+    let server = TestServer::with_config(GameConfig {
+        stream_compression: StreamCompressionConfig {
+            enabled: false,
+            ..StreamCompressionConfig::default()
+        },
+        ..test_config()
+    })
+    .await;
+    assert_eq!(content_encoding(&open(&server, "gzip, br").await), None);
+}
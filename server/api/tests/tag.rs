@@ -0,0 +1,24 @@
+mod harness;
+
+use game_core::config::GameRules;
+use harness::TestServer;
+
+#[tokio::test]
+async fn tag_status_goes_out_when_someone_is_it() {
+    let mut config = harness::test_config();
+    config.game_mode = GameRules::TAG;
+    config.matches.enabled = false;
+    let mut server = TestServer::with_config(config).await;
+    let mut events = server.subscribe("").await;
+    let player_id = server.join().await;
+
+    server.step(1).await;
+    let tag = loop {
+        let tag = events.next_signal("tag").await;
+        if !tag["it"].is_null() {
+            break tag;
+        }
+    };
+    assert_eq!(tag["it"], player_id.to_string());
+    assert_eq!(tag["immune"], serde_json::json!([]));
+}
@@ -0,0 +1,81 @@
+mod harness;
+
+use harness::TestServer;
+use serde_json::json;
+
+async fn team_of(server: &TestServer, player_id: uuid::Uuid) -> Option<String> {
+    server.app_state.game_state.read().await.players[&player_id].team.clone()
+}
+
+#[tokio::test]
+async fn joiners_are_balanced_across_teams() {
+    let mut server = TestServer::start().await;
+    let mut events = server.subscribe("").await;
+    let first = server.join().await;
+    let second = server.join().await;
+    let third = server.join().await;
+
+    assert_eq!(team_of(&server, first).await.as_deref(), Some("red"));
+    assert_eq!(team_of(&server, second).await.as_deref(), Some("blue"));
+    assert_eq!(team_of(&server, third).await.as_deref(), Some("red"));
+
+    server.step(1).await;
+    let game_state = events.next_signal("gameState").await;
+    let blue = game_state
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|p| p["id"] == second.to_string())
+        .unwrap();
+    assert_eq!(blue["team"], "blue");
+    assert_eq!(blue["team_color"], "#3498DB");
+}
+
+#[tokio::test]
+async fn chosen_teams_must_stay_balanced() {
+    let server = TestServer::start().await;
+    let red = server.join().await;
+    server.join().await;
+    let join = |player_id: uuid::Uuid, team: &'static str| {
+        server.post("/api/team/join", json!({ "player_id": player_id, "team": team, "session_token": server.token(player_id) }))
+    };
+
+    // 1 v 1 would become 0 v 2
+    assert_eq!(join(red, "blue").await.status(), 409);
+    assert_eq!(join(red, "green").await.status(), 400);
+
+    // 2 v 1 becoming 1 v 2 stays within one
+    let extra_red = server.join().await;
+    let response = join(extra_red, "blue").await;
+    assert_eq!(response.status(), 200);
+    assert_eq!(team_of(&server, extra_red).await.as_deref(), Some("blue"));
+
+    let teams: serde_json::Value = server.get("/api/teams").await.json().await.unwrap();
+    assert_eq!(teams["teams"][0]["members"], 1);
+    assert_eq!(teams["teams"][1]["members"], 2);
+}
+
+#[tokio::test]
+async fn team_chat_only_reaches_teammates() {
+    let server = TestServer::start().await;
+    let sender = server.join().await;
+    let (_, opponent_token) = server.join_session().await;
+    let (teammate, teammate_token) = server.join_session().await;
+    let mut teammate_events = server.subscribe(&format!("session={}", teammate_token)).await;
+    let mut opponent_events = server.subscribe(&format!("session={}", opponent_token)).await;
+    let mut anonymous = server.subscribe("").await;
+    // Naming a teammate without their session doesn't let a stream read team chat
+    let mut impostor = server.subscribe(&format!("player_id={}", teammate)).await;
+
+    server.chat(sender, "/team rush left").await;
+    server.chat(sender, "good game all").await;
+
+    let html = teammate_events.next_element_containing("rush left").await;
+    assert!(html.contains("[Team]"), "team chat not tagged: {}", html);
+    opponent_events.next_element_containing("good game all").await;
+    anonymous.next_element_containing("good game all").await;
+    impostor.next_element_containing("good game all").await;
+    for events in [&opponent_events, &anonymous, &impostor] {
+        assert!(events.recorded().iter().all(|e| !e.data.contains("rush left")));
+    }
+}
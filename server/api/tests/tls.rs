@@ -0,0 +1,119 @@
+mod harness;
+
+use std::path::PathBuf;
+use game_core::config::TlsConfig;
+use harness::{test_config, TestServer};
+
+/// A self-signed certificate for localhost written to a fresh directory
+fn self_signed() -> (PathBuf, PathBuf) {
+    let certified = rcgen::generate_simple_self_signed(vec!["localhost".to_string(), "127.0.0.1".to_string()]).unwrap();
+    let dir = std::env::temp_dir().join(format!("tls-test-{}", uuid::Uuid::new_v4()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let (cert, key) = (dir.join("cert.pem"), dir.join("key.pem"));
+    std::fs::write(&cert, certified.cert.pem()).unwrap();
+    std::fs::write(&key, certified.key_pair.serialize_pem()).unwrap();
+    (cert, key)
+}
+
+async fn https_server() -> TestServer {
+    let (cert, key) = self_signed();
+    let tls = api::tls::load(cert.to_str().unwrap(), key.to_str().unwrap()).await.unwrap();
+    TestServer::with_tls(test_config(), tls).await
+}
+
+/// Serve the redirect app on a local port, returning its address
+async fn redirect_server(https_port: u16) -> String {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::serve(listener, api::tls::redirect_app(https_port)).await.unwrap();
+    });
+    format!("http://{}", addr)
+}
+
+fn no_redirects() -> reqwest::Client {
+    reqwest::Client::builder()
+        .redirect(reqwest::redirect::Policy::none())
+        .build()
+        .unwrap()
+}
+
+#[tokio::test]
+async fn the_api_and_event_stream_are_served_over_https() {
+    let mut server = https_server().await;
+    assert!(server.base_url.starts_with("https://"));
+    assert_eq!(server.get("/health").await.status(), 200);
+
+    let player = server.join().await;
+    let mut events = server.subscribe(&format!("player_id={}", player)).await;
+    server.step(1).await;
+    let players = events.next_signal("gameState").await;
+    assert!(players.as_array().unwrap().iter().any(|p| p["id"] == player.to_string()));
+}
+
+#[tokio::test]
+async fn plain_http_to_the_https_port_is_refused() {
+    let server = https_server().await;
+    let plain = server.base_url.replace("https://", "http://");
+    let response = reqwest::get(format!("{}/health", plain)).await;
+    assert!(response.map(|r| !r.status().is_success()).unwrap_or(true));
+}
+
+#[tokio::test]
+async fn plain_http_redirects_to_the_same_path_over_https() {
+    let base = redirect_server(8443).await;
+    let response = no_redirects()
+        .post(format!("{}/api/player/init?lang=fr", base))
+        .header("host", "play.example.com:8080")
+        .send()
+        .await
+        .unwrap();
+    // 308 so the POST is repeated as a POST
+    assert_eq!(response.status(), 308);
+    assert_eq!(response.headers()["location"], "https://play.example.com:8443/api/player/init?lang=fr");
+}
+
+#[tokio::test]
+async fn the_default_https_port_is_left_out_of_redirects() {
+    let base = redirect_server(443).await;
+    let response = no_redirects()
+        .get(&base)
+        .header("host", "play.example.com")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.headers()["location"], "https://play.example.com/");
+}
+
+#[tokio::test]
+async fn a_missing_certificate_is_reported() {
+    let error = api::tls::load("/nonexistent/cert.pem", "/nonexistent/key.pem").await.unwrap_err();
+    assert!(error.contains("/nonexistent/cert.pem"), "{}", error);
+}
+
+#[test]
+fn a_certificate_without_a_key_is_a_config_error() {
+    let unset = |name: &str| format!("TLS_TEST_UNSET_{}", name);
+    let config = TlsConfig {
+        cert_path: Some("cert.pem".to_string()),
+        cert_path_env: unset("CERT"),
+        key_path_env: unset("KEY"),
+        redirect_port_env: unset("PORT"),
+        ..TlsConfig::default()
+    };
+    assert!(config.resolve_paths().unwrap_err().contains("no key"));
+
+    let config = TlsConfig {
+        key_path: Some("key.pem".to_string()),
+        ..config
+    };
+    assert_eq!(config.resolve_paths().unwrap(), Some(("cert.pem".to_string(), "key.pem".to_string())));
+    assert_eq!(config.resolve_redirect_port().unwrap(), None);
+
+    let plain = TlsConfig {
+        cert_path_env: unset("CERT"),
+        key_path_env: unset("KEY"),
+        ..TlsConfig::default()
+    };
+    assert_eq!(plain.resolve_paths().unwrap(), None);
+}
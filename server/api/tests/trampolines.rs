@@ -0,0 +1,42 @@
+mod harness;
+
+use game_core::{GameConfig, Surface};
+use harness::TestServer;
+
+/// Bouncy ground everywhere
+fn trampoline_config() -> GameConfig {
+    let mut config = harness::test_config();
+    config.physics.ground_surface = Surface::Bouncy;
+    config
+}
+
+#[tokio::test]
+async fn landing_on_a_trampoline_broadcasts_a_bounce() {
+    let mut server = TestServer::with_config(trampoline_config()).await;
+    let mut events = server.subscribe("").await;
+    let player_id = server.join().await;
+    {
+        let mut game_state = server.app_state.game_state.write().await;
+        let ground_y = game_state.world.config().physics.ground_y;
+        let player = game_state.players.get_mut(&player_id).unwrap();
+        player.x = 0.0;
+        player.y = ground_y + 8.0;
+    }
+
+    server.step(60).await;
+    let bounces = events.next_signal("bounces").await;
+    let bounce = &bounces.as_array().unwrap()[0];
+    assert_eq!(bounce["player_id"], player_id.to_string());
+    assert_eq!(bounce["surface"], "bouncy");
+    assert!(bounce["speed"].as_f64().unwrap() > 0.0);
+}
+
+#[tokio::test]
+async fn config_reports_surfaces_and_bounciness() {
+    let server = TestServer::with_config(trampoline_config()).await;
+    let config = server.get("/api/config").await.json::<serde_json::Value>().await.unwrap();
+    assert_eq!(config["physics"]["ground_surface"], "bouncy");
+    assert_eq!(config["physics"]["surfaces"]["bouncy"]["restitution"], 0.8f32 as f64);
+    assert!(config["physics"]["bounce_min_speed"].as_f64().is_some());
+    assert!(config["platforms"].as_array().unwrap().iter().all(|p| p["surface"] == "normal" && p["restitution"] == 0.0));
+}
@@ -0,0 +1,62 @@
+mod harness;
+
+use game_core::config::WorldEventsConfig;
+use game_core::GameConfig;
+use harness::{test_config, TestServer};
+use serde_json::{json, Value};
+
+const TICKS_PER_SEC: u32 = 60;
+
+#[tokio::test]
+async fn admins_start_world_events_that_go_out_as_signals_notices_and_banners() {
+    let mut server = TestServer::with_config(GameConfig {
+        tick_rate_hz: TICKS_PER_SEC as f32,
+        broadcast_rate_hz: TICKS_PER_SEC as f32,
+        world_events: WorldEventsConfig {
+            duration_secs: 1.0,
+            coins_per_sec: 10.0,
+            ..WorldEventsConfig::default()
+        },
+        ..test_config()
+    })
+    .await;
+    server.join().await;
+    let mut events = server.subscribe("lang=es").await;
+    // No event yet in the signals sent on connect
+    let connected = events.recorded()[0].signals().unwrap();
+    assert_eq!(connected["worldEvent"], Value::Null);
+
+    let unknown = server.admin_post("/api/admin/world-event", json!({ "kind": "earthquake" })).await;
+    assert_eq!(unknown.status(), 400);
+    let started = server.admin_post("/api/admin/world-event", json!({ "kind": "Coin Rain" })).await;
+    assert_eq!(started.status(), 200);
+    let body: Value = started.json().await.unwrap();
+    assert_eq!(body["active"]["kind"], "coin_rain");
+    let again = server.admin_post("/api/admin/world-event", json!({ "kind": "speed_frenzy" })).await;
+    assert_eq!(again.status(), 409);
+
+    server.step(2).await;
+    let event = events.next_signal("worldEvent").await;
+    assert_eq!(event["kind"], "coin_rain");
+    assert_eq!(event["durationSecs"], 1.0);
+    let banner = events.next_element_containing("announcement-banner").await;
+    assert!(banner.contains("Lluvia de monedas"), "{}", banner);
+    events.next_element_containing("¡Comienza <b>Lluvia de monedas</b>!").await;
+    server.step(12).await;
+    events
+        .next_matching("falling coins", |e| {
+            e.signals()
+                .and_then(|s| s.get("worldEntities")?.as_array().cloned())
+                .is_some_and(|coins| coins.iter().any(|coin| coin["kind"] == "coin"))
+        })
+        .await;
+    let active: Value = server.get("/api/world-event").await.json().await.unwrap();
+    assert_eq!(active["active"]["kind"], "coin_rain");
+    assert_eq!(active["scheduled"], false);
+
+    // Over after its duration: the signal clears and the banner goes with it
+    server.step(TICKS_PER_SEC).await;
+    assert_eq!(events.next_signal("worldEvent").await, Value::Null);
+    events.next_element_containing(r#"<div id="announcement-banner"></div>"#).await;
+    events.next_element_containing("<b>Lluvia de monedas</b> ha terminado").await;
+}
@@ -0,0 +1,59 @@
+//! Cost of writing one state frame as JSON and as MessagePack, for rooms of a few sizes
+//!
+//! Run with `cargo bench -p game_core --bench state_encoding`
+
+use std::hint::black_box;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use game_core::config::DeterminismConfig;
+use game_core::{Encoding, GameConfig, GameState, PhysicsWorld, StateFrame};
+
+const FRAMES: u32 = 2_000;
+
+fn room(players: usize) -> GameState {
+    let config = GameConfig {
+        determinism: DeterminismConfig { seed: Some(1) },
+        ..GameConfig::default()
+    };
+    let mut state = GameState::new(Arc::new(PhysicsWorld::new(Arc::new(config))));
+    for _ in 0..players {
+        let player_id = state.new_player_id();
+        state.add_player(player_id);
+    }
+    // A second of play, so players have fallen, landed and picked up some state
+    for _ in 0..60 {
+        state.update(1.0 / 60.0);
+    }
+    state
+}
+
+fn time(frames: u32, mut f: impl FnMut()) -> Duration {
+    let started = Instant::now();
+    for _ in 0..frames {
+        f();
+    }
+    started.elapsed() / frames
+}
+
+fn main() {
+    println!("{:>8} {:>10} {:>11} {:>10} {:>14} {:>8}", "players", "json", "json bytes", "msgpack", "msgpack bytes", "speedup");
+    for players in [8, 32, 128] {
+        let state = room(players);
+        let frame = StateFrame::from_state(&state, 0);
+        let json = time(FRAMES, || {
+            black_box(frame.encode(Encoding::Json));
+        });
+        let msgpack = time(FRAMES, || {
+            black_box(frame.encode(Encoding::MessagePack));
+        });
+        println!(
+            "{:>8} {:>10?} {:>11} {:>10?} {:>14} {:>7.1}x",
+            players,
+            json,
+            frame.encode(Encoding::Json).len(),
+            msgpack,
+            frame.encode(Encoding::MessagePack).len(),
+            json.as_secs_f64() / msgpack.as_secs_f64(),
+        );
+    }
+}
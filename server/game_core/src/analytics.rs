@@ -0,0 +1,154 @@
+//! Live match stats for caster and broadcast overlays
+//!
+//! [`Analytics`] lives in the game state and records what can't be read back from a single
+//! tick: when each player acted and how often the lead changed hands. [`OverlayStats`] pulls
+//! that together with positions and scores into what the overlay shows.
+
+use serde::Serialize;
+use std::collections::{HashMap, VecDeque};
+use crate::config::GameConfig;
+use crate::game_state::GameState;
+use crate::player::{Player, PlayerId};
+
+/// Actions per minute are counted over this trailing window
+const APM_WINDOW_MS: u64 = 60_000;
+
+#[derive(Debug, Clone, Default)]
+pub struct Analytics {
+    /// Times of each player's accepted commands within the APM window, oldest first
+    actions: HashMap<PlayerId, VecDeque<u64>>,
+    /// Sole top scorer, kept through ties until someone pulls ahead
+    leader: Option<PlayerId>,
+    lead_changes: u32,
+}
+
+impl Analytics {
+    pub fn record_action(&mut self, player_id: PlayerId, now_ms: u64) {
+        let times = self.actions.entry(player_id).or_default();
+        times.push_back(now_ms);
+        while times.front().is_some_and(|&t| now_ms.saturating_sub(t) >= APM_WINDOW_MS) {
+            times.pop_front();
+        }
+    }
+
+    /// Commands the player sent over the last minute
+    pub fn apm(&self, player_id: &PlayerId, now_ms: u64) -> usize {
+        self.actions
+            .get(player_id)
+            .map_or(0, |times| times.iter().filter(|&&t| now_ms.saturating_sub(t) < APM_WINDOW_MS).count())
+    }
+
+    pub fn forget(&mut self, player_id: &PlayerId) {
+        self.actions.remove(player_id);
+        if self.leader == Some(*player_id) {
+            self.leader = None;
+        }
+    }
+
+    /// Count a lead change when a different player becomes the sole top scorer
+    pub fn observe_scores(&mut self, players: &HashMap<PlayerId, Player>) {
+        let top = players.values().map(|p| p.score).max().unwrap_or(0);
+        if top == 0 {
+            return;
+        }
+        let mut leaders = players.values().filter(|p| p.score == top);
+        let (Some(leader), None) = (leaders.next(), leaders.next()) else {
+            return;
+        };
+        if self.leader != Some(leader.id) {
+            if self.leader.is_some() {
+                self.lead_changes += 1;
+            }
+            self.leader = Some(leader.id);
+        }
+    }
+
+    /// Start counting lead changes afresh for a new match
+    pub fn start_match(&mut self) {
+        self.leader = None;
+        self.lead_changes = 0;
+    }
+
+    pub fn leader(&self) -> Option<PlayerId> {
+        self.leader
+    }
+
+    pub fn lead_changes(&self) -> u32 {
+        self.lead_changes
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PlayerStats {
+    pub id: PlayerId,
+    pub name: String,
+    pub score: u64,
+    pub apm: usize,
+}
+
+/// Everything the overlay shows at one moment
+#[derive(Debug, Clone, Serialize)]
+pub struct OverlayStats {
+    /// Players by score, highest first
+    pub players: Vec<PlayerStats>,
+    /// Living players in each equal-width column between `min_x` and `max_x`, left to right
+    pub density: Vec<usize>,
+    pub min_x: f32,
+    pub max_x: f32,
+    pub leader: Option<String>,
+    pub lead_changes: u32,
+}
+
+/// Horizontal extent of the map: the respawn bounds, else the platforms and walls
+fn map_extent(config: &GameConfig) -> Option<(f32, f32)> {
+    if let Some(bounds) = config.respawn.bounds {
+        return Some((bounds.min_x, bounds.max_x));
+    }
+    let edges = config
+        .platforms
+        .iter()
+        .flat_map(|p| [p.x_start, p.x_end])
+        .chain(config.walls.iter().flat_map(|w| [w.x, w.x + w.width]));
+    edges.fold(None, |extent, x| match extent {
+        None => Some((x, x)),
+        Some((min, max)) => Some((min.min(x), max.max(x))),
+    })
+}
+
+impl OverlayStats {
+    pub fn of(state: &GameState, now_ms: u64, columns: usize) -> Self {
+        let mut players: Vec<PlayerStats> = state
+            .players
+            .values()
+            .map(|p| PlayerStats {
+                id: p.id,
+                name: p.name.clone(),
+                score: p.score,
+                apm: state.analytics.apm(&p.id, now_ms),
+            })
+            .collect();
+        players.sort_by(|a, b| b.score.cmp(&a.score).then_with(|| a.name.cmp(&b.name)));
+
+        let (min_x, max_x) = map_extent(state.world.config()).unwrap_or((-1.0, 1.0));
+        let columns = columns.max(1);
+        let width = (max_x - min_x).max(f32::EPSILON) / columns as f32;
+        let mut density = vec![0; columns];
+        for player in state.players.values().filter(|p| p.life.is_alive()) {
+            let column = ((player.x - min_x) / width).floor().clamp(0.0, (columns - 1) as f32) as usize;
+            density[column] += 1;
+        }
+
+        Self {
+            players,
+            density,
+            min_x,
+            max_x,
+            leader: state
+                .analytics
+                .leader()
+                .and_then(|id| state.players.get(&id))
+                .map(|p| p.name.clone()),
+            lead_changes: state.analytics.lead_changes(),
+        }
+    }
+}
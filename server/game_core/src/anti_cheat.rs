@@ -0,0 +1,212 @@
+//! Spotting suspicious play and checking it against a replay of the player's own inputs
+//!
+//! Two signs raise suspicion: sending commands faster than a person can, and moving faster
+//! than sprinting, dashing or sliding allows. Either way the player's recent frames are
+//! re-simulated from their inputs alone; a replay that ends up where the player did means
+//! the movement was possible, one that doesn't means something other than their inputs
+//! moved them. The verdict goes to admins for review, nothing is done to the player.
+
+use std::collections::{HashMap, VecDeque};
+use serde::{Deserialize, Serialize};
+use crate::config::GameConfig;
+use crate::game_state::GameState;
+use crate::kill_cam::HistoryFrame;
+use crate::player::{Player, PlayerId};
+
+/// What made a player look suspicious
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Suspicion {
+    /// More commands in the last second than the configured maximum
+    InputRate { per_sec: u32, max: u32 },
+    /// Moved faster in one step than any movement allows
+    Speed { speed: f32, max: f32 },
+}
+
+impl std::fmt::Display for Suspicion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Suspicion::InputRate { per_sec, max } => write!(f, "{} commands in a second (max {})", per_sec, max),
+            Suspicion::Speed { speed, max } => write!(f, "moved at {:.1} units/s (max {:.1})", speed, max),
+        }
+    }
+}
+
+/// Whether the player's inputs account for where they went
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "verdict", rename_all = "snake_case")]
+pub enum Verdict {
+    /// The replay stayed within tolerance of every recorded frame
+    Consistent,
+    /// The replay drifted from the recording at `tick`
+    Impossible {
+        tick: u64,
+        recorded: (f32, f32),
+        replayed: (f32, f32),
+    },
+    /// Too few frames were recorded to replay
+    Inconclusive,
+}
+
+/// A replay of a suspect's recent frames and what it showed
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReplayCheck {
+    #[serde(flatten)]
+    pub verdict: Verdict,
+    pub frames_replayed: usize,
+    /// Largest distance between the replay and the recording
+    pub max_deviation: f32,
+    /// The frames replayed, oldest first, for reviewers
+    pub segment: Vec<HistoryFrame>,
+}
+
+/// A flagged player with the replay check attached, for admin review
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CheatReport {
+    pub player_id: PlayerId,
+    pub tick: u64,
+    pub suspicion: Suspicion,
+    pub check: ReplayCheck,
+}
+
+/// Per-player counters the checks run on
+#[derive(Debug, Clone, Default)]
+pub struct AntiCheat {
+    /// Ticks of each player's commands over the last second
+    inputs: HashMap<PlayerId, VecDeque<u64>>,
+    /// Each player's position and teleport count at the last step
+    last_seen: HashMap<PlayerId, (f32, f32, u64)>,
+    /// Tick each player was last flagged on, for the cooldown
+    last_flagged: HashMap<PlayerId, u64>,
+}
+
+/// Fastest a player can legitimately move horizontally, in units per second
+fn fastest_legal_speed(config: &GameConfig) -> f32 {
+    let physics = &config.physics;
+    let sprint = physics.max_horizontal_velocity * physics.sprint_multiplier.max(1.0);
+    let slide = sprint * physics.slide_boost.max(1.0);
+    sprint.max(slide).max(physics.dash_speed)
+}
+
+impl AntiCheat {
+    /// Note a command for the input rate check
+    pub fn record_input(&mut self, player_id: PlayerId, tick: u64) {
+        self.inputs.entry(player_id).or_default().push_back(tick);
+    }
+
+    /// Check every player after a step, returning the ones that became suspicious
+    /// Players flagged within the cooldown are skipped
+    pub fn observe<'a>(
+        &mut self,
+        tick: u64,
+        delta_time: f32,
+        config: &GameConfig,
+        players: impl Iterator<Item = &'a Player>,
+    ) -> Vec<(PlayerId, Suspicion)> {
+        let settings = &config.anti_cheat;
+        let window_ticks = config.tick_rate_hz.ceil() as u64;
+        let cooldown_ticks = (settings.flag_cooldown_secs * config.tick_rate_hz).ceil() as u64;
+        let max_speed = fastest_legal_speed(config) * settings.speed_tolerance;
+        let mut suspects = Vec::new();
+        for player in players {
+            let inputs = self.inputs.entry(player.id).or_default();
+            while inputs.front().is_some_and(|sent| tick.saturating_sub(*sent) >= window_ticks) {
+                inputs.pop_front();
+            }
+            let per_sec = inputs.len() as u32;
+            // Teleports, respawns and the dead don't count as movement
+            let previous = self.last_seen.insert(player.id, (player.x, player.y, player.teleports));
+            let speed = match previous {
+                Some((x, _, teleports)) if teleports == player.teleports && player.life.is_alive() && delta_time > 0.0 => {
+                    (player.x - x).abs() / delta_time
+                }
+                _ => 0.0,
+            };
+            let suspicion = if per_sec > settings.max_inputs_per_sec {
+                Suspicion::InputRate { per_sec, max: settings.max_inputs_per_sec }
+            } else if speed > max_speed {
+                Suspicion::Speed { speed, max: max_speed }
+            } else {
+                continue;
+            };
+            let cooling_down = self
+                .last_flagged
+                .get(&player.id)
+                .is_some_and(|flagged| tick.saturating_sub(*flagged) < cooldown_ticks);
+            if !cooling_down {
+                self.last_flagged.insert(player.id, tick);
+                suspects.push((player.id, suspicion));
+            }
+        }
+        suspects
+    }
+
+    pub fn forget(&mut self, player_id: &PlayerId) {
+        self.inputs.remove(player_id);
+        self.last_seen.remove(player_id);
+        self.last_flagged.remove(player_id);
+    }
+}
+
+/// Re-simulate a player's recorded frames from their inputs alone, on a copy of the room
+/// with everyone else, projectiles and world events taken out
+///
+/// The replay starts from the first frame's position, velocity, facing, crouch and held
+/// direction; the rest of the player (stamina, dash and slide timers) is as it is now, so tolerance should
+/// allow for a little drift.
+/// `delta_time` is the step at normal speed; each frame's own time scale is applied to it.
+pub fn replay(state: &GameState, player_id: PlayerId, frames: &[HistoryFrame], delta_time: f32) -> ReplayCheck {
+    let segment = frames.to_vec();
+    let inconclusive = |segment| ReplayCheck {
+        verdict: Verdict::Inconclusive,
+        frames_replayed: 0,
+        max_deviation: 0.0,
+        segment,
+    };
+    let (Some(first), Some(player)) = (frames.first(), state.players.get(&player_id)) else {
+        return inconclusive(segment);
+    };
+    if frames.len() < 2 {
+        return inconclusive(segment);
+    }
+    let tolerance = state.world.config().anti_cheat.replay_tolerance;
+    let mut player = player.clone();
+    (player.x, player.y) = (first.x, first.y);
+    (player.velocity_x, player.velocity_y) = (first.velocity_x, first.velocity_y);
+    player.facing_right = first.facing_right;
+    player.crouched = first.crouched;
+    (player.held, player.held_secs) = (first.held, first.held_secs);
+    player.last_processed_seq = 0;
+    let mut sim = state.replay_copy(player);
+
+    let mut max_deviation: f32 = 0.0;
+    for (replayed_frames, frame) in frames[1..].iter().enumerate() {
+        for input in &frame.inputs {
+            sim.apply_command(&player_id, input, 0);
+        }
+        sim.update(delta_time * frame.time_scale);
+        let Some(replayed) = sim.players.get(&player_id) else {
+            return inconclusive(segment);
+        };
+        let deviation = ((replayed.x - frame.x).powi(2) + (replayed.y - frame.y).powi(2)).sqrt();
+        max_deviation = max_deviation.max(deviation);
+        if deviation > tolerance {
+            return ReplayCheck {
+                verdict: Verdict::Impossible {
+                    tick: frame.tick,
+                    recorded: (frame.x, frame.y),
+                    replayed: (replayed.x, replayed.y),
+                },
+                frames_replayed: replayed_frames + 1,
+                max_deviation,
+                segment,
+            };
+        }
+    }
+    ReplayCheck {
+        verdict: Verdict::Consistent,
+        frames_replayed: frames.len() - 1,
+        max_deviation,
+        segment,
+    }
+}
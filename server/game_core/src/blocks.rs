@@ -0,0 +1,306 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+use crate::config::{BuildingConfig, PlatformConfig, Surface};
+use crate::player::PlayerId;
+
+/// Grid cell coordinates (in block units, not world units)
+pub type Cell = (i32, i32);
+
+/// A single player-placed block occupying one grid cell
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Block {
+    pub cell_x: i32,
+    pub cell_y: i32,
+    pub owner: PlayerId,
+    /// Block color as hex string (owner's player color)
+    pub color: String,
+}
+
+impl Block {
+    /// Convert the block into a platform so it participates in collision
+    pub fn to_platform(&self, block_size: f32) -> PlatformConfig {
+        let x_start = self.cell_x as f32 * block_size;
+        let y_bottom = self.cell_y as f32 * block_size;
+        PlatformConfig {
+            id: format!("block_{}_{}", self.cell_x, self.cell_y),
+            x_start,
+            x_end: x_start + block_size,
+            y_top: y_bottom + block_size,
+            height: block_size,
+            color: self.color.clone(),
+            surface: Surface::Normal,
+            restitution: None,
+        }
+    }
+}
+
+/// Number of geometry changes retained for delta resync
+const MAX_GEOMETRY_HISTORY: usize = 1024;
+
+/// Geometry change produced by building commands
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum GeometryEvent {
+    BlockPlaced { block: Block },
+    BlockRemoved { cell_x: i32, cell_y: i32 },
+}
+
+/// Cells added and removed between two geometry versions
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GeometryDelta {
+    pub from_version: u64,
+    pub to_version: u64,
+    pub added: Vec<Block>,
+    pub removed: Vec<Cell>,
+}
+
+impl GeometryDelta {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty()
+    }
+}
+
+/// Full block list at a geometry version
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GeometrySnapshot {
+    pub version: u64,
+    pub blocks: Vec<Block>,
+}
+
+/// What a client needs to catch up from a known geometry version
+/// A delta when the version is still in history, otherwise a full snapshot
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum GeometrySync {
+    Delta(GeometryDelta),
+    Snapshot(GeometrySnapshot),
+}
+
+/// Reasons a build command can be rejected
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BuildError {
+    /// Building is disabled in the configuration
+    Disabled,
+    /// Command came from a player who is not in the game
+    UnknownPlayer,
+    /// Player has no blocks left in their budget
+    BudgetExhausted,
+    /// Player is placing blocks faster than the cooldown allows
+    Cooldown,
+    /// Target cell is farther from the player than the allowed reach
+    OutOfReach,
+    /// Target cell is already occupied by a block or static geometry
+    Occupied,
+    /// Target cell overlaps a player
+    OverlapsPlayer,
+    /// No block exists at the target cell
+    NoBlock,
+    /// Block belongs to a different player
+    NotOwner,
+    /// Target cell is in a hub plot the player doesn't own
+    NotYourPlot,
+}
+
+impl fmt::Display for BuildError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let reason = match self {
+            BuildError::Disabled => "building is disabled",
+            BuildError::UnknownPlayer => "player is not in the game",
+            BuildError::BudgetExhausted => "block budget exhausted",
+            BuildError::Cooldown => "placing too fast",
+            BuildError::OutOfReach => "cell is out of reach",
+            BuildError::Occupied => "cell is occupied",
+            BuildError::OverlapsPlayer => "cell overlaps a player",
+            BuildError::NoBlock => "no block at cell",
+            BuildError::NotOwner => "block belongs to another player",
+            BuildError::NotYourPlot => "cell is in another player's plot",
+        };
+        write!(f, "{}", reason)
+    }
+}
+
+impl std::error::Error for BuildError {}
+
+/// Runtime grid of player-placed blocks
+#[derive(Debug, Clone, Default)]
+pub struct BlockGrid {
+    pub blocks: HashMap<Cell, Block>,
+    /// Geometry version, incremented on every change
+    version: u64,
+    /// Recent changes tagged with the version they produced
+    history: VecDeque<(u64, GeometryEvent)>,
+}
+
+impl BlockGrid {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Snap a world position to the grid cell containing it
+    pub fn cell_at(x: f32, y: f32, block_size: f32) -> Cell {
+        ((x / block_size).floor() as i32, (y / block_size).floor() as i32)
+    }
+
+    pub fn get(&self, cell: &Cell) -> Option<&Block> {
+        self.blocks.get(cell)
+    }
+
+    /// Number of blocks currently owned by a player
+    pub fn count_owned(&self, owner: &PlayerId) -> usize {
+        self.blocks.values().filter(|b| &b.owner == owner).count()
+    }
+
+    /// Current geometry version
+    pub fn version(&self) -> u64 {
+        self.version
+    }
+
+    pub fn insert(&mut self, block: Block) {
+        self.blocks.insert((block.cell_x, block.cell_y), block.clone());
+        self.record(GeometryEvent::BlockPlaced { block });
+    }
+
+    pub fn remove(&mut self, cell: &Cell) -> Option<Block> {
+        let removed = self.blocks.remove(cell);
+        if removed.is_some() {
+            self.record(GeometryEvent::BlockRemoved {
+                cell_x: cell.0,
+                cell_y: cell.1,
+            });
+        }
+        removed
+    }
+
+    fn record(&mut self, event: GeometryEvent) {
+        self.record_at(self.version + 1, event);
+    }
+
+    fn record_at(&mut self, version: u64, event: GeometryEvent) {
+        self.version = version;
+        self.history.push_back((version, event));
+        if self.history.len() > MAX_GEOMETRY_HISTORY {
+            self.history.pop_front();
+        }
+    }
+
+    /// Remove every block owned by a player (used when they leave)
+    pub fn remove_owned(&mut self, owner: &PlayerId) {
+        let cells: Vec<Cell> = self
+            .blocks
+            .iter()
+            .filter(|(_, b)| &b.owner == owner)
+            .map(|(cell, _)| *cell)
+            .collect();
+        for cell in cells {
+            self.remove(&cell);
+        }
+    }
+
+    /// Remove every block, e.g. when the map changes
+    pub fn clear(&mut self) {
+        let cells: Vec<Cell> = self.blocks.keys().copied().collect();
+        for cell in cells {
+            self.remove(&cell);
+        }
+    }
+
+    /// All blocks as collision platforms, ordered by cell so indices are stable between ticks
+    pub fn platforms(&self, building: &BuildingConfig) -> Vec<PlatformConfig> {
+        let mut blocks: Vec<&Block> = self.blocks.values().collect();
+        blocks.sort_by_key(|b| (b.cell_x, b.cell_y));
+        blocks
+            .into_iter()
+            .map(|b| b.to_platform(building.block_size))
+            .collect()
+    }
+
+    /// Full block list at the current version
+    pub fn snapshot(&self) -> GeometrySnapshot {
+        let mut blocks: Vec<Block> = self.blocks.values().cloned().collect();
+        blocks.sort_by_key(|b| (b.cell_x, b.cell_y));
+        GeometrySnapshot {
+            version: self.version,
+            blocks,
+        }
+    }
+
+    /// Net changes since `version`, or None if that version is no longer in history
+    pub fn delta_since(&self, version: u64) -> Option<GeometryDelta> {
+        if version > self.version {
+            return None;
+        }
+        if version < self.version {
+            let oldest = self.history.front().map(|(v, _)| *v)?;
+            if oldest > version + 1 {
+                return None;
+            }
+        }
+
+        // Collapse the event log into the final state of each touched cell
+        let mut touched: HashMap<Cell, Option<Block>> = HashMap::new();
+        for (_, event) in self.history.iter().filter(|(v, _)| *v > version) {
+            match event {
+                GeometryEvent::BlockPlaced { block } => {
+                    touched.insert((block.cell_x, block.cell_y), Some(block.clone()));
+                }
+                GeometryEvent::BlockRemoved { cell_x, cell_y } => {
+                    touched.insert((*cell_x, *cell_y), None);
+                }
+            }
+        }
+
+        let mut added = Vec::new();
+        let mut removed = Vec::new();
+        for (cell, block) in touched {
+            match block {
+                Some(block) => added.push(block),
+                None => removed.push(cell),
+            }
+        }
+        added.sort_by_key(|b| (b.cell_x, b.cell_y));
+        removed.sort();
+
+        Some(GeometryDelta {
+            from_version: version,
+            to_version: self.version,
+            added,
+            removed,
+        })
+    }
+
+    /// Delta since `version` when available, otherwise a full snapshot for resync
+    pub fn sync_since(&self, version: u64) -> GeometrySync {
+        match self.delta_since(version) {
+            Some(delta) => GeometrySync::Delta(delta),
+            None => GeometrySync::Snapshot(self.snapshot()),
+        }
+    }
+
+    /// Mirror another grid from the syncs it broadcasts, keeping its version numbers
+    /// Returns false for a delta that doesn't start at this grid's version; a snapshot is needed
+    pub fn follow(&mut self, sync: &GeometrySync) -> bool {
+        match sync {
+            GeometrySync::Snapshot(snapshot) => {
+                self.blocks = snapshot.blocks.iter().map(|b| ((b.cell_x, b.cell_y), b.clone())).collect();
+                self.history.clear();
+                self.version = snapshot.version;
+                true
+            }
+            GeometrySync::Delta(delta) if delta.from_version != self.version => false,
+            GeometrySync::Delta(delta) => {
+                for &(cell_x, cell_y) in &delta.removed {
+                    self.blocks.remove(&(cell_x, cell_y));
+                    self.record_at(delta.to_version, GeometryEvent::BlockRemoved { cell_x, cell_y });
+                }
+                for block in &delta.added {
+                    self.blocks.insert((block.cell_x, block.cell_y), block.clone());
+                    self.record_at(delta.to_version, GeometryEvent::BlockPlaced { block: block.clone() });
+                }
+                self.version = delta.to_version;
+                true
+            }
+        }
+    }
+}
@@ -0,0 +1,154 @@
+//! Server-controlled players
+//!
+//! A bot is an ordinary player whose commands come from a [`Behavior`] instead of a client.
+//! The server asks each bot's behavior for a command a few times a second and queues it like
+//! any client input, so bots move under exactly the same physics and rules as people.
+
+use serde::{Deserialize, Serialize};
+use crate::commands::PlayerCommand;
+use crate::game_state::GameState;
+use crate::player::{Player, PlayerId};
+
+/// Decides what a bot does next from what it can see of the game
+pub trait Behavior: Send + Sync {
+    /// The command to send now, if any; called at the configured think rate
+    fn think(&mut self, me: &Player, state: &GameState) -> Option<PlayerCommand>;
+}
+
+/// The behaviors that can be picked in the config or over the admin API
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BehaviorKind {
+    /// Walk back and forth around where it spawned
+    Patrol,
+    /// Run and jump towards the nearest other player
+    Chase,
+    /// Wander at random, jumping often
+    Jumper,
+}
+
+impl BehaviorKind {
+    pub fn label(self) -> &'static str {
+        match self {
+            BehaviorKind::Patrol => "Patrol",
+            BehaviorKind::Chase => "Chase",
+            BehaviorKind::Jumper => "Jumper",
+        }
+    }
+
+    /// A fresh behavior for a bot starting out as `me`
+    pub fn build(self, me: &Player) -> Box<dyn Behavior> {
+        match self {
+            BehaviorKind::Patrol => Box::new(Patrol::around(me.x, PATROL_RANGE)),
+            BehaviorKind::Chase => Box::new(Chase::default()),
+            BehaviorKind::Jumper => Box::new(Jumper::seeded(&me.id)),
+        }
+    }
+}
+
+/// How far either side of its spawn a patrolling bot walks
+const PATROL_RANGE: f32 = 6.0;
+
+/// Below this horizontal speed a bot that is trying to move counts as blocked
+const STUCK_SPEED: f32 = 0.1;
+
+/// Walks between two x positions, turning at either end or when something blocks it
+#[derive(Debug, Clone)]
+pub struct Patrol {
+    pub left: f32,
+    pub right: f32,
+    heading_right: bool,
+    moving: bool,
+}
+
+impl Patrol {
+    pub fn around(x: f32, range: f32) -> Self {
+        Self {
+            left: x - range,
+            right: x + range,
+            heading_right: true,
+            moving: false,
+        }
+    }
+}
+
+impl Behavior for Patrol {
+    fn think(&mut self, me: &Player, _state: &GameState) -> Option<PlayerCommand> {
+        let blocked = self.moving && me.ground_state.is_on_surface() && me.velocity_x.abs() < STUCK_SPEED;
+        if (self.heading_right && me.x >= self.right) || (!self.heading_right && me.x <= self.left) || blocked {
+            self.heading_right = !self.heading_right;
+        }
+        self.moving = true;
+        Some(if self.heading_right { PlayerCommand::MoveRight } else { PlayerCommand::MoveLeft })
+    }
+}
+
+/// Heads for the nearest living player, jumping when they are above or the way is blocked
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Chase {
+    /// Whether the last command was a run, so standing still afterwards means something is in the way
+    running: bool,
+}
+
+impl Behavior for Chase {
+    fn think(&mut self, me: &Player, state: &GameState) -> Option<PlayerCommand> {
+        let target = state
+            .players
+            .values()
+            .filter(|p| p.id != me.id && p.life.is_alive())
+            .min_by(|a, b| {
+                let distance = |p: &Player| (p.x - me.x).powi(2) + (p.y - me.y).powi(2);
+                distance(a).total_cmp(&distance(b))
+            })?;
+        let dx = target.x - me.x;
+        let blocked = self.running && me.velocity_x.abs() < STUCK_SPEED;
+        if me.ground_state.is_grounded() && (target.y - me.y > 1.0 || blocked) {
+            self.running = false;
+            return Some(PlayerCommand::Jump);
+        }
+        if dx.abs() < 0.5 {
+            self.running = false;
+            return Some(PlayerCommand::Stop);
+        }
+        self.running = true;
+        Some(if dx > 0.0 { PlayerCommand::MoveRight } else { PlayerCommand::MoveLeft })
+    }
+}
+
+/// Wanders, changing direction now and then and jumping about a third of the time
+#[derive(Debug, Clone)]
+pub struct Jumper {
+    /// xorshift state; bots are seeded from their id so each one moves differently
+    seed: u64,
+    heading_right: bool,
+}
+
+impl Jumper {
+    pub fn seeded(id: &PlayerId) -> Self {
+        let (high, low) = id.as_u64_pair();
+        Self {
+            seed: (high ^ low) | 1,
+            heading_right: low & 1 == 0,
+        }
+    }
+
+    fn next(&mut self) -> u64 {
+        self.seed ^= self.seed << 13;
+        self.seed ^= self.seed >> 7;
+        self.seed ^= self.seed << 17;
+        self.seed
+    }
+}
+
+impl Behavior for Jumper {
+    fn think(&mut self, _me: &Player, _state: &GameState) -> Option<PlayerCommand> {
+        let roll = self.next() % 10;
+        if roll < 3 {
+            return Some(PlayerCommand::Jump);
+        }
+        if roll == 3 {
+            self.heading_right = !self.heading_right;
+        }
+        Some(if self.heading_right { PlayerCommand::MoveRight } else { PlayerCommand::MoveLeft })
+    }
+}
@@ -0,0 +1,187 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use crate::player::PlayerId;
+
+/// Number of challenges active each day
+pub const DAILY_CHALLENGE_COUNT: usize = 3;
+
+/// Seconds in a day, used to derive the challenge day from Unix time
+const SECONDS_PER_DAY: u64 = 86_400;
+
+/// What a challenge counts
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChallengeGoal {
+    /// Number of jumps
+    Jumps,
+    /// Number of blocks placed
+    BlocksPlaced,
+    /// Total points earned
+    Points,
+    /// Longest combo reached (not cumulative)
+    ComboLength,
+}
+
+/// Something a player did that may advance a challenge
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QuestEvent {
+    Jumped,
+    BlockPlaced,
+    PointsEarned(u64),
+    ComboReached(u32),
+}
+
+/// A challenge that can be offered on a given day
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChallengeDefinition {
+    pub id: String,
+    pub description: String,
+    pub goal: ChallengeGoal,
+    pub target: u64,
+    /// Points awarded on completion
+    pub reward_points: u64,
+}
+
+/// A player's progress on one of today's challenges
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChallengeProgress {
+    pub challenge_id: String,
+    pub progress: u64,
+    pub target: u64,
+    pub completed: bool,
+}
+
+/// Reward granted when a player completes a challenge
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChallengeCompletion {
+    pub player_id: PlayerId,
+    pub challenge_id: String,
+    pub reward_points: u64,
+}
+
+/// Every challenge that can rotate in
+fn challenge_pool() -> Vec<ChallengeDefinition> {
+    let def = |id: &str, description: &str, goal, target, reward_points| ChallengeDefinition {
+        id: id.to_string(),
+        description: description.to_string(),
+        goal,
+        target,
+        reward_points,
+    };
+    vec![
+        def("jumps_50", "Jump 50 times", ChallengeGoal::Jumps, 50, 100),
+        def("jumps_200", "Jump 200 times", ChallengeGoal::Jumps, 200, 300),
+        def("builder_10", "Place 10 blocks", ChallengeGoal::BlocksPlaced, 10, 100),
+        def("builder_40", "Place 40 blocks", ChallengeGoal::BlocksPlaced, 40, 250),
+        def("points_500", "Earn 500 points", ChallengeGoal::Points, 500, 150),
+        def("points_2000", "Earn 2000 points", ChallengeGoal::Points, 2000, 400),
+        def("combo_5", "Reach a 5x combo", ChallengeGoal::ComboLength, 5, 150),
+        def("combo_10", "Reach a 10x combo", ChallengeGoal::ComboLength, 10, 400),
+    ]
+}
+
+/// Day number (days since the Unix epoch, UTC) for a Unix timestamp in seconds
+pub fn day_from_unix(seconds: u64) -> u64 {
+    seconds / SECONDS_PER_DAY
+}
+
+/// Challenges for a day, chosen deterministically so every server agrees
+pub fn daily_challenges(day: u64) -> Vec<ChallengeDefinition> {
+    let mut pool = challenge_pool();
+    // xorshift seeded from the day; the constant keeps day 0 from seeding zero
+    let mut seed = day ^ 0x9E37_79B9_7F4A_7C15;
+    let mut chosen = Vec::with_capacity(DAILY_CHALLENGE_COUNT);
+    while chosen.len() < DAILY_CHALLENGE_COUNT && !pool.is_empty() {
+        seed ^= seed << 13;
+        seed ^= seed >> 7;
+        seed ^= seed << 17;
+        let idx = (seed % pool.len() as u64) as usize;
+        chosen.push(pool.swap_remove(idx));
+    }
+    chosen
+}
+
+/// Quest engine evaluating today's challenges for every player
+#[derive(Debug, Clone)]
+pub struct ChallengeTracker {
+    day: u64,
+    challenges: Vec<ChallengeDefinition>,
+    /// Progress per player, indexed like `challenges`
+    progress: HashMap<PlayerId, Vec<u64>>,
+}
+
+impl ChallengeTracker {
+    pub fn new(day: u64) -> Self {
+        Self {
+            day,
+            challenges: daily_challenges(day),
+            progress: HashMap::new(),
+        }
+    }
+
+    pub fn day(&self) -> u64 {
+        self.day
+    }
+
+    pub fn challenges(&self) -> &[ChallengeDefinition] {
+        &self.challenges
+    }
+
+    /// Switch to a new day's challenges, resetting all progress
+    pub fn rotate(&mut self, day: u64) {
+        if day != self.day {
+            *self = Self::new(day);
+        }
+    }
+
+    /// Record an event and return any challenges it completed
+    pub fn record(&mut self, player_id: &PlayerId, event: QuestEvent) -> Vec<ChallengeCompletion> {
+        let progress = self
+            .progress
+            .entry(*player_id)
+            .or_insert_with(|| vec![0; self.challenges.len()]);
+
+        let mut completed = Vec::new();
+        for (challenge, value) in self.challenges.iter().zip(progress.iter_mut()) {
+            if *value >= challenge.target {
+                continue;
+            }
+            match (challenge.goal, event) {
+                (ChallengeGoal::Jumps, QuestEvent::Jumped)
+                | (ChallengeGoal::BlocksPlaced, QuestEvent::BlockPlaced) => *value += 1,
+                (ChallengeGoal::Points, QuestEvent::PointsEarned(points)) => *value += points,
+                (ChallengeGoal::ComboLength, QuestEvent::ComboReached(count)) => {
+                    *value = (*value).max(count as u64)
+                }
+                _ => continue,
+            }
+            if *value >= challenge.target {
+                *value = challenge.target;
+                completed.push(ChallengeCompletion {
+                    player_id: *player_id,
+                    challenge_id: challenge.id.clone(),
+                    reward_points: challenge.reward_points,
+                });
+            }
+        }
+        completed
+    }
+
+    /// A player's progress on each of today's challenges
+    pub fn progress_for(&self, player_id: &PlayerId) -> Vec<ChallengeProgress> {
+        let progress = self.progress.get(player_id);
+        self.challenges
+            .iter()
+            .enumerate()
+            .map(|(idx, challenge)| {
+                let value = progress.map(|p| p[idx]).unwrap_or(0);
+                ChallengeProgress {
+                    challenge_id: challenge.id.clone(),
+                    progress: value,
+                    target: challenge.target,
+                    completed: value >= challenge.target,
+                }
+            })
+            .collect()
+    }
+}
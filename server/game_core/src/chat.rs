@@ -8,5 +8,8 @@ pub struct ChatMessage {
     pub player_color: String, // Hex color string
     pub text: String,
     pub timestamp: u64,
+    /// Set for team chat: only that team's players receive the message
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub team: Option<crate::teams::TeamId>,
 }
 
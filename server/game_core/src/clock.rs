@@ -0,0 +1,88 @@
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+/// Source of time for the game loop, timeouts and cooldowns
+///
+/// Production uses `SystemClock`; tests and replays use `MockClock` to move time deterministically.
+pub trait Clock: std::fmt::Debug + Send + Sync {
+    /// Wall-clock time, for timestamps and timeouts
+    fn now(&self) -> SystemTime;
+
+    /// Monotonic time, for measuring elapsed intervals
+    fn instant(&self) -> Instant;
+
+    /// Unix time in seconds
+    fn unix_secs(&self) -> u64 {
+        self.now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0)
+    }
+
+    /// Unix time in milliseconds
+    fn unix_millis(&self) -> u64 {
+        self.now().duration_since(UNIX_EPOCH).map(|d| d.as_millis() as u64).unwrap_or(0)
+    }
+}
+
+pub type SharedClock = Arc<dyn Clock>;
+
+/// The real clock
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> SystemTime {
+        SystemTime::now()
+    }
+
+    fn instant(&self) -> Instant {
+        Instant::now()
+    }
+}
+
+/// A clock that only moves when told to
+#[derive(Debug)]
+pub struct MockClock {
+    wall_start: SystemTime,
+    instant_start: Instant,
+    elapsed: Mutex<Duration>,
+}
+
+impl MockClock {
+    /// A clock frozen at the current real time
+    pub fn new() -> Self {
+        Self::starting_at(SystemTime::now())
+    }
+
+    /// A clock frozen at `wall_start`, e.g. a recorded match's start time
+    pub fn starting_at(wall_start: SystemTime) -> Self {
+        Self {
+            wall_start,
+            instant_start: Instant::now(),
+            elapsed: Mutex::new(Duration::ZERO),
+        }
+    }
+
+    pub fn advance(&self, by: Duration) {
+        *self.elapsed.lock().unwrap() += by;
+    }
+
+    /// Time advanced since the clock was created
+    pub fn elapsed(&self) -> Duration {
+        *self.elapsed.lock().unwrap()
+    }
+}
+
+impl Default for MockClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Clock for MockClock {
+    fn now(&self) -> SystemTime {
+        self.wall_start + self.elapsed()
+    }
+
+    fn instant(&self) -> Instant {
+        self.instant_start + self.elapsed()
+    }
+}
@@ -0,0 +1,274 @@
+//! Static map geometry baked once per world, so collision checks only look at what's nearby
+//!
+//! Platforms that share a top and thickness and touch or overlap are fused into one span, and
+//! walls stacked on or beside each other into one wall, so players never meet a seam between
+//! them. Bounds are worked out once and kept sorted by left edge; a binary search finds the
+//! shapes a player could touch instead of scanning every one each tick. The ground becomes
+//! columns covering the whole x axis, one per stretch at a single height.
+
+use crate::config::{GroundSegment, PhysicsConfig, PlatformConfig, Surface, WallConfig};
+
+/// Gap between two shapes still treated as touching when fusing
+const TOUCHING: f32 = 1e-3;
+
+/// An axis-aligned box
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Solid {
+    pub left: f32,
+    pub right: f32,
+    pub bottom: f32,
+    pub top: f32,
+}
+
+impl Solid {
+    /// Whether the box overlaps the open x range `left..right`
+    pub fn overlaps_x(&self, left: f32, right: f32) -> bool {
+        right > self.left && left < self.right
+    }
+}
+
+/// One or more configured platforms fused into a single surface
+#[derive(Debug, Clone, PartialEq)]
+pub struct Span {
+    pub solid: Solid,
+    /// Index of each configured platform in the span with its x range, ordered by x
+    pub sources: Vec<(u32, f32, f32)>,
+}
+
+impl Span {
+    /// The configured platform under the x range `left..right`, for ground state
+    pub fn platform_at(&self, left: f32, right: f32) -> u32 {
+        self.sources
+            .iter()
+            .find(|(_, x_start, x_end)| right > *x_start && left < *x_end)
+            .or(self.sources.first())
+            .map(|(idx, _, _)| *idx)
+            .unwrap_or_default()
+    }
+}
+
+/// A stretch of ground at one height, and how it feels to land on
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GroundColumn {
+    pub solid: Solid,
+    pub surface: Surface,
+    pub restitution: f32,
+}
+
+/// A map's platforms and walls, fused and sorted for collision
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct BakedGeometry {
+    /// Ordered by left edge
+    platforms: Vec<Span>,
+    platform_lefts: Vec<f32>,
+    widest_platform: f32,
+    /// Ordered by left edge
+    walls: Vec<Solid>,
+    wall_lefts: Vec<f32>,
+    widest_wall: f32,
+    /// Configured platforms before fusing; runtime platforms are numbered after them
+    platform_count: usize,
+    /// Ordered, touching columns from the far left to the far right; empty without ground
+    ground: Vec<GroundColumn>,
+}
+
+impl BakedGeometry {
+    pub fn bake(platforms: &[PlatformConfig], walls: &[WallConfig]) -> Self {
+        let spans = fuse_platforms(platforms);
+        let walls = fuse_walls(walls);
+        Self {
+            platform_lefts: spans.iter().map(|span| span.solid.left).collect(),
+            widest_platform: spans.iter().map(|span| span.solid.right - span.solid.left).fold(0.0, f32::max),
+            platforms: spans,
+            wall_lefts: walls.iter().map(|wall| wall.left).collect(),
+            widest_wall: walls.iter().map(|wall| wall.right - wall.left).fold(0.0, f32::max),
+            walls,
+            platform_count: platforms.len(),
+            ground: Vec::new(),
+        }
+    }
+
+    /// Add ground at `ground_y`, raised or lowered and resurfaced wherever a segment says
+    pub fn with_ground(mut self, physics: &PhysicsConfig) -> Self {
+        self.ground = ground_columns(physics);
+        self
+    }
+
+    pub fn platforms(&self) -> &[Span] {
+        &self.platforms
+    }
+
+    pub fn walls(&self) -> &[Solid] {
+        &self.walls
+    }
+
+    /// Number of configured platforms the spans were baked from
+    pub fn platform_count(&self) -> usize {
+        self.platform_count
+    }
+
+    /// Spans overlapping the x range `left..right`
+    pub fn platforms_near(&self, left: f32, right: f32) -> impl Iterator<Item = &Span> {
+        let range = near(&self.platform_lefts, self.widest_platform, left, right);
+        self.platforms[range].iter().filter(move |span| span.solid.overlaps_x(left, right))
+    }
+
+    /// Walls overlapping the x range `left..right`
+    pub fn walls_near(&self, left: f32, right: f32) -> impl Iterator<Item = &Solid> {
+        let range = near(&self.wall_lefts, self.widest_wall, left, right);
+        self.walls[range].iter().filter(move |wall| wall.overlaps_x(left, right))
+    }
+
+    /// Columns of ground, ordered from left to right; each reaches down without end
+    pub fn ground(&self) -> &[GroundColumn] {
+        &self.ground
+    }
+
+    /// Ground columns overlapping the x range `left..right`, or at `left` for an empty range
+    pub fn ground_near(&self, left: f32, right: f32) -> &[GroundColumn] {
+        let start = self.ground.partition_point(|column| column.solid.right <= left);
+        let end = self.ground.partition_point(|column| column.solid.left < right).max(start + 1);
+        &self.ground[start.min(self.ground.len())..end.min(self.ground.len())]
+    }
+
+    /// The column holding up the x range `left..right`: the highest one under it, and of
+    /// columns at that height the one covering most of the range
+    pub fn ground_beneath(&self, left: f32, right: f32) -> Option<&GroundColumn> {
+        let covered = |column: &GroundColumn| column.solid.right.min(right) - column.solid.left.max(left);
+        self.ground_near(left, right).iter().max_by(|a, b| {
+            a.solid.top.total_cmp(&b.solid.top).then(covered(a).total_cmp(&covered(b)))
+        })
+    }
+
+    /// Height of the highest ground under the x range `left..right`
+    pub fn ground_under(&self, left: f32, right: f32) -> f32 {
+        self.ground_beneath(left, right).map_or(f32::NEG_INFINITY, |column| column.solid.top)
+    }
+}
+
+/// Ground split into columns at one height each, with the default ground in between
+/// segments and beyond them; neighbours at the same height and surface are merged
+fn ground_columns(physics: &PhysicsConfig) -> Vec<GroundColumn> {
+    let mut sorted: Vec<&GroundSegment> = physics.ground_segments.iter().filter(|s| s.x_end > s.x_start).collect();
+    sorted.sort_by(|a, b| a.x_start.total_cmp(&b.x_start));
+    let column = |left: f32, right: f32, top: f32, surface: Option<Surface>, restitution: Option<f32>| {
+        let surface = surface.unwrap_or(physics.ground_surface);
+        GroundColumn {
+            solid: Solid { left, right, bottom: f32::NEG_INFINITY, top },
+            surface,
+            restitution: restitution.unwrap_or_else(|| physics.surfaces.get(surface).restitution),
+        }
+    };
+    let mut columns: Vec<GroundColumn> = Vec::new();
+    let mut push = |next: GroundColumn| match columns.last_mut() {
+        Some(last)
+            if last.solid.top == next.solid.top
+                && last.surface == next.surface
+                && last.restitution == next.restitution =>
+        {
+            last.solid.right = next.solid.right
+        }
+        _ => columns.push(next),
+    };
+    let mut covered_to = f32::NEG_INFINITY;
+    for segment in sorted {
+        // Overlaps are refused by validation; the earlier segment keeps its stretch
+        let left = segment.x_start.max(covered_to);
+        if segment.x_end <= left {
+            continue;
+        }
+        if left > covered_to {
+            push(column(covered_to, left, physics.ground_y, None, None));
+        }
+        push(column(left, segment.x_end, segment.y_top, segment.surface, segment.restitution));
+        covered_to = segment.x_end;
+    }
+    push(column(covered_to, f32::INFINITY, physics.ground_y, None, None));
+    columns
+}
+
+/// Indices of shapes, sorted by left edge, that could overlap `left..right`: nothing starting
+/// at or past `right`, and nothing starting so far left that even the widest shape ends first
+fn near(lefts: &[f32], widest: f32, left: f32, right: f32) -> std::ops::Range<usize> {
+    let start = lefts.partition_point(|x| *x + widest <= left);
+    let end = lefts.partition_point(|x| *x < right);
+    start..end.max(start)
+}
+
+fn fuse_platforms(platforms: &[PlatformConfig]) -> Vec<Span> {
+    let mut sorted: Vec<(u32, &PlatformConfig)> = platforms.iter().enumerate().map(|(idx, p)| (idx as u32, p)).collect();
+    sorted.sort_by(|(_, a), (_, b)| {
+        a.y_top
+            .total_cmp(&b.y_top)
+            .then(a.height.total_cmp(&b.height))
+            .then(a.x_start.total_cmp(&b.x_start))
+    });
+    let mut spans: Vec<Span> = Vec::new();
+    for (idx, platform) in sorted {
+        let solid = Solid {
+            left: platform.x_start,
+            right: platform.x_end,
+            bottom: platform.y_top - platform.height,
+            top: platform.y_top,
+        };
+        let source = (idx, platform.x_start, platform.x_end);
+        match spans.last_mut() {
+            Some(span)
+                if span.solid.top == solid.top
+                    && span.solid.bottom == solid.bottom
+                    && solid.left <= span.solid.right + TOUCHING =>
+            {
+                span.solid.right = span.solid.right.max(solid.right);
+                span.sources.push(source);
+            }
+            _ => spans.push(Span { solid, sources: vec![source] }),
+        }
+    }
+    spans.sort_by(|a, b| a.solid.left.total_cmp(&b.solid.left));
+    spans
+}
+
+fn fuse_walls(walls: &[WallConfig]) -> Vec<Solid> {
+    let mut solids: Vec<Solid> = walls
+        .iter()
+        .map(|wall| Solid {
+            left: wall.x,
+            right: wall.x + wall.width,
+            bottom: wall.y_bottom,
+            top: wall.y_top,
+        })
+        .collect();
+    // Stacked walls first, then walls side by side
+    solids.sort_by(|a, b| {
+        a.left
+            .total_cmp(&b.left)
+            .then(a.right.total_cmp(&b.right))
+            .then(a.bottom.total_cmp(&b.bottom))
+    });
+    let mut stacked: Vec<Solid> = Vec::new();
+    for wall in solids {
+        match stacked.last_mut() {
+            Some(run) if run.left == wall.left && run.right == wall.right && wall.bottom <= run.top + TOUCHING => {
+                run.top = run.top.max(wall.top);
+            }
+            _ => stacked.push(wall),
+        }
+    }
+    stacked.sort_by(|a, b| {
+        a.bottom
+            .total_cmp(&b.bottom)
+            .then(a.top.total_cmp(&b.top))
+            .then(a.left.total_cmp(&b.left))
+    });
+    let mut fused: Vec<Solid> = Vec::new();
+    for wall in stacked {
+        match fused.last_mut() {
+            Some(run) if run.bottom == wall.bottom && run.top == wall.top && wall.left <= run.right + TOUCHING => {
+                run.right = run.right.max(wall.right);
+            }
+            _ => fused.push(wall),
+        }
+    }
+    fused.sort_by(|a, b| a.left.total_cmp(&b.left));
+    fused
+}
@@ -1,11 +1,139 @@
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(tag = "type")]
 pub enum PlayerCommand {
     MoveLeft,
     MoveRight,
     Jump,
     Stop,
+    /// Climb up a ladder the player overlaps
+    MoveUp,
+    /// Climb down a ladder the player overlaps
+    MoveDown,
+    /// Duck to the crouch height; at speed this starts a ground slide
+    Crouch,
+    /// Stand back up from a crouch as soon as there is headroom
+    StandUp,
+    /// Run at the sprint speed while stamina lasts
+    Sprint,
+    /// Go back to the normal top speed
+    StopSprint,
+    /// Burst forward in the facing direction, spending stamina
+    Dash,
+    /// Place a block in the grid cell containing world position (x, y)
+    PlaceBlock { x: f32, y: f32 },
+    /// Remove the player's own block from the grid cell containing (x, y)
+    RemoveBlock { x: f32, y: f32 },
+    /// Fire a projectile along (dir_x, dir_y); the direction need not be normalized
+    Shoot { dir_x: f32, dir_y: f32 },
+    /// Start holding a direction: the player accelerates that way every tick until it is
+    /// released, another direction is held, or it isn't refreshed within the timeout
+    KeyDown { key: HeldKey },
+    /// Let go of a held direction, stopping like `Stop`; ignored if another is held instead
+    KeyUp { key: HeldKey },
+    /// Steer with an analog stick: accelerate like `MoveRight` scaled by `axis`, clamped to
+    /// -1..1, so -1 is `MoveLeft` and 1 is `MoveRight`
+    Move { axis: f32 },
 }
 
+impl PlayerCommand {
+    /// How hard the command steers left (negative) or right (positive), if it steers at all
+    pub fn move_axis(&self) -> Option<f32> {
+        match self {
+            PlayerCommand::MoveLeft => Some(-1.0),
+            PlayerCommand::MoveRight => Some(1.0),
+            PlayerCommand::Move { axis } if axis.is_finite() => Some(axis.clamp(-1.0, 1.0)),
+            PlayerCommand::Move { .. } => Some(0.0),
+            _ => None,
+        }
+    }
+}
+
+/// A direction that can be held down
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum HeldKey {
+    Left,
+    Right,
+}
+
+impl HeldKey {
+    /// -1.0 for left, 1.0 for right
+    pub fn sign(self) -> f32 {
+        match self {
+            HeldKey::Left => -1.0,
+            HeldKey::Right => 1.0,
+        }
+    }
+}
+
+
+/// Why a command did nothing
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(tag = "code", content = "detail", rename_all = "snake_case")]
+pub enum IgnoredReason {
+    /// Player is not in the game
+    UnknownPlayer,
+    /// Sequence number already processed
+    Stale,
+    /// Dead players sit out until they respawn
+    Dead,
+    /// Steering while flying through the air
+    Flying,
+    /// Jumping or crouching needs the ground underfoot
+    NotGrounded,
+    /// Crouched players can't jump, climb or dash
+    Crouched,
+    /// Not standing up from a crouch
+    NotCrouched,
+    /// No ladder within reach
+    NoLadder,
+    /// Not enough stamina left
+    NoStamina,
+    /// A dash is still under way
+    Dashing,
+    /// Can't dash off a ladder
+    Climbing,
+    /// Letting go of a direction while another is held
+    NotHeld,
+    /// The game mode's rules don't allow it
+    ModeRules,
+    Build(crate::blocks::BuildError),
+    Shoot(crate::projectiles::ShootError),
+}
+
+/// What a command did, or why it did nothing
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum CommandOutcome {
+    Accepted,
+    /// A jump pressed in the air, held to go off if the player lands in time
+    Buffered,
+    Ignored { reason: IgnoredReason },
+}
+
+impl CommandOutcome {
+    pub fn ignored(reason: IgnoredReason) -> Self {
+        Self::Ignored { reason }
+    }
+
+    pub fn is_accepted(&self) -> bool {
+        matches!(self, Self::Accepted)
+    }
+}
+
+/// Milliseconds until each limited action can be used again
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct Cooldowns {
+    pub dash_ms: u64,
+    pub shoot_ms: u64,
+    pub place_block_ms: u64,
+}
+
+/// Reply to a command: its outcome and the cooldowns it leaves the player with
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct CommandAck {
+    #[serde(flatten)]
+    pub outcome: CommandOutcome,
+    pub cooldowns: Cooldowns,
+}
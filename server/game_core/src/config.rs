@@ -1,76 +1,2407 @@
 use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use crate::bots::BehaviorKind;
+use crate::mutators::Mutator;
+use crate::encoding::Encoding;
+use crate::world_events::WorldEventKind;
+
+pub mod tiled;
+pub mod validate;
+
+pub use validate::{ValidationError, ValidationErrors};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GameConfig {
+    /// Optional URL to fetch configuration from remotely
+    /// If set, the server will fetch config from this URL instead of using local file
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub remote_config: Option<String>,
+    /// Idle timeout in seconds - players inactive for this duration will be disconnected
+    /// Default: 180 seconds (3 minutes)
+    #[serde(default = "default_idle_timeout")]
+    pub idle_timeout: u64,
+    /// Optional JSON-lines file for persisting completed match records
+    /// If unset, match history is kept in memory only
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub match_history_path: Option<String>,
+    /// Simulation rate in Hz (fixed physics steps per second)
+    #[serde(default = "default_tick_rate_hz")]
+    pub tick_rate_hz: f32,
+    /// Rate in Hz at which full state is broadcast to clients
+    /// Geometry and gameplay events are still sent on the tick they happen
+    #[serde(default = "default_broadcast_rate_hz")]
+    pub broadcast_rate_hz: f32,
+    /// Number of recent chat messages replayed to clients when they connect
+    #[serde(default = "default_chat_history_size")]
+    pub chat_history_size: usize,
+    /// Seconds an SSE resume token stays valid after its stream disconnects
+    #[serde(default = "default_resume_ttl_secs")]
+    pub resume_ttl_secs: u64,
+    /// Seconds clients are warned before the server shuts down on SIGTERM/SIGINT
+    #[serde(default = "default_shutdown_grace_secs")]
+    pub shutdown_grace_secs: u64,
+    /// State rate in Hz for clients streaming in snapshot (interpolation) mode
+    #[serde(default = "default_snapshot_rate_hz")]
+    pub snapshot_rate_hz: f32,
+    pub physics: PhysicsConfig,
+    /// Map file whose platforms, walls, ladders, hazards and spawn points replace the ones
+    /// below; relative paths are resolved against the config file's directory
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub map_file: Option<String>,
+    /// Format of `map_file`; detected from its extension when unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub map_format: Option<MapFormat>,
+    /// Maps played in rotation; when set the game starts on the first one
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub maps: Vec<MapConfig>,
+    /// Directory whose map files join the rotation after `maps`, in file name order
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub maps_dir: Option<String>,
+    pub platforms: Vec<PlatformConfig>,
+    pub walls: Vec<WallConfig>,
+    /// Regions players can climb with MoveUp and MoveDown
+    #[serde(default)]
+    pub ladders: Vec<LadderConfig>,
+    /// Sandbox building rules (runtime block placement)
+    #[serde(default)]
+    pub building: BuildingConfig,
+    /// Combo multiplier rules for scoring
+    #[serde(default)]
+    pub combo: ComboConfig,
+    /// Optional content moderation provider for chat messages and player names
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub moderation: Option<ModerationConfig>,
+    /// Per-IP connection and player creation limits
+    #[serde(default)]
+    pub limits: LimitsConfig,
+    /// Reverse-proxy settings used to determine the real client IP
+    #[serde(default)]
+    pub proxy: ProxyConfig,
+    /// Display name rules
+    #[serde(default)]
+    pub names: NameConfig,
+    /// Chat filtering, rate limiting and mute escalation
+    #[serde(default)]
+    pub chat: ChatConfig,
+    /// Admin API authentication
+    #[serde(default)]
+    pub admin: AdminConfig,
+    /// Session tokens for reclaiming a player after a dropped connection
+    #[serde(default)]
+    pub session: SessionConfig,
+    /// Keepalives and heartbeats that tell idle players from disconnected ones
+    #[serde(default)]
+    pub presence: PresenceConfig,
+    /// Room capacity and the spectator queue beyond it
+    #[serde(default)]
+    pub room: RoomConfig,
+    /// Recurring banner announcements
+    #[serde(default)]
+    pub announcements: Vec<ScheduledAnnouncement>,
+    /// Storage for per-player client settings
+    #[serde(default)]
+    pub player_settings: PlayerSettingsConfig,
+    /// Teams players are split into; an empty list disables teams
+    #[serde(default)]
+    pub teams: TeamsConfig,
+    /// Language for server-generated text when a player hasn't chosen one
+    #[serde(default)]
+    pub default_language: crate::language::Language,
+    /// Timed matches: lobby, countdown, play and results
+    #[serde(default)]
+    pub matches: MatchConfig,
+    /// Rules the game is played by, named as the registered `GameMode` names them; the
+    /// active entry in `modes` can switch them
+    #[serde(default)]
+    pub game_mode: GameRules,
+    /// Laps in race mode
+    #[serde(default)]
+    pub race: RaceConfig,
+    /// Immunity and scoring in tag mode
+    #[serde(default)]
+    pub tag: TagConfig,
+    /// Capture time, scoring and the score cap in king of the hill
+    #[serde(default)]
+    pub king_of_the_hill: KingOfTheHillConfig,
+    /// Colors used for players who pick the color-blind palette
+    #[serde(default)]
+    pub color_blind_palette: ColorBlindPaletteConfig,
+    /// Where players enter the game and respawn; empty spawns at x = 0 on the ground
+    #[serde(default)]
+    pub spawn_points: Vec<SpawnPoint>,
+    /// Kill plane, map bounds and respawn delay
+    #[serde(default)]
+    pub respawn: RespawnConfig,
+    /// Shooting: projectile flight, hit damage and knockback
+    #[serde(default)]
+    pub projectiles: ProjectileConfig,
+    /// Per-subscriber state rate that backs off for clients falling behind
+    #[serde(default)]
+    pub adaptive_rate: AdaptiveRateConfig,
+    /// Player health, post-hit invulnerability and stomps
+    #[serde(default)]
+    pub health: HealthConfig,
+    /// Areas that damage players who touch them
+    #[serde(default)]
+    pub hazards: Vec<HazardConfig>,
+    /// Areas that push players inside them: updrafts, conveyors and wind tunnels
+    #[serde(default)]
+    pub force_zones: Vec<ForceZoneConfig>,
+    /// Areas that move players who enter them to another portal
+    #[serde(default)]
+    pub portals: Vec<PortalConfig>,
+    /// Race course, touched in order; the last checkpoint is the finish line
+    #[serde(default)]
+    pub checkpoints: Vec<CheckpointConfig>,
+    /// The hill fought over in king of the hill
+    #[serde(default)]
+    pub control_zone: Option<ControlZoneConfig>,
+    /// Cosmetic catalog and where players' unlocks are stored
+    #[serde(default)]
+    pub cosmetics: CosmeticsConfig,
+    /// Where tombstones for deleted player data are kept
+    #[serde(default)]
+    pub privacy: PrivacyConfig,
+    /// Queue sizes and overflow behaviour of the admin, gameplay and cosmetic command lanes
+    #[serde(default)]
+    pub command_lanes: CommandLanesConfig,
+    /// Instances sharing a load balancer, for routing each player to the one owning their room
+    #[serde(default)]
+    pub cluster: ClusterConfig,
+    /// When the map changes between matches and whether players vote on the next one
+    #[serde(default)]
+    pub map_rotation: MapRotationConfig,
+    /// Presets admins can switch to between rounds, each picking the rules and overriding
+    /// settings such as match length; `matches.mode` names the active one
+    #[serde(default)]
+    pub modes: Vec<GameModeConfig>,
+    /// Server-rendered HUD fragments on event streams that ask for them
+    #[serde(default)]
+    pub hud: HudConfig,
+    /// Live stats stream for caster and broadcast overlays
+    #[serde(default)]
+    pub overlay: OverlayConfig,
+    /// Server-controlled players that fill out the room
+    #[serde(default)]
+    pub bots: BotsConfig,
+    /// Recording matches and turning the recordings into shareable summaries
+    #[serde(default)]
+    pub replays: ReplayConfig,
+    /// Seeding the room's randomness so a command log replays to the same state
+    #[serde(default)]
+    pub determinism: DeterminismConfig,
+    /// Round-long rule changes such as low gravity, and how players choose them
+    #[serde(default)]
+    pub mutators: MutatorConfig,
+    /// Plots in the hub players can claim and build houses in
+    #[serde(default)]
+    pub housing: HousingConfig,
+    /// Sharing one simulation between several API instances over Redis pub/sub
+    #[serde(default)]
+    pub backplane: BackplaneConfig,
+    /// Random events such as coin rain and meteor showers started by the server
+    #[serde(default)]
+    pub world_events: WorldEventsConfig,
+    /// Resyncing and dropping event streams that fall behind the broadcast
+    #[serde(default)]
+    pub backpressure: BackpressureConfig,
+    /// Timing commands from request to the broadcast that first shows them
+    #[serde(default)]
+    pub latency_tracing: LatencyTracingConfig,
+    /// Replays of their last moments sent to players killed by other players
+    #[serde(default)]
+    pub kill_cam: KillCamConfig,
+    /// Encoding of state frames sent over the WebSocket and HTTP state transport
+    #[serde(default)]
+    pub state_transport: StateTransportConfig,
+    /// Which physics settings a room's creator may change live, and how far
+    #[serde(default)]
+    pub physics_sandbox: PhysicsSandboxConfig,
+    /// Compressing the /events stream for clients that accept it
+    #[serde(default)]
+    pub stream_compression: StreamCompressionConfig,
+    /// Flagging players who look to be cheating, with a replay of their inputs for review
+    #[serde(default)]
+    pub anti_cheat: AntiCheatConfig,
+    /// Origins allowed to call the API from other sites
+    #[serde(default)]
+    pub cors: CorsConfig,
+    /// Serving HTTPS directly, without a reverse proxy in front
+    #[serde(default)]
+    pub tls: TlsConfig,
+}
+
+fn default_idle_timeout() -> u64 {
+    180 // 3 minutes default
+}
+
+fn default_shutdown_grace_secs() -> u64 {
+    10
+}
+
+fn default_resume_ttl_secs() -> u64 {
+    60
+}
+
+fn default_chat_history_size() -> usize {
+    50
+}
+
+fn default_tick_rate_hz() -> f32 {
+    60.0
+}
+
+fn default_broadcast_rate_hz() -> f32 {
+    60.0
+}
+
+fn default_snapshot_rate_hz() -> f32 {
+    20.0
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PhysicsConfig {
+    pub gravity: f32,
+    pub jump_velocity: f32,
+    pub move_acceleration: f32,
+    pub move_deceleration: f32,
+    pub max_horizontal_velocity: f32,
+    pub ground_y: f32,
+    pub player_width: f32,
+    pub player_height: f32,
+    /// Friction when sliding on ground (applied to horizontal velocity)
+    pub ground_slide_friction: f32,
+    /// Friction when sliding on platforms (applied to horizontal velocity)
+    pub platform_slide_friction: f32,
+    /// Ground color as hex string (e.g., "#8B6F47")
+    pub ground_color: String,
+    /// Stretches of ground at their own height, for uneven terrain; `ground_y` and
+    /// `ground_color` apply wherever no segment does
+    #[serde(default)]
+    pub ground_segments: Vec<GroundSegment>,
+    /// Collision height while crouched, low enough to pass under low platforms
+    #[serde(default = "default_crouch_height")]
+    pub crouch_height: f32,
+    /// Horizontal speed at or above which crouching starts a ground slide
+    #[serde(default = "default_slide_min_speed")]
+    pub slide_min_speed: f32,
+    /// Speed multiplier applied when a ground slide starts
+    #[serde(default = "default_slide_boost")]
+    pub slide_boost: f32,
+    /// Seconds a ground slide ignores friction
+    #[serde(default = "default_slide_secs")]
+    pub slide_secs: f32,
+    /// Size of each player's stamina pool, which sprinting, dashing and wall jumps spend
+    #[serde(default = "default_max_stamina")]
+    pub max_stamina: f32,
+    /// Stamina regained per second while on the ground and not sprinting
+    #[serde(default = "default_stamina_regen_per_sec")]
+    pub stamina_regen_per_sec: f32,
+    /// Top speed and acceleration multiplier while sprinting
+    #[serde(default = "default_sprint_multiplier")]
+    pub sprint_multiplier: f32,
+    /// Stamina spent per second of running while sprinting
+    #[serde(default = "default_sprint_cost_per_sec")]
+    pub sprint_cost_per_sec: f32,
+    /// Horizontal speed a dash launches the player at
+    #[serde(default = "default_dash_speed")]
+    pub dash_speed: f32,
+    /// Seconds a dash may exceed the top speed without friction
+    #[serde(default = "default_dash_secs")]
+    pub dash_secs: f32,
+    /// Stamina a dash costs
+    #[serde(default = "default_dash_cost")]
+    pub dash_cost: f32,
+    /// Stamina a wall jump costs
+    #[serde(default = "default_wall_jump_cost")]
+    pub wall_jump_cost: f32,
+    /// Horizontal speed a wall jump pushes the player away from the wall at
+    #[serde(default = "default_wall_jump_push")]
+    pub wall_jump_push: f32,
+    /// Seconds a jump pressed in the air is held, to go off on landing; zero drops it
+    #[serde(default = "default_jump_buffer_secs")]
+    pub jump_buffer_secs: f32,
+    /// Seconds a held direction lasts without being refreshed, in case its KeyUp is lost
+    #[serde(default = "default_held_input_timeout_secs")]
+    pub held_input_timeout_secs: f32,
+    /// Surface of the ground wherever no segment gives its own
+    #[serde(default)]
+    pub ground_surface: Surface,
+    /// How each kind of surface grips and bounces
+    #[serde(default)]
+    pub surfaces: SurfacePresets,
+    /// Slowest landing that bounces off a bouncy surface; slower ones settle. Never below
+    /// two ticks of gravity, so players standing on a trampoline don't jitter
+    #[serde(default = "default_bounce_min_speed")]
+    pub bounce_min_speed: f32,
+}
+
+/// What a platform or stretch of ground is made of, which sets how it grips and bounces
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Surface {
+    #[default]
+    Normal,
+    /// Slippery: slow to get going and slow to stop
+    Ice,
+    /// Sticky: slow to get going and quick to stop
+    Mud,
+    /// A trampoline, throwing landing players back up
+    Bouncy,
+}
+
+impl Surface {
+    pub fn is_normal(&self) -> bool {
+        *self == Surface::Normal
+    }
+}
+
+/// How a surface changes movement on it
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct SurfaceParams {
+    /// Multiplier on ground friction; below 1 a Stop lets the player slide on instead
+    /// of halting, keeping `1 - friction` of their speed
+    pub friction: f32,
+    /// Multiplier on movement acceleration
+    pub acceleration: f32,
+    /// Fraction of landing speed thrown back upward; 0 lands dead
+    pub restitution: f32,
+}
+
+/// Presets for each surface type
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SurfacePresets {
+    pub normal: SurfaceParams,
+    pub ice: SurfaceParams,
+    pub mud: SurfaceParams,
+    pub bouncy: SurfaceParams,
+}
+
+impl SurfacePresets {
+    pub fn get(&self, surface: Surface) -> &SurfaceParams {
+        match surface {
+            Surface::Normal => &self.normal,
+            Surface::Ice => &self.ice,
+            Surface::Mud => &self.mud,
+            Surface::Bouncy => &self.bouncy,
+        }
+    }
+}
+
+impl Default for SurfacePresets {
+    fn default() -> Self {
+        Self {
+            normal: SurfaceParams { friction: 1.0, acceleration: 1.0, restitution: 0.0 },
+            ice: SurfaceParams { friction: 0.1, acceleration: 0.3, restitution: 0.0 },
+            mud: SurfaceParams { friction: 4.0, acceleration: 0.4, restitution: 0.0 },
+            bouncy: SurfaceParams { friction: 1.0, acceleration: 1.0, restitution: 0.8 },
+        }
+    }
+}
+
+/// A stretch of ground whose surface sits at its own height
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GroundSegment {
+    pub x_start: f32,
+    pub x_end: f32,
+    /// Height of the surface, in place of `ground_y`
+    pub y_top: f32,
+    /// Color as hex string; the physics `ground_color` when unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub color: Option<String>,
+    /// Surface of this stretch; the physics `ground_surface` when unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub surface: Option<Surface>,
+    /// Bounciness in place of the surface's
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub restitution: Option<f32>,
+}
+
+impl PhysicsConfig {
+    /// Height of the ground at `x`
+    pub fn ground_at(&self, x: f32) -> f32 {
+        self.ground_segments
+            .iter()
+            .find(|segment| x >= segment.x_start && x < segment.x_end)
+            .map_or(self.ground_y, |segment| segment.y_top)
+    }
+
+    /// Surface of the ground at `x`
+    pub fn ground_surface_at(&self, x: f32) -> Surface {
+        self.segment_at(x).and_then(|segment| segment.surface).unwrap_or(self.ground_surface)
+    }
+
+    /// Fraction of landing speed the ground at `x` throws back up
+    pub fn ground_restitution_at(&self, x: f32) -> f32 {
+        self.segment_at(x)
+            .and_then(|segment| segment.restitution)
+            .unwrap_or_else(|| self.surfaces.get(self.ground_surface_at(x)).restitution)
+    }
+
+    fn segment_at(&self, x: f32) -> Option<&GroundSegment> {
+        self.ground_segments.iter().find(|segment| x >= segment.x_start && x < segment.x_end)
+    }
+
+    /// Height of the lowest ground anywhere on the map
+    pub fn lowest_ground(&self) -> f32 {
+        self.ground_segments.iter().map(|segment| segment.y_top).fold(self.ground_y, f32::min)
+    }
+}
+
+fn default_crouch_height() -> f32 {
+    0.75
+}
+
+fn default_slide_min_speed() -> f32 {
+    6.0
+}
+
+fn default_slide_boost() -> f32 {
+    1.5
+}
+
+fn default_slide_secs() -> f32 {
+    0.5
+}
+
+pub const DEFAULT_MAX_STAMINA: f32 = 100.0;
+
+fn default_max_stamina() -> f32 {
+    DEFAULT_MAX_STAMINA
+}
+
+fn default_stamina_regen_per_sec() -> f32 {
+    25.0
+}
+
+fn default_sprint_multiplier() -> f32 {
+    1.5
+}
+
+fn default_sprint_cost_per_sec() -> f32 {
+    20.0
+}
+
+fn default_dash_speed() -> f32 {
+    20.0
+}
+
+fn default_dash_secs() -> f32 {
+    0.2
+}
+
+fn default_dash_cost() -> f32 {
+    30.0
+}
+
+fn default_wall_jump_cost() -> f32 {
+    20.0
+}
+
+fn default_wall_jump_push() -> f32 {
+    10.0
+}
+
+fn default_jump_buffer_secs() -> f32 {
+    0.1
+}
+
+fn default_bounce_min_speed() -> f32 {
+    10.0
+}
+
+fn default_held_input_timeout_secs() -> f32 {
+    0.5
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlatformConfig {
+    pub id: String,
+    pub x_start: f32,
+    pub x_end: f32,
+    pub y_top: f32,
+    pub height: f32,
+    /// Platform color as hex string (e.g., "#B34733")
+    pub color: String,
+    /// What the platform is made of; normal when unset
+    #[serde(default, skip_serializing_if = "Surface::is_normal")]
+    pub surface: Surface,
+    /// Bounciness in place of the surface's, for one-off trampolines
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub restitution: Option<f32>,
+}
+
+impl PlatformConfig {
+    /// Fraction of landing speed the platform throws back up
+    pub fn restitution(&self, surfaces: &SurfacePresets) -> f32 {
+        self.restitution.unwrap_or_else(|| surfaces.get(self.surface).restitution)
+    }
+}
+
+/// How a map file is laid out
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MapFormat {
+    /// This game's own geometry fields, as in the main config
+    Native,
+    /// A map saved as JSON by the Tiled editor; see [`tiled`]
+    Tiled,
+}
+
+impl MapFormat {
+    /// Guess a map's format: .tmj files and JSON written by Tiled are Tiled maps
+    pub fn detect(path: &Path, contents: &str) -> Self {
+        let extension = path.extension().and_then(|e| e.to_str()).unwrap_or("").to_ascii_lowercase();
+        let written_by_tiled = || {
+            serde_json::from_str::<serde_json::Value>(contents).is_ok_and(|map| map.get("tiledversion").is_some())
+        };
+        if extension == "tmj" || extension == "tmx" || written_by_tiled() {
+            MapFormat::Tiled
+        } else {
+            MapFormat::Native
+        }
+    }
+}
+
+/// Everything a map file can provide; it replaces the config's own geometry
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MapGeometry {
+    pub platforms: Vec<PlatformConfig>,
+    pub walls: Vec<WallConfig>,
+    pub ladders: Vec<LadderConfig>,
+    pub hazards: Vec<HazardConfig>,
+    pub force_zones: Vec<ForceZoneConfig>,
+    pub portals: Vec<PortalConfig>,
+    pub checkpoints: Vec<CheckpointConfig>,
+    pub control_zone: Option<ControlZoneConfig>,
+    pub spawn_points: Vec<SpawnPoint>,
+}
+
+/// One map in the rotation, with its geometry inline or in a map file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MapConfig {
+    pub id: String,
+    /// Name shown to players; defaults to the id
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// Map file relative to the config file, read at startup into `geometry`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub file: Option<String>,
+    /// Format of `file`, detected from it when unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub format: Option<MapFormat>,
+    #[serde(flatten)]
+    pub geometry: MapGeometry,
+}
+
+impl MapConfig {
+    pub fn display_name(&self) -> &str {
+        self.name.as_deref().unwrap_or(&self.id)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MapRotationConfig {
+    /// Matches played on a map before moving to the next one in order
+    pub matches_per_map: u32,
+    /// Let players vote for the next map; the most votes wins when the results end
+    pub voting: bool,
+}
+
+impl Default for MapRotationConfig {
+    fn default() -> Self {
+        Self {
+            matches_per_map: 1,
+            voting: true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HudConfig {
+    /// Times per second HUD fragments are re-rendered; unchanged ones aren't resent
+    pub rate_hz: f32,
+}
+
+impl Default for HudConfig {
+    fn default() -> Self {
+        Self { rate_hz: 2.0 }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct OverlayConfig {
+    /// Times per second the overlay stream re-renders its stats
+    pub rate_hz: f32,
+    /// Columns the map is split into for the player density strip
+    pub density_columns: usize,
+}
+
+impl Default for OverlayConfig {
+    fn default() -> Self {
+        Self {
+            rate_hz: 1.0,
+            density_columns: 12,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BotsConfig {
+    /// Bots spawned when the server starts
+    pub count: usize,
+    /// Behaviors handed out to bots in turn
+    pub behaviors: Vec<BehaviorKind>,
+    /// Times per second each bot decides what to do; 10 matches a client holding a key
+    pub think_hz: f32,
+}
+
+impl Default for BotsConfig {
+    fn default() -> Self {
+        Self {
+            count: 0,
+            behaviors: vec![BehaviorKind::Patrol, BehaviorKind::Chase, BehaviorKind::Jumper],
+            think_hz: 10.0,
+        }
+    }
+}
+
+impl BotsConfig {
+    /// Behavior for the `index`th bot, cycling through the configured ones
+    pub fn behavior(&self, index: usize) -> BehaviorKind {
+        if self.behaviors.is_empty() {
+            return BehaviorKind::Patrol;
+        }
+        self.behaviors[index % self.behaviors.len()]
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ReplayConfig {
+    /// Directory replays are saved to, one file per match; None turns recording off
+    pub dir: Option<String>,
+    /// Times per second player positions are recorded
+    pub sample_hz: f32,
+    /// Background workers turning replays into summaries
+    pub workers: usize,
+    /// Whether summaries include an SVG of every player's path
+    pub render_svg: bool,
+    /// Ticks between player snapshots in the binary match log
+    pub snapshot_every_ticks: u64,
+    /// Fastest a recorded match can be played back, as a multiple of real time
+    pub max_playback_speed: f32,
+}
+
+impl Default for ReplayConfig {
+    fn default() -> Self {
+        Self {
+            dir: None,
+            sample_hz: 5.0,
+            workers: 2,
+            render_svg: true,
+            snapshot_every_ticks: 6,
+            max_playback_speed: 8.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DeterminismConfig {
+    /// Seed for every random choice the simulation makes; None seeds from the system time
+    pub seed: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MutatorConfig {
+    /// Whether players may vote for the next round's mutator
+    pub voting: bool,
+    /// Gravity multiplier under low gravity
+    pub low_gravity_scale: f32,
+    /// Acceleration and top speed multiplier under double speed
+    pub speed_multiplier: f32,
+    /// How much bigger clients draw heads under big heads
+    pub big_head_scale: f32,
+}
+
+impl Default for MutatorConfig {
+    fn default() -> Self {
+        Self {
+            voting: true,
+            low_gravity_scale: 0.4,
+            speed_multiplier: 2.0,
+            big_head_scale: 2.0,
+        }
+    }
+}
+
+/// A row of equal plots along the hub floor
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HousingConfig {
+    /// Whether players may claim plots
+    pub enabled: bool,
+    /// JSON file claims and the blocks built in them are persisted to; None keeps them in memory only
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+    pub count: usize,
+    /// Left edge of the first plot
+    pub origin_x: f32,
+    /// Bottom edge of every plot, normally the ground
+    pub origin_y: f32,
+    pub plot_width: f32,
+    pub plot_height: f32,
+    /// Space between neighbouring plots
+    pub gap: f32,
+    /// Blocks a plot holds; they don't count toward the owner's building budget
+    pub max_blocks_per_plot: usize,
+}
+
+impl Default for HousingConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            path: None,
+            count: 5,
+            origin_x: -19.5,
+            origin_y: -10.0,
+            plot_width: 6.0,
+            plot_height: 6.0,
+            gap: 1.0,
+            max_blocks_per_plot: 40,
+        }
+    }
+}
+
+/// Distributed mode: one elected instance simulates, every instance serves its streams
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BackplaneConfig {
+    /// Off unless turned on, so a Redis URL set for something else doesn't put the server
+    /// in distributed mode
+    pub enabled: bool,
+    /// Environment variable holding the Redis URL
+    pub url_env: String,
+    /// URL used when the environment variable is unset; `memory://<name>` shares an
+    /// in-process bus between instances in one process. Without either, runs standalone
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    /// Prefix of the channels and the leader key, so several games can share one Redis
+    pub channel_prefix: String,
+    /// How long the leader's claim lasts without renewal; a follower takes over after this
+    pub leader_lease_ms: u64,
+    /// Run as a warm standby: mirror the leader but never take over on its own, only when
+    /// an admin promotes this instance
+    pub standby: bool,
+    /// Environment variable that turns standby on ("1" or "true") or off, overriding `standby`
+    pub standby_env: String,
+    /// How often the leader publishes its whole world, which followers keep to carry on
+    /// from if they take over; state broadcasts in between only bring what clients see
+    pub checkpoint_interval_ms: u64,
+}
+
+impl Default for BackplaneConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            url_env: "BACKPLANE_URL".to_string(),
+            url: None,
+            channel_prefix: "game".to_string(),
+            leader_lease_ms: 5000,
+            standby: false,
+            standby_env: "STANDBY".to_string(),
+            checkpoint_interval_ms: 1000,
+        }
+    }
+}
+
+impl BackplaneConfig {
+    /// The backplane URL from the environment or config, if enabled and one is set
+    pub fn resolve_url(&self) -> Option<String> {
+        if !self.enabled {
+            return None;
+        }
+        std::env::var(&self.url_env)
+            .ok()
+            .or_else(|| self.url.clone())
+            .filter(|url| !url.is_empty())
+    }
+
+    /// Whether this instance starts as a standby, from the environment or config
+    pub fn resolve_standby(&self) -> bool {
+        match std::env::var(&self.standby_env) {
+            Ok(value) => matches!(value.trim().to_ascii_lowercase().as_str(), "1" | "true" | "yes"),
+            Err(_) => self.standby,
+        }
+    }
+}
+
+/// Scheduled random events and what each one drops into the world
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WorldEventsConfig {
+    /// Whether the server starts events on its own; admins can start one either way
+    pub enabled: bool,
+    /// Seconds between one event ending and the next starting, drawn uniformly
+    pub min_interval_secs: f32,
+    pub max_interval_secs: f32,
+    pub duration_secs: f32,
+    /// Events the scheduler picks from
+    pub kinds: Vec<WorldEventKind>,
+    /// Entities drop at a random x between these, from height `spawn_y`
+    pub spawn_min_x: f32,
+    pub spawn_max_x: f32,
+    pub spawn_y: f32,
+    pub coins_per_sec: f32,
+    pub coin_fall_speed: f32,
+    /// Seconds a coin stays after landing
+    pub coin_lifetime_secs: f32,
+    pub meteors_per_sec: f32,
+    pub meteor_fall_speed: f32,
+    pub meteor_damage: u32,
+    /// Radius of coins and meteors for landing and pickup
+    pub entity_radius: f32,
+    /// Stamina regeneration multiplier during a speed frenzy; sprinting and dashing are free
+    pub frenzy_regen_multiplier: f32,
+}
+
+impl Default for WorldEventsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            min_interval_secs: 60.0,
+            max_interval_secs: 120.0,
+            duration_secs: 15.0,
+            kinds: WorldEventKind::ALL.to_vec(),
+            spawn_min_x: -20.0,
+            spawn_max_x: 20.0,
+            spawn_y: 6.0,
+            coins_per_sec: 2.0,
+            coin_fall_speed: 3.0,
+            coin_lifetime_secs: 8.0,
+            meteors_per_sec: 1.0,
+            meteor_fall_speed: 12.0,
+            meteor_damage: 30,
+            entity_radius: 0.4,
+            frenzy_regen_multiplier: 3.0,
+        }
+    }
+}
+
+/// Streams whose subscriber can't keep up miss broadcasts; each miss gets the stream a full
+/// state resync, and too many in a row close it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BackpressureConfig {
+    /// Misses within `lag_window_secs` that close the stream; 0 never closes it
+    pub max_lags: u32,
+    pub lag_window_secs: u64,
+}
+
+impl Default for BackpressureConfig {
+    fn default() -> Self {
+        Self {
+            max_lags: 3,
+            lag_window_secs: 30,
+        }
+    }
+}
+
+/// Input-to-broadcast latency tracing of player commands
+/// Traced commands are logged stage by stage and kept for /api/admin/latency
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LatencyTracingConfig {
+    pub enabled: bool,
+    /// Trace one in this many commands; commands sent with an X-Trace-Id header always are
+    pub sample_every: u32,
+    /// Finished traces kept for the admin API
+    pub keep: usize,
+}
+
+impl Default for LatencyTracingConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            sample_every: 1,
+            keep: 256,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct KillCamConfig {
+    /// Whether killed players are sent a replay of their last moments
+    pub enabled: bool,
+    /// Seconds of movement and input kept per player, and the length of a replay
+    pub history_secs: f32,
+}
+
+impl Default for KillCamConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            history_secs: 1.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StateTransportConfig {
+    /// Encoding for clients that don't ask for one ("json" or "msgpack")
+    pub encoding: Encoding,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AntiCheatConfig {
+    pub enabled: bool,
+    /// Commands a player may send in any one second before they are flagged
+    pub max_inputs_per_sec: u32,
+    /// How far past the fastest legal speed a player may move before they are flagged
+    pub speed_tolerance: f32,
+    /// Seconds of each player's frames kept, and replayed when they are flagged
+    pub history_secs: f32,
+    /// Distance a replay may drift from the recording and still count as consistent
+    pub replay_tolerance: f32,
+    /// Seconds before the same player can be flagged again
+    pub flag_cooldown_secs: f32,
+}
+
+impl Default for AntiCheatConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            max_inputs_per_sec: 40,
+            speed_tolerance: 1.5,
+            history_secs: 1.0,
+            replay_tolerance: 2.0,
+            flag_cooldown_secs: 60.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StreamCompressionConfig {
+    /// Turn off behind proxies that buffer compressed responses until they end
+    pub enabled: bool,
+    pub gzip: bool,
+    pub brotli: bool,
+}
+
+impl Default for StreamCompressionConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            gzip: true,
+            brotli: true,
+        }
+    }
+}
+
+/// Lowest and highest value a sandboxed physics setting may be given
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct PhysicsBounds {
+    pub min: f32,
+    pub max: f32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PhysicsSandboxConfig {
+    /// Whether the room's creator may change physics while the room runs
+    pub enabled: bool,
+    /// Bounds by physics setting name; settings not listed can't be changed
+    pub bounds: std::collections::BTreeMap<String, PhysicsBounds>,
+}
+
+impl Default for PhysicsSandboxConfig {
+    fn default() -> Self {
+        let bounds = [
+            ("gravity", -300.0, -20.0),
+            ("jump_velocity", 10.0, 120.0),
+            ("move_acceleration", 20.0, 400.0),
+            ("move_deceleration", 20.0, 400.0),
+            ("max_horizontal_velocity", 20.0, 300.0),
+            ("ground_slide_friction", 0.0, 50.0),
+            ("platform_slide_friction", 0.0, 50.0),
+        ];
+        Self {
+            enabled: false,
+            bounds: bounds
+                .into_iter()
+                .map(|(field, min, max)| (field.to_string(), PhysicsBounds { min, max }))
+                .collect(),
+        }
+    }
+}
+
+impl MapGeometry {
+    pub fn parse(
+        path: &Path,
+        contents: &str,
+        format: MapFormat,
+        physics: &PhysicsConfig,
+    ) -> Result<Self, Box<dyn std::error::Error>> {
+        if path.extension().is_some_and(|e| e.eq_ignore_ascii_case("tmx")) {
+            return Err("TMX maps are XML; export the map from Tiled as JSON (.tmj) instead".into());
+        }
+        match format {
+            MapFormat::Native => Ok(serde_json::from_str(contents)?),
+            MapFormat::Tiled => Ok(tiled::import(contents, physics)?),
+        }
+    }
+
+    /// Whether a directory entry looks like a map file
+    fn is_map_file(path: &Path) -> bool {
+        let extension = path.extension().and_then(|e| e.to_str()).unwrap_or("").to_ascii_lowercase();
+        matches!(extension.as_str(), "json" | "tmj")
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WallConfig {
+    pub id: String,
+    /// Left edge x position of the wall
+    pub x: f32,
+    /// Bottom y position of the wall
+    pub y_bottom: f32,
+    /// Top y position of the wall
+    pub y_top: f32,
+    /// Width (thickness) of the wall
+    pub width: f32,
+    /// Wall color as hex string (e.g., "#666666")
+    pub color: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LadderConfig {
+    pub id: String,
+    pub x_start: f32,
+    pub x_end: f32,
+    pub y_bottom: f32,
+    pub y_top: f32,
+    /// Vertical speed while climbing, in units per second
+    #[serde(default = "default_climb_speed")]
+    pub climb_speed: f32,
+    /// Ladder color as hex string (e.g., "#A0522D")
+    #[serde(default = "default_ladder_color")]
+    pub color: String,
+}
+
+fn default_climb_speed() -> f32 {
+    6.0
+}
+
+fn default_ladder_color() -> String {
+    "#A0522D".to_string()
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BuildingConfig {
+    /// Whether players may place and remove blocks
+    pub enabled: bool,
+    /// Edge length of a block grid cell in world units
+    pub block_size: f32,
+    /// Maximum number of blocks a single player may have placed at once
+    pub max_blocks_per_player: usize,
+    /// Maximum distance from the player's center to the target cell center
+    pub max_reach: f32,
+    /// Minimum time between two placements by the same player, in milliseconds
+    pub place_cooldown_ms: u64,
+}
+
+impl Default for BuildingConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            block_size: 1.0,
+            max_blocks_per_player: 20,
+            max_reach: 6.0,
+            place_cooldown_ms: 250,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AdaptiveRateConfig {
+    /// Whether slow subscribers get fewer state updates instead of lagging
+    pub enabled: bool,
+    /// State rates to step through, fastest first
+    pub rates_hz: Vec<f32>,
+    /// Updates queued for a subscriber that make it step down a rate
+    pub degrade_backlog: usize,
+    /// Queue depth at or below which a subscriber counts as keeping up
+    pub recover_backlog: usize,
+    /// Seconds a subscriber must keep up before stepping back up a rate
+    pub recover_after_secs: u64,
+}
+
+impl Default for AdaptiveRateConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            rates_hz: vec![60.0, 30.0, 15.0],
+            degrade_backlog: 20,
+            recover_backlog: 2,
+            recover_after_secs: 5,
+        }
+    }
+}
+
+/// Health players start with unless configured otherwise
+pub const DEFAULT_MAX_HEALTH: u32 = 100;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HealthConfig {
+    /// Health players spawn with; at zero they die
+    pub max_health: u32,
+    /// Seconds a player can't be damaged after a hit or respawn
+    pub invulnerable_secs: f32,
+    /// Health lost when another player lands on your head; 0 disables stomps
+    pub stomp_damage: u32,
+    /// Upward speed a stomping player bounces off with
+    pub stomp_bounce: f32,
+}
+
+impl Default for HealthConfig {
+    fn default() -> Self {
+        Self {
+            max_health: DEFAULT_MAX_HEALTH,
+            invulnerable_secs: 1.0,
+            stomp_damage: 50,
+            stomp_bounce: 30.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HazardConfig {
+    pub id: String,
+    pub x_start: f32,
+    pub x_end: f32,
+    pub y_bottom: f32,
+    pub y_top: f32,
+    /// Health lost per touch; invulnerability after the hit spaces touches out
+    #[serde(default)]
+    pub damage: u32,
+    /// Health drained every second a player stays inside, ignoring invulnerability (lava)
+    #[serde(default)]
+    pub damage_per_second: f32,
+    /// Kill on touch regardless of health or invulnerability
+    #[serde(default)]
+    pub instant_kill: bool,
+    /// Upward speed added to players who touch it
+    #[serde(default)]
+    pub knockback: f32,
+    /// Hazard color as hex string (e.g., "#CC2222")
+    pub color: String,
+}
+
+/// An area pushing every player inside it, such as an updraft or a wind tunnel
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ForceZoneConfig {
+    pub id: String,
+    pub x_start: f32,
+    pub x_end: f32,
+    pub y_bottom: f32,
+    pub y_top: f32,
+    /// Acceleration applied to players inside, in units per second squared; an updraft
+    /// needs `force_y` stronger than gravity to lift players
+    #[serde(default)]
+    pub force_x: f32,
+    #[serde(default)]
+    pub force_y: f32,
+    /// Zone color as hex string; clients draw zones translucent
+    #[serde(default = "default_force_zone_color")]
+    pub color: String,
+}
+
+impl ForceZoneConfig {
+    /// Whether the box `left..right`, `bottom..top` overlaps the zone
+    pub fn overlaps(&self, left: f32, right: f32, bottom: f32, top: f32) -> bool {
+        right > self.x_start && left < self.x_end && top > self.y_bottom && bottom < self.y_top
+    }
+}
+
+fn default_force_zone_color() -> String {
+    "#88CCFF".to_string()
+}
+
+/// An area sending players who enter it to the portal named by `exit`; two portals naming
+/// each other make a two-way pair
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PortalConfig {
+    pub id: String,
+    pub x_start: f32,
+    pub x_end: f32,
+    pub y_bottom: f32,
+    pub y_top: f32,
+    /// Id of the portal players come out of, centered in it
+    pub exit: String,
+    /// Bring players out at rest instead of keeping their speed through the portal
+    #[serde(default)]
+    pub drop_velocity: bool,
+    /// Degrees the kept velocity is turned counterclockwise, e.g. 90 to send a fall
+    /// out sideways
+    #[serde(default)]
+    pub rotation_degrees: f32,
+    /// Seconds a player must spend outside every portal after coming through this one
+    /// before another takes them, so they aren't bounced straight back
+    #[serde(default = "default_portal_cooldown_secs")]
+    pub cooldown_secs: f32,
+    /// Portal color as hex string
+    #[serde(default = "default_portal_color")]
+    pub color: String,
+}
+
+impl PortalConfig {
+    /// Whether the box `left..right`, `bottom..top` overlaps the portal
+    pub fn overlaps(&self, left: f32, right: f32, bottom: f32, top: f32) -> bool {
+        right > self.x_start && left < self.x_end && top > self.y_bottom && bottom < self.y_top
+    }
+
+    /// Where players coming out of this portal are put
+    pub fn center(&self) -> (f32, f32) {
+        ((self.x_start + self.x_end) / 2.0, (self.y_bottom + self.y_top) / 2.0)
+    }
+}
+
+/// One gate of a race course
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CheckpointConfig {
+    pub id: String,
+    pub x_start: f32,
+    pub x_end: f32,
+    pub y_bottom: f32,
+    pub y_top: f32,
+    /// Checkpoint color as hex string
+    #[serde(default = "default_checkpoint_color")]
+    pub color: String,
+}
+
+impl CheckpointConfig {
+    /// Whether the box `left..right`, `bottom..top` overlaps the checkpoint
+    pub fn overlaps(&self, left: f32, right: f32, bottom: f32, top: f32) -> bool {
+        right > self.x_start && left < self.x_end && top > self.y_bottom && bottom < self.y_top
+    }
+}
+
+/// The area held in king of the hill
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ControlZoneConfig {
+    pub id: String,
+    pub x_start: f32,
+    pub x_end: f32,
+    pub y_bottom: f32,
+    pub y_top: f32,
+    /// Zone color as hex string, shown while no side owns it
+    #[serde(default = "default_control_zone_color")]
+    pub color: String,
+}
+
+impl ControlZoneConfig {
+    /// Whether the box `left..right`, `bottom..top` overlaps the zone
+    pub fn overlaps(&self, left: f32, right: f32, bottom: f32, top: f32) -> bool {
+        right > self.x_start && left < self.x_end && top > self.y_bottom && bottom < self.y_top
+    }
+}
+
+fn default_control_zone_color() -> String {
+    "#FF8800".to_string()
+}
+
+fn default_checkpoint_color() -> String {
+    "#FFD700".to_string()
+}
+
+fn default_portal_cooldown_secs() -> f32 {
+    0.5
+}
+
+fn default_portal_color() -> String {
+    "#AA44FF".to_string()
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ProjectileConfig {
+    /// Whether players may shoot
+    pub enabled: bool,
+    /// Launch speed in world units per second
+    pub speed: f32,
+    /// Whether projectiles arc under the physics gravity
+    pub gravity: bool,
+    /// Collision radius in world units
+    pub radius: f32,
+    /// Seconds a projectile flies before disappearing
+    pub lifetime_secs: f32,
+    /// Minimum time between two shots by the same player, in milliseconds
+    pub cooldown_ms: u64,
+    /// Most projectiles a single player may have in flight at once
+    pub max_per_player: usize,
+    /// Health a hit takes away; a player at zero health dies; 0 disables damage
+    pub damage: u32,
+    /// Speed a hit adds to the target along the projectile's flight; 0 disables knockback
+    pub knockback: f32,
+}
+
+impl Default for ProjectileConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            speed: 40.0,
+            gravity: false,
+            radius: 0.2,
+            lifetime_secs: 2.0,
+            cooldown_ms: 300,
+            max_per_player: 5,
+            damage: 25,
+            knockback: 20.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ComboConfig {
+    /// Seconds after a scoring event before the combo breaks
+    pub window_secs: f32,
+    /// Multiplier added per consecutive scoring event
+    pub multiplier_step: f32,
+    /// Upper bound for the combo multiplier
+    pub max_multiplier: f32,
+    /// Base points for collecting a coin
+    pub coin_points: u64,
+    /// Base points for tagging a player
+    pub tag_points: u64,
+}
+
+impl Default for ComboConfig {
+    fn default() -> Self {
+        Self {
+            window_secs: 3.0,
+            multiplier_step: 0.5,
+            max_multiplier: 4.0,
+            coin_points: 10,
+            tag_points: 50,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModerationConfig {
+    /// Scoring endpoint (hosted moderation API or local model server)
+    pub endpoint: String,
+    /// Environment variable holding the API key, sent as a bearer token
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub api_key_env: Option<String>,
+    /// Scores at or above this are flagged for admin review
+    #[serde(default = "default_flag_threshold")]
+    pub flag_threshold: f32,
+    /// Scores at or above this automatically mute the player
+    #[serde(default = "default_mute_threshold")]
+    pub mute_threshold: f32,
+    /// Duration of an automatic mute in seconds
+    #[serde(default = "default_mute_secs")]
+    pub mute_secs: u64,
+    /// Request timeout for the provider in milliseconds
+    #[serde(default = "default_moderation_timeout_ms")]
+    pub timeout_ms: u64,
+}
+
+fn default_flag_threshold() -> f32 {
+    0.5
+}
+
+fn default_mute_threshold() -> f32 {
+    0.85
+}
+
+fn default_mute_secs() -> u64 {
+    300
+}
+
+fn default_moderation_timeout_ms() -> u64 {
+    2000
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LimitsConfig {
+    /// Maximum simultaneous SSE connections from one IP
+    pub max_connections_per_ip: usize,
+    /// Maximum live players created from one IP
+    pub max_players_per_ip: usize,
+    /// Request rates allowed on the chat and player command routes
+    pub request_rate: RequestRateConfig,
+}
+
+impl Default for LimitsConfig {
+    fn default() -> Self {
+        Self {
+            max_connections_per_ip: 8,
+            max_players_per_ip: 4,
+            request_rate: RequestRateConfig::default(),
+        }
+    }
+}
+
+/// A token bucket: up to `burst` requests at once, refilled at `per_sec`
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct RateLimit {
+    pub burst: u32,
+    pub per_sec: f32,
+}
+
+/// Per-IP and per-player limits on the routes that feed the game loop, answered with 429
+/// and Retry-After when exceeded
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RequestRateConfig {
+    pub enabled: bool,
+    /// Shared by everyone behind one IP
+    pub per_ip: RateLimit,
+    /// For requests naming a `player_id`
+    pub per_player: RateLimit,
+}
+
+impl Default for RequestRateConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            per_ip: RateLimit { burst: 120, per_sec: 60.0 },
+            per_player: RateLimit { burst: 60, per_sec: 30.0 },
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ProxyConfig {
+    /// Header the `trusted_proxies` carry the client IP in, when it isn't X-Forwarded-For
+    /// (e.g. "X-Real-IP"); it is ignored from every other peer
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub client_ip_header: Option<String>,
+    /// CIDRs of reverse proxies / load balancers whose forwarded headers are trusted
+    /// (X-Forwarded-For, or RFC 7239 Forwarded); requests from other peers use the peer address
+    pub trusted_proxies: Vec<String>,
+    /// Environment variable with comma-separated trusted proxy CIDRs, replacing `trusted_proxies`
+    pub trusted_proxies_env: String,
+    /// Path prefix the server is mounted under (e.g. "/game" behind nginx at /game/)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub base_path: Option<String>,
+    /// Environment variable that overrides `base_path`
+    pub base_path_env: String,
+}
+
+impl Default for ProxyConfig {
+    fn default() -> Self {
+        Self {
+            client_ip_header: None,
+            trusted_proxies: Vec::new(),
+            trusted_proxies_env: "TRUSTED_PROXIES".to_string(),
+            base_path: None,
+            base_path_env: "BASE_PATH".to_string(),
+        }
+    }
+}
+
+impl ProxyConfig {
+    /// Trusted proxy CIDRs from the environment or config
+    pub fn resolve_trusted_proxies(&self) -> Vec<String> {
+        match std::env::var(&self.trusted_proxies_env) {
+            Ok(list) => split_list(&list),
+            Err(_) => self.trusted_proxies.clone(),
+        }
+    }
+
+    /// The mount prefix from the environment or config, with one leading slash and no
+    /// trailing one; empty when mounted at the root
+    pub fn resolve_base_path(&self) -> String {
+        let path = std::env::var(&self.base_path_env)
+            .ok()
+            .or_else(|| self.base_path.clone())
+            .unwrap_or_default();
+        let segments: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+        if segments.is_empty() {
+            String::new()
+        } else {
+            format!("/{}", segments.join("/"))
+        }
+    }
+}
+
+/// Entries of a comma-separated list, trimmed, without empty ones
+fn split_list(list: &str) -> Vec<String> {
+    list.split(',').map(str::trim).filter(|s| !s.is_empty()).map(str::to_string).collect()
+}
+
+/// Cross-origin access for browsers on other origins, e.g. a client hosted separately
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CorsConfig {
+    /// Origins allowed to call the API (e.g. "https://play.example.com"), or "*" for any;
+    /// empty leaves the API same-origin only
+    pub allowed_origins: Vec<String>,
+    /// Environment variable with comma-separated origins, replacing `allowed_origins`
+    pub allowed_origins_env: String,
+    /// Let browsers send cookies and auth headers cross-origin; ignored with "*"
+    pub allow_credentials: bool,
+    /// How long browsers may cache a preflight response
+    pub max_age_secs: u64,
+}
+
+impl Default for CorsConfig {
+    fn default() -> Self {
+        Self {
+            allowed_origins: Vec::new(),
+            allowed_origins_env: "CORS_ALLOWED_ORIGINS".to_string(),
+            allow_credentials: false,
+            max_age_secs: 3600,
+        }
+    }
+}
+
+impl CorsConfig {
+    /// Allowed origins from the environment or config
+    pub fn resolve_origins(&self) -> Vec<String> {
+        match std::env::var(&self.allowed_origins_env) {
+            Ok(list) => split_list(&list),
+            Err(_) => self.allowed_origins.clone(),
+        }
+    }
+}
+
+/// HTTPS served by the server itself, for deployments without a reverse proxy
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TlsConfig {
+    /// PEM certificate chain; HTTPS is served when both this and `key_path` are set
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cert_path: Option<String>,
+    /// Environment variable that overrides `cert_path`
+    pub cert_path_env: String,
+    /// PEM private key for the certificate
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub key_path: Option<String>,
+    /// Environment variable that overrides `key_path`
+    pub key_path_env: String,
+    /// Port for plain HTTP that redirects every request to HTTPS; none to not listen for it
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub redirect_port: Option<u16>,
+    /// Environment variable that overrides `redirect_port`
+    pub redirect_port_env: String,
+}
+
+impl Default for TlsConfig {
+    fn default() -> Self {
+        Self {
+            cert_path: None,
+            cert_path_env: "TLS_CERT_PATH".to_string(),
+            key_path: None,
+            key_path_env: "TLS_KEY_PATH".to_string(),
+            redirect_port: None,
+            redirect_port_env: "HTTP_REDIRECT_PORT".to_string(),
+        }
+    }
+}
+
+impl TlsConfig {
+    /// Certificate and key paths from the environment or config, or none to serve plain
+    /// HTTP; an error when only one of the two is set
+    pub fn resolve_paths(&self) -> Result<Option<(String, String)>, String> {
+        let cert = std::env::var(&self.cert_path_env).ok().or_else(|| self.cert_path.clone());
+        let key = std::env::var(&self.key_path_env).ok().or_else(|| self.key_path.clone());
+        match (cert, key) {
+            (Some(cert), Some(key)) => Ok(Some((cert, key))),
+            (None, None) => Ok(None),
+            (Some(_), None) => Err(format!("a TLS certificate is set but no key ({})", self.key_path_env)),
+            (None, Some(_)) => Err(format!("a TLS key is set but no certificate ({})", self.cert_path_env)),
+        }
+    }
+
+    /// The HTTP redirect port from the environment or config
+    pub fn resolve_redirect_port(&self) -> Result<Option<u16>, String> {
+        match std::env::var(&self.redirect_port_env) {
+            Ok(port) => port
+                .trim()
+                .parse()
+                .map(Some)
+                .map_err(|_| format!("{} must be a port number, got {:?}", self.redirect_port_env, port)),
+            Err(_) => Ok(self.redirect_port),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NameConfig {
+    pub min_length: usize,
+    pub max_length: usize,
+    /// Minimum seconds between renames
+    pub rename_cooldown_secs: u64,
+    /// Words that may not appear in names (matched ignoring case and leetspeak)
+    pub blocked_words: Vec<String>,
+    /// JSON file name reservations are persisted to; None keeps them in memory only
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reservations_path: Option<String>,
+    /// Most names that can be reserved at once; new reservations are refused beyond it
+    pub max_reservations: usize,
+}
+
+impl Default for NameConfig {
+    fn default() -> Self {
+        Self {
+            min_length: 3,
+            max_length: 16,
+            rename_cooldown_secs: 60,
+            blocked_words: ["admin", "moderator", "server", "fuck", "shit", "bitch", "cunt"]
+                .iter()
+                .map(|w| w.to_string())
+                .collect(),
+            reservations_path: None,
+            max_reservations: 10_000,
+        }
+    }
+}
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct GameConfig {
-    /// Optional URL to fetch configuration from remotely
-    /// If set, the server will fetch config from this URL instead of using local file
+#[serde(default)]
+pub struct ChatConfig {
+    /// Maximum message length in characters
+    pub max_length: usize,
+    /// Messages allowed per player within `rate_limit_window_secs`
+    pub rate_limit_messages: usize,
+    pub rate_limit_window_secs: u64,
+    /// Words that may not appear in chat (matched ignoring case and leetspeak)
+    pub blocked_words: Vec<String>,
+    /// Optional file with additional blocked words, one per line ('#' starts a comment)
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub remote_config: Option<String>,
-    /// Idle timeout in seconds - players inactive for this duration will be disconnected
-    /// Default: 180 seconds (3 minutes)
-    #[serde(default = "default_idle_timeout")]
-    pub idle_timeout: u64,
-    pub physics: PhysicsConfig,
-    pub platforms: Vec<PlatformConfig>,
-    pub walls: Vec<WallConfig>,
+    pub blocked_words_file: Option<String>,
+    /// Violations (blocked words, flooding) before a player is muted
+    pub strikes_before_mute: u32,
+    /// Mute durations in seconds for the first, second, ... mute; the last repeats
+    pub mute_escalation_secs: Vec<u64>,
 }
 
-fn default_idle_timeout() -> u64 {
-    180 // 3 minutes default
+impl Default for ChatConfig {
+    fn default() -> Self {
+        Self {
+            max_length: 200,
+            rate_limit_messages: 5,
+            rate_limit_window_secs: 10,
+            blocked_words: ["fuck", "shit", "bitch", "cunt"]
+                .iter()
+                .map(|w| w.to_string())
+                .collect(),
+            blocked_words_file: None,
+            strikes_before_mute: 3,
+            mute_escalation_secs: vec![60, 300, 1800, 86400],
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct PhysicsConfig {
-    pub gravity: f32,
-    pub jump_velocity: f32,
-    pub move_acceleration: f32,
-    pub move_deceleration: f32,
-    pub max_horizontal_velocity: f32,
-    pub ground_y: f32,
-    pub player_width: f32,
-    pub player_height: f32,
-    /// Friction when sliding on ground (applied to horizontal velocity)
-    pub ground_slide_friction: f32,
-    /// Friction when sliding on platforms (applied to horizontal velocity)
-    pub platform_slide_friction: f32,
-    /// Ground color as hex string (e.g., "#8B6F47")
-    pub ground_color: String,
+#[serde(default)]
+pub struct AdminConfig {
+    /// Environment variable holding the admin bearer token
+    pub token_env: String,
+    /// Token used when the environment variable is unset
+    /// Prefer the environment variable so the token stays out of the config file
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub token: Option<String>,
+}
+
+impl Default for AdminConfig {
+    fn default() -> Self {
+        Self {
+            token_env: "ADMIN_TOKEN".to_string(),
+            token: None,
+        }
+    }
+}
+
+impl AdminConfig {
+    /// The admin token from the environment or config; None disables the admin API
+    pub fn resolve_token(&self) -> Option<String> {
+        std::env::var(&self.token_env)
+            .ok()
+            .or_else(|| self.token.clone())
+            .filter(|token| !token.is_empty())
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct PlatformConfig {
+pub struct ClusterInstance {
+    /// Value of the affinity cookie that routes to this instance
     pub id: String,
-    pub x_start: f32,
-    pub x_end: f32,
-    pub y_top: f32,
-    pub height: f32,
-    /// Platform color as hex string (e.g., "#B34733")
-    pub color: String,
+    /// Public base URL clients are redirected to, e.g. "https://eu1.example.com"
+    pub url: String,
 }
 
+/// Room affinity across instances; every instance needs the same list and session secret
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct WallConfig {
+#[serde(default)]
+pub struct ClusterConfig {
+    /// Environment variable holding this instance's id
+    pub instance_id_env: String,
+    /// Id used when the environment variable is unset
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub instance_id: Option<String>,
+    /// Every instance behind the load balancer; empty runs standalone without affinity
+    pub instances: Vec<ClusterInstance>,
+    /// Cookie the load balancer routes on
+    pub cookie_name: String,
+}
+
+impl Default for ClusterConfig {
+    fn default() -> Self {
+        Self {
+            instance_id_env: "INSTANCE_ID".to_string(),
+            instance_id: None,
+            instances: Vec::new(),
+            cookie_name: "room_affinity".to_string(),
+        }
+    }
+}
+
+impl ClusterConfig {
+    /// This instance's id from the environment or config
+    pub fn resolve_instance_id(&self) -> Option<String> {
+        std::env::var(&self.instance_id_env)
+            .ok()
+            .or_else(|| self.instance_id.clone())
+            .filter(|id| !id.is_empty())
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SessionConfig {
+    /// Environment variable holding the key session tokens are signed with
+    pub secret_env: String,
+    /// Key used when the environment variable is unset
+    /// Without either, a random key is generated and tokens don't survive a restart
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub secret: Option<String>,
+    /// How long a player whose stream dropped is kept for them to reconnect
+    pub reconnect_grace_secs: u64,
+}
+
+impl Default for SessionConfig {
+    fn default() -> Self {
+        Self {
+            secret_env: "SESSION_SECRET".to_string(),
+            secret: None,
+            reconnect_grace_secs: 120,
+        }
+    }
+}
+
+impl SessionConfig {
+    /// The signing key from the environment or config, if one is set
+    pub fn resolve_secret(&self) -> Option<String> {
+        std::env::var(&self.secret_env)
+            .ok()
+            .or_else(|| self.secret.clone())
+            .filter(|secret| !secret.is_empty())
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PresenceConfig {
+    /// Seconds between SSE keepalive comments; a dead connection fails on the next one
+    pub keepalive_secs: u64,
+    /// Seconds between client heartbeats to /api/player/heartbeat
+    pub heartbeat_interval_secs: u64,
+    /// Seconds between sweeps for idle and disconnected players
+    pub cleanup_interval_secs: u64,
+    /// Seconds without commands or chat before a player shows as AFK; 0 never marks them
+    /// Players are still only removed after idle_timeout
+    pub afk_after_secs: u64,
+}
+
+impl Default for PresenceConfig {
+    fn default() -> Self {
+        Self {
+            keepalive_secs: 5,
+            heartbeat_interval_secs: 15,
+            cleanup_interval_secs: 5,
+            afk_after_secs: 60,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RoomConfig {
+    /// Players in the game at once; 0 means unlimited
+    /// Joiners beyond this spectate and are promoted in order as slots open
+    pub max_players: usize,
+    /// Spectators allowed to wait for a slot; 0 means unlimited
+    pub max_waiting: usize,
+}
+
+impl Default for RoomConfig {
+    fn default() -> Self {
+        Self {
+            max_players: 32,
+            max_waiting: 64,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PlayerSettingsConfig {
+    /// JSON file settings are persisted to; None keeps them in memory only
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+    /// Largest serialized settings document accepted per player
+    pub max_bytes: usize,
+}
+
+impl Default for PlayerSettingsConfig {
+    fn default() -> Self {
+        Self {
+            path: None,
+            max_bytes: 4096,
+        }
+    }
+}
+
+/// What a full command lane does with one more command
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OverflowPolicy {
+    /// Make the sender wait for room; nothing is lost
+    Block,
+    /// Refuse the new command
+    DropNewest,
+    /// Discard the oldest queued command to make room, keeping input fresh
+    DropOldest,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LaneConfig {
+    /// Commands the lane holds between game loop ticks
+    pub capacity: usize,
+    pub overflow: OverflowPolicy,
+}
+
+/// Commands reach the game loop through three lanes, drained admin first and cosmetic last
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CommandLanesConfig {
+    /// Commands issued by admins and moderators
+    pub admin: LaneConfig,
+    /// Player movement, building and shooting
+    pub gameplay: LaneConfig,
+    /// Cosmetic changes shown on players
+    pub cosmetic: LaneConfig,
+}
+
+impl Default for CommandLanesConfig {
+    fn default() -> Self {
+        Self {
+            admin: LaneConfig { capacity: 64, overflow: OverflowPolicy::Block },
+            gameplay: LaneConfig { capacity: 256, overflow: OverflowPolicy::DropOldest },
+            cosmetic: LaneConfig { capacity: 64, overflow: OverflowPolicy::DropNewest },
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PrivacyConfig {
+    /// JSON file tombstones of deleted players are persisted to; None keeps them in memory only
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tombstones_path: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CosmeticsConfig {
+    /// JSON file unlocks, equipped items and coins are persisted to; None keeps them in memory only
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+    /// Every cosmetic players can unlock
+    pub items: Vec<CosmeticItem>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CosmeticItem {
+    /// Stable id used by the equip and purchase endpoints
     pub id: String,
-    /// Left edge x position of the wall
-    pub x: f32,
-    /// Bottom y position of the wall
-    pub y_bottom: f32,
-    /// Top y position of the wall
-    pub y_top: f32,
-    /// Width (thickness) of the wall
-    pub width: f32,
-    /// Wall color as hex string (e.g., "#666666")
+    pub name: String,
+    pub slot: crate::cosmetics::CosmeticSlot,
+    /// What clients render: a trail or name color as hex, or a hat model id
+    pub value: String,
+    /// How players earn the item
+    #[serde(default)]
+    pub unlock: crate::cosmetics::UnlockRule,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TeamDefinition {
+    /// Stable id used in commands and state updates
+    pub id: String,
+    pub name: String,
+    /// Hex color players on the team are rendered in
     pub color: String,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TeamsConfig {
+    pub teams: Vec<TeamDefinition>,
+    /// Largest allowed size difference when a player picks a team; 0 allows any choice
+    pub max_imbalance: usize,
+}
+
+impl Default for TeamsConfig {
+    fn default() -> Self {
+        Self {
+            teams: vec![
+                TeamDefinition {
+                    id: "red".to_string(),
+                    name: "Red".to_string(),
+                    color: "#E74C3C".to_string(),
+                },
+                TeamDefinition {
+                    id: "blue".to_string(),
+                    name: "Blue".to_string(),
+                    color: "#3498DB".to_string(),
+                },
+            ],
+            max_imbalance: 1,
+        }
+    }
+}
+
+/// Player center position for spawning
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct SpawnPoint {
+    pub x: f32,
+    pub y: f32,
+}
+
+/// Area players must stay inside; there is no lower edge since kill_y covers falling
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct MapBounds {
+    pub min_x: f32,
+    pub max_x: f32,
+    pub max_y: f32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RespawnConfig {
+    /// Players whose center falls below this y die; above ground_y it makes the floor deadly
+    pub kill_y: f32,
+    /// Players leaving these bounds die; None leaves the map open sideways and above
+    pub bounds: Option<MapBounds>,
+    /// Seconds between dying and respawning
+    pub delay_secs: f32,
+}
+
+impl Default for RespawnConfig {
+    fn default() -> Self {
+        Self {
+            kill_y: -50.0,
+            bounds: None,
+            delay_secs: 3.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ColorBlindPaletteConfig {
+    /// Player colors, picked by hashing the player id
+    pub player_colors: Vec<String>,
+    /// Replacement colors by team id
+    pub team_colors: std::collections::HashMap<String, String>,
+}
+
+impl Default for ColorBlindPaletteConfig {
+    fn default() -> Self {
+        // Okabe-Ito palette
+        Self {
+            player_colors: ["#E69F00", "#56B4E9", "#009E73", "#F0E442", "#0072B2", "#D55E00", "#CC79A7"]
+                .into_iter()
+                .map(String::from)
+                .collect(),
+            team_colors: [("red", "#D55E00"), ("blue", "#0072B2")]
+                .into_iter()
+                .map(|(team, color)| (team.to_string(), color.to_string()))
+                .collect(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MatchConfig {
+    /// When false the game stays in the lobby as an endless sandbox
+    pub enabled: bool,
+    /// Id of the active entry in `modes`, also recorded in match history; the rules it
+    /// plays by are `game_mode`
+    pub mode: String,
+    /// Players needed before the countdown starts
+    pub min_players: usize,
+    pub countdown_secs: u64,
+    pub duration_secs: u64,
+    /// How long results are shown before returning to the lobby
+    pub results_secs: u64,
+}
+
+impl Default for MatchConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            mode: "standard".to_string(),
+            min_players: 2,
+            countdown_secs: 10,
+            duration_secs: 300,
+            results_secs: 15,
+        }
+    }
+}
+
+/// Rules a match is played by, named as they are registered in the mode registry
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct GameRules(Cow<'static, str>);
+
+impl GameRules {
+    /// Free play scored by coins and tags
+    pub const STANDARD: GameRules = GameRules(Cow::Borrowed("standard"));
+    /// Run the checkpoints in order; finishing places decide the scores
+    pub const RACE: GameRules = GameRules(Cow::Borrowed("race"));
+    /// One player is it and passes it on by touch; time spent not it scores
+    pub const TAG: GameRules = GameRules(Cow::Borrowed("tag"));
+    /// Hold the control zone alone to score; the first side to the score cap wins
+    pub const KING_OF_THE_HILL: GameRules = GameRules(Cow::Borrowed("king_of_the_hill"));
+
+    /// Rules registered under `name`, e.g. by an embedding server
+    pub fn new(name: impl Into<String>) -> Self {
+        GameRules(Cow::Owned(name.into()))
+    }
+
+    pub fn name(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Default for GameRules {
+    fn default() -> Self {
+        GameRules::STANDARD
+    }
+}
+
+impl std::fmt::Display for GameRules {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RaceConfig {
+    /// Times round the checkpoints to finish
+    pub laps: u32,
+}
+
+impl Default for RaceConfig {
+    fn default() -> Self {
+        Self { laps: 1 }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TagConfig {
+    /// Seconds a player who passed the tag on can't be tagged straight back
+    pub immunity_secs: f32,
+    /// Points earned per second spent not it
+    pub points_per_sec: f32,
+}
+
+impl Default for TagConfig {
+    fn default() -> Self {
+        Self { immunity_secs: 2.0, points_per_sec: 1.0 }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct KingOfTheHillConfig {
+    /// Seconds a side must hold the zone alone to take it over
+    pub capture_secs: f32,
+    /// Points per second for each owning player inside the zone
+    pub points_per_sec: f32,
+    /// Points a side needs to win; 0 plays until time runs out
+    pub score_cap: u64,
+}
+
+impl Default for KingOfTheHillConfig {
+    fn default() -> Self {
+        Self { capture_secs: 3.0, points_per_sec: 1.0, score_cap: 100 }
+    }
+}
+
+/// What happens to players' scores when the game mode changes
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ScorePolicy {
+    /// Everyone starts the new mode from zero
+    #[default]
+    Reset,
+    /// Scores carry over into the new mode
+    Keep,
+}
+
+/// A game mode preset: the rules to play by and the settings to play them with
+/// Settings left unset keep the values from the rest of the config
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GameModeConfig {
+    pub id: String,
+    /// Name shown to players; defaults to the id
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub description: String,
+    /// Asked of the rules when switching into this mode, for the scores players bring
+    #[serde(default)]
+    pub score_policy: ScorePolicy,
+    /// Rules played by in this mode
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub game_mode: Option<GameRules>,
+    /// Match length in this mode
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub duration_secs: Option<u64>,
+    /// Whether players may build; blocks are removed when a mode turns building off
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub building: Option<bool>,
+    /// Whether players may shoot; projectiles in flight are removed when a mode turns it off
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub projectiles: Option<bool>,
+    /// Mutators every round in this mode plays with, on top of any voted for
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub mutators: Vec<Mutator>,
+    /// Room-wide slow motion on highlight moments
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub slow_motion: Option<SlowMotionConfig>,
+}
+
+/// A moment that can set off slow motion
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SlowMotionTrigger {
+    /// One player killing another
+    Kill,
+    /// The match ending
+    MatchEnd,
+}
+
+/// Brief whole-room slow motion, scaling every tick's time step for a few real seconds
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SlowMotionConfig {
+    pub triggers: Vec<SlowMotionTrigger>,
+    /// Fraction of normal speed, clamped to 0..=1; 0 pauses the room
+    #[serde(default = "default_slow_motion_scale")]
+    pub scale: f32,
+    /// Real seconds it lasts
+    #[serde(default = "default_slow_motion_secs")]
+    pub duration_secs: f32,
+    /// Real seconds after a kill's slow motion ends before another kill can start it
+    #[serde(default = "default_slow_motion_kill_gap_secs")]
+    pub kill_gap_secs: f32,
+}
+
+fn default_slow_motion_scale() -> f32 {
+    0.3
+}
+
+fn default_slow_motion_secs() -> f32 {
+    1.5
+}
+
+fn default_slow_motion_kill_gap_secs() -> f32 {
+    5.0
+}
+
+impl GameModeConfig {
+    pub fn display_name(&self) -> &str {
+        self.name.as_deref().unwrap_or(&self.id)
+    }
+}
+
+/// How prominently an announcement banner is styled
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AnnouncementSeverity {
+    #[default]
+    Info,
+    Warning,
+    Critical,
+}
+
+/// A banner shown to every client on a fixed interval
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScheduledAnnouncement {
+    pub text: String,
+    #[serde(default)]
+    pub severity: AnnouncementSeverity,
+    /// Seconds between showings; the first showing is one interval after startup
+    pub interval_secs: u64,
+    /// Seconds the banner stays up; 0 keeps it until the next announcement
+    #[serde(default = "default_announcement_duration_secs")]
+    pub duration_secs: u64,
+}
+
+pub fn default_announcement_duration_secs() -> u64 {
+    10
+}
+
 impl GameConfig {
+    /// Where `map_file` points, relative to the directory of the config at `config_path`
+    fn map_path(&self, config_path: &Path) -> Option<PathBuf> {
+        let map_file = self.map_file.as_ref()?;
+        let base = config_path.parent().unwrap_or_else(|| Path::new(""));
+        Some(base.join(map_file))
+    }
+
+    /// Swap in the geometry of the map file read from `path`
+    fn apply_map(&mut self, path: &Path, contents: &str) -> Result<(), Box<dyn std::error::Error>> {
+        let format = self.map_format.unwrap_or_else(|| MapFormat::detect(path, contents));
+        let map = MapGeometry::parse(path, contents, format, &self.physics)
+            .map_err(|e| format!("failed to load map {}: {}", path.display(), e))?;
+        eprintln!(
+            "🗺️ Loaded {:?} map {}: {} platforms, {} walls, {} ladders, {} hazards, {} force zones, {} portals, {} spawn points",
+            format,
+            path.display(),
+            map.platforms.len(),
+            map.walls.len(),
+            map.ladders.len(),
+            map.hazards.len(),
+            map.force_zones.len(),
+            map.portals.len(),
+            map.spawn_points.len()
+        );
+        self.set_geometry(map);
+        Ok(())
+    }
+
+    /// The platforms, walls, ladders, hazards and spawn points being played on
+    pub fn geometry(&self) -> MapGeometry {
+        MapGeometry {
+            platforms: self.platforms.clone(),
+            walls: self.walls.clone(),
+            ladders: self.ladders.clone(),
+            hazards: self.hazards.clone(),
+            force_zones: self.force_zones.clone(),
+            portals: self.portals.clone(),
+            checkpoints: self.checkpoints.clone(),
+            control_zone: self.control_zone.clone(),
+            spawn_points: self.spawn_points.clone(),
+        }
+    }
+
+    /// Replace the platforms, walls, ladders, hazards and spawn points
+    pub fn set_geometry(&mut self, map: MapGeometry) {
+        self.platforms = map.platforms;
+        self.walls = map.walls;
+        self.ladders = map.ladders;
+        self.hazards = map.hazards;
+        self.force_zones = map.force_zones;
+        self.portals = map.portals;
+        self.checkpoints = map.checkpoints;
+        self.control_zone = map.control_zone;
+        self.spawn_points = map.spawn_points;
+    }
+
+    /// Read the rotation's map files and `maps_dir`, then start on the first map
+    /// Map files are small and read once at startup, so this reads them synchronously
+    fn load_maps(&mut self, config_path: &Path) -> Result<(), Box<dyn std::error::Error>> {
+        let base = config_path.parent().unwrap_or_else(|| Path::new(""));
+        if let Some(dir) = &self.maps_dir {
+            let mut files: Vec<PathBuf> = fs::read_dir(base.join(dir))?
+                .filter_map(|entry| entry.ok().map(|e| e.path()))
+                .filter(|path| MapGeometry::is_map_file(path))
+                .collect();
+            files.sort();
+            for path in files {
+                let Some(id) = path.file_stem().and_then(|s| s.to_str()).map(str::to_string) else {
+                    continue;
+                };
+                if self.maps.iter().any(|m| m.id == id) {
+                    eprintln!("⚠️ Skipping {}: a map with id {} is already configured", path.display(), id);
+                    continue;
+                }
+                self.maps.push(MapConfig {
+                    id,
+                    name: None,
+                    file: path.file_name().map(|name| Path::new(dir).join(name).to_string_lossy().into_owned()),
+                    format: None,
+                    geometry: MapGeometry::default(),
+                });
+            }
+        }
+
+        for map in &mut self.maps {
+            let Some(file) = &map.file else {
+                continue;
+            };
+            let path = base.join(file);
+            let contents = fs::read_to_string(&path)?;
+            let format = map.format.unwrap_or_else(|| MapFormat::detect(&path, &contents));
+            map.geometry = MapGeometry::parse(&path, &contents, format, &self.physics)
+                .map_err(|e| format!("failed to load map {}: {}", path.display(), e))?;
+        }
+
+        if let Some(first) = self.maps.first() {
+            eprintln!(
+                "🗺️ Map rotation: {}",
+                self.maps.iter().map(|m| m.id.as_str()).collect::<Vec<_>>().join(", ")
+            );
+            self.set_geometry(first.geometry.clone());
+        }
+        Ok(())
+    }
+
     /// Load game configuration from JSON file synchronously
     /// This is a convenience wrapper for sync contexts
+    /// A config that fails [`GameConfig::validate`] is an error
     pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, Box<dyn std::error::Error>> {
+        let path = path.as_ref();
         let contents = fs::read_to_string(path)?;
-        let config: GameConfig = serde_json::from_str(&contents)?;
+        let mut config: GameConfig = serde_json::from_str(&contents)?;
+        if let Some(map_path) = config.map_path(path) {
+            let map = fs::read_to_string(&map_path)?;
+            config.apply_map(&map_path, &map)?;
+        }
+        config.load_maps(path)?;
+        config.validate()?;
         
         // If remote_config is specified, we can't fetch it synchronously
         // This will be handled by load_async instead
@@ -86,8 +2417,20 @@ impl GameConfig {
 
     /// Load game configuration from JSON file asynchronously
     /// If the config contains a remote_config URL, it will fetch and use that instead
+    /// A `map_file` in either config is read relative to the local config file
     pub async fn load_async<P: AsRef<Path>>(path: P) -> Result<Self, Box<dyn std::error::Error>> {
         let path = path.as_ref();
+        let mut config = Self::load_config_async(path).await?;
+        if let Some(map_path) = config.map_path(path) {
+            let map = tokio::fs::read_to_string(&map_path).await?;
+            config.apply_map(&map_path, &map)?;
+        }
+        config.load_maps(path)?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    async fn load_config_async(path: &Path) -> Result<Self, Box<dyn std::error::Error>> {
         let contents = tokio::fs::read_to_string(path).await?;
         let config: GameConfig = serde_json::from_str(&contents)?;
         
@@ -133,9 +2476,76 @@ impl GameConfig {
         Ok(GameConfig {
             remote_config: None,
             idle_timeout: config.idle_timeout,
+            match_history_path: config.match_history_path,
+            chat_history_size: config.chat_history_size,
+            resume_ttl_secs: config.resume_ttl_secs,
+            shutdown_grace_secs: config.shutdown_grace_secs,
+            tick_rate_hz: config.tick_rate_hz,
+            broadcast_rate_hz: config.broadcast_rate_hz,
+            snapshot_rate_hz: config.snapshot_rate_hz,
             physics: config.physics,
+            map_file: config.map_file,
+            map_format: config.map_format,
+            maps: config.maps,
+            maps_dir: config.maps_dir,
             platforms: config.platforms,
             walls: config.walls,
+            ladders: config.ladders,
+            building: config.building,
+            combo: config.combo,
+            moderation: config.moderation,
+            limits: config.limits,
+            proxy: config.proxy,
+            names: config.names,
+            chat: config.chat,
+            admin: config.admin,
+            session: config.session,
+            presence: config.presence,
+            room: config.room,
+            announcements: config.announcements,
+            player_settings: config.player_settings,
+            teams: config.teams,
+            default_language: config.default_language,
+            matches: config.matches,
+            game_mode: config.game_mode,
+            race: config.race,
+            tag: config.tag,
+            king_of_the_hill: config.king_of_the_hill,
+            color_blind_palette: config.color_blind_palette,
+            spawn_points: config.spawn_points,
+            respawn: config.respawn,
+            projectiles: config.projectiles,
+            adaptive_rate: config.adaptive_rate,
+            health: config.health,
+            hazards: config.hazards,
+            force_zones: config.force_zones,
+            portals: config.portals,
+            checkpoints: config.checkpoints,
+            control_zone: config.control_zone,
+            cosmetics: config.cosmetics,
+            privacy: config.privacy,
+            command_lanes: config.command_lanes,
+            cluster: config.cluster,
+            map_rotation: config.map_rotation,
+            modes: config.modes,
+            hud: config.hud,
+            overlay: config.overlay,
+            bots: config.bots,
+            replays: config.replays,
+            determinism: config.determinism,
+            mutators: config.mutators,
+            housing: config.housing,
+            backplane: config.backplane,
+            world_events: config.world_events,
+            backpressure: config.backpressure,
+            latency_tracing: config.latency_tracing,
+            kill_cam: config.kill_cam,
+            state_transport: config.state_transport,
+            physics_sandbox: config.physics_sandbox,
+            stream_compression: config.stream_compression,
+            anti_cheat: config.anti_cheat,
+            cors: config.cors,
+            tls: config.tls,
         })
     }
 
@@ -144,12 +2554,21 @@ impl GameConfig {
         let config: GameConfig = serde_json::from_str(json)?;
         Ok(config)
     }
+}
 
+impl Default for GameConfig {
     /// Get default configuration (fallback if file loading fails)
-    pub fn default() -> Self {
+    fn default() -> Self {
         Self {
             remote_config: None,
             idle_timeout: 180, // 3 minutes default
+            match_history_path: None,
+            chat_history_size: default_chat_history_size(),
+            resume_ttl_secs: default_resume_ttl_secs(),
+            shutdown_grace_secs: default_shutdown_grace_secs(),
+            tick_rate_hz: default_tick_rate_hz(),
+            broadcast_rate_hz: default_broadcast_rate_hz(),
+            snapshot_rate_hz: default_snapshot_rate_hz(),
             physics: PhysicsConfig {
                 gravity: -2000.0,
                 jump_velocity: 250.0,
@@ -162,7 +2581,30 @@ impl GameConfig {
                 ground_slide_friction: 800.0,
                 platform_slide_friction: 600.0,
                 ground_color: "#8B6F47".to_string(),
+                ground_segments: Vec::new(),
+                crouch_height: default_crouch_height(),
+                slide_min_speed: default_slide_min_speed(),
+                slide_boost: default_slide_boost(),
+                slide_secs: default_slide_secs(),
+                max_stamina: default_max_stamina(),
+                stamina_regen_per_sec: default_stamina_regen_per_sec(),
+                sprint_multiplier: default_sprint_multiplier(),
+                sprint_cost_per_sec: default_sprint_cost_per_sec(),
+                dash_speed: default_dash_speed(),
+                dash_secs: default_dash_secs(),
+                dash_cost: default_dash_cost(),
+                wall_jump_cost: default_wall_jump_cost(),
+                wall_jump_push: default_wall_jump_push(),
+                jump_buffer_secs: default_jump_buffer_secs(),
+                held_input_timeout_secs: default_held_input_timeout_secs(),
+                ground_surface: Surface::Normal,
+                surfaces: SurfacePresets::default(),
+                bounce_min_speed: default_bounce_min_speed(),
             },
+            map_file: None,
+            map_format: None,
+            maps: Vec::new(),
+            maps_dir: None,
             platforms: vec![PlatformConfig {
                 id: "platform_1".to_string(),
                 x_start: -3.0,
@@ -170,8 +2612,66 @@ impl GameConfig {
                 y_top: 2.0,
                 height: 0.5,
                 color: "#B34733".to_string(),
+                surface: Surface::Normal,
+                restitution: None,
             }],
             walls: vec![],
+            ladders: vec![],
+            building: BuildingConfig::default(),
+            combo: ComboConfig::default(),
+            moderation: None,
+            limits: LimitsConfig::default(),
+            proxy: ProxyConfig::default(),
+            names: NameConfig::default(),
+            chat: ChatConfig::default(),
+            admin: AdminConfig::default(),
+            session: SessionConfig::default(),
+            presence: PresenceConfig::default(),
+            room: RoomConfig::default(),
+            announcements: Vec::new(),
+            player_settings: PlayerSettingsConfig::default(),
+            teams: TeamsConfig::default(),
+            default_language: crate::language::Language::default(),
+            matches: MatchConfig::default(),
+            game_mode: GameRules::default(),
+            race: RaceConfig::default(),
+            tag: TagConfig::default(),
+            king_of_the_hill: KingOfTheHillConfig::default(),
+            color_blind_palette: ColorBlindPaletteConfig::default(),
+            spawn_points: Vec::new(),
+            respawn: RespawnConfig::default(),
+            projectiles: ProjectileConfig::default(),
+            adaptive_rate: AdaptiveRateConfig::default(),
+            health: HealthConfig::default(),
+            hazards: Vec::new(),
+            force_zones: Vec::new(),
+            portals: Vec::new(),
+            checkpoints: Vec::new(),
+            control_zone: None,
+            cosmetics: CosmeticsConfig::default(),
+            privacy: PrivacyConfig::default(),
+            command_lanes: CommandLanesConfig::default(),
+            cluster: ClusterConfig::default(),
+            map_rotation: MapRotationConfig::default(),
+            modes: Vec::new(),
+            hud: HudConfig::default(),
+            overlay: OverlayConfig::default(),
+            bots: BotsConfig::default(),
+            replays: ReplayConfig::default(),
+            determinism: DeterminismConfig::default(),
+            mutators: MutatorConfig::default(),
+            housing: HousingConfig::default(),
+            backplane: BackplaneConfig::default(),
+            world_events: WorldEventsConfig::default(),
+            backpressure: BackpressureConfig::default(),
+            latency_tracing: LatencyTracingConfig::default(),
+            kill_cam: KillCamConfig::default(),
+            state_transport: StateTransportConfig::default(),
+            physics_sandbox: PhysicsSandboxConfig::default(),
+            stream_compression: StreamCompressionConfig::default(),
+            anti_cheat: AntiCheatConfig::default(),
+            cors: CorsConfig::default(),
+            tls: TlsConfig::default(),
         }
     }
 }
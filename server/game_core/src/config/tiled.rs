@@ -0,0 +1,350 @@
+//! Importer for maps made in the Tiled editor (https://www.mapeditor.org), saved as JSON
+//!
+//! Only object layers are read. Each object becomes a platform, wall, ladder, hazard, force
+//! zone (or "wind"), portal or spawn point, chosen by its class (`type` before Tiled 1.9) or
+//! else by its layer's name, e.g. a layer called "platforms". Tile layers are decoration and
+//! are skipped.
+//!
+//! One tile is one world unit. The bottom edge of the map sits on the ground and its
+//! horizontal center at x = 0, so y grows upwards as it does in the game.
+
+use serde::Deserialize;
+use serde_json::Value;
+use super::{ForceZoneConfig, HazardConfig, LadderConfig, MapGeometry, PhysicsConfig, PlatformConfig, PortalConfig, SpawnPoint, Surface, WallConfig};
+
+/// Why a Tiled map couldn't be imported
+#[derive(Debug, Clone, PartialEq)]
+pub enum TiledError {
+    /// The file isn't valid Tiled JSON
+    Parse(String),
+    /// Only orthogonal maps line up with the game's grid
+    Orientation(String),
+    /// An object uses a shape or transform the game has no equivalent for
+    Unsupported { object: String, reason: &'static str },
+}
+
+impl std::fmt::Display for TiledError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TiledError::Parse(e) => write!(f, "invalid Tiled map: {}", e),
+            TiledError::Orientation(o) => write!(f, "{} maps are not supported, use orthogonal", o),
+            TiledError::Unsupported { object, reason } => write!(f, "object {}: {}", object, reason),
+        }
+    }
+}
+
+impl std::error::Error for TiledError {}
+
+#[derive(Deserialize)]
+struct TiledMap {
+    #[serde(default = "default_orientation")]
+    orientation: String,
+    height: u32,
+    width: u32,
+    tilewidth: f32,
+    tileheight: f32,
+    #[serde(default)]
+    layers: Vec<TiledLayer>,
+}
+
+fn default_orientation() -> String {
+    "orthogonal".to_string()
+}
+
+#[derive(Deserialize)]
+struct TiledLayer {
+    #[serde(default)]
+    name: String,
+    #[serde(rename = "type")]
+    kind: String,
+    #[serde(default)]
+    objects: Vec<TiledObject>,
+    /// Groups nest further layers
+    #[serde(default)]
+    layers: Vec<TiledLayer>,
+}
+
+#[derive(Deserialize)]
+struct TiledObject {
+    id: u32,
+    #[serde(default)]
+    name: String,
+    #[serde(default, rename = "type")]
+    type_name: String,
+    #[serde(default)]
+    class: String,
+    x: f32,
+    y: f32,
+    #[serde(default)]
+    width: f32,
+    #[serde(default)]
+    height: f32,
+    #[serde(default)]
+    rotation: f32,
+    #[serde(default)]
+    point: bool,
+    #[serde(default)]
+    ellipse: bool,
+    #[serde(default)]
+    polygon: Option<Value>,
+    #[serde(default)]
+    polyline: Option<Value>,
+    #[serde(default)]
+    properties: Vec<TiledProperty>,
+}
+
+#[derive(Deserialize)]
+struct TiledProperty {
+    name: String,
+    value: Value,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ObjectKind {
+    Platform,
+    Wall,
+    Ladder,
+    Hazard,
+    ForceZone,
+    Portal,
+    Spawn,
+}
+
+impl ObjectKind {
+    /// Kind named by a class or layer name, singular or plural, in any case
+    fn parse(name: &str) -> Option<Self> {
+        let name = name.trim().to_ascii_lowercase();
+        match name.strip_suffix('s').unwrap_or(&name) {
+            "platform" => Some(ObjectKind::Platform),
+            "wall" => Some(ObjectKind::Wall),
+            "ladder" => Some(ObjectKind::Ladder),
+            "hazard" => Some(ObjectKind::Hazard),
+            "force_zone" | "wind" => Some(ObjectKind::ForceZone),
+            "portal" => Some(ObjectKind::Portal),
+            "spawn" => Some(ObjectKind::Spawn),
+            _ => None,
+        }
+    }
+
+    fn label(&self) -> &'static str {
+        match self {
+            ObjectKind::Platform => "platform",
+            ObjectKind::Wall => "wall",
+            ObjectKind::Ladder => "ladder",
+            ObjectKind::Hazard => "hazard",
+            ObjectKind::ForceZone => "force_zone",
+            ObjectKind::Portal => "portal",
+            ObjectKind::Spawn => "spawn",
+        }
+    }
+}
+
+impl TiledObject {
+    fn label(&self) -> String {
+        if self.name.is_empty() {
+            format!("#{}", self.id)
+        } else {
+            format!("\"{}\" (#{})", self.name, self.id)
+        }
+    }
+
+    fn property(&self, name: &str) -> Option<&Value> {
+        self.properties.iter().find(|p| p.name == name).map(|p| &p.value)
+    }
+
+    fn f32_property(&self, name: &str) -> Option<f32> {
+        self.property(name).and_then(Value::as_f64).map(|v| v as f32)
+    }
+
+    /// Normal when unset, `None` when set to something that isn't a surface
+    fn surface_property(&self) -> Option<Surface> {
+        match self.property("surface") {
+            Some(value) => serde_json::from_value(value.clone()).ok(),
+            None => Some(Surface::default()),
+        }
+    }
+
+    /// Tiled writes colors as #AARRGGBB when they have alpha; the game only takes #RRGGBB
+    fn color_property(&self, default: &str) -> String {
+        match self.property("color").and_then(Value::as_str) {
+            Some(color) if color.len() == 9 => match color.get(3..) {
+                Some(rgb) => format!("#{}", rgb).to_uppercase(),
+                // Not ASCII, so not a color; validation reports it as given
+                None => color.to_uppercase(),
+            },
+            Some(color) => color.to_uppercase(),
+            None => default.to_string(),
+        }
+    }
+}
+
+/// Maps Tiled pixel coordinates (y down, origin top left) to world units
+struct Transform {
+    tile_width: f32,
+    tile_height: f32,
+    half_width: f32,
+    height: f32,
+    ground_y: f32,
+}
+
+impl Transform {
+    fn x(&self, px: f32) -> f32 {
+        px / self.tile_width - self.half_width
+    }
+
+    fn y(&self, py: f32) -> f32 {
+        self.ground_y + self.height - py / self.tile_height
+    }
+}
+
+/// Convert a Tiled JSON map into game geometry
+pub fn import(json: &str, physics: &PhysicsConfig) -> Result<MapGeometry, TiledError> {
+    let map: TiledMap = serde_json::from_str(json).map_err(|e| TiledError::Parse(e.to_string()))?;
+    if map.orientation != "orthogonal" {
+        return Err(TiledError::Orientation(map.orientation));
+    }
+    if map.tilewidth <= 0.0 || map.tileheight <= 0.0 {
+        return Err(TiledError::Parse("tile size must be positive".to_string()));
+    }
+    let transform = Transform {
+        tile_width: map.tilewidth,
+        tile_height: map.tileheight,
+        half_width: map.width as f32 / 2.0,
+        height: map.height as f32,
+        ground_y: physics.ground_y,
+    };
+
+    let mut geometry = MapGeometry::default();
+    let mut objects = Vec::new();
+    collect_objects(&map.layers, &mut objects);
+    for (layer_kind, object) in objects {
+        let class = if object.class.is_empty() { &object.type_name } else { &object.class };
+        let Some(kind) = ObjectKind::parse(class).or(layer_kind) else {
+            eprintln!("⚠️ Skipping Tiled object {}: not on a platforms, walls, ladders, hazards, force_zones, portals or spawns layer", object.label());
+            continue;
+        };
+        add_object(&mut geometry, kind, object, &transform, physics)?;
+    }
+    Ok(geometry)
+}
+
+/// Every object in the map with the kind its layer's name implies, descending into groups
+fn collect_objects<'a>(layers: &'a [TiledLayer], out: &mut Vec<(Option<ObjectKind>, &'a TiledObject)>) {
+    for layer in layers {
+        match layer.kind.as_str() {
+            "objectgroup" => {
+                let kind = ObjectKind::parse(&layer.name);
+                out.extend(layer.objects.iter().map(|object| (kind, object)));
+            }
+            "group" => collect_objects(&layer.layers, out),
+            _ => {}
+        }
+    }
+}
+
+fn add_object(
+    geometry: &mut MapGeometry,
+    kind: ObjectKind,
+    object: &TiledObject,
+    transform: &Transform,
+    physics: &PhysicsConfig,
+) -> Result<(), TiledError> {
+    let unsupported = |reason| TiledError::Unsupported { object: object.label(), reason };
+    if object.rotation != 0.0 {
+        return Err(unsupported("rotated objects are not supported"));
+    }
+    if object.ellipse || object.polygon.is_some() || object.polyline.is_some() {
+        return Err(unsupported("only rectangles and points are supported"));
+    }
+    let id = if object.name.is_empty() {
+        format!("{}_{}", kind.label(), object.id)
+    } else {
+        object.name.clone()
+    };
+
+    // Spawns are points where the player's feet go; a rectangle spawns at its bottom center
+    if kind == ObjectKind::Spawn {
+        let x = object.x + object.width / 2.0;
+        let y = object.y + object.height;
+        geometry.spawn_points.push(SpawnPoint {
+            x: transform.x(x),
+            y: transform.y(y) + physics.player_height / 2.0,
+        });
+        return Ok(());
+    }
+    if object.point || object.width <= 0.0 || object.height <= 0.0 {
+        return Err(unsupported("needs a rectangle with a width and height"));
+    }
+
+    let (x_start, x_end) = (transform.x(object.x), transform.x(object.x + object.width));
+    let (y_bottom, y_top) = (transform.y(object.y + object.height), transform.y(object.y));
+    match kind {
+        ObjectKind::Platform => geometry.platforms.push(PlatformConfig {
+            id,
+            x_start,
+            x_end,
+            y_top,
+            height: y_top - y_bottom,
+            color: object.color_property("#B34733"),
+            surface: object.surface_property().ok_or_else(|| unsupported("surface must be normal, ice, mud or bouncy"))?,
+            restitution: object.f32_property("restitution"),
+        }),
+        ObjectKind::Wall => geometry.walls.push(WallConfig {
+            id,
+            x: x_start,
+            y_bottom,
+            y_top,
+            width: x_end - x_start,
+            color: object.color_property("#666666"),
+        }),
+        ObjectKind::Ladder => geometry.ladders.push(LadderConfig {
+            id,
+            x_start,
+            x_end,
+            y_bottom,
+            y_top,
+            climb_speed: object.f32_property("climb_speed").unwrap_or_else(super::default_climb_speed),
+            color: object.color_property(&super::default_ladder_color()),
+        }),
+        ObjectKind::Hazard => geometry.hazards.push(HazardConfig {
+            id,
+            x_start,
+            x_end,
+            y_bottom,
+            y_top,
+            damage: object.property("damage").and_then(Value::as_u64).unwrap_or(0) as u32,
+            damage_per_second: object.f32_property("damage_per_second").unwrap_or(0.0),
+            instant_kill: object.property("instant_kill").and_then(Value::as_bool).unwrap_or(false),
+            knockback: object.f32_property("knockback").unwrap_or(0.0),
+            color: object.color_property("#CC2222"),
+        }),
+        ObjectKind::ForceZone => geometry.force_zones.push(ForceZoneConfig {
+            id,
+            x_start,
+            x_end,
+            y_bottom,
+            y_top,
+            force_x: object.f32_property("force_x").unwrap_or(0.0),
+            force_y: object.f32_property("force_y").unwrap_or(0.0),
+            color: object.color_property(&super::default_force_zone_color()),
+        }),
+        ObjectKind::Portal => geometry.portals.push(PortalConfig {
+            id,
+            x_start,
+            x_end,
+            y_bottom,
+            y_top,
+            exit: object
+                .property("exit")
+                .and_then(Value::as_str)
+                .ok_or_else(|| unsupported("portals need an exit property naming another portal"))?
+                .to_string(),
+            drop_velocity: object.property("drop_velocity").and_then(Value::as_bool).unwrap_or(false),
+            rotation_degrees: object.f32_property("rotation_degrees").unwrap_or(0.0),
+            cooldown_secs: object.f32_property("cooldown_secs").unwrap_or_else(super::default_portal_cooldown_secs),
+            color: object.color_property(&super::default_portal_color()),
+        }),
+        ObjectKind::Spawn => unreachable!("spawns are handled above"),
+    }
+    Ok(())
+}
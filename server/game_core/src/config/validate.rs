@@ -0,0 +1,362 @@
+//! Sanity checks on a loaded config, so broken geometry or physics fails at load time
+//! instead of showing up as players falling through the world
+//!
+//! Every problem found is reported, each naming the object it was found in.
+
+use super::{GameConfig, GameRules, MapGeometry, PhysicsConfig, SpawnPoint};
+
+/// One problem found by [`GameConfig::validate`]
+#[derive(Debug, Clone, PartialEq)]
+pub enum ValidationError {
+    /// A platform with no width or height, or a non-finite edge
+    DegeneratePlatform { id: String },
+    /// Two platforms that share some area; touching edges are fine
+    OverlappingPlatforms { id: String, other: String },
+    /// A wall whose top isn't above its bottom, or with no width
+    DegenerateWall { id: String },
+    /// A force zone with no area, or a non-finite edge or force
+    DegenerateForceZone { id: String },
+    /// A portal with no area, or a non-finite edge, rotation or cooldown
+    DegeneratePortal { id: String },
+    /// A portal whose exit isn't another portal on the same map
+    UnknownPortalExit { id: String, exit: String },
+    /// One of a two-way pair of portals without a positive cooldown, which would bounce a
+    /// player back and forth between them every tick
+    PortalPairWithoutCooldown { id: String, exit: String },
+    /// A checkpoint with no area, or a non-finite edge
+    DegenerateCheckpoint { id: String },
+    /// Race mode on a map with no checkpoints to run
+    RaceWithoutCheckpoints,
+    /// A control zone with no area, or a non-finite edge
+    DegenerateControlZone { id: String },
+    /// King of the hill on a map with no control zone to hold
+    KingOfTheHillWithoutZone,
+    /// A ground segment with no width or a non-finite edge or height
+    DegenerateGroundSegment { index: usize },
+    /// Two ground segments claiming some of the same x range
+    OverlappingGroundSegments { index: usize, other: usize },
+    /// A spawn point whose position is inside a platform or wall
+    SpawnInsideGeometry { index: usize, inside: String },
+    /// A physics value that is NaN or infinite
+    NonFinitePhysics { field: &'static str },
+    /// Physics sandbox bounds on something that isn't a numeric physics setting
+    NonNumericPhysicsBound { field: String },
+    /// A color that isn't `#RGB` or `#RRGGBB`
+    InvalidColor { id: String, color: String },
+    /// A problem in one of the rotation's maps
+    InMap { map: String, error: Box<ValidationError> },
+}
+
+impl std::fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ValidationError::DegeneratePlatform { id } => write!(f, "platform {} has no area", id),
+            ValidationError::OverlappingPlatforms { id, other } => write!(f, "platform {} overlaps platform {}", id, other),
+            ValidationError::DegenerateWall { id } => {
+                write!(f, "wall {} needs y_top above y_bottom and a positive width", id)
+            }
+            ValidationError::DegenerateForceZone { id } => {
+                write!(f, "force zone {} needs an area and a finite force", id)
+            }
+            ValidationError::DegeneratePortal { id } => {
+                write!(f, "portal {} needs an area, a finite rotation and a finite cooldown", id)
+            }
+            ValidationError::UnknownPortalExit { id, exit } => write!(f, "portal {} exits through unknown portal {}", id, exit),
+            ValidationError::PortalPairWithoutCooldown { id, exit } => {
+                write!(f, "portal {} leads to portal {} and back, so it needs a positive cooldown", id, exit)
+            }
+            ValidationError::DegenerateCheckpoint { id } => write!(f, "checkpoint {} has no area", id),
+            ValidationError::RaceWithoutCheckpoints => write!(f, "race mode needs at least one checkpoint"),
+            ValidationError::DegenerateControlZone { id } => write!(f, "control zone {} has no area", id),
+            ValidationError::KingOfTheHillWithoutZone => write!(f, "king of the hill needs a control zone"),
+            ValidationError::DegenerateGroundSegment { index } => {
+                write!(f, "ground segment {} needs x_end right of x_start and a finite height", index)
+            }
+            ValidationError::OverlappingGroundSegments { index, other } => {
+                write!(f, "ground segment {} overlaps ground segment {}", index, other)
+            }
+            ValidationError::SpawnInsideGeometry { index, inside } => write!(f, "spawn point {} is inside {}", index, inside),
+            ValidationError::NonFinitePhysics { field } => write!(f, "physics.{} is not a finite number", field),
+            ValidationError::NonNumericPhysicsBound { field } => {
+                write!(f, "physics_sandbox.bounds.{} isn't a numeric physics setting", field)
+            }
+            ValidationError::InvalidColor { id, color } => write!(f, "{} has color {:?}, expected #RGB or #RRGGBB", id, color),
+            ValidationError::InMap { map, error } => write!(f, "map {}: {}", map, error),
+        }
+    }
+}
+
+/// Everything wrong with a config
+#[derive(Debug, Clone, PartialEq)]
+pub struct ValidationErrors(pub Vec<ValidationError>);
+
+impl std::fmt::Display for ValidationErrors {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "invalid config: ")?;
+        for (i, error) in self.0.iter().enumerate() {
+            if i > 0 {
+                write!(f, "; ")?;
+            }
+            write!(f, "{}", error)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for ValidationErrors {}
+
+/// Whether a string is `#RGB` or `#RRGGBB`
+pub fn is_hex_color(color: &str) -> bool {
+    color
+        .strip_prefix('#')
+        .is_some_and(|hex| matches!(hex.len(), 3 | 6) && hex.chars().all(|c| c.is_ascii_hexdigit()))
+}
+
+/// Left, right, bottom and top of an axis-aligned box
+type Rect = (f32, f32, f32, f32);
+
+fn overlaps(a: Rect, b: Rect) -> bool {
+    a.0 < b.1 && b.0 < a.1 && a.2 < b.3 && b.2 < a.3
+}
+
+fn contains(rect: Rect, spawn: &SpawnPoint) -> bool {
+    rect.0 < spawn.x && spawn.x < rect.1 && rect.2 < spawn.y && spawn.y < rect.3
+}
+
+fn check_physics(physics: &PhysicsConfig, errors: &mut Vec<ValidationError>) {
+    let fields = [
+        ("gravity", physics.gravity),
+        ("jump_velocity", physics.jump_velocity),
+        ("move_acceleration", physics.move_acceleration),
+        ("move_deceleration", physics.move_deceleration),
+        ("max_horizontal_velocity", physics.max_horizontal_velocity),
+        ("ground_y", physics.ground_y),
+        ("player_width", physics.player_width),
+        ("player_height", physics.player_height),
+        ("ground_slide_friction", physics.ground_slide_friction),
+        ("platform_slide_friction", physics.platform_slide_friction),
+        ("crouch_height", physics.crouch_height),
+        ("slide_min_speed", physics.slide_min_speed),
+        ("slide_boost", physics.slide_boost),
+        ("slide_secs", physics.slide_secs),
+    ];
+    for (field, value) in fields {
+        if !value.is_finite() {
+            errors.push(ValidationError::NonFinitePhysics { field });
+        }
+    }
+    if !is_hex_color(&physics.ground_color) {
+        errors.push(ValidationError::InvalidColor {
+            id: "ground".to_string(),
+            color: physics.ground_color.clone(),
+        });
+    }
+
+    let mut segments: Vec<(usize, f32, f32)> = Vec::new();
+    for (index, segment) in physics.ground_segments.iter().enumerate() {
+        let finite = [segment.x_start, segment.x_end, segment.y_top].iter().all(|v| v.is_finite());
+        if !finite || segment.x_end <= segment.x_start {
+            errors.push(ValidationError::DegenerateGroundSegment { index });
+            continue;
+        }
+        if let Some((other, _, _)) = segments
+            .iter()
+            .find(|(_, x_start, x_end)| segment.x_start < *x_end && *x_start < segment.x_end)
+        {
+            errors.push(ValidationError::OverlappingGroundSegments { index, other: *other });
+        }
+        segments.push((index, segment.x_start, segment.x_end));
+        if let Some(color) = segment.color.as_ref().filter(|color| !is_hex_color(color)) {
+            errors.push(ValidationError::InvalidColor {
+                id: format!("ground segment {}", index),
+                color: color.clone(),
+            });
+        }
+    }
+}
+
+/// Sandbox bounds only make sense on settings a number can be patched into
+fn check_sandbox_bounds(config: &GameConfig, errors: &mut Vec<ValidationError>) {
+    // The defaults rather than the configured physics, where a NaN would serialize as null
+    let Ok(serde_json::Value::Object(fields)) = serde_json::to_value(GameConfig::default().physics) else {
+        return;
+    };
+    for field in config.physics_sandbox.bounds.keys() {
+        if !fields.get(field).is_some_and(serde_json::Value::is_number) {
+            errors.push(ValidationError::NonNumericPhysicsBound { field: field.clone() });
+        }
+    }
+}
+
+fn check_geometry(map: &MapGeometry) -> Vec<ValidationError> {
+    let mut errors = Vec::new();
+    let mut solids: Vec<(String, Rect)> = Vec::new();
+
+    let mut platforms: Vec<(&str, Rect)> = Vec::new();
+    for platform in &map.platforms {
+        let rect = (platform.x_start, platform.x_end, platform.y_top - platform.height, platform.y_top);
+        let finite = [rect.0, rect.1, rect.2, rect.3].iter().all(|v| v.is_finite());
+        if !finite || platform.x_end <= platform.x_start || platform.height <= 0.0 {
+            errors.push(ValidationError::DegeneratePlatform { id: platform.id.clone() });
+        } else {
+            if let Some((other, _)) = platforms.iter().find(|(_, other)| overlaps(rect, *other)) {
+                errors.push(ValidationError::OverlappingPlatforms {
+                    id: platform.id.clone(),
+                    other: other.to_string(),
+                });
+            }
+            platforms.push((&platform.id, rect));
+            solids.push((format!("platform {}", platform.id), rect));
+        }
+    }
+
+    for wall in &map.walls {
+        let rect = (wall.x, wall.x + wall.width, wall.y_bottom, wall.y_top);
+        let finite = [rect.0, rect.1, rect.2, rect.3].iter().all(|v| v.is_finite());
+        if !finite || wall.y_top <= wall.y_bottom || wall.width <= 0.0 {
+            errors.push(ValidationError::DegenerateWall { id: wall.id.clone() });
+        } else {
+            solids.push((format!("wall {}", wall.id), rect));
+        }
+    }
+
+    for zone in &map.force_zones {
+        let values = [zone.x_start, zone.x_end, zone.y_bottom, zone.y_top, zone.force_x, zone.force_y];
+        if !values.iter().all(|v| v.is_finite()) || zone.x_end <= zone.x_start || zone.y_top <= zone.y_bottom {
+            errors.push(ValidationError::DegenerateForceZone { id: zone.id.clone() });
+        }
+    }
+
+    for portal in &map.portals {
+        let values = [portal.x_start, portal.x_end, portal.y_bottom, portal.y_top, portal.rotation_degrees, portal.cooldown_secs];
+        if !values.iter().all(|v| v.is_finite()) || portal.x_end <= portal.x_start || portal.y_top <= portal.y_bottom {
+            errors.push(ValidationError::DegeneratePortal { id: portal.id.clone() });
+        }
+        if portal.exit == portal.id || !map.portals.iter().any(|other| other.id == portal.exit) {
+            errors.push(ValidationError::UnknownPortalExit {
+                id: portal.id.clone(),
+                exit: portal.exit.clone(),
+            });
+        }
+        let two_way = map.portals.iter().any(|other| other.id == portal.exit && other.exit == portal.id);
+        if two_way && portal.exit != portal.id && portal.cooldown_secs <= 0.0 {
+            errors.push(ValidationError::PortalPairWithoutCooldown {
+                id: portal.id.clone(),
+                exit: portal.exit.clone(),
+            });
+        }
+    }
+
+    for checkpoint in &map.checkpoints {
+        let finite = [checkpoint.x_start, checkpoint.x_end, checkpoint.y_bottom, checkpoint.y_top].iter().all(|v| v.is_finite());
+        if !finite || checkpoint.x_end <= checkpoint.x_start || checkpoint.y_top <= checkpoint.y_bottom {
+            errors.push(ValidationError::DegenerateCheckpoint { id: checkpoint.id.clone() });
+        }
+    }
+
+    if let Some(zone) = &map.control_zone {
+        let finite = [zone.x_start, zone.x_end, zone.y_bottom, zone.y_top].iter().all(|v| v.is_finite());
+        if !finite || zone.x_end <= zone.x_start || zone.y_top <= zone.y_bottom {
+            errors.push(ValidationError::DegenerateControlZone { id: zone.id.clone() });
+        }
+    }
+
+    for (index, spawn) in map.spawn_points.iter().enumerate() {
+        if let Some((inside, _)) = solids.iter().find(|(_, rect)| contains(*rect, spawn)) {
+            errors.push(ValidationError::SpawnInsideGeometry { index, inside: inside.clone() });
+        }
+    }
+
+    let colors = map
+        .platforms
+        .iter()
+        .map(|p| (&p.id, &p.color))
+        .chain(map.walls.iter().map(|w| (&w.id, &w.color)))
+        .chain(map.ladders.iter().map(|l| (&l.id, &l.color)))
+        .chain(map.hazards.iter().map(|h| (&h.id, &h.color)))
+        .chain(map.force_zones.iter().map(|z| (&z.id, &z.color)))
+        .chain(map.portals.iter().map(|p| (&p.id, &p.color)))
+        .chain(map.checkpoints.iter().map(|c| (&c.id, &c.color)))
+        .chain(map.control_zone.iter().map(|z| (&z.id, &z.color)));
+    for (id, color) in colors {
+        if !is_hex_color(color) {
+            errors.push(ValidationError::InvalidColor {
+                id: id.clone(),
+                color: color.clone(),
+            });
+        }
+    }
+    errors
+}
+
+impl MapGeometry {
+    /// Check the geometry on its own, as after a live edit
+    pub fn validate(&self) -> Result<(), ValidationErrors> {
+        let errors = check_geometry(self);
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(ValidationErrors(errors))
+        }
+    }
+}
+
+impl PhysicsConfig {
+    /// Check the physics on their own, as after a live change
+    pub fn validate(&self) -> Result<(), ValidationErrors> {
+        let mut errors = Vec::new();
+        check_physics(self, &mut errors);
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(ValidationErrors(errors))
+        }
+    }
+}
+
+impl GameConfig {
+    /// Check geometry, physics and colors, reporting every problem found
+    pub fn validate(&self) -> Result<(), ValidationErrors> {
+        let mut errors = Vec::new();
+        check_physics(&self.physics, &mut errors);
+        check_sandbox_bounds(self, &mut errors);
+        if self.maps.is_empty() {
+            errors.extend(check_geometry(&self.geometry()));
+        } else {
+            // The active geometry is a copy of the first map's, so only the maps are checked
+            for map in &self.maps {
+                errors.extend(check_geometry(&map.geometry).into_iter().map(|error| ValidationError::InMap {
+                    map: map.id.clone(),
+                    error: Box::new(error),
+                }));
+            }
+        }
+        if self.game_mode == GameRules::RACE {
+            if self.maps.is_empty() && self.checkpoints.is_empty() {
+                errors.push(ValidationError::RaceWithoutCheckpoints);
+            }
+            for map in self.maps.iter().filter(|map| map.geometry.checkpoints.is_empty()) {
+                errors.push(ValidationError::InMap {
+                    map: map.id.clone(),
+                    error: Box::new(ValidationError::RaceWithoutCheckpoints),
+                });
+            }
+        }
+        if self.game_mode == GameRules::KING_OF_THE_HILL {
+            if self.maps.is_empty() && self.control_zone.is_none() {
+                errors.push(ValidationError::KingOfTheHillWithoutZone);
+            }
+            for map in self.maps.iter().filter(|map| map.geometry.control_zone.is_none()) {
+                errors.push(ValidationError::InMap {
+                    map: map.id.clone(),
+                    error: Box::new(ValidationError::KingOfTheHillWithoutZone),
+                });
+            }
+        }
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(ValidationErrors(errors))
+        }
+    }
+}
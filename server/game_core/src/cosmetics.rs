@@ -0,0 +1,222 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap};
+use std::path::PathBuf;
+use crate::config::CosmeticItem;
+use crate::json_store::JsonStore;
+use crate::player::PlayerId;
+
+/// Where on the player a cosmetic is shown; one item per slot can be equipped
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CosmeticSlot {
+    Trail,
+    Hat,
+    NameColor,
+}
+
+/// How a cosmetic is earned
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum UnlockRule {
+    /// Owned by everyone from the start
+    #[default]
+    Free,
+    /// Unlocked by completing a daily challenge
+    Challenge { challenge_id: String },
+    /// Bought with coins, which challenge rewards pay out
+    Purchase { price: u64 },
+}
+
+/// The items a player shows, one per slot; serialized with the player for every client
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EquippedCosmetics {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub trail: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hat: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name_color: Option<String>,
+}
+
+impl EquippedCosmetics {
+    pub fn is_empty(&self) -> bool {
+        self.trail.is_none() && self.hat.is_none() && self.name_color.is_none()
+    }
+
+    fn slot_mut(&mut self, slot: CosmeticSlot) -> &mut Option<String> {
+        match slot {
+            CosmeticSlot::Trail => &mut self.trail,
+            CosmeticSlot::Hat => &mut self.hat,
+            CosmeticSlot::NameColor => &mut self.name_color,
+        }
+    }
+}
+
+/// A player's coins, earned items and equipped items
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CosmeticProfile {
+    pub coins: u64,
+    /// Ids of items earned or bought; free items are owned without being listed
+    pub unlocked: BTreeSet<String>,
+    pub equipped: EquippedCosmetics,
+}
+
+/// Why a purchase or equip was refused
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CosmeticError {
+    /// No item with that id in the catalog
+    UnknownItem,
+    /// The player hasn't earned or bought the item
+    Locked,
+    /// The item goes in a different slot
+    WrongSlot,
+    /// Only items with a price can be bought
+    NotForSale,
+    /// The player already owns the item
+    AlreadyOwned,
+    /// The player can't afford the item
+    InsufficientCoins { price: u64, coins: u64 },
+}
+
+impl std::fmt::Display for CosmeticError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CosmeticError::UnknownItem => write!(f, "no such cosmetic"),
+            CosmeticError::Locked => write!(f, "cosmetic not unlocked yet"),
+            CosmeticError::WrongSlot => write!(f, "cosmetic doesn't fit that slot"),
+            CosmeticError::NotForSale => write!(f, "cosmetic can't be bought"),
+            CosmeticError::AlreadyOwned => write!(f, "cosmetic already owned"),
+            CosmeticError::InsufficientCoins { price, coins } => {
+                write!(f, "cosmetic costs {} coins, you have {}", price, coins)
+            }
+        }
+    }
+}
+
+impl std::error::Error for CosmeticError {}
+
+/// Whether the profile owns the item
+pub fn owns(profile: &CosmeticProfile, item: &CosmeticItem) -> bool {
+    item.unlock == UnlockRule::Free || profile.unlocked.contains(&item.id)
+}
+
+/// Unlocks, equipped items and coins per player
+/// Optionally persisted to a JSON file so they survive restarts
+#[derive(Debug, Default)]
+pub struct CosmeticStore {
+    profiles: JsonStore<HashMap<PlayerId, CosmeticProfile>>,
+}
+
+impl CosmeticStore {
+    /// In-memory profiles that are lost on restart
+    pub fn in_memory() -> Self {
+        Self::default()
+    }
+
+    /// Load profiles from a JSON file, creating it on first write if missing
+    pub async fn load_async(path: impl Into<PathBuf>) -> Result<Self, Box<dyn std::error::Error>> {
+        Ok(Self {
+            profiles: JsonStore::load(path).await?,
+        })
+    }
+
+    /// The player's profile; players who never earned anything get an empty one
+    pub fn profile(&self, player_id: &PlayerId) -> CosmeticProfile {
+        self.profiles.get().get(player_id).cloned().unwrap_or_default()
+    }
+
+    /// Pay out a completed challenge: its reward as coins plus any items it unlocks
+    /// Returns the newly unlocked item ids; call `save` to persist
+    pub fn reward_challenge(
+        &mut self,
+        player_id: PlayerId,
+        challenge_id: &str,
+        coins: u64,
+        catalog: &[CosmeticItem],
+    ) -> Vec<String> {
+        let profile = self.profiles.get_mut().entry(player_id).or_default();
+        profile.coins += coins;
+        catalog
+            .iter()
+            .filter(|item| {
+                matches!(&item.unlock, UnlockRule::Challenge { challenge_id: id } if id == challenge_id)
+            })
+            .filter(|item| profile.unlocked.insert(item.id.clone()))
+            .map(|item| item.id.clone())
+            .collect()
+    }
+
+    /// Spend coins on an item with a price; call `save` to persist
+    pub fn purchase(
+        &mut self,
+        player_id: PlayerId,
+        item_id: &str,
+        catalog: &[CosmeticItem],
+    ) -> Result<CosmeticProfile, CosmeticError> {
+        let item = catalog.iter().find(|i| i.id == item_id).ok_or(CosmeticError::UnknownItem)?;
+        let UnlockRule::Purchase { price } = item.unlock else {
+            return Err(CosmeticError::NotForSale);
+        };
+        let profile = self.profiles.get_mut().entry(player_id).or_default();
+        if owns(profile, item) {
+            return Err(CosmeticError::AlreadyOwned);
+        }
+        if profile.coins < price {
+            return Err(CosmeticError::InsufficientCoins { price, coins: profile.coins });
+        }
+        profile.coins -= price;
+        profile.unlocked.insert(item.id.clone());
+        Ok(profile.clone())
+    }
+
+    /// Show an owned item in its slot, or clear the slot when `item_id` is None; call `save` to persist
+    pub fn equip(
+        &mut self,
+        player_id: PlayerId,
+        slot: CosmeticSlot,
+        item_id: Option<&str>,
+        catalog: &[CosmeticItem],
+    ) -> Result<EquippedCosmetics, CosmeticError> {
+        let profile = self.profiles.get_mut().entry(player_id).or_default();
+        let value = match item_id {
+            Some(item_id) => {
+                let item = catalog.iter().find(|i| i.id == item_id).ok_or(CosmeticError::UnknownItem)?;
+                if item.slot != slot {
+                    return Err(CosmeticError::WrongSlot);
+                }
+                if !owns(profile, item) {
+                    return Err(CosmeticError::Locked);
+                }
+                Some(item.id.clone())
+            }
+            None => None,
+        };
+        *profile.equipped.slot_mut(slot) = value;
+        Ok(profile.equipped.clone())
+    }
+
+    /// Forget a player's coins and items; false if they had none. Call `save` to persist
+    pub fn remove(&mut self, player_id: &PlayerId) -> bool {
+        self.profiles.get_mut().remove(player_id).is_some()
+    }
+
+    /// Rewrite the profiles file if one is configured
+    pub async fn save(&self) -> Result<(), Box<dyn std::error::Error>> {
+        self.profiles.save().await
+    }
+
+    /// Rewrite the profiles file on a background task, for callers that can't wait on the disk
+    pub fn save_in_background(&self) {
+        self.profiles.save_in_background();
+    }
+
+    pub fn len(&self) -> usize {
+        self.profiles.get().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.profiles.get().is_empty()
+    }
+}
@@ -0,0 +1,55 @@
+//! The document encodings the server reads and writes outside the Datastar event stream:
+//! world snapshots, and state frames over WebSocket or plain HTTP
+
+use serde::{Deserialize, Serialize};
+
+/// How a snapshot or state frame is written
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum Encoding {
+    /// Readable and easy to edit by hand
+    #[default]
+    #[serde(rename = "json")]
+    Json,
+    /// MessagePack with fields by name, so it decodes into the same shapes as the JSON
+    #[serde(rename = "msgpack", alias = "messagepack")]
+    MessagePack,
+}
+
+impl Encoding {
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "json" => Some(Encoding::Json),
+            "msgpack" | "messagepack" => Some(Encoding::MessagePack),
+            _ => None,
+        }
+    }
+
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            Encoding::Json => "application/json",
+            Encoding::MessagePack => "application/msgpack",
+        }
+    }
+
+    /// The encoding a content type names, ignoring parameters such as charset
+    pub fn from_content_type(content_type: &str) -> Option<Self> {
+        match content_type.split(';').next().unwrap_or_default().trim() {
+            "application/json" => Some(Encoding::Json),
+            "application/msgpack" | "application/x-msgpack" | "application/vnd.msgpack" => Some(Encoding::MessagePack),
+            _ => None,
+        }
+    }
+
+    /// The first encoding an Accept header lists, ignoring quality values; None when it
+    /// names neither, e.g. `*/*`
+    pub fn from_accept(accept: &str) -> Option<Self> {
+        accept.split(',').find_map(Self::from_content_type)
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Encoding::Json => "json",
+            Encoding::MessagePack => "msgpack",
+        }
+    }
+}
@@ -0,0 +1,256 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use crate::commands::{IgnoredReason, PlayerCommand};
+use crate::config::{GameConfig, GameModeConfig, GameRules, ScorePolicy};
+use crate::mutators::Mutator;
+use crate::player::{Player, PlayerId};
+use crate::rng::SeededRng;
+use crate::signals::Signal;
+
+/// Why a mode switch was refused
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ModeError {
+    UnknownMode,
+}
+
+impl std::fmt::Display for ModeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ModeError::UnknownMode => write!(f, "no such game mode"),
+        }
+    }
+}
+
+impl std::error::Error for ModeError {}
+
+/// When a requested mode takes effect
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ModeSwitch {
+    /// Switched right away, as no round was being played
+    Applied,
+    /// Switches once the round being played has ended
+    Pending,
+}
+
+/// The active mode and the settings in effect under it, as sent to clients
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ModeManifest {
+    pub id: String,
+    pub name: String,
+    pub description: String,
+    /// Rules the round is played by
+    pub game_mode: GameRules,
+    pub score_policy: ScorePolicy,
+    pub duration_secs: u64,
+    pub building: bool,
+    pub projectiles: bool,
+    /// Mutators the round is played with
+    pub mutators: Vec<Mutator>,
+    /// How much bigger clients should draw heads; 1 unless big heads is on
+    pub head_scale: f32,
+}
+
+impl ModeManifest {
+    /// Describe the mode a config is set up for, played with `mutators`
+    pub fn of(config: &GameConfig, mutators: &[Mutator]) -> Self {
+        let mode = find(config, &config.matches.mode);
+        Self {
+            id: config.matches.mode.clone(),
+            name: mode.map(|m| m.display_name()).unwrap_or(&config.matches.mode).to_string(),
+            description: mode.map(|m| m.description.clone()).unwrap_or_default(),
+            game_mode: config.game_mode.clone(),
+            score_policy: mode.map(|m| m.score_policy).unwrap_or_default(),
+            duration_secs: config.matches.duration_secs,
+            building: config.building.enabled,
+            projectiles: config.projectiles.enabled,
+            mutators: mutators.to_vec(),
+            head_scale: if mutators.contains(&Mutator::BigHeads) {
+                config.mutators.big_head_scale
+            } else {
+                1.0
+            },
+        }
+    }
+}
+
+/// What a mode's rules may read and change while a round is played
+pub struct ModeContext<'a> {
+    pub players: &'a mut HashMap<PlayerId, Player>,
+    pub config: &'a GameConfig,
+    /// Game clock, Unix milliseconds
+    pub now_ms: u64,
+    pub rng: &'a mut SeededRng,
+}
+
+impl ModeContext<'_> {
+    /// Players in id order, so rules applied in turn give the same results every run
+    pub fn player_ids(&self) -> Vec<PlayerId> {
+        let mut ids: Vec<PlayerId> = self.players.keys().copied().collect();
+        ids.sort();
+        ids
+    }
+}
+
+/// A mode's scoreboard and the signal clients receive it on
+#[derive(Debug, Clone, PartialEq)]
+pub struct ModeStatus {
+    pub signal: Signal,
+    pub state: serde_json::Value,
+}
+
+/// Rules a round is played by on top of movement, building and shooting
+/// The game loop only calls these hooks, so a mode plugs in without touching it
+pub trait GameMode: std::fmt::Debug + Send + Sync {
+    /// Which rules these are
+    fn rules(&self) -> GameRules;
+
+    /// A round is starting with everyone present
+    fn on_round_start(&mut self, _ctx: &mut ModeContext) {}
+
+    /// A player joined the room, round or no round
+    fn on_player_join(&mut self, _ctx: &mut ModeContext, _player_id: &PlayerId) {}
+
+    /// Apply the rules once everyone has moved this tick
+    fn on_tick(&mut self, _ctx: &mut ModeContext, _delta_time: f32) {}
+
+    /// Two living players are touching, `a` being the lower id; called after `on_tick`
+    /// for every such pair, in id order
+    fn on_collision(&mut self, _ctx: &mut ModeContext, _a: &PlayerId, _b: &PlayerId) {}
+
+    /// A player sent a command; refusing it leaves it unapplied
+    fn on_command(&mut self, _ctx: &mut ModeContext, _player_id: &PlayerId, _command: &PlayerCommand) -> Result<(), IgnoredReason> {
+        Ok(())
+    }
+
+    /// A player left the room
+    fn on_player_leave(&mut self, _player_id: &PlayerId) {}
+
+    /// The room switched to a mode playing by these rules, asking `policy` of the scores
+    /// players bring; by default a reset clears scores and combos and a keep leaves them
+    fn on_mode_enter(&mut self, ctx: &mut ModeContext, policy: ScorePolicy) {
+        if policy == ScorePolicy::Reset {
+            for player in ctx.players.values_mut() {
+                player.score = 0;
+                player.combo = Default::default();
+            }
+        }
+    }
+
+    /// Whether the round is decided before its time runs out
+    fn round_over(&self, _config: &GameConfig) -> bool {
+        false
+    }
+
+    /// Forget the round, e.g. once its results have been shown
+    fn reset(&mut self) {}
+
+    /// Signal the mode's scoreboard goes out on, if it has one
+    fn signal(&self) -> Option<Signal> {
+        None
+    }
+
+    /// The mode's scoreboard, for the signal it names
+    fn status(&self, _players: &HashMap<PlayerId, Player>, _config: &GameConfig) -> serde_json::Value {
+        serde_json::Value::Null
+    }
+
+    /// Whether the scoreboard changed since the last call
+    fn take_changed(&mut self) -> bool {
+        false
+    }
+
+    /// The round's progress under these rules, for snapshots and handing the room to
+    /// another server; rules without progress of their own save nothing
+    fn save_state(&self) -> serde_json::Value {
+        serde_json::Value::Null
+    }
+
+    /// Carry on from progress `save_state` gave; progress that doesn't read leaves the
+    /// rules as they are
+    fn load_state(&mut self, _state: &serde_json::Value) {}
+
+    fn clone_box(&self) -> Box<dyn GameMode>;
+}
+
+impl Clone for Box<dyn GameMode> {
+    fn clone(&self) -> Self {
+        self.clone_box()
+    }
+}
+
+/// Free play: scoring comes from coins, tags and challenges alone
+#[derive(Debug, Clone, Default)]
+pub struct StandardMode;
+
+impl GameMode for StandardMode {
+    fn rules(&self) -> GameRules {
+        GameRules::STANDARD
+    }
+
+    fn clone_box(&self) -> Box<dyn GameMode> {
+        Box::new(self.clone())
+    }
+}
+
+/// Fresh state for a mode, ready for its first round
+pub type ModeFactory = fn() -> Box<dyn GameMode>;
+
+/// Every mode a room can play, keyed by the name `game_mode` gives it in config
+#[derive(Debug, Clone)]
+pub struct ModeRegistry {
+    factories: BTreeMap<GameRules, ModeFactory>,
+}
+
+impl Default for ModeRegistry {
+    /// The built-in modes
+    fn default() -> Self {
+        let mut registry = Self { factories: BTreeMap::new() };
+        registry.register(|| Box::new(StandardMode));
+        registry.register(|| Box::new(crate::race::RaceMode::default()));
+        registry.register(|| Box::new(crate::tag::TagMode::default()));
+        registry.register(|| Box::new(crate::hill::HillMode::default()));
+        registry
+    }
+}
+
+impl ModeRegistry {
+    /// Add a mode under the name its rules give, replacing any mode of that name
+    pub fn register(&mut self, factory: ModeFactory) -> GameRules {
+        let rules = factory().rules();
+        self.factories.insert(rules.clone(), factory);
+        rules
+    }
+
+    /// Fresh state for playing by `rules`, if a mode of that name is registered
+    pub fn create(&self, rules: &GameRules) -> Option<Box<dyn GameMode>> {
+        self.factories.get(rules).map(|factory| factory())
+    }
+
+    /// Names of the registered modes, alphabetically
+    pub fn names(&self) -> impl Iterator<Item = &GameRules> {
+        self.factories.keys()
+    }
+}
+
+pub fn find<'a>(config: &'a GameConfig, id: &str) -> Option<&'a GameModeConfig> {
+    config.modes.iter().find(|mode| mode.id == id)
+}
+
+/// `base` with a mode's settings laid over it
+pub fn configure(base: &GameConfig, mode: &GameModeConfig) -> GameConfig {
+    let mut config = base.clone();
+    config.matches.mode = mode.id.clone();
+    if let Some(game_mode) = &mode.game_mode {
+        config.game_mode = game_mode.clone();
+    }
+    if let Some(duration_secs) = mode.duration_secs {
+        config.matches.duration_secs = duration_secs;
+    }
+    if let Some(building) = mode.building {
+        config.building.enabled = building;
+    }
+    if let Some(projectiles) = mode.projectiles {
+        config.projectiles.enabled = projectiles;
+    }
+    config
+}
@@ -1,37 +1,1890 @@
 use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
 use crate::player::{Player, PlayerId};
-use crate::commands::PlayerCommand;
+use crate::commands::{CommandAck, CommandOutcome, Cooldowns, IgnoredReason, PlayerCommand};
+use crate::blocks::{Block, BlockGrid, BuildError};
+use crate::physics::{BounceEvent, PhysicsWorld, TeleportEvent};
+use crate::scoring::{ComboBreak, ScoreSource};
+use crate::challenges::{ChallengeCompletion, ChallengeTracker, QuestEvent};
+use crate::names::NameError;
+use crate::clock::{SharedClock, SystemClock};
+use crate::room::{Admission, WaitingQueue};
+use crate::teams::{TeamError, TeamId};
+use crate::match_history::MatchRecord;
+use crate::match_state::{MatchResult, MatchState};
+use crate::respawn::{LifeEvent, LifeState};
+use crate::projectiles::{Impact, Projectile, ShootError};
+use crate::health::{DamageEvent, DamageSource};
+use crate::map_rotation::{MapChange, MapRotation, MapVotes, VoteError};
+use crate::game_mode::{GameMode, ModeContext, ModeError, ModeFactory, ModeManifest, ModeRegistry, ModeStatus, ModeSwitch};
+use crate::map_editor::{MapEdit, MapEditError};
+use crate::analytics::Analytics;
+use crate::rng::SeededRng;
+use crate::mutators::{Mutator, MutatorBallot, MutatorError, MutatorVotes};
+use crate::config::{GameConfig, GameRules, MapGeometry, PhysicsConfig, SlowMotionTrigger};
+use crate::physics_sandbox::PhysicsPatchError;
+use crate::snapshot::{SnapshotError, WorldSnapshot, SNAPSHOT_VERSION};
+use crate::housing::{Housing, PlotError};
+use crate::kill_cam::{InputHistory, KillCam};
+use crate::anti_cheat::{AntiCheat, CheatReport};
+use crate::world_events::{EntityKind, WorldEventChange, WorldEventError, WorldEventKind, WorldEvents, SPEED_FRENZY_EFFECT};
+
+/// Room-wide slow motion, running until a real time
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct SlowMotion {
+    /// Fraction of normal speed
+    pub scale: f32,
+    /// Unix milliseconds by the game clock when it ends
+    pub until_ms: u64,
+}
 
 #[derive(Debug, Clone)]
 pub struct GameState {
+    /// Physics world (config and static geometry) this state simulates in
+    pub world: Arc<PhysicsWorld>,
+    /// Number of fixed simulation steps run so far, increases monotonically
+    pub tick: u64,
     pub players: HashMap<PlayerId, Player>,
+    /// Players spectating until a slot opens
+    pub waiting: WaitingQueue,
+    /// Runtime blocks placed by players
+    pub blocks: BlockGrid,
+    /// Last placement time per player, for the building cooldown
+    last_block_placed: HashMap<PlayerId, std::time::SystemTime>,
+    /// Combos that expired since the last drain, for broadcasting
+    combo_breaks: Vec<ComboBreak>,
+    /// Today's challenges and every player's progress on them
+    pub challenges: ChallengeTracker,
+    /// Challenges completed since the last drain, for broadcasting
+    challenge_completions: Vec<ChallengeCompletion>,
+    /// Phase of the current match
+    pub match_state: MatchState,
+    /// Phases entered since the last drain, for broadcasting
+    match_transitions: Vec<MatchState>,
+    /// Matches finished since the last drain, for match history
+    finished_matches: Vec<MatchRecord>,
+    /// Deaths and respawns since the last drain, for broadcasting
+    life_events: Vec<LifeEvent>,
+    /// Projectiles in flight
+    pub projectiles: Vec<Projectile>,
+    /// Id for the next projectile fired
+    next_projectile_id: u64,
+    /// Last shot time per player, for the shooting cooldown
+    last_shot: HashMap<PlayerId, std::time::SystemTime>,
+    /// Damage taken since the last drain, for broadcasting
+    damage_events: Vec<DamageEvent>,
+    /// Bounces off springy surfaces since the last drain, for broadcasting
+    bounce_events: Vec<BounceEvent>,
+    /// Trips through portals since the last drain, for broadcasting
+    teleport_events: Vec<TeleportEvent>,
+    /// Fractional damage-per-second hazard damage owed per player and hazard, paid in whole points
+    hazard_exposure: HashMap<(PlayerId, String), f32>,
+    /// Modes that can be played here, by name
+    modes: ModeRegistry,
+    /// Rules of the mode being played and the round's progress under them
+    rules: Box<dyn GameMode>,
+    /// Scoreboards of the rules in play since the last drain, for broadcasting
+    mode_statuses: Vec<ModeStatus>,
+    /// Map being played and votes for the next one
+    pub maps: MapRotation,
+    /// Map switches since the last drain, for broadcasting
+    map_changes: Vec<MapChange>,
+    /// Whether an admin edited the map since the last broadcast
+    map_edited: bool,
+    /// Actions and lead changes for the overlay stream
+    pub analytics: Analytics,
+    /// Every random choice the simulation makes; seeded from the config for reproducible runs
+    pub rng: SeededRng,
+    /// Config the state was created with, before any game mode's settings
+    base_config: Arc<GameConfig>,
+    /// Mode to switch to once the round being played ends
+    pending_mode: Option<String>,
+    /// Mode switches since the last drain, for broadcasting
+    mode_changes: Vec<ModeManifest>,
+    /// Votes and admin picks for the next round's mutators
+    pub mutators: MutatorBallot,
+    /// Mutators the current round is played with
+    active_mutators: Vec<Mutator>,
+    /// Whether the active mutators changed since the last broadcast
+    mutators_changed: bool,
+    /// Hub plots, their owners and the houses built in them
+    pub housing: Housing,
+    /// The random event under way, the next one's countdown and the entities events dropped
+    pub world_events: WorldEvents,
+    /// Every player's recent frames, for kill cams
+    pub input_history: InputHistory,
+    /// Players killed by other players this step, waiting for the step's frame: victim,
+    /// killer and cause
+    pending_kill_cams: Vec<(PlayerId, PlayerId, crate::respawn::DeathCause)>,
+    /// Kill cams built since the last drain, for sending to their victims
+    kill_cams: Vec<KillCam>,
+    /// Input rates and movement the anti-cheat checks run on
+    pub anti_cheat: AntiCheat,
+    /// Players flagged since the last drain, with their replay checks
+    cheat_reports: Vec<CheatReport>,
+    /// Whether this is a copy replaying a suspect's inputs, which keeps no history and
+    /// flags nobody
+    replaying: bool,
+    /// First player to join the empty room; they may change its physics until they leave
+    pub room_creator: Option<PlayerId>,
+    /// Bumped whenever settings clients read from /api/config change while the room runs
+    config_version: u64,
+    /// Whether the config version changed since the last broadcast
+    config_changed: bool,
+    /// Room-wide slow motion in effect, if any
+    slow_motion: Option<SlowMotion>,
+    /// Game clock time before which a kill can't start slow motion again
+    kill_slow_motion_after_ms: u64,
+    /// Time source for activity timestamps, cooldowns and the challenge day
+    pub clock: SharedClock,
 }
 
 impl GameState {
-    pub fn new() -> Self {
+    pub fn new(world: Arc<PhysicsWorld>) -> Self {
+        Self::with_clock(world, Arc::new(SystemClock))
+    }
+
+    pub fn with_clock(world: Arc<PhysicsWorld>, clock: SharedClock) -> Self {
+        // Start with the settings of the mode the config names, if it defines one
+        let base_config = world.config().clone();
+        let world = match crate::game_mode::find(&base_config, &base_config.matches.mode) {
+            Some(mode) => Arc::new(PhysicsWorld::new(Arc::new(crate::game_mode::configure(&base_config, mode)))),
+            None => world,
+        };
+        let mut state = Self::blank(world, base_config, clock);
+        state.restore_plot_blocks();
+        state
+    }
+
+    /// A room playing in `world` with nobody and nothing in it yet
+    fn blank(world: Arc<PhysicsWorld>, base_config: Arc<GameConfig>, clock: SharedClock) -> Self {
+        // Claims are read from disk later, by restore_housing, so construction does no IO
+        let housing = Housing::new(&base_config.housing);
+        let modes = ModeRegistry::default();
+        let rules = create_rules(&modes, &world.config().game_mode);
         Self {
+            world,
+            tick: 0,
             players: HashMap::new(),
+            waiting: WaitingQueue::default(),
+            blocks: BlockGrid::new(),
+            last_block_placed: HashMap::new(),
+            combo_breaks: Vec::new(),
+            challenges: ChallengeTracker::new(crate::challenges::day_from_unix(clock.unix_secs())),
+            challenge_completions: Vec::new(),
+            match_state: MatchState::Lobby,
+            match_transitions: Vec::new(),
+            finished_matches: Vec::new(),
+            life_events: Vec::new(),
+            projectiles: Vec::new(),
+            next_projectile_id: 1,
+            last_shot: HashMap::new(),
+            damage_events: Vec::new(),
+            bounce_events: Vec::new(),
+            teleport_events: Vec::new(),
+            hazard_exposure: HashMap::new(),
+            modes,
+            rules,
+            mode_statuses: Vec::new(),
+            maps: MapRotation::default(),
+            map_changes: Vec::new(),
+            map_edited: false,
+            analytics: Analytics::default(),
+            rng: base_config.determinism.seed.map_or_else(SeededRng::from_entropy, SeededRng::new),
+            base_config,
+            pending_mode: None,
+            mode_changes: Vec::new(),
+            mutators: MutatorBallot::default(),
+            active_mutators: Vec::new(),
+            mutators_changed: false,
+            housing,
+            world_events: WorldEvents::default(),
+            input_history: InputHistory::default(),
+            pending_kill_cams: Vec::new(),
+            kill_cams: Vec::new(),
+            anti_cheat: AntiCheat::default(),
+            cheat_reports: Vec::new(),
+            replaying: false,
+            room_creator: None,
+            config_version: 1,
+            config_changed: false,
+            slow_motion: None,
+            kill_slow_motion_after_ms: 0,
+            clock,
         }
     }
 
     pub fn add_player(&mut self, player_id: PlayerId) {
-        self.players.insert(player_id, Player::new(player_id));
+        let config = self.world.config();
+        let mut player = Player::new(player_id, &config.physics);
+        player.last_activity = self.clock.now();
+        player.last_input = player.last_activity;
+        player.health = config.health.max_health;
+        (player.x, player.y) = crate::respawn::spawn_position(config, &self.players, &player_id);
+        // New players balance the teams
+        if let Some(team) = crate::teams::balanced_team(&config.teams, &self.players)
+            .and_then(|team_id| crate::teams::find(&config.teams, &team_id))
+        {
+            player.team = Some(team.id.clone());
+            player.team_color = Some(team.color.clone());
+        }
+        self.players.insert(player_id, player);
+        self.with_rules(|rules, ctx| rules.on_player_join(ctx, &player_id));
+    }
+
+    /// Move a player to a team of their choice, if it keeps the teams balanced
+    pub fn join_team(&mut self, player_id: &PlayerId, team_id: &str) -> Result<TeamId, TeamError> {
+        let config = self.world.config();
+        if config.teams.teams.is_empty() {
+            return Err(TeamError::Disabled);
+        }
+        let team = crate::teams::find(&config.teams, team_id).ok_or(TeamError::UnknownTeam)?;
+        let current = self.players.get(player_id).ok_or(TeamError::UnknownPlayer)?.team.clone();
+        if current.as_deref() == Some(team_id) {
+            return Ok(team.id.clone());
+        }
+
+        let sizes = crate::teams::team_sizes(&config.teams, &self.players);
+        if !crate::teams::move_allowed(&sizes, current.as_deref(), team_id, config.teams.max_imbalance) {
+            return Err(TeamError::Unbalanced);
+        }
+        if let Some(player) = self.players.get_mut(player_id) {
+            player.team = Some(team.id.clone());
+            player.team_color = Some(team.color.clone());
+        }
+        Ok(team.id.clone())
+    }
+
+    /// Add a player if the room has a free slot, otherwise queue them as a spectator
+    /// Idempotent: players already playing or waiting keep their place
+    pub fn join(&mut self, player_id: PlayerId) -> Admission {
+        if self.players.contains_key(&player_id) {
+            return Admission::Playing;
+        }
+        let now = self.clock.now();
+        if self.waiting.touch(&player_id, now) {
+            return Admission::Waiting {
+                position: self.waiting.position(&player_id).unwrap_or(1),
+            };
+        }
+        if self.has_open_slot() {
+            self.add_player(player_id);
+            self.room_creator.get_or_insert(player_id);
+            return Admission::Playing;
+        }
+        let room = &self.world.config().room;
+        if room.max_waiting > 0 && self.waiting.len() >= room.max_waiting {
+            return Admission::Full;
+        }
+        let position = self.waiting.push(player_id, now);
+        eprintln!("⏳ Room full, {} is waiting for a slot (position {})", player_id, position);
+        Admission::Waiting { position }
     }
 
+    /// Add a server-controlled player if the room has a free slot; bots never wait in the queue
+    pub fn add_bot(&mut self, player_id: PlayerId, name: &str) -> bool {
+        if self.players.contains_key(&player_id) || !self.has_open_slot() {
+            return false;
+        }
+        self.add_player(player_id);
+        if let Some(player) = self.players.get_mut(&player_id) {
+            player.name = name.to_string();
+            player.bot = true;
+        }
+        true
+    }
+
+    /// Whether another player fits in the room; a max of zero means unlimited
+    pub fn has_open_slot(&self) -> bool {
+        let max_players = self.world.config().room.max_players;
+        max_players == 0 || self.players.len() < max_players
+    }
+
+    /// Promote waiting players into free slots, front of the queue first
+    fn fill_open_slots(&mut self) {
+        while self.has_open_slot() {
+            let Some(player_id) = self.waiting.pop_front() else { break };
+            eprintln!("🎟️ Slot opened, promoting {} from the waiting queue", player_id);
+            self.add_player(player_id);
+            self.room_creator.get_or_insert(player_id);
+        }
+    }
+
+    /// Change a player's display name after validating it and checking uniqueness in the room
+    /// The rename cooldown only applies once the player has chosen a name before
+    pub fn rename_player(&mut self, player_id: &PlayerId, desired: &str) -> Result<String, NameError> {
+        let config = self.world.config();
+        let name = crate::names::validate_name(desired, &config.names)?;
+
+        let taken = self
+            .players
+            .values()
+            .any(|p| &p.id != player_id && p.name.to_lowercase() == name.to_lowercase());
+        if taken {
+            return Err(NameError::Taken);
+        }
+
+        let player = self.players.get_mut(player_id).ok_or(NameError::UnknownPlayer)?;
+        let now = self.clock.now();
+        if let Some(last) = player.last_renamed {
+            let cooldown = std::time::Duration::from_secs(config.names.rename_cooldown_secs);
+            if now.duration_since(last).map(|d| d < cooldown).unwrap_or(false) {
+                return Err(NameError::Cooldown);
+            }
+        }
+        player.name = name.clone();
+        player.last_renamed = Some(now);
+        Ok(name)
+    }
+
+    /// Remove a player along with every block they placed, or take them out of the queue
+    /// The freed slot goes to the next waiting player
     pub fn remove_player(&mut self, player_id: &PlayerId) {
+        if self.waiting.remove(player_id) {
+            return;
+        }
         self.players.remove(player_id);
+        if self.room_creator == Some(*player_id) {
+            self.room_creator = None;
+        }
+        self.input_history.forget(player_id);
+        self.anti_cheat.forget(player_id);
+        self.remove_loose_blocks(player_id);
+        self.last_block_placed.remove(player_id);
+        self.last_shot.remove(player_id);
+        self.maps.forget(player_id);
+        self.mutators.forget(player_id);
+        self.analytics.forget(player_id);
+        self.rules.on_player_leave(player_id);
+        self.fill_open_slots();
     }
 
-    pub fn apply_command(&mut self, player_id: &PlayerId, command: &PlayerCommand) {
-        if let Some(player) = self.players.get_mut(player_id) {
-            crate::physics::apply_command(player, command);
+    /// Mark players AFK once they've gone the configured time without input
+    /// Input clears the flag again, see `Player::record_input`; bots never go AFK
+    pub fn mark_afk(&mut self) {
+        let afk_after = std::time::Duration::from_secs(self.world.config().presence.afk_after_secs);
+        if afk_after.is_zero() {
+            return;
+        }
+        let now = self.clock.now();
+        for player in self.players.values_mut().filter(|p| !p.bot && !p.afk) {
+            if now.duration_since(player.last_input).unwrap_or_default() > afk_after {
+                eprintln!("💤 {} ({}) is AFK", player.id, player.name);
+                player.afk = true;
+            }
         }
     }
 
-    pub fn update(&mut self, delta_time: f32) {
+    /// Apply a command from a player
+    /// `seq` is the client's sequence number; stale or duplicate sequence numbers are dropped,
+    /// and zero means the client doesn't track sequence numbers
+    pub fn apply_command(&mut self, player_id: &PlayerId, command: &PlayerCommand, seq: u64) -> CommandOutcome {
+        let sender = self.check_sender(player_id, seq).map(|_| ());
+        // Commands from dead players still count as processed
+        if seq > 0 && !matches!(sender, Err(IgnoredReason::Stale)) {
+            if let Some(player) = self.players.get_mut(player_id) {
+                player.last_processed_seq = seq;
+            }
+        }
+        if let Err(reason) = sender {
+            return CommandOutcome::ignored(reason);
+        }
+        if let Err(reason) = self.with_rules(|rules, ctx| rules.on_command(ctx, player_id, command)) {
+            return CommandOutcome::ignored(reason);
+        }
+        self.analytics.record_action(*player_id, self.clock.unix_millis());
+        self.input_history.record_input(*player_id, command);
+        self.anti_cheat.record_input(*player_id, self.tick);
+
+        match command {
+            PlayerCommand::PlaceBlock { x, y } => match self.place_block(player_id, *x, *y) {
+                Ok(_) => CommandOutcome::Accepted,
+                Err(e) => {
+                    eprintln!("🧱 Rejected block placement from {}: {}", player_id, e);
+                    CommandOutcome::ignored(IgnoredReason::Build(e))
+                }
+            },
+            PlayerCommand::RemoveBlock { x, y } => match self.remove_block(player_id, *x, *y) {
+                Ok(_) => CommandOutcome::Accepted,
+                Err(e) => {
+                    eprintln!("🧱 Rejected block removal from {}: {}", player_id, e);
+                    CommandOutcome::ignored(IgnoredReason::Build(e))
+                }
+            },
+            PlayerCommand::Shoot { dir_x, dir_y } => match self.shoot(player_id, *dir_x, *dir_y) {
+                Ok(_) => CommandOutcome::Accepted,
+                Err(e) => {
+                    eprintln!("🎯 Rejected shot from {}: {}", player_id, e);
+                    CommandOutcome::ignored(IgnoredReason::Shoot(e))
+                }
+            },
+            _ => {
+                let Some(player) = self.players.get_mut(player_id) else {
+                    return CommandOutcome::ignored(IgnoredReason::UnknownPlayer);
+                };
+                let was_grounded = player.ground_state.is_grounded();
+                let outcome = self.world.apply_command(player, command);
+                if was_grounded && player.ground_state.is_flying() {
+                    self.record_quest_event(player_id, QuestEvent::Jumped);
+                }
+                outcome
+            }
+        }
+    }
+
+    /// What `apply_command` would do with a command now, and the cooldowns it would leave,
+    /// without changing anything
+    /// Commands queued ahead of it can still change the outcome by the time it is applied
+    pub fn preview_command(&self, player_id: &PlayerId, command: &PlayerCommand, seq: u64) -> CommandAck {
+        let mut cooldowns = self.cooldowns(player_id);
+        let outcome = match self.check_sender(player_id, seq) {
+            Err(reason) => CommandOutcome::ignored(reason),
+            Ok(player) => match command {
+                PlayerCommand::PlaceBlock { x, y } => match self.check_place(player, *x, *y) {
+                    Ok(_) => {
+                        cooldowns.place_block_ms = self.world.config().building.place_cooldown_ms;
+                        CommandOutcome::Accepted
+                    }
+                    Err(e) => CommandOutcome::ignored(IgnoredReason::Build(e)),
+                },
+                PlayerCommand::RemoveBlock { x, y } => match self.check_remove(player, *x, *y) {
+                    Ok(_) => CommandOutcome::Accepted,
+                    Err(e) => CommandOutcome::ignored(IgnoredReason::Build(e)),
+                },
+                PlayerCommand::Shoot { dir_x, dir_y } => match self.check_shot(player, *dir_x, *dir_y) {
+                    Ok(_) => {
+                        cooldowns.shoot_ms = self.world.config().projectiles.cooldown_ms;
+                        CommandOutcome::Accepted
+                    }
+                    Err(e) => CommandOutcome::ignored(IgnoredReason::Shoot(e)),
+                },
+                _ => {
+                    let mut preview = player.clone();
+                    let outcome = self.world.apply_command(&mut preview, command);
+                    cooldowns.dash_ms = secs_to_ms(preview.dash_secs);
+                    outcome
+                }
+            },
+        };
+        CommandAck { outcome, cooldowns }
+    }
+
+    /// Milliseconds until the player can dash, shoot and place a block again
+    pub fn cooldowns(&self, player_id: &PlayerId) -> Cooldowns {
+        let config = self.world.config();
+        let now = self.clock.now();
+        let remaining = |last: Option<&std::time::SystemTime>, cooldown_ms: u64| {
+            match last.map(|last| now.duration_since(*last)) {
+                Some(Ok(elapsed)) => cooldown_ms.saturating_sub(elapsed.as_millis() as u64),
+                _ => 0,
+            }
+        };
+        Cooldowns {
+            dash_ms: self.players.get(player_id).map_or(0, |player| secs_to_ms(player.dash_secs)),
+            shoot_ms: remaining(self.last_shot.get(player_id), config.projectiles.cooldown_ms),
+            place_block_ms: remaining(self.last_block_placed.get(player_id), config.building.place_cooldown_ms),
+        }
+    }
+
+    /// The player sending a command, if they can act on it: in the game, alive, and with a
+    /// sequence number that wasn't processed yet (zero means untracked)
+    fn check_sender(&self, player_id: &PlayerId, seq: u64) -> Result<&Player, IgnoredReason> {
+        let player = self.players.get(player_id).ok_or(IgnoredReason::UnknownPlayer)?;
+        if seq > 0 && seq <= player.last_processed_seq {
+            return Err(IgnoredReason::Stale);
+        }
+        // Dead players sit out until they respawn
+        if !player.life.is_alive() {
+            return Err(IgnoredReason::Dead);
+        }
+        Ok(player)
+    }
+
+    /// Place a block in the cell containing (x, y), enforcing budget and anti-grief rules
+    pub fn place_block(&mut self, player_id: &PlayerId, x: f32, y: f32) -> Result<Block, BuildError> {
+        let player = self.players.get(player_id).ok_or(BuildError::UnknownPlayer)?;
+        let (cell, plot_id) = self.check_place(player, x, y)?;
+        let block = Block {
+            cell_x: cell.0,
+            cell_y: cell.1,
+            owner: *player_id,
+            color: crate::player_color::get_player_color(player_id),
+        };
+        self.blocks.insert(block.clone());
+        if let Some(id) = plot_id {
+            self.housing.add_block(id, block.clone());
+        }
+        self.last_block_placed.insert(*player_id, self.clock.now());
+        self.record_quest_event(player_id, QuestEvent::BlockPlaced);
+        Ok(block)
+    }
+
+    /// The cell a placement would fill and the plot it is in, if the player may build there
+    fn check_place(&self, player: &Player, x: f32, y: f32) -> Result<(crate::blocks::Cell, Option<u32>), BuildError> {
+        let player_id = &player.id;
+        let config = self.world.config();
+        let building = &config.building;
+        if !building.enabled {
+            return Err(BuildError::Disabled);
+        }
+
+        let cell = BlockGrid::cell_at(x, y, building.block_size);
+        if !self.within_reach(player, cell) {
+            return Err(BuildError::OutOfReach);
+        }
+        // Houses have their own budget; only the plot's owner builds in it
+        let plot_id = self.housing.plot_at(cell, building.block_size).map(|plot| plot.id);
+        match plot_id {
+            Some(id) if self.housing.owner_of(id) != Some(player_id) => return Err(BuildError::NotYourPlot),
+            Some(id) if !self.housing.has_room(id) => return Err(BuildError::BudgetExhausted),
+            Some(_) => {}
+            None => {
+                let loose = self.blocks.count_owned(player_id).saturating_sub(self.housing.block_count_of(player_id));
+                if loose >= building.max_blocks_per_player {
+                    return Err(BuildError::BudgetExhausted);
+                }
+            }
+        }
+        let now = self.clock.now();
+        if let Some(last) = self.last_block_placed.get(player_id) {
+            let cooldown = std::time::Duration::from_millis(building.place_cooldown_ms);
+            if now.duration_since(*last).map(|d| d < cooldown).unwrap_or(false) {
+                return Err(BuildError::Cooldown);
+            }
+        }
+        if self.blocks.get(&cell).is_some() || self.cell_overlaps_static_geometry(cell, building.block_size) {
+            return Err(BuildError::Occupied);
+        }
+        if self.cell_overlaps_player(cell, building.block_size) {
+            return Err(BuildError::OverlapsPlayer);
+        }
+        Ok((cell, plot_id))
+    }
+
+    /// Remove the player's own block from the cell containing (x, y)
+    pub fn remove_block(&mut self, player_id: &PlayerId, x: f32, y: f32) -> Result<Block, BuildError> {
+        let player = self.players.get(player_id).ok_or(BuildError::UnknownPlayer)?;
+        let (cell, plot_id) = self.check_remove(player, x, y)?;
+        if let Some(id) = plot_id {
+            self.housing.remove_block(id, cell);
+        }
+        self.blocks.remove(&cell).ok_or(BuildError::NoBlock)
+    }
+
+    /// The cell holding the player's block at (x, y) and the plot it is in, if they may remove it
+    fn check_remove(&self, player: &Player, x: f32, y: f32) -> Result<(crate::blocks::Cell, Option<u32>), BuildError> {
+        let player_id = &player.id;
+        let config = self.world.config();
+        let building = &config.building;
+        if !building.enabled {
+            return Err(BuildError::Disabled);
+        }
+
+        let cell = BlockGrid::cell_at(x, y, building.block_size);
+        if !self.within_reach(player, cell) {
+            return Err(BuildError::OutOfReach);
+        }
+        let plot_id = self.housing.plot_at(cell, building.block_size).map(|plot| plot.id);
+        if plot_id.is_some_and(|id| self.housing.owner_of(id) != Some(player_id)) {
+            return Err(BuildError::NotYourPlot);
+        }
+        match self.blocks.get(&cell) {
+            None => return Err(BuildError::NoBlock),
+            Some(block) if &block.owner != player_id => return Err(BuildError::NotOwner),
+            Some(_) => {}
+        }
+        Ok((cell, plot_id))
+    }
+
+    /// Remove a leaving player's blocks, except the house in their plot
+    fn remove_loose_blocks(&mut self, player_id: &PlayerId) {
+        let cells: Vec<crate::blocks::Cell> = self
+            .blocks
+            .blocks
+            .iter()
+            .filter(|(_, block)| &block.owner == player_id && !self.housing.holds(block))
+            .map(|(cell, _)| *cell)
+            .collect();
+        for cell in cells {
+            self.blocks.remove(&cell);
+        }
+    }
+
+    /// Put back any house block missing from the grid, e.g. after a map change cleared it
+    fn restore_plot_blocks(&mut self) {
+        let missing: Vec<Block> = self
+            .housing
+            .blocks()
+            .filter(|block| self.blocks.get(&(block.cell_x, block.cell_y)).is_none())
+            .cloned()
+            .collect();
+        for block in missing {
+            self.blocks.insert(block);
+        }
+    }
+
+    /// Claim a hub plot for an online registered player: the one asked for, or the first
+    /// free one
+    pub fn claim_plot(&mut self, player_id: &PlayerId, plot_id: Option<u32>) -> Result<u32, PlotError> {
+        let player = self.players.get(player_id).ok_or(PlotError::UnknownPlayer)?;
+        if !player.registered {
+            return Err(PlotError::Unregistered);
+        }
+        let name = player.name.clone();
+        self.housing.claim(*player_id, &name, plot_id, self.clock.unix_secs())
+    }
+
+    /// Give up the player's plot, knocking down the house built in it
+    pub fn release_plot(&mut self, player_id: &PlayerId) -> Option<u32> {
+        let (plot_id, claim) = self.housing.release(player_id)?;
+        for block in claim.blocks {
+            self.blocks.remove(&(block.cell_x, block.cell_y));
+        }
+        Some(plot_id)
+    }
+
+    /// Teleport a player to a plot, landing above whatever is built there
+    /// Not while a match is being played, so plots can't be used to dodge it
+    pub fn visit_plot(&mut self, player_id: &PlayerId, plot_id: u32) -> Result<(f32, f32), PlotError> {
+        if !self.housing.enabled() {
+            return Err(PlotError::Disabled);
+        }
+        let plot = self.housing.plot(plot_id).ok_or(PlotError::UnknownPlot)?;
+        if self.world.config().matches.enabled && matches!(self.match_state, MatchState::Playing { .. }) {
+            return Err(PlotError::MatchInProgress);
+        }
+        let (x, y) = plot.arrival(self.world.config().physics.player_height);
+        let player = self.players.get_mut(player_id).ok_or(PlotError::UnknownPlayer)?;
+        if !player.life.is_alive() {
+            return Err(PlotError::NotAlive);
+        }
+        player.x = x;
+        player.y = y;
+        player.teleports += 1;
+        player.velocity_x = 0.0;
+        player.velocity_y = 0.0;
+        player.ground_state = crate::GroundState::Flying;
+        player.dash_secs = 0.0;
+        player.slide_secs = 0.0;
+        player.jump_buffer_secs = 0.0;
+        player.held = None;
+        Ok((x, y))
+    }
+
+    /// Save plot claims in the background if they or the houses changed since the last call
+    pub fn save_housing(&mut self) -> bool {
+        self.housing.save_changes()
+    }
+
+    /// Swap in plots loaded with [`Housing::load`] and rebuild the houses in them
+    pub fn restore_housing(&mut self, housing: Housing) {
+        self.housing = housing;
+        self.restore_plot_blocks();
+    }
+
+    /// Award points to a player, applying and escalating their combo multiplier
+    /// Returns the points actually awarded, or None if the player is unknown
+    pub fn award_points(&mut self, player_id: &PlayerId, source: ScoreSource) -> Option<u64> {
+        let config = self.world.config();
+        let player = self.players.get_mut(player_id)?;
+        let points = player.combo.register(source, &config.combo);
+        player.score += points;
+        let combo = player.combo.count;
+        self.record_quest_event(player_id, QuestEvent::PointsEarned(points));
+        self.record_quest_event(player_id, QuestEvent::ComboReached(combo));
+        Some(points)
+    }
+
+    /// Feed an event to the quest engine, paying out rewards for completed challenges
+    fn record_quest_event(&mut self, player_id: &PlayerId, event: QuestEvent) {
+        for completion in self.challenges.record(player_id, event) {
+            if let Some(player) = self.players.get_mut(player_id) {
+                player.score += completion.reward_points;
+            }
+            self.challenge_completions.push(completion);
+        }
+    }
+
+    /// Take challenge completions recorded since the last call, for broadcasting
+    pub fn drain_challenge_completions(&mut self) -> Vec<ChallengeCompletion> {
+        std::mem::take(&mut self.challenge_completions)
+    }
+
+    /// Take match phases entered since the last call, for broadcasting
+    pub fn drain_match_transitions(&mut self) -> Vec<MatchState> {
+        std::mem::take(&mut self.match_transitions)
+    }
+
+    /// Take matches finished since the last call, for match history
+    pub fn drain_finished_matches(&mut self) -> Vec<MatchRecord> {
+        std::mem::take(&mut self.finished_matches)
+    }
+
+    /// Move the match through its phases as players arrive and timers run out
+    fn advance_match(&mut self) {
+        let world = self.world.clone();
+        let config = &world.config().matches;
+        if !config.enabled {
+            return;
+        }
+        let now = self.clock.unix_millis();
+        let enough_players = self.players.len() >= config.min_players.max(1);
+
+        let next = match &self.match_state {
+            MatchState::Lobby if enough_players => Some(MatchState::Countdown {
+                starts_at_ms: now + config.countdown_secs * 1000,
+            }),
+            MatchState::Countdown { .. } if !enough_players => Some(MatchState::Lobby),
+            MatchState::Countdown { starts_at_ms } if now >= *starts_at_ms => {
+                self.start_round_mutators();
+                // Everyone starts the match from zero
+                let max_health = self.world.config().health.max_health;
+                let max_stamina = self.world.config().physics.max_stamina;
+                for player in self.players.values_mut() {
+                    player.score = 0;
+                    player.combo = Default::default();
+                    player.health = max_health;
+                    player.stamina = max_stamina;
+                }
+                self.analytics.start_match();
+                self.with_rules(|rules, ctx| rules.on_round_start(ctx));
+                Some(MatchState::Playing {
+                    started_at_ms: now,
+                    ends_at_ms: now + config.duration_secs * 1000,
+                })
+            }
+            MatchState::Playing { started_at_ms, ends_at_ms } if now >= *ends_at_ms || self.players.is_empty() || self.rules.round_over(self.world.config()) => {
+                let mut record = MatchRecord::from_state(self, &config.mode, started_at_ms / 1000, now / 1000);
+                record.id = self.rng.uuid();
+                let result = MatchResult::from_record(&record, &self.players, &world.config().teams);
+                if !record.participants.is_empty() {
+                    self.finished_matches.push(record);
+                }
+                self.trigger_slow_motion(SlowMotionTrigger::MatchEnd);
+                Some(MatchState::Ended {
+                    result,
+                    lobby_at_ms: now + config.results_secs * 1000,
+                })
+            }
+            MatchState::Ended { lobby_at_ms, .. } if now >= *lobby_at_ms => {
+                self.set_mutators(Vec::new());
+                self.rules.reset();
+                if let Some(mode) = self.pending_mode.take() {
+                    self.apply_mode(&mode);
+                }
+                self.rotate_map();
+                Some(MatchState::Lobby)
+            }
+            _ => None,
+        };
+
+        if let Some(next) = next {
+            self.match_transitions.push(next.clone());
+            self.match_state = next;
+        }
+    }
+
+    /// Vote for the map to play after the current match; returns the chosen map's id
+    pub fn vote_map(&mut self, player_id: &PlayerId, map: &str) -> Result<String, VoteError> {
+        if !self.players.contains_key(player_id) {
+            return Err(VoteError::UnknownPlayer);
+        }
+        let world = self.world.clone();
+        let config = world.config();
+        self.maps
+            .vote(&config.maps, &config.map_rotation, *player_id, map)
+            .map(|chosen| chosen.id.clone())
+    }
+
+    /// The configured maps with their votes, in rotation order
+    pub fn map_votes(&self) -> Vec<MapVotes> {
+        self.maps.tally(&self.world.config().maps)
+    }
+
+    /// Take map switches recorded since the last call, for broadcasting
+    pub fn drain_map_changes(&mut self) -> Vec<MapChange> {
+        std::mem::take(&mut self.map_changes)
+    }
+
+    /// Apply an admin's edit to the live map and rebuild the collision world around it
+    /// Edits last until the rotation loads another map
+    pub fn edit_map(&mut self, edit: MapEdit) -> Result<MapGeometry, MapEditError> {
+        let geometry = crate::map_editor::apply(&self.world.config().geometry(), edit)?;
+        let mut config = (**self.world.config()).clone();
+        config.set_geometry(geometry.clone());
+        self.world = Arc::new(PhysicsWorld::new(Arc::new(config)));
+        self.map_edited = true;
+        Ok(geometry)
+    }
+
+    /// The map's geometry if it was edited since the last call, for broadcasting
+    pub fn take_map_edit(&mut self) -> Option<MapGeometry> {
+        std::mem::take(&mut self.map_edited).then(|| self.world.config().geometry())
+    }
+
+    /// Move to the next map once a match's results are over
+    fn rotate_map(&mut self) {
+        let world = self.world.clone();
+        let config = world.config();
+        if let Some(next) = self.maps.advance(&config.maps, &config.map_rotation) {
+            self.load_map(next);
+        }
+    }
+
+    /// Rebuild the physics world around a configured map and respawn everyone on it
+    /// Player-built blocks and projectiles in flight belong to the old map and are removed
+    fn load_map(&mut self, index: usize) {
+        let Some(map) = self.world.config().maps.get(index).cloned() else {
+            return;
+        };
+        let change = MapChange {
+            id: map.id.clone(),
+            name: map.display_name().to_string(),
+        };
+        let mut config = (**self.world.config()).clone();
+        config.set_geometry(map.geometry);
+        self.world = Arc::new(PhysicsWorld::new(Arc::new(config)));
+        self.blocks.clear();
+        self.restore_plot_blocks();
+        self.projectiles.clear();
+        self.hazard_exposure.clear();
+        self.end_world_event();
+        self.input_history.clear();
+
+        let mut player_ids: Vec<PlayerId> = self.players.keys().copied().collect();
+        player_ids.sort();
+        for player_id in player_ids {
+            self.respawn_player(&player_id);
+        }
+        self.map_changes.push(change);
+    }
+
+    /// The active game mode and the settings it puts in effect
+    pub fn mode(&self) -> ModeManifest {
+        ModeManifest::of(self.world.config(), &self.active_mutators)
+    }
+
+    /// Mode waiting for the current round to end, if any
+    pub fn pending_mode(&self) -> Option<&str> {
+        self.pending_mode.as_deref()
+    }
+
+    /// Switch game mode between rounds: straight away unless a round is being played,
+    /// otherwise once it ends
+    pub fn request_mode(&mut self, id: &str) -> Result<ModeSwitch, ModeError> {
+        if crate::game_mode::find(&self.base_config, id).is_none() {
+            return Err(ModeError::UnknownMode);
+        }
+        let playing = matches!(self.match_state, MatchState::Playing { .. });
+        if playing && self.world.config().matches.enabled {
+            self.pending_mode = Some(id.to_string());
+            return Ok(ModeSwitch::Pending);
+        }
+        self.pending_mode = None;
+        self.apply_mode(id);
+        Ok(ModeSwitch::Applied)
+    }
+
+    /// Take mode switches recorded since the last call, for broadcasting
+    pub fn drain_mode_changes(&mut self) -> Vec<ModeManifest> {
+        std::mem::take(&mut self.mode_changes)
+    }
+
+    /// Rebuild the world with a mode's settings, removing what the mode no longer allows
+    fn apply_mode(&mut self, id: &str) {
+        let Some(mode) = crate::game_mode::find(&self.base_config, id) else {
+            return;
+        };
+        let score_policy = mode.score_policy;
+        let mut config = crate::game_mode::configure(&self.base_config, mode);
+        config.set_geometry(self.world.config().geometry());
+        crate::mutators::apply(&mut config, &self.active_mutators);
+        if !config.building.enabled {
+            self.blocks.clear();
+        }
+        if !config.projectiles.enabled {
+            self.projectiles.clear();
+        }
+        self.world = Arc::new(PhysicsWorld::new(Arc::new(config)));
+        if self.world.config().building.enabled {
+            self.restore_plot_blocks();
+        }
+        self.sync_rules();
+        self.with_rules(|rules, ctx| rules.on_mode_enter(ctx, score_policy));
+        self.mode_changes.push(self.mode());
+    }
+
+    /// Mutators the current round is played with
+    pub fn active_mutators(&self) -> &[Mutator] {
+        &self.active_mutators
+    }
+
+    /// Vote for a mutator, by id or name, to play the next round with
+    pub fn vote_mutator(&mut self, player_id: &PlayerId, name: &str) -> Result<Mutator, MutatorError> {
+        if !self.world.config().mutators.voting {
+            return Err(MutatorError::Disabled);
+        }
+        if !self.players.contains_key(player_id) {
+            return Err(MutatorError::UnknownPlayer);
+        }
+        let mutator = Mutator::parse(name).ok_or(MutatorError::UnknownMutator)?;
+        self.mutators.vote(*player_id, mutator);
+        Ok(mutator)
+    }
+
+    /// Every mutator with its votes for the next round
+    pub fn mutator_votes(&self) -> Vec<MutatorVotes> {
+        self.mutators.tally()
+    }
+
+    /// Choose the next round's mutators, overriding votes; without matches there are no
+    /// rounds to wait for, so they apply straight away
+    pub fn pick_mutators(&mut self, mutators: Vec<Mutator>) -> ModeSwitch {
+        if self.world.config().matches.enabled {
+            self.mutators.pick(mutators);
+            return ModeSwitch::Pending;
+        }
+        self.set_mutators(mutators);
+        ModeSwitch::Applied
+    }
+
+    /// The mode manifest if the active mutators changed since the last call, for broadcasting
+    pub fn take_mutator_change(&mut self) -> Option<ModeManifest> {
+        std::mem::take(&mut self.mutators_changed).then(|| self.mode())
+    }
+
+    /// Put the mode's own mutators and the ballot's winners in effect for the round starting
+    fn start_round_mutators(&mut self) {
+        let mut mutators = crate::game_mode::find(&self.base_config, &self.world.config().matches.mode)
+            .map(|mode| mode.mutators.clone())
+            .unwrap_or_default();
+        mutators.extend(self.mutators.take_next());
+        self.set_mutators(mutators);
+    }
+
+    /// Rebuild the world with exactly these mutators in effect
+    fn set_mutators(&mut self, mutators: Vec<Mutator>) {
+        let mutators = crate::mutators::dedup(&mutators);
+        if mutators == self.active_mutators {
+            return;
+        }
+        let mut config = (**self.world.config()).clone();
+        crate::mutators::restore(&mut config, &self.base_config);
+        crate::mutators::apply(&mut config, &mutators);
+        // A new maximum (one-hit KO starting or ending) resets everyone to it
+        if config.health.max_health != self.world.config().health.max_health {
+            for player in self.players.values_mut() {
+                player.health = config.health.max_health;
+            }
+        }
+        self.world = Arc::new(PhysicsWorld::new(Arc::new(config)));
+        self.active_mutators = mutators;
+        self.mutators_changed = true;
+    }
+
+    /// Change physics settings for the rest of the room's life, within the configured bounds
+    /// Active mutators keep scaling the new values; returns the physics now in effect
+    pub fn patch_physics(&mut self, changes: &serde_json::Map<String, serde_json::Value>) -> Result<PhysicsConfig, PhysicsPatchError> {
+        let physics = crate::physics_sandbox::apply(&self.base_config.physics, changes, &self.base_config.physics_sandbox)?;
+        let mut base_config = (*self.base_config).clone();
+        base_config.physics = physics.clone();
+        self.base_config = Arc::new(base_config);
+
+        let mut config = (**self.world.config()).clone();
+        config.physics = physics;
+        crate::mutators::apply(&mut config, &self.active_mutators);
+        self.world = Arc::new(PhysicsWorld::new(Arc::new(config)));
+        self.config_version += 1;
+        self.config_changed = true;
+        Ok(self.world.config().physics.clone())
+    }
+
+    pub fn config_version(&self) -> u64 {
+        self.config_version
+    }
+
+    /// The new config version if it changed since the last call, for broadcasting
+    pub fn take_config_change(&mut self) -> Option<u64> {
+        std::mem::take(&mut self.config_changed).then_some(self.config_version)
+    }
+
+    /// Fire a projectile from the player's center along (dir_x, dir_y)
+    pub fn shoot(&mut self, player_id: &PlayerId, dir_x: f32, dir_y: f32) -> Result<Projectile, ShootError> {
+        let player = self.players.get(player_id).ok_or(ShootError::UnknownPlayer)?;
+        let (aim_x, aim_y) = self.check_shot(player, dir_x, dir_y)?;
+        let speed = self.world.config().projectiles.speed;
+        let projectile = Projectile {
+            id: self.next_projectile_id,
+            owner: *player_id,
+            x: player.x,
+            y: player.y,
+            velocity_x: aim_x * speed,
+            velocity_y: aim_y * speed,
+            age: 0.0,
+        };
+        self.next_projectile_id += 1;
+        self.projectiles.push(projectile.clone());
+        self.last_shot.insert(*player_id, self.clock.now());
+        Ok(projectile)
+    }
+
+    /// The normalized aim of a shot, if the player may fire it
+    fn check_shot(&self, player: &Player, dir_x: f32, dir_y: f32) -> Result<(f32, f32), ShootError> {
+        let player_id = &player.id;
+        let config = self.world.config();
+        let settings = &config.projectiles;
+        if !settings.enabled {
+            return Err(ShootError::Disabled);
+        }
+        if !player.life.is_alive() {
+            return Err(ShootError::Dead);
+        }
+        let (aim_x, aim_y) = Projectile::aim(dir_x, dir_y).ok_or(ShootError::InvalidDirection)?;
+        let now = self.clock.now();
+        if let Some(last) = self.last_shot.get(player_id) {
+            let cooldown = std::time::Duration::from_millis(settings.cooldown_ms);
+            if now.duration_since(*last).map(|d| d < cooldown).unwrap_or(false) {
+                return Err(ShootError::Cooldown);
+            }
+        }
+        if self.projectiles.iter().filter(|p| p.owner == *player_id).count() >= settings.max_per_player {
+            return Err(ShootError::TooMany);
+        }
+        Ok((aim_x, aim_y))
+    }
+
+    /// Fly every projectile one step, applying damage and knockback to players they hit
+    fn update_projectiles(&mut self, delta_time: f32, platforms: &[crate::config::PlatformConfig]) {
+        let world = self.world.clone();
+        let config = world.config();
+        let mut targets: Vec<_> = self
+            .players
+            .values()
+            .filter(|p| p.life.is_alive())
+            .map(|p| (p.id, p.x, p.y, p.height(&config.physics)))
+            .collect();
+        targets.sort_by_key(|target| target.0);
+
+        let mut hits = Vec::new();
+        self.projectiles.retain_mut(|projectile| match projectile.advance(delta_time, &world, platforms, &targets) {
+            None => true,
+            Some(Impact::Player(target)) => {
+                hits.push((projectile.clone(), target));
+                false
+            }
+            Some(Impact::Geometry | Impact::Expired) => false,
+        });
+
+        for (projectile, target) in hits {
+            self.hit_player(&projectile, &target);
+        }
+    }
+
+    /// Start a world event now, outside the schedule
+    pub fn start_world_event(&mut self, kind: WorldEventKind) -> Result<(), WorldEventError> {
+        let world = self.world.clone();
+        self.world_events.start(kind, &world.config().world_events)
+    }
+
+    /// Take world events started or ended since the last call, for broadcasting
+    pub fn drain_world_event_changes(&mut self) -> Vec<WorldEventChange> {
+        self.world_events.drain_changes()
+    }
+
+    /// End the event under way and clear what it dropped, lifting any frenzy effects
+    fn end_world_event(&mut self) {
+        self.world_events.reset();
         for player in self.players.values_mut() {
-            crate::physics::update_player_physics(player, delta_time);
+            player.remove_stamina_effect(SPEED_FRENZY_EFFECT);
         }
     }
+
+    /// Run the event schedule and the event under way, then move coins and meteors,
+    /// scoring the coins players collect and damaging players meteors hit
+    fn update_world_events(&mut self, delta_time: f32, platforms: &[crate::config::PlatformConfig]) {
+        let world = self.world.clone();
+        let config = world.config();
+        let settings = &config.world_events;
+        let allowed = !matches!(self.match_state, MatchState::Ended { .. });
+        if let Some(kind) = self.world_events.schedule(delta_time, settings, &mut self.rng, allowed) {
+            // Nothing is active when the schedule fires, so this can't fail
+            if self.world_events.start(kind, settings).is_ok() {
+                eprintln!("🌠 World event started: {}", kind.label());
+            }
+        }
+        if let Some(ended) = self.world_events.advance(delta_time, settings, &mut self.rng) {
+            eprintln!("🌠 World event over: {}", ended.label());
+            if ended == WorldEventKind::SpeedFrenzy {
+                for player in self.players.values_mut() {
+                    player.remove_stamina_effect(SPEED_FRENZY_EFFECT);
+                }
+            }
+        }
+        let frenzy = self
+            .world_events
+            .active
+            .as_ref()
+            .filter(|event| event.kind == WorldEventKind::SpeedFrenzy)
+            .map(|event| event.remaining_secs);
+        if let Some(remaining_secs) = frenzy {
+            // Players who joined or respawned during the frenzy get it too
+            for player in self.players.values_mut().filter(|p| p.life.is_alive()) {
+                if !player.stamina_effects.iter().any(|effect| effect.id == SPEED_FRENZY_EFFECT) {
+                    player.add_stamina_effect(crate::stamina::StaminaEffect {
+                        id: SPEED_FRENZY_EFFECT.to_string(),
+                        cost_multiplier: 0.0,
+                        regen_multiplier: settings.frenzy_regen_multiplier,
+                        remaining_secs: Some(remaining_secs),
+                    });
+                }
+            }
+        }
+
+        let mut targets: Vec<_> = self
+            .players
+            .values()
+            .filter(|p| p.life.is_alive())
+            .map(|p| (p.id, p.x, p.y, p.height(&config.physics)))
+            .collect();
+        targets.sort_by_key(|target| target.0);
+        for contact in self.world_events.move_entities(delta_time, config, platforms, &targets) {
+            match contact.kind {
+                EntityKind::Coin => {
+                    self.award_points(&contact.player_id, ScoreSource::Coin);
+                }
+                EntityKind::Meteor => {
+                    let source = DamageSource::Meteor { entity_id: contact.entity_id };
+                    self.damage_player(&contact.player_id, settings.meteor_damage, source, None);
+                }
+            }
+        }
+    }
+
+    fn hit_player(&mut self, projectile: &Projectile, target: &PlayerId) {
+        let world = self.world.clone();
+        let settings = &world.config().projectiles;
+        let knockback = Projectile::aim(projectile.velocity_x, projectile.velocity_y)
+            .map(|(x, y)| (x * settings.knockback, y * settings.knockback));
+        let source = DamageSource::Projectile {
+            shooter: projectile.owner,
+            projectile_id: projectile.id,
+        };
+        self.damage_player(target, settings.damage, source, knockback);
+    }
+
+    /// Damage a player from any source, applying knockback through the physics world
+    /// Invulnerable and dead players are unaffected; a player left at zero health dies
+    pub fn damage_player(
+        &mut self,
+        target: &PlayerId,
+        amount: u32,
+        source: DamageSource,
+        knockback: Option<(f32, f32)>,
+    ) {
+        self.hurt(target, amount, source, knockback, false);
+    }
+
+    /// Take health from a player; `lingering` damage (lava, instant kills) ignores
+    /// invulnerability and doesn't grant any, so it keeps applying while the player stays in it
+    fn hurt(
+        &mut self,
+        target: &PlayerId,
+        amount: u32,
+        source: DamageSource,
+        knockback: Option<(f32, f32)>,
+        lingering: bool,
+    ) {
+        let world = self.world.clone();
+        let config = world.config();
+        let Some(player) = self.players.get_mut(target) else {
+            return;
+        };
+        if !player.life.is_alive() || (player.invulnerable_secs > 0.0 && !lingering) {
+            return;
+        }
+
+        if let Some((impulse_x, impulse_y)) = knockback {
+            world.apply_knockback(player, impulse_x, impulse_y);
+        }
+        let amount = amount.min(player.health);
+        if amount == 0 {
+            return;
+        }
+        player.health -= amount;
+        if !lingering {
+            player.invulnerable_secs = config.health.invulnerable_secs;
+        }
+        self.damage_events.push(DamageEvent {
+            target: *target,
+            source: source.clone(),
+            amount,
+            x: player.x,
+            y: player.y,
+        });
+
+        if player.health == 0 {
+            let cause = source.death_cause();
+            let respawn_in_secs = config.respawn.delay_secs.max(0.0);
+            player.life = LifeState::Dead { cause, respawn_in_secs };
+            player.velocity_x = 0.0;
+            player.velocity_y = 0.0;
+            if let Some(killer) = source.attacker().filter(|killer| killer != target) {
+                self.pending_kill_cams.push((*target, killer, cause));
+            }
+            self.life_events.push(LifeEvent::Died {
+                player_id: *target,
+                cause,
+                x: player.x,
+                y: player.y,
+                respawn_in_secs,
+            });
+        }
+    }
+
+    /// Damage players touching hazards and players landed on from above this step
+    /// `previous_y` holds each player's height before the step's physics
+    fn apply_contact_damage(&mut self, delta_time: f32, previous_y: &HashMap<PlayerId, f32>) {
+        let world = self.world.clone();
+        let config = world.config();
+        let mut alive: Vec<&Player> = self.players.values().filter(|p| p.life.is_alive()).collect();
+        alive.sort_by_key(|p| p.id);
+
+        let mut hits = Vec::new();
+        let mut exposure = HashMap::new();
+        for player in &alive {
+            for hazard in crate::health::hazards_touching(player, config) {
+                let source = DamageSource::Hazard { hazard_id: hazard.id.clone() };
+                if hazard.instant_kill {
+                    hits.push((player.id, u32::MAX, source, None, true));
+                    continue;
+                }
+                if hazard.damage > 0 || hazard.knockback != 0.0 {
+                    hits.push((player.id, hazard.damage, source.clone(), Some((0.0, hazard.knockback)), false));
+                }
+                if hazard.damage_per_second > 0.0 {
+                    let key = (player.id, hazard.id.clone());
+                    let owed = self.hazard_exposure.get(&key).copied().unwrap_or(0.0)
+                        + hazard.damage_per_second * delta_time;
+                    let whole = owed.floor();
+                    exposure.insert(key, owed - whole);
+                    if whole >= 1.0 {
+                        hits.push((player.id, whole as u32, source, None, true));
+                    }
+                }
+            }
+        }
+        let mut stompers = Vec::new();
+        if config.health.stomp_damage > 0 {
+            for stomper in &alive {
+                let Some(&from_y) = previous_y.get(&stomper.id) else {
+                    continue;
+                };
+                if let Some(target) = alive.iter().find(|t| crate::health::is_stomp(stomper, from_y, t, config)) {
+                    let source = DamageSource::Stomp { by: stomper.id };
+                    hits.push((target.id, config.health.stomp_damage, source, None, false));
+                    stompers.push(stomper.id);
+                }
+            }
+        }
+
+        for stomper in stompers {
+            if let Some(player) = self.players.get_mut(&stomper) {
+                world.bounce(player, config.health.stomp_bounce);
+            }
+        }
+        // Players who left a hazard start from scratch when they step back in
+        self.hazard_exposure = exposure;
+        for (target, amount, source, knockback, lingering) in hits {
+            self.hurt(&target, amount, source, knockback, lingering);
+        }
+    }
+
+    /// Take damage recorded since the last call, for broadcasting
+    pub fn drain_damage_events(&mut self) -> Vec<DamageEvent> {
+        std::mem::take(&mut self.damage_events)
+    }
+
+    /// Take bounces recorded since the last call, for broadcasting
+    pub fn drain_bounce_events(&mut self) -> Vec<BounceEvent> {
+        std::mem::take(&mut self.bounce_events)
+    }
+
+    /// Take portal trips recorded since the last call, for broadcasting
+    pub fn drain_teleport_events(&mut self) -> Vec<TeleportEvent> {
+        std::mem::take(&mut self.teleport_events)
+    }
+
+    /// Take deaths and respawns recorded since the last call, for broadcasting
+    pub fn drain_life_events(&mut self) -> Vec<LifeEvent> {
+        std::mem::take(&mut self.life_events)
+    }
+
+    /// Put a dead player back in play at the best spawn point
+    fn respawn_player(&mut self, player_id: &PlayerId) {
+        let world = self.world.clone();
+        let (x, y) = crate::respawn::spawn_position(world.config(), &self.players, player_id);
+        let Some(player) = self.players.get_mut(player_id) else {
+            return;
+        };
+        player.x = x;
+        player.y = y;
+        player.teleports += 1;
+        player.velocity_x = 0.0;
+        player.velocity_y = 0.0;
+        player.ground_state = crate::GroundState::Flying;
+        player.crouched = false;
+        player.wants_to_stand = false;
+        player.slide_secs = 0.0;
+        player.life = LifeState::Alive;
+        player.health = world.config().health.max_health;
+        player.stamina = world.config().physics.max_stamina;
+        player.sprinting = false;
+        player.dash_secs = 0.0;
+        player.jump_buffer_secs = 0.0;
+        player.held = None;
+        player.stamina_effects.clear();
+        // Spawn protection
+        player.invulnerable_secs = world.config().health.invulnerable_secs;
+        self.life_events.push(LifeEvent::Respawned { player_id: *player_id, x, y });
+    }
+
+    /// Apply a stamina status effect to a player; false if they aren't here
+    pub fn apply_stamina_effect(&mut self, player_id: &PlayerId, effect: crate::stamina::StaminaEffect) -> bool {
+        let Some(player) = self.players.get_mut(player_id) else {
+            return false;
+        };
+        player.add_stamina_effect(effect);
+        true
+    }
+
+    /// Lift a stamina status effect by id; false if the player didn't have it
+    pub fn clear_stamina_effect(&mut self, player_id: &PlayerId, effect_id: &str) -> bool {
+        self.players
+            .get_mut(player_id)
+            .is_some_and(|player| player.remove_stamina_effect(effect_id))
+    }
+
+    /// Take combo breaks recorded since the last call, for broadcasting
+    pub fn drain_combo_breaks(&mut self) -> Vec<ComboBreak> {
+        std::mem::take(&mut self.combo_breaks)
+    }
+
+    /// The world as it is now, to save and restore later or on another server
+    pub fn snapshot(&self) -> WorldSnapshot {
+        let mut blocks: Vec<Block> = self.blocks.blocks.values().cloned().collect();
+        blocks.sort_by_key(|block| (block.cell_x, block.cell_y));
+        WorldSnapshot {
+            version: SNAPSHOT_VERSION,
+            tick: self.tick,
+            taken_at_ms: self.clock.unix_millis(),
+            mode: self.world.config().matches.mode.clone(),
+            mutators: self.active_mutators.clone(),
+            map_index: self.maps.current(),
+            geometry: self.world.config().geometry(),
+            match_state: self.match_state.clone(),
+            players: self.player_ids().iter().map(|id| self.players[id].clone()).collect(),
+            blocks,
+            projectiles: self.projectiles.clone(),
+            next_projectile_id: self.next_projectile_id,
+            rng_state: self.rng.state(),
+            player_internals: self.player_ids().iter().map(|id| self.players[id].internals()).collect(),
+            rules_state: self.rules.save_state(),
+            physics: Some(self.base_config.physics.clone()),
+            config_version: self.config_version,
+            room_creator: self.room_creator,
+            slow_motion: self.slow_motion,
+        }
+    }
+
+    /// Replace the world with a snapshot's and carry on from there
+    /// Players keep their ids, so their clients can pick up where they were; match deadlines
+    /// move by the time since the snapshot, so a match in progress keeps the time it had left
+    pub fn restore(&mut self, snapshot: WorldSnapshot) -> Result<(), SnapshotError> {
+        let paused_ms = self.clock.unix_millis().saturating_sub(snapshot.taken_at_ms);
+        let match_state = snapshot.match_state.clone().delayed_by(paused_ms).ok_or(SnapshotError::InvalidDeadline)?;
+        let config_version = snapshot.config_version;
+        self.load_snapshot(snapshot)?;
+        self.match_state = match_state;
+        if let Some(slow_motion) = &mut self.slow_motion {
+            slow_motion.until_ms = slow_motion.until_ms.saturating_add(paused_ms);
+        }
+        for player in self.players.values_mut() {
+            // Clients shouldn't interpolate from wherever they last saw the player
+            player.teleports += 1;
+        }
+        self.end_world_event();
+
+        // Tell clients about the restored mode, map, physics and match phase
+        self.config_version = self.config_version.max(config_version) + 1;
+        self.config_changed = true;
+        self.mode_changes.push(self.mode());
+        self.map_edited = true;
+        self.match_transitions.push(self.match_state.clone());
+        Ok(())
+    }
+
+    /// Take on the world another server is simulating, as a follower keeping a copy to
+    /// carry on from should it take over
+    /// Unlike `restore`, nothing is queued for broadcasting, as the other server has already
+    /// told clients; the world event under way and the blocks are kept, since those are
+    /// mirrored from the other server's broadcasts, blocks with their version numbers
+    pub fn mirror(&mut self, snapshot: WorldSnapshot) -> Result<(), SnapshotError> {
+        let config_version = snapshot.config_version;
+        let blocks = std::mem::take(&mut self.blocks);
+        let loaded = self.load_snapshot(snapshot);
+        self.blocks = blocks;
+        loaded?;
+        self.config_version = config_version;
+        self.mode_statuses.clear();
+        Ok(())
+    }
+
+    fn load_snapshot(&mut self, snapshot: WorldSnapshot) -> Result<(), SnapshotError> {
+        if crate::game_mode::find(&self.base_config, &snapshot.mode).is_none() && snapshot.mode != self.base_config.matches.mode {
+            return Err(SnapshotError::UnknownMode(snapshot.mode));
+        }
+        // Checked before anything is replaced, so a bad snapshot leaves the world as it was
+        snapshot.geometry.validate().map_err(SnapshotError::InvalidGeometry)?;
+        if let Some(physics) = snapshot.physics {
+            let mut base_config = (*self.base_config).clone();
+            base_config.physics = physics;
+            self.base_config = Arc::new(base_config);
+        }
+        let mode = crate::game_mode::find(&self.base_config, &snapshot.mode);
+        let mut config = match mode {
+            Some(mode) => crate::game_mode::configure(&self.base_config, mode),
+            None => (*self.base_config).clone(),
+        };
+        config.set_geometry(snapshot.geometry);
+        let mutators = crate::mutators::dedup(&snapshot.mutators);
+        crate::mutators::apply(&mut config, &mutators);
+        self.world = Arc::new(PhysicsWorld::new(Arc::new(config)));
+        self.maps.resume_at(snapshot.map_index);
+        self.active_mutators = mutators;
+        self.mutators = MutatorBallot::default();
+        self.pending_mode = None;
+
+        self.match_state = snapshot.match_state;
+        self.slow_motion = snapshot.slow_motion;
+        self.kill_slow_motion_after_ms = 0;
+        self.tick = snapshot.tick;
+        self.rng = SeededRng::new(snapshot.rng_state);
+        self.players = snapshot.players.into_iter().map(|player| (player.id, player)).collect();
+        for internals in &snapshot.player_internals {
+            if let Some(player) = self.players.get_mut(&internals.id) {
+                player.set_internals(internals);
+            }
+        }
+        self.room_creator = snapshot.room_creator.filter(|id| self.players.contains_key(id));
+        self.blocks.clear();
+        for block in snapshot.blocks {
+            self.blocks.insert(block);
+        }
+        self.restore_plot_blocks();
+        self.projectiles = snapshot.projectiles;
+        self.next_projectile_id = snapshot.next_projectile_id;
+        self.last_block_placed.clear();
+        self.last_shot.clear();
+        self.hazard_exposure.clear();
+        self.sync_rules();
+        self.rules.load_state(&snapshot.rules_state);
+        self.input_history.clear();
+        Ok(())
+    }
+
+    /// Ids of everyone playing, in a stable order
+    pub fn player_ids(&self) -> Vec<PlayerId> {
+        let mut ids: Vec<PlayerId> = self.players.keys().copied().collect();
+        ids.sort();
+        ids
+    }
+
+    /// A fresh player id drawn from the room's RNG, so seeded runs name players the same way
+    pub fn new_player_id(&mut self) -> PlayerId {
+        self.rng.uuid()
+    }
+
+    /// Hash of the tick, every player's physical state and score, projectiles and blocks
+    /// Two runs of the same command log with the same seed have equal fingerprints
+    pub fn fingerprint(&self) -> u64 {
+        // FNV-1a, which unlike the std hasher is the same in every Rust release
+        let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+        let mut feed = |bytes: &[u8]| {
+            for &byte in bytes {
+                hash = (hash ^ byte as u64).wrapping_mul(0x0100_0000_01b3);
+            }
+        };
+        feed(&self.tick.to_le_bytes());
+        for player_id in self.player_ids() {
+            let p = &self.players[&player_id];
+            feed(p.id.as_bytes());
+            for value in [p.x, p.y, p.velocity_x, p.velocity_y, p.invulnerable_secs, p.stamina] {
+                feed(&value.to_bits().to_le_bytes());
+            }
+            feed(&p.score.to_le_bytes());
+            feed(&p.health.to_le_bytes());
+            feed(&[p.life.is_alive() as u8, p.facing_right as u8, p.crouched as u8]);
+        }
+        for projectile in &self.projectiles {
+            feed(&projectile.id.to_le_bytes());
+            feed(&projectile.x.to_bits().to_le_bytes());
+            feed(&projectile.y.to_bits().to_le_bytes());
+        }
+        for entity in &self.world_events.entities {
+            feed(&entity.id.to_le_bytes());
+            feed(&entity.x.to_bits().to_le_bytes());
+            feed(&entity.y.to_bits().to_le_bytes());
+        }
+        feed(&self.blocks.version().to_le_bytes());
+        hash
+    }
+
+    /// Advance the simulation by one fixed step
+    pub fn update(&mut self, delta_time: f32) {
+        self.tick += 1;
+        // Slow motion lasts a span of real time, however little game time passes in it
+        if self.slow_motion.is_some_and(|slow_motion| self.clock.unix_millis() >= slow_motion.until_ms) {
+            self.slow_motion = None;
+        }
+        let time_scale = self.time_scale();
+        let real_delta_time = delta_time;
+        let delta_time = delta_time * time_scale;
+        self.challenges.rotate(crate::challenges::day_from_unix(self.clock.unix_secs()));
+        let config = self.world.config();
+        let mut platforms = self.world.platforms().to_vec();
+        platforms.extend(self.blocks.platforms(&config.building));
+        // The map itself is baked into the world; only the blocks are passed to player physics
+        let blocks = &platforms[self.world.platforms().len()..];
+        let previous_y: HashMap<PlayerId, f32> = self.players.values().map(|p| (p.id, p.y)).collect();
+        let mut respawning = Vec::new();
+        let mut jumped = Vec::new();
+        // Id order, not hash order, so the same inputs always produce the same events and state
+        for player_id in self.player_ids() {
+            let Some(player) = self.players.get_mut(&player_id) else {
+                continue;
+            };
+            player.invulnerable_secs = (player.invulnerable_secs - delta_time).max(0.0);
+            match &mut player.life {
+                LifeState::Alive => {
+                    if self.world.update_player_physics(player, delta_time, blocks) {
+                        jumped.push(player.id);
+                    }
+                    if let Some(speed) = player.bounced {
+                        self.bounce_events.push(BounceEvent {
+                            player_id: player.id,
+                            surface: player.surface,
+                            speed,
+                            x: player.x,
+                            y: player.y,
+                        });
+                    }
+                    if let Some(teleport) = self.world.pass_through_portal(player, delta_time) {
+                        self.teleport_events.push(teleport);
+                    }
+                    if let Some(cause) = crate::respawn::death_cause(player, config) {
+                        let respawn_in_secs = config.respawn.delay_secs.max(0.0);
+                        player.life = LifeState::Dead { cause, respawn_in_secs };
+                        player.velocity_x = 0.0;
+                        player.velocity_y = 0.0;
+                        self.life_events.push(LifeEvent::Died {
+                            player_id: player.id,
+                            cause,
+                            x: player.x,
+                            y: player.y,
+                            respawn_in_secs,
+                        });
+                    }
+                }
+                LifeState::Dead { respawn_in_secs, .. } => {
+                    *respawn_in_secs -= delta_time;
+                    if *respawn_in_secs <= 0.0 {
+                        respawning.push(player.id);
+                    }
+                }
+            }
+            if let Some(broken) = player.combo.decay(delta_time) {
+                self.combo_breaks.push(ComboBreak {
+                    player_id: player.id,
+                    count: broken.count,
+                    multiplier: broken.multiplier,
+                });
+            }
+        }
+        for player_id in respawning {
+            self.respawn_player(&player_id);
+        }
+        for player_id in jumped {
+            self.record_quest_event(&player_id, QuestEvent::Jumped);
+        }
+        self.apply_contact_damage(delta_time, &previous_y);
+        if self.round_in_play() {
+            let contacts = self.player_contacts();
+            self.with_rules(|rules, ctx| {
+                rules.on_tick(ctx, delta_time);
+                for (a, b) in &contacts {
+                    rules.on_collision(ctx, a, b);
+                }
+            });
+        }
+        self.update_projectiles(delta_time, &platforms);
+        self.update_world_events(delta_time, &platforms);
+        self.advance_match();
+        if !self.pending_kill_cams.is_empty() {
+            self.trigger_slow_motion(SlowMotionTrigger::Kill);
+        }
+        self.analytics.observe_scores(&self.players);
+        self.capture_history(real_delta_time, time_scale);
+    }
+
+    /// Whether the mode's rules apply: while a match is played, or always when matches are off
+    fn round_in_play(&self) -> bool {
+        !self.world.config().matches.enabled || matches!(self.match_state, MatchState::Playing { .. })
+    }
+
+    /// Pairs of living players touching each other, lower id first, in id order
+    fn player_contacts(&self) -> Vec<(PlayerId, PlayerId)> {
+        let physics = &self.world.config().physics;
+        let mut alive: Vec<&Player> = self.players.values().filter(|p| p.life.is_alive()).collect();
+        alive.sort_by_key(|p| p.id);
+        let half_w = physics.player_width / 2.0;
+        let mut contacts = Vec::new();
+        for (i, a) in alive.iter().enumerate() {
+            for b in &alive[i + 1..] {
+                let reach_y = (a.height(physics) + b.height(physics)) / 2.0;
+                if (a.x - b.x).abs() < half_w * 2.0 && (a.y - b.y).abs() < reach_y {
+                    contacts.push((a.id, b.id));
+                }
+            }
+        }
+        contacts
+    }
+
+    /// Run one of the rules' hooks with the players, config, clock and RNG
+    fn with_rules<R>(&mut self, hook: impl FnOnce(&mut dyn GameMode, &mut ModeContext) -> R) -> R {
+        let world = self.world.clone();
+        let mut ctx = ModeContext {
+            players: &mut self.players,
+            config: world.config(),
+            now_ms: self.clock.unix_millis(),
+            rng: &mut self.rng,
+        };
+        hook(self.rules.as_mut(), &mut ctx)
+    }
+
+    /// Swap in fresh rules when the mode now in effect plays by different ones, clearing
+    /// the old rules' scoreboard on clients; otherwise start the same rules over
+    fn sync_rules(&mut self) {
+        let game_mode = &self.world.config().game_mode;
+        if self.rules.rules() == *game_mode {
+            self.rules.reset();
+            return;
+        }
+        if let Some(signal) = self.rules.signal() {
+            self.mode_statuses.push(ModeStatus { signal, state: serde_json::Value::Null });
+        }
+        self.rules = create_rules(&self.modes, game_mode);
+    }
+
+    /// Modes this room can switch to
+    pub fn modes(&self) -> &ModeRegistry {
+        &self.modes
+    }
+
+    /// Add a mode, e.g. one defined outside this crate; if config names it, play by it
+    /// from now on
+    pub fn register_mode(&mut self, factory: ModeFactory) -> GameRules {
+        let rules = self.modes.register(factory);
+        if rules == self.world.config().game_mode && self.rules.rules() != rules {
+            self.sync_rules();
+        }
+        rules
+    }
+
+    /// Which rules are being played by
+    pub fn rules(&self) -> &dyn GameMode {
+        self.rules.as_ref()
+    }
+
+    /// The rules' scoreboard for clients; `None` for rules without one
+    pub fn mode_status(&self) -> Option<ModeStatus> {
+        let signal = self.rules.signal()?;
+        Some(ModeStatus {
+            signal,
+            state: self.rules.status(&self.players, self.world.config()),
+        })
+    }
+
+    /// Scoreboards that changed since the last call, for broadcasting
+    pub fn drain_mode_statuses(&mut self) -> Vec<ModeStatus> {
+        if self.rules.take_changed() {
+            if let Some(status) = self.mode_status() {
+                self.mode_statuses.push(status);
+            }
+        }
+        std::mem::take(&mut self.mode_statuses)
+    }
+
+    /// Slow the whole room to `scale` of normal speed for `secs` real seconds; a scale of 0
+    /// pauses it. Replaces any slow motion already running.
+    pub fn start_slow_motion(&mut self, scale: f32, secs: f32) {
+        let scale = if scale.is_finite() { scale.clamp(0.0, 1.0) } else { 1.0 };
+        let until_ms = self.clock.unix_millis() + (secs.max(0.0) * 1000.0) as u64;
+        self.slow_motion = Some(SlowMotion { scale, until_ms });
+    }
+
+    /// Fraction of normal speed the room is running at
+    pub fn time_scale(&self) -> f32 {
+        self.slow_motion.map_or(1.0, |slow_motion| slow_motion.scale)
+    }
+
+    /// Start the current mode's slow motion if it is set off by `trigger`
+    fn trigger_slow_motion(&mut self, trigger: SlowMotionTrigger) {
+        if self.replaying {
+            return;
+        }
+        let Some(settings) = crate::game_mode::find(&self.base_config, &self.world.config().matches.mode)
+            .and_then(|mode| mode.slow_motion.clone())
+            .filter(|settings| settings.triggers.contains(&trigger))
+        else {
+            return;
+        };
+        // A run of kills would otherwise keep the room slowed for as long as it lasts
+        if trigger == SlowMotionTrigger::Kill && self.clock.unix_millis() < self.kill_slow_motion_after_ms {
+            return;
+        }
+        eprintln!("🐢 Slow motion at {}x for {}s ({:?})", settings.scale, settings.duration_secs, trigger);
+        self.start_slow_motion(settings.scale, settings.duration_secs);
+        if trigger == SlowMotionTrigger::Kill {
+            let until_ms = self.slow_motion.map_or(0, |slow_motion| slow_motion.until_ms);
+            self.kill_slow_motion_after_ms = until_ms.saturating_add((settings.kill_gap_secs.max(0.0) * 1000.0) as u64);
+        }
+    }
+
+    /// Record this step's frame for every player, then build the kill cams it completes and
+    /// check everyone for cheating
+    /// History covers the longer of the kill cam and the anti-cheat replay
+    /// `delta_time` is the step before slow motion scaled it by `time_scale`
+    fn capture_history(&mut self, delta_time: f32, time_scale: f32) {
+        let world = self.world.clone();
+        let config = world.config();
+        let (kill_cam, anti_cheat) = (config.kill_cam.enabled, config.anti_cheat.enabled);
+        if !kill_cam {
+            self.pending_kill_cams.clear();
+        }
+        if self.replaying || (!kill_cam && !anti_cheat) {
+            return;
+        }
+        let frames = |secs: f32, enabled: bool| if enabled { (secs * config.tick_rate_hz).ceil() as usize } else { 0 };
+        let kill_cam_frames = frames(config.kill_cam.history_secs, kill_cam);
+        let capacity = kill_cam_frames.max(frames(config.anti_cheat.history_secs, anti_cheat));
+        self.input_history.capture(self.tick, time_scale, self.players.values(), capacity);
+        for (victim, killer, cause) in std::mem::take(&mut self.pending_kill_cams) {
+            let kill_cam = self.input_history.kill_cam(victim, killer, cause, self.tick, kill_cam_frames);
+            self.kill_cams.push(kill_cam);
+        }
+        if anti_cheat {
+            self.check_for_cheats(delta_time, time_scale);
+        }
+    }
+
+    /// Flag players who look to be cheating, replaying each one's recent inputs for review
+    fn check_for_cheats(&mut self, delta_time: f32, time_scale: f32) {
+        let world = self.world.clone();
+        let suspects = self.anti_cheat.observe(self.tick, delta_time * time_scale, world.config(), self.players.values());
+        for (player_id, suspicion) in suspects {
+            let frames: Vec<_> = self.input_history.frames(&player_id).cloned().collect();
+            let check = crate::anti_cheat::replay(self, player_id, &frames, delta_time);
+            eprintln!("🕵️ Flagged {} for review: {} ({:?} after {} frames)", player_id, suspicion, check.verdict, check.frames_replayed);
+            self.cheat_reports.push(CheatReport {
+                player_id,
+                tick: self.tick,
+                suspicion,
+                check,
+            });
+        }
+    }
+
+    /// Take players flagged since the last call, for admin review
+    pub fn drain_cheat_reports(&mut self) -> Vec<CheatReport> {
+        std::mem::take(&mut self.cheat_reports)
+    }
+
+    /// A room for replaying `player`'s inputs: the same map, blocks, match and rules with
+    /// nobody else in it, nothing in flight, no world events, and no history or anti-cheat
+    /// of its own
+    /// Only what the replay touches is copied, as this runs under the game loop's lock
+    pub(crate) fn replay_copy(&self, player: Player) -> GameState {
+        let mut config = (**self.world.config()).clone();
+        config.world_events.enabled = false;
+        let world = Arc::new(PhysicsWorld::new(Arc::new(config)));
+        let mut copy = Self::blank(world, self.base_config.clone(), self.clock.clone());
+        copy.replaying = true;
+        copy.tick = self.tick;
+        copy.blocks = self.blocks.clone();
+        copy.match_state = self.match_state.clone();
+        copy.modes = self.modes.clone();
+        copy.rules = self.rules.clone();
+        copy.rng = self.rng.clone();
+        copy.active_mutators = self.active_mutators.clone();
+        copy.players.insert(player.id, player);
+        copy
+    }
+
+    /// Take kill cams built since the last call, for sending to their victims
+    pub fn drain_kill_cams(&mut self) -> Vec<KillCam> {
+        std::mem::take(&mut self.kill_cams)
+    }
+
+    fn within_reach(&self, player: &Player, cell: crate::blocks::Cell) -> bool {
+        let config = self.world.config();
+        let size = config.building.block_size;
+        let center_x = (cell.0 as f32 + 0.5) * size;
+        let center_y = (cell.1 as f32 + 0.5) * size;
+        let dx = center_x - player.x;
+        let dy = center_y - player.y;
+        (dx * dx + dy * dy).sqrt() <= config.building.max_reach
+    }
+
+    fn cell_overlaps_player(&self, cell: crate::blocks::Cell, size: f32) -> bool {
+        let config = self.world.config();
+        let half_w = config.physics.player_width / 2.0;
+        let (left, bottom) = (cell.0 as f32 * size, cell.1 as f32 * size);
+        self.players.values().any(|p| {
+            let half_h = p.height(&config.physics) / 2.0;
+            p.x + half_w > left && p.x - half_w < left + size
+                && p.y + half_h > bottom && p.y - half_h < bottom + size
+        })
+    }
+
+    /// Whether a grid cell intersects the ground, a configured platform, or a wall
+    fn cell_overlaps_static_geometry(&self, cell: crate::blocks::Cell, size: f32) -> bool {
+        let config = self.world.config();
+        let (left, bottom) = (cell.0 as f32 * size, cell.1 as f32 * size);
+        let (right, top) = (left + size, bottom + size);
+
+        if bottom < self.world.geometry().ground_under(left, right) {
+            return true;
+        }
+        let hits_platform = config.platforms.iter().any(|p| {
+            right > p.x_start && left < p.x_end && top > p.y_top - p.height && bottom < p.y_top
+        });
+        let hits_wall = config.walls.iter().any(|w| {
+            right > w.x && left < w.x + w.width && top > w.y_bottom && bottom < w.y_top
+        });
+        hits_platform || hits_wall
+    }
 }
 
+/// Fresh rules from the registry, or the standard rules for a mode nobody registered
+fn create_rules(modes: &ModeRegistry, rules: &GameRules) -> Box<dyn GameMode> {
+    modes.create(rules).unwrap_or_else(|| {
+        eprintln!("⚠️ No game mode named {}, playing standard rules", rules);
+        Box::new(crate::game_mode::StandardMode)
+    })
+}
+
+/// Whole milliseconds left of a countdown in seconds, rounded up
+fn secs_to_ms(secs: f32) -> u64 {
+    (secs.max(0.0) * 1000.0).ceil() as u64
+}
@@ -27,6 +27,11 @@ pub enum GroundState {
     },
     /// Player is flying/airborne (jumping or falling)
     Flying,
+    /// Player is holding on to a ladder; gravity doesn't apply
+    Climbing {
+        /// Index of the ladder in the config
+        ladder_id: u32,
+    },
 }
 
 impl GroundState {
@@ -45,6 +50,11 @@ impl GroundState {
         matches!(self, GroundState::Flying)
     }
     
+    /// Returns true if the player is on a ladder
+    pub fn is_climbing(&self) -> bool {
+        matches!(self, GroundState::Climbing { .. })
+    }
+    
     /// Returns true if the player is airborne (sliding or flying)
     pub fn is_airborne(&self) -> bool {
         self.is_sliding() || self.is_flying()
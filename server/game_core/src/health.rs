@@ -0,0 +1,77 @@
+use serde::{Deserialize, Serialize};
+use crate::config::{GameConfig, HazardConfig};
+use crate::player::{Player, PlayerId};
+use crate::respawn::DeathCause;
+
+/// What hurt a player
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum DamageSource {
+    /// Hit by another player's projectile
+    Projectile { shooter: PlayerId, projectile_id: u64 },
+    /// Touched a configured hazard
+    Hazard { hazard_id: String },
+    /// Landed on by another player
+    Stomp { by: PlayerId },
+    /// Hit by a meteor during a meteor shower
+    Meteor { entity_id: u64 },
+}
+
+impl DamageSource {
+    /// The player behind the damage, if another player caused it
+    pub fn attacker(&self) -> Option<PlayerId> {
+        match self {
+            DamageSource::Projectile { shooter, .. } => Some(*shooter),
+            DamageSource::Stomp { by } => Some(*by),
+            DamageSource::Hazard { .. } | DamageSource::Meteor { .. } => None,
+        }
+    }
+
+    /// How a player killed by this source died
+    pub fn death_cause(&self) -> DeathCause {
+        match self {
+            DamageSource::Projectile { .. } => DeathCause::Shot,
+            DamageSource::Hazard { .. } => DeathCause::Hazard,
+            DamageSource::Stomp { .. } => DeathCause::Stomped,
+            DamageSource::Meteor { .. } => DeathCause::Hazard,
+        }
+    }
+}
+
+/// A player taking damage, broadcast so clients can play hit effects
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DamageEvent {
+    pub target: PlayerId,
+    pub source: DamageSource,
+    /// Health the target lost
+    pub amount: u32,
+    /// Where the target was when hit
+    pub x: f32,
+    pub y: f32,
+}
+
+/// Every hazard the player's body overlaps
+pub fn hazards_touching<'a>(player: &Player, config: &'a GameConfig) -> impl Iterator<Item = &'a HazardConfig> {
+    let half_w = config.physics.player_width / 2.0;
+    let half_h = player.height(&config.physics) / 2.0;
+    let (x, y) = (player.x, player.y);
+    config.hazards.iter().filter(move |hazard| {
+        x + half_w > hazard.x_start
+            && x - half_w < hazard.x_end
+            && y + half_h > hazard.y_bottom
+            && y - half_h < hazard.y_top
+    })
+}
+
+/// Whether `stomper`, falling from `previous_y`, came down on `target`'s head this step
+pub fn is_stomp(stomper: &Player, previous_y: f32, target: &Player, config: &GameConfig) -> bool {
+    let head = target.y + target.height(&config.physics) / 2.0;
+    let half_h = stomper.height(&config.physics) / 2.0;
+    let feet_before = previous_y - half_h;
+    let feet_after = stomper.y - half_h;
+    stomper.id != target.id
+        && stomper.velocity_y <= 0.0
+        && (stomper.x - target.x).abs() < config.physics.player_width
+        && feet_before >= head
+        && feet_after <= head
+}
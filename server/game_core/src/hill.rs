@@ -0,0 +1,250 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap};
+use crate::config::{GameConfig, GameRules};
+use crate::game_mode::{GameMode, ModeContext};
+use crate::player::{Player, PlayerId};
+use crate::signals::Signal;
+use crate::teams::TeamId;
+
+/// Who can hold the hill: a team, or a player on their own when teams are off
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HillSide {
+    Team(TeamId),
+    Player(PlayerId),
+}
+
+impl HillSide {
+    pub fn of(player: &Player) -> Self {
+        match &player.team {
+            Some(team) => HillSide::Team(team.clone()),
+            None => HillSide::Player(player.id),
+        }
+    }
+}
+
+/// One side's points from holding the hill
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HillScore {
+    pub side: HillSide,
+    pub points: u64,
+}
+
+/// The control zone and the points it has earned, as sent to clients
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HillStatus {
+    /// Side holding the zone; only they score while inside it
+    pub owner: Option<HillSide>,
+    /// Side taking the zone over, alone inside it
+    pub capturing: Option<HillSide>,
+    /// How far `capturing` is through the capture, 0 to 1
+    pub progress: f32,
+    /// More than one side is inside, so nobody captures or scores
+    pub contested: bool,
+    /// Points per side, most first
+    pub scores: Vec<HillScore>,
+    /// Points a side needs to win; 0 for none
+    pub score_cap: u64,
+}
+
+/// King of the hill: a side alone in the control zone takes it over, then its players
+/// score while they stay inside
+#[derive(Debug, Clone, Default)]
+pub struct HillMode {
+    owner: Option<HillSide>,
+    capturing: Option<HillSide>,
+    progress: f32,
+    contested: bool,
+    points: HashMap<HillSide, u64>,
+    /// Fractional points earned by players in the zone, paid out in whole points
+    owed: HashMap<PlayerId, f32>,
+    /// Whether the zone changed since the status was last taken
+    changed: bool,
+}
+
+/// A hill round's progress as saved; points are listed since sides can't key a JSON object
+#[derive(Serialize, Deserialize)]
+struct SavedHill {
+    owner: Option<HillSide>,
+    capturing: Option<HillSide>,
+    progress: f32,
+    contested: bool,
+    points: Vec<HillScore>,
+    owed: HashMap<PlayerId, f32>,
+}
+
+impl HillMode {
+    pub fn owner(&self) -> Option<&HillSide> {
+        self.owner.as_ref()
+    }
+
+    pub fn points(&self, side: &HillSide) -> u64 {
+        self.points.get(side).copied().unwrap_or(0)
+    }
+
+    /// Move the capture along, broadcasting at every tenth of the way
+    fn capture(&mut self, side: &HillSide, capture_secs: f32, delta_time: f32) {
+        if self.capturing.as_ref() != Some(side) {
+            self.capturing = Some(side.clone());
+            self.progress = 0.0;
+            self.changed = true;
+        }
+        let step = (self.progress * 10.0) as u32;
+        self.progress = if capture_secs > 0.0 { self.progress + delta_time / capture_secs } else { 1.0 };
+        if self.progress >= 1.0 {
+            self.owner = self.capturing.take();
+            self.progress = 0.0;
+            self.changed = true;
+        } else if (self.progress * 10.0) as u32 != step {
+            self.changed = true;
+        }
+    }
+}
+
+impl GameMode for HillMode {
+    fn rules(&self) -> GameRules {
+        GameRules::KING_OF_THE_HILL
+    }
+
+    fn on_round_start(&mut self, _ctx: &mut ModeContext) {
+        self.reset();
+    }
+
+    fn on_tick(&mut self, ctx: &mut ModeContext, delta_time: f32) {
+        let config = ctx.config;
+        let Some(zone) = &config.control_zone else {
+            return;
+        };
+        let physics = &config.physics;
+        let inside: Vec<(PlayerId, HillSide)> = ctx
+            .player_ids()
+            .into_iter()
+            .filter_map(|id| ctx.players.get(&id))
+            .filter(|p| {
+                let (half_w, half_h) = (physics.player_width / 2.0, p.height(physics) / 2.0);
+                p.life.is_alive() && zone.overlaps(p.x - half_w, p.x + half_w, p.y - half_h, p.y + half_h)
+            })
+            .map(|p| (p.id, HillSide::of(p)))
+            .collect();
+        let sides: BTreeSet<&HillSide> = inside.iter().map(|(_, side)| side).collect();
+
+        let contested = sides.len() > 1;
+        if contested != self.contested {
+            self.contested = contested;
+            self.changed = true;
+        }
+        let Some(side) = sides.first().filter(|_| !contested).copied() else {
+            return;
+        };
+        if self.owner.as_ref() != Some(side) {
+            self.capture(side, config.king_of_the_hill.capture_secs, delta_time);
+            return;
+        }
+        // The owner back in the zone undoes any capture under way
+        if self.capturing.take().is_some() {
+            self.progress = 0.0;
+            self.changed = true;
+        }
+        for (player_id, _) in &inside {
+            let owed = self.owed.entry(*player_id).or_default();
+            *owed += config.king_of_the_hill.points_per_sec * delta_time;
+            let whole = owed.floor();
+            *owed -= whole;
+            if whole > 0.0 {
+                if let Some(player) = ctx.players.get_mut(player_id) {
+                    player.score += whole as u64;
+                }
+                *self.points.entry(side.clone()).or_default() += whole as u64;
+                self.changed = true;
+            }
+        }
+    }
+
+    fn on_player_leave(&mut self, player_id: &PlayerId) {
+        self.owed.remove(player_id);
+        let side = HillSide::Player(*player_id);
+        if self.owner.as_ref() == Some(&side) {
+            self.owner = None;
+            self.changed = true;
+        }
+        if self.capturing.as_ref() == Some(&side) {
+            self.capturing = None;
+            self.progress = 0.0;
+            self.changed = true;
+        }
+    }
+
+    /// The first side to the score cap wins
+    fn round_over(&self, config: &GameConfig) -> bool {
+        let cap = config.king_of_the_hill.score_cap;
+        cap > 0 && self.points.values().any(|points| *points >= cap)
+    }
+
+    fn reset(&mut self) {
+        let changed = self.changed || self.owner.is_some() || self.capturing.is_some() || !self.points.is_empty();
+        *self = HillMode { changed, ..Default::default() };
+    }
+
+    fn signal(&self) -> Option<Signal> {
+        Some(Signal::Hill)
+    }
+
+    fn status(&self, _players: &HashMap<PlayerId, Player>, config: &GameConfig) -> serde_json::Value {
+        let mut scores: Vec<HillScore> = self
+            .points
+            .iter()
+            .map(|(side, points)| HillScore { side: side.clone(), points: *points })
+            .collect();
+        scores.sort_by(|a, b| b.points.cmp(&a.points).then_with(|| a.side.cmp(&b.side)));
+        serde_json::to_value(HillStatus {
+            owner: self.owner.clone(),
+            capturing: self.capturing.clone(),
+            progress: self.progress,
+            contested: self.contested,
+            scores,
+            score_cap: config.king_of_the_hill.score_cap,
+        })
+        .unwrap_or_default()
+    }
+
+    fn take_changed(&mut self) -> bool {
+        std::mem::take(&mut self.changed)
+    }
+
+    fn save_state(&self) -> serde_json::Value {
+        let mut points: Vec<HillScore> = self
+            .points
+            .iter()
+            .map(|(side, points)| HillScore { side: side.clone(), points: *points })
+            .collect();
+        points.sort_by(|a, b| a.side.cmp(&b.side));
+        serde_json::to_value(SavedHill {
+            owner: self.owner.clone(),
+            capturing: self.capturing.clone(),
+            progress: self.progress,
+            contested: self.contested,
+            points,
+            owed: self.owed.clone(),
+        })
+        .unwrap_or_default()
+    }
+
+    fn load_state(&mut self, state: &serde_json::Value) {
+        let Ok(saved) = SavedHill::deserialize(state) else {
+            return;
+        };
+        *self = HillMode {
+            owner: saved.owner,
+            capturing: saved.capturing,
+            progress: saved.progress,
+            contested: saved.contested,
+            points: saved.points.into_iter().map(|score| (score.side, score.points)).collect(),
+            owed: saved.owed,
+            changed: true,
+        };
+    }
+
+    fn clone_box(&self) -> Box<dyn GameMode> {
+        Box::new(self.clone())
+    }
+}
@@ -0,0 +1,274 @@
+use std::collections::BTreeMap;
+use serde::{Deserialize, Serialize};
+use crate::blocks::{Block, Cell};
+use crate::config::HousingConfig;
+use crate::json_store::JsonStore;
+use crate::player::PlayerId;
+
+/// A region of the hub a player can claim and decorate with blocks
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Plot {
+    pub id: u32,
+    /// Left edge
+    pub x: f32,
+    /// Bottom edge
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+}
+
+impl Plot {
+    /// Whether a block cell's center lies inside the plot
+    pub fn contains_cell(&self, cell: Cell, block_size: f32) -> bool {
+        let center_x = (cell.0 as f32 + 0.5) * block_size;
+        let center_y = (cell.1 as f32 + 0.5) * block_size;
+        center_x > self.x && center_x < self.x + self.width && center_y > self.y && center_y < self.y + self.height
+    }
+
+    /// Where visitors arrive: centered above the plot, clear of anything built in it
+    pub fn arrival(&self, player_height: f32) -> (f32, f32) {
+        (self.x + self.width / 2.0, self.y + self.height + player_height / 2.0)
+    }
+}
+
+/// Who owns a plot and what they built in it; this is what the plots file stores
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlotClaim {
+    pub owner: PlayerId,
+    /// Owner's name when they claimed the plot, for listing plots while they're offline
+    pub owner_name: String,
+    /// Unix time in seconds
+    pub claimed_at: u64,
+    pub blocks: Vec<Block>,
+}
+
+/// A plot as listed by the API
+#[derive(Debug, Clone, Serialize)]
+pub struct PlotInfo {
+    #[serde(flatten)]
+    pub plot: Plot,
+    pub owner: Option<PlayerId>,
+    pub owner_name: Option<String>,
+    pub blocks: usize,
+}
+
+/// Why a plot couldn't be claimed or visited
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlotError {
+    /// Housing is turned off
+    Disabled,
+    UnknownPlot,
+    UnknownPlayer,
+    /// Only players who registered a name can own a plot
+    Unregistered,
+    /// Someone else already owns the plot
+    Taken,
+    /// Players own at most one plot
+    AlreadyOwner,
+    NoFreePlot,
+    /// Visiting would pull the player out of a match being played
+    MatchInProgress,
+    /// Dead players wait to respawn before traveling
+    NotAlive,
+}
+
+impl std::fmt::Display for PlotError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let reason = match self {
+            PlotError::Disabled => "housing is disabled",
+            PlotError::UnknownPlot => "no such plot",
+            PlotError::UnknownPlayer => "player is not in the game",
+            PlotError::Unregistered => "only registered players can claim a plot",
+            PlotError::Taken => "plot belongs to another player",
+            PlotError::AlreadyOwner => "player already owns a plot",
+            PlotError::NoFreePlot => "every plot is taken",
+            PlotError::MatchInProgress => "can't travel during a match",
+            PlotError::NotAlive => "player is not alive",
+        };
+        write!(f, "{}", reason)
+    }
+}
+
+impl std::error::Error for PlotError {}
+
+/// The hub's plots, their owners and the blocks built in them
+/// Optionally persisted to a JSON file so houses survive restarts
+#[derive(Debug, Clone, Default)]
+pub struct Housing {
+    plots: Vec<Plot>,
+    claims: JsonStore<BTreeMap<u32, PlotClaim>>,
+    max_blocks_per_plot: usize,
+    /// Whether claims or blocks changed since the last save
+    changed: bool,
+}
+
+impl Housing {
+    /// Lay out the configured plots, all of them free and kept in memory only, so nothing
+    /// can overwrite the claims file before [`Housing::load`] has read it
+    pub fn new(config: &HousingConfig) -> Self {
+        let plots = if config.enabled {
+            (0..config.count)
+                .map(|i| Plot {
+                    id: i as u32,
+                    x: config.origin_x + i as f32 * (config.plot_width + config.gap),
+                    y: config.origin_y,
+                    width: config.plot_width,
+                    height: config.plot_height,
+                })
+                .collect()
+        } else {
+            Vec::new()
+        };
+        Self {
+            plots,
+            claims: JsonStore::in_memory(),
+            max_blocks_per_plot: config.max_blocks_per_plot,
+            changed: false,
+        }
+    }
+
+    /// Lay out the configured plots and read the claims file, if there is one
+    pub async fn load(config: &HousingConfig) -> Result<Self, Box<dyn std::error::Error>> {
+        let mut housing = Self::new(config);
+        let Some(path) = config.path.as_ref().filter(|_| housing.enabled()) else {
+            return Ok(housing);
+        };
+        housing.claims = JsonStore::load(path).await?;
+        // Claims on plots the config no longer has are dropped
+        let plot_count = housing.plots.len();
+        housing.claims.get_mut().retain(|id, _| (*id as usize) < plot_count);
+        Ok(housing)
+    }
+
+    pub fn enabled(&self) -> bool {
+        !self.plots.is_empty()
+    }
+
+    pub fn plot(&self, plot_id: u32) -> Option<&Plot> {
+        self.plots.get(plot_id as usize)
+    }
+
+    /// Every plot with its owner and number of blocks
+    pub fn list(&self) -> Vec<PlotInfo> {
+        self.plots
+            .iter()
+            .map(|plot| {
+                let claim = self.claims.get().get(&plot.id);
+                PlotInfo {
+                    plot: plot.clone(),
+                    owner: claim.map(|c| c.owner),
+                    owner_name: claim.map(|c| c.owner_name.clone()),
+                    blocks: claim.map_or(0, |c| c.blocks.len()),
+                }
+            })
+            .collect()
+    }
+
+    /// The plot a block cell falls in
+    pub fn plot_at(&self, cell: Cell, block_size: f32) -> Option<&Plot> {
+        self.plots.iter().find(|plot| plot.contains_cell(cell, block_size))
+    }
+
+    pub fn owner_of(&self, plot_id: u32) -> Option<&PlayerId> {
+        self.claims.get().get(&plot_id).map(|claim| &claim.owner)
+    }
+
+    /// The plot a player owns
+    pub fn plot_of(&self, player_id: &PlayerId) -> Option<u32> {
+        self.claims.get().iter().find(|(_, claim)| &claim.owner == player_id).map(|(id, _)| *id)
+    }
+
+    /// Take a plot, or the first free one when `plot_id` is None
+    pub fn claim(&mut self, player_id: PlayerId, name: &str, plot_id: Option<u32>, now: u64) -> Result<u32, PlotError> {
+        if !self.enabled() {
+            return Err(PlotError::Disabled);
+        }
+        if self.plot_of(&player_id).is_some() {
+            return Err(PlotError::AlreadyOwner);
+        }
+        let plot_id = match plot_id {
+            Some(id) if self.plot(id).is_none() => return Err(PlotError::UnknownPlot),
+            Some(id) if self.claims.get().contains_key(&id) => return Err(PlotError::Taken),
+            Some(id) => id,
+            None => self
+                .plots
+                .iter()
+                .map(|plot| plot.id)
+                .find(|id| !self.claims.get().contains_key(id))
+                .ok_or(PlotError::NoFreePlot)?,
+        };
+        self.claims.get_mut().insert(
+            plot_id,
+            PlotClaim {
+                owner: player_id,
+                owner_name: name.to_string(),
+                claimed_at: now,
+                blocks: Vec::new(),
+            },
+        );
+        self.changed = true;
+        Ok(plot_id)
+    }
+
+    /// Give up the player's plot; returns it with the blocks that stood in it
+    pub fn release(&mut self, player_id: &PlayerId) -> Option<(u32, PlotClaim)> {
+        let plot_id = self.plot_of(player_id)?;
+        self.changed = true;
+        self.claims.get_mut().remove(&plot_id).map(|claim| (plot_id, claim))
+    }
+
+    /// Whether the plot has room for another block
+    pub fn has_room(&self, plot_id: u32) -> bool {
+        self.claims.get().get(&plot_id).is_some_and(|claim| claim.blocks.len() < self.max_blocks_per_plot)
+    }
+
+    /// Keep a block built in a claimed plot
+    pub fn add_block(&mut self, plot_id: u32, block: Block) {
+        if let Some(claim) = self.claims.get_mut().get_mut(&plot_id) {
+            claim.blocks.push(block);
+            self.changed = true;
+        }
+    }
+
+    pub fn remove_block(&mut self, plot_id: u32, cell: Cell) {
+        if let Some(claim) = self.claims.get_mut().get_mut(&plot_id) {
+            claim.blocks.retain(|block| (block.cell_x, block.cell_y) != cell);
+            self.changed = true;
+        }
+    }
+
+    /// Whether a block is one kept in a plot
+    pub fn holds(&self, block: &Block) -> bool {
+        self.claims
+            .get()
+            .values()
+            .any(|claim| claim.blocks.iter().any(|b| (b.cell_x, b.cell_y) == (block.cell_x, block.cell_y)))
+    }
+
+    /// Every block kept in a plot
+    pub fn blocks(&self) -> impl Iterator<Item = &Block> {
+        self.claims.get().values().flat_map(|claim| claim.blocks.iter())
+    }
+
+    /// Blocks the player built in their plot
+    pub fn block_count_of(&self, player_id: &PlayerId) -> usize {
+        self.plot_of(player_id)
+            .and_then(|id| self.claims.get().get(&id))
+            .map_or(0, |claim| claim.blocks.len())
+    }
+
+    /// Write the claims file in the background if claims or blocks changed since the last call
+    /// Returns whether anything changed
+    pub fn save_changes(&mut self) -> bool {
+        if !std::mem::take(&mut self.changed) {
+            return false;
+        }
+        self.claims.save_in_background();
+        true
+    }
+
+    /// Write the claims file now, if one is configured
+    pub async fn save(&self) -> Result<(), Box<dyn std::error::Error>> {
+        self.claims.save().await
+    }
+}
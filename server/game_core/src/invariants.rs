@@ -0,0 +1,137 @@
+use std::fmt;
+use crate::config::PlatformConfig;
+use crate::game_state::GameState;
+use crate::ground_state::GroundState;
+use crate::physics::PhysicsWorld;
+use crate::player::{Player, PlayerId};
+
+/// How far a player may overlap geometry before it counts as being inside it
+/// Collision resolution leaves players 0.001 outside surfaces, so this only trips on real tunneling
+pub const OVERLAP_TOLERANCE: f32 = 0.05;
+
+/// A physics invariant a player broke
+#[derive(Debug, Clone, PartialEq)]
+pub enum Violation {
+    /// Position or velocity is NaN or infinite
+    NonFinite,
+    /// Below the ground plane
+    BelowGround { bottom: f32, ground_y: f32 },
+    /// Overlapping a platform or wall
+    InsideGeometry { id: String, overlap_x: f32, overlap_y: f32 },
+    /// Horizontal speed above the configured maximum
+    VelocityOutOfBounds { velocity_x: f32, max: f32 },
+    /// Grounded, but not standing on what the ground state says
+    GroundStateMismatch { ground_state: GroundState, bottom: f32, surface_y: Option<f32> },
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Violation::NonFinite => write!(f, "position or velocity is not finite"),
+            Violation::BelowGround { bottom, ground_y } => {
+                write!(f, "bottom {} is below ground {}", bottom, ground_y)
+            }
+            Violation::InsideGeometry { id, overlap_x, overlap_y } => {
+                write!(f, "inside {} (overlap {} x {})", id, overlap_x, overlap_y)
+            }
+            Violation::VelocityOutOfBounds { velocity_x, max } => {
+                write!(f, "velocity_x {} exceeds max {}", velocity_x, max)
+            }
+            Violation::GroundStateMismatch { ground_state, bottom, surface_y } => write!(
+                f,
+                "{:?} with bottom {} but surface at {:?}",
+                ground_state, bottom, surface_y
+            ),
+        }
+    }
+}
+
+/// Overlap of two intervals, zero or negative when they don't overlap
+fn overlap(a_min: f32, a_max: f32, b_min: f32, b_max: f32) -> f32 {
+    a_max.min(b_max) - a_min.max(b_min)
+}
+
+/// Check one player against the world's invariants
+/// `platforms` must be the configured platforms followed by the blocks passed to `update_player_physics`
+pub fn check_player(world: &PhysicsWorld, player: &Player, platforms: &[PlatformConfig]) -> Vec<Violation> {
+    let physics = &world.config().physics;
+    let mut violations = Vec::new();
+
+    if ![player.x, player.y, player.velocity_x, player.velocity_y]
+        .iter()
+        .all(|v| v.is_finite())
+    {
+        violations.push(Violation::NonFinite);
+        return violations;
+    }
+
+    let half_w = physics.player_width / 2.0;
+    let half_h = player.height(physics) / 2.0;
+    let (left, right) = (player.x - half_w, player.x + half_w);
+    let (bottom, top) = (player.y - half_h, player.y + half_h);
+
+    let ground_y = world.geometry().ground_under(left, right);
+    if bottom < ground_y - OVERLAP_TOLERANCE {
+        violations.push(Violation::BelowGround { bottom, ground_y });
+    }
+
+    let solids = platforms
+        .iter()
+        .map(|p| (&p.id, p.x_start, p.x_end, p.y_top - p.height, p.y_top))
+        .chain(
+            world
+                .walls()
+                .iter()
+                .map(|w| (&w.id, w.x, w.x + w.width, w.y_bottom, w.y_top)),
+        );
+    for (id, s_left, s_right, s_bottom, s_top) in solids {
+        let overlap_x = overlap(left, right, s_left, s_right);
+        let overlap_y = overlap(bottom, top, s_bottom, s_top);
+        if overlap_x > OVERLAP_TOLERANCE && overlap_y > OVERLAP_TOLERANCE {
+            violations.push(Violation::InsideGeometry { id: id.clone(), overlap_x, overlap_y });
+        }
+    }
+
+    if player.velocity_x.abs() > physics.max_horizontal_velocity + f32::EPSILON {
+        violations.push(Violation::VelocityOutOfBounds {
+            velocity_x: player.velocity_x,
+            max: physics.max_horizontal_velocity,
+        });
+    }
+
+    if let GroundState::Grounded { platform_id } = player.ground_state {
+        let surface_y = match platform_id {
+            None => Some(ground_y),
+            Some(idx) => platforms
+                .get(idx as usize)
+                .filter(|p| overlap(left, right, p.x_start, p.x_end) > 0.0)
+                .map(|p| p.y_top),
+        };
+        let standing = surface_y.is_some_and(|y| (bottom - y).abs() <= OVERLAP_TOLERANCE);
+        if !standing {
+            violations.push(Violation::GroundStateMismatch {
+                ground_state: player.ground_state,
+                bottom,
+                surface_y,
+            });
+        }
+    }
+
+    violations
+}
+
+/// Check every player in a game state, including collisions with runtime blocks
+pub fn check_state(state: &GameState) -> Vec<(PlayerId, Violation)> {
+    let config = state.world.config();
+    let mut platforms = state.world.platforms().to_vec();
+    platforms.extend(state.blocks.platforms(&config.building));
+    state
+        .players
+        .values()
+        .flat_map(|player| {
+            check_player(&state.world, player, &platforms)
+                .into_iter()
+                .map(|violation| (player.id, violation))
+        })
+        .collect()
+}
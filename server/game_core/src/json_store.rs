@@ -0,0 +1,100 @@
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+/// Replace `path` with `contents` by writing a sibling file and renaming it over the old
+/// one, so a crash mid-write leaves either the old file or the new one, never half of each
+pub async fn write_atomic(path: &Path, contents: impl AsRef<[u8]>) -> std::io::Result<()> {
+    let mut temp_name = path.file_name().unwrap_or_default().to_os_string();
+    temp_name.push(".tmp");
+    let temp_path = path.with_file_name(temp_name);
+    tokio::fs::write(&temp_path, contents).await?;
+    tokio::fs::rename(&temp_path, path).await
+}
+
+/// A value kept in memory and optionally mirrored to a JSON file so it survives restarts
+///
+/// A missing or empty file loads as `T::default()`; the file is created on the first save.
+/// Clones share the background writer, so their saves stay in order too
+#[derive(Debug, Clone, Default)]
+pub struct JsonStore<T> {
+    value: T,
+    path: Option<PathBuf>,
+    /// Generation of the last background save started, and of the last one written
+    saves: Arc<std::sync::atomic::AtomicU64>,
+    written: Arc<tokio::sync::Mutex<u64>>,
+}
+
+impl<T: Default + Serialize + DeserializeOwned> JsonStore<T> {
+    /// A store that is lost on restart
+    pub fn in_memory() -> Self {
+        Self::default()
+    }
+
+    /// Load the value from a JSON file, creating it on first save if missing
+    pub async fn load(path: impl Into<PathBuf>) -> Result<Self, Box<dyn std::error::Error>> {
+        let path = path.into();
+        let value = match tokio::fs::read_to_string(&path).await {
+            Ok(contents) if contents.trim().is_empty() => T::default(),
+            Ok(contents) => serde_json::from_str(&contents)?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => T::default(),
+            Err(e) => return Err(e.into()),
+        };
+        Ok(Self {
+            value,
+            path: Some(path),
+            ..Self::default()
+        })
+    }
+
+    /// The file the value is saved to, if any
+    pub fn path(&self) -> Option<&Path> {
+        self.path.as_deref()
+    }
+
+    pub fn get(&self) -> &T {
+        &self.value
+    }
+
+    /// Change the value; call `save` or `save_in_background` to persist it
+    pub fn get_mut(&mut self) -> &mut T {
+        &mut self.value
+    }
+
+    /// Rewrite the file if one is configured
+    pub async fn save(&self) -> Result<(), Box<dyn std::error::Error>> {
+        if let Some(path) = &self.path {
+            write_atomic(path, serde_json::to_string(&self.value)?).await?;
+        }
+        Ok(())
+    }
+
+    /// Serialize the value now and write it on a background task, so callers holding a lock
+    /// (such as the game loop mid-tick) don't wait on the disk
+    /// Writes land in order: a save that finishes after a newer one is dropped.
+    pub fn save_in_background(&self) {
+        let Some(path) = self.path.clone() else {
+            return;
+        };
+        let contents = match serde_json::to_string(&self.value) {
+            Ok(contents) => contents,
+            Err(e) => {
+                eprintln!("❌ Failed to serialize {}: {}", path.display(), e);
+                return;
+            }
+        };
+        let generation = self.saves.fetch_add(1, std::sync::atomic::Ordering::SeqCst) + 1;
+        let written = self.written.clone();
+        tokio::spawn(async move {
+            let mut written = written.lock().await;
+            if *written > generation {
+                return;
+            }
+            match write_atomic(&path, contents).await {
+                Ok(()) => *written = generation,
+                Err(e) => eprintln!("❌ Failed to save {}: {}", path.display(), e),
+            }
+        });
+    }
+}
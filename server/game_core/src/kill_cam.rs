@@ -0,0 +1,125 @@
+//! Kill cams: a rolling history of every player's recent moves and inputs, replayed to a
+//! player when another player kills them
+
+use std::collections::{HashMap, VecDeque};
+use serde::{Deserialize, Serialize};
+use crate::commands::{HeldKey, PlayerCommand};
+use crate::player::{Player, PlayerId};
+use crate::respawn::DeathCause;
+
+/// A player at the end of a tick, with the commands they sent during it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HistoryFrame {
+    pub tick: u64,
+    pub x: f32,
+    pub y: f32,
+    pub velocity_x: f32,
+    pub velocity_y: f32,
+    pub facing_right: bool,
+    pub crouched: bool,
+    /// Direction held down
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub held: Option<HeldKey>,
+    /// Seconds left on the held direction's timeout
+    #[serde(skip)]
+    pub held_secs: f32,
+    /// Commands applied since the previous frame, in order
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub inputs: Vec<PlayerCommand>,
+    /// Fraction of normal speed the step up to this frame ran at
+    #[serde(default = "normal_speed", skip_serializing_if = "is_normal_speed")]
+    pub time_scale: f32,
+}
+
+fn normal_speed() -> f32 {
+    1.0
+}
+
+fn is_normal_speed(time_scale: &f32) -> bool {
+    *time_scale == 1.0
+}
+
+/// The last moments before a player died, seen from the player who killed them
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct KillCam {
+    pub victim: PlayerId,
+    pub killer: PlayerId,
+    pub cause: DeathCause,
+    /// Tick the victim died on
+    pub tick: u64,
+    /// The killer's frames, oldest first; clients follow these
+    pub killer_frames: Vec<HistoryFrame>,
+    /// The victim's frames over the same ticks
+    pub victim_frames: Vec<HistoryFrame>,
+}
+
+/// The last few frames of every player, oldest first
+#[derive(Debug, Clone, Default)]
+pub struct InputHistory {
+    frames: HashMap<PlayerId, VecDeque<HistoryFrame>>,
+    /// Commands applied since the last capture
+    pending: HashMap<PlayerId, Vec<PlayerCommand>>,
+}
+
+impl InputHistory {
+    /// Note a command the player sent, for the next captured frame
+    pub fn record_input(&mut self, player_id: PlayerId, command: &PlayerCommand) {
+        self.pending.entry(player_id).or_default().push(command.clone());
+    }
+
+    /// Add a frame for every player, keeping the newest `capacity` per player
+    pub fn capture<'a>(&mut self, tick: u64, time_scale: f32, players: impl Iterator<Item = &'a Player>, capacity: usize) {
+        for player in players {
+            let frames = self.frames.entry(player.id).or_default();
+            frames.push_back(HistoryFrame {
+                tick,
+                x: player.x,
+                y: player.y,
+                velocity_x: player.velocity_x,
+                velocity_y: player.velocity_y,
+                facing_right: player.facing_right,
+                crouched: player.crouched,
+                held: player.held,
+                held_secs: player.held_secs,
+                inputs: self.pending.remove(&player.id).unwrap_or_default(),
+                time_scale,
+            });
+            while frames.len() > capacity.max(1) {
+                frames.pop_front();
+            }
+        }
+        self.pending.clear();
+    }
+
+    /// The player's frames, oldest first
+    pub fn frames(&self, player_id: &PlayerId) -> impl Iterator<Item = &HistoryFrame> {
+        self.frames.get(player_id).into_iter().flatten()
+    }
+
+    /// Replay of the victim's last frames alongside the killer's, over the ticks both cover
+    /// and at most the newest `max_frames` of them
+    pub fn kill_cam(&self, victim: PlayerId, killer: PlayerId, cause: DeathCause, tick: u64, max_frames: usize) -> KillCam {
+        let killer_frames: Vec<HistoryFrame> = self.frames(&killer).cloned().collect();
+        let killer_frames = killer_frames[killer_frames.len().saturating_sub(max_frames.max(1))..].to_vec();
+        let first_tick = killer_frames.first().map_or(tick, |frame| frame.tick);
+        KillCam {
+            victim,
+            killer,
+            cause,
+            tick,
+            victim_frames: self.frames(&victim).filter(|frame| frame.tick >= first_tick).cloned().collect(),
+            killer_frames,
+        }
+    }
+
+    pub fn forget(&mut self, player_id: &PlayerId) {
+        self.frames.remove(player_id);
+        self.pending.remove(player_id);
+    }
+
+    /// Drop everything, e.g. after everyone was moved to a new map
+    pub fn clear(&mut self) {
+        self.frames.clear();
+        self.pending.clear();
+    }
+}
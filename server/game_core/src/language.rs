@@ -0,0 +1,48 @@
+use serde::{Deserialize, Serialize};
+
+/// Languages server-generated text is available in
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Language {
+    #[default]
+    En,
+    Es,
+    Fr,
+}
+
+impl Language {
+    pub const ALL: [Language; 3] = [Language::En, Language::Es, Language::Fr];
+
+    /// Parse a language tag such as "es", "fr-CA" or "EN_us"
+    pub fn parse(tag: &str) -> Option<Self> {
+        let primary = tag.trim().split(['-', '_']).next()?.to_lowercase();
+        Self::ALL.into_iter().find(|language| language.code() == primary)
+    }
+
+    /// The most preferred supported language in an Accept-Language header
+    pub fn from_accept_language(header: &str) -> Option<Self> {
+        let mut candidates: Vec<(f32, Language)> = header
+            .split(',')
+            .filter_map(|entry| {
+                let mut parts = entry.split(';');
+                let language = Self::parse(parts.next()?)?;
+                let quality = parts
+                    .find_map(|param| param.trim().strip_prefix("q="))
+                    .and_then(|q| q.parse().ok())
+                    .unwrap_or(1.0);
+                Some((quality, language))
+            })
+            .collect();
+        // Stable sort keeps header order among equal weights
+        candidates.sort_by(|a, b| b.0.total_cmp(&a.0));
+        candidates.first().map(|(_, language)| *language)
+    }
+
+    pub fn code(&self) -> &'static str {
+        match self {
+            Language::En => "en",
+            Language::Es => "es",
+            Language::Fr => "fr",
+        }
+    }
+}
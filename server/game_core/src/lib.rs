@@ -1,17 +1,98 @@
 pub mod player;
 pub mod game_state;
 pub mod physics;
+pub mod collision;
 pub mod commands;
 pub mod chat;
 pub mod config;
 pub mod ground_state;
 pub mod player_color;
+pub mod blocks;
+pub mod scoring;
+pub mod match_history;
+pub mod challenges;
+pub mod names;
+pub mod name_reservations;
+pub mod map_check;
+pub mod simulation;
+pub mod invariants;
+pub mod clock;
+pub mod room;
+pub mod player_settings;
+pub mod teams;
+pub mod language;
+pub mod match_state;
+pub mod respawn;
+pub mod projectiles;
+pub mod health;
+pub mod cosmetics;
+pub mod map_rotation;
+pub mod signals;
+pub mod game_mode;
+pub mod map_editor;
+pub mod analytics;
+pub mod bots;
+pub mod replay;
+pub mod rng;
+pub mod stamina;
+pub mod mutators;
+pub mod match_log;
+pub mod snapshot;
+pub mod encoding;
+pub mod housing;
+pub mod world_events;
+pub mod kill_cam;
+pub mod state_frame;
+pub mod physics_sandbox;
+pub mod anti_cheat;
+pub mod race;
+pub mod tag;
+pub mod hill;
+pub mod json_store;
 
-pub use player::Player;
-pub use game_state::GameState;
+pub use player::{Player, PlayerInternals};
+pub use game_state::{GameState, SlowMotion};
 pub use physics::*;
-pub use commands::PlayerCommand;
+pub use commands::{CommandAck, CommandOutcome, Cooldowns, HeldKey, IgnoredReason, PlayerCommand};
 pub use chat::ChatMessage;
-pub use config::{GameConfig, PlatformConfig, PhysicsConfig};
+pub use config::{GameConfig, PlatformConfig, PhysicsConfig, BuildingConfig, ComboConfig, Surface};
 pub use ground_state::GroundState;
-
+pub use blocks::{Block, BlockGrid, BuildError, GeometryDelta, GeometryEvent, GeometrySnapshot, GeometrySync};
+pub use scoring::{ComboBreak, ComboState, ScoreSource};
+pub use match_history::{MatchHistory, MatchPage, MatchQuery, MatchRecord};
+pub use challenges::{ChallengeCompletion, ChallengeDefinition, ChallengeProgress, ChallengeTracker};
+pub use names::NameError;
+pub use name_reservations::{NameReservations, ReservationError};
+pub use clock::{Clock, MockClock, SharedClock, SystemClock};
+pub use room::{Admission, WaitingQueue};
+pub use player_settings::{PlayerSettings, SettingsError};
+pub use teams::{TeamError, TeamId};
+pub use language::Language;
+pub use match_state::{MatchResult, MatchState};
+pub use player_color::Palette;
+pub use respawn::{DeathCause, LifeEvent, LifeState};
+pub use projectiles::{Projectile, ShootError};
+pub use health::{DamageEvent, DamageSource};
+pub use cosmetics::{CosmeticError, CosmeticProfile, CosmeticSlot, CosmeticStore, EquippedCosmetics};
+pub use map_rotation::{MapChange, MapRotation, MapVotes, VoteError};
+pub use game_mode::{GameMode, ModeContext, ModeError, ModeFactory, ModeManifest, ModeRegistry, ModeStatus, ModeSwitch, StandardMode};
+pub use map_editor::{MapEdit, MapEditError};
+pub use analytics::{Analytics, OverlayStats, PlayerStats};
+pub use bots::{Behavior, BehaviorKind};
+pub use replay::{RecordedMatch, Replay, ReplayRecorder, ReplaySummary};
+pub use rng::SeededRng;
+pub use json_store::JsonStore;
+pub use stamina::StaminaEffect;
+pub use mutators::{Mutator, MutatorBallot, MutatorError, MutatorVotes};
+pub use match_log::{MatchLog, MatchLogError, PlaybackFrame};
+pub use snapshot::{SnapshotError, WorldSnapshot};
+pub use encoding::Encoding;
+pub use housing::{Housing, Plot, PlotError, PlotInfo};
+pub use kill_cam::{HistoryFrame, InputHistory, KillCam};
+pub use physics_sandbox::PhysicsPatchError;
+pub use anti_cheat::{CheatReport, ReplayCheck, Suspicion, Verdict};
+pub use state_frame::{StateDecodeError, StateFrame};
+pub use race::{RaceMode, RaceProgress, RaceStanding, RaceStandings};
+pub use tag::{TagMode, TagPass, TagStatus};
+pub use hill::{HillMode, HillScore, HillSide, HillStatus};
+pub use world_events::{ActiveWorldEvent, EntityKind, WorldEntity, WorldEventChange, WorldEventError, WorldEventKind, WorldEvents};
@@ -0,0 +1,242 @@
+use std::collections::{HashSet, VecDeque};
+use serde::Serialize;
+use crate::collision::BakedGeometry;
+use crate::commands::PlayerCommand;
+use crate::config::GameConfig;
+use crate::ground_state::GroundState;
+use crate::physics::PhysicsWorld;
+use crate::player::Player;
+
+/// Longest simulated jump or fall before giving up, in seconds
+const MAX_AIRTIME_SECS: f32 = 5.0;
+/// Spacing of simulated take-off points along a surface
+const SAMPLE_SPACING: f32 = 0.5;
+/// Take-off speeds tried in each direction, evenly spaced up to max horizontal velocity
+/// Players build speed gradually, so slow jumps matter as much as full-speed ones
+const SPEED_STEPS: i32 = 64;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    /// The map will not behave correctly
+    Error,
+    /// Probably a mistake, but playable
+    Warning,
+}
+
+/// A problem found in a map
+#[derive(Debug, Clone, Serialize)]
+pub struct MapIssue {
+    pub severity: Severity,
+    /// Platform or wall id, if the issue concerns one
+    pub id: Option<String>,
+    pub message: String,
+}
+
+/// Something a player can stand on
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Surface {
+    Ground,
+    /// Index into the config's platforms
+    Platform(usize),
+}
+
+/// Which platforms can be reached from spawn
+#[derive(Debug, Clone, Serialize)]
+pub struct Reachability {
+    pub reachable: Vec<String>,
+    pub unreachable: Vec<String>,
+}
+
+/// Check map geometry for invalid or suspicious definitions
+pub fn validate(config: &GameConfig) -> Vec<MapIssue> {
+    let ground = BakedGeometry::default().with_ground(&config.physics);
+    let mut issues = Vec::new();
+    let mut issue = |severity, id: &str, message: String| {
+        issues.push(MapIssue {
+            severity,
+            id: Some(id.to_string()),
+            message,
+        })
+    };
+
+    let mut ids = HashSet::new();
+    for platform in &config.platforms {
+        if !ids.insert(platform.id.as_str()) {
+            issue(Severity::Error, &platform.id, "duplicate id".to_string());
+        }
+        if platform.x_end <= platform.x_start {
+            issue(Severity::Error, &platform.id, format!("x_end {} is not right of x_start {}", platform.x_end, platform.x_start));
+        }
+        if platform.height <= 0.0 {
+            issue(Severity::Error, &platform.id, format!("height {} must be positive", platform.height));
+        }
+        let ground_y = ground.ground_under(platform.x_start, platform.x_end);
+        if platform.y_top <= ground_y {
+            issue(Severity::Warning, &platform.id, format!("top {} is at or below the ground ({})", platform.y_top, ground_y));
+        }
+    }
+    for wall in &config.walls {
+        if !ids.insert(wall.id.as_str()) {
+            issue(Severity::Error, &wall.id, "duplicate id".to_string());
+        }
+        if wall.width <= 0.0 {
+            issue(Severity::Error, &wall.id, format!("width {} must be positive", wall.width));
+        }
+        if wall.y_top <= wall.y_bottom {
+            issue(Severity::Error, &wall.id, format!("y_top {} is not above y_bottom {}", wall.y_top, wall.y_bottom));
+        }
+    }
+
+    // Gaps smaller than the player can trap it: collision pushes it out of one solid into the other
+    let (player_w, player_h) = (config.physics.player_width, config.physics.player_height);
+    for (i, a) in config.platforms.iter().enumerate() {
+        for b in &config.platforms[i + 1..] {
+            let gap_x = a.x_start.max(b.x_start) - a.x_end.min(b.x_end);
+            let gap_y = (a.y_top - a.height).max(b.y_top - b.height) - a.y_top.min(b.y_top);
+            if gap_x < 0.0 && gap_y < 0.0 {
+                issue(Severity::Warning, &a.id, format!("overlaps platform {}", b.id));
+            } else if gap_x < player_w && gap_y < player_h {
+                issue(Severity::Warning, &a.id, format!("gap to platform {} is too small for a player", b.id));
+            }
+        }
+        for wall in &config.walls {
+            let gap_x = a.x_start.max(wall.x) - a.x_end.min(wall.x + wall.width);
+            let gap_y = (a.y_top - a.height).max(wall.y_bottom) - a.y_top.min(wall.y_top);
+            if gap_x.max(0.0) < player_w && gap_y < player_h && (gap_x >= 0.0 || gap_y >= 0.0) {
+                issue(Severity::Warning, &a.id, format!("gap to wall {} is too small for a player", wall.id));
+            }
+        }
+    }
+
+    if config.physics.gravity >= 0.0 {
+        issues.push(MapIssue {
+            severity: Severity::Error,
+            id: None,
+            message: format!("gravity {} must be negative", config.physics.gravity),
+        });
+    }
+    issues
+}
+
+/// Horizontal range of the ground the player can walk on from spawn, bounded by walls
+pub fn ground_range(config: &GameConfig) -> (f32, f32) {
+    let spawn_x = 0.0;
+    let left = config
+        .walls
+        .iter()
+        .filter(|w| w.x + w.width <= spawn_x)
+        .map(|w| w.x + w.width)
+        .fold(f32::NEG_INFINITY, f32::max);
+    let right = config
+        .walls
+        .iter()
+        .filter(|w| w.x >= spawn_x)
+        .map(|w| w.x)
+        .fold(f32::INFINITY, f32::min);
+
+    // Without walls, cover everything platforms span plus a margin
+    let min_x = config.platforms.iter().map(|p| p.x_start).fold(spawn_x, f32::min) - 5.0;
+    let max_x = config.platforms.iter().map(|p| p.x_end).fold(spawn_x, f32::max) + 5.0;
+    let half_width = config.physics.player_width / 2.0;
+    (left.max(min_x) + half_width, right.min(max_x) - half_width)
+}
+
+/// Surfaces a player landing from `player` ends up on, stepping the real physics
+fn simulate_landing(world: &PhysicsWorld, mut player: Player, dt: f32) -> Option<Surface> {
+    let steps = (MAX_AIRTIME_SECS / dt) as usize;
+    // Let the player leave the surface before looking for a landing
+    let mut airborne = false;
+    for _ in 0..steps {
+        world.update_player_physics(&mut player, dt, &[]);
+        match player.ground_state {
+            GroundState::Flying => airborne = true,
+            GroundState::Grounded { platform_id } if airborne => {
+                return Some(match platform_id {
+                    Some(idx) => Surface::Platform(idx as usize),
+                    None => Surface::Ground,
+                });
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Surfaces reachable in one jump or walk-off from a surface
+fn neighbours(world: &PhysicsWorld, from: Surface, dt: f32) -> HashSet<Surface> {
+    let config = world.config();
+    let physics = &config.physics;
+    let (left, right, top) = match from {
+        Surface::Ground => {
+            let (left, right) = ground_range(config);
+            (left, right, physics.ground_y)
+        }
+        Surface::Platform(idx) => {
+            let p = &config.platforms[idx];
+            (p.x_start, p.x_end, p.y_top)
+        }
+    };
+
+    let mut found = HashSet::new();
+    let samples = ((right - left) / SAMPLE_SPACING).ceil().max(0.0) as usize;
+    for i in 0..=samples {
+        let x = (left + i as f32 * SAMPLE_SPACING).min(right);
+        for step in -SPEED_STEPS..=SPEED_STEPS {
+            let fraction = step as f32 / SPEED_STEPS as f32;
+            let mut player = Player::new(uuid::Uuid::nil(), physics);
+            player.x = x;
+            // Uneven ground is one surface, standing wherever it is under this sample
+            let top = match from {
+                Surface::Ground => world.geometry().ground_under(x - physics.player_width / 2.0, x + physics.player_width / 2.0),
+                Surface::Platform(_) => top,
+            };
+            player.y = top + physics.player_height / 2.0 + 0.001;
+            player.velocity_x = fraction * physics.max_horizontal_velocity;
+            player.ground_state = GroundState::Grounded {
+                platform_id: match from {
+                    Surface::Platform(idx) => Some(idx as u32),
+                    Surface::Ground => None,
+                },
+            };
+
+            // Walking off an edge at this speed, or jumping from here
+            if let Some(surface) = simulate_landing(world, player.clone(), dt) {
+                found.insert(surface);
+            }
+            world.apply_command(&mut player, &PlayerCommand::Jump);
+            if let Some(surface) = simulate_landing(world, player, dt) {
+                found.insert(surface);
+            }
+        }
+    }
+    found
+}
+
+/// Which platforms can be reached from spawn given the jump physics
+/// Simulates jumps and walk-offs from points along each reachable surface using the
+/// same physics step as the server, so the result matches what players can actually do
+pub fn reachability(world: &PhysicsWorld) -> Reachability {
+    let config = world.config();
+    let dt = 1.0 / config.tick_rate_hz.max(1.0);
+
+    let mut visited = HashSet::from([Surface::Ground]);
+    let mut queue = VecDeque::from([Surface::Ground]);
+    while let Some(surface) = queue.pop_front() {
+        for next in neighbours(world, surface, dt) {
+            if visited.insert(next) {
+                queue.push_back(next);
+            }
+        }
+    }
+
+    let (reachable, unreachable) = config
+        .platforms
+        .iter()
+        .enumerate()
+        .partition::<Vec<_>, _>(|(idx, _)| visited.contains(&Surface::Platform(*idx)));
+    Reachability {
+        reachable: reachable.into_iter().map(|(_, p)| p.id.clone()).collect(),
+        unreachable: unreachable.into_iter().map(|(_, p)| p.id.clone()).collect(),
+    }
+}
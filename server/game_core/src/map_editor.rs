@@ -0,0 +1,80 @@
+use crate::config::{MapGeometry, PlatformConfig, ValidationErrors, WallConfig};
+
+/// A change an admin makes to the live map
+#[derive(Debug, Clone)]
+pub enum MapEdit {
+    AddPlatform(PlatformConfig),
+    AddWall(WallConfig),
+    /// Shift a platform or wall by an offset
+    Move { id: String, dx: f32, dy: f32 },
+    /// Remove a platform or wall
+    Delete { id: String },
+}
+
+/// Why a map edit was refused
+#[derive(Debug, Clone, PartialEq)]
+pub enum MapEditError {
+    /// A platform or wall already has the id
+    DuplicateId(String),
+    /// No platform or wall has the id
+    UnknownObject(String),
+    /// The edited map would fail validation
+    Invalid(ValidationErrors),
+}
+
+impl std::fmt::Display for MapEditError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MapEditError::DuplicateId(id) => write!(f, "an object with id {} already exists", id),
+            MapEditError::UnknownObject(id) => write!(f, "no platform or wall with id {}", id),
+            MapEditError::Invalid(errors) => write!(f, "{}", errors),
+        }
+    }
+}
+
+impl std::error::Error for MapEditError {}
+
+fn exists(geometry: &MapGeometry, id: &str) -> bool {
+    geometry.platforms.iter().any(|p| p.id == id) || geometry.walls.iter().any(|w| w.id == id)
+}
+
+/// Apply an edit to `geometry`, then check the result; `geometry` is unchanged on error
+pub fn apply(geometry: &MapGeometry, edit: MapEdit) -> Result<MapGeometry, MapEditError> {
+    let mut edited = geometry.clone();
+    match edit {
+        MapEdit::AddPlatform(platform) => {
+            if exists(geometry, &platform.id) {
+                return Err(MapEditError::DuplicateId(platform.id));
+            }
+            edited.platforms.push(platform);
+        }
+        MapEdit::AddWall(wall) => {
+            if exists(geometry, &wall.id) {
+                return Err(MapEditError::DuplicateId(wall.id));
+            }
+            edited.walls.push(wall);
+        }
+        MapEdit::Move { id, dx, dy } => {
+            if let Some(platform) = edited.platforms.iter_mut().find(|p| p.id == id) {
+                platform.x_start += dx;
+                platform.x_end += dx;
+                platform.y_top += dy;
+            } else if let Some(wall) = edited.walls.iter_mut().find(|w| w.id == id) {
+                wall.x += dx;
+                wall.y_bottom += dy;
+                wall.y_top += dy;
+            } else {
+                return Err(MapEditError::UnknownObject(id));
+            }
+        }
+        MapEdit::Delete { id } => {
+            if !exists(geometry, &id) {
+                return Err(MapEditError::UnknownObject(id));
+            }
+            edited.platforms.retain(|p| p.id != id);
+            edited.walls.retain(|w| w.id != id);
+        }
+    }
+    edited.validate().map_err(MapEditError::Invalid)?;
+    Ok(edited)
+}
@@ -0,0 +1,131 @@
+use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use crate::config::{MapConfig, MapRotationConfig};
+use crate::player::PlayerId;
+
+/// Why a map vote was refused
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum VoteError {
+    /// Voting is turned off or there is no other map to vote for
+    Disabled,
+    UnknownMap,
+    UnknownPlayer,
+}
+
+impl std::fmt::Display for VoteError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            VoteError::Disabled => write!(f, "map voting is disabled"),
+            VoteError::UnknownMap => write!(f, "no such map"),
+            VoteError::UnknownPlayer => write!(f, "player is not in the game"),
+        }
+    }
+}
+
+impl std::error::Error for VoteError {}
+
+/// A map and the votes it currently has for being played next
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct MapVotes {
+    pub id: String,
+    pub name: String,
+    pub votes: usize,
+}
+
+/// The map now being played, broadcast when it changes
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MapChange {
+    pub id: String,
+    pub name: String,
+}
+
+/// Which of the configured maps is being played and the votes for the next one
+#[derive(Debug, Clone, Default)]
+pub struct MapRotation {
+    /// Index into the configured maps
+    current: usize,
+    /// Matches finished on the current map
+    matches_played: u32,
+    /// Each player's vote, by map id
+    votes: HashMap<PlayerId, String>,
+}
+
+impl MapRotation {
+    /// Index of the map being played
+    pub fn current(&self) -> usize {
+        self.current
+    }
+
+    /// Carry on from a restored map, with no matches played on it yet and no votes
+    pub fn resume_at(&mut self, index: usize) {
+        *self = Self { current: index, ..Self::default() };
+    }
+
+    /// Record a player's vote for the next map, replacing any earlier vote
+    pub fn vote<'a>(
+        &mut self,
+        maps: &'a [MapConfig],
+        config: &MapRotationConfig,
+        player_id: PlayerId,
+        map: &str,
+    ) -> Result<&'a MapConfig, VoteError> {
+        if !config.voting || maps.len() < 2 {
+            return Err(VoteError::Disabled);
+        }
+        // Players may type the display name instead of the id
+        let chosen = maps
+            .iter()
+            .find(|m| m.id == map)
+            .or_else(|| maps.iter().find(|m| m.id.eq_ignore_ascii_case(map) || m.display_name().eq_ignore_ascii_case(map)))
+            .ok_or(VoteError::UnknownMap)?;
+        self.votes.insert(player_id, chosen.id.clone());
+        Ok(chosen)
+    }
+
+    /// Drop the vote of a player who left
+    pub fn forget(&mut self, player_id: &PlayerId) {
+        self.votes.remove(player_id);
+    }
+
+    /// Every map with its current vote count, in rotation order
+    pub fn tally(&self, maps: &[MapConfig]) -> Vec<MapVotes> {
+        maps.iter()
+            .map(|map| MapVotes {
+                id: map.id.clone(),
+                name: map.display_name().to_string(),
+                votes: self.votes.values().filter(|v| **v == map.id).count(),
+            })
+            .collect()
+    }
+
+    /// Called when a match's results are over: the index of the map to play next if it
+    /// changes. The most voted map wins, ties going to whichever comes next in rotation;
+    /// without votes the rotation moves on after `matches_per_map` matches
+    pub fn advance(&mut self, maps: &[MapConfig], config: &MapRotationConfig) -> Option<usize> {
+        if maps.len() < 2 {
+            return None;
+        }
+        self.matches_played += 1;
+        let tally = self.tally(maps);
+        self.votes.clear();
+
+        let in_rotation_order = (1..=maps.len()).map(|offset| (self.current + offset) % maps.len());
+        let voted = in_rotation_order
+            .filter(|&index| tally[index].votes > 0)
+            .fold(None, |best: Option<usize>, index| match best {
+                Some(best) if tally[best].votes >= tally[index].votes => Some(best),
+                _ => Some(index),
+            });
+        let next = match voted {
+            Some(index) => index,
+            None if self.matches_played >= config.matches_per_map.max(1) => (self.current + 1) % maps.len(),
+            None => return None,
+        };
+        self.matches_played = 0;
+        if next == self.current {
+            return None;
+        }
+        self.current = next;
+        Some(next)
+    }
+}
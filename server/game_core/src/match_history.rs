@@ -0,0 +1,237 @@
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use tokio::io::AsyncWriteExt;
+use uuid::Uuid;
+use crate::player::PlayerId;
+
+/// Maximum number of records returned by a single query page
+pub const MAX_PAGE_SIZE: usize = 100;
+
+/// Name that replaces a player's in records after their data is deleted
+pub const DELETED_PLAYER_NAME: &str = "[deleted]";
+
+/// One player's result in a completed match
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MatchParticipant {
+    pub player_id: PlayerId,
+    pub player_name: String,
+    pub score: u64,
+}
+
+/// A completed match
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MatchRecord {
+    pub id: Uuid,
+    /// Game mode name (e.g. "sandbox", "race")
+    pub mode: String,
+    /// Unix timestamps in seconds
+    pub started_at: u64,
+    pub ended_at: u64,
+    /// Participants ordered by final score, highest first
+    pub participants: Vec<MatchParticipant>,
+    pub winner: Option<PlayerId>,
+}
+
+impl MatchRecord {
+    /// Build a record from the players in a finished game, ranking them by score
+    pub fn from_state(state: &crate::GameState, mode: &str, started_at: u64, ended_at: u64) -> Self {
+        let mut participants: Vec<MatchParticipant> = state
+            .players
+            .values()
+            .map(|p| MatchParticipant {
+                player_id: p.id,
+                player_name: p.name.clone(),
+                score: p.score,
+            })
+            .collect();
+        participants.sort_by_key(|p| std::cmp::Reverse(p.score));
+        let winner = participants.first().filter(|p| p.score > 0).map(|p| p.player_id);
+
+        Self {
+            id: Uuid::new_v4(),
+            mode: mode.to_string(),
+            started_at,
+            ended_at,
+            participants,
+            winner,
+        }
+    }
+}
+
+/// Filters for querying match history
+#[derive(Debug, Clone, Default)]
+pub struct MatchQuery {
+    pub player: Option<PlayerId>,
+    pub mode: Option<String>,
+    pub offset: usize,
+    pub limit: usize,
+}
+
+/// One page of query results, newest first
+#[derive(Debug, Clone, Serialize)]
+pub struct MatchPage {
+    pub matches: Vec<MatchRecord>,
+    /// Offset of the next page, None when there are no more results
+    pub next_offset: Option<usize>,
+}
+
+/// Completed match records, optionally persisted to a JSON-lines file
+#[derive(Debug, Default)]
+pub struct MatchHistory {
+    records: Vec<MatchRecord>,
+    path: Option<PathBuf>,
+    /// Lines for the background writer started by the first `record_in_background`
+    appends: Option<tokio::sync::mpsc::UnboundedSender<String>>,
+}
+
+impl MatchHistory {
+    /// In-memory history that is lost on restart
+    pub fn in_memory() -> Self {
+        Self::default()
+    }
+
+    /// Load history from a JSON-lines file, creating it on first write if missing
+    /// Malformed lines are skipped so one bad record can't lose the whole history
+    pub async fn load_async(path: impl Into<PathBuf>) -> Result<Self, Box<dyn std::error::Error>> {
+        let path = path.into();
+        let records = match tokio::fs::read_to_string(&path).await {
+            Ok(contents) => contents
+                .lines()
+                .filter(|line| !line.trim().is_empty())
+                .filter_map(|line| match serde_json::from_str(line) {
+                    Ok(record) => Some(record),
+                    Err(e) => {
+                        eprintln!("⚠️ Skipping malformed match record: {}", e);
+                        None
+                    }
+                })
+                .collect(),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Vec::new(),
+            Err(e) => return Err(e.into()),
+        };
+        Ok(Self {
+            records,
+            path: Some(path),
+            appends: None,
+        })
+    }
+
+    /// Store a completed match, appending it to the history file if one is configured
+    pub async fn record(&mut self, record: MatchRecord) -> Result<(), Box<dyn std::error::Error>> {
+        if let Some(path) = &self.path {
+            let mut line = serde_json::to_string(&record)?;
+            line.push('\n');
+            let mut file = tokio::fs::OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(path)
+                .await?;
+            file.write_all(line.as_bytes()).await?;
+            file.flush().await?;
+        }
+        self.records.push(record);
+        Ok(())
+    }
+
+    /// Store a completed match now and append it to the history file on a background task,
+    /// so callers holding a lock (such as the game loop mid-tick) don't wait on the disk
+    /// Lines are appended in the order their matches were recorded.
+    pub fn record_in_background(&mut self, record: MatchRecord) {
+        if let Some(path) = &self.path {
+            match serde_json::to_string(&record) {
+                Ok(mut line) => {
+                    line.push('\n');
+                    let appends = self.appends.get_or_insert_with(|| append_lines(path.clone()));
+                    let _ = appends.send(line);
+                }
+                Err(e) => eprintln!("❌ Failed to serialize match record: {}", e),
+            }
+        }
+        self.records.push(record);
+    }
+
+    /// Query matches newest first, filtered by participant and mode
+    pub fn query(&self, query: &MatchQuery) -> MatchPage {
+        let limit = query.limit.clamp(1, MAX_PAGE_SIZE);
+        let mut matching = self
+            .records
+            .iter()
+            .rev()
+            .filter(|r| query.mode.as_ref().is_none_or(|mode| &r.mode == mode))
+            .filter(|r| {
+                query
+                    .player
+                    .is_none_or(|player| r.participants.iter().any(|p| p.player_id == player))
+            })
+            .skip(query.offset);
+
+        let matches: Vec<MatchRecord> = matching.by_ref().take(limit).cloned().collect();
+        let next_offset = matching.next().map(|_| query.offset + matches.len());
+        MatchPage { matches, next_offset }
+    }
+
+    /// Every match the player took part in, oldest first
+    pub fn player_matches(&self, player_id: &PlayerId) -> Vec<MatchRecord> {
+        self.records
+            .iter()
+            .filter(|r| r.participants.iter().any(|p| p.player_id == *player_id))
+            .cloned()
+            .collect()
+    }
+
+    /// Strip a player's id and name from every record, keeping the scores so other
+    /// players' results still add up. Rewrites the history file; returns how many
+    /// records changed
+    pub async fn anonymize(&mut self, player_id: &PlayerId) -> Result<usize, Box<dyn std::error::Error>> {
+        let mut changed = 0;
+        for record in &mut self.records {
+            let mut touched = false;
+            for participant in record.participants.iter_mut().filter(|p| p.player_id == *player_id) {
+                participant.player_id = Uuid::nil();
+                participant.player_name = DELETED_PLAYER_NAME.to_string();
+                touched = true;
+            }
+            if record.winner == Some(*player_id) {
+                record.winner = Some(Uuid::nil());
+            }
+            changed += usize::from(touched);
+        }
+        if changed > 0 {
+            if let Some(path) = &self.path {
+                let mut contents = String::new();
+                for record in &self.records {
+                    contents.push_str(&serde_json::to_string(record)?);
+                    contents.push('\n');
+                }
+                crate::json_store::write_atomic(path, contents).await?;
+            }
+        }
+        Ok(changed)
+    }
+
+    pub fn len(&self) -> usize {
+        self.records.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.records.is_empty()
+    }
+}
+
+/// Start a task appending each line sent to it to `path`, one at a time
+fn append_lines(path: PathBuf) -> tokio::sync::mpsc::UnboundedSender<String> {
+    let (sender, mut lines) = tokio::sync::mpsc::unbounded_channel::<String>();
+    tokio::spawn(async move {
+        while let Some(line) = lines.recv().await {
+            let appended = async {
+                let mut file = tokio::fs::OpenOptions::new().create(true).append(true).open(&path).await?;
+                file.write_all(line.as_bytes()).await?;
+                file.flush().await
+            };
+            if let Err(e) = appended.await {
+                eprintln!("❌ Failed to save match record: {}", e);
+            }
+        }
+    });
+    sender
+}
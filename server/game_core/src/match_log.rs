@@ -0,0 +1,548 @@
+//! Compact binary logs of a match: every command accepted, with periodic snapshots
+//!
+//! Where a [`crate::replay::Replay`] samples positions for summaries, a match log keeps each
+//! tick's commands so a match can be studied input by input, and snapshots frequent enough to
+//! play it back to spectators. Saved as `<dir>/<match id>.rlog` next to the replay.
+//!
+//! Layout, integers little-endian:
+//! - `RLOG`, the format version (u8), then the [`MatchLogHeader`] as JSON prefixed by its
+//!   length (u32)
+//! - records to the end of the file, each starting with a tag byte:
+//!   - `N` a player's name from here on: tick (u32), player id (16 bytes), length (u8), UTF-8
+//!   - `C` a command: tick (u32), player id, command tag (u8), then its x and y (f32) for
+//!     commands that carry a position or direction, a key (u8) for held directions, or the
+//!     axis (f32) for analog moves
+//!   - `S` a snapshot: tick (u32), player count (u16), then per player its id, x, y, velocity
+//!     x and y (f32), flags (u8), score (u32) and health (u16)
+//!
+//! Ticks count from the first tick of the match; a command at tick `n` was applied after `n`
+//! steps.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use uuid::Uuid;
+use crate::commands::{HeldKey, PlayerCommand};
+use crate::config::MapGeometry;
+use crate::game_state::GameState;
+use crate::ground_state::GroundState;
+use crate::player::{Player, PlayerId};
+
+const MAGIC: &[u8; 4] = b"RLOG";
+const VERSION: u8 = 1;
+
+/// Why a match log couldn't be read
+#[derive(Debug, Clone, PartialEq)]
+pub enum MatchLogError {
+    NotAMatchLog,
+    UnsupportedVersion(u8),
+    /// The file ends partway through a record
+    Truncated,
+    BadHeader(String),
+    UnknownRecord(u8),
+    UnknownCommand(u8),
+}
+
+impl std::fmt::Display for MatchLogError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MatchLogError::NotAMatchLog => write!(f, "not a match log"),
+            MatchLogError::UnsupportedVersion(version) => write!(f, "unsupported match log version {}", version),
+            MatchLogError::Truncated => write!(f, "match log is truncated"),
+            MatchLogError::BadHeader(e) => write!(f, "bad match log header: {}", e),
+            MatchLogError::UnknownRecord(tag) => write!(f, "unknown match log record {:#04x}", tag),
+            MatchLogError::UnknownCommand(tag) => write!(f, "unknown command {:#04x} in match log", tag),
+        }
+    }
+}
+
+impl std::error::Error for MatchLogError {}
+
+/// What the match was played as and on
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MatchLogHeader {
+    /// Same as the match record's id
+    pub id: Uuid,
+    pub mode: String,
+    pub tick_rate_hz: f32,
+    /// Ticks between snapshots
+    pub snapshot_every_ticks: u64,
+    /// Unix milliseconds the match started at
+    pub started_at_ms: u64,
+    pub geometry: MapGeometry,
+}
+
+/// One player in a snapshot
+#[derive(Debug, Clone, PartialEq)]
+pub struct SnapshotPlayer {
+    pub id: PlayerId,
+    pub x: f32,
+    pub y: f32,
+    pub velocity_x: f32,
+    pub velocity_y: f32,
+    pub facing_right: bool,
+    pub alive: bool,
+    pub crouched: bool,
+    pub ground_state: GroundState,
+    pub score: u32,
+    pub health: u16,
+}
+
+impl SnapshotPlayer {
+    fn of(player: &Player) -> Self {
+        Self {
+            id: player.id,
+            x: player.x,
+            y: player.y,
+            velocity_x: player.velocity_x,
+            velocity_y: player.velocity_y,
+            facing_right: player.facing_right,
+            alive: player.life.is_alive(),
+            crouched: player.crouched,
+            ground_state: player.ground_state,
+            score: player.score.min(u32::MAX as u64) as u32,
+            health: player.health.min(u16::MAX as u32) as u16,
+        }
+    }
+
+    /// Facing, life, crouch and the kind of ground state, packed into one byte
+    /// Platform, ladder and slide details are dropped
+    fn flags(&self) -> u8 {
+        let ground = match self.ground_state {
+            GroundState::Grounded { .. } => 0,
+            GroundState::Sliding { .. } => 1,
+            GroundState::Flying => 2,
+            GroundState::Climbing { .. } => 3,
+        };
+        self.facing_right as u8 | (self.alive as u8) << 1 | (self.crouched as u8) << 2 | ground << 3
+    }
+
+    fn ground_from_flags(flags: u8) -> GroundState {
+        match (flags >> 3) & 0b11 {
+            0 => GroundState::Grounded { platform_id: None },
+            1 => GroundState::Sliding {
+                side: crate::ground_state::SlideSide::Left,
+                platform_id: None,
+            },
+            2 => GroundState::Flying,
+            _ => GroundState::Climbing { ladder_id: 0 },
+        }
+    }
+}
+
+/// One entry of the log, in the order it happened
+#[derive(Debug, Clone, PartialEq)]
+pub enum LogRecord {
+    Name { tick: u32, player_id: PlayerId, name: String },
+    Command { tick: u32, player_id: PlayerId, command: PlayerCommand },
+    Snapshot { tick: u32, players: Vec<SnapshotPlayer> },
+}
+
+/// A player as shown during playback, shaped like a live player in the gameState signal
+#[derive(Debug, Clone, Serialize)]
+pub struct PlaybackPlayer {
+    pub id: PlayerId,
+    pub name: String,
+    pub x: f32,
+    pub y: f32,
+    pub velocity_x: f32,
+    pub velocity_y: f32,
+    pub facing_right: bool,
+    pub ground_state: GroundState,
+    pub score: u64,
+    pub health: u32,
+    pub crouched: bool,
+    pub alive: bool,
+}
+
+/// A snapshot with names filled in, `t` seconds into the match
+#[derive(Debug, Clone, Serialize)]
+pub struct PlaybackFrame {
+    pub tick: u32,
+    pub t: f32,
+    pub players: Vec<PlaybackPlayer>,
+}
+
+#[derive(Debug, Clone)]
+pub struct MatchLog {
+    pub header: MatchLogHeader,
+    pub records: Vec<LogRecord>,
+}
+
+impl MatchLog {
+    /// Where the log of match `id` is stored under `dir`
+    pub fn path(dir: impl AsRef<Path>, id: &Uuid) -> PathBuf {
+        dir.as_ref().join(format!("{}.rlog", id))
+    }
+
+    /// Every command in the log
+    pub fn commands(&self) -> impl Iterator<Item = (u32, &PlayerId, &PlayerCommand)> {
+        self.records.iter().filter_map(|record| match record {
+            LogRecord::Command { tick, player_id, command } => Some((*tick, player_id, command)),
+            _ => None,
+        })
+    }
+
+    /// The snapshots with each player's name at the time, for playback
+    pub fn frames(&self) -> Vec<PlaybackFrame> {
+        let tick_secs = 1.0 / self.header.tick_rate_hz.max(1.0);
+        let mut names: HashMap<PlayerId, &str> = HashMap::new();
+        let mut frames = Vec::new();
+        for record in &self.records {
+            match record {
+                LogRecord::Name { player_id, name, .. } => {
+                    names.insert(*player_id, name);
+                }
+                LogRecord::Command { .. } => {}
+                LogRecord::Snapshot { tick, players } => frames.push(PlaybackFrame {
+                    tick: *tick,
+                    t: *tick as f32 * tick_secs,
+                    players: players
+                        .iter()
+                        .map(|p| PlaybackPlayer {
+                            id: p.id,
+                            name: names.get(&p.id).copied().unwrap_or_default().to_string(),
+                            x: p.x,
+                            y: p.y,
+                            velocity_x: p.velocity_x,
+                            velocity_y: p.velocity_y,
+                            facing_right: p.facing_right,
+                            ground_state: p.ground_state,
+                            score: p.score as u64,
+                            health: p.health as u32,
+                            crouched: p.crouched,
+                            alive: p.alive,
+                        })
+                        .collect(),
+                }),
+            }
+        }
+        frames
+    }
+
+    pub fn duration_secs(&self) -> f32 {
+        let last_tick = self.records.iter().rev().find_map(|record| match record {
+            LogRecord::Snapshot { tick, .. } => Some(*tick),
+            _ => None,
+        });
+        last_tick.unwrap_or(0) as f32 / self.header.tick_rate_hz.max(1.0)
+    }
+
+    pub fn encode(&self) -> Vec<u8> {
+        let mut out = Writer(Vec::new());
+        out.0.extend_from_slice(MAGIC);
+        out.u8(VERSION);
+        let header = serde_json::to_vec(&self.header).expect("match log headers serialize to JSON");
+        out.u32(header.len() as u32);
+        out.0.extend_from_slice(&header);
+        for record in &self.records {
+            match record {
+                LogRecord::Name { tick, player_id, name } => {
+                    out.u8(b'N');
+                    out.u32(*tick);
+                    out.id(player_id);
+                    // Names are short; anything past 255 bytes is cut at a character boundary
+                    let mut end = name.len().min(u8::MAX as usize);
+                    while !name.is_char_boundary(end) {
+                        end -= 1;
+                    }
+                    out.u8(end as u8);
+                    out.0.extend_from_slice(&name.as_bytes()[..end]);
+                }
+                LogRecord::Command { tick, player_id, command } => {
+                    out.u8(b'C');
+                    out.u32(*tick);
+                    out.id(player_id);
+                    out.command(command);
+                }
+                LogRecord::Snapshot { tick, players } => {
+                    out.u8(b'S');
+                    out.u32(*tick);
+                    out.u16(players.len().min(u16::MAX as usize) as u16);
+                    for p in players.iter().take(u16::MAX as usize) {
+                        out.id(&p.id);
+                        for value in [p.x, p.y, p.velocity_x, p.velocity_y] {
+                            out.f32(value);
+                        }
+                        out.u8(p.flags());
+                        out.u32(p.score);
+                        out.u16(p.health);
+                    }
+                }
+            }
+        }
+        out.0
+    }
+
+    pub fn decode(bytes: &[u8]) -> Result<Self, MatchLogError> {
+        let mut input = Reader { bytes, pos: 0 };
+        if input.take(4).ok() != Some(MAGIC.as_slice()) {
+            return Err(MatchLogError::NotAMatchLog);
+        }
+        let version = input.u8()?;
+        if version != VERSION {
+            return Err(MatchLogError::UnsupportedVersion(version));
+        }
+        let header_len = input.u32()? as usize;
+        let header = serde_json::from_slice(input.take(header_len)?)
+            .map_err(|e| MatchLogError::BadHeader(e.to_string()))?;
+        let mut records = Vec::new();
+        while !input.is_empty() {
+            let record = match input.u8()? {
+                b'N' => {
+                    let tick = input.u32()?;
+                    let player_id = input.id()?;
+                    let len = input.u8()? as usize;
+                    let name = String::from_utf8_lossy(input.take(len)?).into_owned();
+                    LogRecord::Name { tick, player_id, name }
+                }
+                b'C' => LogRecord::Command {
+                    tick: input.u32()?,
+                    player_id: input.id()?,
+                    command: input.command()?,
+                },
+                b'S' => {
+                    let tick = input.u32()?;
+                    let count = input.u16()?;
+                    let mut players = Vec::with_capacity(count as usize);
+                    for _ in 0..count {
+                        let id = input.id()?;
+                        let (x, y, velocity_x, velocity_y) = (input.f32()?, input.f32()?, input.f32()?, input.f32()?);
+                        let flags = input.u8()?;
+                        players.push(SnapshotPlayer {
+                            id,
+                            x,
+                            y,
+                            velocity_x,
+                            velocity_y,
+                            facing_right: flags & 1 != 0,
+                            alive: flags & 2 != 0,
+                            crouched: flags & 4 != 0,
+                            ground_state: SnapshotPlayer::ground_from_flags(flags),
+                            score: input.u32()?,
+                            health: input.u16()?,
+                        });
+                    }
+                    LogRecord::Snapshot { tick, players }
+                }
+                tag => return Err(MatchLogError::UnknownRecord(tag)),
+            };
+            records.push(record);
+        }
+        Ok(Self { header, records })
+    }
+
+    pub async fn load(dir: impl AsRef<Path>, id: &Uuid) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        let bytes = tokio::fs::read(Self::path(dir, id)).await?;
+        Ok(Self::decode(&bytes)?)
+    }
+
+    /// Write the log under `dir`, creating it if needed
+    pub async fn save(&self, dir: impl AsRef<Path>) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        tokio::fs::create_dir_all(dir.as_ref()).await?;
+        let path = Self::path(dir, &self.header.id);
+        let temp_path = path.with_extension("rlog.tmp");
+        tokio::fs::write(&temp_path, self.encode()).await?;
+        tokio::fs::rename(&temp_path, &path).await?;
+        Ok(())
+    }
+}
+
+/// The records of the match being logged, kept by [`crate::replay::ReplayRecorder`] next to
+/// its position samples so both share one idea of when the match started
+#[derive(Debug, Clone)]
+pub(crate) struct LogBuilder {
+    pub(crate) snapshot_every_ticks: u64,
+    last_snapshot_tick: Option<u64>,
+    names: HashMap<PlayerId, String>,
+    records: Vec<LogRecord>,
+}
+
+impl LogBuilder {
+    pub(crate) fn new(snapshot_every_ticks: u64) -> Self {
+        Self {
+            snapshot_every_ticks: snapshot_every_ticks.max(1),
+            last_snapshot_tick: None,
+            names: HashMap::new(),
+            records: Vec::new(),
+        }
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.last_snapshot_tick = None;
+        self.names.clear();
+        self.records.clear();
+    }
+
+    pub(crate) fn command(&mut self, tick: u32, player_id: PlayerId, command: PlayerCommand) {
+        self.records.push(LogRecord::Command { tick, player_id, command });
+    }
+
+    /// Snapshot the players if one is due, naming anyone new or renamed first
+    pub(crate) fn observe(&mut self, state: &GameState, tick: u32) {
+        if self
+            .last_snapshot_tick
+            .is_some_and(|last| state.tick - last < self.snapshot_every_ticks)
+        {
+            return;
+        }
+        self.last_snapshot_tick = Some(state.tick);
+        let mut players = Vec::with_capacity(state.players.len());
+        for player_id in state.player_ids() {
+            let player = &state.players[&player_id];
+            if self.names.get(&player_id) != Some(&player.name) {
+                self.names.insert(player_id, player.name.clone());
+                self.records.push(LogRecord::Name { tick, player_id, name: player.name.clone() });
+            }
+            players.push(SnapshotPlayer::of(player));
+        }
+        self.records.push(LogRecord::Snapshot { tick, players });
+    }
+
+    pub(crate) fn finish(&mut self, header: MatchLogHeader) -> MatchLog {
+        self.names.clear();
+        self.last_snapshot_tick = None;
+        MatchLog {
+            header,
+            records: std::mem::take(&mut self.records),
+        }
+    }
+}
+
+struct Writer(Vec<u8>);
+
+impl Writer {
+    fn u8(&mut self, value: u8) {
+        self.0.push(value);
+    }
+
+    fn u16(&mut self, value: u16) {
+        self.0.extend_from_slice(&value.to_le_bytes());
+    }
+
+    fn u32(&mut self, value: u32) {
+        self.0.extend_from_slice(&value.to_le_bytes());
+    }
+
+    fn f32(&mut self, value: f32) {
+        self.0.extend_from_slice(&value.to_le_bytes());
+    }
+
+    fn id(&mut self, id: &Uuid) {
+        self.0.extend_from_slice(id.as_bytes());
+    }
+
+    fn command(&mut self, command: &PlayerCommand) {
+        use PlayerCommand::*;
+        let (tag, position) = match command {
+            MoveLeft => (0, None),
+            MoveRight => (1, None),
+            Jump => (2, None),
+            Stop => (3, None),
+            MoveUp => (4, None),
+            MoveDown => (5, None),
+            Crouch => (6, None),
+            StandUp => (7, None),
+            PlaceBlock { x, y } => (8, Some((*x, *y))),
+            RemoveBlock { x, y } => (9, Some((*x, *y))),
+            Shoot { dir_x, dir_y } => (10, Some((*dir_x, *dir_y))),
+            Sprint => (11, None),
+            StopSprint => (12, None),
+            Dash => (13, None),
+            KeyDown { key } => return self.held_key(14, *key),
+            KeyUp { key } => return self.held_key(15, *key),
+            Move { axis } => {
+                self.u8(16);
+                self.f32(*axis);
+                return;
+            }
+        };
+        self.u8(tag);
+        if let Some((x, y)) = position {
+            self.f32(x);
+            self.f32(y);
+        }
+    }
+
+    fn held_key(&mut self, tag: u8, key: HeldKey) {
+        self.u8(tag);
+        self.u8(match key {
+            HeldKey::Left => 0,
+            HeldKey::Right => 1,
+        });
+    }
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn is_empty(&self) -> bool {
+        self.pos >= self.bytes.len()
+    }
+
+    fn take(&mut self, len: usize) -> Result<&'a [u8], MatchLogError> {
+        let end = self.pos.checked_add(len).filter(|end| *end <= self.bytes.len()).ok_or(MatchLogError::Truncated)?;
+        let slice = &self.bytes[self.pos..end];
+        self.pos = end;
+        Ok(slice)
+    }
+
+    fn array<const N: usize>(&mut self) -> Result<[u8; N], MatchLogError> {
+        Ok(self.take(N)?.try_into().expect("take returns exactly N bytes"))
+    }
+
+    fn u8(&mut self) -> Result<u8, MatchLogError> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn u16(&mut self) -> Result<u16, MatchLogError> {
+        Ok(u16::from_le_bytes(self.array()?))
+    }
+
+    fn u32(&mut self) -> Result<u32, MatchLogError> {
+        Ok(u32::from_le_bytes(self.array()?))
+    }
+
+    fn f32(&mut self) -> Result<f32, MatchLogError> {
+        Ok(f32::from_le_bytes(self.array()?))
+    }
+
+    fn id(&mut self) -> Result<Uuid, MatchLogError> {
+        Ok(Uuid::from_bytes(self.array()?))
+    }
+
+    fn command(&mut self) -> Result<PlayerCommand, MatchLogError> {
+        use PlayerCommand::*;
+        Ok(match self.u8()? {
+            0 => MoveLeft,
+            1 => MoveRight,
+            2 => Jump,
+            3 => Stop,
+            4 => MoveUp,
+            5 => MoveDown,
+            6 => Crouch,
+            7 => StandUp,
+            8 => PlaceBlock { x: self.f32()?, y: self.f32()? },
+            9 => RemoveBlock { x: self.f32()?, y: self.f32()? },
+            10 => Shoot { dir_x: self.f32()?, dir_y: self.f32()? },
+            11 => Sprint,
+            12 => StopSprint,
+            13 => Dash,
+            14 => KeyDown { key: self.held_key()? },
+            15 => KeyUp { key: self.held_key()? },
+            16 => Move { axis: self.f32()? },
+            tag => return Err(MatchLogError::UnknownCommand(tag)),
+        })
+    }
+
+    fn held_key(&mut self) -> Result<HeldKey, MatchLogError> {
+        match self.u8()? {
+            0 => Ok(HeldKey::Left),
+            1 => Ok(HeldKey::Right),
+            tag => Err(MatchLogError::UnknownCommand(tag)),
+        }
+    }
+}
@@ -0,0 +1,80 @@
+use serde::{Deserialize, Serialize};
+use crate::config::TeamsConfig;
+use crate::match_history::{MatchParticipant, MatchRecord};
+use crate::player::{Player, PlayerId};
+use crate::teams::TeamId;
+use std::collections::HashMap;
+
+/// Phase of the current match; times are Unix milliseconds by the game clock
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "phase", rename_all = "snake_case")]
+pub enum MatchState {
+    /// Waiting for enough players to start
+    Lobby,
+    /// The match starts at `starts_at_ms` unless players leave first
+    Countdown { starts_at_ms: u64 },
+    Playing { started_at_ms: u64, ends_at_ms: u64 },
+    /// Results are shown until `lobby_at_ms`, then the next match can start
+    Ended { result: MatchResult, lobby_at_ms: u64 },
+}
+
+impl MatchState {
+    /// The same phase with every deadline moved `ms` later, e.g. to resume after a pause;
+    /// `None` if a deadline would move past the largest time there is
+    pub fn delayed_by(self, ms: u64) -> Option<Self> {
+        Some(match self {
+            MatchState::Lobby => MatchState::Lobby,
+            MatchState::Countdown { starts_at_ms } => MatchState::Countdown { starts_at_ms: starts_at_ms.checked_add(ms)? },
+            MatchState::Playing { started_at_ms, ends_at_ms } => MatchState::Playing {
+                started_at_ms: started_at_ms.checked_add(ms)?,
+                ends_at_ms: ends_at_ms.checked_add(ms)?,
+            },
+            MatchState::Ended { result, lobby_at_ms } => MatchState::Ended { result, lobby_at_ms: lobby_at_ms.checked_add(ms)? },
+        })
+    }
+}
+
+/// Final standings of a finished match
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MatchResult {
+    /// Top scorer, unless nobody scored or the top score is tied
+    pub winner: Option<PlayerId>,
+    /// Team with the highest combined score, unless tied or teams are disabled
+    pub winning_team: Option<TeamId>,
+    /// Participants ordered by final score, highest first
+    pub standings: Vec<MatchParticipant>,
+}
+
+impl MatchResult {
+    pub fn from_record(record: &MatchRecord, players: &HashMap<PlayerId, Player>, teams: &TeamsConfig) -> Self {
+        let top_score = record.participants.first().map(|p| p.score).unwrap_or(0);
+        let tied = record.participants.iter().filter(|p| p.score == top_score).count() > 1;
+        let winner = record.winner.filter(|_| !tied);
+
+        let mut team_scores: Vec<(TeamId, u64)> = teams
+            .teams
+            .iter()
+            .map(|team| {
+                let total = players
+                    .values()
+                    .filter(|p| p.team.as_deref() == Some(team.id.as_str()))
+                    .map(|p| p.score)
+                    .sum();
+                (team.id.clone(), total)
+            })
+            .collect();
+        team_scores.sort_by_key(|(_, total)| std::cmp::Reverse(*total));
+        let winning_team = match team_scores.as_slice() {
+            [(team, best), rest @ ..] if *best > 0 && rest.first().is_none_or(|(_, next)| next < best) => {
+                Some(team.clone())
+            }
+            _ => None,
+        };
+
+        Self {
+            winner,
+            winning_team,
+            standings: record.participants.clone(),
+        }
+    }
+}
@@ -0,0 +1,173 @@
+use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use crate::config::GameConfig;
+use crate::player::PlayerId;
+
+/// A rule change played for a whole round
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Mutator {
+    /// Gravity scaled down, for floaty jumps
+    LowGravity,
+    /// Faster acceleration and top speed
+    DoubleSpeed,
+    /// Every hit kills
+    OneHitKo,
+    /// Players' heads drawn bigger; purely cosmetic
+    BigHeads,
+}
+
+impl Mutator {
+    pub const ALL: [Mutator; 4] = [Mutator::LowGravity, Mutator::DoubleSpeed, Mutator::OneHitKo, Mutator::BigHeads];
+
+    pub fn id(&self) -> &'static str {
+        match self {
+            Mutator::LowGravity => "low_gravity",
+            Mutator::DoubleSpeed => "double_speed",
+            Mutator::OneHitKo => "one_hit_ko",
+            Mutator::BigHeads => "big_heads",
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Mutator::LowGravity => "Low Gravity",
+            Mutator::DoubleSpeed => "Double Speed",
+            Mutator::OneHitKo => "One-Hit KO",
+            Mutator::BigHeads => "Big Heads",
+        }
+    }
+
+    /// Look a mutator up by id or label, ignoring case
+    pub fn parse(name: &str) -> Option<Self> {
+        let name = name.trim();
+        Self::ALL
+            .into_iter()
+            .find(|m| m.id().eq_ignore_ascii_case(name) || m.label().eq_ignore_ascii_case(name))
+    }
+}
+
+/// Why a mutator vote or pick was refused
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MutatorError {
+    /// Voting for mutators is turned off
+    Disabled,
+    UnknownMutator,
+    UnknownPlayer,
+}
+
+impl std::fmt::Display for MutatorError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MutatorError::Disabled => write!(f, "mutator voting is disabled"),
+            MutatorError::UnknownMutator => write!(f, "no such mutator"),
+            MutatorError::UnknownPlayer => write!(f, "player is not in the game"),
+        }
+    }
+}
+
+impl std::error::Error for MutatorError {}
+
+/// A mutator and the votes it has for the next round
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct MutatorVotes {
+    pub id: Mutator,
+    pub name: String,
+    pub votes: usize,
+}
+
+/// Votes and admin picks for the mutators of the next round
+#[derive(Debug, Clone, Default)]
+pub struct MutatorBallot {
+    votes: HashMap<PlayerId, Mutator>,
+    /// Chosen by an admin; overrides the votes
+    picked: Option<Vec<Mutator>>,
+}
+
+impl MutatorBallot {
+    /// Record a player's vote, replacing any earlier vote
+    pub fn vote(&mut self, player_id: PlayerId, mutator: Mutator) {
+        self.votes.insert(player_id, mutator);
+    }
+
+    /// Drop the vote of a player who left
+    pub fn forget(&mut self, player_id: &PlayerId) {
+        self.votes.remove(player_id);
+    }
+
+    /// Play exactly these mutators next round, whatever the votes say
+    pub fn pick(&mut self, mutators: Vec<Mutator>) {
+        self.picked = Some(mutators);
+    }
+
+    /// Mutators an admin picked for the next round, if any
+    pub fn picked(&self) -> Option<&[Mutator]> {
+        self.picked.as_deref()
+    }
+
+    /// Every mutator with its current vote count
+    pub fn tally(&self) -> Vec<MutatorVotes> {
+        Mutator::ALL
+            .into_iter()
+            .map(|mutator| MutatorVotes {
+                id: mutator,
+                name: mutator.label().to_string(),
+                votes: self.votes.values().filter(|v| **v == mutator).count(),
+            })
+            .collect()
+    }
+
+    /// Called at round start: the admin's pick, or else the most voted mutator, ties going
+    /// to whichever is listed first. Clears the ballot for the round after
+    pub fn take_next(&mut self) -> Vec<Mutator> {
+        let tally = self.tally();
+        self.votes.clear();
+        if let Some(picked) = self.picked.take() {
+            return picked;
+        }
+        tally
+            .iter()
+            .filter(|t| t.votes > 0)
+            .fold(None, |best: Option<&MutatorVotes>, t| match best {
+                Some(best) if best.votes >= t.votes => Some(best),
+                _ => Some(t),
+            })
+            .map(|t| vec![t.id])
+            .unwrap_or_default()
+    }
+}
+
+/// Lay mutators over a config; settings they don't touch are left alone
+pub fn apply(config: &mut GameConfig, mutators: &[Mutator]) {
+    let settings = config.mutators.clone();
+    for mutator in dedup(mutators) {
+        match mutator {
+            Mutator::LowGravity => config.physics.gravity *= settings.low_gravity_scale,
+            Mutator::DoubleSpeed => {
+                config.physics.move_acceleration *= settings.speed_multiplier;
+                config.physics.max_horizontal_velocity *= settings.speed_multiplier;
+            }
+            Mutator::OneHitKo => config.health.max_health = 1,
+            Mutator::BigHeads => {}
+        }
+    }
+}
+
+/// Undo [`apply`] by copying back every setting a mutator can change from `original`
+pub fn restore(config: &mut GameConfig, original: &GameConfig) {
+    config.physics.gravity = original.physics.gravity;
+    config.physics.move_acceleration = original.physics.move_acceleration;
+    config.physics.max_horizontal_velocity = original.physics.max_horizontal_velocity;
+    config.health.max_health = original.health.max_health;
+}
+
+/// Each mutator once, in the order first listed
+pub fn dedup(mutators: &[Mutator]) -> Vec<Mutator> {
+    let mut unique = Vec::new();
+    for mutator in mutators {
+        if !unique.contains(mutator) {
+            unique.push(*mutator);
+        }
+    }
+    unique
+}
@@ -0,0 +1,129 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+use crate::json_store::JsonStore;
+use crate::names::normalize_for_filter;
+use crate::player::PlayerId;
+
+/// Why a name can't be reserved
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReservationError {
+    /// Another player reserved this name, or one that reads the same
+    Reserved,
+    /// Too little of the name is letters or digits to tell it apart from others
+    Indistinct,
+    /// The configured number of reservations is reached
+    Full,
+}
+
+impl std::fmt::Display for ReservationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ReservationError::Reserved => write!(f, "name is reserved by another player"),
+            ReservationError::Indistinct => write!(f, "name needs more letters or digits to be reserved"),
+            ReservationError::Full => write!(f, "no more names can be reserved"),
+        }
+    }
+}
+
+impl std::error::Error for ReservationError {}
+
+/// Display names registered players keep for themselves, one per player
+///
+/// Names match ignoring case, punctuation and leetspeak, so "Ada", "ADA_" and "4da" are all
+/// the same reservation. Optionally persisted to a JSON file so they survive restarts.
+#[derive(Debug, Default)]
+pub struct NameReservations {
+    names: JsonStore<HashMap<PlayerId, String>>,
+}
+
+impl NameReservations {
+    /// In-memory reservations that are lost on restart
+    pub fn in_memory() -> Self {
+        Self::default()
+    }
+
+    /// Load reservations from a JSON file, creating it on first write if missing
+    pub async fn load_async(path: impl Into<PathBuf>) -> Result<Self, Box<dyn std::error::Error>> {
+        Ok(Self {
+            names: JsonStore::load(path).await?,
+        })
+    }
+
+    /// The player who reserved `name` or a name that reads the same
+    pub fn owner(&self, name: &str) -> Option<PlayerId> {
+        let key = normalize_for_filter(name);
+        if key.is_empty() {
+            return None;
+        }
+        self.names
+            .get()
+            .iter()
+            .find(|(_, reserved)| normalize_for_filter(reserved) == key)
+            .map(|(player_id, _)| *player_id)
+    }
+
+    /// Whether `name` is reserved by someone other than `player_id`
+    pub fn reserved_for_other(&self, name: &str, player_id: &PlayerId) -> bool {
+        self.owner(name).is_some_and(|owner| owner != *player_id)
+    }
+
+    /// The name a player reserved
+    pub fn get(&self, player_id: &PlayerId) -> Option<&str> {
+        self.names.get().get(player_id).map(String::as_str)
+    }
+
+    /// `name` if `player_id` may use it, otherwise the first of "name2", "name3", ... that is
+    /// neither reserved by another player nor `taken`, shortened to fit `max_length`
+    pub fn usable_name(&self, player_id: &PlayerId, name: &str, max_length: usize, taken: impl Fn(&str) -> bool) -> String {
+        if !self.reserved_for_other(name, player_id) {
+            return name.to_string();
+        }
+        (2u32..)
+            .map(|n| {
+                let suffix = n.to_string();
+                let base: String = name.chars().take(max_length.saturating_sub(suffix.len())).collect();
+                format!("{}{}", base.trim_end(), suffix)
+            })
+            .find(|candidate| !self.reserved_for_other(candidate, player_id) && !taken(candidate))
+            .unwrap_or_else(|| name.to_string())
+    }
+
+    /// Whether a player may reserve `name` while at most `max_reservations` are kept
+    /// Replacing a player's own reservation doesn't count against the limit
+    pub fn check(&self, player_id: &PlayerId, name: &str, max_reservations: usize) -> Result<(), ReservationError> {
+        if normalize_for_filter(name).is_empty() {
+            return Err(ReservationError::Indistinct);
+        }
+        if self.reserved_for_other(name, player_id) {
+            return Err(ReservationError::Reserved);
+        }
+        if self.get(player_id).is_none() && self.len() >= max_reservations {
+            return Err(ReservationError::Full);
+        }
+        Ok(())
+    }
+
+    /// Reserve `name` for a player, replacing any name they reserved before, and rewrite the
+    /// reservations file if one is configured. Call `check` first.
+    pub async fn reserve(&mut self, player_id: PlayerId, name: &str) -> Result<(), Box<dyn std::error::Error>> {
+        self.names.get_mut().insert(player_id, name.to_string());
+        self.names.save().await
+    }
+
+    /// Release a player's reservation, rewriting the reservations file; false if they had none
+    pub async fn remove(&mut self, player_id: &PlayerId) -> Result<bool, Box<dyn std::error::Error>> {
+        if self.names.get_mut().remove(player_id).is_none() {
+            return Ok(false);
+        }
+        self.names.save().await?;
+        Ok(true)
+    }
+
+    pub fn len(&self) -> usize {
+        self.names.get().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.names.get().is_empty()
+    }
+}
@@ -0,0 +1,124 @@
+use std::fmt;
+use serde::{Deserialize, Serialize};
+use crate::config::NameConfig;
+
+/// Reasons a requested display name is rejected
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum NameError {
+    TooShort,
+    TooLong,
+    /// Contains characters other than letters, digits, spaces, '_' or '-'
+    InvalidCharacters,
+    /// Matches the blocked word list
+    Blocked,
+    /// Another player in the room already uses this name
+    Taken,
+    /// Player renamed too recently
+    Cooldown,
+    /// Player is not in the game
+    UnknownPlayer,
+}
+
+impl fmt::Display for NameError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let reason = match self {
+            NameError::TooShort => "name is too short",
+            NameError::TooLong => "name is too long",
+            NameError::InvalidCharacters => "name contains invalid characters",
+            NameError::Blocked => "name is not allowed",
+            NameError::Taken => "name is already taken",
+            NameError::Cooldown => "renamed too recently",
+            NameError::UnknownPlayer => "player is not in the game",
+        };
+        write!(f, "{}", reason)
+    }
+}
+
+impl std::error::Error for NameError {}
+
+/// Lowercase and undo common letter substitutions so "B4dW0rd" matches "badword"
+pub fn normalize_for_filter(text: &str) -> String {
+    text.chars()
+        .filter_map(|c| {
+            let c = match c.to_ascii_lowercase() {
+                '0' => 'o',
+                '1' | '!' => 'i',
+                '3' => 'e',
+                '4' | '@' => 'a',
+                '5' | '$' => 's',
+                '7' => 't',
+                c => c,
+            };
+            c.is_ascii_alphanumeric().then_some(c)
+        })
+        .collect()
+}
+
+/// Split text into normalized words, so filters match whole words and never across spaces
+/// ("what is hit" is three words, not "whatishit"); '!', '@' and '$' stay inside a word as
+/// leetspeak but a trailing '!' is punctuation
+fn filter_words(text: &str) -> Vec<String> {
+    text.split(|c: char| !(c.is_alphanumeric() || matches!(c, '!' | '@' | '$')))
+        .map(|word| normalize_for_filter(word.trim_end_matches('!')))
+        .filter(|word| !word.is_empty())
+        .collect()
+}
+
+/// Whether `words` contains the blocked entry as a run of consecutive words, with each word
+/// either equal to or, for `within_words`, containing the blocked one
+fn contains_entry(words: &[String], blocked: &[String], within_words: bool) -> bool {
+    if blocked.is_empty() || blocked.len() > words.len() {
+        return false;
+    }
+    words.windows(blocked.len()).any(|run| {
+        run.iter().zip(blocked).all(|(word, blocked)| {
+            if within_words && run.len() == 1 {
+                word.contains(blocked.as_str())
+            } else {
+                word == blocked
+            }
+        })
+    })
+}
+
+/// Whether a display name contains any blocked word, ignoring case and leetspeak
+/// Names are short and run words together ("xXAdminXx"), so a blocked word matches
+/// inside a word but never across the spaces between words
+pub fn contains_blocked_word(text: &str, blocked_words: &[String]) -> bool {
+    let words = filter_words(text);
+    blocked_words
+        .iter()
+        .any(|blocked| contains_entry(&words, &filter_words(blocked), true))
+}
+
+/// Whether a chat message uses any blocked word as a whole word, ignoring case and
+/// leetspeak, so "what is hit" and "class" pass
+pub fn contains_blocked_chat_word(text: &str, blocked_words: &[String]) -> bool {
+    let words = filter_words(text);
+    blocked_words
+        .iter()
+        .any(|blocked| contains_entry(&words, &filter_words(blocked), false))
+}
+
+/// Validate a requested display name, returning it trimmed
+/// Uniqueness and cooldown depend on room state and are checked by GameState
+pub fn validate_name(name: &str, config: &NameConfig) -> Result<String, NameError> {
+    let name = name.trim();
+    let len = name.chars().count();
+    if len < config.min_length {
+        return Err(NameError::TooShort);
+    }
+    if len > config.max_length {
+        return Err(NameError::TooLong);
+    }
+    if !name
+        .chars()
+        .all(|c| c.is_alphanumeric() || c == ' ' || c == '_' || c == '-')
+    {
+        return Err(NameError::InvalidCharacters);
+    }
+    if contains_blocked_word(name, &config.blocked_words) {
+        return Err(NameError::Blocked);
+    }
+    Ok(name.to_string())
+}
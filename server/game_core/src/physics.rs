@@ -1,469 +1,982 @@
-use crate::player::Player;
-use crate::config::{GameConfig, PlatformConfig, WallConfig};
+use serde::{Deserialize, Serialize};
+use crate::player::{Player, PlayerId};
+use crate::commands::{CommandOutcome, IgnoredReason};
+use crate::collision::{BakedGeometry, GroundColumn, Solid, Span};
+use crate::config::{GameConfig, PlatformConfig, PortalConfig, Surface, WallConfig};
 use crate::ground_state::GroundState;
 use std::sync::Arc;
-use std::sync::RwLock;
 
-// Global game configuration (loaded from JSON)
-static GAME_CONFIG: RwLock<Option<Arc<GameConfig>>> = RwLock::new(None);
+/// Distance beyond a player's reach this step that collision still looks at
+const NEARBY_MARGIN: f32 = 0.1;
 
-/// Initialize physics system with game configuration
-pub fn init(config: Arc<GameConfig>) {
-    let mut global_config = GAME_CONFIG.write().unwrap();
-    *global_config = Some(config);
+/// A player thrown back up by a springy surface, broadcast so clients can play effects
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BounceEvent {
+    pub player_id: PlayerId,
+    pub surface: Surface,
+    /// Upward speed the player left the surface at
+    pub speed: f32,
+    /// Where the player bounced
+    pub x: f32,
+    pub y: f32,
 }
 
+/// A player sent through a portal, broadcast so clients can play effects at both ends
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TeleportEvent {
+    pub player_id: PlayerId,
+    /// Portal the player entered
+    pub portal: String,
+    /// Portal they came out of
+    pub exit: String,
+    /// Where they left from
+    pub from_x: f32,
+    pub from_y: f32,
+    /// Where they arrived
+    pub x: f32,
+    pub y: f32,
+}
 
-/// Get all platforms from configuration
-pub fn get_platforms() -> Vec<PlatformConfig> {
-    get_config().platforms.clone()
+/// A physics world owning the game configuration and static geometry
+/// Each world is independent, so several rooms (or tests) can run side by side
+#[derive(Debug, Clone)]
+pub struct PhysicsWorld {
+    config: Arc<GameConfig>,
+    /// The configured platforms and walls, baked for collision
+    geometry: Arc<BakedGeometry>,
 }
 
-/// Get all walls from configuration
-pub fn get_walls() -> Vec<WallConfig> {
-    get_config().walls.clone()
+/// A platform a player could touch this step
+struct NearbyPlatform<'a> {
+    solid: Solid,
+    owner: PlatformOwner<'a>,
 }
 
-/// Get the current game configuration (public for player initialization)
-pub fn get_config() -> Arc<GameConfig> {
-    let config = GAME_CONFIG.read().unwrap();
-    config.clone().unwrap_or_else(|| Arc::new(GameConfig::default()))
+enum PlatformOwner<'a> {
+    /// Part of the baked map
+    Static(&'a Span),
+    /// A runtime platform, such as a built block, by its index among all platforms
+    Runtime(u32, &'a PlatformConfig),
 }
 
-pub fn update_player_physics(player: &mut Player, delta_time: f32) {
-    let config = get_config();
-    
-    // Apply gravity only if flying (not when sliding)
-    if player.ground_state.is_flying() {
-        player.velocity_y += config.physics.gravity * delta_time;
-    }
-    
-    // Apply horizontal friction based on ground state
-    // Friction should be much weaker to allow smooth movement
-    // It only slows down movement, doesn't completely stop it immediately
-    match player.ground_state {
-        GroundState::Grounded { platform_id: _ } => {
-            // Normal deceleration when grounded - apply very gentle friction
-            // Friction is applied every frame (60fps), but commands come every 100ms
-            // So we need friction to be extremely weak to allow movement
-            // Use a tiny fraction of deceleration to allow smooth movement
-            let friction = (config.physics.move_deceleration * 0.01) * delta_time; // Reduce friction by 99%
-            if player.velocity_x.abs() > 0.01 { // Only apply friction if velocity is significant
-                if player.velocity_x > 0.0 {
-                    player.velocity_x = (player.velocity_x - friction).max(0.0);
-                } else if player.velocity_x < 0.0 {
-                    player.velocity_x = (player.velocity_x + friction).min(0.0);
-                }
-            } else {
-                // If velocity is very small, just stop it
-                player.velocity_x = 0.0;
-            }
-        }
-        GroundState::Sliding { platform_id, .. } => {
-            // Apply sliding friction (different for ground vs platform)
-            // Sliding friction should be very weak to allow smooth sliding
-            let slide_friction = if platform_id.is_some() {
-                config.physics.platform_slide_friction
-            } else {
-                config.physics.ground_slide_friction
-            } * 0.01 * delta_time; // Reduce sliding friction by 99%
-            
-            if player.velocity_x.abs() > 0.01 { // Only apply friction if velocity is significant
-                if player.velocity_x > 0.0 {
-                    player.velocity_x = (player.velocity_x - slide_friction).max(0.0);
-                } else if player.velocity_x < 0.0 {
-                    player.velocity_x = (player.velocity_x + slide_friction).min(0.0);
-                }
-            } else {
-                // If velocity is very small, just stop it
-                player.velocity_x = 0.0;
-            }
-        }
-        GroundState::Flying => {
-            // No friction when flying
+impl NearbyPlatform<'_> {
+    /// Index of the platform under the x range `left..right`, for ground state
+    fn id(&self, left: f32, right: f32) -> u32 {
+        match self.owner {
+            PlatformOwner::Static(span) => span.platform_at(left, right),
+            PlatformOwner::Runtime(idx, _) => idx,
         }
     }
-    
-    // Clamp velocities
-    clamp_velocities(player);
-    
-    // Update position with continuous collision detection
-    // This prevents players from moving through platforms
-    update_position_with_collision(player, delta_time);
-}
 
-fn clamp_velocities(player: &mut Player) {
-    let config = get_config();
-    if player.velocity_x > config.physics.max_horizontal_velocity {
-        player.velocity_x = config.physics.max_horizontal_velocity;
-    } else if player.velocity_x < -config.physics.max_horizontal_velocity {
-        player.velocity_x = -config.physics.max_horizontal_velocity;
+    /// Surface and restitution of the platform under the x range `left..right`
+    fn footing(&self, left: f32, right: f32, config: &GameConfig) -> (Surface, f32) {
+        let platform = match self.owner {
+            PlatformOwner::Static(span) => config.platforms.get(span.platform_at(left, right) as usize),
+            PlatformOwner::Runtime(_, platform) => Some(platform),
+        };
+        platform.map_or((Surface::Normal, 0.0), |platform| {
+            (platform.surface, platform.restitution(&config.physics.surfaces))
+        })
     }
 }
 
-/// Update player position with continuous collision detection
-/// This prevents players from moving through platforms by checking collisions
-/// at multiple points along the movement path
-fn update_position_with_collision(player: &mut Player, delta_time: f32) {
-    let config = get_config();
-    let player_width = config.physics.player_width;
-    let player_height = config.physics.player_height;
-    
-    // Calculate movement
-    let dx = player.velocity_x * delta_time;
-    let dy = player.velocity_y * delta_time;
-    
-    // Move horizontally first with collision detection
-    player.x += dx;
-    
-    // Check horizontal collision with platforms
-    check_horizontal_collision(player, player_width, player_height);
-    
-    // Move vertically with continuous collision detection
-    // Use multiple steps to prevent passing through thin platforms
-    let steps = (dy.abs() / (player_height * 0.5)).max(1.0) as i32;
-    let step_size = dy / steps as f32;
-    
-    for _ in 0..steps {
-        let old_y = player.y;
-        player.y += step_size;
-        
-        // Check vertical collision
-        if check_vertical_collision(player, player_width, player_height) {
-            // Collision detected, revert to previous position
-            player.y = old_y;
-            break;
+/// Everything a player could touch this step
+struct Nearby<'a> {
+    platforms: Vec<NearbyPlatform<'a>>,
+    walls: Vec<Solid>,
+    ground: &'a [GroundColumn],
+}
+
+impl PhysicsWorld {
+    /// Create a physics world from a game configuration, baking its static geometry
+    pub fn new(config: Arc<GameConfig>) -> Self {
+        let geometry = Arc::new(
+            BakedGeometry::bake(&config.platforms, &config.walls)
+                .with_ground(&config.physics),
+        );
+        Self { config, geometry }
+    }
+
+    /// The static platforms, walls and ground as collision sees them
+    pub fn geometry(&self) -> &BakedGeometry {
+        &self.geometry
+    }
+
+    /// Static and runtime shapes overlapping the x range `left..right`
+    /// Runtime platforms are numbered after the configured ones
+    fn nearby<'a>(&'a self, left: f32, right: f32, blocks: &'a [PlatformConfig]) -> Nearby<'a> {
+        let mut platforms: Vec<NearbyPlatform<'a>> = self
+            .geometry
+            .platforms_near(left, right)
+            .map(|span| NearbyPlatform { solid: span.solid, owner: PlatformOwner::Static(span) })
+            .collect();
+        let first_runtime = self.geometry.platform_count();
+        platforms.extend(blocks.iter().enumerate().filter_map(|(i, block)| {
+            let solid = Solid {
+                left: block.x_start,
+                right: block.x_end,
+                bottom: block.y_top - block.height,
+                top: block.y_top,
+            };
+            solid.overlaps_x(left, right).then_some(NearbyPlatform {
+                solid,
+                owner: PlatformOwner::Runtime((first_runtime + i) as u32, block),
+            })
+        }));
+        Nearby {
+            platforms,
+            walls: self.geometry.walls_near(left, right).copied().collect(),
+            ground: self.geometry.ground_near(left, right),
         }
     }
-    
-    // Final collision check to ensure we're not penetrating anything
-    resolve_collisions(player, player_width, player_height);
-}
 
-/// Check horizontal collision with platforms and walls
-fn check_horizontal_collision(player: &mut Player, player_width: f32, player_height: f32) {
-    let platforms = get_platforms();
-    let walls = get_walls();
-    let player_left = player.x - player_width / 2.0;
-    let player_right = player.x + player_width / 2.0;
-    let player_bottom = player.y - player_height / 2.0;
-    let player_top = player.y + player_height / 2.0;
-    
-    // Check platform collisions
-    for platform in &platforms {
-        let platform_left = platform.x_start;
-        let platform_right = platform.x_end;
-        let platform_bottom = platform.y_top - platform.height;
-        let platform_top = platform.y_top;
+    /// The configuration this world was built from
+    pub fn config(&self) -> &Arc<GameConfig> {
+        &self.config
+    }
+
+    /// Static platforms from configuration
+    pub fn platforms(&self) -> &[PlatformConfig] {
+        &self.config.platforms
+    }
+
+    /// Static walls from configuration
+    pub fn walls(&self) -> &[WallConfig] {
+        &self.config.walls
+    }
+
+    /// Step a single player's physics
+    /// `blocks` holds the runtime platforms (built blocks) colliding alongside the baked map;
+    /// in ground state they are numbered after the configured platforms
+    /// Returns whether a buffered jump went off; a bounce off a springy surface is left in
+    /// `player.bounced`
+    pub fn update_player_physics(&self, player: &mut Player, delta_time: f32, blocks: &[PlatformConfig]) -> bool {
+        let config = &self.config;
+        player.bounced = None;
+        let climbing = player.ground_state.is_climbing();
         
-        // Check if player overlaps with platform horizontally
-        let horizontal_overlap = player_right > platform_left && player_left < platform_right;
-        let vertical_overlap = player_top > platform_bottom && player_bottom < platform_top;
+        // Crouched players who asked to stand do so once nothing is overhead
+        if player.crouched && player.wants_to_stand && self.has_headroom(player, blocks) {
+            player.y += (config.physics.player_height - player.height(&config.physics)) / 2.0;
+            player.crouched = false;
+            player.wants_to_stand = false;
+            player.slide_secs = 0.0;
+        }
+        let sliding = player.slide_secs > 0.0 || player.dash_secs > 0.0;
+        player.slide_secs = (player.slide_secs - delta_time).max(0.0);
+        self.update_stamina(player, delta_time);
+        player.dash_secs = (player.dash_secs - delta_time).max(0.0);
+        let jumped = self.release_buffered_jump(player, delta_time);
+        self.apply_held_input(player, delta_time);
         
-        if horizontal_overlap && vertical_overlap {
-            // Collision detected - properly reset position based on boundary
-            if player.velocity_x > 0.0 {
-                // Moving right, push player to exactly at the left boundary of platform
-                player.x = platform_left - player_width / 2.0 - 0.001; // Small epsilon to prevent overlap
-                player.velocity_x = 0.0;
-            } else if player.velocity_x < 0.0 {
-                // Moving left, push player to exactly at the right boundary of platform
-                player.x = platform_right + player_width / 2.0 + 0.001; // Small epsilon to prevent overlap
-                player.velocity_x = 0.0;
-            } else {
-                // No horizontal velocity, resolve based on which side is closer
-                let dist_to_left = (player_right - platform_left).abs();
-                let dist_to_right = (player_left - platform_right).abs();
-                if dist_to_left < dist_to_right {
-                    player.x = platform_left - player_width / 2.0 - 0.001;
+        // Apply gravity only if flying (not when sliding or climbing)
+        if player.ground_state.is_flying() {
+            player.velocity_y += config.physics.gravity * delta_time;
+        }
+        self.apply_force_zones(player, delta_time);
+        
+        // Apply horizontal friction based on ground state
+        // Friction should be much weaker to allow smooth movement
+        // It only slows down movement, doesn't completely stop it immediately
+        match player.ground_state {
+            GroundState::Grounded { .. } if sliding => {
+                // Ground slides keep their speed until they run out
+            }
+            GroundState::Grounded { platform_id: _ } => {
+                // Normal deceleration when grounded - apply very gentle friction
+                // Friction is applied every frame (60fps), but commands come every 100ms
+                // So we need friction to be extremely weak to allow movement
+                // Use a tiny fraction of deceleration to allow smooth movement
+                let grip = config.physics.surfaces.get(player.surface).friction;
+                let friction = (config.physics.move_deceleration * 0.01) * grip * delta_time; // Reduce friction by 99%
+                if player.velocity_x.abs() > 0.01 { // Only apply friction if velocity is significant
+                    if player.velocity_x > 0.0 {
+                        player.velocity_x = (player.velocity_x - friction).max(0.0);
+                    } else if player.velocity_x < 0.0 {
+                        player.velocity_x = (player.velocity_x + friction).min(0.0);
+                    }
                 } else {
-                    player.x = platform_right + player_width / 2.0 + 0.001;
+                    // If velocity is very small, just stop it
+                    player.velocity_x = 0.0;
                 }
             }
+            GroundState::Sliding { platform_id, .. } => {
+                // Apply sliding friction (different for ground vs platform)
+                // Sliding friction should be very weak to allow smooth sliding
+                let slide_friction = if platform_id.is_some() {
+                    config.physics.platform_slide_friction
+                } else {
+                    config.physics.ground_slide_friction
+                } * 0.01 * delta_time; // Reduce sliding friction by 99%
+                
+                if player.velocity_x.abs() > 0.01 { // Only apply friction if velocity is significant
+                    if player.velocity_x > 0.0 {
+                        player.velocity_x = (player.velocity_x - slide_friction).max(0.0);
+                    } else if player.velocity_x < 0.0 {
+                        player.velocity_x = (player.velocity_x + slide_friction).min(0.0);
+                    }
+                } else {
+                    // If velocity is very small, just stop it
+                    player.velocity_x = 0.0;
+                }
+            }
+            GroundState::Flying | GroundState::Climbing { .. } => {
+                // No friction when flying; climbers keep the speed their last command set
+            }
         }
-    }
-    
-    // Check wall collisions
-    for wall in &walls {
-        let wall_left = wall.x;
-        let wall_right = wall.x + wall.width;
-        let wall_bottom = wall.y_bottom;
-        let wall_top = wall.y_top;
         
-        // Check if player overlaps with wall
-        let horizontal_overlap = player_right > wall_left && player_left < wall_right;
-        let vertical_overlap = player_top > wall_bottom && player_bottom < wall_top;
+        // Clamp velocities
+        self.clamp_velocities(player);
         
-        if horizontal_overlap && vertical_overlap {
-            // Collision detected - properly reset position based on boundary
-            if player.velocity_x > 0.0 {
-                // Moving right, push player to exactly at the left boundary of wall
-                player.x = wall_left - player_width / 2.0 - 0.001; // Small epsilon to prevent overlap
-                player.velocity_x = 0.0;
-                // Check if sliding down wall
-                if player.velocity_y < 0.0 {
-                    player.ground_state = GroundState::Sliding {
-                        side: crate::ground_state::SlideSide::Left,
-                        platform_id: None,
-                    };
-                }
-            } else if player.velocity_x < 0.0 {
-                // Moving left, push player to exactly at the right boundary of wall
-                player.x = wall_right + player_width / 2.0 + 0.001; // Small epsilon to prevent overlap
-                player.velocity_x = 0.0;
-                // Check if sliding down wall
-                if player.velocity_y < 0.0 {
-                    player.ground_state = GroundState::Sliding {
-                        side: crate::ground_state::SlideSide::Right,
-                        platform_id: None,
-                    };
-                }
-            } else {
-                // No horizontal velocity, resolve based on which side is closer
-                let dist_to_left = (player_right - wall_left).abs();
-                let dist_to_right = (player_left - wall_right).abs();
-                if dist_to_left < dist_to_right {
-                    player.x = wall_left - player_width / 2.0 - 0.001;
-                } else {
-                    player.x = wall_right + player_width / 2.0 + 0.001;
-                }
+        // Update position with continuous collision detection
+        // This prevents players from moving through platforms
+        let half_width = config.physics.player_width / 2.0;
+        let reach = (player.velocity_x * delta_time).abs() + half_width + NEARBY_MARGIN;
+        let nearby = self.nearby(player.x - reach, player.x + reach, blocks);
+        self.update_position_with_collision(player, delta_time, &nearby);
+        
+        // Collision checks report anything unsupported as flying; climbers stay on their
+        // ladder until they leave it, land, or hit a wall
+        if climbing && player.ground_state.is_flying() {
+            if let Some(idx) = self.ladder_at(player) {
+                player.ground_state = GroundState::Climbing { ladder_id: idx as u32 };
             }
         }
+        jumped
     }
-}
 
-/// Check vertical collision with ground and platforms
-/// Returns true if collision was detected and resolved
-fn check_vertical_collision(player: &mut Player, player_width: f32, player_height: f32) -> bool {
-    let config = get_config();
-    let platforms = get_platforms();
-    
-    let player_left = player.x - player_width / 2.0;
-    let player_right = player.x + player_width / 2.0;
-    let player_bottom = player.y - player_height / 2.0;
-    let player_top = player.y + player_height / 2.0;
-    
-    // Check ground collision first - properly reset position at exact boundary
-    if player_bottom <= config.physics.ground_y {
-        // Reset player position to exactly at ground boundary
-        player.y = config.physics.ground_y + player_height / 2.0 + 0.001; // Small epsilon to prevent overlap
-        player.velocity_y = 0.0;
-        player.ground_state = GroundState::Grounded { platform_id: None };
-        return true;
-    }
-    
-    // Check platform collisions
-    for (idx, platform) in platforms.iter().enumerate() {
-        let platform_left = platform.x_start;
-        let platform_right = platform.x_end;
-        let platform_bottom = platform.y_top - platform.height;
-        let platform_top = platform.y_top;
-        
-        let horizontal_overlap = player_right > platform_left && player_left < platform_right;
-        
-        // Check landing on top of platform (moving down)
-        if horizontal_overlap 
-            && player.velocity_y <= 0.0
-            && player_bottom <= platform_top + 0.05
-            && player_bottom >= platform_top - 0.2 {
-            // Landing on platform from above - properly reset position at exact boundary
-            player.y = platform_top + player_height / 2.0 + 0.001; // Small epsilon to prevent overlap
+    /// Speed the player up by `secs` worth of movement acceleration in `direction`, up to
+    /// the top speed, and face that way
+    fn accelerate(&self, player: &mut Player, direction: f32, secs: f32) {
+        let acceleration = self.config.physics.move_acceleration * secs * self.sprint_factor(player) * self.surface_factor(player);
+        let top_speed = self.top_speed(player);
+        player.velocity_x = (player.velocity_x + direction * acceleration).clamp(-top_speed, top_speed);
+        // A centered stick keeps the player facing the way they were
+        if direction != 0.0 {
+            player.facing_right = direction > 0.0;
+        }
+    }
+
+    /// How much the surface underfoot scales acceleration; the air and ladders don't
+    fn surface_factor(&self, player: &Player) -> f32 {
+        if self.on_ground(player) {
+            self.config.physics.surfaces.get(player.surface).acceleration
+        } else {
+            1.0
+        }
+    }
+
+    /// Settle a player landing on `surface`, throwing them back up at `restitution` of
+    /// their landing speed if it's springy and they came down fast enough
+    fn land(&self, player: &mut Player, surface: Surface, restitution: f32, platform_id: Option<u32>) {
+        let physics = &self.config.physics;
+        // Grounded players drop a tick's worth of gravity onto the surface every step or two
+        let resting = 2.0 * physics.gravity.abs() / self.config.tick_rate_hz.max(1.0);
+        let landing_speed = -player.velocity_y;
+        player.surface = surface;
+        if restitution > 0.0 && landing_speed > physics.bounce_min_speed.max(resting) {
+            let rebound = landing_speed * restitution;
+            self.bounce(player, rebound);
+            player.bounced = Some(rebound);
+        } else {
             player.velocity_y = 0.0;
-            player.ground_state = GroundState::Grounded { platform_id: Some(idx as u32) };
-            return true;
+            player.ground_state = GroundState::Grounded { platform_id };
         }
-        
-        // Check hitting platform from below (moving up)
-        if horizontal_overlap
-            && player.velocity_y > 0.0
-            && player_top >= platform_bottom - 0.05
-            && player_top <= platform_bottom + 0.2 {
-            // Hit platform from below - properly reset position at exact boundary
-            player.y = platform_bottom - player_height / 2.0 - 0.001; // Small epsilon to prevent overlap
+    }
+
+    /// Push the player by every force zone they overlap; climbers hold on to their ladder
+    fn apply_force_zones(&self, player: &mut Player, delta_time: f32) {
+        if player.ground_state.is_climbing() {
+            return;
+        }
+        let physics = &self.config.physics;
+        let (half_w, half_h) = (physics.player_width / 2.0, player.height(physics) / 2.0);
+        for zone in &self.config.force_zones {
+            if zone.overlaps(player.x - half_w, player.x + half_w, player.y - half_h, player.y + half_h) {
+                player.velocity_x += zone.force_x * delta_time;
+                player.velocity_y += zone.force_y * delta_time;
+            }
+        }
+    }
+
+    /// Send the player to the exit of the first portal they overlap, unless they are still
+    /// cooling down from the last one; the cooldown only runs while they are outside every portal
+    pub fn pass_through_portal(&self, player: &mut Player, delta_time: f32) -> Option<TeleportEvent> {
+        let physics = &self.config.physics;
+        let (half_w, half_h) = (physics.player_width / 2.0, player.height(physics) / 2.0);
+        let (left, right, bottom, top) = (player.x - half_w, player.x + half_w, player.y - half_h, player.y + half_h);
+        let Some(portal) = self.config.portals.iter().find(|p| p.overlaps(left, right, bottom, top)) else {
+            player.portal_cooldown_secs = (player.portal_cooldown_secs - delta_time).max(0.0);
+            return None;
+        };
+        if player.portal_cooldown_secs > 0.0 {
+            return None;
+        }
+        let exit = self.config.portals.iter().find(|p| p.id == portal.exit)?;
+        let (from_x, from_y) = (player.x, player.y);
+        (player.x, player.y) = exit.center();
+        self.carry_velocity(player, portal);
+        player.teleports += 1;
+        player.ground_state = GroundState::Flying;
+        player.portal_cooldown_secs = portal.cooldown_secs.max(0.0);
+        Some(TeleportEvent {
+            player_id: player.id,
+            portal: portal.id.clone(),
+            exit: exit.id.clone(),
+            from_x,
+            from_y,
+            x: player.x,
+            y: player.y,
+        })
+    }
+
+    /// Stop the player, or turn their velocity by the portal's rotation
+    fn carry_velocity(&self, player: &mut Player, portal: &PortalConfig) {
+        if portal.drop_velocity {
+            player.velocity_x = 0.0;
             player.velocity_y = 0.0;
+            return;
+        }
+        let (sin, cos) = portal.rotation_degrees.to_radians().sin_cos();
+        let (vx, vy) = (player.velocity_x, player.velocity_y);
+        player.velocity_x = vx * cos - vy * sin;
+        player.velocity_y = vx * sin + vy * cos;
+    }
+
+    /// Accelerate toward the held direction, letting go once the hold times out
+    fn apply_held_input(&self, player: &mut Player, delta_time: f32) {
+        let Some(key) = player.held else {
+            return;
+        };
+        if player.held_secs <= 0.0 {
+            // Its KeyUp was lost; stop as if it had arrived
+            player.held = None;
+            player.velocity_x = 0.0;
+            return;
+        }
+        player.held_secs -= delta_time;
+        // Horizontal controls while flying don't affect anything, as with MoveLeft/MoveRight;
+        // resting players flicker between grounded and flying, and both count as the ground
+        if !player.ground_state.is_flying() || self.on_ground(player) {
+            self.accelerate(player, key.sign(), delta_time);
+        }
+    }
+
+    /// Hold a jump pressed in the air for the configured window, so it goes off on landing
+    fn buffer_jump(&self, player: &mut Player) -> CommandOutcome {
+        let window = self.config.physics.jump_buffer_secs;
+        if window <= 0.0 {
+            return CommandOutcome::ignored(IgnoredReason::NotGrounded);
+        }
+        player.jump_buffer_secs = window;
+        CommandOutcome::Buffered
+    }
+
+    /// Jump on the first grounded step while a buffered jump is still held
+    fn release_buffered_jump(&self, player: &mut Player, delta_time: f32) -> bool {
+        if player.jump_buffer_secs <= 0.0 {
+            return false;
+        }
+        if player.ground_state.is_grounded() && !player.crouched {
+            player.velocity_y = self.config.physics.jump_velocity;
+            player.ground_state = GroundState::Flying;
+            player.jump_buffer_secs = 0.0;
             return true;
         }
+        player.jump_buffer_secs = (player.jump_buffer_secs - delta_time).max(0.0);
+        false
+    }
+
+    /// Drain stamina while sprinting and refill it on the ground otherwise
+    fn update_stamina(&self, player: &mut Player, delta_time: f32) {
+        let physics = &self.config.physics;
+        player.tick_stamina_effects(delta_time);
+        if !self.on_ground(player) {
+            return;
+        }
+        let running = player.velocity_x.abs() > 0.01;
+        if player.sprinting && running {
+            if !player.drain_stamina(physics.sprint_cost_per_sec * delta_time) {
+                player.sprinting = false;
+            }
+        } else if player.dash_secs <= 0.0 {
+            player.regen_stamina(physics.stamina_regen_per_sec * delta_time, physics.max_stamina);
+        }
+    }
+
+    /// Resting players alternate between grounded and flying each step, without vertical
+    /// speed, so either counts as being on the ground
+    fn on_ground(&self, player: &Player) -> bool {
+        player.ground_state.is_grounded() || (player.ground_state.is_flying() && player.velocity_y == 0.0)
+    }
+
+    /// Fastest the player may move horizontally right now: dashes and sprints raise the limit
+    fn top_speed(&self, player: &Player) -> f32 {
+        let physics = &self.config.physics;
+        if player.dash_secs > 0.0 {
+            physics.dash_speed.max(physics.max_horizontal_velocity)
+        } else if player.sprinting {
+            physics.max_horizontal_velocity * physics.sprint_multiplier
+        } else {
+            physics.max_horizontal_velocity
+        }
+    }
+
+    /// Direction away from a wall the player is pressed against in the air: 1.0 when the wall
+    /// is on their left, -1.0 when it is on their right
+    fn wall_push_direction(&self, player: &Player) -> Option<f32> {
+        if let GroundState::Sliding { side, .. } = player.ground_state {
+            return Some(match side {
+                crate::ground_state::SlideSide::Left => -1.0,
+                crate::ground_state::SlideSide::Right => 1.0,
+            });
+        }
+        let physics = &self.config.physics;
+        let half_width = physics.player_width / 2.0;
+        let half_height = player.height(physics) / 2.0;
+        let (bottom, top) = (player.y - half_height, player.y + half_height);
+        let reach = 0.05;
+        let (near_left, near_right) = (player.x - half_width - reach - NEARBY_MARGIN, player.x + half_width + reach + NEARBY_MARGIN);
+        self.geometry
+            .walls_near(near_left, near_right)
+            .chain(self.geometry.platforms_near(near_left, near_right).map(|span| &span.solid))
+            .chain(self.geometry.ground_near(near_left, near_right).iter().map(|column| &column.solid))
+            .filter(|solid| top > solid.bottom && bottom < solid.top)
+            .map(|solid| (solid.left, solid.right))
+            .find_map(|(left, right)| {
+                if (left - (player.x + half_width)).abs() <= reach {
+                    Some(-1.0)
+                } else if ((player.x - half_width) - right).abs() <= reach {
+                    Some(1.0)
+                } else {
+                    None
+                }
+            })
+    }
+
+    fn sprint_factor(&self, player: &Player) -> f32 {
+        if player.sprinting {
+            self.config.physics.sprint_multiplier
+        } else {
+            1.0
+        }
+    }
+
+    /// Index of the ladder the player can hold on to: their center within its width and
+    /// their body within its height
+    pub fn ladder_at(&self, player: &Player) -> Option<usize> {
+        let half_height = player.height(&self.config.physics) / 2.0;
+        self.config.ladders.iter().position(|ladder| {
+            player.x >= ladder.x_start
+                && player.x <= ladder.x_end
+                && player.y - half_height < ladder.y_top
+                && player.y + half_height > ladder.y_bottom
+        })
+    }
+
+    /// Whether a crouched player could stand up without their head entering a platform or wall
+    fn has_headroom(&self, player: &Player, blocks: &[PlatformConfig]) -> bool {
+        let physics = &self.config.physics;
+        let half_width = physics.player_width / 2.0;
+        let (left, right) = (player.x - half_width, player.x + half_width);
+        let bottom = player.y - player.height(physics) / 2.0;
+        let top = bottom + physics.player_height;
+        let nearby = self.nearby(left, right, blocks);
+        let overlaps = |solid: &Solid| top > solid.bottom && bottom < solid.top;
+        !nearby.platforms.iter().any(|p| overlaps(&p.solid)) && !nearby.walls.iter().any(overlaps)
+    }
+
+    fn clamp_velocities(&self, player: &mut Player) {
+        let top_speed = self.top_speed(player);
+        if player.velocity_x > top_speed {
+            player.velocity_x = top_speed;
+        } else if player.velocity_x < -top_speed {
+            player.velocity_x = -top_speed;
+        }
     }
-    
-    // No collision - player is flying
-    player.ground_state = GroundState::Flying;
-    false
-}
 
-/// Final collision resolution to fix any penetration
-fn resolve_collisions(player: &mut Player, player_width: f32, player_height: f32) {
-    let config = get_config();
-    let platforms = get_platforms();
-    let walls = get_walls();
-    
-    let player_left = player.x - player_width / 2.0;
-    let player_right = player.x + player_width / 2.0;
-    let player_bottom = player.y - player_height / 2.0;
-    let player_top = player.y + player_height / 2.0;
-    
-    // Check ground penetration - properly reset position at exact boundary
-    if player_bottom < config.physics.ground_y {
-        player.y = config.physics.ground_y + player_height / 2.0 + 0.001; // Small epsilon to prevent overlap
-        player.velocity_y = 0.0;
-        player.ground_state = GroundState::Grounded { platform_id: None };
-        return;
-    }
-    
-    // Check wall penetration first (walls take priority for horizontal collisions)
-    for wall in &walls {
-        let wall_left = wall.x;
-        let wall_right = wall.x + wall.width;
-        let wall_bottom = wall.y_bottom;
-        let wall_top = wall.y_top;
+    /// Update player position with continuous collision detection
+    /// This prevents players from moving through platforms by checking collisions
+    /// at multiple points along the movement path
+    fn update_position_with_collision(&self, player: &mut Player, delta_time: f32, nearby: &Nearby) {
+        let config = &self.config;
+        let player_width = config.physics.player_width;
+        let player_height = player.height(&config.physics);
+        
+        // Calculate movement
+        let dx = player.velocity_x * delta_time;
+        let dy = player.velocity_y * delta_time;
+        
+        // Move horizontally first with collision detection
+        player.x += dx;
+        
+        // Check horizontal collision with platforms
+        self.check_horizontal_collision(player, player_width, player_height, nearby);
+        self.check_ground_steps(player, dx, player_width, player_height, nearby);
+        
+        // Move vertically with continuous collision detection
+        // Use multiple steps to prevent passing through thin platforms
+        let steps = (dy.abs() / (player_height * 0.5)).max(1.0) as i32;
+        let step_size = dy / steps as f32;
+        
+        for _ in 0..steps {
+            player.y += step_size;
+            
+            // Check vertical collision
+            if self.check_vertical_collision(player, player_width, player_height, nearby) {
+                // Collision detected; the check already placed the player at the boundary
+                break;
+            }
+        }
         
-        let horizontal_overlap = player_right > wall_left && player_left < wall_right;
-        let vertical_overlap = player_top > wall_bottom && player_bottom < wall_top;
+        // Final collision check to ensure we're not penetrating anything
+        self.resolve_collisions(player, player_width, player_height, nearby);
+    }
+
+    /// Check horizontal collision with platforms and walls
+    fn check_horizontal_collision(&self, player: &mut Player, player_width: f32, player_height: f32, nearby: &Nearby) {
+        let player_left = player.x - player_width / 2.0;
+        let player_right = player.x + player_width / 2.0;
+        let player_bottom = player.y - player_height / 2.0;
+        let player_top = player.y + player_height / 2.0;
         
-        if horizontal_overlap && vertical_overlap {
-            // Player is penetrating wall - resolve based on which side is closer
-            let dist_to_left = (player_right - wall_left).abs();
-            let dist_to_right = (player_left - wall_right).abs();
-            let dist_to_top = (player_bottom - wall_top).abs();
-            let dist_to_bottom = (player_top - wall_bottom).abs();
+        // Check platform collisions
+        for platform in &nearby.platforms {
+            let Solid { left: platform_left, right: platform_right, bottom: platform_bottom, top: platform_top } = platform.solid;
             
-            let min_dist = dist_to_top.min(dist_to_bottom).min(dist_to_left).min(dist_to_right);
+            // Check if player overlaps with platform horizontally
+            let horizontal_overlap = player_right > platform_left && player_left < platform_right;
+            let vertical_overlap = player_top > platform_bottom && player_bottom < platform_top;
             
-            if min_dist == dist_to_left {
-                // Push left - properly reset position at exact boundary
-                player.x = wall_left - player_width / 2.0 - 0.001; // Small epsilon to prevent overlap
-                if player.velocity_y < 0.0 {
-                    // Sliding down left side of wall
-                    player.ground_state = GroundState::Sliding {
-                        side: crate::ground_state::SlideSide::Left,
-                        platform_id: None,
-                    };
-                } else {
+            if horizontal_overlap && vertical_overlap {
+                // Collision detected - properly reset position based on boundary
+                if player.velocity_x > 0.0 {
+                    // Moving right, push player to exactly at the left boundary of platform
+                    player.x = platform_left - player_width / 2.0 - 0.001; // Small epsilon to prevent overlap
+                    player.velocity_x = 0.0;
+                } else if player.velocity_x < 0.0 {
+                    // Moving left, push player to exactly at the right boundary of platform
+                    player.x = platform_right + player_width / 2.0 + 0.001; // Small epsilon to prevent overlap
                     player.velocity_x = 0.0;
-                }
-            } else if min_dist == dist_to_right {
-                // Push right - properly reset position at exact boundary
-                player.x = wall_right + player_width / 2.0 + 0.001; // Small epsilon to prevent overlap
-                if player.velocity_y < 0.0 {
-                    // Sliding down right side of wall
-                    player.ground_state = GroundState::Sliding {
-                        side: crate::ground_state::SlideSide::Right,
-                        platform_id: None,
-                    };
                 } else {
+                    // No horizontal velocity, resolve based on which side is closer
+                    let dist_to_left = (player_right - platform_left).abs();
+                    let dist_to_right = (player_left - platform_right).abs();
+                    if dist_to_left < dist_to_right {
+                        player.x = platform_left - player_width / 2.0 - 0.001;
+                    } else {
+                        player.x = platform_right + player_width / 2.0 + 0.001;
+                    }
+                }
+            }
+        }
+        
+        // Check wall collisions
+        for wall in &nearby.walls {
+            let Solid { left: wall_left, right: wall_right, bottom: wall_bottom, top: wall_top } = *wall;
+            
+            // Check if player overlaps with wall
+            let horizontal_overlap = player_right > wall_left && player_left < wall_right;
+            let vertical_overlap = player_top > wall_bottom && player_bottom < wall_top;
+            
+            if horizontal_overlap && vertical_overlap {
+                // Collision detected - properly reset position based on boundary
+                if player.velocity_x > 0.0 {
+                    // Moving right, push player to exactly at the left boundary of wall
+                    player.x = wall_left - player_width / 2.0 - 0.001; // Small epsilon to prevent overlap
                     player.velocity_x = 0.0;
+                    // Check if sliding down wall
+                    if player.velocity_y < 0.0 {
+                        player.ground_state = GroundState::Sliding {
+                            side: crate::ground_state::SlideSide::Left,
+                            platform_id: None,
+                        };
+                    }
+                } else if player.velocity_x < 0.0 {
+                    // Moving left, push player to exactly at the right boundary of wall
+                    player.x = wall_right + player_width / 2.0 + 0.001; // Small epsilon to prevent overlap
+                    player.velocity_x = 0.0;
+                    // Check if sliding down wall
+                    if player.velocity_y < 0.0 {
+                        player.ground_state = GroundState::Sliding {
+                            side: crate::ground_state::SlideSide::Right,
+                            platform_id: None,
+                        };
+                    }
+                } else {
+                    // No horizontal velocity, resolve based on which side is closer
+                    let dist_to_left = (player_right - wall_left).abs();
+                    let dist_to_right = (player_left - wall_right).abs();
+                    if dist_to_left < dist_to_right {
+                        player.x = wall_left - player_width / 2.0 - 0.001;
+                    } else {
+                        player.x = wall_right + player_width / 2.0 + 0.001;
+                    }
                 }
-            } else if min_dist == dist_to_top && player_bottom < wall_top {
-                // Push up to top of wall - properly reset position at exact boundary
-                player.y = wall_top + player_height / 2.0 + 0.001; // Small epsilon to prevent overlap
-                player.velocity_y = 0.0;
-            } else if min_dist == dist_to_bottom && player_top > wall_bottom {
-                // Push down below wall - properly reset position at exact boundary
-                player.y = wall_bottom - player_height / 2.0 - 0.001; // Small epsilon to prevent overlap
-                player.velocity_y = 0.0;
             }
-            return;
         }
     }
-    
-    // Check platform penetration
-    for (idx, platform) in platforms.iter().enumerate() {
-        let platform_left = platform.x_start;
-        let platform_right = platform.x_end;
-        let platform_bottom = platform.y_top - platform.height;
-        let platform_top = platform.y_top;
+
+    /// Ground raised above the player's feet blocks them from the side, like a wall
+    /// Ground they were already over is left to the vertical checks, which lift them onto it
+    fn check_ground_steps(&self, player: &mut Player, dx: f32, player_width: f32, player_height: f32, nearby: &Nearby) {
+        let player_left = player.x - player_width / 2.0;
+        let player_right = player.x + player_width / 2.0;
+        let player_bottom = player.y - player_height / 2.0;
+
+        for GroundColumn { solid: column, .. } in nearby.ground {
+            let entering = column.overlaps_x(player_left, player_right)
+                && !column.overlaps_x(player_left - dx, player_right - dx);
+            if !entering || player_bottom >= column.top {
+                continue;
+            }
+            if dx > 0.0 {
+                player.x = column.left - player_width / 2.0 - 0.001; // Small epsilon to prevent overlap
+            } else {
+                player.x = column.right + player_width / 2.0 + 0.001;
+            }
+            player.velocity_x = 0.0;
+            break;
+        }
+    }
+
+    /// Check vertical collision with ground and platforms
+    /// Returns true if collision was detected and resolved
+    fn check_vertical_collision(&self, player: &mut Player, player_width: f32, player_height: f32, nearby: &Nearby) -> bool {
+        let player_left = player.x - player_width / 2.0;
+        let player_right = player.x + player_width / 2.0;
+        let player_bottom = player.y - player_height / 2.0;
+        let player_top = player.y + player_height / 2.0;
         
-        let horizontal_overlap = player_right > platform_left && player_left < platform_right;
-        let vertical_overlap = player_top > platform_bottom && player_bottom < platform_top;
+        // Check ground collision first - properly reset position at exact boundary
+        let ground = self.geometry.ground_beneath(player_left, player_right);
+        if let Some(ground) = ground.filter(|ground| player_bottom <= ground.solid.top) {
+            // Reset player position to exactly at ground boundary
+            player.y = ground.solid.top + player_height / 2.0 + 0.001; // Small epsilon to prevent overlap
+            self.land(player, ground.surface, ground.restitution, None);
+            return true;
+        }
         
-        if horizontal_overlap && vertical_overlap {
-            // Player is penetrating platform - resolve based on which side is closer
-            let dist_to_top = (player_bottom - platform_top).abs();
-            let dist_to_bottom = (player_top - platform_bottom).abs();
-            let dist_to_left = (player_right - platform_left).abs();
-            let dist_to_right = (player_left - platform_right).abs();
+        // Check platform collisions
+        for platform in &nearby.platforms {
+            let Solid { left: platform_left, right: platform_right, bottom: platform_bottom, top: platform_top } = platform.solid;
             
-            let min_dist = dist_to_top.min(dist_to_bottom).min(dist_to_left).min(dist_to_right);
+            let horizontal_overlap = player_right > platform_left && player_left < platform_right;
             
-            if min_dist == dist_to_top && player_bottom < platform_top {
-                // Push up to top of platform - properly reset position at exact boundary
+            // Check landing on top of platform (moving down)
+            if horizontal_overlap 
+                && player.velocity_y <= 0.0
+                && player_bottom <= platform_top + 0.05
+                && player_bottom >= platform_top - 0.2 {
+                // Landing on platform from above - properly reset position at exact boundary
                 player.y = platform_top + player_height / 2.0 + 0.001; // Small epsilon to prevent overlap
-                player.velocity_y = 0.0;
-                player.ground_state = GroundState::Grounded { platform_id: Some(idx as u32) };
-            } else if min_dist == dist_to_bottom && player_top > platform_bottom {
-                // Push down below platform - properly reset position at exact boundary
+                let (surface, restitution) = platform.footing(player_left, player_right, &self.config);
+                self.land(player, surface, restitution, Some(platform.id(player_left, player_right)));
+                return true;
+            }
+            
+            // Check hitting platform from below (moving up)
+            if horizontal_overlap
+                && player.velocity_y > 0.0
+                && player_top >= platform_bottom - 0.05
+                && player_top <= platform_bottom + 0.2 {
+                // Hit platform from below - properly reset position at exact boundary
                 player.y = platform_bottom - player_height / 2.0 - 0.001; // Small epsilon to prevent overlap
                 player.velocity_y = 0.0;
-            } else if min_dist == dist_to_left {
-                // Push left - properly reset position at exact boundary
-                player.x = platform_left - player_width / 2.0 - 0.001; // Small epsilon to prevent overlap
-                if player.velocity_y < 0.0 {
-                    // Sliding down left side
-                    player.ground_state = GroundState::Sliding { 
-                        side: crate::ground_state::SlideSide::Left, 
-                        platform_id: Some(idx as u32) 
-                    };
-                } else {
-                    player.velocity_x = 0.0;
-                }
-            } else if min_dist == dist_to_right {
-                // Push right - properly reset position at exact boundary
-                player.x = platform_right + player_width / 2.0 + 0.001; // Small epsilon to prevent overlap
-                if player.velocity_y < 0.0 {
-                    // Sliding down right side
-                    player.ground_state = GroundState::Sliding { 
-                        side: crate::ground_state::SlideSide::Right, 
-                        platform_id: Some(idx as u32) 
-                    };
-                } else {
-                    player.velocity_x = 0.0;
-                }
+                return true;
             }
-            return;
         }
+        
+        // No collision - player is flying
+        player.ground_state = GroundState::Flying;
+        false
     }
-}
 
-pub fn apply_command(player: &mut Player, command: &crate::commands::PlayerCommand) {
-    let config = get_config();
-    match command {
-        crate::commands::PlayerCommand::MoveLeft => {
-            // Horizontal controls while in flying state should not affect anything
-            if player.ground_state.is_flying() {
-                return;
-            }
+    /// Final collision resolution to fix any penetration
+    fn resolve_collisions(&self, player: &mut Player, player_width: f32, player_height: f32, nearby: &Nearby) {
+        let player_left = player.x - player_width / 2.0;
+        let player_right = player.x + player_width / 2.0;
+        let player_bottom = player.y - player_height / 2.0;
+        let player_top = player.y + player_height / 2.0;
+        
+        // Check ground penetration - properly reset position at exact boundary
+        let ground = self.geometry.ground_beneath(player_left, player_right);
+        if let Some(ground) = ground.filter(|ground| player_bottom < ground.solid.top) {
+            player.y = ground.solid.top + player_height / 2.0 + 0.001; // Small epsilon to prevent overlap
+            self.land(player, ground.surface, ground.restitution, None);
+            return;
+        }
+        
+        // Check wall penetration first (walls take priority for horizontal collisions)
+        for wall in &nearby.walls {
+            let Solid { left: wall_left, right: wall_right, bottom: wall_bottom, top: wall_top } = *wall;
+            
+            let horizontal_overlap = player_right > wall_left && player_left < wall_right;
+            let vertical_overlap = player_top > wall_bottom && player_bottom < wall_top;
             
-            // Apply acceleration, but clamp to max velocity
-            // When grounded, this enables smooth horizontal movement that can transition to sliding
-            // Use a larger acceleration value to overcome friction
-            let acceleration = config.physics.move_acceleration * 0.016;
-            player.velocity_x = (player.velocity_x - acceleration)
-                .max(-config.physics.max_horizontal_velocity);
-            player.facing_right = false;
-        }
-        crate::commands::PlayerCommand::MoveRight => {
-            // Horizontal controls while in flying state should not affect anything
-            if player.ground_state.is_flying() {
+            if horizontal_overlap && vertical_overlap {
+                // Player is penetrating wall - resolve based on which side is closer
+                let dist_to_left = (player_right - wall_left).abs();
+                let dist_to_right = (player_left - wall_right).abs();
+                let dist_to_top = (player_bottom - wall_top).abs();
+                let dist_to_bottom = (player_top - wall_bottom).abs();
+                
+                let min_dist = dist_to_top.min(dist_to_bottom).min(dist_to_left).min(dist_to_right);
+                
+                if min_dist == dist_to_left {
+                    // Push left - properly reset position at exact boundary
+                    player.x = wall_left - player_width / 2.0 - 0.001; // Small epsilon to prevent overlap
+                    if player.velocity_y < 0.0 {
+                        // Sliding down left side of wall
+                        player.ground_state = GroundState::Sliding {
+                            side: crate::ground_state::SlideSide::Left,
+                            platform_id: None,
+                        };
+                    } else {
+                        player.velocity_x = 0.0;
+                    }
+                } else if min_dist == dist_to_right {
+                    // Push right - properly reset position at exact boundary
+                    player.x = wall_right + player_width / 2.0 + 0.001; // Small epsilon to prevent overlap
+                    if player.velocity_y < 0.0 {
+                        // Sliding down right side of wall
+                        player.ground_state = GroundState::Sliding {
+                            side: crate::ground_state::SlideSide::Right,
+                            platform_id: None,
+                        };
+                    } else {
+                        player.velocity_x = 0.0;
+                    }
+                } else if min_dist == dist_to_top && player_bottom < wall_top {
+                    // Push up to top of wall - properly reset position at exact boundary
+                    player.y = wall_top + player_height / 2.0 + 0.001; // Small epsilon to prevent overlap
+                    player.velocity_y = 0.0;
+                } else if min_dist == dist_to_bottom && player_top > wall_bottom {
+                    // Push down below wall - properly reset position at exact boundary
+                    player.y = wall_bottom - player_height / 2.0 - 0.001; // Small epsilon to prevent overlap
+                    player.velocity_y = 0.0;
+                }
                 return;
             }
+        }
+        
+        // Check platform penetration
+        for platform in &nearby.platforms {
+            let Solid { left: platform_left, right: platform_right, bottom: platform_bottom, top: platform_top } = platform.solid;
+            let idx = platform.id(player_left, player_right);
+            
+            let horizontal_overlap = player_right > platform_left && player_left < platform_right;
+            let vertical_overlap = player_top > platform_bottom && player_bottom < platform_top;
             
-            // Apply acceleration, but clamp to max velocity
-            // When grounded, this enables smooth horizontal movement that can transition to sliding
-            // Use a larger acceleration value to overcome friction
-            let acceleration = config.physics.move_acceleration * 0.016;
-            player.velocity_x = (player.velocity_x + acceleration)
-                .min(config.physics.max_horizontal_velocity);
-            player.facing_right = true;
-        }
-        crate::commands::PlayerCommand::Jump => {
-            // Only jump if grounded (not sliding or flying)
-            if player.ground_state.is_grounded() {
-                player.velocity_y = config.physics.jump_velocity;
-                player.ground_state = GroundState::Flying;
+            if horizontal_overlap && vertical_overlap {
+                // Player is penetrating platform - resolve based on which side is closer
+                let dist_to_top = (player_bottom - platform_top).abs();
+                let dist_to_bottom = (player_top - platform_bottom).abs();
+                let dist_to_left = (player_right - platform_left).abs();
+                let dist_to_right = (player_left - platform_right).abs();
+                
+                let min_dist = dist_to_top.min(dist_to_bottom).min(dist_to_left).min(dist_to_right);
+                
+                if min_dist == dist_to_top && player_bottom < platform_top {
+                    // Push up to top of platform - properly reset position at exact boundary
+                    player.y = platform_top + player_height / 2.0 + 0.001; // Small epsilon to prevent overlap
+                    let (surface, restitution) = platform.footing(player_left, player_right, &self.config);
+                    self.land(player, surface, restitution, Some(idx));
+                } else if min_dist == dist_to_bottom && player_top > platform_bottom {
+                    // Push down below platform - properly reset position at exact boundary
+                    player.y = platform_bottom - player_height / 2.0 - 0.001; // Small epsilon to prevent overlap
+                    player.velocity_y = 0.0;
+                } else if min_dist == dist_to_left {
+                    // Push left - properly reset position at exact boundary
+                    player.x = platform_left - player_width / 2.0 - 0.001; // Small epsilon to prevent overlap
+                    if player.velocity_y < 0.0 {
+                        // Sliding down left side
+                        player.ground_state = GroundState::Sliding { 
+                            side: crate::ground_state::SlideSide::Left, 
+                            platform_id: Some(idx) 
+                        };
+                    } else {
+                        player.velocity_x = 0.0;
+                    }
+                } else if min_dist == dist_to_right {
+                    // Push right - properly reset position at exact boundary
+                    player.x = platform_right + player_width / 2.0 + 0.001; // Small epsilon to prevent overlap
+                    if player.velocity_y < 0.0 {
+                        // Sliding down right side
+                        player.ground_state = GroundState::Sliding { 
+                            side: crate::ground_state::SlideSide::Right, 
+                            platform_id: Some(idx) 
+                        };
+                    } else {
+                        player.velocity_x = 0.0;
+                    }
+                }
+                return;
             }
         }
-        crate::commands::PlayerCommand::Stop => {
-            // Stop horizontal movement immediately
-            player.velocity_x = 0.0;
+    }
+
+    /// Add a knockback impulse to a player's velocity; upward knockback lifts them off the ground
+    pub fn apply_knockback(&self, player: &mut Player, impulse_x: f32, impulse_y: f32) {
+        player.velocity_x += impulse_x;
+        player.velocity_y += impulse_y;
+        self.clamp_velocities(player);
+        if impulse_y > 0.0 && player.velocity_y > 0.0 {
+            player.ground_state = GroundState::Flying;
+        }
+    }
+
+    /// Launch a player upward at `velocity`, replacing their vertical velocity
+    pub fn bounce(&self, player: &mut Player, velocity: f32) {
+        player.velocity_y = velocity;
+        player.ground_state = GroundState::Flying;
+    }
+
+    /// Apply a movement command to the player, reporting why it did nothing if it was ignored
+    pub fn apply_command(&self, player: &mut Player, command: &crate::commands::PlayerCommand) -> CommandOutcome {
+        let config = &self.config;
+        match command {
+            crate::commands::PlayerCommand::MoveLeft
+            | crate::commands::PlayerCommand::MoveRight
+            | crate::commands::PlayerCommand::Move { .. } => {
+                // Horizontal controls while in flying state should not affect anything
+                if player.ground_state.is_flying() {
+                    return CommandOutcome::ignored(IgnoredReason::Flying);
+                }
+                
+                // Apply acceleration, but clamp to max velocity
+                // When grounded, this enables smooth horizontal movement that can transition to sliding
+                // Use a larger acceleration value to overcome friction
+                // Analog sticks scale it; the digital commands are full tilt either way
+                let axis = command.move_axis().unwrap_or_default();
+                self.accelerate(player, axis, 0.016);
+            }
+            crate::commands::PlayerCommand::Jump => {
+                // Only jump if grounded or climbing (not sliding or flying), and standing
+                if player.crouched {
+                    return CommandOutcome::ignored(IgnoredReason::Crouched);
+                }
+                if player.ground_state.is_grounded() || player.ground_state.is_climbing() {
+                    player.velocity_y = config.physics.jump_velocity;
+                    player.ground_state = GroundState::Flying;
+                    player.jump_buffer_secs = 0.0;
+                } else if self.on_ground(player) {
+                    // Resting between steps; a normal jump waits for the next grounded step
+                    return self.buffer_jump(player);
+                } else if let Some(away) = self.wall_push_direction(player) {
+                    // Kick off a wall the player is pressed against in the air
+                    if !player.try_spend_stamina(config.physics.wall_jump_cost) {
+                        return CommandOutcome::ignored(IgnoredReason::NoStamina);
+                    }
+                    player.velocity_y = config.physics.jump_velocity;
+                    player.velocity_x = away * config.physics.wall_jump_push;
+                    player.facing_right = away > 0.0;
+                    player.ground_state = GroundState::Flying;
+                } else {
+                    return self.buffer_jump(player);
+                }
+            }
+            crate::commands::PlayerCommand::Stop => {
+                // Stop horizontal movement immediately, or slide on where the footing is
+                // slippery; climbers also hold still on the ladder
+                let grip = config.physics.surfaces.get(player.surface).friction;
+                player.velocity_x = if self.on_ground(player) && grip < 1.0 {
+                    player.velocity_x * (1.0 - grip.max(0.0))
+                } else {
+                    0.0
+                };
+                if player.ground_state.is_climbing() {
+                    player.velocity_y = 0.0;
+                }
+            }
+            crate::commands::PlayerCommand::MoveUp | crate::commands::PlayerCommand::MoveDown => {
+                // Climbing only works standing and within reach of a ladder
+                if player.crouched {
+                    return CommandOutcome::ignored(IgnoredReason::Crouched);
+                }
+                let Some(idx) = self.ladder_at(player) else {
+                    return CommandOutcome::ignored(IgnoredReason::NoLadder);
+                };
+                let speed = config.ladders[idx].climb_speed;
+                player.velocity_y = match command {
+                    crate::commands::PlayerCommand::MoveUp => speed,
+                    _ => -speed,
+                };
+                player.velocity_x = 0.0;
+                player.ground_state = GroundState::Climbing { ladder_id: idx as u32 };
+            }
+            crate::commands::PlayerCommand::Crouch => {
+                let on_ground = self.on_ground(player);
+                if player.crouched {
+                    player.wants_to_stand = false;
+                    return CommandOutcome::Accepted;
+                }
+                if !on_ground {
+                    return CommandOutcome::ignored(IgnoredReason::NotGrounded);
+                }
+                // Keep the feet where they are while the body shrinks
+                player.crouched = true;
+                player.y -= (config.physics.player_height - player.height(&config.physics)) / 2.0;
+                player.wants_to_stand = false;
+                if player.velocity_x.abs() >= config.physics.slide_min_speed {
+                    player.velocity_x *= config.physics.slide_boost;
+                    player.slide_secs = config.physics.slide_secs;
+                    self.clamp_velocities(player);
+                }
+            }
+            crate::commands::PlayerCommand::StandUp => {
+                if !player.crouched {
+                    return CommandOutcome::ignored(IgnoredReason::NotCrouched);
+                }
+                player.wants_to_stand = true;
+            }
+            crate::commands::PlayerCommand::Sprint => {
+                if player.stamina <= 0.0 {
+                    return CommandOutcome::ignored(IgnoredReason::NoStamina);
+                }
+                player.sprinting = true;
+            }
+            crate::commands::PlayerCommand::StopSprint => {
+                player.sprinting = false;
+            }
+            crate::commands::PlayerCommand::Dash => {
+                if player.crouched {
+                    return CommandOutcome::ignored(IgnoredReason::Crouched);
+                }
+                if player.ground_state.is_climbing() {
+                    return CommandOutcome::ignored(IgnoredReason::Climbing);
+                }
+                if player.dash_secs > 0.0 {
+                    return CommandOutcome::ignored(IgnoredReason::Dashing);
+                }
+                if !player.try_spend_stamina(config.physics.dash_cost) {
+                    return CommandOutcome::ignored(IgnoredReason::NoStamina);
+                }
+                let direction = if player.facing_right { 1.0 } else { -1.0 };
+                player.dash_secs = config.physics.dash_secs;
+                player.velocity_x = direction * config.physics.dash_speed;
+                self.clamp_velocities(player);
+            }
+            crate::commands::PlayerCommand::PlaceBlock { .. }
+            | crate::commands::PlayerCommand::RemoveBlock { .. } => {
+                // Building commands change world geometry, handled by GameState
+            }
+            crate::commands::PlayerCommand::Shoot { .. } => {
+                // Projectiles are simulated by GameState
+            }
+            crate::commands::PlayerCommand::KeyDown { key } => {
+                // Applied every tick by the physics step; repeats just refresh the timeout
+                player.held = Some(*key);
+                player.held_secs = config.physics.held_input_timeout_secs;
+            }
+            crate::commands::PlayerCommand::KeyUp { key } => {
+                if player.held.is_some_and(|held| held != *key) {
+                    return CommandOutcome::ignored(IgnoredReason::NotHeld);
+                }
+                player.held = None;
+                player.velocity_x = 0.0;
+            }
         }
+        CommandOutcome::Accepted
     }
 }
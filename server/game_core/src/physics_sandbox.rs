@@ -0,0 +1,79 @@
+//! Live physics changes made by a room's creator, each setting kept within bounds an admin
+//! configured
+
+use serde_json::{Map, Value};
+use crate::config::{PhysicsConfig, PhysicsSandboxConfig};
+
+/// Why a physics change was refused; nothing is changed when any setting is refused
+#[derive(Debug, Clone, PartialEq)]
+pub enum PhysicsPatchError {
+    /// The server doesn't allow physics changes
+    Disabled,
+    /// The change names no settings
+    Empty,
+    /// The setting doesn't exist or has no configured bounds
+    NotAdjustable(String),
+    /// The setting was given something other than a finite number
+    NotANumber(String),
+    OutOfBounds { field: String, min: f32, max: f32 },
+    /// The changed physics don't make a config that would load
+    Invalid(String),
+}
+
+impl std::fmt::Display for PhysicsPatchError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PhysicsPatchError::Disabled => write!(f, "physics changes are disabled"),
+            PhysicsPatchError::Empty => write!(f, "no physics settings given"),
+            PhysicsPatchError::NotAdjustable(field) => write!(f, "{} can't be changed", field),
+            PhysicsPatchError::NotANumber(field) => write!(f, "{} must be a number", field),
+            PhysicsPatchError::OutOfBounds { field, min, max } => {
+                write!(f, "{} must be between {} and {}", field, min, max)
+            }
+            PhysicsPatchError::Invalid(reason) => write!(f, "{}", reason),
+        }
+    }
+}
+
+impl std::error::Error for PhysicsPatchError {}
+
+/// `physics` with `changes` (setting name to new value) applied, if every change is allowed
+pub fn apply(
+    physics: &PhysicsConfig,
+    changes: &Map<String, Value>,
+    settings: &PhysicsSandboxConfig,
+) -> Result<PhysicsConfig, PhysicsPatchError> {
+    if !settings.enabled {
+        return Err(PhysicsPatchError::Disabled);
+    }
+    if changes.is_empty() {
+        return Err(PhysicsPatchError::Empty);
+    }
+    let Ok(Value::Object(mut fields)) = serde_json::to_value(physics) else {
+        unreachable!("physics config serializes to an object");
+    };
+    for (field, value) in changes {
+        let bounds = settings
+            .bounds
+            .get(field)
+            .filter(|_| fields.get(field).is_some_and(Value::is_number))
+            .ok_or_else(|| PhysicsPatchError::NotAdjustable(field.clone()))?;
+        let number = value
+            .as_f64()
+            .map(|n| n as f32)
+            .filter(|n| n.is_finite())
+            .ok_or_else(|| PhysicsPatchError::NotANumber(field.clone()))?;
+        if number < bounds.min || number > bounds.max {
+            return Err(PhysicsPatchError::OutOfBounds {
+                field: field.clone(),
+                min: bounds.min,
+                max: bounds.max,
+            });
+        }
+        fields.insert(field.clone(), Value::from(number));
+    }
+    let physics: PhysicsConfig =
+        serde_json::from_value(Value::Object(fields)).map_err(|e| PhysicsPatchError::Invalid(e.to_string()))?;
+    physics.validate().map_err(|errors| PhysicsPatchError::Invalid(errors.to_string()))?;
+    Ok(physics)
+}
@@ -1,7 +1,15 @@
 use serde::{Deserialize, Serialize, Deserializer};
 use uuid::Uuid;
 use crate::ground_state::GroundState;
-use crate::physics::get_config;
+use crate::scoring::ComboState;
+use crate::config::{PhysicsConfig, Surface, DEFAULT_MAX_HEALTH, DEFAULT_MAX_STAMINA};
+use crate::teams::TeamId;
+use crate::language::Language;
+use crate::player_color::Palette;
+use crate::respawn::LifeState;
+use crate::cosmetics::EquippedCosmetics;
+use crate::stamina::StaminaEffect;
+use crate::commands::HeldKey;
 
 #[derive(Debug, Clone, Serialize)]
 pub struct Player {
@@ -13,8 +21,116 @@ pub struct Player {
     pub velocity_y: f32,
     pub facing_right: bool,
     pub ground_state: GroundState,
+    pub score: u64,
+    pub combo: ComboState,
+    /// Highest command sequence number processed for this player
+    /// Clients use it to drop acknowledged inputs when reconciling predictions
+    pub last_processed_seq: u64,
+    /// Alive, or dead and waiting to respawn
+    pub life: LifeState,
+    /// Remaining health; projectiles, hazards and stomps take it away
+    pub health: u32,
+    /// Seconds left in which the player can't be damaged
+    pub invulnerable_secs: f32,
+    /// Ducking at the crouch height, so clients can play the crouch animation
+    pub crouched: bool,
+    /// Asked to stand up while a ceiling was in the way; stands once there is room
+    #[serde(skip_serializing)]
+    pub wants_to_stand: bool,
+    /// Seconds left in a ground slide, during which friction doesn't apply
+    #[serde(skip_serializing)]
+    pub slide_secs: f32,
+    /// Remaining stamina, for HUD bars; sprinting, dashing and wall jumps spend it
+    pub stamina: f32,
+    /// Running at the sprint speed while stamina lasts
+    pub sprinting: bool,
+    /// Seconds left in a dash, during which the player may exceed the top speed
+    #[serde(skip_serializing)]
+    pub dash_secs: f32,
+    /// Seconds left to jump on landing, after a jump was pressed in the air
+    #[serde(skip_serializing)]
+    pub jump_buffer_secs: f32,
+    /// Direction held down, accelerating the player every tick
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub held: Option<HeldKey>,
+    /// Seconds until the held direction lets go unless refreshed
+    #[serde(skip_serializing)]
+    pub held_secs: f32,
+    /// What the player last landed on, setting their grip while grounded
+    #[serde(skip_serializing)]
+    pub surface: Surface,
+    /// Upward speed a springy surface threw the player at during the last physics step
+    #[serde(skip_serializing)]
+    pub bounced: Option<f32>,
+    /// Seconds outside every portal the player must spend before another takes them
+    #[serde(skip_serializing)]
+    pub portal_cooldown_secs: f32,
+    /// Status effects changing how stamina drains and refills
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub stamina_effects: Vec<StaminaEffect>,
+    /// Bumped whenever the player is moved without traveling (respawns), so encoders can
+    /// tell clients not to interpolate across the jump
+    #[serde(skip_serializing)]
+    pub teleports: u64,
+    /// Team the player is on, when teams are enabled
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub team: Option<TeamId>,
+    /// The team's color, so clients can render players without a team lookup
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub team_color: Option<String>,
+    /// Trail, hat and name color the player shows, as cosmetic item ids
+    #[serde(skip_serializing_if = "EquippedCosmetics::is_empty")]
+    pub cosmetics: EquippedCosmetics,
+    /// Controlled by the server rather than a client
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub bot: bool,
+    /// No input for the presence AFK threshold; clients gray the player out
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub afk: bool,
+    /// Language for server-generated text; None falls back to the request's or the default
+    #[serde(skip_serializing)]
+    pub language: Option<Language>,
+    /// Palette this player sees colors in; None falls back to the stream's
+    #[serde(skip_serializing)]
+    pub palette: Option<Palette>,
     #[serde(skip_serializing)]
     pub last_activity: std::time::SystemTime,
+    /// When the player last sent a command or chat line; heartbeats don't count
+    #[serde(skip_serializing)]
+    pub last_input: std::time::SystemTime,
+    /// When the player last changed their display name
+    #[serde(skip_serializing)]
+    pub last_renamed: Option<std::time::SystemTime>,
+    /// Chose a display name through /api/player/register; only registered players can
+    /// reserve their name
+    #[serde(skip_serializing)]
+    pub registered: bool,
+}
+
+/// What a player carries that clients aren't sent, so serializing a `Player` drops it
+/// Sent alongside players when another server has to carry on simulating them
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PlayerInternals {
+    pub id: Uuid,
+    pub wants_to_stand: bool,
+    pub slide_secs: f32,
+    pub dash_secs: f32,
+    pub jump_buffer_secs: f32,
+    pub held_secs: f32,
+    pub surface: Surface,
+    pub portal_cooldown_secs: f32,
+    pub teleports: u64,
+    pub language: Option<Language>,
+    pub palette: Option<Palette>,
+    pub registered: bool,
+}
+
+fn default_health() -> u32 {
+    DEFAULT_MAX_HEALTH
+}
+
+fn default_stamina() -> f32 {
+    DEFAULT_MAX_STAMINA
 }
 
 impl<'de> Deserialize<'de> for Player {
@@ -32,6 +148,38 @@ impl<'de> Deserialize<'de> for Player {
             velocity_y: f32,
             facing_right: bool,
             ground_state: GroundState,
+            #[serde(default)]
+            score: u64,
+            #[serde(default)]
+            combo: ComboState,
+            #[serde(default)]
+            last_processed_seq: u64,
+            #[serde(default)]
+            life: LifeState,
+            #[serde(default = "default_health")]
+            health: u32,
+            #[serde(default)]
+            invulnerable_secs: f32,
+            #[serde(default)]
+            crouched: bool,
+            #[serde(default = "default_stamina")]
+            stamina: f32,
+            #[serde(default)]
+            sprinting: bool,
+            #[serde(default)]
+            stamina_effects: Vec<StaminaEffect>,
+            #[serde(default)]
+            held: Option<HeldKey>,
+            #[serde(default)]
+            team: Option<TeamId>,
+            #[serde(default)]
+            team_color: Option<String>,
+            #[serde(default)]
+            cosmetics: EquippedCosmetics,
+            #[serde(default)]
+            bot: bool,
+            #[serde(default)]
+            afk: bool,
         }
         
         let helper = PlayerHelper::deserialize(deserializer)?;
@@ -44,19 +192,47 @@ impl<'de> Deserialize<'de> for Player {
             velocity_y: helper.velocity_y,
             facing_right: helper.facing_right,
             ground_state: helper.ground_state,
+            score: helper.score,
+            combo: helper.combo,
+            last_processed_seq: helper.last_processed_seq,
+            life: helper.life,
+            health: helper.health,
+            invulnerable_secs: helper.invulnerable_secs,
+            crouched: helper.crouched,
+            wants_to_stand: false,
+            slide_secs: 0.0,
+            stamina: helper.stamina,
+            sprinting: helper.sprinting,
+            dash_secs: 0.0,
+            jump_buffer_secs: 0.0,
+            held: helper.held,
+            held_secs: 0.0,
+            surface: Surface::Normal,
+            bounced: None,
+            portal_cooldown_secs: 0.0,
+            stamina_effects: helper.stamina_effects,
+            teleports: 0,
+            team: helper.team,
+            team_color: helper.team_color,
+            cosmetics: helper.cosmetics,
+            bot: helper.bot,
+            afk: helper.afk,
+            language: None,
+            palette: None,
             last_activity: std::time::SystemTime::now(),
+            last_input: std::time::SystemTime::now(),
+            last_renamed: None,
+            registered: false,
         })
     }
 }
 
 impl Player {
     /// Create a new player at the configured starting position
-    /// Player starts on the ground at: ground_y + player_height/2
-    pub fn new(id: Uuid) -> Self {
-        // Get config to determine starting position
-        let config = get_config();
-        let ground_y = config.physics.ground_y;
-        let player_height = config.physics.player_height;
+    /// Player starts on the ground at: ground height at x = 0 + player_height/2
+    pub fn new(id: Uuid, physics: &PhysicsConfig) -> Self {
+        let ground_y = physics.ground_at(0.0);
+        let player_height = physics.player_height;
         
         // Player center when on ground = ground_y + player_height/2
         let start_y = ground_y + player_height / 2.0;
@@ -73,7 +249,78 @@ impl Player {
             velocity_y: 0.0,
             facing_right: true,
             ground_state: GroundState::Grounded { platform_id: None }, // Start on ground
+            score: 0,
+            combo: ComboState::default(),
+            last_processed_seq: 0,
+            life: LifeState::Alive,
+            health: DEFAULT_MAX_HEALTH,
+            invulnerable_secs: 0.0,
+            crouched: false,
+            wants_to_stand: false,
+            slide_secs: 0.0,
+            stamina: physics.max_stamina,
+            sprinting: false,
+            dash_secs: 0.0,
+            jump_buffer_secs: 0.0,
+            held: None,
+            held_secs: 0.0,
+            surface: Surface::Normal,
+            bounced: None,
+            portal_cooldown_secs: 0.0,
+            stamina_effects: Vec::new(),
+            teleports: 0,
+            team: None,
+            team_color: None,
+            cosmetics: EquippedCosmetics::default(),
+            bot: false,
+            afk: false,
+            language: None,
+            palette: None,
             last_activity: std::time::SystemTime::now(),
+            last_input: std::time::SystemTime::now(),
+            last_renamed: None,
+            registered: false,
+        }
+    }
+    
+    /// The state serializing leaves out, to restore with `set_internals`
+    pub fn internals(&self) -> PlayerInternals {
+        PlayerInternals {
+            id: self.id,
+            wants_to_stand: self.wants_to_stand,
+            slide_secs: self.slide_secs,
+            dash_secs: self.dash_secs,
+            jump_buffer_secs: self.jump_buffer_secs,
+            held_secs: self.held_secs,
+            surface: self.surface,
+            portal_cooldown_secs: self.portal_cooldown_secs,
+            teleports: self.teleports,
+            language: self.language,
+            palette: self.palette,
+            registered: self.registered,
+        }
+    }
+
+    pub fn set_internals(&mut self, internals: &PlayerInternals) {
+        self.wants_to_stand = internals.wants_to_stand;
+        self.slide_secs = internals.slide_secs;
+        self.dash_secs = internals.dash_secs;
+        self.jump_buffer_secs = internals.jump_buffer_secs;
+        self.held_secs = internals.held_secs;
+        self.surface = internals.surface;
+        self.portal_cooldown_secs = internals.portal_cooldown_secs;
+        self.teleports = internals.teleports;
+        self.language = internals.language;
+        self.palette = internals.palette;
+        self.registered = internals.registered;
+    }
+
+    /// Collision height: the crouch height while crouched, the full height otherwise
+    pub fn height(&self, physics: &PhysicsConfig) -> f32 {
+        if self.crouched {
+            physics.crouch_height.min(physics.player_height)
+        } else {
+            physics.player_height
         }
     }
     
@@ -101,8 +348,15 @@ impl Player {
     }
     
     /// Update the player's last activity timestamp
-    pub fn update_activity(&mut self) {
-        self.last_activity = std::time::SystemTime::now();
+    pub fn update_activity(&mut self, now: std::time::SystemTime) {
+        self.last_activity = now;
+    }
+
+    /// Note input from the player, which also brings them back from AFK
+    pub fn record_input(&mut self, now: std::time::SystemTime) {
+        self.last_activity = now;
+        self.last_input = now;
+        self.afk = false;
     }
 }
 
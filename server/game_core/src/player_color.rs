@@ -1,9 +1,59 @@
+use serde::{Deserialize, Serialize};
 use uuid::Uuid;
+use crate::config::{ColorBlindPaletteConfig, TeamDefinition};
 
-/// Generate a unique color for a player based on their ID
-/// This matches the client-side color generation algorithm
-pub fn get_player_color(player_id: &Uuid) -> String {
-    // Hash the player ID to get a consistent color
+/// Color scheme a player sees players and teams in
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Palette {
+    /// Hue-hashed player colors and the configured team colors
+    #[default]
+    Standard,
+    /// Colors that stay distinguishable with red-green and blue-yellow color blindness
+    ColorBlind,
+}
+
+impl Palette {
+    pub const ALL: [Palette; 2] = [Palette::Standard, Palette::ColorBlind];
+
+    /// Parse a palette name such as "standard", "colorblind" or "color-blind"
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.trim().to_lowercase().replace(['-', '_', ' '], "").as_str() {
+            "standard" | "default" => Some(Palette::Standard),
+            "colorblind" | "cb" => Some(Palette::ColorBlind),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Palette::Standard => "standard",
+            Palette::ColorBlind => "colorblind",
+        }
+    }
+}
+
+/// A player's color in the given palette
+pub fn player_color(player_id: &Uuid, palette: Palette, config: &ColorBlindPaletteConfig) -> String {
+    match palette {
+        Palette::ColorBlind if !config.player_colors.is_empty() => {
+            let index = color_hash(player_id) as usize % config.player_colors.len();
+            config.player_colors[index].clone()
+        }
+        _ => get_player_color(player_id),
+    }
+}
+
+/// A team's color in the given palette; teams without a color-blind color keep their own
+pub fn team_color(team: &TeamDefinition, palette: Palette, config: &ColorBlindPaletteConfig) -> String {
+    match palette {
+        Palette::ColorBlind => config.team_colors.get(&team.id).unwrap_or(&team.color).clone(),
+        Palette::Standard => team.color.clone(),
+    }
+}
+
+/// Hash the player ID to get a consistent color
+fn color_hash(player_id: &Uuid) -> u32 {
     let bytes = player_id.as_bytes();
     let mut hash: u32 = 0;
     
@@ -12,11 +62,18 @@ pub fn get_player_color(player_id: &Uuid) -> String {
         // (hash << 5) - hash is equivalent to hash * 31
         hash = hash.wrapping_mul(31).wrapping_add(byte as u32);
     }
+    hash
+}
+
+/// Generate a unique color for a player based on their ID
+/// This matches the client-side color generation algorithm
+pub fn get_player_color(player_id: &Uuid) -> String {
+    let hash = color_hash(player_id);
     
     // Generate HSL color values
-    let hue = (hash % 360) as u32;
-    let saturation = 70 + ((hash % 30) as u32); // 70-100%
-    let lightness = 50 + ((hash % 20) as u32); // 50-70%
+    let hue = hash % 360;
+    let saturation = 70 + (hash % 30); // 70-100%
+    let lightness = 50 + (hash % 20); // 50-70%
     
     // Convert HSL to RGB
     let (r, g, b) = hsl_to_rgb(hue, saturation, lightness);
@@ -0,0 +1,84 @@
+use serde_json::Value;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use crate::json_store::JsonStore;
+use crate::player::PlayerId;
+
+/// Why a settings document was rejected
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SettingsError {
+    /// Settings must be a JSON object
+    NotAnObject,
+    /// The serialized settings exceed the configured size limit
+    TooLarge { max_bytes: usize },
+}
+
+impl std::fmt::Display for SettingsError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SettingsError::NotAnObject => write!(f, "settings must be a JSON object"),
+            SettingsError::TooLarge { max_bytes } => write!(f, "settings must be at most {} bytes", max_bytes),
+        }
+    }
+}
+
+impl std::error::Error for SettingsError {}
+
+/// Small client preferences (keybinds, volume, HUD layout) per player, opaque to the server
+/// Optionally persisted to a JSON file so they survive restarts
+#[derive(Debug, Default)]
+pub struct PlayerSettings {
+    settings: JsonStore<HashMap<PlayerId, Value>>,
+}
+
+impl PlayerSettings {
+    /// In-memory settings that are lost on restart
+    pub fn in_memory() -> Self {
+        Self::default()
+    }
+
+    /// Load settings from a JSON file, creating it on first write if missing
+    pub async fn load_async(path: impl Into<PathBuf>) -> Result<Self, Box<dyn std::error::Error>> {
+        Ok(Self {
+            settings: JsonStore::load(path).await?,
+        })
+    }
+
+    /// Check a settings document against the size limit before storing it
+    pub fn validate(settings: &Value, max_bytes: usize) -> Result<(), SettingsError> {
+        if !settings.is_object() {
+            return Err(SettingsError::NotAnObject);
+        }
+        if settings.to_string().len() > max_bytes {
+            return Err(SettingsError::TooLarge { max_bytes });
+        }
+        Ok(())
+    }
+
+    pub fn get(&self, player_id: &PlayerId) -> Option<&Value> {
+        self.settings.get().get(player_id)
+    }
+
+    /// Replace a player's settings, rewriting the settings file if one is configured
+    pub async fn set(&mut self, player_id: PlayerId, settings: Value) -> Result<(), Box<dyn std::error::Error>> {
+        self.settings.get_mut().insert(player_id, settings);
+        self.settings.save().await
+    }
+
+    /// Forget a player's settings, rewriting the settings file; false if they had none
+    pub async fn remove(&mut self, player_id: &PlayerId) -> Result<bool, Box<dyn std::error::Error>> {
+        if self.settings.get_mut().remove(player_id).is_none() {
+            return Ok(false);
+        }
+        self.settings.save().await?;
+        Ok(true)
+    }
+
+    pub fn len(&self) -> usize {
+        self.settings.get().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.settings.get().is_empty()
+    }
+}
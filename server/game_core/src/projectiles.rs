@@ -0,0 +1,137 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use crate::config::PlatformConfig;
+use crate::physics::PhysicsWorld;
+use crate::player::PlayerId;
+
+/// Most sub-steps a projectile takes per tick, however fast it is configured to fly
+const MAX_SUBSTEPS: f32 = 64.0;
+
+/// A shot in flight, broadcast with the game state
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Projectile {
+    pub id: u64,
+    /// Player who fired it; projectiles never hit their shooter
+    pub owner: PlayerId,
+    pub x: f32,
+    pub y: f32,
+    pub velocity_x: f32,
+    pub velocity_y: f32,
+    /// Seconds since launch
+    #[serde(skip)]
+    pub age: f32,
+}
+
+/// Reasons a shot is rejected
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ShootError {
+    /// Shooting is disabled in the configuration
+    Disabled,
+    /// Command came from a player who is not in the game
+    UnknownPlayer,
+    /// Dead players can't shoot until they respawn
+    Dead,
+    /// Player is shooting faster than the cooldown allows
+    Cooldown,
+    /// Player already has the maximum number of projectiles in flight
+    TooMany,
+    /// Aim direction is zero or not a number
+    InvalidDirection,
+}
+
+impl fmt::Display for ShootError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let reason = match self {
+            ShootError::Disabled => "shooting is disabled",
+            ShootError::UnknownPlayer => "player is not in the game",
+            ShootError::Dead => "player is dead",
+            ShootError::Cooldown => "shooting too fast",
+            ShootError::TooMany => "too many projectiles in flight",
+            ShootError::InvalidDirection => "invalid aim direction",
+        };
+        write!(f, "{}", reason)
+    }
+}
+
+impl std::error::Error for ShootError {}
+
+/// What ended a projectile's flight
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Impact {
+    /// Hit the ground, a platform, a block or a wall
+    Geometry,
+    /// Hit a player
+    Player(PlayerId),
+    /// Ran out of lifetime or left the map
+    Expired,
+}
+
+impl Projectile {
+    /// Unit aim vector for a raw direction, or None if it has no usable length
+    pub fn aim(dir_x: f32, dir_y: f32) -> Option<(f32, f32)> {
+        let length = dir_x.hypot(dir_y);
+        (length.is_finite() && length > f32::EPSILON).then(|| (dir_x / length, dir_y / length))
+    }
+
+    /// Fly for `delta_time` seconds, stopping at the first thing hit
+    /// `targets` are the centers and heights of players that can be hit; the shooter is skipped
+    pub fn advance(
+        &mut self,
+        delta_time: f32,
+        world: &PhysicsWorld,
+        platforms: &[PlatformConfig],
+        targets: &[(PlayerId, f32, f32, f32)],
+    ) -> Option<Impact> {
+        let config = world.config();
+        let settings = &config.projectiles;
+        self.age += delta_time;
+        if self.age > settings.lifetime_secs {
+            return Some(Impact::Expired);
+        }
+        if settings.gravity {
+            self.velocity_y += config.physics.gravity * delta_time;
+        }
+
+        // Sub-step so fast projectiles can't tunnel through thin platforms or players
+        let radius = settings.radius.max(0.01);
+        let distance = self.velocity_x.hypot(self.velocity_y) * delta_time;
+        let steps = (distance / radius).ceil().clamp(1.0, MAX_SUBSTEPS);
+        let (step_x, step_y) = (self.velocity_x * delta_time / steps, self.velocity_y * delta_time / steps);
+        let half_w = config.physics.player_width / 2.0 + radius;
+        for _ in 0..steps as u32 {
+            self.x += step_x;
+            self.y += step_y;
+            let hit = targets
+                .iter()
+                .find(|(id, x, y, height)| {
+                    *id != self.owner && (self.x - x).abs() <= half_w && (self.y - y).abs() <= height / 2.0 + radius
+                });
+            if let Some((target, _, _, _)) = hit {
+                return Some(Impact::Player(*target));
+            }
+            if self.hits_geometry(world, platforms, radius) {
+                return Some(Impact::Geometry);
+            }
+        }
+
+        crate::respawn::outside_map(self.x, self.y, config).map(|_| Impact::Expired)
+    }
+
+    /// Whether the projectile overlaps the ground, a platform or block, or a wall
+    fn hits_geometry(&self, world: &PhysicsWorld, platforms: &[PlatformConfig], radius: f32) -> bool {
+        let config = world.config();
+        if self.y - radius <= world.geometry().ground_under(self.x - radius, self.x + radius) {
+            return true;
+        }
+        let (left, right, bottom, top) = (self.x - radius, self.x + radius, self.y - radius, self.y + radius);
+        let hits_platform = platforms
+            .iter()
+            .any(|p| right > p.x_start && left < p.x_end && top > p.y_top - p.height && bottom < p.y_top);
+        let hits_wall = config
+            .walls
+            .iter()
+            .any(|w| right > w.x && left < w.x + w.width && top > w.y_bottom && bottom < w.y_top);
+        hits_platform || hits_wall
+    }
+}
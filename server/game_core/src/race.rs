@@ -0,0 +1,218 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use crate::config::{CheckpointConfig, GameConfig, GameRules, PhysicsConfig};
+use crate::game_mode::{GameMode, ModeContext};
+use crate::player::{Player, PlayerId};
+use crate::signals::Signal;
+
+/// How far a player has got around the course
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RaceProgress {
+    /// Index of the checkpoint to touch next; the last one is the finish line
+    pub next_checkpoint: usize,
+    /// Laps completed
+    pub laps: u32,
+    /// When the lap being run started, Unix milliseconds by the game clock
+    pub lap_started_ms: u64,
+    /// Time of each completed lap in milliseconds
+    pub lap_times_ms: Vec<u64>,
+    /// Total race time, once the player has crossed the finish on the last lap
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub finished_ms: Option<u64>,
+}
+
+/// Something a racer did this tick
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RaceStep {
+    /// Touched the next checkpoint in order
+    Checkpoint,
+    /// Crossed the finish line, starting another lap
+    Lap,
+    /// Crossed the finish line on the last lap, finishing in this 1-based place
+    Finished { place: usize },
+}
+
+/// One racer's line in the standings
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RaceStanding {
+    pub player_id: PlayerId,
+    pub player_name: String,
+    /// 1-based finishing place, once the racer has finished
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub place: Option<usize>,
+    #[serde(flatten)]
+    pub progress: RaceProgress,
+}
+
+/// Everyone's progress, leaders first, as sent to clients
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RaceStandings {
+    /// Laps needed to finish
+    pub laps: u32,
+    /// Checkpoints per lap, the finish line included
+    pub checkpoints: usize,
+    pub racers: Vec<RaceStanding>,
+    /// Every racer has finished
+    pub complete: bool,
+}
+
+/// Race rules: checkpoint progress and lap times, with finishers scoring by place
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RaceMode {
+    racers: HashMap<PlayerId, RaceProgress>,
+    /// Finishers, first place first
+    finish_order: Vec<PlayerId>,
+    /// Progress changed since the last standings were taken
+    #[serde(skip)]
+    changed: bool,
+}
+
+impl RaceMode {
+    /// Put everyone back at the start line as of `now_ms`
+    pub fn start(&mut self, players: impl Iterator<Item = PlayerId>, now_ms: u64) {
+        self.racers = players
+            .map(|id| (id, RaceProgress { lap_started_ms: now_ms, ..Default::default() }))
+            .collect();
+        self.finish_order.clear();
+        self.changed = true;
+    }
+
+    /// Players in the race, finished or not
+    pub fn racer_count(&self) -> usize {
+        self.racers.len()
+    }
+
+    pub fn progress(&self, player_id: &PlayerId) -> Option<&RaceProgress> {
+        self.racers.get(player_id)
+    }
+
+    /// Advance a player touching `checkpoints` at `now_ms`; players who weren't at the start
+    /// join the race from here
+    pub fn touch(&mut self, player: &Player, checkpoints: &[CheckpointConfig], laps: u32, physics: &PhysicsConfig, now_ms: u64) -> Option<RaceStep> {
+        let progress = self.racers.entry(player.id).or_insert_with(|| {
+            self.changed = true;
+            RaceProgress { lap_started_ms: now_ms, ..Default::default() }
+        });
+        if progress.finished_ms.is_some() {
+            return None;
+        }
+        let next = checkpoints.get(progress.next_checkpoint)?;
+        let (half_w, half_h) = (physics.player_width / 2.0, player.height(physics) / 2.0);
+        if !next.overlaps(player.x - half_w, player.x + half_w, player.y - half_h, player.y + half_h) {
+            return None;
+        }
+        self.changed = true;
+        progress.next_checkpoint += 1;
+        if progress.next_checkpoint < checkpoints.len() {
+            return Some(RaceStep::Checkpoint);
+        }
+        progress.next_checkpoint = 0;
+        progress.laps += 1;
+        progress.lap_times_ms.push(now_ms.saturating_sub(progress.lap_started_ms));
+        progress.lap_started_ms = now_ms;
+        if progress.laps < laps.max(1) {
+            return Some(RaceStep::Lap);
+        }
+        progress.finished_ms = Some(progress.lap_times_ms.iter().sum());
+        self.finish_order.push(player.id);
+        Some(RaceStep::Finished { place: self.finish_order.len() })
+    }
+
+    /// Whether there are racers and all of them have finished
+    pub fn complete(&self) -> bool {
+        !self.racers.is_empty() && self.finish_order.len() == self.racers.len()
+    }
+
+    /// Finishers in order, then everyone else by laps and checkpoints done
+    pub fn standings(&self, players: &HashMap<PlayerId, Player>, checkpoints: usize, laps: u32) -> RaceStandings {
+        let mut racers: Vec<RaceStanding> = self
+            .racers
+            .iter()
+            .map(|(id, progress)| RaceStanding {
+                player_id: *id,
+                player_name: players.get(id).map(|p| p.name.clone()).unwrap_or_default(),
+                place: self.finish_order.iter().position(|finisher| finisher == id).map(|i| i + 1),
+                progress: progress.clone(),
+            })
+            .collect();
+        racers.sort_by(|a, b| {
+            let rank = |r: &RaceStanding| (r.place.unwrap_or(usize::MAX), std::cmp::Reverse((r.progress.laps, r.progress.next_checkpoint)));
+            rank(a).cmp(&rank(b)).then(a.player_id.cmp(&b.player_id))
+        });
+        RaceStandings {
+            laps: laps.max(1),
+            checkpoints,
+            racers,
+            complete: self.complete(),
+        }
+    }
+}
+
+impl GameMode for RaceMode {
+    fn rules(&self) -> GameRules {
+        GameRules::RACE
+    }
+
+    fn on_round_start(&mut self, ctx: &mut ModeContext) {
+        self.start(ctx.players.keys().copied(), ctx.now_ms);
+    }
+
+    /// Advance racers touching their next checkpoint; finishers score one point for every
+    /// racer, less one for each who finished ahead of them
+    fn on_tick(&mut self, ctx: &mut ModeContext, _delta_time: f32) {
+        let config = ctx.config;
+        for player_id in ctx.player_ids() {
+            let Some(player) = ctx.players.get_mut(&player_id).filter(|p| p.life.is_alive()) else {
+                continue;
+            };
+            if let Some(RaceStep::Finished { place }) = self.touch(player, &config.checkpoints, config.race.laps, &config.physics, ctx.now_ms) {
+                player.score += (self.racer_count() + 1).saturating_sub(place) as u64;
+            }
+        }
+    }
+
+    fn on_player_leave(&mut self, player_id: &PlayerId) {
+        if self.racers.remove(player_id).is_some() {
+            self.finish_order.retain(|id| id != player_id);
+            self.changed = true;
+        }
+    }
+
+    fn round_over(&self, _config: &GameConfig) -> bool {
+        self.complete()
+    }
+
+    fn reset(&mut self) {
+        if !self.racers.is_empty() {
+            self.changed = true;
+        }
+        self.racers.clear();
+        self.finish_order.clear();
+    }
+
+    fn signal(&self) -> Option<Signal> {
+        Some(Signal::Race)
+    }
+
+    fn status(&self, players: &HashMap<PlayerId, Player>, config: &GameConfig) -> serde_json::Value {
+        serde_json::to_value(self.standings(players, config.checkpoints.len(), config.race.laps)).unwrap_or_default()
+    }
+
+    fn take_changed(&mut self) -> bool {
+        std::mem::take(&mut self.changed)
+    }
+
+    fn save_state(&self) -> serde_json::Value {
+        serde_json::to_value(self).unwrap_or_default()
+    }
+
+    fn load_state(&mut self, state: &serde_json::Value) {
+        if let Ok(saved) = RaceMode::deserialize(state) {
+            *self = RaceMode { changed: true, ..saved };
+        }
+    }
+
+    fn clone_box(&self) -> Box<dyn GameMode> {
+        Box::new(self.clone())
+    }
+}
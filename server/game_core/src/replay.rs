@@ -0,0 +1,427 @@
+//! Match replays and the shareable summaries made from them
+//!
+//! The game loop samples player positions into a [`ReplayRecorder`] while a match is played and
+//! saves the [`Replay`] as `<dir>/<match id>.json` when it ends, next to the match log the same
+//! recorder keeps. Summaries are worked out from those files later, away from the game loop:
+//! per-player stats, highlights such as lead changes and deaths, and an optional SVG of
+//! everyone's path over the map.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+use uuid::Uuid;
+use crate::commands::PlayerCommand;
+use crate::config::{GroundSegment, MapGeometry};
+use crate::game_state::GameState;
+use crate::match_log::{LogBuilder, MatchLog, MatchLogHeader};
+use crate::match_state::MatchState;
+use crate::player::PlayerId;
+
+/// One sampled player
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReplayPlayer {
+    pub id: PlayerId,
+    pub name: String,
+    pub x: f32,
+    pub y: f32,
+    pub score: u64,
+    pub alive: bool,
+}
+
+/// Every player at one moment, `t` seconds into the match
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReplayFrame {
+    pub t: f32,
+    pub players: Vec<ReplayPlayer>,
+}
+
+/// A recorded match: the map it was played on and players sampled over time
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Replay {
+    /// Same as the match record's id
+    pub id: Uuid,
+    pub mode: String,
+    pub ground_y: f32,
+    /// Ground at other heights than `ground_y`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub ground_segments: Vec<GroundSegment>,
+    /// Players are sampled at their center, this far above their feet
+    pub player_height: f32,
+    pub geometry: MapGeometry,
+    pub frames: Vec<ReplayFrame>,
+}
+
+impl Replay {
+    /// Where the replay with `id` is stored under `dir`
+    pub fn path(dir: impl AsRef<Path>, id: &Uuid) -> PathBuf {
+        dir.as_ref().join(format!("{}.json", id))
+    }
+
+    pub async fn load(dir: impl AsRef<Path>, id: &Uuid) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        let contents = tokio::fs::read_to_string(Self::path(dir, id)).await?;
+        Ok(serde_json::from_str(&contents)?)
+    }
+
+    /// Write the replay under `dir`, creating it if needed
+    pub async fn save(&self, dir: impl AsRef<Path>) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        tokio::fs::create_dir_all(dir.as_ref()).await?;
+        let path = Self::path(dir, &self.id);
+        let temp_path = path.with_extension("json.tmp");
+        tokio::fs::write(&temp_path, serde_json::to_vec(self)?).await?;
+        tokio::fs::rename(&temp_path, &path).await?;
+        Ok(())
+    }
+
+    pub fn duration_secs(&self) -> f32 {
+        self.frames.last().map_or(0.0, |frame| frame.t)
+    }
+}
+
+/// A finished match as recorded: the sampled replay and the command log
+#[derive(Debug, Clone)]
+pub struct RecordedMatch {
+    pub replay: Replay,
+    pub log: MatchLog,
+}
+
+/// Records the match being played: players sampled at a fixed rate for the replay, and every
+/// accepted command plus snapshots every few ticks for the match log
+#[derive(Debug, Clone)]
+pub struct ReplayRecorder {
+    interval_secs: f32,
+    tick_rate_hz: f32,
+    /// Start of the match being recorded, in unix milliseconds
+    started_at_ms: Option<u64>,
+    /// Tick the match started on; match log ticks count from here
+    first_tick: u64,
+    ground_y: f32,
+    ground_segments: Vec<GroundSegment>,
+    player_height: f32,
+    geometry: MapGeometry,
+    frames: Vec<ReplayFrame>,
+    log: LogBuilder,
+}
+
+impl ReplayRecorder {
+    pub fn new(sample_hz: f32, snapshot_every_ticks: u64, tick_rate_hz: f32) -> Self {
+        Self {
+            interval_secs: 1.0 / sample_hz.max(0.1),
+            tick_rate_hz,
+            started_at_ms: None,
+            first_tick: 0,
+            ground_y: 0.0,
+            ground_segments: Vec::new(),
+            player_height: 0.0,
+            geometry: MapGeometry::default(),
+            frames: Vec::new(),
+            log: LogBuilder::new(snapshot_every_ticks),
+        }
+    }
+
+    /// Log a command the state just applied, if its match is being recorded
+    pub fn record_command(&mut self, state: &GameState, player_id: &PlayerId, command: &PlayerCommand) {
+        if !matches!(state.match_state, MatchState::Playing { started_at_ms, .. } if self.started_at_ms == Some(started_at_ms)) {
+            return;
+        }
+        let tick = self.relative_tick(state);
+        self.log.command(tick, *player_id, command.clone());
+    }
+
+    /// Start recording when a match starts, then sample and snapshot the players when due
+    pub fn observe(&mut self, state: &GameState) {
+        let MatchState::Playing { started_at_ms, .. } = state.match_state else {
+            return;
+        };
+        if self.started_at_ms != Some(started_at_ms) {
+            let config = state.world.config();
+            self.started_at_ms = Some(started_at_ms);
+            self.first_tick = state.tick;
+            self.ground_y = config.physics.ground_y;
+            self.ground_segments = config.physics.ground_segments.clone();
+            self.player_height = config.physics.player_height;
+            self.geometry = config.geometry();
+            self.frames.clear();
+            self.log.clear();
+        }
+        let tick = self.relative_tick(state);
+        self.log.observe(state, tick);
+
+        let t = state.clock.unix_millis().saturating_sub(started_at_ms) as f32 / 1000.0;
+        if self.frames.last().is_some_and(|last| t - last.t < self.interval_secs) {
+            return;
+        }
+        let mut players: Vec<ReplayPlayer> = state
+            .players
+            .values()
+            .map(|p| ReplayPlayer {
+                id: p.id,
+                name: p.name.clone(),
+                x: p.x,
+                y: p.y,
+                score: p.score,
+                alive: p.life.is_alive(),
+            })
+            .collect();
+        players.sort_by_key(|p| p.id);
+        self.frames.push(ReplayFrame { t, players });
+    }
+
+    /// The recorded match, saved under the finished match's id; recording starts afresh next match
+    pub fn finish(&mut self, id: Uuid, mode: &str) -> Option<RecordedMatch> {
+        let started_at_ms = self.started_at_ms.take()?;
+        let log = self.log.finish(MatchLogHeader {
+            id,
+            mode: mode.to_string(),
+            tick_rate_hz: self.tick_rate_hz,
+            snapshot_every_ticks: self.log.snapshot_every_ticks,
+            started_at_ms,
+            geometry: self.geometry.clone(),
+        });
+        let replay = Replay {
+            id,
+            mode: mode.to_string(),
+            ground_y: self.ground_y,
+            ground_segments: std::mem::take(&mut self.ground_segments),
+            player_height: self.player_height,
+            geometry: std::mem::take(&mut self.geometry),
+            frames: std::mem::take(&mut self.frames),
+        };
+        Some(RecordedMatch { replay, log })
+    }
+
+    fn relative_tick(&self, state: &GameState) -> u32 {
+        state.tick.saturating_sub(self.first_tick).min(u32::MAX as u64) as u32
+    }
+}
+
+/// How one player did over the match
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlayerSummary {
+    pub id: PlayerId,
+    pub name: String,
+    pub final_score: u64,
+    /// Path length through the sampled positions, in world units
+    pub distance: f32,
+    /// Highest their feet got above the ground
+    pub max_height: f32,
+    pub deaths: u32,
+}
+
+/// A moment worth pointing out, `t` seconds into the match
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Highlight {
+    /// Became the sole top scorer
+    TookLead { t: f32, player: String, score: u64 },
+    Died { t: f32, player: String },
+    /// The highest anyone got all match
+    HighestPoint { t: f32, player: String, height: f32 },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReplaySummary {
+    pub id: Uuid,
+    pub mode: String,
+    pub duration_secs: f32,
+    /// Players by final score, highest first
+    pub players: Vec<PlayerSummary>,
+    /// In match order
+    pub highlights: Vec<Highlight>,
+}
+
+impl ReplaySummary {
+    pub fn of(replay: &Replay) -> Self {
+        let mut players: HashMap<PlayerId, PlayerSummary> = HashMap::new();
+        let mut last_seen: HashMap<PlayerId, &ReplayPlayer> = HashMap::new();
+        let mut highlights = Vec::new();
+        let mut leader: Option<PlayerId> = None;
+        let mut highest: Option<(f32, &str, f32)> = None;
+
+        for frame in &replay.frames {
+            for player in &frame.players {
+                let height = player.y - replay.player_height / 2.0 - replay.ground_y;
+                let summary = players.entry(player.id).or_insert_with(|| PlayerSummary {
+                    id: player.id,
+                    name: player.name.clone(),
+                    final_score: 0,
+                    distance: 0.0,
+                    max_height: height,
+                    deaths: 0,
+                });
+                summary.final_score = player.score;
+                summary.max_height = summary.max_height.max(height);
+                if let Some(previous) = last_seen.get(&player.id) {
+                    // Respawns teleport, so only count travel between living samples
+                    if previous.alive && player.alive {
+                        summary.distance += ((player.x - previous.x).powi(2) + (player.y - previous.y).powi(2)).sqrt();
+                    }
+                    if previous.alive && !player.alive {
+                        summary.deaths += 1;
+                        highlights.push(Highlight::Died { t: frame.t, player: player.name.clone() });
+                    }
+                }
+                if highest.is_none_or(|(_, _, best)| height > best) {
+                    highest = Some((frame.t, &player.name, height));
+                }
+                last_seen.insert(player.id, player);
+            }
+
+            let top = frame.players.iter().map(|p| p.score).max().unwrap_or(0);
+            let mut leaders = frame.players.iter().filter(|p| p.score == top);
+            if let (true, Some(first), None) = (top > 0, leaders.next(), leaders.next()) {
+                if leader != Some(first.id) {
+                    leader = Some(first.id);
+                    highlights.push(Highlight::TookLead { t: frame.t, player: first.name.clone(), score: top });
+                }
+            }
+        }
+
+        if let Some((t, player, height)) = highest {
+            highlights.push(Highlight::HighestPoint { t, player: player.to_string(), height });
+        }
+        highlights.sort_by(|a, b| a.t().total_cmp(&b.t()));
+
+        let mut players: Vec<PlayerSummary> = players.into_values().collect();
+        players.sort_by(|a, b| b.final_score.cmp(&a.final_score).then_with(|| a.name.cmp(&b.name)));
+        Self {
+            id: replay.id,
+            mode: replay.mode.clone(),
+            duration_secs: replay.duration_secs(),
+            players,
+            highlights,
+        }
+    }
+}
+
+impl Highlight {
+    pub fn t(&self) -> f32 {
+        match self {
+            Highlight::TookLead { t, .. } | Highlight::Died { t, .. } | Highlight::HighestPoint { t, .. } => *t,
+        }
+    }
+}
+
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+/// Pixels per world unit in trajectory SVGs
+const SVG_SCALE: f32 = 20.0;
+/// Margin around the map in world units
+const SVG_MARGIN: f32 = 2.0;
+
+/// Draw the map with every player's path over it, each in the player's color
+pub fn trajectory_svg(replay: &Replay) -> String {
+    let geometry = &replay.geometry;
+    let positions = replay.frames.iter().flat_map(|f| f.players.iter().map(|p| (p.x, p.y)));
+    let xs = geometry
+        .platforms
+        .iter()
+        .flat_map(|p| [p.x_start, p.x_end])
+        .chain(geometry.walls.iter().flat_map(|w| [w.x, w.x + w.width]))
+        .chain(replay.ground_segments.iter().flat_map(|g| [g.x_start, g.x_end]))
+        .chain(positions.clone().map(|(x, _)| x))
+        .chain([0.0]);
+    let ys = geometry
+        .platforms
+        .iter()
+        .flat_map(|p| [p.y_top, p.y_top - p.height])
+        .chain(geometry.walls.iter().flat_map(|w| [w.y_bottom, w.y_top]))
+        .chain(positions.map(|(_, y)| y))
+        .chain(replay.ground_segments.iter().map(|g| g.y_top))
+        .chain([replay.ground_y]);
+    let (min_x, max_x) = xs.fold((f32::INFINITY, f32::NEG_INFINITY), |(lo, hi), x| (lo.min(x), hi.max(x)));
+    let (min_y, max_y) = ys.fold((f32::INFINITY, f32::NEG_INFINITY), |(lo, hi), y| (lo.min(y), hi.max(y)));
+    let (min_x, max_x) = (min_x - SVG_MARGIN, max_x + SVG_MARGIN);
+    let (min_y, max_y) = (min_y - SVG_MARGIN, max_y + SVG_MARGIN);
+
+    let width = (max_x - min_x) * SVG_SCALE;
+    let height = (max_y - min_y) * SVG_SCALE;
+    // World y points up, SVG y points down
+    let px = |x: f32| (x - min_x) * SVG_SCALE;
+    let py = |y: f32| (max_y - y) * SVG_SCALE;
+
+    let mut svg = String::new();
+    let _ = writeln!(
+        svg,
+        r##"<svg xmlns="http://www.w3.org/2000/svg" width="{:.0}" height="{:.0}" viewBox="0 0 {:.1} {:.1}">"##,
+        width, height, width, height
+    );
+    let _ = writeln!(svg, r##"  <rect width="100%" height="100%" fill="#1E1E2E"/>"##);
+    let _ = writeln!(
+        svg,
+        r##"  <rect x="0" y="{:.1}" width="{:.1}" height="{:.1}" fill="#3A3A4A"/>"##,
+        py(replay.ground_y),
+        width,
+        height - py(replay.ground_y)
+    );
+    for segment in &replay.ground_segments {
+        // Cut the default ground away, then fill in the segment at its own height
+        let (x, segment_width) = (px(segment.x_start), (segment.x_end - segment.x_start) * SVG_SCALE);
+        let _ = writeln!(
+            svg,
+            r##"  <rect x="{:.1}" y="0" width="{:.1}" height="{:.1}" fill="#1E1E2E"/>"##,
+            x, segment_width, height
+        );
+        let _ = writeln!(
+            svg,
+            r##"  <rect x="{:.1}" y="{:.1}" width="{:.1}" height="{:.1}" fill="#3A3A4A"/>"##,
+            x,
+            py(segment.y_top),
+            segment_width,
+            height - py(segment.y_top)
+        );
+    }
+    for wall in &geometry.walls {
+        let _ = writeln!(
+            svg,
+            r##"  <rect x="{:.1}" y="{:.1}" width="{:.1}" height="{:.1}" fill="{}"/>"##,
+            px(wall.x),
+            py(wall.y_top),
+            wall.width * SVG_SCALE,
+            (wall.y_top - wall.y_bottom) * SVG_SCALE,
+            wall.color
+        );
+    }
+    for platform in &geometry.platforms {
+        let _ = writeln!(
+            svg,
+            r##"  <rect x="{:.1}" y="{:.1}" width="{:.1}" height="{:.1}" fill="{}"/>"##,
+            px(platform.x_start),
+            py(platform.y_top),
+            (platform.x_end - platform.x_start) * SVG_SCALE,
+            platform.height * SVG_SCALE,
+            platform.color
+        );
+    }
+
+    // One line per stretch of life, so respawns don't draw a jump across the map
+    let mut paths: Vec<(&ReplayPlayer, Vec<(f32, f32)>)> = Vec::new();
+    let mut open: HashMap<PlayerId, usize> = HashMap::new();
+    for frame in &replay.frames {
+        for player in &frame.players {
+            if !player.alive {
+                open.remove(&player.id);
+                continue;
+            }
+            let index = *open.entry(player.id).or_insert_with(|| {
+                paths.push((player, Vec::new()));
+                paths.len() - 1
+            });
+            paths[index].1.push((player.x, player.y));
+        }
+    }
+    for (player, points) in paths {
+        let points: Vec<String> = points.iter().map(|&(x, y)| format!("{:.1},{:.1}", px(x), py(y))).collect();
+        let _ = writeln!(
+            svg,
+            r##"  <polyline points="{}" fill="none" stroke="{}" stroke-width="2" stroke-linejoin="round"><title>{}</title></polyline>"##,
+            points.join(" "),
+            crate::player_color::get_player_color(&player.id),
+            escape_xml(&player.name)
+        );
+    }
+    svg.push_str("</svg>\n");
+    svg
+}
@@ -0,0 +1,93 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use crate::config::{GameConfig, SpawnPoint};
+use crate::player::{Player, PlayerId};
+
+/// Whether a player is in play, serialized with the player so clients can hide the dead
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "state", rename_all = "snake_case")]
+pub enum LifeState {
+    #[default]
+    Alive,
+    /// Out of play until the respawn timer runs out
+    Dead { cause: DeathCause, respawn_in_secs: f32 },
+}
+
+impl LifeState {
+    pub fn is_alive(&self) -> bool {
+        matches!(self, LifeState::Alive)
+    }
+}
+
+/// Why a player died
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DeathCause {
+    /// Fell below the kill plane
+    FellOut,
+    /// Left the map bounds
+    OutOfBounds,
+    /// Lost the last of their health to a projectile
+    Shot,
+    /// Lost the last of their health to a hazard
+    Hazard,
+    /// Lost the last of their health to being landed on
+    Stomped,
+}
+
+/// A player dying or coming back, broadcast to clients
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum LifeEvent {
+    /// Died at (x, y); respawns after `respawn_in_secs`
+    Died {
+        player_id: PlayerId,
+        cause: DeathCause,
+        x: f32,
+        y: f32,
+        respawn_in_secs: f32,
+    },
+    /// Back in play at the spawn point (x, y)
+    Respawned { player_id: PlayerId, x: f32, y: f32 },
+}
+
+/// Why the player's position kills them, if it does
+pub fn death_cause(player: &Player, config: &GameConfig) -> Option<DeathCause> {
+    outside_map(player.x, player.y, config)
+}
+
+/// Whether (x, y) is below the kill plane or outside the map bounds
+pub fn outside_map(x: f32, y: f32, config: &GameConfig) -> Option<DeathCause> {
+    if y < config.respawn.kill_y {
+        return Some(DeathCause::FellOut);
+    }
+    let bounds = config.respawn.bounds.as_ref()?;
+    let outside = x < bounds.min_x || x > bounds.max_x || y > bounds.max_y;
+    outside.then_some(DeathCause::OutOfBounds)
+}
+
+/// The spawn point farthest from every living player, so nobody respawns on top of someone
+/// Without configured spawn points players spawn at x = 0 on the ground
+pub fn spawn_position(config: &GameConfig, players: &HashMap<PlayerId, Player>, spawning: &PlayerId) -> (f32, f32) {
+    let default_spawn = SpawnPoint {
+        x: 0.0,
+        y: config.physics.ground_at(0.0) + config.physics.player_height / 2.0,
+    };
+    let distance_to_nearest = |spawn: &SpawnPoint| {
+        players
+            .values()
+            .filter(|p| p.id != *spawning && p.life.is_alive())
+            .map(|p| (p.x - spawn.x).powi(2) + (p.y - spawn.y).powi(2))
+            .fold(f32::INFINITY, f32::min)
+    };
+    // First point wins ties, so spawns are deterministic with nobody else around
+    let spawn = config
+        .spawn_points
+        .iter()
+        .fold(None::<&SpawnPoint>, |best, spawn| match best {
+            Some(best) if distance_to_nearest(best) >= distance_to_nearest(spawn) => Some(best),
+            _ => Some(spawn),
+        })
+        .unwrap_or(&default_spawn);
+    (spawn.x, spawn.y)
+}
@@ -0,0 +1,60 @@
+use uuid::Uuid;
+
+/// The simulation's only source of randomness
+///
+/// SplitMix64: small, fast and identical on every platform, so a seeded run makes the same
+/// draws in the same order every time. Unseeded rooms start from the system time instead.
+#[derive(Debug, Clone)]
+pub struct SeededRng {
+    state: u64,
+}
+
+impl SeededRng {
+    pub fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    /// Seeded from the system time, for rooms that don't need to be reproducible
+    pub fn from_entropy() -> Self {
+        let nanos = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_nanos();
+        Self::new(nanos as u64 ^ (nanos >> 64) as u64)
+    }
+
+    /// Where the generator is up to; `SeededRng::new(state)` carries on from the same draw
+    pub fn state(&self) -> u64 {
+        self.state
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform in [0, 1)
+    pub fn next_f32(&mut self) -> f32 {
+        // The top 24 bits, as many as an f32 mantissa holds
+        (self.next_u64() >> 40) as f32 / (1u64 << 24) as f32
+    }
+
+    /// Uniform in [min, max); min when the range is empty
+    pub fn range(&mut self, min: f32, max: f32) -> f32 {
+        if max <= min {
+            return min;
+        }
+        min + (max - min) * self.next_f32()
+    }
+
+    /// A version 4 UUID made from the next draws
+    pub fn uuid(&mut self) -> Uuid {
+        let mut bytes = [0u8; 16];
+        bytes[..8].copy_from_slice(&self.next_u64().to_le_bytes());
+        bytes[8..].copy_from_slice(&self.next_u64().to_le_bytes());
+        uuid::Builder::from_random_bytes(bytes).into_uuid()
+    }
+}
@@ -0,0 +1,78 @@
+use std::collections::VecDeque;
+use std::time::{Duration, SystemTime};
+use crate::player::PlayerId;
+
+/// Outcome of a player asking to join the room
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Admission {
+    /// The player has a slot in the game
+    Playing,
+    /// The room is full; the player spectates at this 1-based place in the queue
+    Waiting { position: usize },
+    /// The room and its waiting queue are both full
+    Full,
+}
+
+/// Players spectating until a slot opens, first come first served
+#[derive(Debug, Clone, Default)]
+pub struct WaitingQueue {
+    /// Waiting players and when they were last active
+    entries: VecDeque<(PlayerId, SystemTime)>,
+}
+
+impl WaitingQueue {
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// 1-based place in the queue, if the player is waiting
+    pub fn position(&self, player_id: &PlayerId) -> Option<usize> {
+        self.entries.iter().position(|(id, _)| id == player_id).map(|i| i + 1)
+    }
+
+    /// Queue a player at the back, returning their position
+    pub fn push(&mut self, player_id: PlayerId, now: SystemTime) -> usize {
+        self.entries.push_back((player_id, now));
+        self.entries.len()
+    }
+
+    /// Record activity from a waiting player; false if they aren't queued
+    pub fn touch(&mut self, player_id: &PlayerId, now: SystemTime) -> bool {
+        match self.entries.iter_mut().find(|(id, _)| id == player_id) {
+            Some((_, last_activity)) => {
+                *last_activity = now;
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn remove(&mut self, player_id: &PlayerId) -> bool {
+        let before = self.entries.len();
+        self.entries.retain(|(id, _)| id != player_id);
+        self.entries.len() != before
+    }
+
+    /// Take the player at the front of the queue
+    pub fn pop_front(&mut self) -> Option<PlayerId> {
+        self.entries.pop_front().map(|(id, _)| id)
+    }
+
+    /// Waiting players with no activity for longer than `timeout` as of `now`
+    pub fn idle(&self, now: SystemTime, timeout: Duration) -> Vec<PlayerId> {
+        self.entries
+            .iter()
+            .filter(|(_, last_activity)| now.duration_since(*last_activity).unwrap_or_default() > timeout)
+            .map(|(id, _)| *id)
+            .collect()
+    }
+
+    /// Waiting player ids, front of the queue first
+    pub fn ids(&self) -> impl Iterator<Item = &PlayerId> {
+        self.entries.iter().map(|(id, _)| id)
+    }
+}
@@ -0,0 +1,81 @@
+use serde::{Deserialize, Serialize};
+use crate::config::ComboConfig;
+use crate::player::PlayerId;
+
+/// Something a player did that earns points
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ScoreSource {
+    /// Picked up a coin
+    Coin,
+    /// Tagged another player
+    Tag,
+}
+
+impl ScoreSource {
+    /// Base points before the combo multiplier is applied
+    pub fn base_points(&self, config: &ComboConfig) -> u64 {
+        match self {
+            ScoreSource::Coin => config.coin_points,
+            ScoreSource::Tag => config.tag_points,
+        }
+    }
+}
+
+/// Per-player combo state, serialized with the player for HUD display
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ComboState {
+    /// Consecutive scoring events inside the combo window
+    pub count: u32,
+    /// Multiplier applied to the next scoring event
+    pub multiplier: f32,
+    /// Seconds left before the combo breaks
+    pub time_remaining: f32,
+}
+
+impl Default for ComboState {
+    fn default() -> Self {
+        Self {
+            count: 0,
+            multiplier: 1.0,
+            time_remaining: 0.0,
+        }
+    }
+}
+
+impl ComboState {
+    /// Register a scoring event and return the points it is worth after the multiplier
+    pub fn register(&mut self, source: ScoreSource, config: &ComboConfig) -> u64 {
+        let points = (source.base_points(config) as f32 * self.multiplier).round() as u64;
+
+        // Escalate for the next event in the chain
+        self.count += 1;
+        self.multiplier = (1.0 + config.multiplier_step * self.count as f32).min(config.max_multiplier);
+        self.time_remaining = config.window_secs;
+        points
+    }
+
+    /// Count down the combo window, returning the broken combo if it just expired
+    pub fn decay(&mut self, delta_time: f32) -> Option<ComboState> {
+        if self.count == 0 {
+            return None;
+        }
+        self.time_remaining -= delta_time;
+        if self.time_remaining > 0.0 {
+            return None;
+        }
+        let broken = self.clone();
+        *self = ComboState::default();
+        Some(broken)
+    }
+}
+
+/// Emitted when a player's combo window expires
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ComboBreak {
+    pub player_id: PlayerId,
+    /// Length of the combo that just ended
+    pub count: u32,
+    /// Multiplier the combo had built up when it ended
+    pub multiplier: f32,
+}
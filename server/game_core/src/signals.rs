@@ -0,0 +1,368 @@
+//! Every Datastar signal the server patches into clients, by name
+//!
+//! Signals are only ever sent through [`SignalPatch`], so a name can't drift from this
+//! registry. `/api/signals` serves [`manifest`] for clients to check their own names against.
+
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+/// Bumped whenever a signal is added, removed, renamed or changes shape
+/// Doubles as the minor event protocol version
+pub const SIGNALS_VERSION: u32 = 14;
+
+/// Major event protocol version; bumped only for changes older clients can't ignore
+pub const PROTOCOL_MAJOR: u32 = 1;
+
+/// Majors the server can still speak; streams asking for any other are refused
+pub const SUPPORTED_MAJORS: [u32; 1] = [1];
+
+/// Event protocol version a client speaks, as `MAJOR.MINOR`
+/// Minors only add signals, so a stream leaves out those newer than its client's minor
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ProtocolVersion {
+    pub major: u32,
+    pub minor: u32,
+}
+
+impl ProtocolVersion {
+    /// The version this server speaks
+    pub const CURRENT: ProtocolVersion = ProtocolVersion { major: PROTOCOL_MAJOR, minor: SIGNALS_VERSION };
+
+    /// Read `MAJOR.MINOR`, or a bare `MAJOR` for its latest minor
+    pub fn parse(version: &str) -> Option<Self> {
+        let (major, minor) = match version.trim().split_once('.') {
+            Some((major, minor)) => (major.parse().ok()?, minor.parse().ok()?),
+            None => (version.trim().parse().ok()?, u32::MAX),
+        };
+        Some(Self { major, minor })
+    }
+
+    pub fn is_supported(self) -> bool {
+        SUPPORTED_MAJORS.contains(&self.major)
+    }
+
+    /// Whether a client on this version knows `signal`
+    pub fn knows(self, signal: Signal) -> bool {
+        signal.since() <= self.minor
+    }
+}
+
+impl std::fmt::Display for ProtocolVersion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}.{}", self.major, self.minor)
+    }
+}
+
+/// JSON type of a signal's value
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SignalKind {
+    Object,
+    Array,
+    Number,
+    String,
+}
+
+/// A signal the server sends
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Signal {
+    ResumeToken,
+    Geometry,
+    GeometryDelta,
+    SpectatorCount,
+    MatchState,
+    Map,
+    MapGeometry,
+    Mode,
+    StreamRate,
+    GameState,
+    Projectiles,
+    Tick,
+    ServerTime,
+    TimeScale,
+    WaitingForSlot,
+    ChallengeProgress,
+    ChallengeCompleted,
+    ComboBreak,
+    Damage,
+    LifeEvents,
+    PlayerLeft,
+    ServerShutdown,
+    Playback,
+    WorldEvent,
+    WorldEntities,
+    KillCam,
+    ConfigVersion,
+    ProtocolError,
+    Bounces,
+    Teleports,
+    Race,
+    Tag,
+    Hill,
+}
+
+impl Signal {
+    pub const ALL: [Signal; 33] = [
+        Signal::ResumeToken,
+        Signal::Geometry,
+        Signal::GeometryDelta,
+        Signal::SpectatorCount,
+        Signal::MatchState,
+        Signal::Map,
+        Signal::MapGeometry,
+        Signal::Mode,
+        Signal::StreamRate,
+        Signal::GameState,
+        Signal::Projectiles,
+        Signal::Tick,
+        Signal::ServerTime,
+        Signal::TimeScale,
+        Signal::WaitingForSlot,
+        Signal::ChallengeProgress,
+        Signal::ChallengeCompleted,
+        Signal::ComboBreak,
+        Signal::Damage,
+        Signal::LifeEvents,
+        Signal::PlayerLeft,
+        Signal::ServerShutdown,
+        Signal::Playback,
+        Signal::WorldEvent,
+        Signal::WorldEntities,
+        Signal::KillCam,
+        Signal::ConfigVersion,
+        Signal::ProtocolError,
+        Signal::Bounces,
+        Signal::Teleports,
+        Signal::Race,
+        Signal::Tag,
+        Signal::Hill,
+    ];
+
+    /// Key of the signal in patches
+    pub fn name(self) -> &'static str {
+        match self {
+            Signal::ResumeToken => "resumeToken",
+            Signal::Geometry => "geometry",
+            Signal::GeometryDelta => "geometryDelta",
+            Signal::SpectatorCount => "spectatorCount",
+            Signal::MatchState => "matchState",
+            Signal::Map => "map",
+            Signal::MapGeometry => "mapGeometry",
+            Signal::Mode => "mode",
+            Signal::StreamRate => "streamRate",
+            Signal::GameState => "gameState",
+            Signal::Projectiles => "projectiles",
+            Signal::Tick => "tick",
+            Signal::ServerTime => "serverTime",
+            Signal::TimeScale => "timeScale",
+            Signal::WaitingForSlot => "waitingForSlot",
+            Signal::ChallengeProgress => "challengeProgress",
+            Signal::ChallengeCompleted => "challengeCompleted",
+            Signal::ComboBreak => "comboBreak",
+            Signal::Damage => "damage",
+            Signal::LifeEvents => "lifeEvents",
+            Signal::PlayerLeft => "playerLeft",
+            Signal::ServerShutdown => "serverShutdown",
+            Signal::Playback => "playback",
+            Signal::WorldEvent => "worldEvent",
+            Signal::WorldEntities => "worldEntities",
+            Signal::KillCam => "killCam",
+            Signal::ConfigVersion => "configVersion",
+            Signal::ProtocolError => "protocolError",
+            Signal::Bounces => "bounces",
+            Signal::Teleports => "teleports",
+            Signal::Race => "race",
+            Signal::Tag => "tag",
+            Signal::Hill => "hill",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|signal| signal.name() == name)
+    }
+
+    pub fn kind(self) -> SignalKind {
+        match self {
+            Signal::ResumeToken => SignalKind::String,
+            Signal::SpectatorCount
+            | Signal::StreamRate
+            | Signal::Tick
+            | Signal::ServerTime
+            | Signal::TimeScale
+            | Signal::ConfigVersion => SignalKind::Number,
+            Signal::GameState
+            | Signal::Projectiles
+            | Signal::ChallengeProgress
+            | Signal::ChallengeCompleted
+            | Signal::ComboBreak
+            | Signal::Damage
+            | Signal::LifeEvents
+            | Signal::WorldEntities
+            | Signal::Bounces
+            | Signal::Teleports => SignalKind::Array,
+            Signal::Geometry
+            | Signal::GeometryDelta
+            | Signal::MatchState
+            | Signal::Map
+            | Signal::MapGeometry
+            | Signal::Mode
+            | Signal::WaitingForSlot
+            | Signal::PlayerLeft
+            | Signal::ServerShutdown
+            | Signal::Playback
+            | Signal::WorldEvent
+            | Signal::KillCam
+            | Signal::ProtocolError
+            | Signal::Race
+            | Signal::Tag
+            | Signal::Hill => SignalKind::Object,
+        }
+    }
+
+    /// Whether the signal can be null, e.g. once a condition it reports has cleared
+    pub fn nullable(self) -> bool {
+        matches!(self, Signal::Map | Signal::StreamRate | Signal::WaitingForSlot | Signal::WorldEvent | Signal::Race | Signal::Tag | Signal::Hill)
+    }
+
+    pub fn description(self) -> &'static str {
+        match self {
+            Signal::ResumeToken => "Token for resuming this stream's filter after a reconnect",
+            Signal::Geometry => "Snapshot of player-built blocks, sent on connect and after a missed update",
+            Signal::GeometryDelta => "Blocks placed or removed since a geometry version",
+            Signal::SpectatorCount => "Number of open spectator streams",
+            Signal::MatchState => "Phase of the current match and its deadlines",
+            Signal::Map => "Map being played; null without a rotation",
+            Signal::MapGeometry => "Platforms, walls, ladders, hazards, force zones, portals, checkpoints, the control zone and spawn points after an admin edit",
+            Signal::Mode => "Active game mode and the settings it puts in effect",
+            Signal::StreamRate => "Reduced state rate for a slow subscriber; null at full rate",
+            Signal::GameState => "Every player's state",
+            Signal::Projectiles => "Projectiles in flight",
+            Signal::Tick => "Simulation tick the state was produced on",
+            Signal::ServerTime => "Server Unix time in milliseconds when the state was produced",
+            Signal::TimeScale => "Fraction of normal speed the room runs at; below 1 in slow motion, 0 when paused",
+            Signal::WaitingForSlot => "Queue position while waiting for a slot; null once playing",
+            Signal::ChallengeProgress => "The stream's player's progress on today's challenges",
+            Signal::ChallengeCompleted => "Challenges completed this tick",
+            Signal::ComboBreak => "Combos that expired this tick",
+            Signal::Damage => "Damage taken this tick",
+            Signal::LifeEvents => "Deaths and respawns this tick",
+            Signal::PlayerLeft => "A player left the game",
+            Signal::ServerShutdown => "Seconds until the server shuts down",
+            Signal::Playback => "Progress through a recorded match being played back",
+            Signal::WorldEvent => "Random world event under way; null when none is",
+            Signal::WorldEntities => "Coins and meteors dropped by world events",
+            Signal::KillCam => "The stream's player's last moments before another player killed them",
+            Signal::ConfigVersion => "Version of the settings served by /api/config; clients refetch when it changes",
+            Signal::ProtocolError => "Why the stream was refused, sent alone before it closes",
+            Signal::Bounces => "Players thrown back up by bouncy surfaces this tick",
+            Signal::Teleports => "Players sent through portals this tick, with both ends of each trip",
+            Signal::Race => "Checkpoint progress, lap times and finishing places; null outside race mode",
+            Signal::Tag => "Who is it, who can't be tagged yet and the last tag; null outside tag mode",
+            Signal::Hill => "Who owns the control zone, capture progress and each side's points; null outside king of the hill",
+        }
+    }
+
+    /// Signals version that added the signal
+    pub fn since(self) -> u32 {
+        match self {
+            Signal::ResumeToken
+            | Signal::Geometry
+            | Signal::GeometryDelta
+            | Signal::SpectatorCount
+            | Signal::MatchState
+            | Signal::Map
+            | Signal::StreamRate
+            | Signal::GameState
+            | Signal::Projectiles
+            | Signal::Tick
+            | Signal::ServerTime
+            | Signal::WaitingForSlot
+            | Signal::ChallengeProgress
+            | Signal::ChallengeCompleted
+            | Signal::ComboBreak
+            | Signal::Damage
+            | Signal::LifeEvents
+            | Signal::PlayerLeft
+            | Signal::ServerShutdown => 1,
+            Signal::Mode => 2,
+            Signal::MapGeometry => 3,
+            Signal::Playback => 4,
+            Signal::WorldEvent | Signal::WorldEntities => 5,
+            Signal::KillCam => 6,
+            Signal::ConfigVersion => 7,
+            Signal::TimeScale => 8,
+            Signal::ProtocolError => 9,
+            Signal::Bounces => 10,
+            Signal::Teleports => 11,
+            Signal::Race => 12,
+            Signal::Tag => 13,
+            Signal::Hill => 14,
+        }
+    }
+}
+
+/// Signals for one patch-signals event, keyed by their registered names
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(transparent)]
+pub struct SignalPatch(Map<String, Value>);
+
+impl SignalPatch {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with(mut self, signal: Signal, value: impl Serialize) -> Self {
+        let value = serde_json::to_value(value).expect("signal values serialize to JSON");
+        self.0.insert(signal.name().to_string(), value);
+        self
+    }
+
+    /// The patch without signals a client on `version` doesn't know
+    pub fn for_protocol(mut self, version: ProtocolVersion) -> Self {
+        self.0.retain(|name, _| Signal::from_name(name).is_some_and(|signal| version.knows(signal)));
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+/// One registry entry as listed in the manifest
+#[derive(Debug, Clone, Serialize)]
+pub struct SignalEntry {
+    pub name: &'static str,
+    pub kind: SignalKind,
+    pub nullable: bool,
+    pub description: &'static str,
+    /// Signals version, and so protocol minor, that added it
+    pub since: u32,
+}
+
+/// The registry as served at `/api/signals`
+#[derive(Debug, Clone, Serialize)]
+pub struct SignalManifest {
+    pub version: u32,
+    /// Full event protocol version, `MAJOR.MINOR`
+    pub protocol_version: String,
+    pub supported_majors: Vec<u32>,
+    pub signals: Vec<SignalEntry>,
+}
+
+pub fn manifest() -> SignalManifest {
+    SignalManifest {
+        version: SIGNALS_VERSION,
+        protocol_version: ProtocolVersion::CURRENT.to_string(),
+        supported_majors: SUPPORTED_MAJORS.to_vec(),
+        signals: Signal::ALL
+            .into_iter()
+            .map(|signal| SignalEntry {
+                name: signal.name(),
+                kind: signal.kind(),
+                nullable: signal.nullable(),
+                description: signal.description(),
+                since: signal.since(),
+            })
+            .collect(),
+    }
+}
@@ -0,0 +1,243 @@
+use std::sync::Arc;
+use serde::{Deserialize, Serialize};
+use crate::clock::MockClock;
+use crate::commands::PlayerCommand;
+use crate::config::GameConfig;
+use crate::game_state::GameState;
+use crate::physics::PhysicsWorld;
+use crate::player::{Player, PlayerId};
+
+/// How often a held key is re-sent, matching the web client
+pub const HOLD_REPEAT_SECS: f32 = 0.1;
+
+/// An input a scripted bot performs at a point in time
+#[derive(Debug, Clone)]
+pub enum BotAction {
+    /// Send a command once (e.g. a jump)
+    Press(PlayerCommand),
+    /// Send a command now and keep re-sending it like a held key
+    Hold(PlayerCommand),
+    /// Let go of the held key, sending Stop like the client does on key-up
+    Release,
+}
+
+/// A bot replaying timed inputs the way the web client would send them
+#[derive(Debug, Clone, Default)]
+pub struct ScriptedBot {
+    /// Actions sorted by time in seconds from the start of the simulation
+    script: Vec<(f32, BotAction)>,
+    next: usize,
+    held: Option<PlayerCommand>,
+    last_sent: f32,
+}
+
+impl ScriptedBot {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add an action at `at_secs`; actions may be added in any order
+    pub fn at(mut self, at_secs: f32, action: BotAction) -> Self {
+        self.script.push((at_secs, action));
+        self.script.sort_by(|a, b| a.0.total_cmp(&b.0));
+        self
+    }
+
+    /// Commands to send at time `now`
+    fn poll(&mut self, now: f32) -> Vec<PlayerCommand> {
+        let mut commands = Vec::new();
+        while let Some((at, action)) = self.script.get(self.next) {
+            if *at > now {
+                break;
+            }
+            match action {
+                BotAction::Press(command) => commands.push(command.clone()),
+                BotAction::Hold(command) => {
+                    self.held = Some(command.clone());
+                    self.last_sent = now;
+                    commands.push(command.clone());
+                }
+                BotAction::Release => {
+                    self.held = None;
+                    commands.push(PlayerCommand::Stop);
+                }
+            }
+            self.next += 1;
+        }
+        if let Some(command) = &self.held {
+            if now - self.last_sent >= HOLD_REPEAT_SECS {
+                self.last_sent = now;
+                commands.push(command.clone());
+            }
+        }
+        commands
+    }
+}
+
+/// An input to a simulation, stamped with the tick it was applied before
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum LoggedInput {
+    Join { tick: u64, player_id: PlayerId },
+    Command { tick: u64, player_id: PlayerId, command: PlayerCommand },
+}
+
+/// Everything a simulation was fed, enough to replay it to the same state
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CommandLog {
+    /// Seed the run was made with; replays only match with the same one
+    pub seed: Option<u64>,
+    pub inputs: Vec<LoggedInput>,
+    /// Ticks stepped in total
+    pub ticks: u64,
+}
+
+/// Where seeded simulations start their clock, so timestamps and the challenge day match too
+const DETERMINISTIC_START_SECS: u64 = 1_700_000_000;
+
+/// A headless game room stepped at the configured tick rate, for tools and tests
+/// Its clock advances with the ticks, so cooldowns and timestamps are deterministic
+/// With `determinism.seed` set, the same inputs give the same state on every run
+pub struct Simulation {
+    state: GameState,
+    clock: Arc<MockClock>,
+    dt: f32,
+    time: f32,
+    bots: Vec<(PlayerId, ScriptedBot)>,
+    log: CommandLog,
+}
+
+impl Simulation {
+    pub fn new(config: Arc<GameConfig>) -> Self {
+        let dt = 1.0 / config.tick_rate_hz.max(1.0);
+        let seed = config.determinism.seed;
+        let clock = Arc::new(match seed {
+            Some(_) => MockClock::starting_at(std::time::UNIX_EPOCH + std::time::Duration::from_secs(DETERMINISTIC_START_SECS)),
+            None => MockClock::new(),
+        });
+        let world = Arc::new(PhysicsWorld::new(config));
+        Self {
+            state: GameState::with_clock(world, clock.clone()),
+            clock,
+            dt,
+            time: 0.0,
+            bots: Vec::new(),
+            log: CommandLog { seed, ..CommandLog::default() },
+        }
+    }
+
+    /// Run a recorded log from the start; with the log's seed the result matches the original run
+    pub fn replay(config: &GameConfig, log: &CommandLog) -> Self {
+        let mut config = config.clone();
+        config.determinism.seed = log.seed;
+        let mut sim = Self::new(Arc::new(config));
+        let mut inputs = log.inputs.iter().peekable();
+        for tick in 0..=log.ticks {
+            while let Some(input) = inputs.next_if(|input| input.tick() == tick) {
+                match input {
+                    LoggedInput::Join { player_id, .. } => sim.join(*player_id),
+                    LoggedInput::Command { player_id, command, .. } => sim.command(player_id, command),
+                }
+            }
+            if tick < log.ticks {
+                sim.step();
+            }
+        }
+        sim
+    }
+
+    /// Add a player controlled through `command`
+    pub fn add_player(&mut self) -> PlayerId {
+        let id = self.state.new_player_id();
+        self.state.add_player(id);
+        self.log.inputs.push(LoggedInput::Join { tick: self.state.tick, player_id: id });
+        id
+    }
+
+    /// Add a player with a logged id, drawing from the RNG as `add_player` would
+    fn join(&mut self, player_id: PlayerId) {
+        self.state.new_player_id();
+        self.state.add_player(player_id);
+        self.log.inputs.push(LoggedInput::Join { tick: self.state.tick, player_id });
+    }
+
+    /// Add a player driven by a script
+    pub fn add_bot(&mut self, bot: ScriptedBot) -> PlayerId {
+        let id = self.add_player();
+        self.bots.push((id, bot));
+        id
+    }
+
+    /// Send a command as if it arrived from the player's client
+    pub fn command(&mut self, player_id: &PlayerId, command: &PlayerCommand) {
+        self.state.apply_command(player_id, command, 0);
+        self.log.inputs.push(LoggedInput::Command {
+            tick: self.state.tick,
+            player_id: *player_id,
+            command: command.clone(),
+        });
+    }
+
+    /// Advance one fixed tick: bots send their due inputs, then the world steps
+    pub fn step(&mut self) {
+        let mut due = Vec::new();
+        for (id, bot) in &mut self.bots {
+            due.extend(bot.poll(self.time).into_iter().map(|command| (*id, command)));
+        }
+        for (id, command) in due {
+            self.command(&id, &command);
+        }
+        self.state.update(self.dt);
+        self.clock.advance(std::time::Duration::from_secs_f32(self.dt));
+        self.time += self.dt;
+        self.log.ticks += 1;
+    }
+
+    /// Step until `done` returns true or `max_secs` of simulated time pass
+    /// Returns whether `done` was reached
+    pub fn run_until(&mut self, max_secs: f32, mut done: impl FnMut(&Simulation) -> bool) -> bool {
+        let deadline = self.time + max_secs;
+        while self.time < deadline {
+            self.step();
+            if done(self) {
+                return true;
+            }
+        }
+        false
+    }
+
+    pub fn player(&self, player_id: &PlayerId) -> Option<&Player> {
+        self.state.players.get(player_id)
+    }
+
+    pub fn player_mut(&mut self, player_id: &PlayerId) -> Option<&mut Player> {
+        self.state.players.get_mut(player_id)
+    }
+
+    pub fn state(&self) -> &GameState {
+        &self.state
+    }
+
+    /// Everything fed to the simulation so far
+    pub fn log(&self) -> &CommandLog {
+        &self.log
+    }
+
+    /// Simulated seconds since the start
+    pub fn time(&self) -> f32 {
+        self.time
+    }
+
+    /// Seconds per tick
+    pub fn dt(&self) -> f32 {
+        self.dt
+    }
+}
+
+impl LoggedInput {
+    pub fn tick(&self) -> u64 {
+        match self {
+            LoggedInput::Join { tick, .. } | LoggedInput::Command { tick, .. } => *tick,
+        }
+    }
+}
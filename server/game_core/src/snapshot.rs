@@ -0,0 +1,117 @@
+//! Saving the live world to a file and loading it back, across restarts or onto another server
+//!
+//! A snapshot holds what players would notice going missing: where everyone is, their scores
+//! and health, built blocks, projectiles in flight, the match and its time left, the map, mode
+//! and mutators being played, the round's progress under the mode's rules, slow motion under
+//! way, and the physics as patched. Per-connection and per-day bookkeeping (cooldowns, votes,
+//! challenge progress, analytics) starts afresh.
+//!
+//! Snapshots are written as JSON or MessagePack, the two `Encoding`s. There is no bincode
+//! form: it can't read back the internally tagged enums and skipped optional fields the
+//! snapshot is made of, and MessagePack is already the compact choice.
+
+use serde::{Deserialize, Serialize};
+use crate::blocks::Block;
+use crate::config::{MapGeometry, PhysicsConfig, ValidationErrors};
+use crate::encoding::Encoding;
+use crate::game_state::SlowMotion;
+use crate::match_state::MatchState;
+use crate::mutators::Mutator;
+use crate::player::{Player, PlayerId, PlayerInternals};
+use crate::projectiles::Projectile;
+
+/// Bumped whenever the snapshot layout changes in a way older servers can't read
+pub const SNAPSHOT_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorldSnapshot {
+    pub version: u32,
+    pub tick: u64,
+    /// Unix milliseconds by the game clock; match deadlines are shifted by the time since
+    pub taken_at_ms: u64,
+    /// Game mode id
+    pub mode: String,
+    pub mutators: Vec<Mutator>,
+    /// Index of the map in the rotation
+    pub map_index: usize,
+    /// The map as played, including admin edits
+    pub geometry: MapGeometry,
+    pub match_state: MatchState,
+    /// Ordered by id
+    pub players: Vec<Player>,
+    pub blocks: Vec<Block>,
+    pub projectiles: Vec<Projectile>,
+    pub next_projectile_id: u64,
+    /// Where the seeded RNG is up to, so a seeded run carries on drawing the same numbers
+    pub rng_state: u64,
+    /// What players carry that isn't serialized with them, such as dashes under way
+    #[serde(default)]
+    pub player_internals: Vec<PlayerInternals>,
+    /// Progress of the round under the mode's rules (race laps, who is it, the hill's owner)
+    #[serde(default)]
+    pub rules_state: serde_json::Value,
+    /// Physics as the room creator patched it; older snapshots leave the config's
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub physics: Option<PhysicsConfig>,
+    #[serde(default)]
+    pub config_version: u64,
+    #[serde(default)]
+    pub room_creator: Option<PlayerId>,
+    /// Room-wide slow motion under way; older snapshots have none
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub slow_motion: Option<SlowMotion>,
+}
+
+/// Why a snapshot couldn't be read or restored
+#[derive(Debug, Clone, PartialEq)]
+pub enum SnapshotError {
+    Decode(String),
+    /// Written by a newer server
+    UnsupportedVersion(u32),
+    /// The snapshot's game mode isn't configured on this server
+    UnknownMode(String),
+    /// The snapshot's map fails the checks a config's would
+    InvalidGeometry(ValidationErrors),
+    /// A match deadline too far in the future to move on by the time since the snapshot
+    InvalidDeadline,
+}
+
+impl std::fmt::Display for SnapshotError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SnapshotError::Decode(e) => write!(f, "snapshot can't be read: {}", e),
+            SnapshotError::UnsupportedVersion(version) => write!(f, "unsupported snapshot version {}", version),
+            SnapshotError::UnknownMode(mode) => write!(f, "game mode {} is not configured here", mode),
+            SnapshotError::InvalidGeometry(errors) => {
+                let errors: Vec<String> = errors.0.iter().map(|e| e.to_string()).collect();
+                write!(f, "snapshot map is invalid: {}", errors.join("; "))
+            }
+            SnapshotError::InvalidDeadline => write!(f, "snapshot match deadline is out of range"),
+        }
+    }
+}
+
+impl std::error::Error for SnapshotError {}
+
+impl WorldSnapshot {
+    pub fn encode(&self, format: Encoding) -> Vec<u8> {
+        match format {
+            Encoding::Json => serde_json::to_vec_pretty(self).expect("snapshots serialize to JSON"),
+            // Fields by name, so the tagged enums and optional fields read back as in JSON
+            Encoding::MessagePack => rmp_serde::to_vec_named(self).expect("snapshots serialize to MessagePack"),
+        }
+    }
+
+    pub fn decode(bytes: &[u8], format: Encoding) -> Result<Self, SnapshotError> {
+        let snapshot: Self = match format {
+            Encoding::Json => serde_json::from_slice(bytes).map_err(|e| SnapshotError::Decode(e.to_string()))?,
+            Encoding::MessagePack => {
+                rmp_serde::from_slice(bytes).map_err(|e| SnapshotError::Decode(e.to_string()))?
+            }
+        };
+        if snapshot.version > SNAPSHOT_VERSION {
+            return Err(SnapshotError::UnsupportedVersion(snapshot.version));
+        }
+        Ok(snapshot)
+    }
+}
@@ -0,0 +1,78 @@
+use serde::{Deserialize, Serialize};
+use crate::player::Player;
+
+/// A temporary change to how fast a player's stamina drains and refills, from a pickup,
+/// a hazard or a game mode rule
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StaminaEffect {
+    /// Names the effect; applying one with the same id replaces it instead of stacking
+    pub id: String,
+    /// Multiplies every stamina cost; 0 makes sprinting, dashing and wall jumps free
+    #[serde(default = "one")]
+    pub cost_multiplier: f32,
+    /// Multiplies regeneration; 0 stops it
+    #[serde(default = "one")]
+    pub regen_multiplier: f32,
+    /// Seconds left; None lasts until removed
+    #[serde(default)]
+    pub remaining_secs: Option<f32>,
+}
+
+fn one() -> f32 {
+    1.0
+}
+
+impl Player {
+    /// Spend `cost` stamina, scaled by active effects, if the player has that much
+    pub fn try_spend_stamina(&mut self, cost: f32) -> bool {
+        let cost = cost * self.stamina_cost_multiplier();
+        if self.stamina < cost {
+            return false;
+        }
+        self.stamina -= cost;
+        true
+    }
+
+    /// Drain up to `cost` stamina for a continuous action; false once the pool is empty
+    pub fn drain_stamina(&mut self, cost: f32) -> bool {
+        self.stamina = (self.stamina - cost * self.stamina_cost_multiplier()).max(0.0);
+        self.stamina > 0.0
+    }
+
+    /// Regain `amount` stamina, scaled by active effects, up to `max`
+    pub fn regen_stamina(&mut self, amount: f32, max: f32) {
+        self.stamina = (self.stamina + amount * self.stamina_regen_multiplier()).min(max);
+    }
+
+    /// Apply an effect, replacing any active effect with the same id
+    pub fn add_stamina_effect(&mut self, effect: StaminaEffect) {
+        self.remove_stamina_effect(&effect.id);
+        self.stamina_effects.push(effect);
+    }
+
+    /// Remove an effect by id; returns whether it was active
+    pub fn remove_stamina_effect(&mut self, id: &str) -> bool {
+        let before = self.stamina_effects.len();
+        self.stamina_effects.retain(|effect| effect.id != id);
+        self.stamina_effects.len() != before
+    }
+
+    /// Count down timed effects, dropping those that ran out
+    pub fn tick_stamina_effects(&mut self, delta_time: f32) {
+        self.stamina_effects.retain_mut(|effect| match &mut effect.remaining_secs {
+            Some(secs) => {
+                *secs -= delta_time;
+                *secs > 0.0
+            }
+            None => true,
+        });
+    }
+
+    fn stamina_cost_multiplier(&self) -> f32 {
+        self.stamina_effects.iter().map(|effect| effect.cost_multiplier.max(0.0)).product()
+    }
+
+    fn stamina_regen_multiplier(&self) -> f32 {
+        self.stamina_effects.iter().map(|effect| effect.regen_multiplier.max(0.0)).product()
+    }
+}
@@ -0,0 +1,162 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use crate::config::{GameConfig, GameRules};
+use crate::game_mode::{GameMode, ModeContext};
+use crate::player::{Player, PlayerId};
+use crate::signals::Signal;
+
+/// The tag passing from one player to another
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TagPass {
+    /// Who was it, and is now immune for a while
+    pub from: PlayerId,
+    /// Who is it now
+    pub to: PlayerId,
+}
+
+/// Who is it, as sent to clients
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TagStatus {
+    pub it: Option<PlayerId>,
+    /// Players who can't be tagged yet, having just passed the tag on
+    pub immune: Vec<PlayerId>,
+    /// The last time the tag changed hands
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_pass: Option<TagPass>,
+}
+
+/// Tag rules: one player is it and passes it on by touching someone; everyone else
+/// scores for the time they spend not it
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TagMode {
+    it: Option<PlayerId>,
+    /// Seconds of immunity left for players who just passed the tag on
+    immunity: HashMap<PlayerId, f32>,
+    /// Fractional points earned by players who aren't it, paid out in whole points
+    owed: HashMap<PlayerId, f32>,
+    last_pass: Option<TagPass>,
+    /// The tag already changed hands this tick
+    #[serde(skip)]
+    passed: bool,
+    /// Whether the tag changed hands since the scoreboard was last taken
+    #[serde(skip)]
+    changed: bool,
+}
+
+impl TagMode {
+    pub fn it(&self) -> Option<PlayerId> {
+        self.it
+    }
+
+    /// Make a random player it
+    fn pick_it(&mut self, ctx: &mut ModeContext) {
+        let ids = ctx.player_ids();
+        self.it = (!ids.is_empty()).then(|| ids[(ctx.rng.next_u64() % ids.len() as u64) as usize]);
+        self.changed = true;
+    }
+}
+
+impl GameMode for TagMode {
+    fn rules(&self) -> GameRules {
+        GameRules::TAG
+    }
+
+    fn on_round_start(&mut self, ctx: &mut ModeContext) {
+        self.reset();
+        self.pick_it(ctx);
+    }
+
+    fn on_tick(&mut self, ctx: &mut ModeContext, delta_time: f32) {
+        self.passed = false;
+        if self.it.is_none_or(|it| !ctx.players.contains_key(&it)) {
+            self.pick_it(ctx);
+        }
+        self.immunity.retain(|_, secs| {
+            *secs -= delta_time;
+            *secs > 0.0
+        });
+
+        let config = &ctx.config.tag;
+        for player_id in ctx.player_ids() {
+            if Some(player_id) == self.it {
+                continue;
+            }
+            let owed = self.owed.entry(player_id).or_default();
+            *owed += config.points_per_sec * delta_time;
+            let whole = owed.floor();
+            *owed -= whole;
+            if let Some(player) = ctx.players.get_mut(&player_id) {
+                player.score += whole as u64;
+            }
+        }
+    }
+
+    /// It touching someone who isn't immune passes the tag on, once a tick
+    fn on_collision(&mut self, ctx: &mut ModeContext, a: &PlayerId, b: &PlayerId) {
+        let Some(it) = self.it.filter(|_| !self.passed) else {
+            return;
+        };
+        let tagged = match it {
+            it if it == *a => *b,
+            it if it == *b => *a,
+            _ => return,
+        };
+        if self.immunity.contains_key(&tagged) {
+            return;
+        }
+        self.immunity.insert(it, ctx.config.tag.immunity_secs);
+        self.owed.remove(&tagged);
+        self.it = Some(tagged);
+        self.last_pass = Some(TagPass { from: it, to: tagged });
+        self.passed = true;
+        self.changed = true;
+    }
+
+    fn on_player_leave(&mut self, player_id: &PlayerId) {
+        self.immunity.remove(player_id);
+        self.owed.remove(player_id);
+        if self.it == Some(*player_id) {
+            // Someone else is picked next tick
+            self.it = None;
+            self.changed = true;
+        }
+    }
+
+    fn reset(&mut self) {
+        let changed = self.changed || self.it.is_some();
+        *self = TagMode { changed, ..Default::default() };
+    }
+
+    fn signal(&self) -> Option<Signal> {
+        Some(Signal::Tag)
+    }
+
+    fn status(&self, _players: &HashMap<PlayerId, Player>, _config: &GameConfig) -> serde_json::Value {
+        let mut immune: Vec<PlayerId> = self.immunity.keys().copied().collect();
+        immune.sort();
+        serde_json::to_value(TagStatus {
+            it: self.it,
+            immune,
+            last_pass: self.last_pass,
+        })
+        .unwrap_or_default()
+    }
+
+    fn take_changed(&mut self) -> bool {
+        std::mem::take(&mut self.changed)
+    }
+
+    fn save_state(&self) -> serde_json::Value {
+        serde_json::to_value(self).unwrap_or_default()
+    }
+
+    fn load_state(&mut self, state: &serde_json::Value) {
+        if let Ok(saved) = TagMode::deserialize(state) {
+            *self = TagMode { changed: true, ..saved };
+        }
+    }
+
+    fn clone_box(&self) -> Box<dyn GameMode> {
+        Box::new(self.clone())
+    }
+}
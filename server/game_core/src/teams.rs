@@ -0,0 +1,83 @@
+use std::collections::HashMap;
+use std::fmt;
+use crate::config::{TeamDefinition, TeamsConfig};
+use crate::player::{Player, PlayerId};
+
+pub type TeamId = String;
+
+/// Reasons a team change is rejected
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TeamError {
+    /// No teams are configured
+    Disabled,
+    /// No team has the requested id
+    UnknownTeam,
+    /// Player is not in the game
+    UnknownPlayer,
+    /// Joining would leave the teams further apart than allowed
+    Unbalanced,
+}
+
+impl fmt::Display for TeamError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let reason = match self {
+            TeamError::Disabled => "teams are disabled",
+            TeamError::UnknownTeam => "no such team",
+            TeamError::UnknownPlayer => "player is not in the game",
+            TeamError::Unbalanced => "that team is full, pick another",
+        };
+        write!(f, "{}", reason)
+    }
+}
+
+impl std::error::Error for TeamError {}
+
+pub fn find<'a>(config: &'a TeamsConfig, team_id: &str) -> Option<&'a TeamDefinition> {
+    config.teams.iter().find(|team| team.id == team_id)
+}
+
+/// Member count of every configured team, in config order
+pub fn team_sizes(config: &TeamsConfig, players: &HashMap<PlayerId, Player>) -> Vec<(TeamId, usize)> {
+    config
+        .teams
+        .iter()
+        .map(|team| {
+            let size = players.values().filter(|p| p.team.as_deref() == Some(team.id.as_str())).count();
+            (team.id.clone(), size)
+        })
+        .collect()
+}
+
+/// The team with the fewest members, earliest in config order on ties
+pub fn balanced_team(config: &TeamsConfig, players: &HashMap<PlayerId, Player>) -> Option<TeamId> {
+    team_sizes(config, players)
+        .into_iter()
+        .min_by_key(|(_, size)| *size)
+        .map(|(team_id, _)| team_id)
+}
+
+/// Whether moving a player from `from` to `to` keeps the teams within `max_imbalance`
+/// A move that narrows the gap is always allowed, so uneven teams can even out
+pub fn move_allowed(sizes: &[(TeamId, usize)], from: Option<&str>, to: &str, max_imbalance: usize) -> bool {
+    if max_imbalance == 0 {
+        return true;
+    }
+    let spread = |sizes: &[usize]| {
+        sizes.iter().max().copied().unwrap_or(0) - sizes.iter().min().copied().unwrap_or(0)
+    };
+    let before: Vec<usize> = sizes.iter().map(|(_, size)| *size).collect();
+    let after: Vec<usize> = sizes
+        .iter()
+        .map(|(team_id, size)| {
+            let mut size = *size;
+            if Some(team_id.as_str()) == from {
+                size = size.saturating_sub(1);
+            }
+            if team_id == to {
+                size += 1;
+            }
+            size
+        })
+        .collect();
+    spread(&after) <= max_imbalance || spread(&after) < spread(&before)
+}
@@ -0,0 +1,283 @@
+//! Random world events: coin rain, meteor showers and speed frenzies
+//!
+//! The scheduler draws from the room's seeded RNG, so a seeded run has the same events at the
+//! same ticks. Coin rain and meteor showers drop short-lived entities into the world; a speed
+//! frenzy gives every player a stamina status effect for as long as it lasts.
+
+use serde::{Deserialize, Serialize};
+use crate::config::{GameConfig, PlatformConfig, WorldEventsConfig};
+use crate::player::PlayerId;
+use crate::rng::SeededRng;
+
+/// Status effect id a speed frenzy gives every player
+pub const SPEED_FRENZY_EFFECT: &str = "speed_frenzy";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WorldEventKind {
+    /// Coins fall from the sky for players to collect
+    CoinRain,
+    /// Meteors fall from the sky and hurt whoever they hit
+    MeteorShower,
+    /// Sprinting and dashing cost no stamina and it refills faster
+    SpeedFrenzy,
+}
+
+impl WorldEventKind {
+    pub const ALL: [WorldEventKind; 3] = [WorldEventKind::CoinRain, WorldEventKind::MeteorShower, WorldEventKind::SpeedFrenzy];
+
+    pub fn id(&self) -> &'static str {
+        match self {
+            WorldEventKind::CoinRain => "coin_rain",
+            WorldEventKind::MeteorShower => "meteor_shower",
+            WorldEventKind::SpeedFrenzy => "speed_frenzy",
+        }
+    }
+
+    /// Display name, for logs and as the English banner text
+    pub fn label(&self) -> &'static str {
+        match self {
+            WorldEventKind::CoinRain => "Coin Rain",
+            WorldEventKind::MeteorShower => "Meteor Shower",
+            WorldEventKind::SpeedFrenzy => "Speed Frenzy",
+        }
+    }
+
+    /// Look an event up by id or display name, ignoring case
+    pub fn parse(name: &str) -> Option<Self> {
+        let name = name.trim();
+        Self::ALL
+            .into_iter()
+            .find(|kind| kind.id().eq_ignore_ascii_case(name) || kind.label().eq_ignore_ascii_case(name))
+    }
+}
+
+/// The event under way
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ActiveWorldEvent {
+    pub kind: WorldEventKind,
+    pub duration_secs: f32,
+    pub remaining_secs: f32,
+}
+
+/// An event starting or ending, for broadcasting
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum WorldEventChange {
+    Started(ActiveWorldEvent),
+    Ended { kind: WorldEventKind },
+}
+
+/// Why an event couldn't be started
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WorldEventError {
+    /// Another event is still under way
+    AlreadyActive,
+}
+
+impl std::fmt::Display for WorldEventError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            WorldEventError::AlreadyActive => write!(f, "another world event is under way"),
+        }
+    }
+}
+
+impl std::error::Error for WorldEventError {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EntityKind {
+    Coin,
+    Meteor,
+}
+
+/// Something an event dropped into the world, broadcast with the game state
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorldEntity {
+    pub id: u64,
+    pub kind: EntityKind,
+    pub x: f32,
+    pub y: f32,
+    pub velocity_y: f32,
+    /// Seconds a landed coin stays before vanishing
+    #[serde(skip)]
+    pub lifetime_secs: f32,
+}
+
+/// A player reaching an entity this step
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EntityContact {
+    pub entity_id: u64,
+    pub kind: EntityKind,
+    pub player_id: PlayerId,
+}
+
+impl WorldEntity {
+    /// Fall for `delta_time` seconds; returns false once the entity is gone
+    /// Coins come to rest on whatever they land on; meteors break up on impact
+    fn advance(&mut self, delta_time: f32, config: &GameConfig, platforms: &[PlatformConfig]) -> bool {
+        let settings = &config.world_events;
+        if self.velocity_y == 0.0 {
+            self.lifetime_secs -= delta_time;
+            return self.lifetime_secs > 0.0;
+        }
+        let previous_bottom = self.y - settings.entity_radius;
+        self.y += self.velocity_y * delta_time;
+        let bottom = self.y - settings.entity_radius;
+        // The highest surface passed through this step, so fast meteors can't tunnel
+        let landed_on = platforms
+            .iter()
+            .filter(|p| self.x > p.x_start && self.x < p.x_end && previous_bottom >= p.y_top && bottom <= p.y_top)
+            .map(|p| p.y_top)
+            .chain(Some(config.physics.ground_at(self.x)).filter(|ground| bottom <= *ground))
+            .reduce(f32::max);
+        let Some(surface) = landed_on else {
+            return crate::respawn::outside_map(self.x, self.y, config).is_none();
+        };
+        match self.kind {
+            EntityKind::Meteor => false,
+            EntityKind::Coin => {
+                self.y = surface + settings.entity_radius;
+                self.velocity_y = 0.0;
+                true
+            }
+        }
+    }
+
+    fn touches(&self, radius: f32, x: f32, y: f32, half_width: f32, height: f32) -> bool {
+        (self.x - x).abs() <= half_width + radius && (self.y - y).abs() <= height / 2.0 + radius
+    }
+}
+
+/// Schedules events and keeps the entities they drop
+#[derive(Debug, Clone, Default)]
+pub struct WorldEvents {
+    pub active: Option<ActiveWorldEvent>,
+    pub entities: Vec<WorldEntity>,
+    /// Seconds until the scheduler starts the next event; drawn once the previous one ends
+    next_in_secs: Option<f32>,
+    next_entity_id: u64,
+    /// Entities owed by the spawn rate but not yet dropped
+    spawn_owed: f32,
+    /// Events started or ended since the last drain, for broadcasting
+    changes: Vec<WorldEventChange>,
+}
+
+impl WorldEvents {
+    /// Count down to the next event; returns the kind to start when it's due
+    /// `allowed` pauses the countdown, e.g. while match results are shown
+    pub fn schedule(&mut self, delta_time: f32, config: &WorldEventsConfig, rng: &mut SeededRng, allowed: bool) -> Option<WorldEventKind> {
+        if !config.enabled || config.kinds.is_empty() || self.active.is_some() || !allowed {
+            return None;
+        }
+        let next = self
+            .next_in_secs
+            .get_or_insert_with(|| rng.range(config.min_interval_secs, config.max_interval_secs));
+        *next -= delta_time;
+        if *next > 0.0 {
+            return None;
+        }
+        self.next_in_secs = None;
+        let index = ((rng.next_f32() * config.kinds.len() as f32) as usize).min(config.kinds.len() - 1);
+        Some(config.kinds[index])
+    }
+
+    pub fn start(&mut self, kind: WorldEventKind, config: &WorldEventsConfig) -> Result<(), WorldEventError> {
+        if self.active.is_some() {
+            return Err(WorldEventError::AlreadyActive);
+        }
+        let event = ActiveWorldEvent {
+            kind,
+            duration_secs: config.duration_secs,
+            remaining_secs: config.duration_secs,
+        };
+        self.changes.push(WorldEventChange::Started(event.clone()));
+        self.active = Some(event);
+        self.spawn_owed = 0.0;
+        self.next_in_secs = None;
+        Ok(())
+    }
+
+    /// Run the active event for `delta_time`, dropping its entities; returns the kind of an
+    /// event that ended this step
+    pub fn advance(&mut self, delta_time: f32, config: &WorldEventsConfig, rng: &mut SeededRng) -> Option<WorldEventKind> {
+        let active = self.active.as_mut()?;
+        active.remaining_secs -= delta_time;
+        let (kind, ended) = (active.kind, active.remaining_secs <= 0.0);
+        let drops = match kind {
+            WorldEventKind::CoinRain => Some((EntityKind::Coin, config.coins_per_sec, config.coin_fall_speed)),
+            WorldEventKind::MeteorShower => Some((EntityKind::Meteor, config.meteors_per_sec, config.meteor_fall_speed)),
+            WorldEventKind::SpeedFrenzy => None,
+        };
+        if let Some((entity_kind, rate, speed)) = drops {
+            self.spawn_owed += rate.max(0.0) * delta_time;
+            while self.spawn_owed >= 1.0 {
+                self.spawn_owed -= 1.0;
+                self.next_entity_id += 1;
+                self.entities.push(WorldEntity {
+                    id: self.next_entity_id,
+                    kind: entity_kind,
+                    x: rng.range(config.spawn_min_x, config.spawn_max_x),
+                    y: config.spawn_y,
+                    velocity_y: -speed.abs().max(0.1),
+                    lifetime_secs: config.coin_lifetime_secs,
+                });
+            }
+        }
+        if !ended {
+            return None;
+        }
+        self.active = None;
+        self.changes.push(WorldEventChange::Ended { kind });
+        Some(kind)
+    }
+
+    /// Move every entity and take the ones players reached
+    /// `players` are the centers and heights of living players, in id order
+    pub fn move_entities(
+        &mut self,
+        delta_time: f32,
+        config: &GameConfig,
+        platforms: &[PlatformConfig],
+        players: &[(PlayerId, f32, f32, f32)],
+    ) -> Vec<EntityContact> {
+        let radius = config.world_events.entity_radius;
+        let half_width = config.physics.player_width / 2.0;
+        let mut contacts = Vec::new();
+        self.entities.retain_mut(|entity| {
+            if !entity.advance(delta_time, config, platforms) {
+                return false;
+            }
+            let hit = players.iter().find(|(_, x, y, height)| entity.touches(radius, *x, *y, half_width, *height));
+            match hit {
+                Some((player_id, ..)) => {
+                    contacts.push(EntityContact {
+                        entity_id: entity.id,
+                        kind: entity.kind,
+                        player_id: *player_id,
+                    });
+                    false
+                }
+                None => true,
+            }
+        });
+        contacts
+    }
+
+    /// Take events started or ended since the last call, for broadcasting
+    pub fn drain_changes(&mut self) -> Vec<WorldEventChange> {
+        std::mem::take(&mut self.changes)
+    }
+
+    /// End whatever is under way and clear the world, e.g. when the map changes
+    pub fn reset(&mut self) {
+        if let Some(active) = self.active.take() {
+            self.changes.push(WorldEventChange::Ended { kind: active.kind });
+        }
+        self.entities.clear();
+        self.spawn_owed = 0.0;
+        self.next_in_secs = None;
+    }
+}
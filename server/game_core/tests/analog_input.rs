@@ -0,0 +1,64 @@
+mod common;
+
+use std::sync::Arc;
+use game_core::{CommandOutcome, IgnoredReason, PhysicsWorld, Player, PlayerCommand};
+
+fn world() -> PhysicsWorld {
+    PhysicsWorld::new(Arc::new(common::open_ground()))
+}
+
+/// Velocity after one command from standing still
+fn velocity_after(world: &PhysicsWorld, command: PlayerCommand) -> (f32, Player) {
+    let mut player = Player::new(uuid::Uuid::nil(), &world.config().physics);
+    assert_eq!(world.apply_command(&mut player, &command), CommandOutcome::Accepted);
+    (player.velocity_x, player)
+}
+
+#[test]
+fn a_stick_scales_acceleration_and_full_tilt_matches_the_digital_commands() {
+    let world = world();
+    let (right, _) = velocity_after(&world, PlayerCommand::MoveRight);
+    let (left, _) = velocity_after(&world, PlayerCommand::MoveLeft);
+    assert!(right > 0.0);
+    assert_eq!(velocity_after(&world, PlayerCommand::Move { axis: 1.0 }).0, right);
+    assert_eq!(velocity_after(&world, PlayerCommand::Move { axis: -1.0 }).0, left);
+
+    let (half, player) = velocity_after(&world, PlayerCommand::Move { axis: -0.5 });
+    assert!((half - left / 2.0).abs() < 1e-5, "half tilt gave {}", half);
+    assert!(!player.facing_right);
+}
+
+#[test]
+fn axes_out_of_range_are_clamped_and_a_centered_stick_does_nothing() {
+    let world = world();
+    let (right, _) = velocity_after(&world, PlayerCommand::MoveRight);
+    assert_eq!(velocity_after(&world, PlayerCommand::Move { axis: 40.0 }).0, right);
+    assert_eq!(velocity_after(&world, PlayerCommand::Move { axis: f32::NAN }).0, 0.0);
+
+    let mut player = Player::new(uuid::Uuid::nil(), &world.config().physics);
+    world.apply_command(&mut player, &PlayerCommand::MoveLeft);
+    let velocity = player.velocity_x;
+    world.apply_command(&mut player, &PlayerCommand::Move { axis: 0.0 });
+    assert_eq!(player.velocity_x, velocity);
+    assert!(!player.facing_right);
+}
+
+#[test]
+fn sticks_steer_no_more_than_keys_in_the_air() {
+    let world = world();
+    let mut player = Player::new(uuid::Uuid::nil(), &world.config().physics);
+    player.y += 5.0;
+    player.ground_state = game_core::GroundState::Flying;
+    assert_eq!(
+        world.apply_command(&mut player, &PlayerCommand::Move { axis: 0.7 }),
+        CommandOutcome::ignored(IgnoredReason::Flying)
+    );
+}
+
+#[test]
+fn moves_arrive_as_json_with_their_axis() {
+    let command: PlayerCommand = serde_json::from_str(r#"{ "type": "Move", "axis": 0.25 }"#).unwrap();
+    assert_eq!(command, PlayerCommand::Move { axis: 0.25 });
+    assert_eq!(command.move_axis(), Some(0.25));
+    assert_eq!(PlayerCommand::Jump.move_axis(), None);
+}
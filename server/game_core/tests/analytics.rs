@@ -0,0 +1,47 @@
+use std::sync::Arc;
+use game_core::config::MapBounds;
+use game_core::{Clock, GameConfig, GameState, MockClock, OverlayStats, PhysicsWorld};
+
+#[test]
+fn apm_window_lead_changes_and_density() {
+    let mut config = GameConfig::default();
+    config.respawn.bounds = Some(MapBounds {
+        min_x: -10.0,
+        max_x: 10.0,
+        max_y: 50.0,
+    });
+    let clock = Arc::new(MockClock::new());
+    let mut state = GameState::with_clock(Arc::new(PhysicsWorld::new(Arc::new(config))), clock.clone());
+    let (a, b, c) = (uuid::Uuid::new_v4(), uuid::Uuid::new_v4(), uuid::Uuid::new_v4());
+    for id in [a, b, c] {
+        state.add_player(id);
+    }
+
+    let start = clock.unix_millis();
+    state.analytics.record_action(a, start);
+    state.analytics.record_action(a, start + 30_000);
+    state.analytics.record_action(a, start + 61_000);
+    assert_eq!(state.analytics.apm(&a, start + 61_000), 2);
+    assert_eq!(state.analytics.apm(&b, start + 61_000), 0);
+
+    // The first leader isn't a change, and a tie keeps the lead where it was
+    state.players.get_mut(&a).unwrap().score = 5;
+    state.analytics.observe_scores(&state.players);
+    state.players.get_mut(&b).unwrap().score = 5;
+    state.analytics.observe_scores(&state.players);
+    assert_eq!(state.analytics.lead_changes(), 0);
+    state.players.get_mut(&b).unwrap().score = 6;
+    state.analytics.observe_scores(&state.players);
+    state.players.get_mut(&a).unwrap().score = 9;
+    state.analytics.observe_scores(&state.players);
+    assert_eq!(state.analytics.lead_changes(), 2);
+    assert_eq!(state.analytics.leader(), Some(a));
+
+    state.players.get_mut(&a).unwrap().x = -9.0;
+    state.players.get_mut(&b).unwrap().x = 9.5;
+    state.players.get_mut(&c).unwrap().x = 40.0;
+    let stats = OverlayStats::of(&state, start + 61_000, 4);
+    assert_eq!(stats.density, vec![1, 0, 0, 2]);
+    assert_eq!(stats.players[0].id, a);
+    assert_eq!(stats.players[0].apm, 2);
+}
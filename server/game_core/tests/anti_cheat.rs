@@ -0,0 +1,127 @@
+use std::sync::Arc;
+use game_core::config::AntiCheatConfig;
+use game_core::{GameConfig, GameState, PhysicsWorld, PlayerCommand, Suspicion, Verdict};
+
+const DT: f32 = 1.0 / 60.0;
+
+fn room(anti_cheat: AntiCheatConfig) -> (GameState, uuid::Uuid) {
+    let config = GameConfig {
+        tick_rate_hz: 60.0,
+        anti_cheat,
+        ..GameConfig::default()
+    };
+    let mut state = GameState::new(Arc::new(PhysicsWorld::new(Arc::new(config))));
+    let player = state.new_player_id();
+    state.add_player(player);
+    // Let them land and lose spawn protection before anything is judged
+    run(&mut state, 120);
+    (state, player)
+}
+
+fn run(state: &mut GameState, ticks: usize) {
+    for _ in 0..ticks {
+        state.update(DT);
+    }
+}
+
+fn spam(state: &mut GameState, player: uuid::Uuid, commands: usize) {
+    for i in 0..commands {
+        let command = if i % 2 == 0 { PlayerCommand::MoveRight } else { PlayerCommand::Stop };
+        state.apply_command(&player, &command, 0);
+    }
+}
+
+#[test]
+fn ordinary_play_is_never_flagged() {
+    let (mut state, player) = room(AntiCheatConfig::default());
+    state.apply_command(&player, &PlayerCommand::Sprint, 0);
+    state.apply_command(&player, &PlayerCommand::MoveRight, 0);
+    run(&mut state, 60);
+    state.apply_command(&player, &PlayerCommand::Dash, 0);
+    run(&mut state, 60);
+    state.apply_command(&player, &PlayerCommand::Stop, 0);
+    run(&mut state, 60);
+    assert!(state.drain_cheat_reports().is_empty());
+}
+
+#[test]
+fn input_spam_is_flagged_and_its_replay_found_consistent() {
+    let (mut state, player) = room(AntiCheatConfig::default());
+    spam(&mut state, player, 41);
+    run(&mut state, 1);
+
+    let reports = state.drain_cheat_reports();
+    assert_eq!(reports.len(), 1);
+    let report = &reports[0];
+    assert_eq!(report.player_id, player);
+    assert_eq!(report.tick, state.tick);
+    assert_eq!(report.suspicion, Suspicion::InputRate { per_sec: 41, max: 40 });
+    assert_eq!(report.check.verdict, Verdict::Consistent);
+    assert_eq!(report.check.segment.len(), 60);
+    assert_eq!(report.check.frames_replayed, 59);
+    assert_eq!(report.check.segment.last().unwrap().inputs.len(), 41);
+}
+
+#[test]
+fn being_moved_by_something_other_than_inputs_is_impossible() {
+    let (mut state, player) = room(AntiCheatConfig::default());
+    state.players.get_mut(&player).unwrap().x += 50.0;
+    run(&mut state, 1);
+
+    let reports = state.drain_cheat_reports();
+    assert_eq!(reports.len(), 1);
+    assert!(matches!(reports[0].suspicion, Suspicion::Speed { .. }));
+    let check = &reports[0].check;
+    let Verdict::Impossible { tick, recorded, replayed } = check.verdict else {
+        panic!("expected an impossible verdict, got {:?}", check.verdict);
+    };
+    assert_eq!(tick, state.tick);
+    assert!((recorded.0 - replayed.0 - 50.0).abs() < 1.0);
+    assert!(check.max_deviation > 49.0);
+    // The replay ran on a copy; the room itself is untouched
+    assert_eq!(state.players.len(), 1);
+    assert_eq!(state.players[&player].x, recorded.0);
+}
+
+#[test]
+fn teleports_are_not_movement() {
+    let (mut state, player) = room(AntiCheatConfig::default());
+    let teleported = state.players.get_mut(&player).unwrap();
+    teleported.x += 50.0;
+    teleported.teleports += 1;
+    run(&mut state, 1);
+    assert!(state.drain_cheat_reports().is_empty());
+}
+
+#[test]
+fn flagged_players_cool_down_before_being_flagged_again() {
+    let (mut state, player) = room(AntiCheatConfig {
+        flag_cooldown_secs: 2.0,
+        ..AntiCheatConfig::default()
+    });
+    spam(&mut state, player, 41);
+    run(&mut state, 1);
+    assert_eq!(state.drain_cheat_reports().len(), 1);
+
+    run(&mut state, 60);
+    spam(&mut state, player, 41);
+    run(&mut state, 1);
+    assert!(state.drain_cheat_reports().is_empty());
+
+    run(&mut state, 60);
+    spam(&mut state, player, 41);
+    run(&mut state, 1);
+    assert_eq!(state.drain_cheat_reports().len(), 1);
+}
+
+#[test]
+fn nothing_is_flagged_when_disabled() {
+    let (mut state, player) = room(AntiCheatConfig {
+        enabled: false,
+        ..AntiCheatConfig::default()
+    });
+    spam(&mut state, player, 100);
+    state.players.get_mut(&player).unwrap().x += 50.0;
+    run(&mut state, 1);
+    assert!(state.drain_cheat_reports().is_empty());
+}
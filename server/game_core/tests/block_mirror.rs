@@ -0,0 +1,38 @@
+use game_core::{Block, BlockGrid, GeometrySync};
+
+fn block(cell_x: i32, cell_y: i32) -> Block {
+    Block {
+        cell_x,
+        cell_y,
+        owner: uuid::Uuid::nil(),
+        color: "#fff".to_string(),
+    }
+}
+
+#[test]
+fn a_mirror_follows_deltas_and_keeps_the_source_versions() {
+    let mut source = BlockGrid::new();
+    let mut mirror = BlockGrid::new();
+    source.insert(block(0, 0));
+    source.insert(block(1, 0));
+    assert!(mirror.follow(&source.sync_since(0)));
+
+    source.remove(&(0, 0));
+    source.insert(block(2, 0));
+    let delta = source.sync_since(2);
+    assert!(mirror.follow(&delta));
+    assert_eq!(mirror.version(), source.version());
+    assert_eq!(mirror.snapshot().blocks.len(), 2);
+    assert!(mirror.get(&(0, 0)).is_none());
+
+    // A delta applies only on top of the version it starts from
+    source.insert(block(3, 0));
+    source.insert(block(4, 0));
+    assert!(!mirror.follow(&source.sync_since(5)));
+    assert_eq!(mirror.version(), 4);
+    assert!(mirror.follow(&GeometrySync::Snapshot(source.snapshot())));
+    assert_eq!(mirror.version(), 6);
+    assert_eq!(mirror.snapshot().blocks.len(), 4);
+    // Clients of the mirror catch up from versions it has broadcast
+    assert!(matches!(mirror.sync_since(6), GeometrySync::Delta(ref d) if d.is_empty()));
+}
@@ -0,0 +1,85 @@
+mod common;
+
+use std::sync::Arc;
+use game_core::config::BuildingConfig;
+use game_core::{BuildError, GameConfig, GameState, PhysicsWorld};
+
+const DT: f32 = 1.0 / 60.0;
+
+fn state(building: BuildingConfig) -> GameState {
+    let config = GameConfig {
+        building,
+        ..common::open_ground()
+    };
+    GameState::new(Arc::new(PhysicsWorld::new(Arc::new(config))))
+}
+
+/// A player standing on the ground at `x`
+fn player_at(state: &mut GameState, x: f32) -> uuid::Uuid {
+    let id = state.new_player_id();
+    state.add_player(id);
+    state.players.get_mut(&id).unwrap().x = x;
+    for _ in 0..120 {
+        state.update(DT);
+    }
+    id
+}
+
+fn no_cooldown() -> BuildingConfig {
+    BuildingConfig {
+        place_cooldown_ms: 0,
+        ..BuildingConfig::default()
+    }
+}
+
+#[test]
+fn blocks_hold_players_up_and_only_their_builder_removes_them() {
+    let mut state = state(no_cooldown());
+    let builder = player_at(&mut state, 0.0);
+    let other = player_at(&mut state, 4.0);
+    let feet = state.players[&builder].y - state.world.config().physics.player_height / 2.0;
+
+    let block = state.place_block(&builder, 2.5, feet + 0.5).unwrap();
+    let top = (block.cell_y + 1) as f32;
+    assert_eq!(state.place_block(&other, 2.5, feet + 0.5).unwrap_err(), BuildError::Occupied);
+    assert_eq!(state.place_block(&builder, 0.0, feet + 0.5).unwrap_err(), BuildError::OverlapsPlayer);
+    assert_eq!(state.place_block(&builder, 20.5, feet + 0.5).unwrap_err(), BuildError::OutOfReach);
+    assert_eq!(state.remove_block(&other, 2.5, feet + 0.5).unwrap_err(), BuildError::NotOwner);
+
+    // Dropped onto the block, the other player comes to rest on top of it
+    let player = state.players.get_mut(&other).unwrap();
+    player.x = 2.5;
+    player.y = top + 3.0;
+    for _ in 0..120 {
+        state.update(DT);
+    }
+    let half_height = state.world.config().physics.player_height / 2.0;
+    assert!((state.players[&other].y - half_height - top).abs() < 0.05, "{}", state.players[&other].y);
+
+    let removed = state.remove_block(&builder, 2.5, feet + 0.5).unwrap();
+    assert_eq!((removed.cell_x, removed.cell_y), (block.cell_x, block.cell_y));
+    assert_eq!(state.remove_block(&builder, 2.5, feet + 0.5).unwrap_err(), BuildError::NoBlock);
+}
+
+#[test]
+fn budgets_and_the_cooldown_limit_placement() {
+    let mut state = state(BuildingConfig {
+        max_blocks_per_player: 2,
+        ..no_cooldown()
+    });
+    let builder = player_at(&mut state, 0.0);
+    let feet = state.players[&builder].y - state.world.config().physics.player_height / 2.0;
+    state.place_block(&builder, 2.5, feet + 0.5).unwrap();
+    state.place_block(&builder, 3.5, feet + 0.5).unwrap();
+    assert_eq!(state.place_block(&builder, 4.5, feet + 0.5).unwrap_err(), BuildError::BudgetExhausted);
+    state.remove_block(&builder, 3.5, feet + 0.5).unwrap();
+    state.place_block(&builder, 4.5, feet + 0.5).unwrap();
+
+    let mut state = self::state(BuildingConfig {
+        place_cooldown_ms: 60_000,
+        ..BuildingConfig::default()
+    });
+    let builder = player_at(&mut state, 0.0);
+    state.place_block(&builder, 2.5, feet + 0.5).unwrap();
+    assert_eq!(state.place_block(&builder, 3.5, feet + 0.5).unwrap_err(), BuildError::Cooldown);
+}
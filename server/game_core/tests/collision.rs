@@ -0,0 +1,136 @@
+use std::sync::Arc;
+use game_core::collision::BakedGeometry;
+use game_core::config::{GameConfig, PlatformConfig, WallConfig};
+use game_core::{GroundState, PhysicsWorld, Player};
+
+const DT: f32 = 1.0 / 60.0;
+
+fn platform(id: &str, x_start: f32, x_end: f32, y_top: f32, height: f32) -> PlatformConfig {
+    PlatformConfig {
+        id: id.to_string(),
+        x_start,
+        x_end,
+        y_top,
+        height,
+        color: "#FFFFFF".to_string(),
+        surface: Default::default(),
+        restitution: None,
+    }
+}
+
+fn wall(id: &str, x: f32, width: f32, y_bottom: f32, y_top: f32) -> WallConfig {
+    WallConfig {
+        id: id.to_string(),
+        x,
+        y_bottom,
+        y_top,
+        width,
+        color: "#FFFFFF".to_string(),
+    }
+}
+
+#[test]
+fn touching_platforms_at_one_height_fuse_and_the_rest_stay_apart() {
+    let geometry = BakedGeometry::bake(
+        &[
+            platform("b", 10.0, 20.0, 5.0, 1.0),
+            platform("a", 0.0, 10.0, 5.0, 1.0),
+            // Overlapping the first two
+            platform("c", 18.0, 25.0, 5.0, 1.0),
+            // A gap away
+            platform("d", 30.0, 40.0, 5.0, 1.0),
+            // Touching, but thicker
+            platform("e", 40.0, 50.0, 5.0, 2.0),
+            // Touching, but higher
+            platform("f", 50.0, 60.0, 6.0, 2.0),
+        ],
+        &[],
+    );
+    let spans: Vec<_> = geometry.platforms().iter().map(|s| (s.solid.left, s.solid.right, s.solid.bottom, s.solid.top)).collect();
+    assert_eq!(
+        spans,
+        vec![(0.0, 25.0, 4.0, 5.0), (30.0, 40.0, 4.0, 5.0), (40.0, 50.0, 3.0, 5.0), (50.0, 60.0, 4.0, 6.0)]
+    );
+    assert_eq!(geometry.platform_count(), 6);
+
+    // Ground state still names the configured platform underfoot
+    let fused = &geometry.platforms()[0];
+    assert_eq!(fused.platform_at(2.0, 4.0), 1);
+    assert_eq!(fused.platform_at(12.0, 14.0), 0);
+    assert_eq!(fused.platform_at(22.0, 24.0), 2);
+}
+
+#[test]
+fn stacked_and_side_by_side_walls_fuse() {
+    let geometry = BakedGeometry::bake(
+        &[],
+        &[
+            wall("low", 0.0, 1.0, 0.0, 5.0),
+            wall("high", 0.0, 1.0, 5.0, 10.0),
+            wall("beside", 1.0, 1.0, 0.0, 10.0),
+            wall("apart", 20.0, 1.0, 0.0, 10.0),
+        ],
+    );
+    let walls: Vec<_> = geometry.walls().iter().map(|w| (w.left, w.right, w.bottom, w.top)).collect();
+    assert_eq!(walls, vec![(0.0, 2.0, 0.0, 10.0), (20.0, 21.0, 0.0, 10.0)]);
+}
+
+#[test]
+fn nearby_queries_skip_geometry_out_of_reach() {
+    let platforms: Vec<_> = (0..100).map(|i| platform(&i.to_string(), i as f32 * 10.0, i as f32 * 10.0 + 5.0, 0.0, 1.0)).collect();
+    let walls: Vec<_> = (0..100).map(|i| wall(&i.to_string(), i as f32 * 10.0 + 6.0, 1.0, 0.0, 3.0)).collect();
+    let geometry = BakedGeometry::bake(&platforms, &walls);
+
+    let near: Vec<f32> = geometry.platforms_near(304.0, 316.0).map(|s| s.solid.left).collect();
+    assert_eq!(near, vec![300.0, 310.0]);
+    let near: Vec<f32> = geometry.walls_near(304.0, 316.0).map(|w| w.left).collect();
+    assert_eq!(near, vec![306.0]);
+    assert_eq!(geometry.platforms_near(-20.0, -10.0).count(), 0);
+    assert_eq!(geometry.walls_near(2000.0, 2010.0).count(), 0);
+}
+
+fn run_right(platforms: Vec<PlatformConfig>) -> Vec<(f32, f32, GroundState)> {
+    let config = GameConfig {
+        platforms,
+        walls: Vec::new(),
+        ladders: Vec::new(),
+        ..GameConfig::default()
+    };
+    let world = PhysicsWorld::new(Arc::new(config));
+    let physics = &world.config().physics;
+    let mut player = Player::new(uuid::Uuid::nil(), physics);
+    player.x = 2.0;
+    player.y = 5.0 + physics.player_height / 2.0;
+    player.ground_state = GroundState::Grounded { platform_id: Some(0) };
+    let speed = physics.max_horizontal_velocity.min(5.0);
+
+    let mut path = Vec::new();
+    while player.x < 15.0 {
+        // Holding right
+        player.velocity_x = speed;
+        world.update_player_physics(&mut player, DT, &[]);
+        path.push((player.x, player.y, player.ground_state));
+        assert!(path.len() < 1000, "never got across, stuck at x = {}", player.x);
+    }
+    path
+}
+
+#[test]
+fn running_across_a_seam_is_the_same_as_running_along_one_platform() {
+    let seamed = run_right(vec![platform("a", 0.0, 10.0, 5.0, 1.0), platform("b", 10.0, 20.0, 5.0, 1.0)]);
+    let whole = run_right(vec![platform("a", 0.0, 20.0, 5.0, 1.0)]);
+    assert_eq!(seamed.len(), whole.len());
+    for ((x, y, _), (whole_x, whole_y, _)) in seamed.iter().zip(&whole) {
+        assert_eq!((x, y), (whole_x, whole_y), "caught on the seam");
+    }
+    // Ground state still names whichever platform is underfoot
+    let grounded: Vec<_> = seamed
+        .iter()
+        .filter_map(|(_, _, state)| match state {
+            GroundState::Grounded { platform_id } => *platform_id,
+            _ => None,
+        })
+        .collect();
+    assert_eq!(grounded.first(), Some(&0));
+    assert_eq!(grounded.last(), Some(&1));
+}
@@ -0,0 +1,11 @@
+use game_core::GameConfig;
+
+/// Open ground and nothing else: the default physics with no platforms, walls or ladders
+pub fn open_ground() -> GameConfig {
+    GameConfig {
+        platforms: Vec::new(),
+        walls: Vec::new(),
+        ladders: Vec::new(),
+        ..GameConfig::default()
+    }
+}
@@ -0,0 +1,134 @@
+use game_core::config::{MapConfig, MapGeometry, PhysicsBounds, PhysicsSandboxConfig, SpawnPoint, ValidationError, WallConfig};
+use game_core::{GameConfig, PlatformConfig};
+
+fn platform(id: &str, x_start: f32, x_end: f32, y_top: f32) -> PlatformConfig {
+    PlatformConfig {
+        id: id.to_string(),
+        x_start,
+        x_end,
+        y_top,
+        height: 0.5,
+        color: "#B34733".to_string(),
+        surface: Default::default(),
+        restitution: None,
+    }
+}
+
+fn wall(id: &str, y_bottom: f32, y_top: f32) -> WallConfig {
+    WallConfig {
+        id: id.to_string(),
+        x: 10.0,
+        y_bottom,
+        y_top,
+        width: 1.0,
+        color: "#666".to_string(),
+    }
+}
+
+#[test]
+fn every_problem_is_reported_with_the_object_it_is_in() {
+    let mut config = GameConfig::default();
+    config.physics.gravity = f32::NAN;
+    config.physics.ground_color = "brown".to_string();
+    config.set_geometry(MapGeometry {
+        platforms: vec![
+            platform("ledge", 0.0, 4.0, 3.0),
+            // Shares an edge with the ledge, which is fine
+            platform("step", 4.0, 6.0, 3.0),
+            platform("overlap", 3.0, 5.0, 2.8),
+            platform("flat", 8.0, 8.0, 1.0),
+            PlatformConfig {
+                color: "#12345G".to_string(),
+                ..platform("odd_color", -6.0, -4.0, 1.0)
+            },
+        ],
+        walls: vec![wall("upside_down", 5.0, 2.0), wall("pillar", 0.0, 4.0)],
+        spawn_points: vec![SpawnPoint { x: 1.0, y: 5.0 }, SpawnPoint { x: 10.5, y: 1.0 }],
+        ..MapGeometry::default()
+    });
+
+    let errors = config.validate().unwrap_err().0;
+    assert_eq!(
+        errors,
+        vec![
+            ValidationError::NonFinitePhysics { field: "gravity" },
+            ValidationError::InvalidColor {
+                id: "ground".to_string(),
+                color: "brown".to_string()
+            },
+            ValidationError::OverlappingPlatforms {
+                id: "overlap".to_string(),
+                other: "ledge".to_string()
+            },
+            ValidationError::DegeneratePlatform { id: "flat".to_string() },
+            ValidationError::DegenerateWall { id: "upside_down".to_string() },
+            ValidationError::SpawnInsideGeometry {
+                index: 1,
+                inside: "wall pillar".to_string()
+            },
+            ValidationError::InvalidColor {
+                id: "odd_color".to_string(),
+                color: "#12345G".to_string()
+            },
+        ]
+    );
+}
+
+#[test]
+fn rotation_maps_are_checked_and_loading_rejects_invalid_configs() {
+    let broken = MapConfig {
+        id: "broken".to_string(),
+        name: None,
+        file: None,
+        format: None,
+        geometry: MapGeometry {
+            walls: vec![wall("flat_wall", 1.0, 1.0)],
+            ..MapGeometry::default()
+        },
+    };
+    let fine = MapConfig {
+        id: "fine".to_string(),
+        geometry: MapGeometry::default(),
+        ..broken.clone()
+    };
+    let config = GameConfig {
+        maps: vec![fine, broken],
+        ..GameConfig::default()
+    };
+    assert_eq!(
+        config.validate().unwrap_err().0,
+        vec![ValidationError::InMap {
+            map: "broken".to_string(),
+            error: Box::new(ValidationError::DegenerateWall { id: "flat_wall".to_string() })
+        }]
+    );
+
+    let path = std::env::temp_dir().join(format!("config-{}.json", uuid::Uuid::new_v4()));
+    std::fs::write(&path, serde_json::to_string(&config).unwrap()).unwrap();
+    let error = GameConfig::load(&path).unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "invalid config: map broken: wall flat_wall needs y_top above y_bottom and a positive width"
+    );
+    let _ = std::fs::remove_file(&path);
+}
+
+#[test]
+fn sandbox_bounds_must_name_numeric_physics_settings() {
+    let mut sandbox = PhysicsSandboxConfig::default();
+    for field in ["ground_color", "surfaces", "no_such_setting"] {
+        sandbox.bounds.insert(field.to_string(), PhysicsBounds { min: 0.0, max: 1.0 });
+    }
+    let config = GameConfig {
+        physics_sandbox: sandbox,
+        ..GameConfig::default()
+    };
+    assert_eq!(
+        config.validate().unwrap_err().0,
+        vec![
+            ValidationError::NonNumericPhysicsBound { field: "ground_color".to_string() },
+            ValidationError::NonNumericPhysicsBound { field: "no_such_setting".to_string() },
+            ValidationError::NonNumericPhysicsBound { field: "surfaces".to_string() },
+        ]
+    );
+}
@@ -0,0 +1,91 @@
+mod common;
+
+use std::sync::Arc;
+use game_core::config::{ForceZoneConfig, ValidationError};
+use game_core::{GameConfig, PhysicsWorld, Player};
+
+const DT: f32 = 1.0 / 60.0;
+
+fn zone(id: &str, x_start: f32, x_end: f32, y_bottom: f32, y_top: f32, force: (f32, f32)) -> ForceZoneConfig {
+    ForceZoneConfig {
+        id: id.to_string(),
+        x_start,
+        x_end,
+        y_bottom,
+        y_top,
+        force_x: force.0,
+        force_y: force.1,
+        color: "#88CCFF".to_string(),
+    }
+}
+
+/// Open ground at 0 with an updraft from x = 0 to 4 reaching 20 up, and a rightward wind
+/// tunnel from x = 20 to 40
+fn world() -> PhysicsWorld {
+    let mut config = common::open_ground();
+    config.physics.ground_y = 0.0;
+    let gravity = config.physics.gravity;
+    config.force_zones = vec![
+        zone("updraft", 0.0, 4.0, 0.0, 20.0, (0.0, -gravity * 1.5)),
+        zone("tunnel", 20.0, 40.0, 0.0, 5.0, (200.0, 0.0)),
+    ];
+    PhysicsWorld::new(Arc::new(config))
+}
+
+fn standing_at(world: &PhysicsWorld, x: f32) -> Player {
+    let mut player = Player::new(uuid::Uuid::nil(), &world.config().physics);
+    player.x = x;
+    player.y = world.config().physics.player_height / 2.0 + 0.001;
+    player
+}
+
+fn run(world: &PhysicsWorld, player: &mut Player, ticks: usize) {
+    for _ in 0..ticks {
+        world.update_player_physics(player, DT, &[]);
+    }
+}
+
+#[test]
+fn updrafts_lift_players_off_the_ground_and_let_them_fall_back_above_the_top() {
+    let world = world();
+    let mut player = standing_at(&world, 2.0);
+    let half_height = world.config().physics.player_height / 2.0;
+    let mut highest: f32 = 0.0;
+    for _ in 0..300 {
+        world.update_player_physics(&mut player, DT, &[]);
+        highest = highest.max(player.y - half_height);
+    }
+    assert!(highest > 20.0, "only rose to {}", highest);
+    // Past the top gravity wins again, so they coast up a little and fall back
+    assert!(highest < 35.0, "flew up to {}", highest);
+}
+
+#[test]
+fn wind_pushes_players_inside_it_along() {
+    let world = world();
+    let mut player = standing_at(&world, 21.0);
+    run(&world, &mut player, 30);
+    assert!(player.velocity_x > 0.0);
+    assert!(player.x > 21.5, "only reached {}", player.x);
+
+    // Outside any zone nothing pushes
+    let mut bystander = standing_at(&world, 10.0);
+    run(&world, &mut bystander, 30);
+    assert_eq!(bystander.x, 10.0);
+}
+
+#[test]
+fn degenerate_zones_fail_validation() {
+    let config = GameConfig {
+        force_zones: vec![
+            zone("flat", 0.0, 4.0, 2.0, 2.0, (0.0, 10.0)),
+            zone("wild", 0.0, 4.0, 0.0, 2.0, (f32::NAN, 0.0)),
+            ForceZoneConfig { color: "blue".to_string(), ..zone("blue", 0.0, 4.0, 0.0, 2.0, (1.0, 0.0)) },
+        ],
+        ..GameConfig::default()
+    };
+    let errors = config.validate().unwrap_err().0;
+    assert!(errors.contains(&ValidationError::DegenerateForceZone { id: "flat".to_string() }));
+    assert!(errors.contains(&ValidationError::DegenerateForceZone { id: "wild".to_string() }));
+    assert!(errors.contains(&ValidationError::InvalidColor { id: "blue".to_string(), color: "blue".to_string() }));
+}
@@ -0,0 +1,156 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use game_core::commands::IgnoredReason;
+use game_core::config::{GameModeConfig, GameRules, MatchConfig, ScorePolicy};
+use game_core::player::PlayerId;
+use game_core::{CommandOutcome, GameConfig, GameMode, GameState, ModeContext, ModeRegistry, PhysicsWorld, Player, PlayerCommand};
+
+/// A mode from outside the crate: no building, counting joins and touches
+#[derive(Debug, Clone, Default)]
+struct NoBuilding {
+    joins: usize,
+    collisions: Vec<(PlayerId, PlayerId)>,
+}
+
+impl GameMode for NoBuilding {
+    fn rules(&self) -> GameRules {
+        GameRules::new("no_building")
+    }
+
+    fn on_player_join(&mut self, _ctx: &mut ModeContext, _player_id: &PlayerId) {
+        self.joins += 1;
+    }
+
+    fn on_collision(&mut self, _ctx: &mut ModeContext, a: &PlayerId, b: &PlayerId) {
+        self.collisions.push((*a, *b));
+    }
+
+    fn on_command(&mut self, _ctx: &mut ModeContext, _player_id: &PlayerId, command: &PlayerCommand) -> Result<(), IgnoredReason> {
+        match command {
+            PlayerCommand::PlaceBlock { .. } => Err(IgnoredReason::ModeRules),
+            _ => Ok(()),
+        }
+    }
+
+    fn status(&self, _players: &HashMap<PlayerId, Player>, _config: &GameConfig) -> serde_json::Value {
+        serde_json::json!({ "joins": self.joins, "collisions": self.collisions.len() })
+    }
+
+    fn clone_box(&self) -> Box<dyn GameMode> {
+        Box::new(self.clone())
+    }
+}
+
+/// A mode whose rules carry scores over whatever the mode's config asks
+#[derive(Debug, Clone, Default)]
+struct Marathon;
+
+impl GameMode for Marathon {
+    fn rules(&self) -> GameRules {
+        GameRules::new("marathon")
+    }
+
+    fn on_mode_enter(&mut self, _ctx: &mut ModeContext, _policy: ScorePolicy) {}
+
+    fn clone_box(&self) -> Box<dyn GameMode> {
+        Box::new(self.clone())
+    }
+}
+
+fn state() -> GameState {
+    let config = GameConfig {
+        game_mode: GameRules::new("no_building"),
+        matches: MatchConfig {
+            enabled: false,
+            ..MatchConfig::default()
+        },
+        ..GameConfig::default()
+    };
+    GameState::new(Arc::new(PhysicsWorld::new(Arc::new(config))))
+}
+
+fn status(state: &GameState) -> serde_json::Value {
+    state.rules().status(&state.players, state.world.config())
+}
+
+#[test]
+fn built_in_modes_are_registered_by_name() {
+    let registry = ModeRegistry::default();
+    let names: Vec<&str> = registry.names().map(|rules| rules.name()).collect();
+    assert_eq!(names, ["king_of_the_hill", "race", "standard", "tag"]);
+    assert_eq!(registry.create(&GameRules::TAG).unwrap().rules(), GameRules::TAG);
+    assert!(registry.create(&GameRules::new("capture_the_flag")).is_none());
+
+    // Config names modes as plain strings
+    assert_eq!(serde_json::from_str::<GameRules>(r#""race""#).unwrap(), GameRules::RACE);
+    assert_eq!(serde_json::to_value(GameRules::KING_OF_THE_HILL).unwrap(), "king_of_the_hill");
+}
+
+#[test]
+fn unknown_modes_play_standard_rules_until_registered() {
+    let mut state = state();
+    assert_eq!(state.rules().rules(), GameRules::STANDARD);
+
+    let rules = state.register_mode(|| Box::new(NoBuilding::default()));
+    assert_eq!(rules, GameRules::new("no_building"));
+    assert_eq!(state.rules().rules(), rules);
+    assert!(state.modes().create(&rules).is_some());
+}
+
+#[test]
+fn registered_modes_hear_joins_touches_and_commands() {
+    let mut state = state();
+    state.register_mode(|| Box::new(NoBuilding::default()));
+    let (alice, bob) = (state.new_player_id(), state.new_player_id());
+    state.add_player(alice);
+    state.add_player(bob);
+    assert_eq!(status(&state)["joins"], 2);
+
+    // Bob steps onto Alice
+    let (x, y) = (state.players[&alice].x, state.players[&alice].y);
+    let bob_player = state.players.get_mut(&bob).unwrap();
+    (bob_player.x, bob_player.y) = (x, y);
+    state.update(1.0 / 60.0);
+    assert_eq!(status(&state)["collisions"], 1);
+
+    let outcome = state.apply_command(&alice, &PlayerCommand::PlaceBlock { x: x + 2.0, y }, 0);
+    assert_eq!(outcome, CommandOutcome::ignored(IgnoredReason::ModeRules));
+    assert_eq!(state.blocks.count_owned(&alice), 0);
+}
+
+#[test]
+fn the_rules_entered_decide_what_happens_to_scores() {
+    let preset = |id: &str, game_mode: GameRules| GameModeConfig {
+        id: id.to_string(),
+        name: None,
+        description: String::new(),
+        score_policy: ScorePolicy::Reset,
+        game_mode: Some(game_mode),
+        duration_secs: None,
+        building: None,
+        projectiles: None,
+        mutators: Vec::new(),
+        slow_motion: None,
+    };
+    let config = GameConfig {
+        matches: MatchConfig {
+            enabled: false,
+            ..MatchConfig::default()
+        },
+        modes: vec![preset("free", GameRules::STANDARD), preset("marathon", GameRules::new("marathon"))],
+        ..GameConfig::default()
+    };
+    let mut state = GameState::new(Arc::new(PhysicsWorld::new(Arc::new(config))));
+    state.register_mode(|| Box::new(Marathon));
+    let alice = state.new_player_id();
+    state.add_player(alice);
+
+    state.players.get_mut(&alice).unwrap().score = 30;
+    state.request_mode("marathon").unwrap();
+    assert_eq!(state.rules().rules(), GameRules::new("marathon"));
+    assert_eq!(state.players[&alice].score, 30);
+
+    // Standard rules take the preset's reset at its word
+    state.request_mode("free").unwrap();
+    assert_eq!(state.players[&alice].score, 0);
+}
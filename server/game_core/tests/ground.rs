@@ -0,0 +1,159 @@
+mod common;
+
+use std::sync::Arc;
+use game_core::config::{GroundSegment, ValidationError};
+use game_core::{GameConfig, GroundState, PhysicsWorld, Player, PlayerCommand};
+
+const DT: f32 = 1.0 / 60.0;
+
+fn segment(x_start: f32, x_end: f32, y_top: f32) -> GroundSegment {
+    GroundSegment {
+        x_start,
+        x_end,
+        y_top,
+        color: None,
+        surface: None,
+        restitution: None,
+    }
+}
+
+/// Default ground at -10 with a raised step from 5 to 15 and a pit from 20 to 30
+fn terrain() -> GameConfig {
+    let mut config = common::open_ground();
+    config.physics.ground_y = -10.0;
+    config.physics.ground_segments = vec![segment(20.0, 30.0, -14.0), segment(5.0, 15.0, -8.0)];
+    config
+}
+
+fn world(config: GameConfig) -> PhysicsWorld {
+    PhysicsWorld::new(Arc::new(config))
+}
+
+/// A player standing on the ground at `x`
+fn player_at(world: &PhysicsWorld, x: f32) -> Player {
+    let physics = &world.config().physics;
+    let mut player = Player::new(uuid::Uuid::nil(), physics);
+    player.x = x;
+    player.y = world.geometry().ground_under(x - physics.player_width / 2.0, x + physics.player_width / 2.0)
+        + physics.player_height / 2.0
+        + 0.001;
+    player
+}
+
+fn feet(world: &PhysicsWorld, player: &Player) -> f32 {
+    player.y - player.height(&world.config().physics) / 2.0
+}
+
+fn run(world: &PhysicsWorld, player: &mut Player, ticks: usize) {
+    for _ in 0..ticks {
+        world.update_player_physics(player, DT, &[]);
+    }
+}
+
+#[test]
+fn ground_height_follows_the_segments_and_the_default_elsewhere() {
+    let physics = terrain().physics;
+    assert_eq!(physics.ground_at(0.0), -10.0);
+    assert_eq!(physics.ground_at(5.0), -8.0);
+    assert_eq!(physics.ground_at(15.0), -10.0);
+    assert_eq!(physics.ground_at(25.0), -14.0);
+    assert_eq!(physics.lowest_ground(), -14.0);
+
+    let baked = world(terrain());
+    let tops: Vec<f32> = baked.geometry().ground().iter().map(|column| column.solid.top).collect();
+    assert_eq!(tops, vec![-10.0, -8.0, -10.0, -14.0, -10.0]);
+    // Straddling an edge, the higher side holds the player up
+    assert_eq!(baked.geometry().ground_under(4.0, 6.0), -8.0);
+    assert_eq!(baked.geometry().ground_under(19.0, 21.0), -10.0);
+    assert_eq!(baked.geometry().ground_under(21.0, 29.0), -14.0);
+}
+
+#[test]
+fn players_land_on_raised_ground_and_fall_into_pits() {
+    let world = world(terrain());
+    let mut player = player_at(&world, 10.0);
+    player.y += 3.0;
+    run(&world, &mut player, 60);
+    assert!((feet(&world, &player) - -8.0).abs() < 0.01, "feet at {}", feet(&world, &player));
+
+    let mut player = player_at(&world, 25.0);
+    player.y = 0.0;
+    run(&world, &mut player, 120);
+    assert!((feet(&world, &player) - -14.0).abs() < 0.01, "feet at {}", feet(&world, &player));
+}
+
+#[test]
+fn raised_ground_blocks_from_the_side_and_dropping_off_it_falls() {
+    let world = world(terrain());
+    let half_width = world.config().physics.player_width / 2.0;
+
+    // Running right from the default ground into the step
+    let mut player = player_at(&world, 0.0);
+    for _ in 0..120 {
+        player.velocity_x = 5.0;
+        run(&world, &mut player, 1);
+    }
+    assert!(player.x + half_width <= 5.0, "ran into the step to x = {}", player.x);
+    assert!((feet(&world, &player) - -10.0).abs() < 0.01);
+
+    // Jumping up onto it works
+    player.ground_state = GroundState::Grounded { platform_id: None };
+    world.apply_command(&mut player, &PlayerCommand::Jump);
+    for _ in 0..120 {
+        player.velocity_x = 5.0;
+        run(&world, &mut player, 1);
+        if player.x > 8.0 {
+            break;
+        }
+    }
+    assert!(player.x > 8.0, "stuck at x = {}", player.x);
+    run(&world, &mut player, 60);
+    assert!((feet(&world, &player) - -8.0).abs() < 0.01, "feet at {}", feet(&world, &player));
+
+    // Running right off the far edge drops back to the default ground
+    for _ in 0..120 {
+        player.velocity_x = 5.0;
+        run(&world, &mut player, 1);
+    }
+    assert!(player.x > 15.0 + half_width);
+    assert!((feet(&world, &player) - -10.0).abs() < 0.01, "feet at {}", feet(&world, &player));
+}
+
+#[test]
+fn players_spawn_on_the_ground_under_the_spawn() {
+    let mut config = terrain();
+    config.physics.ground_segments.push(segment(-2.0, 2.0, -6.0));
+    let physics = &config.physics;
+    let player = Player::new(uuid::Uuid::nil(), physics);
+    assert_eq!(player.y - physics.player_height / 2.0, -6.0);
+    let (_, y) = game_core::respawn::spawn_position(&config, &Default::default(), &player.id);
+    assert_eq!(y - physics.player_height / 2.0, -6.0);
+}
+
+#[test]
+fn broken_segments_fail_validation() {
+    let mut config = terrain();
+    config.physics.ground_segments = vec![
+        segment(0.0, 10.0, -8.0),
+        // Overlaps the first
+        segment(8.0, 12.0, -9.0),
+        segment(20.0, 20.0, -9.0),
+        segment(30.0, 40.0, f32::NAN),
+        GroundSegment {
+            color: Some("green".to_string()),
+            ..segment(50.0, 60.0, -9.0)
+        },
+    ];
+    assert_eq!(
+        config.validate().unwrap_err().0,
+        vec![
+            ValidationError::OverlappingGroundSegments { index: 1, other: 0 },
+            ValidationError::DegenerateGroundSegment { index: 2 },
+            ValidationError::DegenerateGroundSegment { index: 3 },
+            ValidationError::InvalidColor {
+                id: "ground segment 4".to_string(),
+                color: "green".to_string()
+            },
+        ]
+    );
+}
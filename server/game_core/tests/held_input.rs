@@ -0,0 +1,79 @@
+mod common;
+
+use std::sync::Arc;
+use game_core::{CommandOutcome, HeldKey, IgnoredReason, PhysicsWorld, Player, PlayerCommand};
+
+const DT: f32 = 1.0 / 60.0;
+
+fn world() -> PhysicsWorld {
+    PhysicsWorld::new(Arc::new(common::open_ground()))
+}
+
+fn player(world: &PhysicsWorld) -> Player {
+    Player::new(uuid::Uuid::nil(), &world.config().physics)
+}
+
+fn run(world: &PhysicsWorld, player: &mut Player, ticks: usize) {
+    for _ in 0..ticks {
+        world.update_player_physics(player, DT, &[]);
+    }
+}
+
+#[test]
+fn a_held_direction_accelerates_every_tick() {
+    let world = world();
+    let physics = world.config().physics.clone();
+    let mut held = player(&world);
+    world.apply_command(&mut held, &PlayerCommand::KeyDown { key: HeldKey::Left });
+    run(&world, &mut held, 10);
+    assert!(!held.facing_right);
+    let expected = (physics.move_acceleration * DT * 10.0).min(physics.max_horizontal_velocity);
+    assert!(held.velocity_x <= -expected * 0.9, "velocity after ten held ticks: {}", held.velocity_x);
+
+    // One discrete command moves the player far less over the same ticks
+    let mut tapped = player(&world);
+    world.apply_command(&mut tapped, &PlayerCommand::MoveLeft);
+    run(&world, &mut tapped, 10);
+    assert!(held.x < tapped.x);
+}
+
+#[test]
+fn a_hold_without_a_refresh_lets_go_after_the_timeout() {
+    let world = world();
+    let timeout_ticks = (world.config().physics.held_input_timeout_secs / DT).ceil() as usize;
+    let mut player = player(&world);
+    world.apply_command(&mut player, &PlayerCommand::KeyDown { key: HeldKey::Right });
+    run(&world, &mut player, timeout_ticks - 2);
+    assert_eq!(player.held, Some(HeldKey::Right));
+    assert!(player.velocity_x > 0.0);
+    run(&world, &mut player, 3);
+    assert_eq!(player.held, None);
+    assert_eq!(player.velocity_x, 0.0);
+
+    // Refreshing keeps it going
+    let mut player = self::player(&world);
+    for _ in 0..4 {
+        world.apply_command(&mut player, &PlayerCommand::KeyDown { key: HeldKey::Right });
+        run(&world, &mut player, timeout_ticks - 2);
+    }
+    assert_eq!(player.held, Some(HeldKey::Right));
+}
+
+#[test]
+fn the_newest_direction_wins_and_only_its_release_stops() {
+    let world = world();
+    let mut player = player(&world);
+    world.apply_command(&mut player, &PlayerCommand::KeyDown { key: HeldKey::Right });
+    run(&world, &mut player, 5);
+    world.apply_command(&mut player, &PlayerCommand::KeyDown { key: HeldKey::Left });
+    assert_eq!(
+        world.apply_command(&mut player, &PlayerCommand::KeyUp { key: HeldKey::Right }),
+        CommandOutcome::ignored(IgnoredReason::NotHeld)
+    );
+    run(&world, &mut player, 20);
+    assert!(player.velocity_x < 0.0);
+
+    assert_eq!(world.apply_command(&mut player, &PlayerCommand::KeyUp { key: HeldKey::Left }), CommandOutcome::Accepted);
+    assert_eq!(player.held, None);
+    assert_eq!(player.velocity_x, 0.0);
+}
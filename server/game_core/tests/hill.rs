@@ -0,0 +1,152 @@
+use std::sync::Arc;
+use std::time::Duration;
+use game_core::config::{ControlZoneConfig, GameRules, KingOfTheHillConfig, MatchConfig, ValidationError};
+use game_core::{GameConfig, GameState, HillSide, HillStatus, MatchState, MockClock, PhysicsWorld};
+
+const DT: f32 = 1.0 / 60.0;
+
+/// A hill on the ground at x = 8..12 taking a second to capture, worth ten points a
+/// second, with the match won at 20 and starting as soon as two players are in
+fn room() -> (GameState, Arc<MockClock>) {
+    let mut config = GameConfig {
+        game_mode: GameRules::KING_OF_THE_HILL,
+        king_of_the_hill: KingOfTheHillConfig { capture_secs: 1.0, points_per_sec: 10.0, score_cap: 20 },
+        matches: MatchConfig {
+            min_players: 2,
+            countdown_secs: 0,
+            ..MatchConfig::default()
+        },
+        platforms: Vec::new(),
+        ..GameConfig::default()
+    };
+    let ground_y = config.physics.ground_y;
+    config.control_zone = Some(ControlZoneConfig {
+        id: "hill".to_string(),
+        x_start: 8.0,
+        x_end: 12.0,
+        y_bottom: ground_y,
+        y_top: ground_y + 5.0,
+        color: "#FF8800".to_string(),
+    });
+    let clock = Arc::new(MockClock::new());
+    let state = GameState::with_clock(Arc::new(PhysicsWorld::new(Arc::new(config))), clock.clone());
+    (state, clock)
+}
+
+fn run(state: &mut GameState, clock: &MockClock, ticks: usize) {
+    for _ in 0..ticks {
+        clock.advance(Duration::from_secs_f32(DT));
+        state.update(DT);
+    }
+}
+
+fn status(state: &GameState) -> HillStatus {
+    serde_json::from_value(state.mode_status().expect("king of the hill keeps a status").state).unwrap()
+}
+
+/// Keep a player standing still on the ground at `x`
+fn hold(state: &mut GameState, player_id: uuid::Uuid, x: f32) {
+    let ground_y = state.world.config().physics.ground_y;
+    let player = state.players.get_mut(&player_id).unwrap();
+    player.x = x;
+    player.y = ground_y + 1.0;
+    player.velocity_x = 0.0;
+}
+
+/// A started round with the players on opposite teams, both away from the hill
+fn started() -> (GameState, Arc<MockClock>, uuid::Uuid, uuid::Uuid) {
+    let (mut state, clock) = room();
+    let (alice, bob) = (state.new_player_id(), state.new_player_id());
+    state.add_player(alice);
+    state.add_player(bob);
+    run(&mut state, &clock, 2);
+    assert!(matches!(state.match_state, MatchState::Playing { .. }));
+    for (player_id, team) in [(alice, "red"), (bob, "blue")] {
+        state.players.get_mut(&player_id).unwrap().team = Some(team.to_string());
+        hold(&mut state, player_id, -20.0);
+    }
+    (state, clock, alice, bob)
+}
+
+fn red() -> HillSide {
+    HillSide::Team("red".to_string())
+}
+
+/// Run `ticks` ticks with a player held at `x`
+fn stay(state: &mut GameState, clock: &MockClock, player_id: uuid::Uuid, x: f32, ticks: usize) {
+    for _ in 0..ticks {
+        hold(state, player_id, x);
+        run(state, clock, 1);
+    }
+}
+
+#[test]
+fn holding_the_zone_alone_captures_it_then_scores() {
+    let (mut state, clock, alice, _) = started();
+    let score = state.players[&alice].score;
+
+    stay(&mut state, &clock, alice, 10.0, 30);
+    let halfway = status(&state);
+    assert_eq!(halfway.owner, None);
+    assert_eq!(halfway.capturing, Some(red()));
+    assert!(halfway.progress >= 0.4 && halfway.progress <= 0.6, "progress {}", halfway.progress);
+    assert_eq!(state.players[&alice].score, score);
+
+    stay(&mut state, &clock, alice, 10.0, 60);
+    let held = status(&state);
+    assert_eq!(held.owner, Some(red()));
+    assert!(held.scores[0].points >= 4, "scored {:?}", held.scores);
+    assert_eq!(state.players[&alice].score - score, held.scores[0].points);
+}
+
+#[test]
+fn a_second_side_in_the_zone_contests_it() {
+    let (mut state, clock, alice, bob) = started();
+    stay(&mut state, &clock, alice, 10.0, 70);
+    assert_eq!(status(&state).owner, Some(red()));
+    let points = status(&state).scores[0].points;
+
+    for _ in 0..30 {
+        hold(&mut state, alice, 9.0);
+        hold(&mut state, bob, 11.0);
+        run(&mut state, &clock, 1);
+    }
+    let contested = status(&state);
+    assert!(contested.contested);
+    assert_eq!(contested.owner, Some(red()));
+    assert_eq!(contested.scores[0].points, points);
+}
+
+#[test]
+fn teammates_hold_the_zone_together() {
+    let (mut state, clock, alice, bob) = started();
+    state.players.get_mut(&bob).unwrap().team = Some("red".to_string());
+    for _ in 0..70 {
+        hold(&mut state, alice, 9.0);
+        hold(&mut state, bob, 11.0);
+        run(&mut state, &clock, 1);
+    }
+    let held = status(&state);
+    assert!(!held.contested);
+    assert_eq!(held.owner, Some(red()));
+}
+
+#[test]
+fn reaching_the_score_cap_ends_the_match() {
+    let (mut state, clock, alice, _) = started();
+    stay(&mut state, &clock, alice, 10.0, 60 + 60 * 3);
+    match &state.match_state {
+        MatchState::Ended { result, .. } => assert_eq!(result.winner, Some(alice)),
+        other => panic!("score cap didn't end the match: {:?}", other),
+    }
+}
+
+#[test]
+fn king_of_the_hill_needs_a_zone() {
+    let config = GameConfig {
+        game_mode: GameRules::KING_OF_THE_HILL,
+        ..GameConfig::default()
+    };
+    let errors = config.validate().unwrap_err().0;
+    assert!(errors.contains(&ValidationError::KingOfTheHillWithoutZone));
+}
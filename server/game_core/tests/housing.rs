@@ -0,0 +1,90 @@
+use std::sync::Arc;
+use game_core::config::{BuildingConfig, HousingConfig};
+use game_core::{BuildError, GameConfig, GameState, Housing, PhysicsWorld, PlotError};
+
+/// Five plots from x = -19.5, each 6 wide with a gap of 1, on the ground at y = -10
+fn config(path: Option<String>) -> GameConfig {
+    GameConfig {
+        building: BuildingConfig {
+            max_blocks_per_player: 1,
+            place_cooldown_ms: 0,
+            ..BuildingConfig::default()
+        },
+        housing: HousingConfig {
+            enabled: true,
+            path,
+            max_blocks_per_plot: 3,
+            ..HousingConfig::default()
+        },
+        ..GameConfig::default()
+    }
+}
+
+fn state(config: &GameConfig) -> GameState {
+    GameState::new(Arc::new(PhysicsWorld::new(Arc::new(config.clone()))))
+}
+
+/// Add a registered player standing at `x` on the ground
+fn player_at(state: &mut GameState, x: f32) -> uuid::Uuid {
+    let id = uuid::Uuid::new_v4();
+    state.add_player(id);
+    let player = state.players.get_mut(&id).unwrap();
+    player.x = x;
+    player.registered = true;
+    id
+}
+
+#[test]
+fn only_owners_build_in_their_plot_and_houses_outlast_them() {
+    let mut state = state(&config(None));
+    let owner = player_at(&mut state, -16.5);
+    let neighbour = player_at(&mut state, -10.0);
+    assert_eq!(state.claim_plot(&owner, Some(0)), Ok(0));
+    assert_eq!(state.claim_plot(&neighbour, Some(0)), Err(PlotError::Taken));
+    assert_eq!(state.claim_plot(&owner, None), Err(PlotError::AlreadyOwner));
+    let guest = player_at(&mut state, -3.0);
+    state.players.get_mut(&guest).unwrap().registered = false;
+    assert_eq!(state.claim_plot(&guest, None), Err(PlotError::Unregistered));
+
+    // A house doesn't use up the owner's single loose block, but has its own limit
+    for (x, y) in [(-18.5, -9.5), (-16.5, -7.5), (-14.5, -9.5)] {
+        state.place_block(&owner, x, y).unwrap();
+    }
+    assert_eq!(state.place_block(&owner, -18.5, -8.5).unwrap_err(), BuildError::BudgetExhausted);
+    state.place_block(&owner, -13.2, -9.5).unwrap();
+    assert_eq!(state.place_block(&neighbour, -16.0, -7.5).unwrap_err(), BuildError::NotYourPlot);
+    assert_eq!(state.remove_block(&neighbour, -14.5, -9.5).unwrap_err(), BuildError::NotYourPlot);
+
+    state.remove_player(&owner);
+    assert_eq!(state.blocks.blocks.len(), 3, "only the loose block goes when the owner leaves");
+    assert_eq!(state.housing.list()[0].blocks, 3);
+
+    let plot = state.visit_plot(&neighbour, 0).unwrap();
+    assert_eq!((state.players[&neighbour].x, state.players[&neighbour].y), plot);
+    assert!(plot.1 > -4.0, "visitors arrive above the plot");
+    assert_eq!(state.visit_plot(&neighbour, 9), Err(PlotError::UnknownPlot));
+}
+
+#[tokio::test]
+async fn claimed_plots_and_houses_are_reloaded_from_disk() {
+    let path = std::env::temp_dir().join(format!("plots-{}.json", uuid::Uuid::new_v4()));
+    let config = config(Some(path.to_string_lossy().into_owned()));
+    let mut first = state(&config);
+    first.restore_housing(Housing::load(&config.housing).await.unwrap());
+    let owner = player_at(&mut first, -9.5);
+    assert_eq!(first.claim_plot(&owner, None), Ok(0));
+    first.release_plot(&owner);
+    assert_eq!(first.claim_plot(&owner, Some(1)), Ok(1));
+    first.place_block(&owner, -11.5, -9.5).unwrap();
+    first.housing.save().await.unwrap();
+
+    let mut second = state(&config);
+    assert!(second.housing.list().iter().all(|plot| plot.owner.is_none()), "construction reads nothing");
+    second.restore_housing(Housing::load(&config.housing).await.unwrap());
+    let plots = second.housing.list();
+    assert_eq!(plots[1].owner, Some(owner));
+    assert_eq!(plots[1].blocks, 1);
+    assert!(plots[0].owner.is_none());
+    assert_eq!(second.blocks.blocks.len(), 1, "the house is rebuilt at startup");
+    let _ = std::fs::remove_file(&path);
+}
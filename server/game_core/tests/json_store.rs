@@ -0,0 +1,41 @@
+use std::collections::HashMap;
+use game_core::JsonStore;
+
+fn temp_path(name: &str) -> std::path::PathBuf {
+    std::env::temp_dir().join(format!("{}-{}.json", name, uuid::Uuid::new_v4()))
+}
+
+#[tokio::test]
+async fn saved_values_load_back_and_missing_files_start_empty() {
+    let path = temp_path("store");
+    let mut store: JsonStore<HashMap<String, u32>> = JsonStore::load(&path).await.unwrap();
+    assert!(store.get().is_empty());
+
+    store.get_mut().insert("coins".to_string(), 12);
+    store.save().await.unwrap();
+    let reloaded: JsonStore<HashMap<String, u32>> = JsonStore::load(&path).await.unwrap();
+    assert_eq!(reloaded.get()["coins"], 12);
+    let _ = std::fs::remove_file(&path);
+}
+
+#[tokio::test]
+async fn background_saves_leave_the_latest_value() {
+    let path = temp_path("background");
+    let mut store: JsonStore<Vec<u32>> = JsonStore::load(&path).await.unwrap();
+    for n in 0..20 {
+        store.get_mut().push(n);
+        store.save_in_background();
+    }
+    // Wait for the writer tasks to drain
+    for _ in 0..200 {
+        let saved: JsonStore<Vec<u32>> = JsonStore::load(&path).await.unwrap();
+        if saved.get().len() == 20 {
+            break;
+        }
+        tokio::task::yield_now().await;
+        std::thread::sleep(std::time::Duration::from_millis(5));
+    }
+    let saved: JsonStore<Vec<u32>> = JsonStore::load(&path).await.unwrap();
+    assert_eq!(saved.get(), store.get());
+    let _ = std::fs::remove_file(&path);
+}
@@ -0,0 +1,84 @@
+mod common;
+
+use std::sync::Arc;
+use game_core::{CommandOutcome, IgnoredReason, PhysicsWorld, Player, PlayerCommand};
+
+const DT: f32 = 1.0 / 60.0;
+
+fn world(jump_buffer_secs: f32) -> PhysicsWorld {
+    let mut config = common::open_ground();
+    config.physics.jump_buffer_secs = jump_buffer_secs;
+    PhysicsWorld::new(Arc::new(config))
+}
+
+/// A player falling from `height` above the ground
+fn falling(world: &PhysicsWorld, height: f32) -> Player {
+    let mut player = Player::new(uuid::Uuid::nil(), &world.config().physics);
+    player.y += height;
+    player.ground_state = game_core::GroundState::Flying;
+    player
+}
+
+/// Step until the player lands, returning the number of steps taken
+fn land(world: &PhysicsWorld, player: &mut Player) -> usize {
+    (1..=600)
+        .find(|_| {
+            world.update_player_physics(player, DT, &[]);
+            player.ground_state.is_grounded()
+        })
+        .expect("never landed")
+}
+
+#[test]
+fn a_jump_pressed_just_before_landing_goes_off_on_the_first_grounded_step() {
+    let world = world(0.1);
+    let mut player = falling(&world, 3.0);
+    let steps_to_land = land(&world, &mut player.clone());
+    let jump_velocity = world.config().physics.jump_velocity;
+
+    // Falling, a few steps from the ground
+    for _ in 0..steps_to_land - 3 {
+        world.update_player_physics(&mut player, DT, &[]);
+    }
+    assert_eq!(world.apply_command(&mut player, &PlayerCommand::Jump), CommandOutcome::Buffered);
+    let mut jumped_on = None;
+    for step in 1..=6 {
+        if world.update_player_physics(&mut player, DT, &[]) {
+            jumped_on = Some(step);
+            break;
+        }
+        assert!(player.velocity_y < jump_velocity);
+    }
+    // Landed on the third step, jumped on the next
+    assert_eq!(jumped_on, Some(4));
+    assert!(player.ground_state.is_flying());
+    assert!(player.velocity_y > 0.0);
+    assert_eq!(player.jump_buffer_secs, 0.0);
+}
+
+#[test]
+fn a_jump_pressed_too_early_is_dropped() {
+    let world = world(0.1);
+    let mut player = falling(&world, 50.0);
+    let steps_to_land = land(&world, &mut player.clone());
+    assert!(steps_to_land > 12, "lands after {} steps", steps_to_land);
+
+    assert_eq!(world.apply_command(&mut player, &PlayerCommand::Jump), CommandOutcome::Buffered);
+    land(&world, &mut player);
+    for _ in 0..30 {
+        assert!(!world.update_player_physics(&mut player, DT, &[]));
+    }
+    assert!(player.velocity_y <= 0.0);
+}
+
+#[test]
+fn without_a_window_jumps_in_the_air_are_ignored() {
+    let world = world(0.0);
+    let mut player = falling(&world, 3.0);
+    world.update_player_physics(&mut player, DT, &[]);
+    assert_eq!(
+        world.apply_command(&mut player, &PlayerCommand::Jump),
+        CommandOutcome::ignored(IgnoredReason::NotGrounded)
+    );
+    assert_eq!(player.jump_buffer_secs, 0.0);
+}
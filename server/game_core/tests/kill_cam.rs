@@ -0,0 +1,74 @@
+use std::sync::Arc;
+use game_core::config::KillCamConfig;
+use game_core::{DamageSource, GameConfig, GameState, PhysicsWorld, PlayerCommand};
+
+const DT: f32 = 1.0 / 60.0;
+
+fn room(kill_cam: KillCamConfig) -> (GameState, uuid::Uuid, uuid::Uuid) {
+    let config = GameConfig {
+        tick_rate_hz: 60.0,
+        kill_cam,
+        ..GameConfig::default()
+    };
+    let mut state = GameState::new(Arc::new(PhysicsWorld::new(Arc::new(config))));
+    let killer = state.new_player_id();
+    let victim = state.new_player_id();
+    state.add_player(killer);
+    state.add_player(victim);
+    (state, killer, victim)
+}
+
+fn run(state: &mut GameState, ticks: usize) {
+    for _ in 0..ticks {
+        state.update(DT);
+    }
+}
+
+fn kill(state: &mut GameState, victim: uuid::Uuid, source: DamageSource) {
+    state.players.get_mut(&victim).unwrap().invulnerable_secs = 0.0;
+    state.damage_player(&victim, u32::MAX, source, None);
+}
+
+#[test]
+fn victims_of_other_players_get_the_last_second_from_the_killers_side() {
+    let (mut state, killer, victim) = room(KillCamConfig::default());
+    run(&mut state, 90);
+    state.apply_command(&killer, &PlayerCommand::MoveRight, 0);
+    run(&mut state, 20);
+    kill(&mut state, victim, DamageSource::Stomp { by: killer });
+    // Built once the step's frame is captured
+    assert!(state.drain_kill_cams().is_empty());
+    run(&mut state, 1);
+
+    let kill_cams = state.drain_kill_cams();
+    assert_eq!(kill_cams.len(), 1);
+    let kill_cam = &kill_cams[0];
+    assert_eq!((kill_cam.killer, kill_cam.victim), (killer, victim));
+    assert_eq!(kill_cam.tick, state.tick);
+    assert_eq!(kill_cam.killer_frames.len(), 60);
+    assert_eq!(kill_cam.killer_frames.last().unwrap().tick, state.tick);
+    let ticks = |frames: &[game_core::HistoryFrame]| frames.iter().map(|f| f.tick).collect::<Vec<_>>();
+    assert_eq!(ticks(&kill_cam.killer_frames), ticks(&kill_cam.victim_frames));
+    let inputs: Vec<_> = kill_cam.killer_frames.iter().flat_map(|f| f.inputs.clone()).collect();
+    assert_eq!(inputs, vec![PlayerCommand::MoveRight]);
+    let killer_now = &state.players[&killer];
+    assert_eq!(kill_cam.killer_frames.last().unwrap().x, killer_now.x);
+}
+
+#[test]
+fn deaths_without_a_killer_or_with_kill_cams_off_get_none() {
+    let (mut state, _, victim) = room(KillCamConfig::default());
+    run(&mut state, 10);
+    kill(&mut state, victim, DamageSource::Hazard { hazard_id: "lava".to_string() });
+    run(&mut state, 1);
+    assert!(state.drain_kill_cams().is_empty());
+
+    let (mut state, killer, victim) = room(KillCamConfig {
+        enabled: false,
+        ..KillCamConfig::default()
+    });
+    run(&mut state, 10);
+    kill(&mut state, victim, DamageSource::Stomp { by: killer });
+    run(&mut state, 1);
+    assert!(state.drain_kill_cams().is_empty());
+}
@@ -0,0 +1,63 @@
+mod common;
+
+use std::sync::Arc;
+use game_core::config::PlatformConfig;
+use game_core::map_check::{self, Severity};
+use game_core::PhysicsWorld;
+
+fn platform(id: &str, x_start: f32, y_top: f32) -> PlatformConfig {
+    PlatformConfig {
+        id: id.to_string(),
+        x_start,
+        x_end: x_start + 6.0,
+        y_top,
+        height: 0.5,
+        color: "#888888".to_string(),
+        surface: Default::default(),
+        restitution: None,
+    }
+}
+
+#[test]
+fn platforms_are_reachable_by_jumping_from_surfaces_the_player_can_reach() {
+    let mut config = common::open_ground();
+    let physics = &config.physics;
+    let jump_height = physics.jump_velocity * physics.jump_velocity / (2.0 * -physics.gravity);
+    let ground = physics.ground_y;
+    config.platforms = vec![
+        platform("step", 4.0, ground + jump_height * 0.6),
+        // Too high from the ground, but a jump from the step reaches it
+        platform("ledge", 12.0, ground + jump_height * 1.2),
+        platform("cloud", -20.0, ground + jump_height * 3.0),
+    ];
+    assert!(map_check::validate(&config).is_empty(), "{:?}", map_check::validate(&config));
+
+    let reachability = map_check::reachability(&PhysicsWorld::new(Arc::new(config)));
+    assert_eq!(reachability.reachable, ["step", "ledge"]);
+    assert_eq!(reachability.unreachable, ["cloud"]);
+}
+
+#[test]
+fn broken_geometry_is_reported_against_the_offending_object() {
+    let mut config = common::open_ground();
+    let ground = config.physics.ground_y;
+    let mut flat = platform("flat", 0.0, ground + 3.0);
+    flat.height = 0.0;
+    config.platforms = vec![platform("twin", 10.0, ground + 3.0), platform("twin", 30.0, ground + 3.0), flat];
+    config.physics.gravity = 1.0;
+
+    let issues = map_check::validate(&config);
+    let errors: Vec<(Option<&str>, &str)> = issues
+        .iter()
+        .filter(|issue| issue.severity == Severity::Error)
+        .map(|issue| (issue.id.as_deref(), issue.message.as_str()))
+        .collect();
+    assert_eq!(
+        errors,
+        [
+            (Some("twin"), "duplicate id"),
+            (Some("flat"), "height 0 must be positive"),
+            (None, "gravity 1 must be negative"),
+        ]
+    );
+}
@@ -0,0 +1,51 @@
+use game_core::config::{MapConfig, MapGeometry};
+use game_core::GameConfig;
+use serde_json::json;
+
+fn platform(id: &str) -> serde_json::Value {
+    json!({ "id": id, "x_start": -1.0, "x_end": 1.0, "y_top": 0.0, "height": 0.5, "color": "#B34733" })
+}
+
+#[test]
+fn maps_load_from_the_config_and_maps_dir_and_start_on_the_first() {
+    let dir = std::env::temp_dir().join(format!("maps-{}", uuid::Uuid::new_v4()));
+    std::fs::create_dir_all(dir.join("maps")).unwrap();
+    std::fs::write(dir.join("arena.json"), json!({ "platforms": [platform("arena_floor")] }).to_string()).unwrap();
+    std::fs::write(dir.join("maps/caves.json"), json!({ "platforms": [platform("caves_floor")] }).to_string()).unwrap();
+    std::fs::write(dir.join("maps/bridge.json"), json!({ "walls": [] }).to_string()).unwrap();
+    std::fs::write(dir.join("maps/notes.txt"), "not a map").unwrap();
+    // Already configured, so the directory's copy is skipped
+    std::fs::write(dir.join("maps/arena.json"), "{}").unwrap();
+
+    let inline = MapConfig {
+        id: "sky".to_string(),
+        name: Some("Sky Islands".to_string()),
+        file: None,
+        format: None,
+        geometry: serde_json::from_value::<MapGeometry>(json!({ "platforms": [platform("sky_island")] })).unwrap(),
+    };
+    let arena = MapConfig {
+        id: "arena".to_string(),
+        name: None,
+        file: Some("arena.json".to_string()),
+        format: None,
+        geometry: MapGeometry::default(),
+    };
+    let config = GameConfig {
+        maps: vec![arena, inline],
+        maps_dir: Some("maps".to_string()),
+        ..GameConfig::default()
+    };
+    std::fs::write(dir.join("config.json"), serde_json::to_string(&config).unwrap()).unwrap();
+
+    let loaded = GameConfig::load(dir.join("config.json")).unwrap();
+    let ids: Vec<&str> = loaded.maps.iter().map(|m| m.id.as_str()).collect();
+    assert_eq!(ids, ["arena", "sky", "bridge", "caves"]);
+    assert_eq!(loaded.maps[1].display_name(), "Sky Islands");
+    assert_eq!(loaded.maps[3].geometry.platforms[0].id, "caves_floor");
+    // The game opens on the first map's geometry
+    assert_eq!(loaded.platforms.len(), 1);
+    assert_eq!(loaded.platforms[0].id, "arena_floor");
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
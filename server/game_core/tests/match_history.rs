@@ -0,0 +1,34 @@
+use game_core::{MatchHistory, MatchQuery, MatchRecord};
+
+fn record(ended_at: u64) -> MatchRecord {
+    MatchRecord {
+        id: uuid::Uuid::new_v4(),
+        mode: "sandbox".to_string(),
+        started_at: ended_at - 60,
+        ended_at,
+        participants: Vec::new(),
+        winner: None,
+    }
+}
+
+#[tokio::test]
+async fn background_records_are_queryable_at_once_and_reach_the_file_in_order() {
+    let path = std::env::temp_dir().join(format!("matches-{}.jsonl", uuid::Uuid::new_v4()));
+    let mut history = MatchHistory::load_async(&path).await.unwrap();
+    for ended_at in 100..120 {
+        history.record_in_background(record(ended_at));
+    }
+    assert_eq!(history.len(), 20);
+
+    for _ in 0..200 {
+        if std::fs::read_to_string(&path).is_ok_and(|contents| contents.lines().count() == 20) {
+            break;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(5)).await;
+    }
+    let reloaded = MatchHistory::load_async(&path).await.unwrap();
+    let page = reloaded.query(&MatchQuery { limit: 100, ..MatchQuery::default() });
+    let ended: Vec<u64> = page.matches.iter().map(|record| record.ended_at).collect();
+    assert_eq!(ended, (100..120).rev().collect::<Vec<_>>());
+    let _ = std::fs::remove_file(&path);
+}
@@ -0,0 +1,98 @@
+use game_core::config::MapGeometry;
+use game_core::match_log::{LogRecord, MatchLogHeader, SnapshotPlayer};
+use game_core::{GroundState, HeldKey, MatchLog, MatchLogError, PlayerCommand};
+use uuid::Uuid;
+
+fn snapshot(id: Uuid, x: f32, score: u32, ground_state: GroundState) -> SnapshotPlayer {
+    SnapshotPlayer {
+        id,
+        x,
+        y: 1.0,
+        velocity_x: -2.5,
+        velocity_y: 0.0,
+        facing_right: false,
+        alive: true,
+        crouched: true,
+        ground_state,
+        score,
+        health: 75,
+    }
+}
+
+fn sample_log() -> MatchLog {
+    let (ada, bob) = (Uuid::from_u128(1), Uuid::from_u128(2));
+    let commands = [
+        PlayerCommand::MoveLeft,
+        PlayerCommand::Jump,
+        PlayerCommand::PlaceBlock { x: 3.5, y: -1.25 },
+        PlayerCommand::Shoot { dir_x: 0.6, dir_y: 0.8 },
+        PlayerCommand::Dash,
+        PlayerCommand::KeyDown { key: HeldKey::Right },
+        PlayerCommand::KeyUp { key: HeldKey::Left },
+        PlayerCommand::Move { axis: -0.35 },
+    ];
+    let mut records = vec![
+        LogRecord::Name { tick: 0, player_id: ada, name: "Ada".to_string() },
+        LogRecord::Name { tick: 0, player_id: bob, name: "Bøb".to_string() },
+        LogRecord::Snapshot {
+            tick: 0,
+            players: vec![
+                snapshot(ada, 0.0, 0, GroundState::Grounded { platform_id: None }),
+                snapshot(bob, 5.0, 0, GroundState::Flying),
+            ],
+        },
+    ];
+    records.extend(commands.into_iter().map(|command| LogRecord::Command { tick: 3, player_id: ada, command }));
+    records.push(LogRecord::Name { tick: 6, player_id: ada, name: "Ada II".to_string() });
+    records.push(LogRecord::Snapshot { tick: 6, players: vec![snapshot(ada, 2.0, 4, GroundState::Climbing { ladder_id: 0 })] });
+    MatchLog {
+        header: MatchLogHeader {
+            id: Uuid::from_u128(9),
+            mode: "standard".to_string(),
+            tick_rate_hz: 60.0,
+            snapshot_every_ticks: 6,
+            started_at_ms: 1_700_000_000_000,
+            geometry: MapGeometry::default(),
+        },
+        records,
+    }
+}
+
+#[test]
+fn logs_survive_encoding() {
+    let log = sample_log();
+    let bytes = log.encode();
+    assert!(bytes.starts_with(b"RLOG"));
+    let decoded = MatchLog::decode(&bytes).unwrap();
+    assert_eq!(decoded.header.id, log.header.id);
+    assert_eq!(decoded.header.snapshot_every_ticks, 6);
+    assert_eq!(decoded.records, log.records);
+    assert_eq!(decoded.commands().count(), 8);
+}
+
+#[test]
+fn frames_carry_the_names_at_the_time() {
+    let frames = sample_log().frames();
+    assert_eq!(frames.len(), 2);
+    assert_eq!(frames[0].players[1].name, "Bøb");
+    assert_eq!(frames[0].players[0].name, "Ada");
+    assert_eq!(frames[1].players[0].name, "Ada II");
+    assert_eq!(frames[1].players[0].score, 4);
+    assert!((frames[1].t - 0.1).abs() < 1e-6);
+    assert!((sample_log().duration_secs() - 0.1).abs() < 1e-6);
+}
+
+#[test]
+fn damaged_logs_are_refused() {
+    let bytes = sample_log().encode();
+    assert_eq!(MatchLog::decode(b"{\"id\": 1}").unwrap_err(), MatchLogError::NotAMatchLog);
+    assert_eq!(MatchLog::decode(&bytes[..bytes.len() - 3]).unwrap_err(), MatchLogError::Truncated);
+
+    let mut newer = bytes.clone();
+    newer[4] = 99;
+    assert_eq!(MatchLog::decode(&newer).unwrap_err(), MatchLogError::UnsupportedVersion(99));
+
+    let mut unknown = bytes;
+    unknown.push(b'X');
+    assert_eq!(MatchLog::decode(&unknown).unwrap_err(), MatchLogError::UnknownRecord(b'X'));
+}
@@ -0,0 +1,36 @@
+use game_core::mutators::{self, Mutator, MutatorBallot};
+use game_core::GameConfig;
+
+#[test]
+fn ballots_pick_the_most_voted_with_ties_in_listed_order() {
+    let (a, b, c) = (uuid::Uuid::new_v4(), uuid::Uuid::new_v4(), uuid::Uuid::new_v4());
+    let mut ballot = MutatorBallot::default();
+    ballot.vote(a, Mutator::BigHeads);
+    ballot.vote(b, Mutator::DoubleSpeed);
+    assert_eq!(ballot.take_next(), vec![Mutator::DoubleSpeed]);
+    assert!(ballot.take_next().is_empty(), "votes outlived the round");
+
+    ballot.vote(a, Mutator::BigHeads);
+    ballot.vote(b, Mutator::BigHeads);
+    ballot.vote(c, Mutator::LowGravity);
+    ballot.forget(&b);
+    ballot.vote(c, Mutator::OneHitKo);
+    assert_eq!(ballot.take_next(), vec![Mutator::OneHitKo]);
+
+    assert_eq!(Mutator::parse(" one-hit ko "), Some(Mutator::OneHitKo));
+    assert_eq!(Mutator::parse("DOUBLE_SPEED"), Some(Mutator::DoubleSpeed));
+}
+
+#[test]
+fn applying_and_restoring_round_trips_the_config() {
+    let original = GameConfig::default();
+    let mut config = original.clone();
+    mutators::apply(&mut config, &[Mutator::DoubleSpeed, Mutator::DoubleSpeed, Mutator::OneHitKo]);
+    assert_eq!(config.physics.max_horizontal_velocity, original.physics.max_horizontal_velocity * 2.0);
+    assert_eq!(config.health.max_health, 1);
+
+    mutators::restore(&mut config, &original);
+    assert_eq!(config.physics.max_horizontal_velocity, original.physics.max_horizontal_velocity);
+    assert_eq!(config.physics.move_acceleration, original.physics.move_acceleration);
+    assert_eq!(config.health.max_health, original.health.max_health);
+}
@@ -0,0 +1,141 @@
+use std::sync::Arc;
+use proptest::prelude::*;
+use game_core::config::{GameConfig, PhysicsConfig, PlatformConfig, WallConfig};
+use game_core::invariants;
+use game_core::map_check;
+use game_core::simulation::Simulation;
+use game_core::PlayerCommand;
+
+fn physics() -> impl Strategy<Value = PhysicsConfig> {
+    (
+        -300.0f32..-50.0,
+        10.0f32..80.0,
+        20.0f32..300.0,
+        20.0f32..300.0,
+        5.0f32..150.0,
+        -20.0f32..0.0,
+        0.5f32..2.0,
+        0.5f32..2.0,
+        0.0f32..50.0,
+    )
+        .prop_map(|(gravity, jump_velocity, move_acceleration, move_deceleration, max_horizontal_velocity, ground_y, player_width, player_height, friction)| {
+            PhysicsConfig {
+                gravity,
+                jump_velocity,
+                move_acceleration,
+                move_deceleration,
+                max_horizontal_velocity,
+                ground_y,
+                player_width,
+                player_height,
+                ground_slide_friction: friction,
+                platform_slide_friction: friction,
+                ground_color: "#8B6F47".to_string(),
+                crouch_height: player_height / 2.0,
+                slide_min_speed: 6.0,
+                slide_boost: 1.5,
+                slide_secs: 0.5,
+                ..GameConfig::default().physics
+            }
+        })
+}
+
+/// Platforms described relative to the ground so they always sit above it
+fn platforms() -> impl Strategy<Value = Vec<(f32, f32, f32, f32)>> {
+    prop::collection::vec((-20.0f32..15.0, 1.0f32..8.0, 1.0f32..20.0, 0.2f32..2.0), 0..5)
+}
+
+/// Walls bounding the map on either side, as (x, width, height)
+fn walls() -> impl Strategy<Value = Vec<(f32, f32, f32)>> {
+    (
+        prop::option::of((-30.0f32..-22.0, 0.5f32..2.0, 5.0f32..30.0)),
+        prop::option::of((23.0f32..30.0, 0.5f32..2.0, 5.0f32..30.0)),
+    )
+        .prop_map(|(left, right)| left.into_iter().chain(right).collect())
+}
+
+fn command() -> impl Strategy<Value = PlayerCommand> {
+    prop_oneof![
+        Just(PlayerCommand::MoveLeft),
+        Just(PlayerCommand::MoveRight),
+        Just(PlayerCommand::Jump),
+        Just(PlayerCommand::Stop),
+        (-2.0f32..2.0).prop_map(|axis| PlayerCommand::Move { axis }),
+    ]
+}
+
+fn config() -> impl Strategy<Value = GameConfig> {
+    (physics(), platforms(), walls()).prop_map(|(physics, platforms, walls)| {
+        let ground_y = physics.ground_y;
+        let platforms = platforms
+            .into_iter()
+            .enumerate()
+            .map(|(i, (x_start, width, above_ground, height))| PlatformConfig {
+                id: format!("platform_{}", i),
+                x_start,
+                x_end: x_start + width,
+                y_top: ground_y + above_ground + height,
+                height,
+                color: "#B34733".to_string(),
+                surface: Default::default(),
+                restitution: None,
+            })
+            .collect();
+        let walls = walls
+            .into_iter()
+            .enumerate()
+            .map(|(i, (x, width, height))| WallConfig {
+                id: format!("wall_{}", i),
+                x,
+                y_bottom: ground_y,
+                y_top: ground_y + height,
+                width,
+                color: "#666666".to_string(),
+            })
+            .collect();
+        GameConfig {
+            physics,
+            platforms,
+            walls,
+            ..GameConfig::default()
+        }
+    })
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig {
+        cases: 256,
+        max_global_rejects: 100_000,
+        ..ProptestConfig::default()
+    })]
+
+    /// Random maps and inputs never leave a player inside geometry, too fast,
+    /// or grounded on something they aren't standing on
+    #[test]
+    fn physics_invariants_hold(
+        config in config(),
+        inputs in prop::collection::vec((command(), 1usize..10), 1..150),
+    ) {
+        // Maps with gaps a player can get wedged into are flagged by map_check instead
+        prop_assume!(map_check::validate(&config).is_empty());
+        let mut sim = Simulation::new(Arc::new(config));
+        let player = sim.add_player();
+        // Spawning inside geometry is a map error, not a physics one
+        prop_assume!(invariants::check_state(sim.state()).is_empty());
+
+        for (command, ticks) in inputs {
+            sim.command(&player, &command);
+            for _ in 0..ticks {
+                sim.step();
+                let violations = invariants::check_state(sim.state());
+                prop_assert!(
+                    violations.is_empty(),
+                    "tick {} after {:?}: {}",
+                    sim.state().tick,
+                    command,
+                    violations.iter().map(|(_, v)| v.to_string()).collect::<Vec<_>>().join("; ")
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,103 @@
+use std::sync::Arc;
+use serde_json::{json, Map, Value};
+use game_core::config::{MatchConfig, PhysicsBounds, PhysicsSandboxConfig};
+use game_core::{GameConfig, GameState, ModeSwitch, Mutator, PhysicsPatchError, PhysicsWorld};
+
+fn room() -> GameState {
+    let config = GameConfig {
+        matches: MatchConfig {
+            enabled: false,
+            ..MatchConfig::default()
+        },
+        physics_sandbox: PhysicsSandboxConfig { enabled: true, ..PhysicsSandboxConfig::default() },
+        ..GameConfig::default()
+    };
+    GameState::new(Arc::new(PhysicsWorld::new(Arc::new(config))))
+}
+
+fn changes(value: Value) -> Map<String, Value> {
+    value.as_object().unwrap().clone()
+}
+
+#[test]
+fn changes_within_bounds_rebuild_the_world_and_bump_the_config_version() {
+    let mut state = room();
+    let version = state.config_version();
+    let physics = state.patch_physics(&changes(json!({ "gravity": -60.0, "jump_velocity": 80 }))).unwrap();
+    assert_eq!(physics.gravity, -60.0);
+    assert_eq!(state.world.config().physics.jump_velocity, 80.0);
+    assert_eq!(state.take_config_change(), Some(version + 1));
+    assert_eq!(state.take_config_change(), None);
+
+    // Mutators keep scaling the changed value, and undo to it rather than the original
+    let scale = state.world.config().mutators.low_gravity_scale;
+    assert_eq!(state.pick_mutators(vec![Mutator::LowGravity]), ModeSwitch::Applied);
+    assert_eq!(state.world.config().physics.gravity, -60.0 * scale);
+    state.patch_physics(&changes(json!({ "gravity": -100.0 }))).unwrap();
+    assert_eq!(state.world.config().physics.gravity, -100.0 * scale);
+    state.pick_mutators(Vec::new());
+    assert_eq!(state.world.config().physics.gravity, -100.0);
+}
+
+#[test]
+fn changes_outside_bounds_or_to_fixed_settings_are_refused_whole() {
+    let mut state = room();
+    let before = state.world.config().physics.clone();
+    let out_of_bounds = state.patch_physics(&changes(json!({ "jump_velocity": 80, "gravity": 10.0 })));
+    assert_eq!(
+        out_of_bounds.unwrap_err(),
+        PhysicsPatchError::OutOfBounds { field: "gravity".to_string(), min: -300.0, max: -20.0 }
+    );
+    assert_eq!(
+        state.patch_physics(&changes(json!({ "player_width": 3.0 }))).unwrap_err(),
+        PhysicsPatchError::NotAdjustable("player_width".to_string())
+    );
+    assert_eq!(
+        state.patch_physics(&changes(json!({ "gravity": "heavy" }))).unwrap_err(),
+        PhysicsPatchError::NotANumber("gravity".to_string())
+    );
+    assert_eq!(state.patch_physics(&Map::new()).unwrap_err(), PhysicsPatchError::Empty);
+    assert_eq!(state.world.config().physics.jump_velocity, before.jump_velocity);
+    assert_eq!(state.take_config_change(), None);
+}
+
+#[test]
+fn bounds_on_settings_that_arent_numbers_refuse_instead_of_panicking() {
+    let mut sandbox = PhysicsSandboxConfig { enabled: true, ..PhysicsSandboxConfig::default() };
+    sandbox.bounds.insert("ground_color".to_string(), PhysicsBounds { min: 0.0, max: 1.0 });
+    let config = GameConfig { physics_sandbox: sandbox, ..GameConfig::default() };
+    let mut state = GameState::new(Arc::new(PhysicsWorld::new(Arc::new(config))));
+    assert_eq!(
+        state.patch_physics(&changes(json!({ "ground_color": 0.5 }))).unwrap_err(),
+        PhysicsPatchError::NotAdjustable("ground_color".to_string())
+    );
+    assert_eq!(state.take_config_change(), None);
+}
+
+#[test]
+fn physics_stay_fixed_unless_the_sandbox_is_turned_on() {
+    let mut state = GameState::new(Arc::new(PhysicsWorld::new(Arc::new(GameConfig::default()))));
+    let before = state.world.config().physics.gravity;
+    assert_eq!(
+        state.patch_physics(&changes(json!({ "gravity": -60.0 }))).unwrap_err(),
+        PhysicsPatchError::Disabled
+    );
+    assert_eq!(state.world.config().physics.gravity, before);
+}
+
+#[test]
+fn the_first_player_to_join_creates_the_room_until_they_leave() {
+    let mut state = room();
+    let creator = state.new_player_id();
+    let other = state.new_player_id();
+    let bot = state.new_player_id();
+    assert!(state.add_bot(bot, "Bot"));
+    state.join(creator);
+    state.join(other);
+    assert_eq!(state.room_creator, Some(creator));
+    state.remove_player(&creator);
+    assert_eq!(state.room_creator, None);
+    let next = state.new_player_id();
+    state.join(next);
+    assert_eq!(state.room_creator, Some(next));
+}
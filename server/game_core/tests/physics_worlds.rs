@@ -0,0 +1,45 @@
+mod common;
+
+use std::sync::Arc;
+use game_core::{GroundState, PhysicsWorld, Player};
+
+const DT: f32 = 1.0 / 60.0;
+
+/// A player dropped from five units above the ground
+fn falling(world: &PhysicsWorld) -> Player {
+    let mut player = Player::new(uuid::Uuid::nil(), &world.config().physics);
+    player.y += 5.0;
+    player.ground_state = GroundState::Flying;
+    player
+}
+
+#[test]
+fn worlds_with_different_configs_simulate_side_by_side() {
+    let earth = PhysicsWorld::new(Arc::new(common::open_ground()));
+    let mut moon_config = common::open_ground();
+    moon_config.physics.gravity /= 6.0;
+    moon_config.physics.ground_y -= 20.0;
+    let moon = PhysicsWorld::new(Arc::new(moon_config));
+
+    let mut on_earth = falling(&earth);
+    let mut on_moon = falling(&moon);
+    let (earth_start, moon_start) = (on_earth.y, on_moon.y);
+    assert_eq!(moon_start, earth_start - 20.0);
+    for _ in 0..4 {
+        earth.update_player_physics(&mut on_earth, DT, &[]);
+        moon.update_player_physics(&mut on_moon, DT, &[]);
+    }
+    // Each world keeps its own gravity, whichever was built last
+    let earth_drop = earth_start - on_earth.y;
+    let moon_drop = moon_start - on_moon.y;
+    assert!(moon_drop > 0.0);
+    assert!(earth_drop > 4.0 * moon_drop, "earth {} moon {}", earth_drop, moon_drop);
+
+    // And its own ground
+    for _ in 0..600 {
+        earth.update_player_physics(&mut on_earth, DT, &[]);
+        moon.update_player_physics(&mut on_moon, DT, &[]);
+    }
+    assert!((on_earth.y - (earth_start - 5.0)).abs() < 0.01);
+    assert!((on_moon.y - (moon_start - 5.0)).abs() < 0.01);
+}
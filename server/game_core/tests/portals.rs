@@ -0,0 +1,114 @@
+mod common;
+
+use std::sync::Arc;
+use game_core::config::{PortalConfig, ValidationError};
+use game_core::{GameConfig, PhysicsWorld, Player};
+
+const DT: f32 = 1.0 / 60.0;
+
+fn portal(id: &str, x_start: f32, y_bottom: f32, exit: &str) -> PortalConfig {
+    PortalConfig {
+        id: id.to_string(),
+        x_start,
+        x_end: x_start + 2.0,
+        y_bottom,
+        y_top: y_bottom + 4.0,
+        exit: exit.to_string(),
+        drop_velocity: false,
+        rotation_degrees: 0.0,
+        cooldown_secs: 0.5,
+        color: "#AA44FF".to_string(),
+    }
+}
+
+/// Open ground at 0 with a two-way pair at x = 0 and x = 20, and a one-way portal at
+/// x = 40 coming out high up at x = 60
+fn world() -> PhysicsWorld {
+    let mut config = common::open_ground();
+    config.physics.ground_y = 0.0;
+    config.portals = vec![
+        portal("a", 0.0, 0.0, "b"),
+        portal("b", 20.0, 0.0, "a"),
+        PortalConfig { rotation_degrees: 90.0, ..portal("chute", 40.0, 0.0, "sky") },
+        PortalConfig { drop_velocity: true, ..portal("sky", 60.0, 30.0, "chute") },
+    ];
+    PhysicsWorld::new(Arc::new(config))
+}
+
+fn player_at(world: &PhysicsWorld, x: f32, y: f32) -> Player {
+    let mut player = Player::new(uuid::Uuid::nil(), &world.config().physics);
+    player.x = x;
+    player.y = y;
+    player
+}
+
+#[test]
+fn entering_a_portal_comes_out_of_its_exit_once_until_the_player_leaves() {
+    let world = world();
+    let mut player = player_at(&world, 1.0, 1.0);
+    let teleport = world.pass_through_portal(&mut player, DT).unwrap();
+    assert_eq!((teleport.portal.as_str(), teleport.exit.as_str()), ("a", "b"));
+    assert_eq!((teleport.from_x, teleport.from_y), (1.0, 1.0));
+    assert_eq!((player.x, player.y), (21.0, 2.0));
+    assert_eq!(player.teleports, 1);
+
+    // However long they stand in the exit, it doesn't send them back
+    for _ in 0..120 {
+        assert!(world.pass_through_portal(&mut player, DT).is_none());
+    }
+
+    // Out of every portal for the cooldown, the next one takes them again
+    player.x = 30.0;
+    for _ in 0..40 {
+        assert!(world.pass_through_portal(&mut player, DT).is_none());
+    }
+    player.x = 21.0;
+    assert_eq!(world.pass_through_portal(&mut player, DT).unwrap().exit, "a");
+}
+
+#[test]
+fn velocity_is_turned_by_the_entry_portal_or_dropped() {
+    let world = world();
+    let mut player = player_at(&world, 41.0, 1.0);
+    player.velocity_x = 10.0;
+    player.velocity_y = 0.0;
+    world.pass_through_portal(&mut player, DT).unwrap();
+    assert!(player.velocity_x.abs() < 1e-4);
+    assert!((player.velocity_y - 10.0).abs() < 1e-4);
+    assert_eq!((player.x, player.y), (61.0, 32.0));
+
+    let mut falling = player_at(&world, 61.0, 32.0);
+    falling.velocity_y = -15.0;
+    world.pass_through_portal(&mut falling, DT).unwrap();
+    assert_eq!((falling.velocity_x, falling.velocity_y), (0.0, 0.0));
+}
+
+#[test]
+fn portals_must_exit_through_another_portal() {
+    let config = GameConfig {
+        portals: vec![
+            portal("loop", 0.0, 0.0, "loop"),
+            portal("lost", 4.0, 0.0, "nowhere"),
+            PortalConfig { rotation_degrees: f32::INFINITY, ..portal("spin", 8.0, 0.0, "loop") },
+        ],
+        ..GameConfig::default()
+    };
+    let errors = config.validate().unwrap_err().0;
+    assert!(errors.contains(&ValidationError::UnknownPortalExit { id: "loop".to_string(), exit: "loop".to_string() }));
+    assert!(errors.contains(&ValidationError::UnknownPortalExit { id: "lost".to_string(), exit: "nowhere".to_string() }));
+    assert!(errors.contains(&ValidationError::DegeneratePortal { id: "spin".to_string() }));
+}
+
+#[test]
+fn two_way_pairs_need_a_cooldown() {
+    let config = GameConfig {
+        portals: vec![
+            PortalConfig { cooldown_secs: 0.0, ..portal("a", 0.0, 0.0, "b") },
+            portal("b", 20.0, 0.0, "a"),
+            PortalConfig { cooldown_secs: 0.0, ..portal("chute", 40.0, 0.0, "a") },
+        ],
+        ..GameConfig::default()
+    };
+    let errors = config.validate().unwrap_err().0;
+    assert_eq!(errors, vec![ValidationError::PortalPairWithoutCooldown { id: "a".to_string(), exit: "b".to_string() }]);
+}
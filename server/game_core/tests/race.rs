@@ -0,0 +1,142 @@
+use std::sync::Arc;
+use std::time::Duration;
+use game_core::config::{CheckpointConfig, GameRules, MatchConfig, RaceConfig, ValidationError};
+use game_core::{GameConfig, GameState, MatchState, MockClock, PhysicsWorld, RaceStandings};
+
+const DT: f32 = 1.0 / 60.0;
+
+fn checkpoint(id: &str, x_start: f32, ground_y: f32) -> CheckpointConfig {
+    CheckpointConfig {
+        id: id.to_string(),
+        x_start,
+        x_end: x_start + 2.0,
+        y_bottom: ground_y,
+        y_top: ground_y + 5.0,
+        color: "#FFD700".to_string(),
+    }
+}
+
+/// A two-lap race out to a gate at x = 10 and back to the finish at x = -10, starting
+/// as soon as two players are in
+fn room() -> (GameState, Arc<MockClock>) {
+    let mut config = GameConfig {
+        game_mode: GameRules::RACE,
+        race: RaceConfig { laps: 2 },
+        matches: MatchConfig {
+            min_players: 2,
+            countdown_secs: 0,
+            ..MatchConfig::default()
+        },
+        platforms: Vec::new(),
+        ..GameConfig::default()
+    };
+    let ground_y = config.physics.ground_y;
+    config.checkpoints = vec![checkpoint("gate", 10.0, ground_y), checkpoint("finish", -11.0, ground_y)];
+    let clock = Arc::new(MockClock::new());
+    let state = GameState::with_clock(Arc::new(PhysicsWorld::new(Arc::new(config))), clock.clone());
+    (state, clock)
+}
+
+fn run(state: &mut GameState, clock: &MockClock, ticks: usize) {
+    for _ in 0..ticks {
+        clock.advance(Duration::from_secs_f32(DT));
+        state.update(DT);
+    }
+}
+
+fn join(state: &mut GameState) -> uuid::Uuid {
+    let player_id = state.new_player_id();
+    state.add_player(player_id);
+    player_id
+}
+
+fn standings(state: &GameState) -> RaceStandings {
+    serde_json::from_value(state.mode_status().expect("race mode keeps standings").state).unwrap()
+}
+
+fn progress(state: &GameState, player_id: uuid::Uuid) -> game_core::RaceStanding {
+    standings(state).racers.into_iter().find(|r| r.player_id == player_id).unwrap()
+}
+
+/// Put a player on the ground at `x` and let a tick pass
+fn visit(state: &mut GameState, clock: &MockClock, player_id: uuid::Uuid, x: f32) {
+    let ground_y = state.world.config().physics.ground_y;
+    let player = state.players.get_mut(&player_id).unwrap();
+    player.x = x;
+    player.y = ground_y + 1.0;
+    player.velocity_x = 0.0;
+    run(state, clock, 1);
+}
+
+#[test]
+fn checkpoints_count_in_order_and_laps_are_timed() {
+    let (mut state, clock) = room();
+    let (alice, bob) = (join(&mut state), join(&mut state));
+    run(&mut state, &clock, 2);
+    assert!(matches!(state.match_state, MatchState::Playing { .. }));
+    assert_eq!(standings(&state).racers.len(), 2);
+
+    // The finish doesn't count before the gate
+    visit(&mut state, &clock, alice, -10.0);
+    assert_eq!(progress(&state, alice).progress.next_checkpoint, 0);
+
+    visit(&mut state, &clock, alice, 11.0);
+    run(&mut state, &clock, 60);
+    visit(&mut state, &clock, alice, -10.0);
+    let progress = progress(&state, alice).progress;
+    assert_eq!((progress.laps, progress.next_checkpoint), (1, 0));
+    assert!(progress.lap_times_ms[0] >= 1000, "lap took {}ms", progress.lap_times_ms[0]);
+
+    visit(&mut state, &clock, alice, 11.0);
+    visit(&mut state, &clock, alice, -10.0);
+    let standings = standings(&state);
+    assert_eq!(standings.racers[0].player_id, alice);
+    assert_eq!(standings.racers[0].place, Some(1));
+    assert!(!standings.complete);
+    assert_eq!(state.players[&alice].score, 2);
+    assert!(matches!(state.match_state, MatchState::Playing { .. }));
+
+    // The last racer home ends the match, with the winner on top
+    for _ in 0..2 {
+        visit(&mut state, &clock, bob, 11.0);
+        visit(&mut state, &clock, bob, -10.0);
+    }
+    assert_eq!(state.players[&bob].score, 1);
+    match &state.match_state {
+        MatchState::Ended { result, .. } => assert_eq!(result.winner, Some(alice)),
+        other => panic!("race didn't end the match: {:?}", other),
+    }
+}
+
+#[test]
+fn checkpoints_only_count_in_race_mode() {
+    let (state, _) = room();
+    let mut config = (**state.world.config()).clone();
+    config.game_mode = GameRules::STANDARD;
+    let clock = Arc::new(MockClock::new());
+    let mut state = GameState::with_clock(Arc::new(PhysicsWorld::new(Arc::new(config))), clock.clone());
+    let alice = join(&mut state);
+    join(&mut state);
+    run(&mut state, &clock, 2);
+    assert_eq!(state.rules().rules(), GameRules::STANDARD);
+
+    // Two full laps of the course would win a race
+    for _ in 0..2 {
+        visit(&mut state, &clock, alice, 11.0);
+        visit(&mut state, &clock, alice, -10.0);
+    }
+    assert!(state.mode_status().is_none());
+    assert!(!state.rules().round_over(state.world.config()));
+    assert_eq!(state.players[&alice].score, 0);
+    assert!(matches!(state.match_state, MatchState::Playing { .. }));
+}
+
+#[test]
+fn race_mode_needs_a_course() {
+    let config = GameConfig {
+        game_mode: GameRules::RACE,
+        ..GameConfig::default()
+    };
+    let errors = config.validate().unwrap_err().0;
+    assert!(errors.contains(&ValidationError::RaceWithoutCheckpoints));
+}
@@ -0,0 +1,59 @@
+use game_core::config::MapGeometry;
+use game_core::replay::{trajectory_svg, Highlight, Replay, ReplayFrame, ReplayPlayer};
+use game_core::ReplaySummary;
+use uuid::Uuid;
+
+fn sample(id: Uuid, name: &str, x: f32, y: f32, score: u64, alive: bool) -> ReplayPlayer {
+    ReplayPlayer {
+        id,
+        name: name.to_string(),
+        x,
+        y,
+        score,
+        alive,
+    }
+}
+
+#[test]
+fn summaries_track_travel_deaths_and_lead_changes() {
+    let (ada, bob) = (Uuid::from_u128(1), Uuid::from_u128(2));
+    // Players stand 1 above the ground (center of a 2-tall player)
+    let frames = vec![
+        ReplayFrame { t: 0.0, players: vec![sample(ada, "Ada", 0.0, 1.0, 0, true), sample(bob, "Bob", 5.0, 1.0, 0, true)] },
+        ReplayFrame { t: 1.0, players: vec![sample(ada, "Ada", 3.0, 5.0, 1, true), sample(bob, "Bob", 5.0, 1.0, 0, true)] },
+        ReplayFrame { t: 2.0, players: vec![sample(ada, "Ada", 3.0, 1.0, 1, false), sample(bob, "Bob", 5.0, 1.0, 2, true)] },
+        // Ada respawns far away; the teleport isn't travel
+        ReplayFrame { t: 3.0, players: vec![sample(ada, "Ada", 40.0, 1.0, 1, true), sample(bob, "Bob", 6.0, 1.0, 2, true)] },
+    ];
+    let replay = Replay {
+        id: Uuid::from_u128(9),
+        mode: "standard".to_string(),
+        ground_y: 0.0,
+        ground_segments: Vec::new(),
+        player_height: 2.0,
+        geometry: MapGeometry::default(),
+        frames,
+    };
+
+    let summary = ReplaySummary::of(&replay);
+    assert_eq!(summary.duration_secs, 3.0);
+    let names: Vec<&str> = summary.players.iter().map(|p| p.name.as_str()).collect();
+    assert_eq!(names, ["Bob", "Ada"]);
+    let ada_stats = &summary.players[1];
+    assert_eq!(ada_stats.distance, 5.0);
+    assert_eq!(ada_stats.max_height, 4.0);
+    assert_eq!(ada_stats.deaths, 1);
+    assert_eq!(
+        summary.highlights,
+        vec![
+            Highlight::TookLead { t: 1.0, player: "Ada".to_string(), score: 1 },
+            Highlight::HighestPoint { t: 1.0, player: "Ada".to_string(), height: 4.0 },
+            Highlight::Died { t: 2.0, player: "Ada".to_string() },
+            Highlight::TookLead { t: 2.0, player: "Bob".to_string(), score: 2 },
+        ]
+    );
+
+    // Ada's path breaks at the death: two lines for her, one for Bob
+    let svg = trajectory_svg(&replay);
+    assert_eq!(svg.matches("<polyline").count(), 3);
+}
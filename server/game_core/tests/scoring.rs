@@ -0,0 +1,102 @@
+use std::sync::Arc;
+use game_core::config::{ComboConfig, MatchConfig};
+use game_core::{ChallengeTracker, ComboState, GameConfig, GameState, PhysicsWorld, ScoreSource};
+
+const DT: f32 = 1.0 / 60.0;
+
+/// A sandbox with one player, so no match start resets their combo
+fn state_with_player() -> (GameState, uuid::Uuid) {
+    let config = GameConfig {
+        matches: MatchConfig {
+            enabled: false,
+            ..MatchConfig::default()
+        },
+        ..GameConfig::default()
+    };
+    let mut state = GameState::new(Arc::new(PhysicsWorld::new(Arc::new(config))));
+    let player_id = state.new_player_id();
+    state.add_player(player_id);
+    (state, player_id)
+}
+
+fn run(state: &mut GameState, secs: f32) {
+    for _ in 0..(secs / DT).round() as usize {
+        state.update(DT);
+    }
+}
+
+#[test]
+fn a_combo_multiplies_each_source_and_resets_once_its_window_expires() {
+    let config = ComboConfig {
+        window_secs: 1.0,
+        multiplier_step: 1.0,
+        max_multiplier: 2.5,
+        ..ComboConfig::default()
+    };
+    let mut combo = ComboState::default();
+    assert_eq!(combo.decay(5.0).map(|broken| broken.count), None);
+
+    assert_eq!(combo.register(ScoreSource::Tag, &config), 50);
+    assert_eq!(combo.register(ScoreSource::Coin, &config), 20);
+    assert_eq!(combo.register(ScoreSource::Tag, &config), 125);
+    assert_eq!(combo.multiplier, 2.5);
+
+    assert!(combo.decay(0.6).is_none());
+    let broken = combo.decay(0.6).unwrap();
+    assert_eq!((broken.count, broken.multiplier), (3, 2.5));
+    assert_eq!((combo.count, combo.multiplier), (0, 1.0));
+    assert!(combo.decay(1.0).is_none());
+}
+
+#[test]
+fn combos_escalate_up_to_the_cap_and_break_when_the_window_runs_out() {
+    let (mut state, player_id) = state_with_player();
+    let combo = state.world.config().combo.clone();
+
+    let awarded: Vec<u64> = (0..8).map(|_| state.award_points(&player_id, ScoreSource::Coin).unwrap()).collect();
+    // 10 points a coin, half again more for each coin in the chain, at most four times as many
+    assert_eq!(awarded, vec![10, 15, 20, 25, 30, 35, 40, 40]);
+    assert_eq!(state.players[&player_id].combo.count, 8);
+    assert_eq!(state.players[&player_id].combo.multiplier, combo.max_multiplier);
+    assert_eq!(state.award_points(&uuid::Uuid::new_v4(), ScoreSource::Coin), None);
+
+    // Scoring again inside the window keeps the chain going
+    run(&mut state, combo.window_secs / 2.0);
+    assert!(state.drain_combo_breaks().is_empty());
+    state.award_points(&player_id, ScoreSource::Coin);
+
+    run(&mut state, combo.window_secs + 0.1);
+    let breaks = state.drain_combo_breaks();
+    assert_eq!(breaks.len(), 1);
+    assert_eq!((breaks[0].player_id, breaks[0].count), (player_id, 9));
+    assert!(state.drain_combo_breaks().is_empty());
+    assert_eq!(state.players[&player_id].combo.count, 0);
+    assert_eq!(state.award_points(&player_id, ScoreSource::Tag), Some(combo.tag_points));
+}
+
+#[test]
+fn completed_challenges_pay_out_once_and_are_drained_once() {
+    let (mut state, player_id) = state_with_player();
+    // A day whose challenges include earning 500 points
+    let day = (0..1000)
+        .find(|day| ChallengeTracker::new(*day).challenges().iter().any(|c| c.id == "points_500"))
+        .unwrap();
+    state.challenges = ChallengeTracker::new(day);
+    let reward = state.challenges.challenges().iter().find(|c| c.id == "points_500").unwrap().reward_points;
+
+    let mut earned = 0;
+    while earned < 500 {
+        assert!(state.drain_challenge_completions().iter().all(|c| c.challenge_id != "points_500"));
+        earned += state.award_points(&player_id, ScoreSource::Tag).unwrap();
+    }
+    let completions = state.drain_challenge_completions();
+    let points = completions.iter().find(|c| c.challenge_id == "points_500").unwrap();
+    assert_eq!((points.player_id, points.reward_points), (player_id, reward));
+    let rewards: u64 = completions.iter().map(|c| c.reward_points).sum();
+    assert_eq!(state.players[&player_id].score, earned + rewards);
+    let progress = state.challenges.progress_for(&player_id);
+    assert!(progress.iter().any(|p| p.challenge_id == "points_500" && p.completed && p.progress == p.target));
+
+    state.award_points(&player_id, ScoreSource::Tag);
+    assert!(state.drain_challenge_completions().iter().all(|c| c.challenge_id != "points_500"));
+}
@@ -0,0 +1,167 @@
+use std::sync::Arc;
+use std::time::Duration;
+use game_core::config::{GameModeConfig, MatchConfig};
+use game_core::{DamageSource, GameConfig, GameState, HeldKey, MatchState, MockClock, PhysicsWorld, PlayerCommand, StateFrame, Verdict};
+use serde_json::json;
+
+const DT: f32 = 1.0 / 60.0;
+
+/// A room whose mode slows to `scale` for a second on the given triggers
+fn room(triggers: serde_json::Value, scale: f32) -> (GameState, Arc<MockClock>) {
+    room_with(MatchConfig::default(), triggers, scale)
+}
+
+fn room_with(matches: MatchConfig, triggers: serde_json::Value, scale: f32) -> (GameState, Arc<MockClock>) {
+    let mut config = GameConfig {
+        tick_rate_hz: 60.0,
+        matches,
+        ..GameConfig::default()
+    };
+    let mode: GameModeConfig = serde_json::from_value(json!({
+        "id": config.matches.mode,
+        "slow_motion": { "triggers": triggers, "scale": scale, "duration_secs": 1.0 }
+    }))
+    .unwrap();
+    config.modes = vec![mode];
+    let clock = Arc::new(MockClock::new());
+    let state = GameState::with_clock(Arc::new(PhysicsWorld::new(Arc::new(config))), clock.clone());
+    (state, clock)
+}
+
+/// Step the room `ticks` times, advancing the clock in real time before each
+fn run(state: &mut GameState, clock: &MockClock, ticks: usize) {
+    for _ in 0..ticks {
+        clock.advance(Duration::from_secs_f32(DT));
+        state.update(DT);
+    }
+}
+
+fn join(state: &mut GameState) -> uuid::Uuid {
+    let player_id = state.new_player_id();
+    state.add_player(player_id);
+    player_id
+}
+
+#[test]
+fn kills_slow_the_room_for_the_configured_real_time() {
+    let (mut state, clock) = room(json!(["kill"]), 0.25);
+    let (killer, victim) = (join(&mut state), join(&mut state));
+    run(&mut state, &clock, 90);
+    assert_eq!(state.time_scale(), 1.0);
+
+    state.players.get_mut(&victim).unwrap().invulnerable_secs = 0.0;
+    state.damage_player(&victim, u32::MAX, DamageSource::Stomp { by: killer }, None);
+    run(&mut state, &clock, 1);
+    assert_eq!(state.time_scale(), 0.25);
+    assert_eq!(StateFrame::from_state(&state, 0).time_scale, 0.25);
+
+    // A second of real time, not of slowed game time
+    run(&mut state, &clock, 59);
+    assert_eq!(state.time_scale(), 0.25);
+    run(&mut state, &clock, 1);
+    assert_eq!(state.time_scale(), 1.0);
+}
+
+#[test]
+fn a_pause_freezes_everyone_until_it_runs_out() {
+    let (mut state, clock) = room(json!([]), 0.0);
+    let player_id = join(&mut state);
+    run(&mut state, &clock, 90);
+    state.apply_command(&player_id, &PlayerCommand::KeyDown { key: HeldKey::Right }, 0);
+    run(&mut state, &clock, 10);
+
+    state.start_slow_motion(0.0, 0.5);
+    let x = state.players[&player_id].x;
+    run(&mut state, &clock, 20);
+    assert_eq!(state.players[&player_id].x, x);
+    run(&mut state, &clock, 20);
+    assert!(state.players[&player_id].x > x);
+}
+
+#[test]
+fn only_the_modes_triggers_start_it() {
+    let (mut state, clock) = room(json!(["kill"]), 0.5);
+    let player_id = join(&mut state);
+    run(&mut state, &clock, 90);
+    // Dying to nobody isn't a kill
+    state.players.get_mut(&player_id).unwrap().invulnerable_secs = 0.0;
+    state.damage_player(&player_id, u32::MAX, DamageSource::Hazard { hazard_id: "lava".to_string() }, None);
+    run(&mut state, &clock, 1);
+    assert_eq!(state.time_scale(), 1.0);
+
+    let matches = MatchConfig {
+        enabled: true,
+        min_players: 1,
+        countdown_secs: 0,
+        duration_secs: 1,
+        ..MatchConfig::default()
+    };
+    let (mut state, clock) = room_with(matches, json!(["match_end"]), 0.5);
+    join(&mut state);
+    run(&mut state, &clock, 2);
+    assert!(matches!(state.match_state, MatchState::Playing { .. }));
+    assert_eq!(state.time_scale(), 1.0);
+    run(&mut state, &clock, 60);
+    assert!(matches!(state.match_state, MatchState::Ended { .. }));
+    assert_eq!(state.time_scale(), 0.5);
+}
+
+#[test]
+fn inputs_replay_the_same_through_slow_motion() {
+    let (mut state, clock) = room(json!([]), 0.5);
+    let player_id = join(&mut state);
+    run(&mut state, &clock, 120);
+    state.apply_command(&player_id, &PlayerCommand::MoveRight, 0);
+    run(&mut state, &clock, 20);
+    state.start_slow_motion(0.4, 0.25);
+    state.apply_command(&player_id, &PlayerCommand::Jump, 0);
+    run(&mut state, &clock, 30);
+
+    let frames: Vec<_> = state.input_history.frames(&player_id).cloned().collect();
+    assert!(frames.iter().any(|frame| frame.time_scale == 0.4));
+    assert!(frames.iter().any(|frame| frame.time_scale == 1.0));
+    let check = game_core::anti_cheat::replay(&state, player_id, &frames, DT);
+    assert_eq!(check.verdict, Verdict::Consistent, "deviated by {}", check.max_deviation);
+}
+
+#[test]
+fn kills_close_together_slow_the_room_once() {
+    let (mut state, clock) = room(json!(["kill"]), 0.25);
+    let killer = join(&mut state);
+    let (first, second) = (join(&mut state), join(&mut state));
+    run(&mut state, &clock, 90);
+    let kill = |state: &mut GameState, victim: uuid::Uuid| {
+        state.players.get_mut(&victim).unwrap().invulnerable_secs = 0.0;
+        state.damage_player(&victim, u32::MAX, DamageSource::Stomp { by: killer }, None);
+    };
+
+    kill(&mut state, first);
+    run(&mut state, &clock, 61);
+    assert_eq!(state.time_scale(), 1.0);
+    // Within the default gap after the first slow motion ended
+    kill(&mut state, second);
+    run(&mut state, &clock, 1);
+    assert_eq!(state.time_scale(), 1.0);
+
+    run(&mut state, &clock, 5 * 60);
+    kill(&mut state, first);
+    run(&mut state, &clock, 1);
+    assert_eq!(state.time_scale(), 0.25);
+}
+
+#[test]
+fn slow_motion_under_way_is_saved_in_snapshots() {
+    let (mut state, clock) = room(json!([]), 0.5);
+    join(&mut state);
+    run(&mut state, &clock, 10);
+    state.start_slow_motion(0.4, 1.0);
+    let snapshot = state.snapshot();
+    assert!(snapshot.slow_motion.is_some());
+
+    let (mut restored, restored_clock) = room(json!([]), 0.5);
+    restored_clock.advance(clock.elapsed());
+    restored.restore(snapshot).unwrap();
+    assert_eq!(restored.time_scale(), 0.4);
+    run(&mut restored, &restored_clock, 61);
+    assert_eq!(restored.time_scale(), 1.0);
+}
@@ -0,0 +1,115 @@
+use std::sync::Arc;
+use game_core::config::{DeterminismConfig, GameRules, MatchConfig, PhysicsSandboxConfig, ValidationError, WallConfig};
+use game_core::simulation::{BotAction, ScriptedBot, Simulation};
+use game_core::{Encoding, GameConfig, GameState, MatchState, PhysicsWorld, PlayerCommand, SnapshotError, WorldSnapshot};
+
+fn busy_world() -> GameState {
+    let config = GameConfig {
+        determinism: DeterminismConfig { seed: Some(5) },
+        ..GameConfig::default()
+    };
+    let mut sim = Simulation::new(Arc::new(config));
+    sim.add_bot(ScriptedBot::new().at(0.0, BotAction::Hold(PlayerCommand::MoveRight)).at(0.5, BotAction::Press(PlayerCommand::Jump)));
+    let shooter = sim.add_player();
+    sim.run_until(1.0, |_| false);
+    sim.command(&shooter, &PlayerCommand::Shoot { dir_x: 1.0, dir_y: 0.5 });
+    let (x, y) = (sim.state().players[&shooter].x, sim.state().players[&shooter].y);
+    sim.command(&shooter, &PlayerCommand::PlaceBlock { x: x - 2.0, y });
+    sim.run_until(0.1, |_| false);
+    sim.state().clone()
+}
+
+#[test]
+fn restored_worlds_carry_on_exactly_where_they_were() {
+    for format in [Encoding::Json, Encoding::MessagePack] {
+        let mut original = busy_world();
+        assert!(!original.projectiles.is_empty(), "no projectiles");
+        assert!(!original.blocks.blocks.is_empty(), "no blocks");
+        let bytes = original.snapshot().encode(format);
+
+        let mut restored = GameState::new(Arc::new(PhysicsWorld::new(original.world.config().clone())));
+        restored.restore(WorldSnapshot::decode(&bytes, format).unwrap()).unwrap();
+        assert_eq!(restored.tick, original.tick);
+        assert_eq!(restored.fingerprint(), original.fingerprint(), "{:?}", format);
+
+        for _ in 0..30 {
+            original.update(1.0 / 60.0);
+            restored.update(1.0 / 60.0);
+        }
+        assert_eq!(restored.fingerprint(), original.fingerprint(), "{:?} after stepping", format);
+        assert_eq!(restored.new_player_id(), original.new_player_id());
+    }
+}
+
+#[test]
+fn snapshots_from_unknown_modes_or_newer_servers_are_refused() {
+    let world = busy_world();
+    let mut snapshot = world.snapshot();
+    snapshot.version += 1;
+    let bytes = snapshot.encode(Encoding::Json);
+    assert!(matches!(WorldSnapshot::decode(&bytes, Encoding::Json), Err(SnapshotError::UnsupportedVersion(_))));
+    assert!(matches!(WorldSnapshot::decode(&bytes, Encoding::MessagePack), Err(SnapshotError::Decode(_))));
+
+    let mut snapshot = world.snapshot();
+    snapshot.mode = "no_such_mode".to_string();
+    let mut other = GameState::new(Arc::new(PhysicsWorld::new(Arc::new(GameConfig::default()))));
+    assert_eq!(other.restore(snapshot), Err(SnapshotError::UnknownMode("no_such_mode".to_string())));
+    assert!(other.players.is_empty());
+
+    let mut snapshot = world.snapshot();
+    snapshot.geometry.walls.push(WallConfig {
+        id: "sliver".to_string(),
+        x: 0.0,
+        y_bottom: 0.0,
+        y_top: 5.0,
+        width: 0.0,
+        color: "#666666".to_string(),
+    });
+    match other.restore(snapshot) {
+        Err(SnapshotError::InvalidGeometry(errors)) => {
+            assert_eq!(errors.0, vec![ValidationError::DegenerateWall { id: "sliver".to_string() }])
+        }
+        other => panic!("bad geometry restored: {:?}", other),
+    }
+    assert!(other.players.is_empty());
+
+    let mut snapshot = world.snapshot();
+    snapshot.match_state = MatchState::Countdown { starts_at_ms: u64::MAX };
+    snapshot.taken_at_ms = 0;
+    assert_eq!(other.restore(snapshot), Err(SnapshotError::InvalidDeadline));
+    assert!(other.players.is_empty());
+}
+
+#[test]
+fn rules_progress_and_patched_physics_survive_a_restore() {
+    let config = GameConfig {
+        game_mode: GameRules::TAG,
+        matches: MatchConfig { min_players: 2, countdown_secs: 0, ..MatchConfig::default() },
+        physics_sandbox: PhysicsSandboxConfig { enabled: true, ..PhysicsSandboxConfig::default() },
+        ..GameConfig::default()
+    };
+    let mut original = GameState::new(Arc::new(PhysicsWorld::new(Arc::new(config))));
+    for _ in 0..2 {
+        let player_id = original.new_player_id();
+        original.add_player(player_id);
+    }
+    for _ in 0..3 {
+        original.update(1.0 / 60.0);
+    }
+    let changes = serde_json::json!({ "gravity": -80.0 });
+    original.patch_physics(changes.as_object().unwrap()).unwrap();
+    let dasher = original.player_ids()[0];
+    original.players.get_mut(&dasher).unwrap().dash_secs = 0.15;
+
+    let mut restored = GameState::new(Arc::new(PhysicsWorld::new(Arc::new(GameConfig {
+        game_mode: GameRules::TAG,
+        ..GameConfig::default()
+    }))));
+    restored.restore(original.snapshot()).unwrap();
+    let it = |state: &GameState| state.mode_status().unwrap().state["it"].clone();
+    assert!(!it(&original).is_null());
+    assert_eq!(it(&restored), it(&original));
+    assert_eq!(restored.world.config().physics.gravity, -80.0);
+    assert_eq!(restored.players[&dasher].dash_secs, 0.15);
+    assert!(restored.config_version() > original.config_version());
+}
@@ -0,0 +1,137 @@
+use std::sync::Arc;
+use game_core::config::WallConfig;
+use game_core::{GameConfig, GroundState, PhysicsWorld, Player, PlayerCommand, StaminaEffect};
+
+const DT: f32 = 1.0 / 60.0;
+
+/// Open ground with one wall whose left face is at x = 5
+fn world() -> PhysicsWorld {
+    let config = GameConfig {
+        platforms: Vec::new(),
+        walls: vec![WallConfig {
+            id: "wall".to_string(),
+            x: 5.0,
+            y_bottom: -10.0,
+            y_top: 20.0,
+            width: 1.0,
+            color: "#666666".to_string(),
+        }],
+        ..GameConfig::default()
+    };
+    PhysicsWorld::new(Arc::new(config))
+}
+
+fn player(world: &PhysicsWorld) -> Player {
+    Player::new(uuid::Uuid::new_v4(), &world.config().physics)
+}
+
+/// Step one second, holding the player's horizontal speed at `velocity_x`
+fn run(world: &PhysicsWorld, player: &mut Player, velocity_x: f32) {
+    for _ in 0..60 {
+        player.velocity_x = velocity_x;
+        world.update_player_physics(player, DT, &[]);
+    }
+}
+
+#[test]
+fn sprinting_drains_stamina_and_standing_refills_it() {
+    let world = world();
+    let physics = world.config().physics.clone();
+    let mut runner = player(&world);
+    world.apply_command(&mut runner, &PlayerCommand::Sprint);
+    run(&world, &mut runner, -3.0);
+    let drained = physics.max_stamina - physics.sprint_cost_per_sec;
+    assert!((runner.stamina - drained).abs() < 0.5, "stamina after a second of sprinting: {}", runner.stamina);
+
+    // Sprinting without moving costs nothing and regenerates
+    run(&world, &mut runner, 0.0);
+    assert!((runner.stamina - physics.max_stamina).abs() < 0.5, "stamina after resting: {}", runner.stamina);
+
+    // Running out drops back to the normal top speed
+    runner.stamina = 1.0;
+    run(&world, &mut runner, -3.0);
+    assert!(!runner.sprinting);
+
+    // Nothing comes back in the air
+    let mut jumper = player(&world);
+    jumper.stamina = 10.0;
+    jumper.ground_state = GroundState::Flying;
+    jumper.y += 5.0;
+    jumper.velocity_y = 1.0;
+    world.update_player_physics(&mut jumper, DT, &[]);
+    assert_eq!(jumper.stamina, 10.0);
+}
+
+#[test]
+fn dashes_cost_stamina_and_break_the_top_speed() {
+    let world = world();
+    let physics = world.config().physics.clone();
+    let mut dasher = player(&world);
+    world.apply_command(&mut dasher, &PlayerCommand::Dash);
+    assert_eq!(dasher.velocity_x, physics.dash_speed);
+    assert_eq!(dasher.stamina, physics.max_stamina - physics.dash_cost);
+
+    // Too tired to dash again
+    let mut tired = player(&world);
+    tired.stamina = physics.dash_cost - 1.0;
+    tired.facing_right = false;
+    world.apply_command(&mut tired, &PlayerCommand::Dash);
+    assert_eq!(tired.velocity_x, 0.0);
+    assert_eq!(tired.stamina, physics.dash_cost - 1.0);
+}
+
+#[test]
+fn wall_jumps_kick_away_from_the_wall() {
+    let world = world();
+    let physics = world.config().physics.clone();
+    let mut climber = player(&world);
+    climber.x = 5.0 - physics.player_width / 2.0 - 0.001;
+    climber.y += 5.0;
+    climber.velocity_y = -3.0;
+    climber.ground_state = GroundState::Flying;
+
+    world.apply_command(&mut climber, &PlayerCommand::Jump);
+    assert_eq!(climber.velocity_y, physics.jump_velocity);
+    assert_eq!(climber.velocity_x, -physics.wall_jump_push);
+    assert!(!climber.facing_right);
+    assert_eq!(climber.stamina, physics.max_stamina - physics.wall_jump_cost);
+
+    // Away from any wall, a mid-air jump does nothing
+    let mut faller = player(&world);
+    faller.y += 5.0;
+    faller.velocity_y = -3.0;
+    faller.ground_state = GroundState::Flying;
+    world.apply_command(&mut faller, &PlayerCommand::Jump);
+    assert_eq!(faller.velocity_y, -3.0);
+    assert_eq!(faller.stamina, physics.max_stamina);
+}
+
+#[test]
+fn status_effects_scale_costs_and_regeneration_until_they_expire() {
+    let world = world();
+    let physics = world.config().physics.clone();
+    let mut player = player(&world);
+    player.add_stamina_effect(StaminaEffect {
+        id: "second_wind".to_string(),
+        cost_multiplier: 0.0,
+        regen_multiplier: 2.0,
+        remaining_secs: Some(0.5),
+    });
+    world.apply_command(&mut player, &PlayerCommand::Dash);
+    assert_eq!(player.stamina, physics.max_stamina, "dash wasn't free");
+
+    player.stamina = 0.0;
+    player.dash_secs = 0.0;
+    run(&world, &mut player, 0.0);
+    // Half a second at double speed, then half a second at the normal rate
+    let expected = physics.stamina_regen_per_sec * 1.5;
+    assert!((player.stamina - expected).abs() < 1.0, "stamina after the effect: {}", player.stamina);
+    assert!(player.stamina_effects.is_empty());
+
+    // Reapplying replaces rather than stacks
+    let slowed = StaminaEffect { id: "slowed".to_string(), cost_multiplier: 2.0, regen_multiplier: 1.0, remaining_secs: None };
+    player.add_stamina_effect(slowed.clone());
+    player.add_stamina_effect(slowed);
+    assert_eq!(player.stamina_effects.len(), 1);
+    assert!(player.remove_stamina_effect("slowed"));
+}
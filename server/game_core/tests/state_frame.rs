@@ -0,0 +1,51 @@
+use std::sync::Arc;
+use game_core::config::{DeterminismConfig, StateTransportConfig};
+use game_core::simulation::{BotAction, ScriptedBot, Simulation};
+use game_core::{Encoding, GameConfig, GameState, PlayerCommand, StateFrame};
+
+fn busy_world() -> GameState {
+    let config = GameConfig {
+        determinism: DeterminismConfig { seed: Some(3) },
+        ..GameConfig::default()
+    };
+    let mut sim = Simulation::new(Arc::new(config));
+    sim.add_bot(ScriptedBot::new().at(0.0, BotAction::Hold(PlayerCommand::MoveRight)));
+    let shooter = sim.add_player();
+    sim.run_until(1.0, |_| false);
+    sim.command(&shooter, &PlayerCommand::Shoot { dir_x: 1.0, dir_y: 0.5 });
+    sim.run_until(0.1, |_| false);
+    sim.state().clone()
+}
+
+#[test]
+fn frames_read_back_the_same_in_either_encoding() {
+    let state = busy_world();
+    let frame = StateFrame::from_state(&state, 1_234);
+    assert_eq!(frame.players.len(), 2);
+    assert!(frame.players.windows(2).all(|pair| pair[0].id < pair[1].id), "players out of order");
+    assert!(!frame.projectiles.is_empty(), "no projectiles");
+
+    let json = frame.encode(Encoding::Json);
+    let msgpack = frame.encode(Encoding::MessagePack);
+    assert!(msgpack.len() < json.len(), "msgpack {} bytes, json {}", msgpack.len(), json.len());
+    for (bytes, encoding) in [(json, Encoding::Json), (msgpack, Encoding::MessagePack)] {
+        let decoded = StateFrame::decode(&bytes, encoding).unwrap();
+        assert_eq!(decoded.tick, state.tick);
+        assert_eq!(decoded.server_time_ms, 1_234);
+        // Same document either way
+        assert_eq!(serde_json::to_value(&decoded).unwrap(), serde_json::to_value(&frame).unwrap(), "{:?}", encoding);
+    }
+    assert!(StateFrame::decode(b"not a frame", Encoding::MessagePack).is_err());
+}
+
+#[test]
+fn encodings_are_picked_by_name_or_accept_header() {
+    assert_eq!(Encoding::parse("MessagePack"), Some(Encoding::MessagePack));
+    assert_eq!(Encoding::parse("cbor"), None);
+    assert_eq!(Encoding::from_accept("application/x-msgpack;q=0.9, */*"), Some(Encoding::MessagePack));
+    assert_eq!(Encoding::from_accept("text/html, application/json"), Some(Encoding::Json));
+    assert_eq!(Encoding::from_accept("*/*"), None);
+    let config: StateTransportConfig = serde_json::from_str(r#"{"encoding": "msgpack"}"#).unwrap();
+    assert_eq!(config.encoding, Encoding::MessagePack);
+    assert_eq!(StateTransportConfig::default().encoding, Encoding::Json);
+}
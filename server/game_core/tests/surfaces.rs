@@ -0,0 +1,207 @@
+mod common;
+
+use std::sync::Arc;
+use game_core::config::GroundSegment;
+use game_core::{GameConfig, GroundState, PhysicsWorld, PlatformConfig, Player, PlayerCommand, Surface};
+
+const DT: f32 = 1.0 / 60.0;
+
+fn segment(x_start: f32, x_end: f32, surface: Surface) -> GroundSegment {
+    GroundSegment {
+        x_start,
+        x_end,
+        y_top: 0.0,
+        color: None,
+        surface: Some(surface),
+        restitution: None,
+    }
+}
+
+/// Flat ground at 0: ice from 0 to 50, mud from 100 to 150, springy normal ground from
+/// 300 to 350, plain normal elsewhere, and a trampoline from 200 to 204 with its top at 2
+fn world() -> PhysicsWorld {
+    let mut config = GameConfig {
+        platforms: vec![PlatformConfig {
+            id: "trampoline".to_string(),
+            x_start: 200.0,
+            x_end: 204.0,
+            y_top: 2.0,
+            height: 0.5,
+            color: "#33CC33".to_string(),
+            surface: Surface::Bouncy,
+            restitution: None,
+        }],
+        ..common::open_ground()
+    };
+    config.physics.ground_y = 0.0;
+    config.physics.ground_segments = vec![
+        segment(0.0, 50.0, Surface::Ice),
+        segment(100.0, 150.0, Surface::Mud),
+        GroundSegment { restitution: Some(0.5), ..segment(300.0, 350.0, Surface::Normal) },
+    ];
+    PhysicsWorld::new(Arc::new(config))
+}
+
+/// A player that has dropped onto the ground at `x` and settled
+fn landed_at(world: &PhysicsWorld, x: f32) -> Player {
+    let mut player = Player::new(uuid::Uuid::nil(), &world.config().physics);
+    player.x = x;
+    player.y = world.config().physics.player_height / 2.0 + 0.5;
+    run(world, &mut player, 60);
+    player
+}
+
+fn run(world: &PhysicsWorld, player: &mut Player, ticks: usize) {
+    for _ in 0..ticks {
+        world.update_player_physics(player, DT, &[]);
+    }
+}
+
+#[test]
+fn landing_picks_up_the_surface_underfoot() {
+    let world = world();
+    assert_eq!(world.config().physics.ground_surface_at(10.0), Surface::Ice);
+    assert_eq!(landed_at(&world, 10.0).surface, Surface::Ice);
+    assert_eq!(landed_at(&world, 120.0).surface, Surface::Mud);
+    assert_eq!(landed_at(&world, 75.0).surface, Surface::Normal);
+}
+
+#[test]
+fn straddling_an_edge_takes_the_surface_of_the_ground_holding_the_player_up() {
+    let mut config = (*world().config().as_ref()).clone();
+    config.physics.ground_segments.push(GroundSegment { y_top: 1.0, ..segment(400.0, 450.0, Surface::Ice) });
+    let world = PhysicsWorld::new(Arc::new(config));
+    // The center is over normal ground, but only the raised ice is under the feet
+    let edge = 400.0 - world.config().physics.player_width / 4.0;
+    let player = landed_at(&world, edge);
+    assert_eq!(world.config().physics.ground_surface_at(edge), Surface::Normal);
+    assert_eq!(player.surface, Surface::Ice);
+    assert!(player.y > 1.0);
+}
+
+#[test]
+fn stopping_on_ice_slides_on_while_normal_ground_halts() {
+    let world = world();
+    let mut on_ice = landed_at(&world, 10.0);
+    let mut on_grass = landed_at(&world, 60.0);
+    for player in [&mut on_ice, &mut on_grass] {
+        player.velocity_x = 8.0;
+        world.apply_command(player, &PlayerCommand::Stop);
+    }
+    assert_eq!(on_grass.velocity_x, 0.0);
+    assert!(on_ice.velocity_x > 0.0);
+
+    let (ice_start, grass_start) = (on_ice.x, on_grass.x);
+    run(&world, &mut on_ice, 30);
+    run(&world, &mut on_grass, 30);
+    assert!(on_ice.x - ice_start > 1.0, "ice slid {}", on_ice.x - ice_start);
+    assert!(on_grass.x - grass_start < 0.01);
+}
+
+#[test]
+fn mud_and_ice_are_slower_to_get_going() {
+    let world = world();
+    let speed_after_a_push = |x: f32| {
+        let mut player = landed_at(&world, x);
+        world.apply_command(&mut player, &PlayerCommand::MoveRight);
+        player.velocity_x
+    };
+    let normal = speed_after_a_push(75.0);
+    assert!(speed_after_a_push(10.0) < normal);
+    assert!(speed_after_a_push(120.0) < normal);
+}
+
+#[test]
+fn trampolines_throw_players_back_up_until_they_settle() {
+    let world = world();
+    let mut player = Player::new(uuid::Uuid::nil(), &world.config().physics);
+    player.x = 202.0;
+    player.y = 10.0;
+
+    let mut bounced = false;
+    for _ in 0..120 {
+        let falling = player.velocity_y < 0.0;
+        world.update_player_physics(&mut player, DT, &[]);
+        if falling && player.velocity_y > 0.0 {
+            bounced = true;
+            break;
+        }
+    }
+    assert!(bounced, "never bounced off the trampoline");
+    assert_eq!(player.surface, Surface::Bouncy);
+
+    // Each bounce loses a fifth of its speed, so they come to rest on top
+    run(&world, &mut player, 60 * 20);
+    assert!(matches!(player.ground_state, GroundState::Grounded { platform_id: Some(_) }));
+    assert!((player.y - player.height(&world.config().physics) / 2.0 - 2.0).abs() < 0.01);
+}
+
+#[test]
+fn platforms_and_segments_read_their_surface_from_json() {
+    let platform: PlatformConfig = serde_json::from_str(
+        r##"{"id": "rink", "x_start": 0, "x_end": 4, "y_top": 1, "height": 0.5, "color": "#FFFFFF", "surface": "ice"}"##,
+    )
+    .unwrap();
+    assert_eq!(platform.surface, Surface::Ice);
+
+    let plain: PlatformConfig = serde_json::from_str(
+        r##"{"id": "ledge", "x_start": 0, "x_end": 4, "y_top": 1, "height": 0.5, "color": "#FFFFFF"}"##,
+    )
+    .unwrap();
+    assert_eq!(plain.surface, Surface::Normal);
+    assert!(serde_json::to_value(&plain).unwrap().get("surface").is_none());
+}
+
+/// A player falling from `height` above the ground at `x`, run until their first landing
+fn drop_onto(world: &PhysicsWorld, x: f32, height: f32) -> Player {
+    let mut player = Player::new(uuid::Uuid::nil(), &world.config().physics);
+    player.x = x;
+    player.y = height + world.config().physics.player_height / 2.0;
+    for _ in 0..120 {
+        let falling = player.velocity_y < 0.0;
+        world.update_player_physics(&mut player, DT, &[]);
+        if falling && (player.velocity_y >= 0.0) {
+            break;
+        }
+    }
+    player
+}
+
+#[test]
+fn segments_can_override_the_surface_bounciness() {
+    let world = world();
+    let springy = drop_onto(&world, 320.0, 8.0);
+    assert!(springy.velocity_y > 0.0);
+    let speed = springy.bounced.expect("bounce recorded");
+    assert_eq!(speed, springy.velocity_y);
+    assert_eq!(world.config().physics.ground_restitution_at(320.0), 0.5);
+
+    let plain = drop_onto(&world, 75.0, 8.0);
+    assert_eq!(plain.velocity_y, 0.0);
+    assert_eq!(plain.bounced, None);
+}
+
+#[test]
+fn landings_below_the_bounce_speed_settle() {
+    let mut config = (*world().config().as_ref()).clone();
+    config.physics.bounce_min_speed = 1_000.0;
+    let world = PhysicsWorld::new(Arc::new(config));
+    let player = drop_onto(&world, 320.0, 8.0);
+    assert_eq!(player.velocity_y, 0.0);
+    assert_eq!(player.bounced, None);
+}
+
+#[test]
+fn players_standing_on_springy_footing_stay_put() {
+    let world = world();
+    // On the trampoline, then on the springy ground
+    for (x, top) in [(202.0, 2.0), (320.0, 0.0)] {
+        let mut player = Player::new(uuid::Uuid::nil(), &world.config().physics);
+        player.x = x;
+        player.y = top + world.config().physics.player_height / 2.0 + 0.001;
+        for _ in 0..120 {
+            world.update_player_physics(&mut player, DT, &[]);
+            assert_eq!(player.bounced, None, "jittered at x = {}", x);
+        }
+    }
+}
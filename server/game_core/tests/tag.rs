@@ -0,0 +1,115 @@
+use std::sync::Arc;
+use std::time::Duration;
+use game_core::config::{GameRules, MatchConfig, TagConfig};
+use game_core::{GameConfig, GameState, MatchState, MockClock, PhysicsWorld, TagStatus};
+
+const DT: f32 = 1.0 / 60.0;
+
+/// Tag with a one-second immunity, starting as soon as two players are in
+fn room() -> (GameState, Arc<MockClock>) {
+    let config = GameConfig {
+        game_mode: GameRules::TAG,
+        tag: TagConfig { immunity_secs: 1.0, points_per_sec: 1.0 },
+        matches: MatchConfig {
+            min_players: 2,
+            countdown_secs: 0,
+            ..MatchConfig::default()
+        },
+        platforms: Vec::new(),
+        ..GameConfig::default()
+    };
+    let clock = Arc::new(MockClock::new());
+    let state = GameState::with_clock(Arc::new(PhysicsWorld::new(Arc::new(config))), clock.clone());
+    (state, clock)
+}
+
+fn run(state: &mut GameState, clock: &MockClock, ticks: usize) {
+    for _ in 0..ticks {
+        clock.advance(Duration::from_secs_f32(DT));
+        state.update(DT);
+    }
+}
+
+fn join(state: &mut GameState) -> uuid::Uuid {
+    let player_id = state.new_player_id();
+    state.add_player(player_id);
+    player_id
+}
+
+fn status(state: &GameState) -> TagStatus {
+    serde_json::from_value(state.mode_status().expect("tag mode keeps a status").state).unwrap()
+}
+
+/// Stand a player still on the ground at `x`
+fn place(state: &mut GameState, player_id: uuid::Uuid, x: f32) {
+    let ground_y = state.world.config().physics.ground_y;
+    let player = state.players.get_mut(&player_id).unwrap();
+    player.x = x;
+    player.y = ground_y + 1.0;
+    player.velocity_x = 0.0;
+}
+
+/// A started round with the two players apart, returning who is it and who isn't
+fn started() -> (GameState, Arc<MockClock>, uuid::Uuid, uuid::Uuid) {
+    let (mut state, clock) = room();
+    let (alice, bob) = (join(&mut state), join(&mut state));
+    run(&mut state, &clock, 2);
+    assert!(matches!(state.match_state, MatchState::Playing { .. }));
+    place(&mut state, alice, -20.0);
+    place(&mut state, bob, 20.0);
+    run(&mut state, &clock, 1);
+    let it = status(&state).it.expect("someone is it once the round starts");
+    let runner = if it == alice { bob } else { alice };
+    (state, clock, it, runner)
+}
+
+#[test]
+fn touching_passes_the_tag_and_the_tagger_is_immune_for_a_while() {
+    let (mut state, clock, it, runner) = started();
+
+    place(&mut state, runner, 0.0);
+    place(&mut state, it, 0.0);
+    run(&mut state, &clock, 1);
+    let tagged = status(&state);
+    assert_eq!(tagged.it, Some(runner));
+    assert_eq!(tagged.immune, vec![it]);
+    let pass = tagged.last_pass.unwrap();
+    assert_eq!((pass.from, pass.to), (it, runner));
+
+    // Still overlapping, but the old it can't be tagged straight back
+    run(&mut state, &clock, 30);
+    assert_eq!(status(&state).it, Some(runner));
+
+    place(&mut state, runner, 0.0);
+    place(&mut state, it, 0.0);
+    run(&mut state, &clock, 40);
+    assert_eq!(status(&state).it, Some(it));
+}
+
+#[test]
+fn time_spent_not_it_scores() {
+    let (mut state, clock, it, runner) = started();
+    let (it_score, runner_score) = (state.players[&it].score, state.players[&runner].score);
+
+    run(&mut state, &clock, 180);
+    assert_eq!(state.players[&it].score, it_score);
+    let earned = state.players[&runner].score - runner_score;
+    assert!((2..=3).contains(&earned), "earned {} points in 3s", earned);
+}
+
+#[test]
+fn someone_else_is_it_when_it_leaves() {
+    let (mut state, clock, it, runner) = started();
+    state.remove_player(&it);
+    run(&mut state, &clock, 1);
+    assert_eq!(status(&state).it, Some(runner));
+}
+
+#[test]
+fn other_rules_have_no_tag() {
+    let (state, _) = room();
+    let mut config = (**state.world.config()).clone();
+    config.game_mode = GameRules::STANDARD;
+    let state = GameState::new(Arc::new(PhysicsWorld::new(Arc::new(config))));
+    assert!(state.mode_status().is_none());
+}
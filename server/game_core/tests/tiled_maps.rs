@@ -0,0 +1,165 @@
+use game_core::config::tiled::{self, TiledError};
+use game_core::config::{MapFormat, PhysicsConfig};
+use game_core::GameConfig;
+use serde_json::{json, Value};
+
+/// 40 x 12 tiles of 16 px: world x runs from -20 to 20 and y from the ground (-10) to 2
+fn map(layers: Value) -> String {
+    json!({
+        "type": "map",
+        "tiledversion": "1.10.2",
+        "orientation": "orthogonal",
+        "width": 40,
+        "height": 12,
+        "tilewidth": 16,
+        "tileheight": 16,
+        "layers": layers,
+    })
+    .to_string()
+}
+
+fn rect(id: u32, name: &str, x: f32, y: f32, width: f32, height: f32) -> Value {
+    json!({ "id": id, "name": name, "x": x * 16.0, "y": y * 16.0, "width": width * 16.0, "height": height * 16.0 })
+}
+
+fn physics() -> PhysicsConfig {
+    GameConfig::default().physics
+}
+
+fn level() -> String {
+    let mut lava = rect(3, "", 4.0, 11.5, 3.0, 0.5);
+    lava["class"] = json!("hazard");
+    lava["properties"] = json!([
+        { "name": "damage_per_second", "type": "float", "value": 40.0 },
+        { "name": "color", "type": "color", "value": "#ffff5a00" },
+    ]);
+    map(json!([
+        { "type": "tilelayer", "name": "background", "data": [] },
+        { "type": "objectgroup", "name": "Platforms", "objects": [rect(1, "ledge", 18.0, 6.0, 4.0, 0.5), lava] },
+        { "type": "group", "name": "structure", "layers": [
+            { "type": "objectgroup", "name": "walls", "objects": [rect(2, "", 0.0, 0.0, 1.0, 12.0)] },
+        ] },
+        { "type": "objectgroup", "name": "spawns", "objects": [
+            { "id": 4, "name": "", "x": 320.0, "y": 192.0, "point": true },
+        ] },
+    ]))
+}
+
+#[test]
+fn tiled_objects_become_geometry_in_world_units() {
+    let geometry = tiled::import(&level(), &physics()).unwrap();
+
+    let ledge = &geometry.platforms[0];
+    assert_eq!(ledge.id, "ledge");
+    assert_eq!((ledge.x_start, ledge.x_end, ledge.y_top, ledge.height), (-2.0, 2.0, -4.0, 0.5));
+
+    let wall = &geometry.walls[0];
+    assert_eq!(wall.id, "wall_2");
+    assert_eq!((wall.x, wall.width, wall.y_bottom, wall.y_top), (-20.0, 1.0, -10.0, 2.0));
+
+    // The object's class beats its layer, and Tiled's #AARRGGBB colors lose their alpha
+    let lava = &geometry.hazards[0];
+    assert_eq!((lava.x_start, lava.x_end, lava.y_bottom, lava.y_top), (-16.0, -13.0, -10.0, -9.5));
+    assert_eq!(lava.damage_per_second, 40.0);
+    assert_eq!(lava.color, "#FF5A00");
+
+    // Spawn points mark where feet go; the player's center is half a body higher
+    let spawn = &geometry.spawn_points[0];
+    assert_eq!((spawn.x, spawn.y), (0.0, -10.0 + physics().player_height / 2.0));
+}
+
+#[test]
+fn unsupported_maps_and_shapes_are_rejected() {
+    let mut isometric: Value = serde_json::from_str(&map(json!([]))).unwrap();
+    isometric["orientation"] = json!("isometric");
+    assert_eq!(
+        tiled::import(&isometric.to_string(), &physics()).unwrap_err(),
+        TiledError::Orientation("isometric".to_string())
+    );
+
+    let mut rotated = rect(7, "tilted", 0.0, 0.0, 2.0, 1.0);
+    rotated["rotation"] = json!(45.0);
+    let layers = json!([{ "type": "objectgroup", "name": "platforms", "objects": [rotated] }]);
+    assert!(matches!(
+        tiled::import(&map(layers), &physics()),
+        Err(TiledError::Unsupported { .. })
+    ));
+}
+
+#[test]
+fn config_map_files_replace_geometry_by_detected_format() {
+    let dir = std::env::temp_dir().join(format!("tiled-{}", uuid::Uuid::new_v4()));
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(dir.join("level.tmj"), level()).unwrap();
+    let config = GameConfig {
+        map_file: Some("level.tmj".to_string()),
+        ..GameConfig::default()
+    };
+    std::fs::write(dir.join("config.json"), serde_json::to_string(&config).unwrap()).unwrap();
+
+    let loaded = GameConfig::load(dir.join("config.json")).unwrap();
+    assert_eq!(loaded.platforms.len(), 1);
+    assert_eq!(loaded.platforms[0].id, "ledge");
+    assert_eq!(loaded.hazards.len(), 1);
+    assert_eq!(loaded.spawn_points.len(), 1);
+
+    // A .json Tiled export is recognised from its contents; native maps need no conversion
+    assert_eq!(MapFormat::detect(&dir.join("level.json"), &level()), MapFormat::Tiled);
+    let native = json!({ "platforms": [], "walls": [] }).to_string();
+    assert_eq!(MapFormat::detect(&dir.join("arena.json"), &native), MapFormat::Native);
+
+    // TMX is Tiled's XML format, which needs exporting to JSON first
+    std::fs::write(dir.join("level.tmx"), "<map/>").unwrap();
+    let tmx = GameConfig {
+        map_file: Some("level.tmx".to_string()),
+        ..GameConfig::default()
+    };
+    std::fs::write(dir.join("tmx.json"), serde_json::to_string(&tmx).unwrap()).unwrap();
+    let error = GameConfig::load(dir.join("tmx.json")).unwrap_err().to_string();
+    assert!(error.contains("export the map from Tiled as JSON"), "{}", error);
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn colors_that_are_not_ascii_are_kept_for_validation_to_refuse() {
+    let mut updraft = rect(1, "updraft", 0.0, 2.0, 2.0, 10.0);
+    // Nine bytes, like #AARRGGBB, but the alpha would be cut mid-character
+    updraft["properties"] = json!([{ "name": "color", "type": "color", "value": "#a€1234" }]);
+    let contents = map(json!([{ "type": "objectgroup", "name": "wind", "objects": [updraft] }]));
+    let geometry = tiled::import(&contents, &physics()).unwrap();
+    assert_eq!(geometry.force_zones[0].color, "#A€1234");
+    assert!(geometry.validate().is_err());
+}
+
+#[test]
+fn wind_layers_become_force_zones() {
+    let mut updraft = rect(1, "updraft", 0.0, 2.0, 2.0, 10.0);
+    updraft["properties"] = json!([{ "name": "force_y", "type": "float", "value": 3000.0 }]);
+    let contents = map(json!([{ "type": "objectgroup", "name": "wind", "objects": [updraft] }]));
+    let geometry = tiled::import(&contents, &physics()).unwrap();
+
+    let zone = &geometry.force_zones[0];
+    assert_eq!(zone.id, "updraft");
+    assert_eq!((zone.x_start, zone.x_end, zone.y_bottom, zone.y_top), (-20.0, -18.0, -10.0, 0.0));
+    assert_eq!((zone.force_x, zone.force_y), (0.0, 3000.0));
+    assert_eq!(zone.color, "#88CCFF");
+}
+
+#[test]
+fn portals_take_their_exit_from_a_property() {
+    let mut entry = rect(1, "door", 0.0, 0.0, 2.0, 3.0);
+    entry["properties"] = json!([
+        { "name": "exit", "type": "string", "value": "far_door" },
+        { "name": "rotation_degrees", "type": "float", "value": 180.0 }
+    ]);
+    let contents = map(json!([{ "type": "objectgroup", "name": "portals", "objects": [entry] }]));
+    let portal = &tiled::import(&contents, &physics()).unwrap().portals[0];
+    assert_eq!((portal.id.as_str(), portal.exit.as_str()), ("door", "far_door"));
+    assert_eq!(portal.rotation_degrees, 180.0);
+    assert!(!portal.drop_velocity);
+
+    let lost = rect(2, "lost", 0.0, 0.0, 2.0, 3.0);
+    let contents = map(json!([{ "type": "objectgroup", "name": "portals", "objects": [lost] }]));
+    assert!(matches!(tiled::import(&contents, &physics()), Err(TiledError::Unsupported { .. })));
+}
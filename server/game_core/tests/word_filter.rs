@@ -0,0 +1,23 @@
+use game_core::names::{contains_blocked_chat_word, contains_blocked_word};
+
+fn words(list: &[&str]) -> Vec<String> {
+    list.iter().map(|word| word.to_string()).collect()
+}
+
+#[test]
+fn chat_only_blocks_whole_words() {
+    let blocked = words(&["shit", "bad word"]);
+    assert!(contains_blocked_chat_word("oh SH1T!", &blocked));
+    assert!(contains_blocked_chat_word("that's a b4d w0rd", &blocked));
+    assert!(!contains_blocked_chat_word("what is hit", &blocked));
+    assert!(!contains_blocked_chat_word("shitake mushrooms", &blocked));
+    assert!(!contains_blocked_chat_word("bad, wordy", &blocked));
+}
+
+#[test]
+fn names_block_words_run_together_but_not_across_spaces() {
+    let blocked = words(&["admin"]);
+    assert!(contains_blocked_word("xX4dminXx", &blocked));
+    assert!(contains_blocked_word("the_admin", &blocked));
+    assert!(!contains_blocked_word("ad min", &blocked));
+}
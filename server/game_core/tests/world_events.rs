@@ -0,0 +1,140 @@
+use std::sync::Arc;
+use game_core::config::{DeterminismConfig, WorldEventsConfig};
+use game_core::world_events::SPEED_FRENZY_EFFECT;
+use game_core::{DamageSource, GameConfig, GameState, PhysicsWorld, WorldEventChange, WorldEventError, WorldEventKind};
+
+const DT: f32 = 1.0 / 60.0;
+
+fn state(world_events: WorldEventsConfig) -> GameState {
+    let config = GameConfig {
+        platforms: Vec::new(),
+        world_events,
+        determinism: DeterminismConfig { seed: Some(7) },
+        ..GameConfig::default()
+    };
+    GameState::new(Arc::new(PhysicsWorld::new(Arc::new(config))))
+}
+
+/// A state with one player, whose events drop everything straight onto that player
+fn state_with_player(world_events: WorldEventsConfig) -> (GameState, uuid::Uuid) {
+    let mut state = state(world_events);
+    let player_id = state.new_player_id();
+    state.add_player(player_id);
+    let x = state.players[&player_id].x;
+    let mut config = (**state.world.config()).clone();
+    config.world_events.spawn_min_x = x;
+    config.world_events.spawn_max_x = x;
+    state.world = Arc::new(PhysicsWorld::new(Arc::new(config)));
+    (state, player_id)
+}
+
+fn run(state: &mut GameState, secs: f32) {
+    for _ in 0..(secs / DT).round() as usize {
+        state.update(DT);
+    }
+}
+
+#[test]
+fn seeded_schedules_start_the_same_events_at_the_same_ticks() {
+    let config = WorldEventsConfig {
+        enabled: true,
+        min_interval_secs: 1.0,
+        max_interval_secs: 3.0,
+        duration_secs: 1.0,
+        ..WorldEventsConfig::default()
+    };
+    let started = |mut state: GameState| {
+        let mut started = Vec::new();
+        for _ in 0..60 * 20 {
+            state.update(DT);
+            for change in state.drain_world_event_changes() {
+                if let WorldEventChange::Started(event) = change {
+                    started.push((state.tick, event.kind));
+                }
+            }
+        }
+        started
+    };
+    let first = started(state(config.clone()));
+    assert!(first.len() >= 4, "events started: {:?}", first);
+    assert_eq!(first, started(state(config.clone())));
+
+    // Off by default, and never on a schedule while disabled
+    assert!(started(state(WorldEventsConfig::default())).is_empty());
+}
+
+#[test]
+fn only_one_event_runs_at_a_time_and_each_is_announced_once() {
+    let mut state = state(WorldEventsConfig {
+        duration_secs: 0.5,
+        ..WorldEventsConfig::default()
+    });
+    state.start_world_event(WorldEventKind::CoinRain).unwrap();
+    assert_eq!(state.start_world_event(WorldEventKind::MeteorShower), Err(WorldEventError::AlreadyActive));
+    run(&mut state, 1.0);
+    let changes = state.drain_world_event_changes();
+    assert_eq!(changes.len(), 2, "{:?}", changes);
+    assert!(matches!(&changes[0], WorldEventChange::Started(event) if event.kind == WorldEventKind::CoinRain));
+    assert_eq!(changes[1], WorldEventChange::Ended { kind: WorldEventKind::CoinRain });
+    assert!(state.world_events.active.is_none());
+    assert_eq!(WorldEventKind::parse("Meteor Shower"), Some(WorldEventKind::MeteorShower));
+}
+
+#[test]
+fn coins_falling_on_a_player_score_and_vanish() {
+    let (mut state, player_id) = state_with_player(WorldEventsConfig {
+        duration_secs: 2.0,
+        coins_per_sec: 4.0,
+        coin_fall_speed: 20.0,
+        ..WorldEventsConfig::default()
+    });
+    state.start_world_event(WorldEventKind::CoinRain).unwrap();
+    // Long enough for the last coin to land
+    run(&mut state, 3.0);
+    let coin_points = state.world.config().combo.coin_points;
+    let score = state.players[&player_id].score;
+    assert!(score >= 8 * coin_points, "score after two seconds of coin rain: {}", score);
+    assert!(state.world_events.entities.is_empty(), "{} coins left", state.world_events.entities.len());
+}
+
+#[test]
+fn meteors_hurt_the_players_they_hit() {
+    let (mut state, player_id) = state_with_player(WorldEventsConfig {
+        meteor_fall_speed: 30.0,
+        meteors_per_sec: 2.0,
+        ..WorldEventsConfig::default()
+    });
+    state.players.get_mut(&player_id).unwrap().invulnerable_secs = 0.0;
+    state.start_world_event(WorldEventKind::MeteorShower).unwrap();
+    run(&mut state, 1.5);
+    let hits: Vec<_> = state
+        .drain_damage_events()
+        .into_iter()
+        .filter(|event| matches!(event.source, DamageSource::Meteor { .. }))
+        .collect();
+    assert!(!hits.is_empty());
+    assert_eq!(hits[0].target, player_id);
+    assert_eq!(hits[0].amount, state.world.config().world_events.meteor_damage);
+    let max_health = state.world.config().health.max_health;
+    assert!(state.players[&player_id].health < max_health);
+}
+
+#[test]
+fn a_speed_frenzy_makes_stamina_free_until_it_ends() {
+    let (mut state, player_id) = state_with_player(WorldEventsConfig {
+        duration_secs: 1.0,
+        ..WorldEventsConfig::default()
+    });
+    state.start_world_event(WorldEventKind::SpeedFrenzy).unwrap();
+    run(&mut state, 0.1);
+    let player = state.players.get_mut(&player_id).unwrap();
+    let effect = player.stamina_effects.iter().find(|effect| effect.id == SPEED_FRENZY_EFFECT).unwrap();
+    assert_eq!(effect.cost_multiplier, 0.0);
+    let stamina = player.stamina;
+    assert!(player.try_spend_stamina(stamina + 50.0));
+    assert_eq!(player.stamina, stamina);
+
+    run(&mut state, 1.0);
+    assert!(state.players[&player_id].stamina_effects.iter().all(|effect| effect.id != SPEED_FRENZY_EFFECT));
+    assert!(state.world_events.entities.is_empty());
+}